@@ -1,6 +1,6 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use cosmian_kms_client::{
-    access::{Access, ObjectOperationType},
+    access::{Access, GroupMember, ObjectOperationType, Role},
     cosmian_kmip::kmip::kmip_types::UniqueIdentifier,
     KmsClient,
 };
@@ -15,6 +15,10 @@ pub enum AccessAction {
     List(ListAccessesGranted),
     Owned(ListOwnedObjects),
     Obtained(ListAccessRightsObtained),
+    #[command(subcommand)]
+    Roles(RolesAction),
+    #[command(subcommand)]
+    Groups(GroupsAction),
 }
 
 impl AccessAction {
@@ -25,6 +29,8 @@ impl AccessAction {
             Self::List(action) => action.run(kms_rest_client).await?,
             Self::Owned(action) => action.run(kms_rest_client).await?,
             Self::Obtained(action) => action.run(kms_rest_client).await?,
+            Self::Roles(action) => action.process(kms_rest_client).await?,
+            Self::Groups(action) => action.process(kms_rest_client).await?,
         };
 
         Ok(())
@@ -36,12 +42,15 @@ impl AccessAction {
 /// This command can only be called by the owner of the object.
 ///
 /// The right is granted for one or multiple supported KMIP operations:
-/// `create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`.
+/// `create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`, `sign`, `mac`, `derive`, `set_attributes`, `activate`, `archive`.
 ///
 /// Multiple operations must be supplied whitespace separated, such as: 'create get rekey'
+///
+/// A role can be granted with `--role`: later changes to the role apply to the access rights.
+/// Access rights granted to `group:<name>` are obtained by all the members of the group.
 #[derive(Parser, Debug)]
 pub struct GrantAccess {
-    /// The user identifier to allow
+    /// The user identifier to allow, or `group:<name>` to allow the members of a group
     #[clap(required = true)]
     user: String,
 
@@ -49,9 +58,13 @@ pub struct GrantAccess {
     #[clap(required = true)]
     object_uid: String,
 
-    /// The operations to grant (`create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`)
-    #[clap(required = true)]
+    /// The operations to grant (`create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`, `sign`, `mac`, `derive`, `set_attributes`, `activate`, `archive`)
+    #[clap(required_unless_present = "role")]
     operations: Vec<ObjectOperationType>,
+
+    /// The name of a role to grant
+    #[clap(long, short = 'r')]
+    role: Option<String>,
 }
 
 impl GrantAccess {
@@ -60,6 +73,7 @@ impl GrantAccess {
             unique_identifier: Some(UniqueIdentifier::TextString(self.object_uid.clone())),
             user_id: self.user.clone(),
             operation_types: self.operations.clone(),
+            role: self.role.clone(),
         };
 
         kms_rest_client
@@ -67,10 +81,17 @@ impl GrantAccess {
             .await
             .with_context(|| "Can't execute the query on the kms server")?;
 
-        println!(
-            "The following access right were successfully granted to `{}`: {:?}",
-            self.user, self.operations,
-        );
+        match &self.role {
+            Some(role) => println!(
+                "The following access right were successfully granted to `{}`: {:?} and the role \
+                 {role}",
+                self.user, self.operations,
+            ),
+            None => println!(
+                "The following access right were successfully granted to `{}`: {:?}",
+                self.user, self.operations,
+            ),
+        }
 
         Ok(())
     }
@@ -81,12 +102,14 @@ impl GrantAccess {
 /// This command can only be called by the owner of the object.
 ///
 /// The right is revoked for one or multiple supported KMIP operations:
-/// `create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`
+/// `create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`, `sign`, `mac`, `derive`, `set_attributes`, `activate`, `archive`
 ///
 /// Multiple operations must be supplied whitespace separated, such as: 'create get rekey'
+///
+/// A role granted with `--role` can be revoked the same way.
 #[derive(Parser, Debug)]
 pub struct RevokeAccess {
-    /// The user to revoke access to
+    /// The user to revoke access to, or `group:<name>` for a group
     #[clap(required = true)]
     user: String,
 
@@ -94,9 +117,13 @@ pub struct RevokeAccess {
     #[clap(required = true)]
     object_uid: String,
 
-    /// The operations to revoke (`create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`)
-    #[clap(required = true)]
+    /// The operations to revoke (`create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`, `sign`, `mac`, `derive`, `set_attributes`, `activate`, `archive`)
+    #[clap(required_unless_present = "role")]
     operations: Vec<ObjectOperationType>,
+
+    /// The name of a role to revoke
+    #[clap(long, short = 'r')]
+    role: Option<String>,
 }
 
 impl RevokeAccess {
@@ -105,6 +132,7 @@ impl RevokeAccess {
             unique_identifier: Some(UniqueIdentifier::TextString(self.object_uid.clone())),
             user_id: self.user.clone(),
             operation_types: self.operations.clone(),
+            role: self.role.clone(),
         };
 
         kms_rest_client
@@ -112,10 +140,17 @@ impl RevokeAccess {
             .await
             .with_context(|| "Can't execute the query on the kms server")?;
 
-        println!(
-            "The following permissions have been properly removed for `{}`: {:?}",
-            self.user, self.operations
-        );
+        match &self.role {
+            Some(role) => println!(
+                "The following permissions have been properly removed for `{}`: {:?} and the role \
+                 {role}",
+                self.user, self.operations
+            ),
+            None => println!(
+                "The following permissions have been properly removed for `{}`: {:?}",
+                self.user, self.operations
+            ),
+        }
 
         Ok(())
     }
@@ -193,3 +228,209 @@ impl ListAccessRightsObtained {
         Ok(())
     }
 }
+
+/// Manage the roles: named bundles of operations which can be granted at once.
+///
+/// Only the access rights administrators of the server can set and delete roles.
+#[derive(Subcommand, Debug)]
+pub enum RolesAction {
+    Set(SetRole),
+    Delete(DeleteRole),
+    List(ListRoles),
+}
+
+impl RolesAction {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        match self {
+            Self::Set(action) => action.run(kms_rest_client).await?,
+            Self::Delete(action) => action.run(kms_rest_client).await?,
+            Self::List(action) => action.run(kms_rest_client).await?,
+        };
+
+        Ok(())
+    }
+}
+
+/// Create or replace a role.
+///
+/// Granting a role grants the operations the role has at that time:
+/// the access rights already granted are not updated when the role changes.
+#[derive(Parser, Debug)]
+pub struct SetRole {
+    /// The name of the role
+    #[clap(required = true)]
+    name: String,
+
+    /// The operations of the role (`create`, `get`, `encrypt`, `decrypt`, `import`, `revoke`, `locate`, `rekey`, `destroy`, `sign`, `mac`, `derive`, `set_attributes`, `activate`, `archive`)
+    #[clap(required = true)]
+    operations: Vec<ObjectOperationType>,
+}
+
+impl SetRole {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        kms_rest_client
+            .set_role(Role {
+                name: self.name.clone(),
+                operation_types: self.operations.clone(),
+            })
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        println!(
+            "The role `{}` was successfully set: {:?}",
+            self.name, self.operations
+        );
+
+        Ok(())
+    }
+}
+
+/// Delete a role and revoke the access rights granted with it.
+#[derive(Parser, Debug)]
+pub struct DeleteRole {
+    /// The name of the role
+    #[clap(required = true)]
+    name: String,
+}
+
+impl DeleteRole {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        kms_rest_client
+            .delete_role(Role {
+                name: self.name.clone(),
+                operation_types: vec![],
+            })
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        println!("The role `{}` was successfully deleted", self.name);
+
+        Ok(())
+    }
+}
+
+/// List the roles and their operations
+#[derive(Parser, Debug)]
+pub struct ListRoles;
+
+impl ListRoles {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let roles = kms_rest_client
+            .list_roles()
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        println!("The roles are:");
+        for role in roles {
+            println!(" - {}: {:?}", role.name, role.operations);
+        }
+        Ok(())
+    }
+}
+
+/// Manage the local groups of users.
+///
+/// Access rights granted to `group:<name>` are obtained by the members of the group.
+/// The members are the users added locally and the users whose JWT lists the group
+/// in its `groups` claim.
+///
+/// Only the access rights administrators of the server can manage the local groups.
+#[derive(Subcommand, Debug)]
+pub enum GroupsAction {
+    Add(AddGroupMember),
+    Remove(RemoveGroupMember),
+    List(ListGroups),
+}
+
+impl GroupsAction {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        match self {
+            Self::Add(action) => action.run(kms_rest_client).await?,
+            Self::Remove(action) => action.run(kms_rest_client).await?,
+            Self::List(action) => action.run(kms_rest_client).await?,
+        };
+
+        Ok(())
+    }
+}
+
+/// Add a user to a local group
+#[derive(Parser, Debug)]
+pub struct AddGroupMember {
+    /// The name of the group
+    #[clap(required = true)]
+    group: String,
+
+    /// The user identifier to add to the group
+    #[clap(required = true)]
+    user: String,
+}
+
+impl AddGroupMember {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        kms_rest_client
+            .add_group_member(GroupMember {
+                group: self.group.clone(),
+                user_id: self.user.clone(),
+            })
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        println!(
+            "The user `{}` was successfully added to the group `{}`",
+            self.user, self.group
+        );
+
+        Ok(())
+    }
+}
+
+/// Remove a user from a local group
+#[derive(Parser, Debug)]
+pub struct RemoveGroupMember {
+    /// The name of the group
+    #[clap(required = true)]
+    group: String,
+
+    /// The user identifier to remove from the group
+    #[clap(required = true)]
+    user: String,
+}
+
+impl RemoveGroupMember {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        kms_rest_client
+            .remove_group_member(GroupMember {
+                group: self.group.clone(),
+                user_id: self.user.clone(),
+            })
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        println!(
+            "The user `{}` was successfully removed from the group `{}`",
+            self.user, self.group
+        );
+
+        Ok(())
+    }
+}
+
+/// List the local groups and their members
+#[derive(Parser, Debug)]
+pub struct ListGroups;
+
+impl ListGroups {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let groups = kms_rest_client
+            .list_groups()
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        println!("The local groups are:");
+        for group in groups {
+            println!(" - {}: {:?}", group.group, group.members);
+        }
+        Ok(())
+    }
+}
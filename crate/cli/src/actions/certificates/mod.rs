@@ -7,7 +7,8 @@ use self::{
     certify::CertifyAction, decrypt_certificate::DecryptCertificateAction,
     destroy_certificate::DestroyCertificateAction, encrypt_certificate::EncryptCertificateAction,
     export_certificate::ExportCertificateAction, import_certificate::ImportCertificateAction,
-    revoke_certificate::RevokeCertificateAction,
+    ocsp_responder::OcspResponderAction, revoke_certificate::RevokeCertificateAction,
+    validate_certificate::ValidateCertificateAction,
 };
 use crate::error::CliError;
 
@@ -18,9 +19,11 @@ mod destroy_certificate;
 mod encrypt_certificate;
 mod export_certificate;
 mod import_certificate;
+mod ocsp_responder;
 mod revoke_certificate;
+mod validate_certificate;
 
-/// Manage certificates. Create, import, destroy and revoke. Encrypt and decrypt data
+/// Manage certificates. Create, import, destroy, revoke and validate. Encrypt and decrypt data
 #[derive(Subcommand)]
 pub enum CertificatesCommands {
     Certify(CertifyAction),
@@ -31,6 +34,8 @@ pub enum CertificatesCommands {
     Import(ImportCertificateAction),
     Revoke(RevokeCertificateAction),
     Destroy(DestroyCertificateAction),
+    OcspResponder(OcspResponderAction),
+    Validate(ValidateCertificateAction),
 }
 
 impl CertificatesCommands {
@@ -44,6 +49,8 @@ impl CertificatesCommands {
             Self::Import(action) => action.run(client_connector).await,
             Self::Revoke(action) => action.run(client_connector).await,
             Self::Destroy(action) => action.run(client_connector).await,
+            Self::OcspResponder(action) => action.run(client_connector).await,
+            Self::Validate(action) => action.run(client_connector).await,
         }
     }
 }
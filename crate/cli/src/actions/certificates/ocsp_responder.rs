@@ -0,0 +1,136 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::{
+        crypto::elliptic_curves::kmip_requests::create_ec_key_pair_request,
+        kmip::{
+            kmip_objects::ObjectType,
+            kmip_operations::Certify,
+            kmip_types::{
+                Attributes, CertificateAttributes, LinkType, LinkedObjectIdentifier,
+                RecommendedCurve, UniqueIdentifier,
+            },
+        },
+    },
+    KmsClient,
+};
+
+use crate::error::{result::CliResultHelper, CliError};
+
+/// The extensions of a delegated OCSP responder certificate
+const OCSP_RESPONDER_EXTENSIONS: &str = "[v3_ca]
+keyUsage=critical,digitalSignature
+extendedKeyUsage=OCSPSigning
+";
+
+/// Provision the delegated OCSP responder of an issuer held in the KMS.
+///
+/// A NIST P-256 key pair is created in the KMS and its public key is certified
+/// by the issuer with the `OCSPSigning` extended key usage.
+/// The KMS OCSP responder then signs the responses about the certificates
+/// of this issuer with this key.
+///
+/// Tags can later be used to retrieve the responder key pair and certificate. Tags are optional.
+#[derive(Parser)]
+#[clap(verbatim_doc_comment)]
+pub struct OcspResponderAction {
+    /// The unique identifier of the certificate of the issuer.
+    /// A private key must be linked to that certificate
+    /// if no issuer private key id is provided.
+    #[clap(long = "issuer-certificate-id", short = 'c')]
+    issuer_certificate_id: Option<String>,
+
+    /// The unique identifier of the private key of the issuer.
+    /// A certificate must be linked to that private key
+    /// if no issuer certificate id is provided.
+    #[clap(long = "issuer-private-key-id", short = 'k')]
+    issuer_private_key_id: Option<String>,
+
+    /// The subject name of the responder certificate
+    ///
+    /// For instance: "CN=OCSP Responder,O=Org Name,C=US"
+    #[clap(
+        long = "subject-name",
+        short = 's',
+        default_value = "CN=OCSP Responder"
+    )]
+    subject_name: String,
+
+    /// The requested number of validity days of the responder certificate.
+    /// The server may grant a different value
+    #[clap(long = "days", short = 'd', default_value = "365")]
+    number_of_days: usize,
+
+    /// The tag to associate to the responder key pair and certificate.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG")]
+    tags: Vec<String>,
+}
+
+impl OcspResponderAction {
+    pub async fn run(&self, client_connector: &KmsClient) -> Result<(), CliError> {
+        if self.issuer_certificate_id.is_none() && self.issuer_private_key_id.is_none() {
+            return Err(CliError::Default(
+                "Either an issuer certificate id or an issuer private key id or both must be \
+                 provided"
+                    .to_string(),
+            ))
+        }
+
+        // Create the responder key pair
+        let create_key_pair_response = client_connector
+            .create_key_pair(create_ec_key_pair_request(
+                &self.tags,
+                RecommendedCurve::P256,
+            )?)
+            .await
+            .with_context(|| "failed creating the OCSP responder key pair")?;
+
+        // Certify its public key with the issuer
+        let mut attributes = Attributes {
+            object_type: Some(ObjectType::Certificate),
+            ..Attributes::default()
+        };
+        if let Some(issuer_certificate_id) = &self.issuer_certificate_id {
+            attributes.add_link(
+                LinkType::CertificateLink,
+                LinkedObjectIdentifier::TextString(issuer_certificate_id.clone()),
+            );
+        }
+        if let Some(issuer_private_key_id) = &self.issuer_private_key_id {
+            attributes.add_link(
+                LinkType::PrivateKeyLink,
+                LinkedObjectIdentifier::TextString(issuer_private_key_id.clone()),
+            );
+        }
+        attributes.set_requested_validity_days(self.number_of_days);
+        attributes.set_tags(&self.tags)?;
+        attributes.certificate_attributes = Some(Box::new(
+            CertificateAttributes::parse_subject_line(&self.subject_name)?,
+        ));
+        attributes.set_x509_extension_file(OCSP_RESPONDER_EXTENSIONS.as_bytes().to_vec());
+
+        let certify_request = Certify {
+            unique_identifier: Some(UniqueIdentifier::TextString(
+                create_key_pair_response
+                    .public_key_unique_identifier
+                    .to_string(),
+            )),
+            attributes: Some(attributes),
+            ..Certify::default()
+        };
+        let certificate_unique_identifier = client_connector
+            .certify(certify_request)
+            .await
+            .with_context(|| "failed certifying the OCSP responder public key")?
+            .unique_identifier;
+
+        println!(
+            "The OCSP responder certificate was issued with id: {certificate_unique_identifier}."
+        );
+        println!(
+            "  Private key unique identifier: {}",
+            create_key_pair_response.private_key_unique_identifier
+        );
+        Ok(())
+    }
+}
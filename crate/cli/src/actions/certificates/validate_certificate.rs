@@ -0,0 +1,78 @@
+use chrono::DateTime;
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::{
+        kmip_operations::Validate,
+        kmip_types::{UniqueIdentifier, ValidityIndicator},
+    },
+    KmsClient,
+};
+
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Validate a certificate chain.
+///
+/// The chain starts with the leaf certificate and is completed with the
+/// certificates held by the KMS up to a self-signed root, which must also be
+/// held by the KMS. The validity periods, the revocation status and the key
+/// usage of the issuers are checked.
+///
+/// When the chain is not valid, the first reason found is displayed.
+#[derive(Parser, Debug)]
+pub struct ValidateCertificateAction {
+    /// The unique identifier of the leaf certificate.
+    #[clap(long = "certificate-id", short = 'k', required = true)]
+    certificate_id: String,
+
+    /// The unique identifiers of intermediate certificates to use to build the chain.
+    /// To specify multiple certificates, use the option multiple times.
+    #[clap(long = "intermediate-id", short = 'i', value_name = "ID")]
+    intermediate_ids: Vec<String>,
+
+    /// The date at which the chain must be valid, in RFC 3339 format
+    /// (e.g. 2025-01-01T00:00:00Z). Defaults to now.
+    #[clap(long = "validity-time", short = 'd')]
+    validity_time: Option<String>,
+}
+
+impl ValidateCertificateAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let validity_date = self
+            .validity_time
+            .as_deref()
+            .map(|validity_time| {
+                DateTime::parse_from_rfc3339(validity_time)
+                    .map(|date| date.timestamp_millis() as u64)
+                    .map_err(|e| {
+                        CliError::Conversion(format!("invalid validity time {validity_time}: {e}"))
+                    })
+            })
+            .transpose()?;
+
+        let unique_identifiers = std::iter::once(&self.certificate_id)
+            .chain(&self.intermediate_ids)
+            .map(|id| UniqueIdentifier::TextString(id.clone()))
+            .collect();
+
+        let response = kms_rest_client
+            .validate(Validate {
+                unique_identifiers: Some(unique_identifiers),
+                validity_date,
+            })
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        match response.validity_indicator {
+            ValidityIndicator::Valid => println!("The certificate chain is valid"),
+            other => cli_bail!(
+                "The certificate chain is not valid ({other}): {}",
+                response.result_message.unwrap_or_default()
+            ),
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use clap::Parser;
 use cosmian_kms_client::KmsClient;
 
-use self::keys::KeysCommands;
+use self::{keys::KeysCommands, sign::SignAction, verify::VerifyAction};
 #[cfg(not(feature = "fips"))]
 use self::{decrypt::DecryptAction, encrypt::EncryptAction};
 use crate::error::CliError;
@@ -11,8 +11,10 @@ mod decrypt;
 #[cfg(not(feature = "fips"))]
 mod encrypt;
 mod keys;
+mod sign;
+mod verify;
 
-/// Manage elliptic curve keys. Encrypt and decrypt data using ECIES.
+/// Manage elliptic curve keys. Encrypt and decrypt data using ECIES. Sign and verify using ECDSA or `EdDSA`.
 #[derive(Parser)]
 pub enum EllipticCurveCommands {
     #[command(subcommand)]
@@ -21,6 +23,8 @@ pub enum EllipticCurveCommands {
     Encrypt(EncryptAction),
     #[cfg(not(feature = "fips"))]
     Decrypt(DecryptAction),
+    Sign(SignAction),
+    Verify(VerifyAction),
 }
 
 impl EllipticCurveCommands {
@@ -31,6 +35,8 @@ impl EllipticCurveCommands {
             Self::Encrypt(action) => action.run(kms_rest_client).await?,
             #[cfg(not(feature = "fips"))]
             Self::Decrypt(action) => action.run(kms_rest_client).await?,
+            Self::Sign(action) => action.run(kms_rest_client).await?,
+            Self::Verify(action) => action.run(kms_rest_client).await?,
         };
         Ok(())
     }
@@ -0,0 +1,101 @@
+use std::{fs::File, io::Write, path::PathBuf};
+
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::{
+        crypto::generic::kmip_requests::build_sign_request,
+        kmip::kmip_types::CryptographicParameters,
+    },
+    read_bytes_from_file, KmsClient,
+};
+
+use crate::{
+    actions::rsa::HashFn,
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Sign a file with the given private key using
+///  - ECDSA for keys on the NIST curves
+///  - `EdDSA` for keys on curves Ed25519 and Ed448 (not available in FIPS mode)
+///
+/// The hashing algorithm is used by ECDSA only: `EdDSA` hashes the data internally.
+///
+/// Note: this is not a streaming call: the file is entirely loaded in memory before being sent for signature.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct SignAction {
+    /// The file to sign
+    #[clap(required = true, name = "FILE")]
+    input_file: PathBuf,
+
+    /// The private key unique identifier.
+    /// If not specified, tags should be specified
+    #[clap(long = "key-id", short = 'k', group = "key-tags")]
+    key_id: Option<String>,
+
+    /// Tag to use to retrieve the key when no key id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "key-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The hashing algorithm
+    #[clap(long = "hashing-algorithm", short = 's', default_value = "sha256")]
+    hash_fn: HashFn,
+
+    /// The signature output file path
+    #[clap(required = false, long, short = 'o')]
+    output_file: Option<PathBuf>,
+}
+
+impl SignAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        // Read the file to sign
+        let data = read_bytes_from_file(&self.input_file)
+            .with_context(|| "Cannot read bytes from the file to sign")?;
+
+        // Recover the unique identifier or set of tags
+        let id = if let Some(key_id) = &self.key_id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either `--key-id` or one or more `--tag` must be specified")
+        };
+
+        // Create the kmip query
+        let sign_request = build_sign_request(
+            &id,
+            data,
+            Some(CryptographicParameters {
+                hashing_algorithm: Some(self.hash_fn.into()),
+                ..Default::default()
+            }),
+        );
+
+        // Query the KMS with your kmip data and get the signature
+        let sign_response = kms_rest_client
+            .sign(sign_request)
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        let signature = sign_response
+            .signature_data
+            .context("The signature is empty")?;
+
+        // Write the signature file
+        let output_file = self
+            .output_file
+            .clone()
+            .unwrap_or_else(|| self.input_file.with_extension("sig"));
+        let mut buffer =
+            File::create(&output_file).with_context(|| "failed to write the signature file")?;
+        buffer
+            .write_all(&signature)
+            .with_context(|| "failed to write the signature file")?;
+
+        println!("The signature file is available at {output_file:?}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::{
+        crypto::generic::kmip_requests::build_signature_verify_request,
+        kmip::kmip_types::{CryptographicParameters, ValidityIndicator},
+    },
+    read_bytes_from_file, KmsClient,
+};
+
+use crate::{
+    actions::rsa::HashFn,
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Verify the signature of a file with the given public key or certificate
+///
+/// The hashing algorithm must match the one used to produce an ECDSA signature.
+///
+/// Note: this is not a streaming call: the file is entirely loaded in memory before being sent for verification.
+#[derive(Parser, Debug)]
+pub struct VerifyAction {
+    /// The file that was signed
+    #[clap(required = true, name = "FILE")]
+    input_file: PathBuf,
+
+    /// The signature file
+    #[clap(required = true, name = "SIGNATURE_FILE")]
+    signature_file: PathBuf,
+
+    /// The public key or certificate unique identifier.
+    /// If not specified, tags should be specified
+    #[clap(long = "key-id", short = 'k', group = "key-tags")]
+    key_id: Option<String>,
+
+    /// Tag to use to retrieve the key when no key id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "key-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The hashing algorithm
+    #[clap(long = "hashing-algorithm", short = 's', default_value = "sha256")]
+    hash_fn: HashFn,
+}
+
+impl VerifyAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        // Read the signed file and the signature
+        let data = read_bytes_from_file(&self.input_file)
+            .with_context(|| "Cannot read bytes from the signed file")?;
+        let signature = read_bytes_from_file(&self.signature_file)
+            .with_context(|| "Cannot read bytes from the signature file")?;
+
+        // Recover the unique identifier or set of tags
+        let id = if let Some(key_id) = &self.key_id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either `--key-id` or one or more `--tag` must be specified")
+        };
+
+        // Create the kmip query
+        let verify_request = build_signature_verify_request(
+            &id,
+            data,
+            signature,
+            Some(CryptographicParameters {
+                hashing_algorithm: Some(self.hash_fn.into()),
+                ..Default::default()
+            }),
+        );
+
+        // Query the KMS with your kmip data and get the validity indicator
+        let verify_response = kms_rest_client
+            .signature_verify(verify_request)
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        match verify_response.validity_indicator {
+            ValidityIndicator::Valid => println!("The signature is valid"),
+            other => cli_bail!("The signature verification failed: {other}"),
+        }
+
+        Ok(())
+    }
+}
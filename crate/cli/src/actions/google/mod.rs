@@ -1,29 +1,40 @@
 use std::path::PathBuf;
 
 use clap::Parser;
+use cosmian_kms_client::KmsClient;
 
-use self::{identities::IdentitiesCommands, keypairs::KeypairsCommands};
+use self::{
+    identities::IdentitiesCommands, keypairs::KeypairsCommands, rotate_cse_key::RotateCseKeyAction,
+};
 use crate::error::CliError;
 
 mod gmail_client;
 mod identities;
 mod keypairs;
+mod rotate_cse_key;
 pub(crate) use gmail_client::GoogleApiError;
 
-/// Manage google elements. Handle keypairs and identities from Gmail API.
+/// Manage google elements. Handle keypairs and identities from Gmail API,
+/// and rotate the Google CSE key of the KMS.
 #[derive(Parser)]
 pub enum GoogleCommands {
     #[command(subcommand)]
     Keypairs(KeypairsCommands),
     #[command(subcommand)]
     Identities(IdentitiesCommands),
+    Rotate(RotateCseKeyAction),
 }
 
 impl GoogleCommands {
-    pub async fn process(&self, conf_path: &PathBuf) -> Result<(), CliError> {
+    pub async fn process(
+        &self,
+        conf_path: &PathBuf,
+        kms_rest_client: &KmsClient,
+    ) -> Result<(), CliError> {
         match self {
             Self::Keypairs(command) => command.process(conf_path).await?,
             Self::Identities(command) => command.process(conf_path).await?,
+            Self::Rotate(action) => action.run(kms_rest_client).await?,
         };
         Ok(())
     }
@@ -0,0 +1,83 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    access::Access,
+    cosmian_kmip::{
+        crypto::symmetric::symmetric_key_rekey_request,
+        kmip::{
+            kmip_operations::GetAttributes,
+            kmip_types::{LinkType, UniqueIdentifier},
+        },
+    },
+    KmsClient,
+};
+
+use crate::error::{result::CliResultHelper, CliError};
+
+/// The tag of the Google CSE keys in the KMS
+const GOOGLE_CSE_TAG: &str = "google_cse";
+
+/// Rotate the Google CSE key
+///
+/// A new version of the key tagged `google_cse` is created and wraps
+/// the Data Encryption Keys and private keys from now on.
+/// The previous version is deactivated: it is kept to unwrap the keys
+/// it wrapped, but no longer wraps any key.
+///
+/// The access rights granted on the previous version are granted
+/// on the new version.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct RotateCseKeyAction {
+    /// The number of seconds between the creation and the activation
+    /// of the new version of the key
+    #[clap(long = "offset", short = 'o')]
+    offset: Option<i32>,
+}
+
+impl RotateCseKeyAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let new_key_id = kms_rest_client
+            .rekey(symmetric_key_rekey_request(
+                &serde_json::to_string(&[GOOGLE_CSE_TAG])?,
+                self.offset,
+            ))
+            .await
+            .with_context(|| "failed rotating the Google CSE key")?
+            .unique_identifier;
+
+        // the new version is linked to the version it replaces
+        let previous_key_id = kms_rest_client
+            .get_attributes(GetAttributes::from(new_key_id.to_string()))
+            .await
+            .with_context(|| "failed getting the attributes of the new Google CSE key")?
+            .attributes
+            .get_link(LinkType::ReplacedObjectLink)
+            .context("the new Google CSE key is not linked to the previous one")?;
+
+        // the users of the previous version must be able to use the new one
+        for user_access in kms_rest_client
+            .list_access(&previous_key_id)
+            .await
+            .with_context(|| "failed listing the access rights on the previous Google CSE key")?
+        {
+            kms_rest_client
+                .grant_access(Access {
+                    unique_identifier: Some(UniqueIdentifier::TextString(new_key_id.to_string())),
+                    user_id: user_access.user_id.clone(),
+                    operation_types: user_access.operations.into_iter().collect(),
+                    role: None,
+                })
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed granting the access rights of {} on the new Google CSE key",
+                        user_access.user_id
+                    )
+                })?;
+        }
+
+        println!("The Google CSE key was rotated with new id: {new_key_id}.");
+        println!("The previous key {previous_key_id} is kept to unwrap the keys it wrapped.");
+        Ok(())
+    }
+}
@@ -7,7 +7,9 @@ pub mod google;
 pub mod login;
 pub mod logout;
 pub mod markdown;
+pub mod ms_dke;
 pub mod new_database;
+pub mod rng;
 pub mod rsa;
 pub mod shared;
 pub mod symmetric;
@@ -0,0 +1,40 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::crypto::rsa::kmip_requests::create_rsa_key_pair_request, KmsClient,
+};
+
+use super::DKE_KEY_SIZE;
+use crate::error::{result::CliResultHelper, CliError};
+
+/// Create a Microsoft DKE key
+///
+/// The key is a 2048-bit RSA key pair tagged with the key name,
+/// which ends the Double Key Encryption URL of the sensitivity label.
+///
+/// The version of the key is the unique identifier of its public key.
+/// Grant the decrypt access right on the private key to the Azure AD
+/// users or groups allowed to open the documents.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct CreateDkeKeyAction {
+    /// The name of the DKE key
+    #[clap(required = true)]
+    key_name: String,
+}
+
+impl CreateDkeKeyAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let response = kms_rest_client
+            .create_key_pair(create_rsa_key_pair_request([&self.key_name], DKE_KEY_SIZE)?)
+            .await
+            .with_context(|| format!("failed creating the DKE key {}", self.key_name))?;
+
+        println!("The DKE key {} was created.", self.key_name);
+        println!("  Key version: {}", response.public_key_unique_identifier);
+        println!(
+            "  Private key unique identifier: {}",
+            response.private_key_unique_identifier
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use clap::Parser;
+use cosmian_kms_client::KmsClient;
+
+use self::{create_key::CreateDkeKeyAction, rotate_key::RotateDkeKeyAction};
+use crate::error::CliError;
+
+mod create_key;
+mod rotate_key;
+
+/// The size in bits of the RSA keys used by Microsoft Double Key Encryption
+const DKE_KEY_SIZE: usize = 2048;
+
+/// Manage the Microsoft Double Key Encryption (DKE) keys.
+#[derive(Parser)]
+pub enum DkeCommands {
+    Create(CreateDkeKeyAction),
+    Rotate(RotateDkeKeyAction),
+}
+
+impl DkeCommands {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        match self {
+            Self::Create(action) => action.run(kms_rest_client).await?,
+            Self::Rotate(action) => action.run(kms_rest_client).await?,
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    access::Access,
+    cosmian_kmip::{
+        crypto::{
+            generic::kmip_requests::build_revoke_key_request,
+            rsa::kmip_requests::create_rsa_key_pair_request,
+        },
+        kmip::{kmip_operations::GetAttributes, kmip_types::RevocationReason},
+    },
+    KmsClient,
+};
+
+use super::DKE_KEY_SIZE;
+use crate::error::{result::CliResultHelper, CliError};
+
+/// Rotate a Microsoft DKE key
+///
+/// A new version of the key is created and encrypts the documents from now on.
+/// The previous version is deactivated: it is kept to decrypt the documents
+/// it encrypted, but Office clients no longer receive its public key.
+///
+/// The access rights granted on the private key of the previous version
+/// are granted on the private key of the new version.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct RotateDkeKeyAction {
+    /// The name of the DKE key
+    #[clap(required = true)]
+    key_name: String,
+}
+
+impl RotateDkeKeyAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        // the private key of the current version
+        let previous_key_id = kms_rest_client
+            .get_attributes(GetAttributes::from(serde_json::to_string(&[
+                self.key_name.as_str(),
+                "_sk",
+            ])?))
+            .await
+            .with_context(|| format!("failed finding the current version of {}", self.key_name))?
+            .unique_identifier;
+
+        let response = kms_rest_client
+            .create_key_pair(create_rsa_key_pair_request([&self.key_name], DKE_KEY_SIZE)?)
+            .await
+            .with_context(|| format!("failed creating a new version of {}", self.key_name))?;
+        let new_key_id = response.private_key_unique_identifier;
+
+        // the users and groups of the previous version must be able to use the new one
+        for user_access in kms_rest_client
+            .list_access(&previous_key_id.to_string())
+            .await
+            .with_context(|| "failed listing the access rights on the previous DKE key")?
+        {
+            kms_rest_client
+                .grant_access(Access {
+                    unique_identifier: Some(new_key_id.clone()),
+                    user_id: user_access.user_id.clone(),
+                    operation_types: user_access.operations.into_iter().collect(),
+                    role: None,
+                })
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed granting the access rights of {} on the new DKE key",
+                        user_access.user_id
+                    )
+                })?;
+        }
+
+        // deactivate the previous version: it now only decrypts
+        kms_rest_client
+            .revoke(build_revoke_key_request(
+                &previous_key_id.to_string(),
+                RevocationReason::TextString("rotated".to_owned()),
+            )?)
+            .await
+            .with_context(|| "failed deactivating the previous DKE key")?;
+
+        println!(
+            "The DKE key {} was rotated with new version: {}",
+            self.key_name, response.public_key_unique_identifier
+        );
+        println!("  Private key unique identifier: {new_key_id}");
+        println!("  The previous version is kept to decrypt the documents it encrypted.");
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::kmip_operations::{RNGRetrieve, RNGSeed},
+    read_bytes_from_file, write_bytes_to_file, KmsClient,
+};
+
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Retrieve random bytes from the server or seed its random number generator.
+#[derive(Parser)]
+pub enum RngCommands {
+    Retrieve(RngRetrieveAction),
+    Seed(RngSeedAction),
+}
+
+impl RngCommands {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        match self {
+            Self::Retrieve(action) => action.run(kms_rest_client).await?,
+            Self::Seed(action) => action.run(kms_rest_client).await?,
+        };
+        Ok(())
+    }
+}
+
+/// Retrieve random bytes from the random number generator of the server.
+///
+/// The bytes are drawn from the OpenSSL DRBG of the server,
+/// the FIPS one when the server runs in FIPS mode.
+/// A single request cannot retrieve more than 1 MiB.
+///
+/// The bytes are written to the output file if specified,
+/// otherwise they are printed in hexadecimal.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct RngRetrieveAction {
+    /// The number of random bytes to retrieve
+    #[clap(long = "length", short = 'l', default_value = "32")]
+    length: usize,
+
+    /// The file to write the random bytes to
+    #[clap(required = false, long, short = 'o')]
+    output_file: Option<PathBuf>,
+}
+
+impl RngRetrieveAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        if self.length == 0 {
+            cli_bail!("The length must be strictly positive")
+        }
+        let data_length = i32::try_from(self.length).context("The length is too large")?;
+
+        let data = kms_rest_client
+            .rng_retrieve(RNGRetrieve { data_length })
+            .await
+            .with_context(|| "Can't execute the RNG retrieve query on the kms server")?
+            .data;
+
+        match &self.output_file {
+            Some(output_file) => {
+                write_bytes_to_file(&data, output_file)
+                    .with_context(|| "failed to write the random bytes")?;
+                println!(
+                    "{} random bytes were written to {}",
+                    data.len(),
+                    output_file.display()
+                );
+            }
+            None => println!("{}", hex::encode(&data)),
+        }
+        Ok(())
+    }
+}
+
+/// Provide seed data to the random number generator of the server.
+///
+/// The server reports the number of seed bytes it accepted: a server seeding
+/// its DRBG from the entropy source of its host, as required in FIPS mode,
+/// accepts none.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct RngSeedAction {
+    /// The file containing the seed data
+    #[clap(required = true, name = "FILE")]
+    input_file: PathBuf,
+}
+
+impl RngSeedAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let data =
+            read_bytes_from_file(&self.input_file).with_context(|| "Cannot read the seed file")?;
+        if data.is_empty() {
+            cli_bail!("The seed file is empty")
+        }
+
+        let response = kms_rest_client
+            .rng_seed(RNGSeed { data })
+            .await
+            .with_context(|| "Can't execute the RNG seed query on the kms server")?;
+
+        println!(
+            "The server accepted {} bytes of seed data.",
+            response.data_length
+        );
+        Ok(())
+    }
+}
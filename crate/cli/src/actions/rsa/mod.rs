@@ -7,12 +7,17 @@ use cosmian_kms_client::{
     KmsClient,
 };
 
-use self::{decrypt::DecryptAction, encrypt::EncryptAction, keys::KeysCommands};
+use self::{
+    decrypt::DecryptAction, encrypt::EncryptAction, keys::KeysCommands, sign::SignAction,
+    verify::VerifyAction,
+};
 use crate::error::CliError;
 
 mod decrypt;
 mod encrypt;
 mod keys;
+mod sign;
+mod verify;
 
 /// Manage RSA keys.
 #[derive(Parser)]
@@ -21,6 +26,8 @@ pub enum RsaCommands {
     Keys(KeysCommands),
     Encrypt(EncryptAction),
     Decrypt(DecryptAction),
+    Sign(SignAction),
+    Verify(VerifyAction),
 }
 
 impl RsaCommands {
@@ -29,6 +36,8 @@ impl RsaCommands {
             Self::Keys(command) => command.process(kms_rest_client).await?,
             Self::Encrypt(action) => action.run(kms_rest_client).await?,
             Self::Decrypt(action) => action.run(kms_rest_client).await?,
+            Self::Sign(action) => action.run(kms_rest_client).await?,
+            Self::Verify(action) => action.run(kms_rest_client).await?,
         };
         Ok(())
     }
@@ -56,6 +65,23 @@ impl Display for EncryptionAlgorithm {
     }
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum SignatureAlgorithm {
+    // a.k.a RSASSA-PSS
+    RsaPss,
+    // a.k.a RSASSA-PKCS1-v1_5
+    RsaPkcsV15,
+}
+
+impl Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureAlgorithm::RsaPss => write!(f, "rsa-pss"),
+            SignatureAlgorithm::RsaPkcsV15 => write!(f, "rsa-pkcs-v15"),
+        }
+    }
+}
+
 #[derive(clap::ValueEnum, Debug, Clone, Copy)]
 pub enum HashFn {
     Sha1,
@@ -127,3 +153,18 @@ fn to_cryptographic_parameters(
         },
     }
 }
+
+fn to_signature_cryptographic_parameters(
+    alg: SignatureAlgorithm,
+    hash_fn: HashFn,
+) -> CryptographicParameters {
+    CryptographicParameters {
+        cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
+        padding_method: Some(match alg {
+            SignatureAlgorithm::RsaPss => PaddingMethod::PSS,
+            SignatureAlgorithm::RsaPkcsV15 => PaddingMethod::PKCS1v15,
+        }),
+        hashing_algorithm: Some(hash_fn.into()),
+        ..Default::default()
+    }
+}
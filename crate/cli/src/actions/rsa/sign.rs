@@ -0,0 +1,103 @@
+use std::{fs::File, io::Write, path::PathBuf};
+
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::crypto::generic::kmip_requests::build_sign_request, read_bytes_from_file,
+    KmsClient,
+};
+
+use crate::{
+    actions::rsa::{to_signature_cryptographic_parameters, HashFn, SignatureAlgorithm},
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Sign a file with the given private key using either
+///  - `RSASSA-PSS` a.k.a `CKM_SHA256_RSA_PKCS_PSS` as specified in PKCS#11 v2.40
+///  - `RSASSA-PKCS1-v1_5` a.k.a `CKM_SHA256_RSA_PKCS` as specified in PKCS#11 v2.40
+///
+/// The data is hashed by the server using the given hashing algorithm
+/// before being signed.
+///
+/// Note: this is not a streaming call: the file is entirely loaded in memory before being sent for signature.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct SignAction {
+    /// The file to sign
+    #[clap(required = true, name = "FILE")]
+    input_file: PathBuf,
+
+    /// The private key unique identifier.
+    /// If not specified, tags should be specified
+    #[clap(long = "key-id", short = 'k', group = "key-tags")]
+    key_id: Option<String>,
+
+    /// Tag to use to retrieve the key when no key id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "key-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The signature algorithm
+    #[clap(long = "signature-algorithm", short = 'a', default_value = "rsa-pss")]
+    signature_algorithm: SignatureAlgorithm,
+
+    /// The hashing algorithm
+    #[clap(long = "hashing-algorithm", short = 's', default_value = "sha256")]
+    hash_fn: HashFn,
+
+    /// The signature output file path
+    #[clap(required = false, long, short = 'o')]
+    output_file: Option<PathBuf>,
+}
+
+impl SignAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        // Read the file to sign
+        let data = read_bytes_from_file(&self.input_file)
+            .with_context(|| "Cannot read bytes from the file to sign")?;
+
+        // Recover the unique identifier or set of tags
+        let id = if let Some(key_id) = &self.key_id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either `--key-id` or one or more `--tag` must be specified")
+        };
+
+        // Create the kmip query
+        let sign_request = build_sign_request(
+            &id,
+            data,
+            Some(to_signature_cryptographic_parameters(
+                self.signature_algorithm,
+                self.hash_fn,
+            )),
+        );
+
+        // Query the KMS with your kmip data and get the signature
+        let sign_response = kms_rest_client
+            .sign(sign_request)
+            .await
+            .with_context(|| "Can't execute the query on the kms server")?;
+
+        let signature = sign_response
+            .signature_data
+            .context("The signature is empty")?;
+
+        // Write the signature file
+        let output_file = self
+            .output_file
+            .clone()
+            .unwrap_or_else(|| self.input_file.with_extension("sig"));
+        let mut buffer =
+            File::create(&output_file).with_context(|| "failed to write the signature file")?;
+        buffer
+            .write_all(&signature)
+            .with_context(|| "failed to write the signature file")?;
+
+        println!("The signature file is available at {output_file:?}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::{kmip_operations::Activate, kmip_types::UniqueIdentifier},
+    KmsClient,
+};
+
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Activate a pre-active KMIP object.
+///
+/// Objects created with an activation date in the future are pre-active:
+/// they cannot be used until they are activated, either by this command
+/// or automatically once their activation date is reached.
+///
+/// When using tags to retrieve the object, rather than the object id,
+/// an error is returned if multiple objects matching the tags are found.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct ActivateAction {
+    /// The unique identifier of the cryptographic object.
+    /// If not specified, tags should be specified
+    #[clap(long = "id", short = 'i', group = "id-tags")]
+    id: Option<String>,
+
+    /// Tag to use to retrieve the object when no object id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "id-tags")]
+    tags: Option<Vec<String>>,
+}
+
+impl ActivateAction {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let id = if let Some(key_id) = &self.id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either --id or one or more --tag must be specified")
+        };
+
+        let response = kms_rest_client
+            .activate(Activate {
+                unique_identifier: Some(UniqueIdentifier::TextString(id)),
+            })
+            .await
+            .with_context(|| "failed activating the object")?;
+
+        println!("The object {} was activated.", response.unique_identifier);
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::{kmip_operations::Archive, kmip_types::UniqueIdentifier},
+    KmsClient,
+};
+
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Archive a KMIP object.
+///
+/// The object is moved to the cold storage of the server:
+/// it is no longer available for any operation until it is recovered
+/// using the `recover` command and its unique identifier.
+///
+/// When using tags to retrieve the object, rather than the object id,
+/// an error is returned if multiple objects matching the tags are found.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct ArchiveAction {
+    /// The unique identifier of the cryptographic object.
+    /// If not specified, tags should be specified
+    #[clap(long = "id", short = 'i', group = "id-tags")]
+    id: Option<String>,
+
+    /// Tag to use to retrieve the object when no object id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "id-tags")]
+    tags: Option<Vec<String>>,
+}
+
+impl ArchiveAction {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let id = if let Some(key_id) = &self.id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either --id or one or more --tag must be specified")
+        };
+
+        let response = kms_rest_client
+            .archive(Archive {
+                unique_identifier: Some(UniqueIdentifier::TextString(id)),
+            })
+            .await
+            .with_context(|| "failed archiving the object")?;
+
+        println!("The object {} was archived.", response.unique_identifier);
+        Ok(())
+    }
+}
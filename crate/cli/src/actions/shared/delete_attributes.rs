@@ -0,0 +1,243 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::{
+        kmip_operations::{DeleteAttribute, GetAttributes},
+        kmip_types::{
+            Attribute, AttributeReference, Link, LinkType, LinkedObjectIdentifier, Tag,
+            UniqueIdentifier, VendorAttributeReference,
+        },
+    },
+    KmsClient,
+};
+
+use super::AttributeTag;
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Delete the KMIP object attributes and tags.
+///
+/// The attributes are deleted with a KMIP `DeleteAttribute` request.
+/// Only the attributes that are not set by the server can be deleted
+/// (e.g. the cryptographic algorithm or length cannot be deleted).
+///
+/// Deleting the `tags` attribute deletes all the user tags of the object;
+/// use `--delete-tag` to delete some of them only.
+/// The system tags, starting with `_`, are never deleted.
+///
+/// When using tags to retrieve the object, rather than the object id,
+/// an error is returned if multiple objects matching the tags are found.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct DeleteAttributesAction {
+    /// The unique identifier of the cryptographic object.
+    /// If not specified, tags should be specified
+    #[clap(long = "id", short = 'i', group = "id-tags")]
+    id: Option<String>,
+
+    /// Tag to use to retrieve the object when no object id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "id-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The attributes to delete.
+    /// To specify multiple attributes, use the option multiple times.
+    #[clap(
+        long = "attribute",
+        short = 'a',
+        value_name = "ATTRIBUTE",
+        verbatim_doc_comment
+    )]
+    attribute_tags: Vec<AttributeTag>,
+
+    /// A user tag to delete from the object.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "delete-tag", short = 'd', value_name = "TAG")]
+    delete_tags: Option<Vec<String>>,
+
+    /// The vendor identification of a vendor attribute to delete
+    #[clap(long = "vendor-identification", requires = "attribute_name")]
+    vendor_identification: Option<String>,
+
+    /// The name of the vendor attribute to delete
+    #[clap(long = "attribute-name", requires = "vendor_identification")]
+    attribute_name: Option<String>,
+}
+
+impl DeleteAttributesAction {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let id = if let Some(key_id) = &self.id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either --id or one or more --tag must be specified")
+        };
+        let mut unique_identifier = UniqueIdentifier::TextString(id);
+
+        let mut references = Vec::with_capacity(self.attribute_tags.len());
+        let mut link_types = Vec::new();
+        for tag in &self.attribute_tags {
+            match tag {
+                AttributeTag::ActivationDate => {
+                    references.push(AttributeReference::Standard(Tag::ActivationDate));
+                }
+                AttributeTag::CryptographicAlgorithm => {
+                    references.push(AttributeReference::Standard(Tag::CryptographicAlgorithm));
+                }
+                AttributeTag::CryptographicLength => {
+                    references.push(AttributeReference::Standard(Tag::CryptographicLength));
+                }
+                AttributeTag::CryptographicParameters => {
+                    references.push(AttributeReference::Standard(Tag::CryptographicParameters));
+                }
+                AttributeTag::CryptographicDomainParameters => references.push(
+                    AttributeReference::Standard(Tag::CryptographicDomainParameters),
+                ),
+                AttributeTag::CryptographicUsageMask => {
+                    references.push(AttributeReference::Standard(Tag::CryptographicUsageMask));
+                }
+                AttributeTag::KeyFormatType => {
+                    references.push(AttributeReference::Standard(Tag::KeyFormatType));
+                }
+                AttributeTag::CompromiseDate => {
+                    references.push(AttributeReference::Standard(Tag::CompromiseDate));
+                }
+                AttributeTag::CompromiseOccurrenceDate => {
+                    references.push(AttributeReference::Standard(Tag::CompromiseOccurrenceDate));
+                }
+                AttributeTag::DeactivationDate => {
+                    references.push(AttributeReference::Standard(Tag::DeactivationDate));
+                }
+                AttributeTag::RevocationReason => {
+                    references.push(AttributeReference::Standard(Tag::RevocationReason));
+                }
+                AttributeTag::Comment => {
+                    references.push(AttributeReference::Standard(Tag::Comment));
+                }
+                AttributeTag::ContactInformation => {
+                    references.push(AttributeReference::Standard(Tag::ContactInformation));
+                }
+                AttributeTag::Description => {
+                    references.push(AttributeReference::Standard(Tag::Description));
+                }
+                AttributeTag::DestroyDate => {
+                    references.push(AttributeReference::Standard(Tag::DestroyDate));
+                }
+                AttributeTag::Extractable => {
+                    references.push(AttributeReference::Standard(Tag::Extractable));
+                }
+                AttributeTag::Fresh => {
+                    references.push(AttributeReference::Standard(Tag::Fresh));
+                }
+                AttributeTag::InitialDate => {
+                    references.push(AttributeReference::Standard(Tag::InitialDate));
+                }
+                AttributeTag::LastChangeDate => {
+                    references.push(AttributeReference::Standard(Tag::LastChangeDate));
+                }
+                AttributeTag::Name => {
+                    references.push(AttributeReference::Standard(Tag::Name));
+                }
+                AttributeTag::NeverExtractable => {
+                    references.push(AttributeReference::Standard(Tag::NeverExtractable));
+                }
+                AttributeTag::ObjectGroup => {
+                    references.push(AttributeReference::Standard(Tag::ObjectGroup));
+                }
+                AttributeTag::OriginalCreationDate => {
+                    references.push(AttributeReference::Standard(Tag::OriginalCreationDate));
+                }
+                AttributeTag::ProcessStartDate => {
+                    references.push(AttributeReference::Standard(Tag::ProcessStartDate));
+                }
+                AttributeTag::ProtectStopDate => {
+                    references.push(AttributeReference::Standard(Tag::ProtectStopDate));
+                }
+                AttributeTag::Sensitive => {
+                    references.push(AttributeReference::Standard(Tag::Sensitive));
+                }
+                AttributeTag::State => {
+                    references.push(AttributeReference::Standard(Tag::State));
+                }
+                AttributeTag::UsageLimits => {
+                    references.push(AttributeReference::Standard(Tag::UsageLimits));
+                }
+                AttributeTag::LinkedPrivateKeyId => {
+                    link_types.push((Tag::PrivateKey, LinkType::PrivateKeyLink));
+                }
+                AttributeTag::LinkedPublicKeyId => {
+                    link_types.push((Tag::PublicKey, LinkType::PublicKeyLink));
+                }
+                AttributeTag::LinkedIssuerCertificateId => {
+                    link_types.push((Tag::Certificate, LinkType::CertificateLink));
+                }
+                AttributeTag::LinkedCertificateId => {
+                    link_types.push((Tag::Certificate, LinkType::PKCS12CertificateLink));
+                }
+                AttributeTag::Tags => {
+                    references.push(AttributeReference::tags_reference());
+                }
+            }
+        }
+        if let (Some(vendor_identification), Some(attribute_name)) =
+            (&self.vendor_identification, &self.attribute_name)
+        {
+            references.push(AttributeReference::Vendor(VendorAttributeReference {
+                vendor_identification: vendor_identification.clone(),
+                attribute_name: attribute_name.clone(),
+            }));
+        }
+
+        // the current instances of the links to delete are retrieved first
+        let mut current_attributes = Vec::new();
+        for (tag, link_type) in link_types {
+            let response = kms_rest_client
+                .get_attributes(GetAttributes {
+                    unique_identifier: Some(unique_identifier.clone()),
+                    attribute_references: Some(vec![AttributeReference::Standard(tag)]),
+                })
+                .await?;
+            let Some(linked_object_id) = response.attributes.get_link(link_type) else {
+                cli_bail!("the object has no {link_type} to delete")
+            };
+            current_attributes.push(Attribute::Link(Link {
+                link_type,
+                linked_object_identifier: LinkedObjectIdentifier::TextString(linked_object_id),
+            }));
+        }
+        if let Some(delete_tags) = &self.delete_tags {
+            current_attributes.push(Attribute::tags(delete_tags)?);
+        }
+        if references.is_empty() && current_attributes.is_empty() {
+            cli_bail!("At least one attribute to delete must be specified")
+        }
+
+        for current_attribute in current_attributes {
+            unique_identifier = kms_rest_client
+                .delete_attribute(DeleteAttribute {
+                    unique_identifier: Some(unique_identifier),
+                    current_attribute: Some(current_attribute),
+                    attribute_references: None,
+                })
+                .await
+                .with_context(|| "failed deleting the attribute")?
+                .unique_identifier;
+        }
+        if !references.is_empty() {
+            unique_identifier = kms_rest_client
+                .delete_attribute(DeleteAttribute {
+                    unique_identifier: Some(unique_identifier),
+                    current_attribute: None,
+                    attribute_references: Some(references),
+                })
+                .await
+                .with_context(|| "failed deleting the attributes")?
+                .unique_identifier;
+        }
+
+        println!("The attributes were deleted for {unique_identifier}.");
+        Ok(())
+    }
+}
@@ -16,12 +16,33 @@ use crate::{cli_bail, error::CliError};
 #[derive(clap::ValueEnum, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AttributeTag {
     ActivationDate,
+    CompromiseDate,
+    CompromiseOccurrenceDate,
     CryptographicAlgorithm,
     CryptographicLength,
     CryptographicParameters,
     CryptographicDomainParameters,
     CryptographicUsageMask,
+    DeactivationDate,
     KeyFormatType,
+    RevocationReason,
+    Comment,
+    ContactInformation,
+    Description,
+    DestroyDate,
+    Extractable,
+    Fresh,
+    InitialDate,
+    LastChangeDate,
+    Name,
+    NeverExtractable,
+    ObjectGroup,
+    OriginalCreationDate,
+    ProcessStartDate,
+    ProtectStopDate,
+    Sensitive,
+    State,
+    UsageLimits,
     LinkedPrivateKeyId,
     LinkedPublicKeyId,
     LinkedIssuerCertificateId,
@@ -29,14 +50,35 @@ pub enum AttributeTag {
     Tags,
 }
 
-const ALL_ATTRIBUTE_TAGS: [AttributeTag; 12] = [
+const ALL_ATTRIBUTE_TAGS: [AttributeTag; 33] = [
     AttributeTag::ActivationDate,
+    AttributeTag::CompromiseDate,
+    AttributeTag::CompromiseOccurrenceDate,
     AttributeTag::CryptographicAlgorithm,
     AttributeTag::CryptographicLength,
     AttributeTag::CryptographicParameters,
     AttributeTag::CryptographicDomainParameters,
     AttributeTag::CryptographicUsageMask,
+    AttributeTag::DeactivationDate,
     AttributeTag::KeyFormatType,
+    AttributeTag::RevocationReason,
+    AttributeTag::Comment,
+    AttributeTag::ContactInformation,
+    AttributeTag::Description,
+    AttributeTag::DestroyDate,
+    AttributeTag::Extractable,
+    AttributeTag::Fresh,
+    AttributeTag::InitialDate,
+    AttributeTag::LastChangeDate,
+    AttributeTag::Name,
+    AttributeTag::NeverExtractable,
+    AttributeTag::ObjectGroup,
+    AttributeTag::OriginalCreationDate,
+    AttributeTag::ProcessStartDate,
+    AttributeTag::ProtectStopDate,
+    AttributeTag::Sensitive,
+    AttributeTag::State,
+    AttributeTag::UsageLimits,
     AttributeTag::LinkedPrivateKeyId,
     AttributeTag::LinkedPublicKeyId,
     AttributeTag::LinkedIssuerCertificateId,
@@ -93,6 +135,12 @@ impl GetAttributesAction {
                 AttributeTag::ActivationDate => {
                     references.push(AttributeReference::Standard(Tag::ActivationDate));
                 }
+                AttributeTag::CompromiseDate => {
+                    references.push(AttributeReference::Standard(Tag::CompromiseDate));
+                }
+                AttributeTag::CompromiseOccurrenceDate => {
+                    references.push(AttributeReference::Standard(Tag::CompromiseOccurrenceDate));
+                }
                 AttributeTag::CryptographicAlgorithm => {
                     references.push(AttributeReference::Standard(Tag::CryptographicAlgorithm));
                 }
@@ -111,6 +159,63 @@ impl GetAttributesAction {
                 AttributeTag::KeyFormatType => {
                     references.push(AttributeReference::Standard(Tag::KeyFormatType));
                 }
+                AttributeTag::DeactivationDate => {
+                    references.push(AttributeReference::Standard(Tag::DeactivationDate));
+                }
+                AttributeTag::RevocationReason => {
+                    references.push(AttributeReference::Standard(Tag::RevocationReason));
+                }
+                AttributeTag::Comment => {
+                    references.push(AttributeReference::Standard(Tag::Comment));
+                }
+                AttributeTag::ContactInformation => {
+                    references.push(AttributeReference::Standard(Tag::ContactInformation));
+                }
+                AttributeTag::Description => {
+                    references.push(AttributeReference::Standard(Tag::Description));
+                }
+                AttributeTag::DestroyDate => {
+                    references.push(AttributeReference::Standard(Tag::DestroyDate));
+                }
+                AttributeTag::Extractable => {
+                    references.push(AttributeReference::Standard(Tag::Extractable));
+                }
+                AttributeTag::Fresh => {
+                    references.push(AttributeReference::Standard(Tag::Fresh));
+                }
+                AttributeTag::InitialDate => {
+                    references.push(AttributeReference::Standard(Tag::InitialDate));
+                }
+                AttributeTag::LastChangeDate => {
+                    references.push(AttributeReference::Standard(Tag::LastChangeDate));
+                }
+                AttributeTag::Name => {
+                    references.push(AttributeReference::Standard(Tag::Name));
+                }
+                AttributeTag::NeverExtractable => {
+                    references.push(AttributeReference::Standard(Tag::NeverExtractable));
+                }
+                AttributeTag::ObjectGroup => {
+                    references.push(AttributeReference::Standard(Tag::ObjectGroup));
+                }
+                AttributeTag::OriginalCreationDate => {
+                    references.push(AttributeReference::Standard(Tag::OriginalCreationDate));
+                }
+                AttributeTag::ProcessStartDate => {
+                    references.push(AttributeReference::Standard(Tag::ProcessStartDate));
+                }
+                AttributeTag::ProtectStopDate => {
+                    references.push(AttributeReference::Standard(Tag::ProtectStopDate));
+                }
+                AttributeTag::Sensitive => {
+                    references.push(AttributeReference::Standard(Tag::Sensitive));
+                }
+                AttributeTag::State => {
+                    references.push(AttributeReference::Standard(Tag::State));
+                }
+                AttributeTag::UsageLimits => {
+                    references.push(AttributeReference::Standard(Tag::UsageLimits));
+                }
                 AttributeTag::LinkedPrivateKeyId => {
                     references.push(AttributeReference::Standard(Tag::PrivateKey));
                 }
@@ -157,6 +262,22 @@ impl GetAttributesAction {
                         );
                     }
                 }
+                AttributeTag::CompromiseDate => {
+                    if let Some(v) = attributes.compromise_date.as_ref() {
+                        results.insert(
+                            "compromise-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::CompromiseOccurrenceDate => {
+                    if let Some(v) = attributes.compromise_occurrence_date.as_ref() {
+                        results.insert(
+                            "compromise-occurrence-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
                 AttributeTag::CryptographicAlgorithm => {
                     if let Some(v) = attributes.cryptographic_algorithm.as_ref() {
                         results.insert(
@@ -205,6 +326,158 @@ impl GetAttributesAction {
                         );
                     }
                 }
+                AttributeTag::DeactivationDate => {
+                    if let Some(v) = attributes.deactivation_date.as_ref() {
+                        results.insert(
+                            "deactivation-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::RevocationReason => {
+                    if let Some(v) = attributes.revocation_reason.as_ref() {
+                        results.insert(
+                            "revocation-reason".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::Comment => {
+                    if let Some(v) = attributes.comment.as_ref() {
+                        results.insert(
+                            "comment".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::ContactInformation => {
+                    if let Some(v) = attributes.contact_information.as_ref() {
+                        results.insert(
+                            "contact-information".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::Description => {
+                    if let Some(v) = attributes.description.as_ref() {
+                        results.insert(
+                            "description".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::DestroyDate => {
+                    if let Some(v) = attributes.destroy_date.as_ref() {
+                        results.insert(
+                            "destroy-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::Extractable => {
+                    if let Some(v) = attributes.extractable.as_ref() {
+                        results.insert(
+                            "extractable".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::Fresh => {
+                    if let Some(v) = attributes.fresh.as_ref() {
+                        results.insert(
+                            "fresh".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::InitialDate => {
+                    if let Some(v) = attributes.initial_date.as_ref() {
+                        results.insert(
+                            "initial-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::LastChangeDate => {
+                    if let Some(v) = attributes.last_change_date.as_ref() {
+                        results.insert(
+                            "last-change-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::Name => {
+                    if let Some(v) = attributes.name.as_ref() {
+                        results.insert(
+                            "name".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::NeverExtractable => {
+                    if let Some(v) = attributes.never_extractable.as_ref() {
+                        results.insert(
+                            "never-extractable".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::ObjectGroup => {
+                    if let Some(v) = attributes.object_group.as_ref() {
+                        results.insert(
+                            "object-group".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::OriginalCreationDate => {
+                    if let Some(v) = attributes.original_creation_date.as_ref() {
+                        results.insert(
+                            "original-creation-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::ProcessStartDate => {
+                    if let Some(v) = attributes.process_start_date.as_ref() {
+                        results.insert(
+                            "process-start-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::ProtectStopDate => {
+                    if let Some(v) = attributes.protect_stop_date.as_ref() {
+                        results.insert(
+                            "protect-stop-date".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::Sensitive => {
+                    if let Some(v) = attributes.sensitive.as_ref() {
+                        results.insert(
+                            "sensitive".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::State => {
+                    if let Some(v) = attributes.state.as_ref() {
+                        results.insert(
+                            "state".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
+                AttributeTag::UsageLimits => {
+                    if let Some(v) = attributes.usage_limits.as_ref() {
+                        results.insert(
+                            "usage-limits".to_string(),
+                            serde_json::to_value(v).unwrap_or_default(),
+                        );
+                    }
+                }
                 AttributeTag::LinkedPrivateKeyId => {
                     if let Some(v) = attributes.get_link(LinkType::PrivateKeyLink) {
                         results.insert(
@@ -9,6 +9,7 @@ use cosmian_kms_client::{
         kmip_operations::Locate,
         kmip_types::{
             Attributes, CryptographicAlgorithm, KeyFormatType, LinkType, LinkedObjectIdentifier,
+            Name, NameType,
         },
     },
     KmsClient,
@@ -72,6 +73,14 @@ pub struct LocateObjectsAction {
     /// Locate an object which has a link to this certificate key id.
     #[clap(long = "certificate-id", short = 'c')]
     certificate_id: Option<String>,
+
+    /// Locate an object which has this name.
+    #[clap(long = "name", short = 'n')]
+    name: Option<String>,
+
+    /// Locate an object which belongs to this object group.
+    #[clap(long = "object-group", short = 'g')]
+    object_group: Option<String>,
 }
 
 impl LocateObjectsAction {
@@ -112,6 +121,17 @@ impl LocateObjectsAction {
             );
         }
 
+        if let Some(name) = &self.name {
+            attributes.name = Some(vec![Name {
+                name_value: name.clone(),
+                name_type: NameType::UninterpretedTextString,
+            }]);
+        }
+
+        if let Some(object_group) = &self.object_group {
+            attributes.object_group = Some(object_group.clone());
+        }
+
         if let Some(tags) = &self.tags {
             attributes.set_tags(tags.clone())?;
         }
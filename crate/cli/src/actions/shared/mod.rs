@@ -1,7 +1,12 @@
+mod activate;
+mod archive;
+mod delete_attributes;
 mod export_key;
 mod get_attributes;
 pub(crate) mod import_key;
 mod locate;
+mod recover;
+mod set_attributes;
 pub mod utils;
 
 #[cfg(feature = "openssl")]
@@ -10,10 +15,15 @@ mod wrap_key;
 #[cfg(feature = "openssl")]
 mod unwrap_key;
 
+pub use activate::ActivateAction;
+pub use archive::ArchiveAction;
+pub use delete_attributes::DeleteAttributesAction;
 pub use export_key::{ExportKeyAction, ExportKeyFormat};
 pub use get_attributes::{AttributeTag, GetAttributesAction};
 pub use import_key::ImportKeyAction;
 pub use locate::LocateObjectsAction;
+pub use recover::RecoverAction;
+pub use set_attributes::SetAttributesAction;
 #[cfg(feature = "openssl")]
 pub use unwrap_key::UnwrapKeyAction;
 #[cfg(feature = "openssl")]
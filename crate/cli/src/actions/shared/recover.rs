@@ -0,0 +1,33 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::{kmip_operations::Recover, kmip_types::UniqueIdentifier},
+    KmsClient,
+};
+
+use crate::error::{result::CliResultHelper, CliError};
+
+/// Recover an archived KMIP object.
+///
+/// The object is moved back from the cold storage of the server
+/// and is available again, in the state it was archived.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct RecoverAction {
+    /// The unique identifier of the archived object
+    #[clap(long = "id", short = 'i', required = true)]
+    id: String,
+}
+
+impl RecoverAction {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let response = kms_rest_client
+            .recover(Recover {
+                unique_identifier: Some(UniqueIdentifier::TextString(self.id.clone())),
+            })
+            .await
+            .with_context(|| "failed recovering the object")?;
+
+        println!("The object {} was recovered.", response.unique_identifier);
+        Ok(())
+    }
+}
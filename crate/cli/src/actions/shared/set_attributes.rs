@@ -0,0 +1,197 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::{
+        kmip_operations::SetAttribute,
+        kmip_types::{
+            Attribute, Link, LinkType, LinkedObjectIdentifier, Name, NameType, UniqueIdentifier,
+            VendorAttribute,
+        },
+    },
+    KmsClient,
+};
+
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Set the KMIP object attributes and tags.
+///
+/// Each attribute is set with a KMIP `SetAttribute` request:
+/// an existing value is replaced, a missing one is created.
+///
+/// The user tags of the object are replaced by the `--new-tag` tags;
+/// the system tags, starting with `_`, are preserved.
+///
+/// When using tags to retrieve the object, rather than the object id,
+/// an error is returned if multiple objects matching the tags are found.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct SetAttributesAction {
+    /// The unique identifier of the cryptographic object.
+    /// If not specified, tags should be specified
+    #[clap(long = "id", short = 'i', group = "id-tags")]
+    id: Option<String>,
+
+    /// Tag to use to retrieve the object when no object id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "id-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The activation date of the object, in milliseconds since the UNIX epoch
+    #[clap(long = "activation-date", short = 'd')]
+    activation_date: Option<u64>,
+
+    /// A name of the object. It replaces the name of the object, if any
+    #[clap(long = "name")]
+    name: Option<String>,
+
+    /// The object group of the object
+    #[clap(long = "object-group")]
+    object_group: Option<String>,
+
+    /// The description of the object
+    #[clap(long = "description")]
+    description: Option<String>,
+
+    /// A comment on the object
+    #[clap(long = "comment")]
+    comment: Option<String>,
+
+    /// The contact information of the object, such as the name of its owner
+    #[clap(long = "contact-information")]
+    contact_information: Option<String>,
+
+    /// Whether the key material can only be exported wrapped
+    #[clap(long = "sensitive")]
+    sensitive: Option<bool>,
+
+    /// Whether the key material can be exported
+    #[clap(long = "extractable")]
+    extractable: Option<bool>,
+
+    /// The new user tags of the object.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "new-tag", short = 'n', value_name = "TAG")]
+    new_tags: Option<Vec<String>>,
+
+    /// The unique identifier of the linked private key
+    #[clap(long = "linked-private-key-id")]
+    linked_private_key_id: Option<String>,
+
+    /// The unique identifier of the linked public key
+    #[clap(long = "linked-public-key-id")]
+    linked_public_key_id: Option<String>,
+
+    /// The unique identifier of the linked issuer certificate
+    #[clap(long = "linked-issuer-certificate-id")]
+    linked_issuer_certificate_id: Option<String>,
+
+    /// The unique identifier of the linked certificate
+    #[clap(long = "linked-certificate-id")]
+    linked_certificate_id: Option<String>,
+
+    /// The vendor identification of a vendor attribute to set
+    #[clap(long = "vendor-identification", requires_all = ["attribute_name", "attribute_value"])]
+    vendor_identification: Option<String>,
+
+    /// The name of the vendor attribute to set
+    #[clap(long = "attribute-name", requires = "vendor_identification")]
+    attribute_name: Option<String>,
+
+    /// The value of the vendor attribute to set, as a hex string
+    #[clap(long = "attribute-value", requires = "vendor_identification")]
+    attribute_value: Option<String>,
+}
+
+impl SetAttributesAction {
+    pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        let id = if let Some(key_id) = &self.id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either --id or one or more --tag must be specified")
+        };
+
+        let mut attributes = Vec::new();
+        if let Some(activation_date) = self.activation_date {
+            attributes.push(Attribute::ActivationDate(activation_date));
+        }
+        if let Some(name) = &self.name {
+            attributes.push(Attribute::Name(Name {
+                name_value: name.clone(),
+                name_type: NameType::UninterpretedTextString,
+            }));
+        }
+        if let Some(object_group) = &self.object_group {
+            attributes.push(Attribute::ObjectGroup(object_group.clone()));
+        }
+        if let Some(description) = &self.description {
+            attributes.push(Attribute::Description(description.clone()));
+        }
+        if let Some(comment) = &self.comment {
+            attributes.push(Attribute::Comment(comment.clone()));
+        }
+        if let Some(contact_information) = &self.contact_information {
+            attributes.push(Attribute::ContactInformation(contact_information.clone()));
+        }
+        if let Some(sensitive) = self.sensitive {
+            attributes.push(Attribute::Sensitive(sensitive));
+        }
+        if let Some(extractable) = self.extractable {
+            attributes.push(Attribute::Extractable(extractable));
+        }
+        if let Some(new_tags) = &self.new_tags {
+            attributes.push(Attribute::tags(new_tags)?);
+        }
+        for (link_type, linked_object_id) in [
+            (LinkType::PrivateKeyLink, &self.linked_private_key_id),
+            (LinkType::PublicKeyLink, &self.linked_public_key_id),
+            (
+                LinkType::CertificateLink,
+                &self.linked_issuer_certificate_id,
+            ),
+            (LinkType::PKCS12CertificateLink, &self.linked_certificate_id),
+        ] {
+            if let Some(linked_object_id) = linked_object_id {
+                attributes.push(Attribute::Link(Link {
+                    link_type,
+                    linked_object_identifier: LinkedObjectIdentifier::TextString(
+                        linked_object_id.clone(),
+                    ),
+                }));
+            }
+        }
+        if let (Some(vendor_identification), Some(attribute_name), Some(attribute_value)) = (
+            &self.vendor_identification,
+            &self.attribute_name,
+            &self.attribute_value,
+        ) {
+            attributes.push(Attribute::VendorAttribute(VendorAttribute {
+                vendor_identification: vendor_identification.clone(),
+                attribute_name: attribute_name.clone(),
+                attribute_value: hex::decode(attribute_value)
+                    .with_context(|| "the attribute value is not a valid hex string")?,
+            }));
+        }
+        if attributes.is_empty() {
+            cli_bail!("At least one attribute to set must be specified")
+        }
+
+        let mut unique_identifier = UniqueIdentifier::TextString(id);
+        for attribute in attributes {
+            unique_identifier = kms_rest_client
+                .set_attribute(SetAttribute {
+                    unique_identifier: Some(unique_identifier),
+                    new_attribute: attribute,
+                })
+                .await
+                .with_context(|| "failed setting the attribute")?
+                .unique_identifier;
+        }
+
+        println!("The attributes were set for {unique_identifier}.");
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use clap::Parser;
 use cosmian_kms_client::{
     cosmian_kmip::{
         crypto::symmetric::{create_symmetric_key_kmip_object, symmetric_key_create_request},
-        kmip::kmip_types::CryptographicAlgorithm,
+        kmip::kmip_types::{CryptographicAlgorithm, CryptographicUsageMask},
     },
     import_object, KmsClient,
 };
@@ -20,6 +20,10 @@ pub enum SymmetricAlgorithm {
     Aes,
     Sha3,
     Shake,
+    HmacSha256,
+    HmacSha384,
+    HmacSha512,
+    Cmac,
 }
 
 /// Create a new symmetric key
@@ -29,6 +33,9 @@ pub enum SymmetricAlgorithm {
 ///
 /// If no options are specified, a fresh 256-bit AES key will be created.
 ///
+/// HMAC keys (`hmac-sha256`, `hmac-sha384`, `hmac-sha512`) and CMAC keys (`cmac`,
+/// an AES key of 128, 192 or 256 bits) can only be used to compute and verify MACs.
+///
 /// Tags can later be used to retrieve the key. Tags are optional.
 #[derive(Parser)]
 #[clap(verbatim_doc_comment)]
@@ -92,11 +99,29 @@ impl CreateKeyAction {
                 256 => CryptographicAlgorithm::SHAKE256,
                 _ => cli_bail!("invalid number of bits for shake {}", number_of_bits),
             },
+            SymmetricAlgorithm::HmacSha256 => CryptographicAlgorithm::HMACSHA256,
+            SymmetricAlgorithm::HmacSha384 => CryptographicAlgorithm::HMACSHA384,
+            SymmetricAlgorithm::HmacSha512 => CryptographicAlgorithm::HMACSHA512,
+            SymmetricAlgorithm::Cmac => match number_of_bits {
+                128 | 192 | 256 => CryptographicAlgorithm::AES,
+                _ => cli_bail!("invalid number of bits for cmac {}", number_of_bits),
+            },
+        };
+
+        // CMAC keys are AES keys restricted to MAC operations
+        let usage_mask = match self.algorithm {
+            SymmetricAlgorithm::Cmac => {
+                Some(CryptographicUsageMask::MACGenerate | CryptographicUsageMask::MACVerify)
+            }
+            _ => None,
         };
 
         let unique_identifier = match key_bytes {
             Some(key_bytes) => {
-                let object = create_symmetric_key_kmip_object(key_bytes.as_slice(), algorithm);
+                let mut object = create_symmetric_key_kmip_object(key_bytes.as_slice(), algorithm);
+                if usage_mask.is_some() {
+                    object.attributes_mut()?.cryptographic_usage_mask = usage_mask;
+                }
                 import_object(
                     kms_rest_client,
                     None,
@@ -109,8 +134,11 @@ impl CreateKeyAction {
                 .await?
             }
             None => {
-                let create_key_request =
+                let mut create_key_request =
                     symmetric_key_create_request(number_of_bits, algorithm, &self.tags)?;
+                if usage_mask.is_some() {
+                    create_key_request.attributes.cryptographic_usage_mask = usage_mask;
+                }
                 kms_rest_client
                     .create(create_key_request)
                     .await
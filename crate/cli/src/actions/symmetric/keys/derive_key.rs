@@ -0,0 +1,183 @@
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::{
+        crypto::generic::kmip_requests::build_derive_key_request,
+        kmip::{
+            kmip_objects::ObjectType,
+            kmip_operations::{DerivationMethod, DerivationParameters},
+            kmip_types::{CryptographicAlgorithm, CryptographicParameters, HashingAlgorithm},
+        },
+    },
+    KmsClient,
+};
+
+use super::create_key::SymmetricAlgorithm;
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum KeyDerivationMethod {
+    Hkdf,
+    Pbkdf2,
+    /// NIST SP 800-108 in counter mode
+    #[value(name = "nist800-108-c")]
+    Nist800108C,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+pub enum PrfHashingAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+}
+
+/// Derive a new key from an existing symmetric key or secret data
+///
+/// The base key must have the `DeriveKey` usage.
+/// The derived object is linked to its base key.
+///
+/// The pseudo-random function of the derivation methods is an HMAC
+/// using `--hashing-algorithm` (SHA-256 by default).
+/// - `hkdf`: the optional salt and derivation data (info) are used
+/// - `pbkdf2`: the salt and the iteration count are mandatory
+/// - `nist800-108-c`: the derivation data is the fixed input data
+///
+/// By default, a 256-bit AES key is derived. Use `--secret-data`
+/// to derive secret data (a seed) instead of a symmetric key.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct DeriveKeyAction {
+    /// The base key unique identifier.
+    /// If not specified, tags should be specified
+    #[clap(long = "key-id", short = 'k', group = "key-tags")]
+    key_id: Option<String>,
+
+    /// Tag to use to retrieve the base key when no key id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "key-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The derivation method
+    #[clap(long = "derivation-method", short = 'm', default_value = "hkdf")]
+    derivation_method: KeyDerivationMethod,
+
+    /// The hashing algorithm of the pseudo-random function
+    #[clap(long = "hashing-algorithm", short = 'g', default_value = "sha256")]
+    hashing_algorithm: PrfHashingAlgorithm,
+
+    /// The salt as a hex string
+    #[clap(long = "salt", short = 's')]
+    salt: Option<String>,
+
+    /// The number of iterations (PBKDF2 only)
+    #[clap(long = "iteration-count", short = 'i')]
+    iteration_count: Option<i32>,
+
+    /// The derivation data as a hex string
+    #[clap(long = "derivation-data", short = 'd')]
+    derivation_data: Option<String>,
+
+    /// The length of the derived key in bits
+    #[clap(long = "number-of-bits", short = 'l', default_value = "256")]
+    number_of_bits: usize,
+
+    /// The algorithm of the derived symmetric key
+    #[clap(long = "algorithm", short = 'a', default_value = "aes")]
+    algorithm: SymmetricAlgorithm,
+
+    /// Derive secret data instead of a symmetric key
+    #[clap(long = "secret-data", default_value = "false")]
+    secret_data: bool,
+
+    /// The tag to associate with the derived key.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "derived-key-tag", value_name = "TAG")]
+    derived_key_tags: Vec<String>,
+}
+
+impl DeriveKeyAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        // Recover the unique identifier or set of tags
+        let id = if let Some(key_id) = &self.key_id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either `--key-id` or one or more `--tag` must be specified")
+        };
+
+        let derivation_method = match self.derivation_method {
+            KeyDerivationMethod::Hkdf => DerivationMethod::HKDF,
+            KeyDerivationMethod::Pbkdf2 => DerivationMethod::PBKDF2,
+            KeyDerivationMethod::Nist800108C => DerivationMethod::NIST800_108C,
+        };
+        let hashing_algorithm = match self.hashing_algorithm {
+            PrfHashingAlgorithm::Sha256 => HashingAlgorithm::SHA256,
+            PrfHashingAlgorithm::Sha384 => HashingAlgorithm::SHA384,
+            PrfHashingAlgorithm::Sha512 => HashingAlgorithm::SHA512,
+            PrfHashingAlgorithm::Sha3_256 => HashingAlgorithm::SHA3256,
+            PrfHashingAlgorithm::Sha3_384 => HashingAlgorithm::SHA3384,
+            PrfHashingAlgorithm::Sha3_512 => HashingAlgorithm::SHA3512,
+        };
+
+        let derivation_parameters = DerivationParameters {
+            cryptographic_parameters: Some(CryptographicParameters {
+                hashing_algorithm: Some(hashing_algorithm),
+                ..CryptographicParameters::default()
+            }),
+            salt: self
+                .salt
+                .as_ref()
+                .map(hex::decode)
+                .transpose()
+                .with_context(|| "the salt is not a valid hex string")?,
+            iteration_count: self.iteration_count,
+            derivation_data: self
+                .derivation_data
+                .as_ref()
+                .map(hex::decode)
+                .transpose()
+                .with_context(|| "the derivation data is not a valid hex string")?
+                .map(Into::into),
+            ..DerivationParameters::default()
+        };
+
+        let (object_type, cryptographic_algorithm) = if self.secret_data {
+            (ObjectType::SecretData, None)
+        } else {
+            let algorithm = match self.algorithm {
+                SymmetricAlgorithm::Aes => CryptographicAlgorithm::AES,
+                #[cfg(not(feature = "fips"))]
+                SymmetricAlgorithm::Chacha20 => CryptographicAlgorithm::ChaCha20,
+                SymmetricAlgorithm::HmacSha256 => CryptographicAlgorithm::HMACSHA256,
+                SymmetricAlgorithm::HmacSha384 => CryptographicAlgorithm::HMACSHA384,
+                SymmetricAlgorithm::HmacSha512 => CryptographicAlgorithm::HMACSHA512,
+                other => cli_bail!("deriving a {other:?} key is not supported"),
+            };
+            (ObjectType::SymmetricKey, Some(algorithm))
+        };
+
+        let request = build_derive_key_request(
+            &id,
+            object_type,
+            derivation_method,
+            derivation_parameters,
+            cryptographic_algorithm,
+            self.number_of_bits,
+            &self.derived_key_tags,
+        )?;
+        let unique_identifier = kms_rest_client
+            .derive_key(request)
+            .await
+            .with_context(|| "failed deriving the key")?
+            .unique_identifier;
+
+        println!("The key was derived with id: {unique_identifier}.");
+        Ok(())
+    }
+}
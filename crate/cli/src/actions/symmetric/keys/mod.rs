@@ -2,7 +2,8 @@ use clap::Subcommand;
 use cosmian_kms_client::KmsClient;
 
 use self::{
-    create_key::CreateKeyAction, destroy_key::DestroyKeyAction, revoke_key::RevokeKeyAction,
+    create_key::CreateKeyAction, derive_key::DeriveKeyAction, destroy_key::DestroyKeyAction,
+    rekey::ReKeyAction, revoke_key::RevokeKeyAction,
 };
 #[cfg(feature = "openssl")]
 use crate::actions::shared::{UnwrapKeyAction, WrapKeyAction};
@@ -12,19 +13,23 @@ use crate::{
 };
 
 mod create_key;
+mod derive_key;
 mod destroy_key;
+mod rekey;
 mod revoke_key;
 
-/// Create, destroy, import, and export symmetric keys
+/// Create, derive, rotate, destroy, import, and export symmetric keys
 #[derive(Subcommand)]
 pub enum KeysCommands {
     Create(CreateKeyAction),
+    Derive(DeriveKeyAction),
     Export(ExportKeyAction),
     Import(ImportKeyAction),
     #[cfg(feature = "openssl")]
     Wrap(WrapKeyAction),
     #[cfg(feature = "openssl")]
     Unwrap(UnwrapKeyAction),
+    Rekey(ReKeyAction),
     Revoke(RevokeKeyAction),
     Destroy(DestroyKeyAction),
 }
@@ -33,12 +38,14 @@ impl KeysCommands {
     pub async fn process(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
         match self {
             Self::Create(action) => action.run(kms_rest_client).await?,
+            Self::Derive(action) => action.run(kms_rest_client).await?,
             Self::Export(action) => action.run(kms_rest_client).await?,
             Self::Import(action) => action.run(kms_rest_client).await?,
             #[cfg(feature = "openssl")]
             Self::Wrap(action) => action.run(kms_rest_client).await?,
             #[cfg(feature = "openssl")]
             Self::Unwrap(action) => action.run(kms_rest_client).await?,
+            Self::Rekey(action) => action.run(kms_rest_client).await?,
             Self::Revoke(action) => action.run(kms_rest_client).await?,
             Self::Destroy(action) => action.run(kms_rest_client).await?,
         };
@@ -0,0 +1,55 @@
+use clap::Parser;
+use cosmian_kms_client::{cosmian_kmip::crypto::symmetric::symmetric_key_rekey_request, KmsClient};
+
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Rotate a symmetric key
+///
+/// A new key is created with the same algorithm, length, usage and tags.
+/// The existing key is deactivated and linked to its replacement.
+///
+/// The replacement key can be scheduled for activation `--offset` seconds
+/// after its creation.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct ReKeyAction {
+    /// The unique identifier of the key to rotate.
+    /// If not specified, tags should be specified
+    #[clap(long = "key-id", short = 'k', group = "key-tags")]
+    key_id: Option<String>,
+
+    /// Tag to use to retrieve the key when no key id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "key-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The number of seconds between the creation and the activation
+    /// of the replacement key
+    #[clap(long = "offset", short = 'o')]
+    offset: Option<i32>,
+}
+
+impl ReKeyAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        // Recover the unique identifier or set of tags
+        let id = if let Some(key_id) = &self.key_id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either `--key-id` or one or more `--tag` must be specified")
+        };
+
+        let unique_identifier = kms_rest_client
+            .rekey(symmetric_key_rekey_request(&id, self.offset))
+            .await
+            .with_context(|| "failed rotating the key")?
+            .unique_identifier;
+
+        println!("The symmetric key was rotated with new id: {unique_identifier}.");
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::{crypto::generic::kmip_requests::build_mac_request, kmip::kmip_operations::MAC},
+    KmsClient,
+};
+
+use crate::{
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Compute the message authentication code (MAC) of a file
+/// using an HMAC or a CMAC key
+///
+/// The MAC algorithm is that of the key.
+///
+/// Files larger than `--chunk-size` bytes are streamed to the server by parts.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct MacAction {
+    /// The file to MAC
+    #[clap(required = true, name = "FILE")]
+    input_file: PathBuf,
+
+    /// The HMAC or CMAC key unique identifier.
+    /// If not specified, tags should be specified
+    #[clap(long = "key-id", short = 'k', group = "key-tags")]
+    key_id: Option<String>,
+
+    /// Tag to use to retrieve the key when no key id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "key-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The size in bytes of the parts sent to the server
+    #[clap(long = "chunk-size", short = 'c', default_value = "1048576")]
+    chunk_size: usize,
+
+    /// The MAC output file path
+    #[clap(required = false, long, short = 'o')]
+    output_file: Option<PathBuf>,
+}
+
+impl MacAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        if self.chunk_size == 0 {
+            cli_bail!("The chunk size must be strictly positive")
+        }
+
+        // Recover the unique identifier or set of tags
+        let id = if let Some(key_id) = &self.key_id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either `--key-id` or one or more `--tag` must be specified")
+        };
+
+        let mut file =
+            File::open(&self.input_file).with_context(|| "Cannot open the file to MAC")?;
+        let mut chunk = read_chunk(&mut file, self.chunk_size)?;
+        let mut next_chunk = read_chunk(&mut file, self.chunk_size)?;
+
+        let mac_data = if next_chunk.is_empty() {
+            // the file fits in a single request
+            kms_rest_client
+                .mac(build_mac_request(&id, chunk, None))
+                .await
+                .with_context(|| "Can't execute the query on the kms server")?
+                .mac_data
+        } else {
+            // stream the file by parts
+            let mut request = build_mac_request(&id, chunk, None);
+            request.init_indicator = Some(true);
+            let mut correlation_value = kms_rest_client
+                .mac(request)
+                .await
+                .with_context(|| "Can't execute the query on the kms server")?
+                .correlation_value
+                .context("The server did not return a correlation value")?;
+            loop {
+                chunk = next_chunk;
+                next_chunk = read_chunk(&mut file, self.chunk_size)?;
+                let is_final = next_chunk.is_empty();
+                let response = kms_rest_client
+                    .mac(MAC {
+                        data: Some(chunk),
+                        correlation_value: Some(correlation_value),
+                        final_indicator: Some(is_final),
+                        ..MAC::default()
+                    })
+                    .await
+                    .with_context(|| "Can't execute the query on the kms server")?;
+                if is_final {
+                    break response.mac_data
+                }
+                correlation_value = response
+                    .correlation_value
+                    .context("The server did not return a correlation value")?;
+            }
+        };
+        let mac_data = mac_data.context("The MAC is empty")?;
+
+        // Write the MAC file
+        let output_file = self
+            .output_file
+            .clone()
+            .unwrap_or_else(|| self.input_file.with_extension("mac"));
+        let mut buffer =
+            File::create(&output_file).with_context(|| "failed to write the MAC file")?;
+        buffer
+            .write_all(&mac_data)
+            .with_context(|| "failed to write the MAC file")?;
+
+        println!("The MAC file is available at {output_file:?}");
+
+        Ok(())
+    }
+}
+
+/// Read at most `chunk_size` bytes from `file`.
+/// An empty chunk is returned at the end of the file.
+pub(crate) fn read_chunk(file: &mut File, chunk_size: usize) -> Result<Vec<u8>, CliError> {
+    let mut chunk = Vec::with_capacity(chunk_size);
+    file.take(chunk_size as u64)
+        .read_to_end(&mut chunk)
+        .with_context(|| "Cannot read bytes from the file")?;
+    Ok(chunk)
+}
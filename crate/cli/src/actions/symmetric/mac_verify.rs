@@ -0,0 +1,125 @@
+use std::{fs::File, path::PathBuf};
+
+use clap::Parser;
+use cosmian_kms_client::{
+    cosmian_kmip::{
+        crypto::generic::kmip_requests::build_mac_verify_request,
+        kmip::{
+            kmip_operations::MACVerify,
+            kmip_types::{UniqueIdentifier, ValidityIndicator},
+        },
+    },
+    read_bytes_from_file, KmsClient,
+};
+
+use crate::{
+    actions::symmetric::mac::read_chunk,
+    cli_bail,
+    error::{result::CliResultHelper, CliError},
+};
+
+/// Verify the message authentication code (MAC) of a file
+/// using an HMAC or a CMAC key
+///
+/// Files larger than `--chunk-size` bytes are streamed to the server by parts.
+#[derive(Parser, Debug)]
+#[clap(verbatim_doc_comment)]
+pub struct MacVerifyAction {
+    /// The file that was MACed
+    #[clap(required = true, name = "FILE")]
+    input_file: PathBuf,
+
+    /// The MAC file
+    #[clap(required = true, name = "MAC_FILE")]
+    mac_file: PathBuf,
+
+    /// The HMAC or CMAC key unique identifier.
+    /// If not specified, tags should be specified
+    #[clap(long = "key-id", short = 'k', group = "key-tags")]
+    key_id: Option<String>,
+
+    /// Tag to use to retrieve the key when no key id is specified.
+    /// To specify multiple tags, use the option multiple times.
+    #[clap(long = "tag", short = 't', value_name = "TAG", group = "key-tags")]
+    tags: Option<Vec<String>>,
+
+    /// The size in bytes of the parts sent to the server
+    #[clap(long = "chunk-size", short = 'c', default_value = "1048576")]
+    chunk_size: usize,
+}
+
+impl MacVerifyAction {
+    pub async fn run(&self, kms_rest_client: &KmsClient) -> Result<(), CliError> {
+        if self.chunk_size == 0 {
+            cli_bail!("The chunk size must be strictly positive")
+        }
+
+        let mac_data = read_bytes_from_file(&self.mac_file)
+            .with_context(|| "Cannot read bytes from the MAC file")?;
+
+        // Recover the unique identifier or set of tags
+        let id = if let Some(key_id) = &self.key_id {
+            key_id.clone()
+        } else if let Some(tags) = &self.tags {
+            serde_json::to_string(&tags)?
+        } else {
+            cli_bail!("Either `--key-id` or one or more `--tag` must be specified")
+        };
+
+        let mut file =
+            File::open(&self.input_file).with_context(|| "Cannot open the MACed file")?;
+        let mut chunk = read_chunk(&mut file, self.chunk_size)?;
+        let mut next_chunk = read_chunk(&mut file, self.chunk_size)?;
+
+        let validity_indicator = if next_chunk.is_empty() {
+            // the file fits in a single request
+            kms_rest_client
+                .mac_verify(build_mac_verify_request(&id, chunk, mac_data, None))
+                .await
+                .with_context(|| "Can't execute the query on the kms server")?
+                .validity_indicator
+        } else {
+            // stream the file by parts: the MAC is sent with the final part
+            let mut correlation_value = kms_rest_client
+                .mac_verify(MACVerify {
+                    unique_identifier: Some(UniqueIdentifier::TextString(id)),
+                    data: Some(chunk),
+                    init_indicator: Some(true),
+                    ..MACVerify::default()
+                })
+                .await
+                .with_context(|| "Can't execute the query on the kms server")?
+                .correlation_value
+                .context("The server did not return a correlation value")?;
+            loop {
+                chunk = next_chunk;
+                next_chunk = read_chunk(&mut file, self.chunk_size)?;
+                let is_final = next_chunk.is_empty();
+                let response = kms_rest_client
+                    .mac_verify(MACVerify {
+                        data: Some(chunk),
+                        mac_data: is_final.then(|| mac_data.clone()),
+                        correlation_value: Some(correlation_value),
+                        final_indicator: Some(is_final),
+                        ..MACVerify::default()
+                    })
+                    .await
+                    .with_context(|| "Can't execute the query on the kms server")?;
+                if is_final {
+                    break response.validity_indicator
+                }
+                correlation_value = response
+                    .correlation_value
+                    .context("The server did not return a correlation value")?;
+            }
+        };
+
+        match validity_indicator {
+            Some(ValidityIndicator::Valid) => println!("The MAC is valid"),
+            Some(other) => cli_bail!("The MAC verification failed: {other}"),
+            None => cli_bail!("The MAC verification failed: no validity indicator returned"),
+        }
+
+        Ok(())
+    }
+}
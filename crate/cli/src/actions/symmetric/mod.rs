@@ -1,20 +1,27 @@
 use clap::Parser;
 use cosmian_kms_client::KmsClient;
 
-use self::{decrypt::DecryptAction, encrypt::EncryptAction, keys::KeysCommands};
+use self::{
+    decrypt::DecryptAction, encrypt::EncryptAction, keys::KeysCommands, mac::MacAction,
+    mac_verify::MacVerifyAction,
+};
 use crate::error::CliError;
 
 mod decrypt;
 mod encrypt;
 mod keys;
+mod mac;
+mod mac_verify;
 
-/// Manage symmetric keys. Encrypt and decrypt data.
+/// Manage symmetric keys. Encrypt and decrypt data. Compute and verify MACs.
 #[derive(Parser)]
 pub enum SymmetricCommands {
     #[command(subcommand)]
     Keys(KeysCommands),
     Encrypt(EncryptAction),
     Decrypt(DecryptAction),
+    Mac(MacAction),
+    MacVerify(MacVerifyAction),
 }
 
 impl SymmetricCommands {
@@ -23,6 +30,8 @@ impl SymmetricCommands {
             Self::Keys(command) => command.process(kms_rest_client).await?,
             Self::Encrypt(action) => action.run(kms_rest_client).await?,
             Self::Decrypt(action) => action.run(kms_rest_client).await?,
+            Self::Mac(action) => action.run(kms_rest_client).await?,
+            Self::MacVerify(action) => action.run(kms_rest_client).await?,
         };
         Ok(())
     }
@@ -12,9 +12,14 @@ use cosmian_kms_cli::{
         login::LoginAction,
         logout::LogoutAction,
         markdown::MarkdownAction,
+        ms_dke::DkeCommands,
         new_database::NewDatabaseAction,
+        rng::RngCommands,
         rsa::RsaCommands,
-        shared::{GetAttributesAction, LocateObjectsAction},
+        shared::{
+            ActivateAction, ArchiveAction, DeleteAttributesAction, GetAttributesAction,
+            LocateObjectsAction, RecoverAction, SetAttributesAction,
+        },
         symmetric::SymmetricCommands,
         version::ServerVersionAction,
     },
@@ -40,16 +45,25 @@ struct Cli {
 enum CliCommands {
     #[command(subcommand)]
     AccessRights(AccessAction),
+    Activate(ActivateAction),
+    Archive(ArchiveAction),
     #[cfg(not(feature = "fips"))]
     #[command(subcommand)]
     Cc(CovercryptCommands),
     #[command(subcommand)]
     Certificates(CertificatesCommands),
     #[command(subcommand)]
+    Dke(DkeCommands),
+    #[command(subcommand)]
     Ec(EllipticCurveCommands),
     GetAttributes(GetAttributesAction),
+    SetAttributes(SetAttributesAction),
+    DeleteAttributes(DeleteAttributesAction),
     Locate(LocateObjectsAction),
     NewDatabase(NewDatabaseAction),
+    Recover(RecoverAction),
+    #[command(subcommand)]
+    Rng(RngCommands),
     #[command(subcommand)]
     Rsa(RsaCommands),
     ServerVersion(ServerVersionAction),
@@ -106,15 +120,24 @@ async fn main_() -> Result<(), CliError> {
                 CliCommands::Locate(action) => action.process(&kms_rest_client).await?,
                 #[cfg(not(feature = "fips"))]
                 CliCommands::Cc(action) => action.process(&kms_rest_client).await?,
+                CliCommands::Dke(action) => action.process(&kms_rest_client).await?,
                 CliCommands::Ec(action) => action.process(&kms_rest_client).await?,
                 CliCommands::Rsa(action) => action.process(&kms_rest_client).await?,
+                CliCommands::Rng(action) => action.process(&kms_rest_client).await?,
                 CliCommands::Sym(action) => action.process(&kms_rest_client).await?,
                 CliCommands::AccessRights(action) => action.process(&kms_rest_client).await?,
                 CliCommands::Certificates(action) => action.process(&kms_rest_client).await?,
                 CliCommands::NewDatabase(action) => action.process(&kms_rest_client).await?,
                 CliCommands::ServerVersion(action) => action.process(&kms_rest_client).await?,
                 CliCommands::GetAttributes(action) => action.process(&kms_rest_client).await?,
-                CliCommands::Google(action) => action.process(&conf_path).await?,
+                CliCommands::SetAttributes(action) => action.process(&kms_rest_client).await?,
+                CliCommands::DeleteAttributes(action) => action.process(&kms_rest_client).await?,
+                CliCommands::Activate(action) => action.process(&kms_rest_client).await?,
+                CliCommands::Archive(action) => action.process(&kms_rest_client).await?,
+                CliCommands::Recover(action) => action.process(&kms_rest_client).await?,
+                CliCommands::Google(action) => {
+                    action.process(&conf_path, &kms_rest_client).await?;
+                }
                 _ => {
                     println!("Error: unexpected command");
                 }
@@ -70,7 +70,7 @@ pub(crate) fn revoke_access(
 }
 
 /// List accesses granted on an object
-fn list_access(cli_conf_path: &str, object_id: &str) -> Result<String, CliError> {
+pub(crate) fn list_access(cli_conf_path: &str, object_id: &str) -> Result<String, CliError> {
     let mut cmd = Command::cargo_bin(PROG_NAME)?;
     cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
     cmd.env("RUST_LOG", "cosmian_kms_cli=info");
@@ -680,3 +680,119 @@ pub async fn test_grant_multiple_operations() -> Result<(), CliError> {
 
     Ok(())
 }
+
+/// Run an `access-rights` command and return its output
+fn access_rights(cli_conf_path: &str, args: &[&str]) -> Result<String, CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND).args(args);
+
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        let out = String::from_utf8(output.stdout)?;
+        return Ok(out)
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+pub async fn test_roles_and_groups() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+    let key_id = gen_key(&ctx.owner_client_conf_path)?;
+
+    // the owner is an access rights administrator: it can set roles
+    access_rights(
+        &ctx.owner_client_conf_path,
+        &["roles", "set", "crypto-user", "encrypt", "decrypt"],
+    )?;
+    let roles = access_rights(&ctx.owner_client_conf_path, &["roles", "list"])?;
+    assert!(roles.contains("crypto-user: {decrypt, encrypt}"));
+    // the user is not
+    assert!(
+        access_rights(
+            &ctx.user_client_conf_path,
+            &["roles", "set", "crypto-user", "get"]
+        )
+        .is_err()
+    );
+    assert!(
+        access_rights(
+            &ctx.user_client_conf_path,
+            &["groups", "add", "crypto-team", "user.client@acme.com"]
+        )
+        .is_err()
+    );
+
+    // grant the role to a group
+    access_rights(
+        &ctx.owner_client_conf_path,
+        &[
+            "grant",
+            "group:crypto-team",
+            &key_id,
+            "--role",
+            "crypto-user",
+        ],
+    )?;
+    let owner_list = list_access(&ctx.owner_client_conf_path, &key_id)?;
+    assert!(owner_list.contains("group:crypto-team: {decrypt, encrypt}"));
+    // the user is not a member of the group yet
+    assert!(run_encrypt_decrypt_test(&ctx.user_client_conf_path, &key_id).is_err());
+
+    // the members of the group obtain its access rights
+    access_rights(
+        &ctx.owner_client_conf_path,
+        &["groups", "add", "crypto-team", "user.client@acme.com"],
+    )?;
+    let groups = access_rights(&ctx.owner_client_conf_path, &["groups", "list"])?;
+    assert!(groups.contains("crypto-team: {\"user.client@acme.com\"}"));
+    run_encrypt_decrypt_test(&ctx.user_client_conf_path, &key_id)?;
+    let list = list_accesses_rights_obtained(&ctx.user_client_conf_path)?;
+    assert!(list.contains(&key_id));
+    // but not the other operations
+    assert!(revoke(&ctx.user_client_conf_path, "sym", &key_id, "failed revoke").is_err());
+
+    // removed from the group, the user loses them
+    access_rights(
+        &ctx.owner_client_conf_path,
+        &["groups", "remove", "crypto-team", "user.client@acme.com"],
+    )?;
+    assert!(run_encrypt_decrypt_test(&ctx.user_client_conf_path, &key_id).is_err());
+
+    // the role is kept by reference: editing it changes the access rights of its grantees
+    access_rights(
+        &ctx.owner_client_conf_path,
+        &["roles", "set", "crypto-user", "encrypt"],
+    )?;
+    let owner_list = list_access(&ctx.owner_client_conf_path, &key_id)?;
+    assert!(owner_list.contains("group:crypto-team: {encrypt}"));
+
+    // deleting the role revokes the access rights granted with it
+    access_rights(
+        &ctx.owner_client_conf_path,
+        &["roles", "delete", "crypto-user"],
+    )?;
+    let roles = access_rights(&ctx.owner_client_conf_path, &["roles", "list"])?;
+    assert!(!roles.contains("crypto-user"));
+    let owner_list = list_access(&ctx.owner_client_conf_path, &key_id)?;
+    assert!(!owner_list.contains("group:crypto-team"));
+    // and an unknown role cannot be granted
+    assert!(
+        access_rights(
+            &ctx.owner_client_conf_path,
+            &[
+                "grant",
+                "group:crypto-team",
+                &key_id,
+                "--role",
+                "crypto-user"
+            ],
+        )
+        .is_err()
+    );
+
+    Ok(())
+}
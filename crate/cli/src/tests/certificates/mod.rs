@@ -3,6 +3,7 @@ pub mod encrypt;
 mod export;
 mod get_attributes;
 pub mod import;
+mod ocsp_responder;
 pub mod openssl;
 pub mod quick_cert;
 mod validate;
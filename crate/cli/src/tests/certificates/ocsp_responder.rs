@@ -0,0 +1,114 @@
+use std::process::Command;
+
+use assert_cmd::cargo::CommandCargoExt;
+use cosmian_kms_client::{
+    cosmian_kmip::kmip::{
+        kmip_objects::Object,
+        kmip_types::{Attributes, LinkType},
+        ttlv::{deserializer::from_ttlv, TTLV},
+    },
+    read_from_json_file, read_object_from_json_ttlv_file, KMS_CLI_CONF_ENV,
+};
+use kms_test_server::{start_default_test_kms_server, ONCE};
+use tempfile::TempDir;
+use uuid::Uuid;
+use x509_parser::prelude::*;
+
+use crate::{
+    actions::certificates::{CertificateExportFormat, CertificateInputFormat},
+    error::CliError,
+    tests::{
+        certificates::{
+            certify::extract_certificate_id, export::export_certificate,
+            import::import_certificate, SUB_COMMAND,
+        },
+        utils::recover_cmd_logs,
+        PROG_NAME,
+    },
+};
+
+pub fn provision_ocsp_responder(
+    cli_conf_path: &str,
+    issuer_private_key_id: &str,
+    subject_name: &str,
+) -> Result<String, CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND).args(vec![
+        "ocsp-responder",
+        "--issuer-private-key-id",
+        issuer_private_key_id,
+        "--subject-name",
+        subject_name,
+    ]);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        let stdout = std::str::from_utf8(&output.stdout)?;
+        return Ok(extract_certificate_id(stdout)
+            .ok_or_else(|| CliError::Default("failed extracting the certificate id".to_owned()))?
+            .to_owned())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_provision_ocsp_responder() -> Result<(), CliError> {
+    let tmp_dir = TempDir::new()?;
+    let tmp_path = tmp_dir.path();
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // import Intermediate p12
+    let issuer_private_key_id = import_certificate(
+        &ctx.owner_client_conf_path,
+        "certificates",
+        "test_data/certificates/csr/intermediate.p12",
+        CertificateInputFormat::Pkcs12,
+        Some("secret"),
+        Some(Uuid::new_v4().to_string()),
+        None,
+        None,
+        Some(&["ocsp_intermediate_ca"]),
+        None,
+        false,
+        true,
+    )?;
+
+    let responder_certificate_id = provision_ocsp_responder(
+        &ctx.owner_client_conf_path,
+        &issuer_private_key_id,
+        "CN=Test OCSP Responder",
+    )?;
+
+    // export the responder certificate
+    let exported_cert_file = tmp_path.join("exported_responder_cert.json");
+    export_certificate(
+        &ctx.owner_client_conf_path,
+        &responder_certificate_id,
+        exported_cert_file.to_str().unwrap(),
+        Some(CertificateExportFormat::JsonTtlv),
+        None,
+        true,
+    )?;
+    let Object::Certificate {
+        certificate_value, ..
+    } = read_object_from_json_ttlv_file(&exported_cert_file)?
+    else {
+        panic!("wrong object type")
+    };
+
+    // the responder certificate is dedicated to signing OCSP responses
+    let (_, certificate) = X509Certificate::from_der(&certificate_value).unwrap();
+    let eku = certificate.extended_key_usage().unwrap().unwrap();
+    assert!(eku.value.ocsp_signing);
+
+    // and is linked to its issuer certificate
+    let ttlv: TTLV =
+        read_from_json_file(&tmp_path.join("exported_responder_cert.attributes.json")).unwrap();
+    let attributes: Attributes = from_ttlv(&ttlv).unwrap();
+    assert!(attributes.get_link(LinkType::CertificateLink).is_some());
+    assert!(attributes.get_link(LinkType::PublicKeyLink).is_some());
+    Ok(())
+}
@@ -1,15 +1,105 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, process::Command};
 
+use assert_cmd::cargo::CommandCargoExt;
+use cosmian_kms_client::KMS_CLI_CONF_ENV;
 use kms_test_server::{start_default_test_kms_server, ONCE};
 use tempfile::TempDir;
 use tracing::debug;
+use uuid::Uuid;
 
 use crate::{
     actions::certificates::CertificateInputFormat,
     error::CliError,
-    tests::certificates::{encrypt::encrypt, import::import_certificate},
+    tests::{
+        certificates::{
+            encrypt::encrypt, import::import_certificate, quick_cert::revoke, SUB_COMMAND,
+        },
+        utils::recover_cmd_logs,
+        PROG_NAME,
+    },
 };
 
+pub fn validate_certificate(
+    cli_conf_path: &str,
+    certificate_id: &str,
+    intermediate_ids: &[&str],
+    validity_time: Option<&str>,
+) -> Result<String, CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    let mut args = vec!["validate", "--certificate-id", certificate_id];
+    for intermediate_id in intermediate_ids {
+        args.extend(["--intermediate-id", intermediate_id]);
+    }
+    if let Some(validity_time) = validity_time {
+        args.extend(["--validity-time", validity_time]);
+    }
+    cmd.arg(SUB_COMMAND).args(args);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        return Ok(std::str::from_utf8(&output.stdout)?.to_owned())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+fn import_pem_certificate(
+    cli_conf_path: &str,
+    file_name: &str,
+    issuer_certificate_id: Option<String>,
+) -> Result<String, CliError> {
+    import_certificate(
+        cli_conf_path,
+        SUB_COMMAND,
+        &format!("test_data/certificates/csr/{file_name}"),
+        CertificateInputFormat::Pem,
+        None,
+        Some(Uuid::new_v4().to_string()),
+        None,
+        issuer_certificate_id,
+        None,
+        None,
+        false,
+        true,
+    )
+}
+
+#[tokio::test]
+async fn test_validate_certificate_chain() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+    let conf = &ctx.owner_client_conf_path;
+
+    // the intermediate is not linked to the root: its issuer is found by name
+    let root_id = import_pem_certificate(conf, "ca.crt", None)?;
+    let intermediate_id = import_pem_certificate(conf, "intermediate.crt", None)?;
+    let leaf_id = import_pem_certificate(conf, "leaf.crt", Some(intermediate_id.clone()))?;
+
+    let output = validate_certificate(conf, &leaf_id, &[], None)?;
+    assert!(output.contains("The certificate chain is valid"));
+    validate_certificate(conf, &leaf_id, &[&intermediate_id], None)?;
+    validate_certificate(conf, &root_id, &[], None)?;
+
+    // the leaf and the intermediate expire in 2028
+    let error = validate_certificate(conf, &leaf_id, &[], Some("2030-01-01T00:00:00Z"))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains(&format!("the certificate {leaf_id} is not valid on")));
+    validate_certificate(conf, &leaf_id, &[], Some("2025-01-01T00:00:00+02:00"))?;
+
+    // a revoked intermediate invalidates the chain
+    revoke(conf, SUB_COMMAND, &intermediate_id, "key compromise")?;
+    let error = validate_certificate(conf, &leaf_id, &[], None)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains(&format!(
+        "the certificate {intermediate_id} is revoked (key compromise)"
+    )));
+
+    Ok(())
+}
+
 async fn import_revoked_certificate_encrypt(curve_name: &str) -> Result<(), CliError> {
     let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
 
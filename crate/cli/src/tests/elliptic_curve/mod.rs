@@ -1,5 +1,6 @@
 pub mod create_key_pair;
 #[cfg(not(feature = "fips"))]
 pub mod encrypt_decrypt;
+pub mod sign_verify;
 
 pub(crate) const SUB_COMMAND: &str = "ec";
@@ -0,0 +1,70 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::KMS_CLI_CONF_ENV;
+use kms_test_server::{start_default_test_kms_server, ONCE};
+
+use crate::{
+    error::CliError,
+    tests::{
+        access::{grant_access, list_access},
+        symmetric::create_key::create_symmetric_key,
+        utils::{extract_uids::extract_uid, recover_cmd_logs},
+        PROG_NAME,
+    },
+};
+
+const SUB_COMMAND: &str = "google";
+
+/// Rotate the Google CSE key and return the id of the new version
+fn rotate_cse_key(cli_conf_path: &str) -> Result<String, CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND).args(vec!["rotate"]);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        let output = std::str::from_utf8(&output.stdout)?;
+        let unique_identifier = extract_uid(output, "The Google CSE key was rotated with new id")
+            .ok_or_else(|| {
+            CliError::Default("failed extracting the unique identifier".to_owned())
+        })?;
+        return Ok(unique_identifier.to_string())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_rotate_cse_key() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    let key_id = create_symmetric_key(
+        &ctx.owner_client_conf_path,
+        None,
+        None,
+        None,
+        &["google_cse"],
+    )?;
+    grant_access(
+        &ctx.owner_client_conf_path,
+        &key_id,
+        "user.client@acme.com",
+        &["encrypt", "decrypt"],
+    )?;
+
+    let new_key_id = rotate_cse_key(&ctx.owner_client_conf_path)?;
+    assert_ne!(key_id, new_key_id);
+
+    // the access rights are granted on the new version
+    let access_rights = list_access(&ctx.owner_client_conf_path, &new_key_id)?;
+    assert!(access_rights.contains("user.client@acme.com"));
+
+    // the previous version is deactivated: a second rotation rotates the new version
+    let newest_key_id = rotate_cse_key(&ctx.owner_client_conf_path)?;
+    assert_ne!(newest_key_id, new_key_id);
+    assert_ne!(newest_key_id, key_id);
+
+    Ok(())
+}
@@ -4,7 +4,10 @@ mod certificates;
 #[cfg(not(feature = "fips"))]
 mod cover_crypt;
 mod elliptic_curve;
+mod google;
+mod ms_dke;
 mod new_database;
+mod rng;
 mod rsa;
 mod shared;
 mod symmetric;
@@ -0,0 +1,89 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::KMS_CLI_CONF_ENV;
+use kms_test_server::{start_default_test_kms_server, ONCE};
+
+use crate::{
+    error::CliError,
+    tests::{
+        access::{grant_access, list_access},
+        utils::{
+            extract_uids::{extract_private_key, extract_uid},
+            recover_cmd_logs,
+        },
+        PROG_NAME,
+    },
+};
+
+const SUB_COMMAND: &str = "dke";
+
+/// Run a `dke` command on the key `key_name`
+/// and return the key version and the private key id
+fn dke_command(
+    cli_conf_path: &str,
+    command: &str,
+    key_version_pattern: &str,
+    key_name: &str,
+) -> Result<(String, String), CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND).args(vec![command, key_name]);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        let output = std::str::from_utf8(&output.stdout)?;
+        let key_version = extract_uid(output, key_version_pattern)
+            .ok_or_else(|| CliError::Default("failed extracting the key version".to_owned()))?;
+        let private_key_id = extract_private_key(output).ok_or_else(|| {
+            CliError::Default("failed extracting the private key unique identifier".to_owned())
+        })?;
+        return Ok((key_version.to_string(), private_key_id.to_string()))
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_dke_key_rotation() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    let (version, private_key_id) = dke_command(
+        &ctx.owner_client_conf_path,
+        "create",
+        "Key version",
+        "dke_cli_test",
+    )?;
+    // the Azure AD group allowed to decrypt
+    grant_access(
+        &ctx.owner_client_conf_path,
+        &private_key_id,
+        "3f1a7c2e-finance",
+        &["decrypt"],
+    )?;
+
+    let (new_version, new_private_key_id) = dke_command(
+        &ctx.owner_client_conf_path,
+        "rotate",
+        "The DKE key dke_cli_test was rotated with new version",
+        "dke_cli_test",
+    )?;
+    assert_ne!(version, new_version);
+    assert_ne!(private_key_id, new_private_key_id);
+
+    // the access rights are granted on the new version
+    let access_rights = list_access(&ctx.owner_client_conf_path, &new_private_key_id)?;
+    assert!(access_rights.contains("3f1a7c2e-finance"));
+
+    // the previous version is deactivated: a second rotation rotates the new version
+    let (newest_version, _) = dke_command(
+        &ctx.owner_client_conf_path,
+        "rotate",
+        "The DKE key dke_cli_test was rotated with new version",
+        "dke_cli_test",
+    )?;
+    assert_ne!(newest_version, new_version);
+
+    Ok(())
+}
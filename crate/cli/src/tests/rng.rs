@@ -0,0 +1,73 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::{read_bytes_from_file, KMS_CLI_CONF_ENV};
+use kms_test_server::{start_default_test_kms_server, ONCE};
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+use crate::{
+    error::CliError,
+    tests::{utils::recover_cmd_logs, PROG_NAME},
+};
+
+const SUB_COMMAND: &str = "rng";
+
+#[tokio::test]
+async fn test_rng_retrieve() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // printed in hexadecimal
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, &ctx.owner_client_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND).args(["retrieve", "--length", "16"]);
+    let output = recover_cmd_logs(&mut cmd);
+    assert!(output.status.success());
+    let random = hex::decode(std::str::from_utf8(&output.stdout)?.trim()).unwrap();
+    assert_eq!(random.len(), 16);
+
+    // written to a file
+    let tmp_dir = TempDir::new()?;
+    let output_file = tmp_dir.path().join("random.bin");
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, &ctx.owner_client_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND).args([
+        "retrieve",
+        "--length",
+        "1000",
+        "-o",
+        output_file.to_str().unwrap(),
+    ]);
+    recover_cmd_logs(&mut cmd);
+    cmd.assert().success();
+    assert_eq!(read_bytes_from_file(&output_file)?.len(), 1000);
+
+    // more than the server accepts in a single request
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, &ctx.owner_client_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND)
+        .args(["retrieve", "--length", "2000000"]);
+    recover_cmd_logs(&mut cmd);
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rng_seed() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, &ctx.owner_client_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(SUB_COMMAND).args(["seed", "test_data/plain.txt"]);
+    recover_cmd_logs(&mut cmd);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "The server accepted 0 bytes of seed data.",
+    ));
+
+    Ok(())
+}
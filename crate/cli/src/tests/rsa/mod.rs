@@ -1,4 +1,5 @@
 pub mod create_key_pair;
 pub mod encrypt_decrypt;
+pub mod sign_verify;
 
 pub(crate) const SUB_COMMAND: &str = "rsa";
@@ -0,0 +1,198 @@
+use std::{fs, path::PathBuf, process::Command};
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::KMS_CLI_CONF_ENV;
+use kms_test_server::{start_default_test_kms_server, ONCE};
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+use super::SUB_COMMAND;
+use crate::{
+    actions::rsa::{HashFn, SignatureAlgorithm},
+    error::CliError,
+    tests::{
+        rsa::create_key_pair::create_rsa_4096_bits_key_pair, utils::recover_cmd_logs, PROG_NAME,
+    },
+};
+
+/// Sign a file using the given private key
+pub fn sign(
+    cli_conf_path: &str,
+    input_file: &str,
+    private_key_id: &str,
+    signature_algorithm: SignatureAlgorithm,
+    hash_fn: Option<HashFn>,
+    output_file: Option<&str>,
+) -> Result<(), CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+
+    let mut args = vec!["sign", input_file, "--key-id", private_key_id];
+    args.push("--signature-algorithm");
+    let signature_algorithm = signature_algorithm.to_string();
+    args.push(&signature_algorithm);
+    let hash_fn_s = hash_fn.map(|h| h.to_string()).unwrap_or_default();
+    if hash_fn.is_some() {
+        args.push("--hashing-algorithm");
+        args.push(&hash_fn_s);
+    }
+    if let Some(output_file) = output_file {
+        args.push("-o");
+        args.push(output_file);
+    }
+    cmd.arg(SUB_COMMAND).args(args);
+    recover_cmd_logs(&mut cmd);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "The signature file is available at",
+    ));
+    Ok(())
+}
+
+/// Verify the signature of a file using the given public key
+pub fn verify(
+    cli_conf_path: &str,
+    input_file: &str,
+    signature_file: &str,
+    public_key_id: &str,
+    signature_algorithm: SignatureAlgorithm,
+    hash_fn: Option<HashFn>,
+) -> Result<(), CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+
+    let mut args = vec!["verify", input_file, signature_file, "--key-id", public_key_id];
+    args.push("--signature-algorithm");
+    let signature_algorithm = signature_algorithm.to_string();
+    args.push(&signature_algorithm);
+    let hash_fn_s = hash_fn.map(|h| h.to_string()).unwrap_or_default();
+    if hash_fn.is_some() {
+        args.push("--hashing-algorithm");
+        args.push(&hash_fn_s);
+    }
+    cmd.arg(SUB_COMMAND).args(args);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        return Ok(())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_rsa_sign_verify_using_rsa_pss() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // create a temp dir
+    let tmp_dir = TempDir::new()?;
+    let tmp_path = tmp_dir.path();
+
+    let input_file = PathBuf::from("test_data/plain.txt");
+    let signature_file = tmp_path.join("plain.sig");
+
+    fs::remove_file(&signature_file).ok();
+    assert!(!signature_file.exists());
+
+    let (private_key_id, public_key_id) =
+        create_rsa_4096_bits_key_pair(&ctx.owner_client_conf_path, &[])?;
+
+    sign(
+        &ctx.owner_client_conf_path,
+        input_file.to_str().unwrap(),
+        &private_key_id,
+        SignatureAlgorithm::RsaPss,
+        Some(HashFn::Sha256),
+        Some(signature_file.to_str().unwrap()),
+    )?;
+    assert!(signature_file.exists());
+
+    // the public key should verify the signature
+    verify(
+        &ctx.owner_client_conf_path,
+        input_file.to_str().unwrap(),
+        signature_file.to_str().unwrap(),
+        &public_key_id,
+        SignatureAlgorithm::RsaPss,
+        Some(HashFn::Sha256),
+    )?;
+
+    // verification with another hashing algorithm must fail
+    assert!(
+        verify(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            signature_file.to_str().unwrap(),
+            &public_key_id,
+            SignatureAlgorithm::RsaPss,
+            Some(HashFn::Sha512),
+        )
+        .is_err()
+    );
+
+    // verification of other data must fail
+    assert!(
+        verify(
+            &ctx.owner_client_conf_path,
+            "test_data/plain2.txt",
+            signature_file.to_str().unwrap(),
+            &public_key_id,
+            SignatureAlgorithm::RsaPss,
+            Some(HashFn::Sha256),
+        )
+        .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rsa_sign_verify_using_rsa_pkcs_v15() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // create a temp dir
+    let tmp_dir = TempDir::new()?;
+    let tmp_path = tmp_dir.path();
+
+    let input_file = PathBuf::from("test_data/plain.txt");
+    let signature_file = tmp_path.join("plain.sig");
+
+    let (_private_key_id, public_key_id) =
+        create_rsa_4096_bits_key_pair(&ctx.owner_client_conf_path, &["tag_rsa_sign"])?;
+
+    // sign using the tags: only the private key can be used to sign
+    sign(
+        &ctx.owner_client_conf_path,
+        input_file.to_str().unwrap(),
+        "[\"tag_rsa_sign\"]",
+        SignatureAlgorithm::RsaPkcsV15,
+        None,
+        Some(signature_file.to_str().unwrap()),
+    )?;
+
+    // verify using the tags: only the public key can be used to verify
+    verify(
+        &ctx.owner_client_conf_path,
+        input_file.to_str().unwrap(),
+        signature_file.to_str().unwrap(),
+        "[\"tag_rsa_sign\"]",
+        SignatureAlgorithm::RsaPkcsV15,
+        None,
+    )?;
+
+    // a PKCS#1 v1.5 signature is not a valid PSS signature
+    assert!(
+        verify(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            signature_file.to_str().unwrap(),
+            &public_key_id,
+            SignatureAlgorithm::RsaPss,
+            None,
+        )
+        .is_err()
+    );
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, process::Command};
 
 use assert_cmd::cargo::CommandCargoExt;
+use clap::ValueEnum;
 use cosmian_kms_client::KMS_CLI_CONF_ENV;
 use serde_json::Value;
 
@@ -31,21 +32,10 @@ pub fn get_attributes(
 
     for tag in attribute_tags {
         args.push("--attribute".to_owned());
-        let arg_value = match tag {
-            AttributeTag::ActivationDate => "activation-date",
-            AttributeTag::CryptographicAlgorithm => "cryptographic-algorithm",
-            AttributeTag::CryptographicLength => "cryptographic-length",
-            AttributeTag::CryptographicParameters => "cryptographic-parameters",
-            AttributeTag::CryptographicUsageMask => "cryptographic-usage-mask",
-            AttributeTag::KeyFormatType => "key-format-type",
-            AttributeTag::LinkedPrivateKeyId => "linked-private-key-id",
-            AttributeTag::LinkedPublicKeyId => "linked-public-key-id",
-            AttributeTag::LinkedIssuerCertificateId => "linked-issuer-certificate-id",
-            AttributeTag::LinkedCertificateId => "linked-certificate-id",
-            AttributeTag::CryptographicDomainParameters => "cryptographic-domain-parameters",
-            AttributeTag::Tags => "tags",
-        };
-        args.push(arg_value.to_owned());
+        let arg_value = tag
+            .to_possible_value()
+            .context("the attribute tag has no value")?;
+        args.push(arg_value.get_name().to_owned());
     }
 
     let mut cmd = Command::cargo_bin(PROG_NAME)?;
@@ -58,21 +48,8 @@ pub fn get_attributes(
         let output: HashMap<String, Value> = serde_json::from_str(&output)?;
         let mut result = HashMap::with_capacity(output.len());
         for (k, v) in output {
-            let tag = match k.as_str() {
-                "activation-date" => AttributeTag::ActivationDate,
-                "cryptographic-algorithm" => AttributeTag::CryptographicAlgorithm,
-                "cryptographic-length" => AttributeTag::CryptographicLength,
-                "cryptographic-parameters" => AttributeTag::CryptographicParameters,
-                "cryptographic-usage-mask" => AttributeTag::CryptographicUsageMask,
-                "key-format-type" => AttributeTag::KeyFormatType,
-                "linked-private-key-id" => AttributeTag::LinkedPrivateKeyId,
-                "linked-public-key-id" => AttributeTag::LinkedPublicKeyId,
-                "linked-issuer-certificate-id" => AttributeTag::LinkedIssuerCertificateId,
-                "linked-certificate-id" => AttributeTag::LinkedCertificateId,
-                "cryptographic-domain-parameters" => AttributeTag::CryptographicDomainParameters,
-                "tags" => AttributeTag::Tags,
-                _ => return Err(CliError::Default(format!("unknown attribute tag: {k}"))),
-            };
+            let tag = AttributeTag::from_str(&k, false)
+                .map_err(|e| CliError::Default(format!("unknown attribute tag: {k}: {e}")))?;
             result.insert(tag, v);
         }
         return Ok(result)
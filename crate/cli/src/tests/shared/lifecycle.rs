@@ -0,0 +1,51 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::KMS_CLI_CONF_ENV;
+use kms_test_server::{start_default_test_kms_server, ONCE};
+
+use crate::{
+    actions::shared::AttributeTag,
+    error::CliError,
+    tests::{
+        shared::get_attributes, symmetric::create_key::create_symmetric_key,
+        utils::recover_cmd_logs, PROG_NAME,
+    },
+};
+
+fn run_lifecycle_command(cli_conf_path: &str, command: &str, uid: &str) -> Result<(), CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(command).args(["--id", uid]);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        return Ok(())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_archive_and_recover() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+    let conf = &ctx.owner_client_conf_path;
+
+    let key_id = create_symmetric_key(conf, None, None, None, &["lifecycle_test"])?;
+
+    // the key was created active
+    assert!(run_lifecycle_command(conf, "activate", &key_id).is_err());
+
+    // an archived key is no longer available
+    run_lifecycle_command(conf, "archive", &key_id)?;
+    assert!(get_attributes(conf, &key_id, &[AttributeTag::Tags]).is_err());
+    assert!(run_lifecycle_command(conf, "archive", &key_id).is_err());
+
+    // until it is recovered
+    run_lifecycle_command(conf, "recover", &key_id)?;
+    assert!(get_attributes(conf, &key_id, &[AttributeTag::Tags]).is_ok());
+    assert!(run_lifecycle_command(conf, "recover", &key_id).is_err());
+
+    Ok(())
+}
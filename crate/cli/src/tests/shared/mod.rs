@@ -12,7 +12,9 @@ mod import;
 mod import_export_encodings;
 #[cfg(not(feature = "fips"))]
 mod import_export_wrapping;
+mod lifecycle;
 mod locate;
 mod revoke;
+mod set_attributes;
 #[cfg(all(not(feature = "fips"), feature = "openssl"))]
 mod wrap_unwrap;
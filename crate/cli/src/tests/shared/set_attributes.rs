@@ -0,0 +1,139 @@
+use std::{collections::HashSet, process::Command};
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::KMS_CLI_CONF_ENV;
+use kms_test_server::{start_default_test_kms_server, ONCE};
+
+use crate::{
+    actions::shared::AttributeTag,
+    error::CliError,
+    tests::{
+        shared::get_attributes, symmetric::create_key::create_symmetric_key,
+        utils::recover_cmd_logs, PROG_NAME,
+    },
+};
+
+fn run_attributes_command(
+    cli_conf_path: &str,
+    command: &str,
+    uid: &str,
+    args: &[&str],
+) -> Result<(), CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    cmd.arg(command).args(["--id", uid]).args(args);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        return Ok(())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+pub fn set_attributes(cli_conf_path: &str, uid: &str, args: &[&str]) -> Result<(), CliError> {
+    run_attributes_command(cli_conf_path, "set-attributes", uid, args)
+}
+
+pub fn delete_attributes(cli_conf_path: &str, uid: &str, args: &[&str]) -> Result<(), CliError> {
+    run_attributes_command(cli_conf_path, "delete-attributes", uid, args)
+}
+
+fn get_tags(cli_conf_path: &str, uid: &str) -> Result<HashSet<String>, CliError> {
+    let attributes = get_attributes(cli_conf_path, uid, &[AttributeTag::Tags])?;
+    Ok(serde_json::from_value(
+        attributes
+            .get(&AttributeTag::Tags)
+            .cloned()
+            .unwrap_or_default(),
+    )?)
+}
+
+#[tokio::test]
+async fn test_set_and_delete_attributes() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+    let conf = &ctx.owner_client_conf_path;
+
+    let key_id = create_symmetric_key(conf, None, None, None, &["set_attributes_test"])?;
+
+    // the user tags are replaced, the system tags are preserved
+    set_attributes(conf, &key_id, &["--new-tag", "new_1", "--new-tag", "new_2"])?;
+    assert_eq!(
+        get_tags(conf, &key_id)?,
+        HashSet::from(["new_1".to_owned(), "new_2".to_owned(), "_kk".to_owned()])
+    );
+    // system tags cannot be set
+    assert!(set_attributes(conf, &key_id, &["--new-tag", "_kk"]).is_err());
+
+    // activation date and links
+    set_attributes(
+        conf,
+        &key_id,
+        &[
+            "--activation-date",
+            "1697201574000",
+            "--linked-public-key-id",
+            "public_key_id",
+        ],
+    )?;
+    let attributes = get_attributes(
+        conf,
+        &key_id,
+        &[
+            AttributeTag::ActivationDate,
+            AttributeTag::LinkedPublicKeyId,
+        ],
+    )?;
+    assert_eq!(
+        attributes.get(&AttributeTag::ActivationDate),
+        Some(&serde_json::json!(1_697_201_574_000_u64))
+    );
+    assert_eq!(
+        attributes.get(&AttributeTag::LinkedPublicKeyId),
+        Some(&serde_json::json!("public_key_id"))
+    );
+
+    // delete some user tags, then all of them
+    delete_attributes(conf, &key_id, &["--delete-tag", "new_1"])?;
+    assert_eq!(
+        get_tags(conf, &key_id)?,
+        HashSet::from(["new_2".to_owned(), "_kk".to_owned()])
+    );
+    assert!(delete_attributes(conf, &key_id, &["--delete-tag", "_kk"]).is_err());
+    delete_attributes(conf, &key_id, &["--attribute", "tags"])?;
+    assert_eq!(get_tags(conf, &key_id)?, HashSet::from(["_kk".to_owned()]));
+
+    // delete the activation date and the link
+    delete_attributes(
+        conf,
+        &key_id,
+        &[
+            "--attribute",
+            "activation-date",
+            "--attribute",
+            "linked-public-key-id",
+        ],
+    )?;
+    let attributes = get_attributes(
+        conf,
+        &key_id,
+        &[
+            AttributeTag::ActivationDate,
+            AttributeTag::LinkedPublicKeyId,
+        ],
+    )?;
+    assert!(attributes.get(&AttributeTag::ActivationDate).is_none());
+    assert!(attributes.get(&AttributeTag::LinkedPublicKeyId).is_none());
+
+    // attributes set by the server cannot be deleted
+    assert!(delete_attributes(conf, &key_id, &["--attribute", "cryptographic-algorithm"]).is_err());
+    // nor can the usage mask, which restricts the usage of the key
+    assert!(
+        delete_attributes(conf, &key_id, &["--attribute", "cryptographic-usage-mask"]).is_err()
+    );
+    // the attribute to delete must exist
+    assert!(delete_attributes(conf, &key_id, &["--attribute", "activation-date"]).is_err());
+
+    Ok(())
+}
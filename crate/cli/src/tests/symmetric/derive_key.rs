@@ -0,0 +1,158 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::{read_bytes_from_file, KMS_CLI_CONF_ENV};
+use kms_test_server::{start_default_test_kms_server, ONCE};
+use tempfile::TempDir;
+
+use super::SUB_COMMAND;
+use crate::{
+    actions::shared::ExportKeyFormat,
+    error::CliError,
+    tests::{
+        shared::export_key,
+        symmetric::{
+            create_key::create_symmetric_key,
+            encrypt_decrypt::{decrypt, encrypt},
+        },
+        utils::{extract_uids::extract_uid, recover_cmd_logs},
+        PROG_NAME,
+    },
+};
+
+/// Derive a key from the base key `key_id` and return the derived key id
+pub fn derive_key(cli_conf_path: &str, key_id: &str, options: &[&str]) -> Result<String, CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    let mut args = vec!["keys", "derive", "--key-id", key_id];
+    args.extend(options);
+    cmd.arg(SUB_COMMAND).args(args);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        let output = std::str::from_utf8(&output.stdout)?;
+        let unique_identifier =
+            extract_uid(output, "The key was derived with id").ok_or_else(|| {
+                CliError::Default("failed extracting the unique identifier".to_owned())
+            })?;
+        return Ok(unique_identifier.to_string())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_derive_key() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // create a temp dir
+    let tmp_dir = TempDir::new()?;
+    let tmp_path = tmp_dir.path();
+
+    let base_key_id = create_symmetric_key(&ctx.owner_client_conf_path, None, None, None, &[])?;
+
+    for options in [
+        vec![
+            "--derivation-method",
+            "hkdf",
+            "--salt",
+            "000102030405060708090a0b0c0d0e0f",
+            "--derivation-data",
+            "f0f1f2f3",
+        ],
+        vec![
+            "--derivation-method",
+            "pbkdf2",
+            "--salt",
+            "000102030405060708090a0b0c0d0e0f",
+            "--iteration-count",
+            "1000",
+            "--hashing-algorithm",
+            "sha512",
+        ],
+        vec![
+            "--derivation-method",
+            "nist800-108-c",
+            "--derivation-data",
+            "6c6162656c00636f6e74657874",
+        ],
+    ] {
+        // the derivation is deterministic
+        let key_id_1 = derive_key(&ctx.owner_client_conf_path, &base_key_id, &options)?;
+        let key_id_2 = derive_key(&ctx.owner_client_conf_path, &base_key_id, &options)?;
+        let key_file_1 = tmp_path.join("derived_1.key");
+        let key_file_2 = tmp_path.join("derived_2.key");
+        for (key_id, key_file) in [(&key_id_1, &key_file_1), (&key_id_2, &key_file_2)] {
+            export_key(
+                &ctx.owner_client_conf_path,
+                SUB_COMMAND,
+                key_id,
+                key_file.to_str().unwrap(),
+                Some(ExportKeyFormat::Raw),
+                false,
+                None,
+                false,
+            )?;
+        }
+        let key_bytes = read_bytes_from_file(&key_file_1)?;
+        assert_eq!(key_bytes.len(), 32);
+        assert_eq!(key_bytes, read_bytes_from_file(&key_file_2)?);
+
+        // the derived key is usable
+        let encrypted_file = tmp_path.join("plain.enc");
+        let recovered_file = tmp_path.join("plain.txt");
+        encrypt(
+            &ctx.owner_client_conf_path,
+            "test_data/plain.txt",
+            &key_id_1,
+            Some(encrypted_file.to_str().unwrap()),
+            None,
+        )?;
+        decrypt(
+            &ctx.owner_client_conf_path,
+            encrypted_file.to_str().unwrap(),
+            &key_id_2,
+            Some(recovered_file.to_str().unwrap()),
+            None,
+        )?;
+        assert_eq!(
+            read_bytes_from_file(&recovered_file)?,
+            read_bytes_from_file("test_data/plain.txt")?
+        );
+    }
+
+    // derive a seed then a key from the seed
+    let seed_id = derive_key(
+        &ctx.owner_client_conf_path,
+        &base_key_id,
+        &["--secret-data", "--number-of-bits", "512"],
+    )?;
+    derive_key(
+        &ctx.owner_client_conf_path,
+        &seed_id,
+        &["--number-of-bits", "128"],
+    )?;
+
+    // PBKDF2 requires a salt
+    assert!(
+        derive_key(
+            &ctx.owner_client_conf_path,
+            &base_key_id,
+            &["--derivation-method", "pbkdf2", "--iteration-count", "1000"],
+        )
+        .is_err()
+    );
+
+    // an HMAC key cannot be used to derive keys
+    let hmac_key_id = create_symmetric_key(
+        &ctx.owner_client_conf_path,
+        None,
+        None,
+        Some("hmac-sha256"),
+        &[],
+    )?;
+    assert!(derive_key(&ctx.owner_client_conf_path, &hmac_key_id, &[]).is_err());
+
+    Ok(())
+}
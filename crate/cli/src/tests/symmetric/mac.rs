@@ -0,0 +1,222 @@
+use std::{path::PathBuf, process::Command};
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::{read_bytes_from_file, KMS_CLI_CONF_ENV};
+use kms_test_server::{start_default_test_kms_server, ONCE};
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+use super::SUB_COMMAND;
+use crate::{
+    error::CliError,
+    tests::{
+        symmetric::{create_key::create_symmetric_key, encrypt_decrypt::encrypt},
+        utils::recover_cmd_logs,
+        PROG_NAME,
+    },
+};
+
+/// Compute the MAC of a file using the given key
+pub fn mac(
+    cli_conf_path: &str,
+    input_file: &str,
+    key_id: &str,
+    chunk_size: Option<usize>,
+    output_file: Option<&str>,
+) -> Result<(), CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    let mut args = vec!["mac", input_file, "--key-id", key_id];
+    let chunk_size_s = chunk_size.map(|c| c.to_string()).unwrap_or_default();
+    if chunk_size.is_some() {
+        args.push("--chunk-size");
+        args.push(&chunk_size_s);
+    }
+    if let Some(output_file) = output_file {
+        args.push("-o");
+        args.push(output_file);
+    }
+    cmd.arg(SUB_COMMAND).args(args);
+    recover_cmd_logs(&mut cmd);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("The MAC file is available at"));
+    Ok(())
+}
+
+/// Verify the MAC of a file using the given key
+pub fn mac_verify(
+    cli_conf_path: &str,
+    input_file: &str,
+    mac_file: &str,
+    key_id: &str,
+    chunk_size: Option<usize>,
+) -> Result<(), CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    let mut args = vec!["mac-verify", input_file, mac_file, "--key-id", key_id];
+    let chunk_size_s = chunk_size.map(|c| c.to_string()).unwrap_or_default();
+    if chunk_size.is_some() {
+        args.push("--chunk-size");
+        args.push(&chunk_size_s);
+    }
+    cmd.arg(SUB_COMMAND).args(args);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        return Ok(())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_hmac() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // create a temp dir
+    let tmp_dir = TempDir::new()?;
+    let tmp_path = tmp_dir.path();
+
+    let input_file = PathBuf::from("test_data/plain.txt");
+    let mac_file = tmp_path.join("plain.mac");
+    let streamed_mac_file = tmp_path.join("plain.streamed.mac");
+
+    for (algorithm, mac_length) in [
+        ("hmac-sha256", 32),
+        ("hmac-sha384", 48),
+        ("hmac-sha512", 64),
+    ] {
+        let key_id = create_symmetric_key(
+            &ctx.owner_client_conf_path,
+            None,
+            None,
+            Some(algorithm),
+            &[],
+        )?;
+
+        // MAC in one shot
+        mac(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            &key_id,
+            None,
+            Some(mac_file.to_str().unwrap()),
+        )?;
+        assert_eq!(read_bytes_from_file(&mac_file)?.len(), mac_length);
+
+        // MAC by parts: the result must be the same
+        mac(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            &key_id,
+            Some(5),
+            Some(streamed_mac_file.to_str().unwrap()),
+        )?;
+        assert_eq!(
+            read_bytes_from_file(&mac_file)?,
+            read_bytes_from_file(&streamed_mac_file)?
+        );
+
+        // verify in one shot and by parts
+        mac_verify(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            mac_file.to_str().unwrap(),
+            &key_id,
+            None,
+        )?;
+        mac_verify(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            mac_file.to_str().unwrap(),
+            &key_id,
+            Some(7),
+        )?;
+
+        // verification of other data must fail
+        assert!(
+            mac_verify(
+                &ctx.owner_client_conf_path,
+                "test_data/plain2.txt",
+                mac_file.to_str().unwrap(),
+                &key_id,
+                None,
+            )
+            .is_err()
+        );
+    }
+
+    // an HMAC key cannot be used to encrypt
+    let key_id = create_symmetric_key(
+        &ctx.owner_client_conf_path,
+        None,
+        None,
+        Some("hmac-sha256"),
+        &[],
+    )?;
+    assert!(
+        encrypt(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            &key_id,
+            Some(tmp_path.join("plain.enc").to_str().unwrap()),
+            None,
+        )
+        .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cmac() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // create a temp dir
+    let tmp_dir = TempDir::new()?;
+    let tmp_path = tmp_dir.path();
+
+    let input_file = PathBuf::from("test_data/plain.txt");
+    let mac_file = tmp_path.join("plain.mac");
+
+    let key_id = create_symmetric_key(
+        &ctx.owner_client_conf_path,
+        Some(128),
+        None,
+        Some("cmac"),
+        &[],
+    )?;
+    mac(
+        &ctx.owner_client_conf_path,
+        input_file.to_str().unwrap(),
+        &key_id,
+        Some(16),
+        Some(mac_file.to_str().unwrap()),
+    )?;
+    assert_eq!(read_bytes_from_file(&mac_file)?.len(), 16);
+    mac_verify(
+        &ctx.owner_client_conf_path,
+        input_file.to_str().unwrap(),
+        mac_file.to_str().unwrap(),
+        &key_id,
+        None,
+    )?;
+
+    // a plain AES key cannot be used to MAC
+    let aes_key_id = create_symmetric_key(&ctx.owner_client_conf_path, None, None, None, &[])?;
+    assert!(
+        mac_verify(
+            &ctx.owner_client_conf_path,
+            input_file.to_str().unwrap(),
+            mac_file.to_str().unwrap(),
+            &aes_key_id,
+            None,
+        )
+        .is_err()
+    );
+
+    Ok(())
+}
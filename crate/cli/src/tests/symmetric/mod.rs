@@ -1,4 +1,7 @@
 pub mod create_key;
+pub mod derive_key;
 pub mod encrypt_decrypt;
+pub mod mac;
+pub mod rekey;
 
 pub(crate) const SUB_COMMAND: &str = "sym";
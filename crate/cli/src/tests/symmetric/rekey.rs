@@ -0,0 +1,162 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use cosmian_kms_client::{read_bytes_from_file, KMS_CLI_CONF_ENV};
+use kms_test_server::{start_default_test_kms_server, ONCE};
+use tempfile::TempDir;
+
+use super::SUB_COMMAND;
+use crate::{
+    actions::shared::ExportKeyFormat,
+    error::CliError,
+    tests::{
+        access::grant_access,
+        shared::export_key,
+        symmetric::{create_key::create_symmetric_key, encrypt_decrypt::encrypt},
+        utils::{extract_uids::extract_uid, recover_cmd_logs},
+        PROG_NAME,
+    },
+};
+
+/// Rotate the symmetric key `key_id` and return the id of the replacement key
+pub fn rekey_symmetric_key(
+    cli_conf_path: &str,
+    key_id: &str,
+    offset: Option<i32>,
+) -> Result<String, CliError> {
+    let mut cmd = Command::cargo_bin(PROG_NAME)?;
+    cmd.env(KMS_CLI_CONF_ENV, cli_conf_path);
+    cmd.env("RUST_LOG", "cosmian_kms_cli=info");
+    let mut args = vec!["keys", "rekey", "--key-id", key_id];
+    let offset_s = offset.map(|o| o.to_string()).unwrap_or_default();
+    if offset.is_some() {
+        args.push("--offset");
+        args.push(&offset_s);
+    }
+    cmd.arg(SUB_COMMAND).args(args);
+    let output = recover_cmd_logs(&mut cmd);
+    if output.status.success() {
+        let output = std::str::from_utf8(&output.stdout)?;
+        let unique_identifier = extract_uid(output, "The symmetric key was rotated with new id")
+            .ok_or_else(|| {
+                CliError::Default("failed extracting the unique identifier".to_owned())
+            })?;
+        return Ok(unique_identifier.to_string())
+    }
+    Err(CliError::Default(
+        std::str::from_utf8(&output.stderr)?.to_owned(),
+    ))
+}
+
+#[tokio::test]
+async fn test_rekey_symmetric_key() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    // create a temp dir
+    let tmp_dir = TempDir::new()?;
+    let tmp_path = tmp_dir.path();
+    let encrypted_file = tmp_path.join("plain.enc");
+
+    let mut algorithms = vec![("aes", 128)];
+    #[cfg(not(feature = "fips"))]
+    algorithms.push(("chacha20", 256));
+
+    for (algorithm, number_of_bits) in algorithms {
+        let key_id = create_symmetric_key(
+            &ctx.owner_client_conf_path,
+            Some(number_of_bits),
+            None,
+            Some(algorithm),
+            &["rekey_test"],
+        )?;
+        let new_key_id = rekey_symmetric_key(&ctx.owner_client_conf_path, &key_id, None)?;
+        assert_ne!(key_id, new_key_id);
+
+        // the replacement key has the same length
+        let key_file = tmp_path.join("new.key");
+        export_key(
+            &ctx.owner_client_conf_path,
+            SUB_COMMAND,
+            &new_key_id,
+            key_file.to_str().unwrap(),
+            Some(ExportKeyFormat::Raw),
+            false,
+            None,
+            false,
+        )?;
+        assert_eq!(read_bytes_from_file(&key_file)?.len(), number_of_bits / 8);
+
+        // the replacement key can be used, the existing key is deactivated
+        encrypt(
+            &ctx.owner_client_conf_path,
+            "test_data/plain.txt",
+            &new_key_id,
+            Some(encrypted_file.to_str().unwrap()),
+            None,
+        )?;
+        assert!(
+            encrypt(
+                &ctx.owner_client_conf_path,
+                "test_data/plain.txt",
+                &key_id,
+                Some(encrypted_file.to_str().unwrap()),
+                None,
+            )
+            .is_err()
+        );
+
+        // a deactivated key cannot be rotated again
+        assert!(rekey_symmetric_key(&ctx.owner_client_conf_path, &key_id, None).is_err());
+    }
+
+    // with an offset
+    let key_id = create_symmetric_key(&ctx.owner_client_conf_path, None, None, None, &[])?;
+    rekey_symmetric_key(&ctx.owner_client_conf_path, &key_id, Some(3600))?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rekey_granted_symmetric_key() -> Result<(), CliError> {
+    let ctx = ONCE.get_or_try_init(start_default_test_kms_server).await?;
+
+    let tmp_dir = TempDir::new()?;
+    let key_file = tmp_dir.path().join("new.key");
+
+    // the owner allows the user to rotate the key
+    let key_id = create_symmetric_key(&ctx.owner_client_conf_path, None, None, None, &[])?;
+    grant_access(
+        &ctx.owner_client_conf_path,
+        &key_id,
+        "user.client@acme.com",
+        &["rekey"],
+    )?;
+    let new_key_id = rekey_symmetric_key(&ctx.user_client_conf_path, &key_id, None)?;
+
+    // the replacement key still belongs to the owner
+    export_key(
+        &ctx.owner_client_conf_path,
+        SUB_COMMAND,
+        &new_key_id,
+        key_file.to_str().unwrap(),
+        None,
+        false,
+        None,
+        false,
+    )?;
+    assert!(
+        export_key(
+            &ctx.user_client_conf_path,
+            SUB_COMMAND,
+            &new_key_id,
+            key_file.to_str().unwrap(),
+            None,
+            false,
+            None,
+            false,
+        )
+        .is_err()
+    );
+
+    Ok(())
+}
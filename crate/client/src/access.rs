@@ -17,6 +17,32 @@ pub struct Access {
     pub user_id: String,
     /// Operation types for the access
     pub operation_types: Vec<ObjectOperationType>,
+    /// Name of a role granted or revoked along with `operation_types`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+/// Prefix of the user identifiers designating a group of users
+/// e.g. `group:finance`
+pub const GROUP_PREFIX: &str = "group:";
+
+/// A role: a named bundle of operation types
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Role {
+    /// The name of the role
+    pub name: String,
+    /// Operation types granted with the role
+    #[serde(default)]
+    pub operation_types: Vec<ObjectOperationType>,
+}
+
+/// The membership of a user to a group
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupMember {
+    /// The name of the group, without the `group:` prefix
+    pub group: String,
+    /// User identifier, member of the group
+    pub user_id: String,
 }
 /// Operation types that can get or create objects
 /// These operations use `retrieve` or `get` methods.
@@ -35,6 +61,12 @@ pub enum ObjectOperationType {
     Locate,
     Revoke,
     Rekey,
+    Sign,
+    Mac,
+    Derive,
+    SetAttributes,
+    Activate,
+    Archive,
 }
 
 impl fmt::Debug for ObjectOperationType {
@@ -58,6 +90,12 @@ impl fmt::Display for ObjectOperationType {
             Self::Locate => "locate",
             Self::Revoke => "revoke",
             Self::Rekey => "rekey",
+            Self::Sign => "sign",
+            Self::Mac => "mac",
+            Self::Derive => "derive",
+            Self::SetAttributes => "set_attributes",
+            Self::Activate => "activate",
+            Self::Archive => "archive",
         };
         write!(f, "{str}")
     }
@@ -83,6 +121,12 @@ impl FromStr for ObjectOperationType {
             "locate" => Ok(Self::Locate),
             "rekey" => Ok(Self::Rekey),
             "revoke" => Ok(Self::Revoke),
+            "sign" => Ok(Self::Sign),
+            "mac" => Ok(Self::Mac),
+            "derive" => Ok(Self::Derive),
+            "set_attributes" => Ok(Self::SetAttributes),
+            "activate" => Ok(Self::Activate),
+            "archive" => Ok(Self::Archive),
             _ => Err("Could not parse an operation {op}"),
         }
     }
@@ -163,6 +207,18 @@ impl
         }
     }
 }
+#[derive(Deserialize, Serialize, Debug)] // Debug is required by ok_json()
+pub struct RoleResponse {
+    pub name: String,
+    /// A `BTreeSet` is used to keep results sorted
+    pub operations: BTreeSet<ObjectOperationType>,
+}
+#[derive(Deserialize, Serialize, Debug)] // Debug is required by ok_json()
+pub struct GroupResponse {
+    pub group: String,
+    /// A `BTreeSet` is used to keep results sorted
+    pub members: BTreeSet<String>,
+}
 // Response for success
 #[derive(Deserialize, Serialize, Debug)] // Debug is required by ok_json()
 pub struct SuccessResponse {
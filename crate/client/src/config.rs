@@ -97,6 +97,88 @@ pub struct GmailApiConf {
     pub universe_domain: String,
 }
 
+/// A slot of the PKCS#11 provider.
+///
+/// Each slot connects to a KMS server, or a database group of a KMS server,
+/// with its own identity. The credential of the identity is unlocked
+/// by the PIN of the token when the application logs in.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct Pkcs11SlotConf {
+    /// The label of the token in the slot (32 characters max.)
+    pub token_label: String,
+    /// The URL of the KMS server; defaults to the `kms_server_url` of the configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_server_url: Option<String>,
+    /// The secret of the database group of the KMS server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kms_database_secret: Option<String>,
+    /// The PKCS#12 client certificate of the identity.
+    /// Once the PIN is initialized, the PKCS#12 is protected by the PIN.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssl_client_pkcs12_path: Option<String>,
+    /// The password of the PKCS#12 before the PIN is initialized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssl_client_pkcs12_password: Option<String>,
+    /// The `OAuth2` refresh token of the identity before the PIN is initialized.
+    /// The JWT is obtained from the token endpoint of the `oauth2_conf`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth2_refresh_token: Option<String>,
+    /// The `OAuth2` refresh token encrypted under a key derived from the PIN
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin_protected_oauth2_refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OAuth2TokenResponse {
+    access_token: String,
+    id_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+/// The tokens returned by the token endpoint of an `OAuth2` identity provider
+pub struct OAuth2Tokens {
+    /// The JWT to authenticate to the KMS
+    pub jwt: String,
+    /// The new refresh token, when the identity provider rotates them
+    pub refresh_token: Option<String>,
+    /// The lifetime of the JWT in seconds, if known
+    pub expires_in: Option<u64>,
+}
+
+impl Oauth2Conf {
+    /// Obtain a JWT from the token endpoint using the `refresh_token`.
+    ///
+    /// Identity providers such as Google return the JWT in the `id_token` field.
+    /// The refresh token may be rotated: the new one is then returned
+    /// and the `refresh_token` must no longer be used.
+    pub async fn refresh_jwt(&self, refresh_token: &str) -> Result<OAuth2Tokens, ClientError> {
+        let response = reqwest::Client::new()
+            .post(&self.token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(ClientError::Default(format!(
+                "failed refreshing the OAuth2 token: {}",
+                response.text().await.unwrap_or_default()
+            )))
+        }
+        let tokens = response.json::<OAuth2TokenResponse>().await?;
+        Ok(OAuth2Tokens {
+            jwt: tokens.id_token.unwrap_or(tokens.access_token),
+            refresh_token: tokens.refresh_token,
+            expires_in: tokens.expires_in,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct ClientConf {
     // accept_invalid_certs is useful if the cli needs to connect to an HTTPS KMS server
@@ -119,6 +201,10 @@ pub struct ClientConf {
     pub oauth2_conf: Option<Oauth2Conf>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gmail_api_conf: Option<GmailApiConf>,
+    /// The slots of the PKCS#11 provider;
+    /// a single slot using this configuration is exposed if none is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pkcs11_slots: Option<Vec<Pkcs11SlotConf>>,
 }
 
 impl Default for ClientConf {
@@ -133,6 +219,7 @@ impl Default for ClientConf {
             ssl_client_pkcs12_password: None,
             oauth2_conf: None,
             gmail_api_conf: None,
+            pkcs11_slots: None,
         }
     }
 }
@@ -282,6 +369,25 @@ impl ClientConf {
 
         Ok(kms_rest_client)
     }
+
+    /// The configuration of the identity of a PKCS#11 slot:
+    /// the slot settings override those of this configuration,
+    /// and the credentials of this configuration are not inherited.
+    #[must_use]
+    pub fn for_pkcs11_slot(&self, slot: &Pkcs11SlotConf) -> Self {
+        Self {
+            kms_server_url: slot
+                .kms_server_url
+                .clone()
+                .unwrap_or_else(|| self.kms_server_url.clone()),
+            kms_access_token: None,
+            kms_database_secret: slot.kms_database_secret.clone(),
+            ssl_client_pkcs12_path: slot.ssl_client_pkcs12_path.clone(),
+            ssl_client_pkcs12_password: slot.ssl_client_pkcs12_password.clone(),
+            pkcs11_slots: None,
+            ..self.clone()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -9,11 +9,18 @@ use cosmian_kmip::kmip::kmip_messages::{Message, MessageResponse};
 // re-export the kmip module as kmip
 use cosmian_kmip::kmip::{
     kmip_operations::{
-        Certify, CertifyResponse, Create, CreateKeyPair, CreateKeyPairResponse, CreateResponse,
-        Decrypt, DecryptResponse, Destroy, DestroyResponse, Encrypt, EncryptResponse, Export,
+        Activate, ActivateResponse, AddAttribute, AddAttributeResponse, Archive, ArchiveResponse,
+        Cancel, CancelResponse, Certify, CertifyResponse, Create, CreateKeyPair,
+        CreateKeyPairResponse, CreateResponse, Decrypt, DecryptResponse, DeleteAttribute,
+        DeleteAttributeResponse, DeriveKey, DeriveKeyResponse, Destroy, DestroyResponse,
+        DiscoverVersions, DiscoverVersionsResponse, Encrypt, EncryptResponse, Export,
         ExportResponse, Get, GetAttributes, GetAttributesResponse, GetResponse, Import,
-        ImportResponse, Locate, LocateResponse, ReKeyKeyPair, ReKeyKeyPairResponse, Revoke,
-        RevokeResponse,
+        ImportResponse, Locate, LocateResponse, MACResponse, MACVerify, MACVerifyResponse,
+        ModifyAttribute, ModifyAttributeResponse, Query, QueryResponse, RNGRetrieve,
+        RNGRetrieveResponse, RNGSeed, RNGSeedResponse, ReKey, ReKeyKeyPair, ReKeyKeyPairResponse,
+        ReKeyResponse, Recover, RecoverResponse, Revoke, RevokeResponse, SetAttribute,
+        SetAttributeResponse, Sign, SignResponse, SignatureVerify, SignatureVerifyResponse,
+        Validate, ValidateResponse, MAC,
     },
     ttlv::{deserializer::from_ttlv, serializer::to_ttlv, TTLV},
 };
@@ -25,8 +32,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     access::{
-        Access, AccessRightsObtainedResponse, ObjectOwnedResponse, SuccessResponse,
-        UserAccessResponse,
+        Access, AccessRightsObtainedResponse, GroupMember, GroupResponse, ObjectOwnedResponse,
+        Role, RoleResponse, SuccessResponse, UserAccessResponse,
     },
     certificate_verifier::{LeafCertificateVerifier, NoVerifier},
     error::ClientError,
@@ -131,6 +138,16 @@ impl KmsClient {
         self.post_ttlv::<Decrypt, DecryptResponse>(&request).await
     }
 
+    /// This request is used to derive a Symmetric Key or Secret Data object
+    /// from a key or Secret Data object that is already known to the key
+    /// management system. The base object must have the Derive Key bit set
+    /// in its Cryptographic Usage Mask.
+    /// The response contains the Unique Identifier of the derived object.
+    pub async fn derive_key(&self, request: DeriveKey) -> Result<DeriveKeyResponse, ClientError> {
+        self.post_ttlv::<DeriveKey, DeriveKeyResponse>(&request)
+            .await
+    }
+
     /// This operation is used to indicate to the server that the key material
     /// for the specified Managed Object SHALL be destroyed or rendered
     /// inaccessible. The meta-data for the key material SHALL be retained by
@@ -140,6 +157,20 @@ impl KmsClient {
         self.post_ttlv::<Destroy, DestroyResponse>(&request).await
     }
 
+    /// This operation is used to specify that a Managed Object MAY be archived.
+    /// Objects that have been archived are not available for any operation,
+    /// other than Recover, until they are recovered.
+    pub async fn archive(&self, request: Archive) -> Result<ArchiveResponse, ClientError> {
+        self.post_ttlv::<Archive, ArchiveResponse>(&request).await
+    }
+
+    /// This operation is used to obtain access to a Managed Object that has
+    /// been archived. Once the response is received, the object is now
+    /// on-line, and MAY be obtained (e.g., via a Get operation).
+    pub async fn recover(&self, request: Recover) -> Result<RecoverResponse, ClientError> {
+        self.post_ttlv::<Recover, RecoverResponse>(&request).await
+    }
+
     /// This operation requests the server to perform an encryption operation on
     /// the provided data using a Managed Cryptographic Object as the key
     /// for the encryption operation. The request contains information about
@@ -221,6 +252,54 @@ impl KmsClient {
             .await
     }
 
+    /// This operation requests the server to either add or modify an attribute.
+    /// If the object did not have any instances of the attribute, one is
+    /// created. If the object had exactly one instance, then it is modified.
+    /// If it has more than one instance an error is raised.
+    ///
+    /// The tags of the object are set using the Cosmian `tag` vendor attribute:
+    /// the user tags are replaced and the system tags are preserved.
+    pub async fn set_attribute(
+        &self,
+        request: SetAttribute,
+    ) -> Result<SetAttributeResponse, ClientError> {
+        self.post_ttlv::<SetAttribute, SetAttributeResponse>(&request)
+            .await
+    }
+
+    /// This operation requests the server to add a new attribute instance to
+    /// be associated with a Managed Object and set its value. Existing
+    /// attribute values SHALL NOT be changed by this operation.
+    pub async fn add_attribute(
+        &self,
+        request: AddAttribute,
+    ) -> Result<AddAttributeResponse, ClientError> {
+        self.post_ttlv::<AddAttribute, AddAttributeResponse>(&request)
+            .await
+    }
+
+    /// This operation requests the server to modify the value of an existing
+    /// attribute instance associated with a Managed Object. The attribute
+    /// instance SHALL exist, otherwise an error is returned.
+    pub async fn modify_attribute(
+        &self,
+        request: ModifyAttribute,
+    ) -> Result<ModifyAttributeResponse, ClientError> {
+        self.post_ttlv::<ModifyAttribute, ModifyAttributeResponse>(&request)
+            .await
+    }
+
+    /// This operation requests the server to delete an attribute associated
+    /// with a Managed Object: either a given attribute instance, or all the
+    /// instances of the referenced attributes.
+    pub async fn delete_attribute(
+        &self,
+        request: DeleteAttribute,
+    ) -> Result<DeleteAttributeResponse, ClientError> {
+        self.post_ttlv::<DeleteAttribute, DeleteAttributeResponse>(&request)
+            .await
+    }
+
     /// This operation requests the server to Import a Managed Object specified
     /// by its Unique Identifier. The request specifies the object being
     /// imported and all the attributes to be assigned to the object. The
@@ -328,6 +407,56 @@ impl KmsClient {
         self.post_ttlv::<Locate, LocateResponse>(&request).await
     }
 
+    /// This operation is used by the client to interrogate the server to
+    /// determine its capabilities and/or protocol mechanisms: supported
+    /// operations and object types, server information and capabilities.
+    pub async fn query(&self, request: Query) -> Result<QueryResponse, ClientError> {
+        self.post_ttlv::<Query, QueryResponse>(&request).await
+    }
+
+    /// This operation is used by the client to determine a list of protocol
+    /// versions that is supported by the server.
+    pub async fn discover_versions(
+        &self,
+        request: DiscoverVersions,
+    ) -> Result<DiscoverVersionsResponse, ClientError> {
+        self.post_ttlv::<DiscoverVersions, DiscoverVersionsResponse>(&request)
+            .await
+    }
+
+    /// This operation requests the server to return output from a Random
+    /// Number Generator (RNG).
+    pub async fn rng_retrieve(
+        &self,
+        request: RNGRetrieve,
+    ) -> Result<RNGRetrieveResponse, ClientError> {
+        self.post_ttlv::<RNGRetrieve, RNGRetrieveResponse>(&request)
+            .await
+    }
+
+    /// This operation requests the server to seed a Random Number Generator.
+    /// The response contains the amount of seed data accepted by the server.
+    pub async fn rng_seed(&self, request: RNGSeed) -> Result<RNGSeedResponse, ClientError> {
+        self.post_ttlv::<RNGSeed, RNGSeedResponse>(&request).await
+    }
+
+    /// This request is used to generate a replacement key for an existing
+    /// symmetric key. It is analogous to the Create operation, except that
+    /// attributes of the replacement key are copied from the existing key.
+    ///
+    /// For the existing key, the server SHALL create a Link attribute of Link
+    /// Type Replacement Object pointing to the replacement key. For the
+    /// replacement key, the server SHALL create a Link attribute of Link Type
+    /// Replaced Object pointing to the existing key. The existing key is
+    /// deactivated.
+    ///
+    /// An Offset MAY be used to indicate the difference between the Initial
+    /// Date and the Activation Date of the replacement key. If no Offset is
+    /// specified, the Activation Date is copied from the existing key.
+    pub async fn rekey(&self, request: ReKey) -> Result<ReKeyResponse, ClientError> {
+        self.post_ttlv::<ReKey, ReKeyResponse>(&request).await
+    }
+
     // This request is used to generate a replacement key pair for an existing
     // public/private key pair.  It is analogous to the Create Key Pair operation,
     // except that attributes of the replacement key pair are copied from the
@@ -361,6 +490,14 @@ impl KmsClient {
             .await
     }
 
+    /// This operation requests the server to activate a Managed Cryptographic
+    /// Object. The operation SHALL only be performed on an object in the
+    /// Pre-Active state and has the effect of changing its state to Active,
+    /// and setting its Activation Date to the current date and time.
+    pub async fn activate(&self, request: Activate) -> Result<ActivateResponse, ClientError> {
+        self.post_ttlv::<Activate, ActivateResponse>(&request).await
+    }
+
     /// This operation requests the server to revoke a Managed Cryptographic
     /// Object or an Opaque Object. The request contains a reason for the
     /// revocation (e.g., "key compromise", "cessation of operation", etc.). The
@@ -377,6 +514,60 @@ impl KmsClient {
         self.post_ttlv::<Revoke, RevokeResponse>(&request).await
     }
 
+    /// This operation requests the server to perform a signature operation on
+    /// the provided data using a Managed Cryptographic Object as the key for
+    /// the signature operation. The request contains information about the
+    /// cryptographic parameters (digital signature algorithm or cryptographic
+    /// algorithm and hash algorithm) and the data to be signed.
+    /// The response contains the Unique Identifier of the Managed Cryptographic
+    /// Object used as the key and the result of the signature operation.
+    pub async fn sign(&self, request: Sign) -> Result<SignResponse, ClientError> {
+        self.post_ttlv::<Sign, SignResponse>(&request).await
+    }
+
+    /// This operation requests the server to perform a signature verify
+    /// operation on the provided data using a Managed Cryptographic Object as
+    /// the key for the signature verification operation. The request contains
+    /// the signature to be verified and the data that was passed to the
+    /// signing operation. The response contains a Validity Indicator stating
+    /// whether the signature is valid.
+    pub async fn signature_verify(
+        &self,
+        request: SignatureVerify,
+    ) -> Result<SignatureVerifyResponse, ClientError> {
+        self.post_ttlv::<SignatureVerify, SignatureVerifyResponse>(&request)
+            .await
+    }
+
+    /// This operation requests the server to validate a certificate chain,
+    /// starting with the leaf certificate, up to a root held by the server.
+    /// The response contains a Validity Indicator stating whether the chain
+    /// is valid and, if not, the first reason found.
+    pub async fn validate(&self, request: Validate) -> Result<ValidateResponse, ClientError> {
+        self.post_ttlv::<Validate, ValidateResponse>(&request).await
+    }
+
+    /// This operation requests the server to perform message authentication
+    /// code (MAC) operation on the provided data using a Managed Cryptographic
+    /// Object as the key for the MAC operation.
+    /// Large data can be MACed by parts using the Init and Final Indicators
+    /// and the Correlation Value returned by the server.
+    /// The response contains the Unique Identifier of the Managed Cryptographic
+    /// Object used as the key and, on completion, the result of the MAC operation.
+    pub async fn mac(&self, request: MAC) -> Result<MACResponse, ClientError> {
+        self.post_ttlv::<MAC, MACResponse>(&request).await
+    }
+
+    /// This operation requests the server to perform message authentication
+    /// code (MAC) verify operation on the provided data using a Managed
+    /// Cryptographic Object as the key for the MAC verify operation.
+    /// The response contains, on completion, a Validity Indicator stating
+    /// whether the MAC is valid.
+    pub async fn mac_verify(&self, request: MACVerify) -> Result<MACVerifyResponse, ClientError> {
+        self.post_ttlv::<MACVerify, MACVerifyResponse>(&request)
+            .await
+    }
+
     /// This operation requests the server to send a message, which is a list of operations,
     /// to the server.
     ///The messages in the protocol consist of a message header, one or more batch items
@@ -390,6 +581,15 @@ impl KmsClient {
         self.post_ttlv::<Message, MessageResponse>(&request).await
     }
 
+    /// This operation requests the server to cancel an outstanding asynchronous
+    /// operation, identified by the Asynchronous Correlation Value returned
+    /// in the pending batch item of a message.
+    /// The response contains the result of the cancellation.
+    /// Asynchronous operations are polled using a `Poll` batch item in a message.
+    pub async fn cancel(&self, request: Cancel) -> Result<CancelResponse, ClientError> {
+        self.post_ttlv::<Cancel, CancelResponse>(&request).await
+    }
+
     /// This operation requests the server to create a new database.
     /// The returned secrets could be shared between several users.
     pub async fn new_database(&self) -> Result<String, ClientError> {
@@ -433,6 +633,47 @@ impl KmsClient {
         self.get_no_ttlv("/access/obtained", None::<&()>).await
     }
 
+    /// This operation requests the server to create or replace a role,
+    /// i.e. a named bundle of operation types which can be granted at once.
+    /// Only the access rights administrators can manage the roles.
+    pub async fn set_role(&self, role: Role) -> Result<SuccessResponse, ClientError> {
+        self.post_no_ttlv("/access/roles/set", Some(&role)).await
+    }
+
+    /// This operation requests the server to delete a role.
+    /// The access rights already granted with the role are kept.
+    pub async fn delete_role(&self, role: Role) -> Result<SuccessResponse, ClientError> {
+        self.post_no_ttlv("/access/roles/delete", Some(&role)).await
+    }
+
+    /// This operation requests the server to list the roles
+    pub async fn list_roles(&self) -> Result<Vec<RoleResponse>, ClientError> {
+        self.get_no_ttlv("/access/roles", None::<&()>).await
+    }
+
+    /// This operation requests the server to add a user to a local group.
+    /// Only the access rights administrators can manage the groups.
+    pub async fn add_group_member(
+        &self,
+        member: GroupMember,
+    ) -> Result<SuccessResponse, ClientError> {
+        self.post_no_ttlv("/access/groups/add", Some(&member)).await
+    }
+
+    /// This operation requests the server to remove a user from a local group
+    pub async fn remove_group_member(
+        &self,
+        member: GroupMember,
+    ) -> Result<SuccessResponse, ClientError> {
+        self.post_no_ttlv("/access/groups/remove", Some(&member))
+            .await
+    }
+
+    /// This operation requests the server to list the local groups and their members
+    pub async fn list_groups(&self) -> Result<Vec<GroupResponse>, ClientError> {
+        self.get_no_ttlv("/access/groups", None::<&()>).await
+    }
+
     /// This operation requests the version of the server
     pub async fn version(&self) -> Result<String, ClientError> {
         self.get_no_ttlv("/version", None::<&()>).await
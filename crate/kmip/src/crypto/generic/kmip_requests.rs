@@ -6,9 +6,13 @@ use crate::{
     kmip::{
         kmip_data_structures::KeyWrappingSpecification,
         kmip_objects::{Object, ObjectType},
-        kmip_operations::{Decrypt, Encrypt, ErrorReason, Import, Revoke},
+        kmip_operations::{
+            Decrypt, DerivationMethod, DerivationParameters, DeriveKey, Encrypt, ErrorReason,
+            Import, MACVerify, Revoke, Sign, SignatureVerify, MAC,
+        },
         kmip_types::{
-            Attributes, CryptographicParameters, KeyWrapType, RevocationReason, UniqueIdentifier,
+            Attributes, CryptographicAlgorithm, CryptographicParameters, KeyWrapType,
+            RevocationReason, UniqueIdentifier,
         },
     },
 };
@@ -95,6 +99,132 @@ pub fn build_decryption_request(
     }
 }
 
+/// Build a `Sign` request to sign the provided `data`
+/// using the private key identified by `key_unique_identifier`
+///
+/// The signature scheme is derived from the `cryptographic_parameters`
+/// or, if omitted, from the type of the key
+#[must_use]
+pub fn build_sign_request(
+    key_unique_identifier: &str,
+    data: Vec<u8>,
+    cryptographic_parameters: Option<CryptographicParameters>,
+) -> Sign {
+    Sign {
+        unique_identifier: Some(UniqueIdentifier::TextString(
+            key_unique_identifier.to_owned(),
+        )),
+        cryptographic_parameters,
+        data: Some(Zeroizing::from(data)),
+        digested_data: None,
+        correlation_value: None,
+        init_indicator: None,
+        final_indicator: None,
+    }
+}
+
+/// Build a `SignatureVerify` request to verify the `signature` of the provided `data`
+/// using the public key or certificate identified by `key_unique_identifier`
+///
+/// The `cryptographic_parameters` must match the ones used for the signature
+#[must_use]
+pub fn build_signature_verify_request(
+    key_unique_identifier: &str,
+    data: Vec<u8>,
+    signature: Vec<u8>,
+    cryptographic_parameters: Option<CryptographicParameters>,
+) -> SignatureVerify {
+    SignatureVerify {
+        unique_identifier: Some(UniqueIdentifier::TextString(
+            key_unique_identifier.to_owned(),
+        )),
+        cryptographic_parameters,
+        data: Some(data),
+        digested_data: None,
+        signature_data: Some(signature),
+        correlation_value: None,
+        init_indicator: None,
+        final_indicator: None,
+    }
+}
+
+/// Build a `MAC` request to compute the message authentication code of the
+/// provided `data` using the key identified by `key_unique_identifier`
+///
+/// The MAC algorithm is derived from the `cryptographic_parameters`
+/// or, if omitted, from the algorithm of the key
+#[must_use]
+pub fn build_mac_request(
+    key_unique_identifier: &str,
+    data: Vec<u8>,
+    cryptographic_parameters: Option<CryptographicParameters>,
+) -> MAC {
+    MAC {
+        unique_identifier: Some(UniqueIdentifier::TextString(
+            key_unique_identifier.to_owned(),
+        )),
+        cryptographic_parameters,
+        data: Some(data),
+        correlation_value: None,
+        init_indicator: None,
+        final_indicator: None,
+    }
+}
+
+/// Build a `MACVerify` request to verify the `mac_data` of the provided `data`
+/// using the key identified by `key_unique_identifier`
+#[must_use]
+pub fn build_mac_verify_request(
+    key_unique_identifier: &str,
+    data: Vec<u8>,
+    mac_data: Vec<u8>,
+    cryptographic_parameters: Option<CryptographicParameters>,
+) -> MACVerify {
+    MACVerify {
+        unique_identifier: Some(UniqueIdentifier::TextString(
+            key_unique_identifier.to_owned(),
+        )),
+        cryptographic_parameters,
+        data: Some(data),
+        mac_data: Some(mac_data),
+        correlation_value: None,
+        init_indicator: None,
+        final_indicator: None,
+    }
+}
+
+/// Build a `DeriveKey` request to derive a new object of type `object_type`
+/// and of `cryptographic_length` bits from the base key identified by
+/// `base_key_unique_identifier`
+///
+/// The `cryptographic_algorithm` is mandatory when deriving a symmetric key.
+pub fn build_derive_key_request(
+    base_key_unique_identifier: &str,
+    object_type: ObjectType,
+    derivation_method: DerivationMethod,
+    derivation_parameters: DerivationParameters,
+    cryptographic_algorithm: Option<CryptographicAlgorithm>,
+    cryptographic_length: usize,
+    tags: &[String],
+) -> Result<DeriveKey, KmipError> {
+    let mut attributes = Attributes {
+        object_type: Some(object_type),
+        cryptographic_algorithm,
+        cryptographic_length: Some(cryptographic_length as i32),
+        ..Attributes::default()
+    };
+    attributes.set_tags(tags)?;
+    Ok(DeriveKey {
+        object_type,
+        object_unique_identifier: UniqueIdentifier::TextString(
+            base_key_unique_identifier.to_owned(),
+        ),
+        derivation_method,
+        derivation_parameters,
+        attributes,
+    })
+}
+
 /// Build a `Import` request for a generic Object
 #[must_use]
 pub fn build_import_object_request(
@@ -0,0 +1,301 @@
+//! Derivation of key material from a base key, as requested by the KMIP
+//! `DeriveKey` operation.
+//!
+//! Supported derivation methods are:
+//! - `PBKDF2` (RFC 8018) with an HMAC PRF, using the base key as the password
+//! - `HKDF` (RFC 5869), using the derivation data as the info
+//! - `NIST800_108C`: NIST SP 800-108 KDF in counter mode with an HMAC PRF,
+//!   using the derivation data as the fixed input data
+//!
+//! The hashing algorithm of the PRF is that of the `CryptographicParameters`
+//! of the derivation parameters, SHA-256 by default.
+use openssl::{
+    md::MdRef,
+    md_ctx::MdCtx,
+    pkcs5::pbkdf2_hmac,
+    pkey::{Id, PKey},
+    pkey_ctx::PkeyCtx,
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    error::KmipError,
+    kmip::{
+        kmip_operations::{DerivationMethod, DerivationParameters, ErrorReason},
+        kmip_types::HashingAlgorithm,
+    },
+    kmip_bail,
+};
+
+/// Minimum salt size in bytes for PBKDF2 in FIPS mode.
+#[cfg(feature = "fips")]
+const FIPS_MIN_SALT_SIZE: usize = 16;
+
+/// Minimum derived key length in bits in FIPS mode.
+#[cfg(feature = "fips")]
+const FIPS_MIN_KLEN: usize = 112;
+
+/// Minimum number of PBKDF2 iterations in FIPS mode.
+#[cfg(feature = "fips")]
+const FIPS_MIN_ITER: usize = 1000;
+
+/// Derive `length` bytes of key material from `base_key`
+/// using the given derivation method and parameters.
+pub fn derive_key_material(
+    derivation_method: DerivationMethod,
+    base_key: &[u8],
+    derivation_parameters: &DerivationParameters,
+    length: usize,
+) -> Result<Zeroizing<Vec<u8>>, KmipError> {
+    if length == 0 {
+        kmip_bail!(KmipError::InvalidSize(
+            "DeriveKey: the length of the derived key must be strictly positive".to_owned()
+        ))
+    }
+    #[cfg(feature = "fips")]
+    if length * 8 < FIPS_MIN_KLEN {
+        kmip_bail!(KmipError::InvalidSize(format!(
+            "DeriveKey: the length of the derived key must be at least {FIPS_MIN_KLEN} bits in \
+             FIPS mode"
+        )))
+    }
+
+    let hashing_algorithm = derivation_parameters
+        .cryptographic_parameters
+        .as_ref()
+        .and_then(|cp| cp.hashing_algorithm)
+        .unwrap_or(HashingAlgorithm::SHA256);
+    let derivation_data = derivation_parameters
+        .derivation_data
+        .as_deref()
+        .map_or(&[][..], Vec::as_slice);
+
+    match derivation_method {
+        DerivationMethod::PBKDF2 => pbkdf2(
+            base_key,
+            derivation_parameters.salt.as_deref(),
+            derivation_parameters.iteration_count,
+            hashing_algorithm,
+            length,
+        ),
+        DerivationMethod::HKDF => hkdf(
+            base_key,
+            derivation_parameters.salt.as_deref(),
+            derivation_data,
+            hashing_algorithm,
+            length,
+        ),
+        DerivationMethod::NIST800_108C => {
+            nist800_108_counter(base_key, derivation_data, hashing_algorithm, length)
+        }
+        other => kmip_bail!(KmipError::NotSupported(format!(
+            "DeriveKey: the derivation method {other} is not supported"
+        ))),
+    }
+}
+
+/// PBKDF2 (RFC 8018) with HMAC as the PRF
+fn pbkdf2(
+    password: &[u8],
+    salt: Option<&[u8]>,
+    iteration_count: Option<i32>,
+    hashing_algorithm: HashingAlgorithm,
+    length: usize,
+) -> Result<Zeroizing<Vec<u8>>, KmipError> {
+    let salt = salt.ok_or_else(|| {
+        KmipError::InvalidKmipValue(
+            ErrorReason::Missing_Data,
+            "PBKDF2: the salt is mandatory".to_owned(),
+        )
+    })?;
+    let iteration_count = match iteration_count {
+        Some(i) if i > 0 => i as usize,
+        _ => kmip_bail!(KmipError::InvalidKmipValue(
+            ErrorReason::Missing_Data,
+            "PBKDF2: a strictly positive iteration count is mandatory".to_owned(),
+        )),
+    };
+    #[cfg(feature = "fips")]
+    {
+        if salt.len() < FIPS_MIN_SALT_SIZE {
+            kmip_bail!(KmipError::InvalidSize(format!(
+                "PBKDF2: the salt must be at least {FIPS_MIN_SALT_SIZE} bytes long in FIPS mode"
+            )))
+        }
+        if iteration_count < FIPS_MIN_ITER {
+            kmip_bail!(KmipError::InvalidKmipValue(
+                ErrorReason::Invalid_Field,
+                format!(
+                    "PBKDF2: the iteration count must be at least {FIPS_MIN_ITER} in FIPS mode"
+                )
+            ))
+        }
+    }
+    let mut output = Zeroizing::new(vec![0_u8; length]);
+    pbkdf2_hmac(
+        password,
+        salt,
+        iteration_count,
+        hashing_algorithm.try_into()?,
+        &mut output,
+    )?;
+    Ok(output)
+}
+
+/// HKDF (RFC 5869): extract then expand
+fn hkdf(
+    key: &[u8],
+    salt: Option<&[u8]>,
+    info: &[u8],
+    hashing_algorithm: HashingAlgorithm,
+    length: usize,
+) -> Result<Zeroizing<Vec<u8>>, KmipError> {
+    let mut ctx = PkeyCtx::new_id(Id::HKDF)?;
+    ctx.derive_init()?;
+    ctx.set_hkdf_md(hashing_algorithm.try_into()?)?;
+    ctx.set_hkdf_key(key)?;
+    if let Some(salt) = salt {
+        ctx.set_hkdf_salt(salt)?;
+    }
+    if !info.is_empty() {
+        ctx.add_hkdf_info(info)?;
+    }
+    let mut output = Zeroizing::new(vec![0_u8; length]);
+    ctx.derive(Some(&mut output))?;
+    Ok(output)
+}
+
+/// NIST SP 800-108 KDF in counter mode, with HMAC as the PRF
+/// and a 32-bit big endian counter placed before the fixed input data
+fn nist800_108_counter(
+    key: &[u8],
+    fixed_input: &[u8],
+    hashing_algorithm: HashingAlgorithm,
+    length: usize,
+) -> Result<Zeroizing<Vec<u8>>, KmipError> {
+    if key.is_empty() {
+        kmip_bail!(KmipError::InvalidSize(
+            "NIST800-108: the base key must not be empty".to_owned()
+        ))
+    }
+    let md = <&MdRef>::try_from(hashing_algorithm)?;
+    let pkey = PKey::hmac(key)?;
+    let mut output = Zeroizing::new(Vec::with_capacity(length + md.size()));
+    let mut counter: u32 = 1;
+    while output.len() < length {
+        let mut ctx = MdCtx::new()?;
+        ctx.digest_sign_init(Some(md), &pkey)?;
+        ctx.digest_sign_update(&counter.to_be_bytes())?;
+        ctx.digest_sign_update(fixed_input)?;
+        let mut block = Zeroizing::new(vec![]);
+        ctx.digest_sign_final_to_vec(&mut block)?;
+        output.extend_from_slice(&block);
+        counter = counter.checked_add(1).ok_or_else(|| {
+            KmipError::InvalidSize("NIST800-108: the derived key is too long".to_owned())
+        })?;
+    }
+    output.truncate(length);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "fips")]
+    use openssl::provider::Provider;
+
+    use crate::{
+        crypto::key_derivation::derive_key_material,
+        kmip::{
+            kmip_operations::{DerivationMethod, DerivationParameters},
+            kmip_types::{CryptographicParameters, HashingAlgorithm},
+        },
+    };
+
+    #[test]
+    fn test_hkdf_rfc5869() {
+        #[cfg(feature = "fips")]
+        // Load FIPS provider module from OpenSSL.
+        Provider::load(None, "fips").unwrap();
+
+        // RFC 5869 test case 1
+        let ikm = hex::decode("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b").unwrap();
+        let parameters = DerivationParameters {
+            salt: Some(hex::decode("000102030405060708090a0b0c").unwrap()),
+            derivation_data: Some(hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap().into()),
+            ..DerivationParameters::default()
+        };
+        let okm = derive_key_material(DerivationMethod::HKDF, &ikm, &parameters, 42).unwrap();
+        assert_eq!(
+            hex::encode(&*okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_rfc6070() {
+        #[cfg(feature = "fips")]
+        // Load FIPS provider module from OpenSSL.
+        Provider::load(None, "fips").unwrap();
+
+        // RFC 6070 test case 2 (PBKDF2-HMAC-SHA1, 2 iterations)
+        let parameters = DerivationParameters {
+            cryptographic_parameters: Some(CryptographicParameters {
+                hashing_algorithm: Some(HashingAlgorithm::SHA1),
+                ..CryptographicParameters::default()
+            }),
+            salt: Some(b"salt".to_vec()),
+            iteration_count: Some(2),
+            ..DerivationParameters::default()
+        };
+        let result = derive_key_material(DerivationMethod::PBKDF2, b"password", &parameters, 20);
+        #[cfg(not(feature = "fips"))]
+        assert_eq!(
+            hex::encode(&*result.unwrap()),
+            "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957"
+        );
+        // the salt is too short and the iteration count too low in FIPS mode
+        #[cfg(feature = "fips")]
+        assert!(result.is_err());
+
+        // the salt is mandatory
+        let parameters = DerivationParameters {
+            iteration_count: Some(2),
+            ..DerivationParameters::default()
+        };
+        assert!(
+            derive_key_material(DerivationMethod::PBKDF2, b"password", &parameters, 20).is_err()
+        );
+    }
+
+    #[test]
+    fn test_nist800_108_counter() {
+        #[cfg(feature = "fips")]
+        // Load FIPS provider module from OpenSSL.
+        Provider::load(None, "fips").unwrap();
+
+        let key = [0x42_u8; 32];
+        let parameters = DerivationParameters {
+            derivation_data: Some(b"label\x00context".to_vec().into()),
+            ..DerivationParameters::default()
+        };
+        let okm_32 =
+            derive_key_material(DerivationMethod::NIST800_108C, &key, &parameters, 32).unwrap();
+        let okm_80 =
+            derive_key_material(DerivationMethod::NIST800_108C, &key, &parameters, 80).unwrap();
+        assert_eq!(okm_80.len(), 80);
+        // the first block is the same whatever the requested length
+        assert_eq!(okm_32[..], okm_80[..32]);
+
+        // other fixed input data yield other keys
+        let parameters = DerivationParameters {
+            derivation_data: Some(b"other\x00context".to_vec().into()),
+            ..DerivationParameters::default()
+        };
+        let other =
+            derive_key_material(DerivationMethod::NIST800_108C, &key, &parameters, 32).unwrap();
+        assert_ne!(okm_32, other);
+
+        // unsupported method
+        assert!(derive_key_material(DerivationMethod::HASH, &key, &parameters, 32).is_err());
+    }
+}
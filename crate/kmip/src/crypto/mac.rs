@@ -0,0 +1,236 @@
+//! Message Authentication Codes (MAC) generation and verification.
+//!
+//! The MAC algorithm is derived from the `CryptographicAlgorithm` of the
+//! `CryptographicParameters` or, when not specified, from that of the key:
+//! - `HMACSHA*`, `HMACSHA3*` (and `HMACMD5` outside FIPS mode) compute an HMAC
+//!   with the corresponding hash function
+//! - `AES` computes an AES-CMAC (NIST SP 800-38B) with a 128, 192 or 256 bits key
+//!
+//! Data can be MACed in one shot using [`mac`] and [`mac_verify`],
+//! or by parts using a [`MacContext`].
+use openssl::{
+    md::{Md, MdRef},
+    md_ctx::MdCtx,
+    memcmp,
+    pkey::{PKey, Private},
+    symm::Cipher,
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    error::KmipError,
+    kmip::{
+        kmip_operations::ErrorReason,
+        kmip_types::{CryptographicAlgorithm, CryptographicParameters},
+    },
+    kmip_bail,
+};
+
+/// The MAC algorithm resolved from the cryptographic parameters and the key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacAlgorithm {
+    /// HMAC with the given hashing algorithm, identified by its
+    /// KMIP `CryptographicAlgorithm`
+    Hmac(CryptographicAlgorithm),
+    /// AES-CMAC
+    Cmac,
+}
+
+impl MacAlgorithm {
+    /// Resolve the MAC algorithm to use with a key of algorithm `key_algorithm`.
+    ///
+    /// The `CryptographicAlgorithm` of the `CryptographicParameters`, when provided,
+    /// takes precedence over the algorithm of the key.
+    pub fn resolve(
+        key_algorithm: Option<CryptographicAlgorithm>,
+        cryptographic_parameters: Option<&CryptographicParameters>,
+    ) -> Result<Self, KmipError> {
+        let algorithm = cryptographic_parameters
+            .and_then(|cp| cp.cryptographic_algorithm)
+            .or(key_algorithm)
+            .ok_or_else(|| {
+                KmipError::InvalidKmipValue(
+                    ErrorReason::Invalid_Message,
+                    "MAC: the cryptographic algorithm must be specified".to_owned(),
+                )
+            })?;
+        match algorithm {
+            CryptographicAlgorithm::AES => Ok(Self::Cmac),
+            CryptographicAlgorithm::HMACSHA1
+            | CryptographicAlgorithm::HMACSHA224
+            | CryptographicAlgorithm::HMACSHA256
+            | CryptographicAlgorithm::HMACSHA384
+            | CryptographicAlgorithm::HMACSHA512
+            | CryptographicAlgorithm::HMACSHA3224
+            | CryptographicAlgorithm::HMACSHA3256
+            | CryptographicAlgorithm::HMACSHA3384
+            | CryptographicAlgorithm::HMACSHA3512 => Ok(Self::Hmac(algorithm)),
+            #[cfg(not(feature = "fips"))]
+            CryptographicAlgorithm::HMACMD5 => Ok(Self::Hmac(algorithm)),
+            other => kmip_bail!(KmipError::NotSupported(format!(
+                "MAC: the cryptographic algorithm {other} is not supported"
+            ))),
+        }
+    }
+
+    /// The message digest of an HMAC algorithm
+    fn message_digest(algorithm: CryptographicAlgorithm) -> Result<&'static MdRef, KmipError> {
+        Ok(match algorithm {
+            CryptographicAlgorithm::HMACSHA1 => Md::sha1(),
+            CryptographicAlgorithm::HMACSHA224 => Md::sha224(),
+            CryptographicAlgorithm::HMACSHA256 => Md::sha256(),
+            CryptographicAlgorithm::HMACSHA384 => Md::sha384(),
+            CryptographicAlgorithm::HMACSHA512 => Md::sha512(),
+            CryptographicAlgorithm::HMACSHA3224 => Md::sha3_224(),
+            CryptographicAlgorithm::HMACSHA3256 => Md::sha3_256(),
+            CryptographicAlgorithm::HMACSHA3384 => Md::sha3_384(),
+            CryptographicAlgorithm::HMACSHA3512 => Md::sha3_512(),
+            #[cfg(not(feature = "fips"))]
+            CryptographicAlgorithm::HMACMD5 => Md::md5(),
+            other => kmip_bail!(KmipError::NotSupported(format!(
+                "HMAC: the cryptographic algorithm {other} is not supported"
+            ))),
+        })
+    }
+}
+
+/// A MAC computation in progress, used to MAC data by parts
+pub struct MacContext {
+    ctx: MdCtx,
+}
+
+impl MacContext {
+    /// Initialize a MAC computation using the given algorithm and key bytes
+    pub fn new(algorithm: MacAlgorithm, key: &[u8]) -> Result<Self, KmipError> {
+        let mut ctx = MdCtx::new()?;
+        match algorithm {
+            MacAlgorithm::Hmac(hmac_algorithm) => {
+                if key.is_empty() {
+                    kmip_bail!(KmipError::InvalidSize(
+                        "HMAC: the key must not be empty".to_owned()
+                    ))
+                }
+                let pkey: PKey<Private> = PKey::hmac(key)?;
+                ctx.digest_sign_init(Some(MacAlgorithm::message_digest(hmac_algorithm)?), &pkey)?;
+            }
+            MacAlgorithm::Cmac => {
+                let cipher = match key.len() {
+                    16 => Cipher::aes_128_cbc(),
+                    24 => Cipher::aes_192_cbc(),
+                    32 => Cipher::aes_256_cbc(),
+                    other => kmip_bail!(KmipError::InvalidSize(format!(
+                        "CMAC: invalid AES key length: {other} bytes"
+                    ))),
+                };
+                let pkey: PKey<Private> = PKey::cmac(&cipher, key)?;
+                ctx.digest_sign_init(None, &pkey)?;
+            }
+        }
+        Ok(Self { ctx })
+    }
+
+    /// Add data to the MAC computation
+    pub fn update(&mut self, data: &[u8]) -> Result<(), KmipError> {
+        self.ctx.digest_sign_update(data)?;
+        Ok(())
+    }
+
+    /// Terminate the MAC computation and return the MAC
+    pub fn finalize(mut self) -> Result<Vec<u8>, KmipError> {
+        let mut mac = vec![];
+        self.ctx.digest_sign_final_to_vec(&mut mac)?;
+        Ok(mac)
+    }
+
+    /// Terminate the MAC computation and compare the result
+    /// with the given MAC, in constant time
+    pub fn verify(self, mac_data: &[u8]) -> Result<bool, KmipError> {
+        let mac = Zeroizing::new(self.finalize()?);
+        Ok(mac.len() == mac_data.len() && memcmp::eq(&mac, mac_data))
+    }
+}
+
+/// Compute the MAC of `data` using the given algorithm and key bytes
+pub fn mac(algorithm: MacAlgorithm, key: &[u8], data: &[u8]) -> Result<Vec<u8>, KmipError> {
+    let mut ctx = MacContext::new(algorithm, key)?;
+    ctx.update(data)?;
+    ctx.finalize()
+}
+
+/// Verify that `mac_data` is the MAC of `data` using the given algorithm and key bytes
+pub fn mac_verify(
+    algorithm: MacAlgorithm,
+    key: &[u8],
+    data: &[u8],
+    mac_data: &[u8],
+) -> Result<bool, KmipError> {
+    let mut ctx = MacContext::new(algorithm, key)?;
+    ctx.update(data)?;
+    ctx.verify(mac_data)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "fips")]
+    use openssl::provider::Provider;
+
+    use crate::{
+        crypto::mac::{mac, mac_verify, MacAlgorithm, MacContext},
+        kmip::kmip_types::CryptographicAlgorithm,
+    };
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        #[cfg(feature = "fips")]
+        // Load FIPS provider module from OpenSSL.
+        Provider::load(None, "fips").unwrap();
+
+        // RFC 4231 test case 2
+        let algorithm = MacAlgorithm::Hmac(CryptographicAlgorithm::HMACSHA256);
+        let expected =
+            hex::decode("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+                .unwrap();
+        let data = b"what do ya want for nothing?";
+        assert_eq!(mac(algorithm, b"Jefe", data).unwrap(), expected);
+        assert!(mac_verify(algorithm, b"Jefe", data, &expected).unwrap());
+        assert!(!mac_verify(algorithm, b"Jefe", b"something else", &expected).unwrap());
+        assert!(!mac_verify(algorithm, b"Jefe", data, &expected[..16]).unwrap());
+
+        // MACing by parts yields the same result
+        let mut ctx = MacContext::new(algorithm, b"Jefe").unwrap();
+        ctx.update(&data[..10]).unwrap();
+        ctx.update(&data[10..]).unwrap();
+        assert_eq!(ctx.finalize().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_aes_cmac_rfc4493() {
+        #[cfg(feature = "fips")]
+        // Load FIPS provider module from OpenSSL.
+        Provider::load(None, "fips").unwrap();
+
+        // RFC 4493 example 2
+        let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+        let data = hex::decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+        let expected = hex::decode("070a16b46b4d4144f79bdd9dd04a287c").unwrap();
+        assert_eq!(mac(MacAlgorithm::Cmac, &key, &data).unwrap(), expected);
+        assert!(mac_verify(MacAlgorithm::Cmac, &key, &data, &expected).unwrap());
+
+        // invalid AES key length
+        assert!(mac(MacAlgorithm::Cmac, &key[..10], &data).is_err());
+    }
+
+    #[test]
+    fn test_resolve_mac_algorithm() {
+        assert_eq!(
+            MacAlgorithm::resolve(Some(CryptographicAlgorithm::AES), None).unwrap(),
+            MacAlgorithm::Cmac
+        );
+        assert_eq!(
+            MacAlgorithm::resolve(Some(CryptographicAlgorithm::HMACSHA512), None).unwrap(),
+            MacAlgorithm::Hmac(CryptographicAlgorithm::HMACSHA512)
+        );
+        assert!(MacAlgorithm::resolve(Some(CryptographicAlgorithm::RSA), None).is_err());
+        assert!(MacAlgorithm::resolve(None, None).is_err());
+    }
+}
@@ -12,10 +12,16 @@ pub mod cover_crypt;
 pub mod dh_shared_keys;
 pub mod elliptic_curves;
 pub mod generic;
+#[cfg(feature = "openssl")]
+pub mod key_derivation;
+#[cfg(feature = "openssl")]
+pub mod mac;
 #[cfg(any(feature = "openssl", feature = "fips"))]
 pub mod password_derivation;
 pub mod rsa;
 pub mod secret;
+#[cfg(feature = "openssl")]
+pub mod signature;
 pub mod symmetric;
 
 #[cfg(feature = "openssl")]
@@ -0,0 +1,236 @@
+//! Digital signature generation and verification using OpenSSL keys.
+//!
+//! The signature scheme is derived from the KMIP `CryptographicParameters`:
+//! either the `DigitalSignatureAlgorithm` is specified, or the combination of
+//! the `PaddingMethod` (RSA only) and the `HashingAlgorithm`.
+//!
+//! Supported schemes are:
+//! - RSA with PSS padding (default for RSA keys) or PKCS#1 v1.5 padding
+//! - ECDSA on the NIST curves
+//! - `EdDSA` on curves Ed25519 and Ed448 (not available in FIPS mode)
+//!
+//! When the data has already been hashed by the caller, the digest can be
+//! signed or verified directly, except for `EdDSA` which does not support
+//! pre-hashed data.
+use openssl::{
+    md::MdRef,
+    md_ctx::MdCtx,
+    pkey::{Id, PKey, PKeyRef, Private, Public},
+    pkey_ctx::{PkeyCtx, PkeyCtxRef},
+    rsa::Padding,
+    sign::RsaPssSaltlen,
+};
+
+use crate::{
+    error::KmipError,
+    kmip::kmip_types::{
+        CryptographicParameters, DigitalSignatureAlgorithm, HashingAlgorithm, PaddingMethod,
+    },
+    kmip_bail,
+};
+
+/// The signature scheme resolved from the cryptographic parameters and the key type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SignatureScheme {
+    /// `None` for `EdDSA` which hashes the data internally
+    hashing_algorithm: Option<HashingAlgorithm>,
+    /// Only relevant for RSA keys
+    padding_method: Option<PaddingMethod>,
+}
+
+impl SignatureScheme {
+    fn message_digest(self) -> Result<Option<&'static MdRef>, KmipError> {
+        self.hashing_algorithm.map(<&MdRef>::try_from).transpose()
+    }
+}
+
+/// Resolve the signature scheme to use with a key of type `key_id`.
+///
+/// The `DigitalSignatureAlgorithm`, when provided, takes precedence over the
+/// `PaddingMethod` and `HashingAlgorithm` fields.
+/// RSA keys default to PSS with SHA-256, EC keys to ECDSA with SHA-256.
+fn signature_scheme(
+    key_id: Id,
+    cryptographic_parameters: Option<&CryptographicParameters>,
+) -> Result<SignatureScheme, KmipError> {
+    let hashing_algorithm = cryptographic_parameters.and_then(|cp| cp.hashing_algorithm);
+    let padding_method = cryptographic_parameters.and_then(|cp| cp.padding_method);
+    let digital_signature_algorithm =
+        cryptographic_parameters.and_then(|cp| cp.digital_signature_algorithm);
+
+    match key_id {
+        Id::RSA => {
+            let (padding_method, hashing_algorithm) = match digital_signature_algorithm {
+                Some(dsa) => rsa_scheme_from_digital_signature_algorithm(dsa, hashing_algorithm)?,
+                None => (
+                    padding_method.unwrap_or(PaddingMethod::PSS),
+                    hashing_algorithm.unwrap_or(HashingAlgorithm::SHA256),
+                ),
+            };
+            if padding_method != PaddingMethod::PSS && padding_method != PaddingMethod::PKCS1v15 {
+                kmip_bail!("RSA signature: padding method not supported: {padding_method:?}")
+            }
+            Ok(SignatureScheme {
+                hashing_algorithm: Some(hashing_algorithm),
+                padding_method: Some(padding_method),
+            })
+        }
+        Id::EC => {
+            let hashing_algorithm = match digital_signature_algorithm {
+                Some(dsa) => ecdsa_hashing_algorithm(dsa)?,
+                None => hashing_algorithm.unwrap_or(HashingAlgorithm::SHA256),
+            };
+            Ok(SignatureScheme {
+                hashing_algorithm: Some(hashing_algorithm),
+                padding_method: None,
+            })
+        }
+        #[cfg(not(feature = "fips"))]
+        Id::ED25519 | Id::ED448 => Ok(SignatureScheme {
+            hashing_algorithm: None,
+            padding_method: None,
+        }),
+        other => kmip_bail!("signature: key type not supported: {other:?}"),
+    }
+}
+
+fn rsa_scheme_from_digital_signature_algorithm(
+    digital_signature_algorithm: DigitalSignatureAlgorithm,
+    hashing_algorithm: Option<HashingAlgorithm>,
+) -> Result<(PaddingMethod, HashingAlgorithm), KmipError> {
+    let pkcs1v15 = |h| Ok((PaddingMethod::PKCS1v15, h));
+    match digital_signature_algorithm {
+        DigitalSignatureAlgorithm::SHA1WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA1),
+        DigitalSignatureAlgorithm::SHA224WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA224),
+        DigitalSignatureAlgorithm::SHA256WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA256),
+        DigitalSignatureAlgorithm::SHA384WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA384),
+        DigitalSignatureAlgorithm::SHA512WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA512),
+        DigitalSignatureAlgorithm::SHA3256WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA3256),
+        DigitalSignatureAlgorithm::SHA3384WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA3384),
+        DigitalSignatureAlgorithm::SHA3512WithRSAEncryption => pkcs1v15(HashingAlgorithm::SHA3512),
+        DigitalSignatureAlgorithm::RSASSAPSS => Ok((
+            PaddingMethod::PSS,
+            hashing_algorithm.unwrap_or(HashingAlgorithm::SHA256),
+        )),
+        other => kmip_bail!("RSA signature: digital signature algorithm not supported: {other:?}"),
+    }
+}
+
+fn ecdsa_hashing_algorithm(
+    digital_signature_algorithm: DigitalSignatureAlgorithm,
+) -> Result<HashingAlgorithm, KmipError> {
+    Ok(match digital_signature_algorithm {
+        DigitalSignatureAlgorithm::ECDSAWithSHA1 => HashingAlgorithm::SHA1,
+        DigitalSignatureAlgorithm::ECDSAWithSHA224 => HashingAlgorithm::SHA224,
+        DigitalSignatureAlgorithm::ECDSAWithSHA256 => HashingAlgorithm::SHA256,
+        DigitalSignatureAlgorithm::ECDSAWithSHA384 => HashingAlgorithm::SHA384,
+        DigitalSignatureAlgorithm::ECDSAWithSHA512 => HashingAlgorithm::SHA512,
+        other => {
+            kmip_bail!("ECDSA signature: digital signature algorithm not supported: {other:?}")
+        }
+    })
+}
+
+/// Sign `data` or, if `data` is `None`, the already hashed `digested_data`
+/// with the private key.
+///
+/// Arguments:
+/// - `private_key`: the RSA, EC or Edwards private key
+/// - `cryptographic_parameters`: the requested signature scheme, if any
+/// - `data`: the data to sign
+/// - `digested_data`: the digest of the data to sign, used when `data` is `None`
+pub fn sign(
+    private_key: &PKey<Private>,
+    cryptographic_parameters: Option<&CryptographicParameters>,
+    data: Option<&[u8]>,
+    digested_data: Option<&[u8]>,
+) -> Result<Vec<u8>, KmipError> {
+    let scheme = signature_scheme(private_key.id(), cryptographic_parameters)?;
+    let mut signature = vec![];
+    match (data, digested_data) {
+        (Some(data), _) => {
+            let mut ctx = MdCtx::new()?;
+            let pkey_ctx = ctx.digest_sign_init(scheme.message_digest()?, private_key)?;
+            set_rsa_padding(pkey_ctx, scheme)?;
+            ctx.digest_sign_to_vec(data, &mut signature)?;
+        }
+        (None, Some(digest)) => {
+            let mut ctx = digest_context(private_key, scheme)?;
+            ctx.sign_init()?;
+            configure_digest_context(&mut ctx, scheme)?;
+            ctx.sign_to_vec(digest, &mut signature)?;
+        }
+        (None, None) => kmip_bail!("signature: the data or the digested data must be provided"),
+    }
+    Ok(signature)
+}
+
+/// Verify the `signature` of `data` or, if `data` is `None`, of the already
+/// hashed `digested_data` with the public key.
+///
+/// Returns `true` if the signature is valid, `false` if it is not,
+/// and an error if it could not be verified, e.g. with unsupported parameters.
+///
+/// Arguments:
+/// - `public_key`: the RSA, EC or Edwards public key
+/// - `cryptographic_parameters`: the requested signature scheme, if any
+/// - `data`: the data that was signed
+/// - `digested_data`: the digest of the data that was signed, used when `data` is `None`
+/// - `signature`: the signature to verify
+pub fn verify(
+    public_key: &PKey<Public>,
+    cryptographic_parameters: Option<&CryptographicParameters>,
+    data: Option<&[u8]>,
+    digested_data: Option<&[u8]>,
+    signature: &[u8],
+) -> Result<bool, KmipError> {
+    let scheme = signature_scheme(public_key.id(), cryptographic_parameters)?;
+    match (data, digested_data) {
+        (Some(data), _) => {
+            let mut ctx = MdCtx::new()?;
+            let pkey_ctx = ctx.digest_verify_init(scheme.message_digest()?, public_key)?;
+            set_rsa_padding(pkey_ctx, scheme)?;
+            Ok(ctx.digest_verify(data, signature)?)
+        }
+        (None, Some(digest)) => {
+            let mut ctx = digest_context(public_key, scheme)?;
+            ctx.verify_init()?;
+            configure_digest_context(&mut ctx, scheme)?;
+            Ok(ctx.verify(digest, signature)?)
+        }
+        (None, None) => kmip_bail!("signature: the data or the digested data must be provided"),
+    }
+}
+
+fn digest_context<T>(key: &PKeyRef<T>, scheme: SignatureScheme) -> Result<PkeyCtx<T>, KmipError> {
+    if scheme.hashing_algorithm.is_none() {
+        kmip_bail!("signature: EdDSA does not support signing pre-hashed data")
+    }
+    Ok(PkeyCtx::new(key)?)
+}
+
+fn configure_digest_context<T>(
+    ctx: &mut PkeyCtxRef<T>,
+    scheme: SignatureScheme,
+) -> Result<(), KmipError> {
+    if let Some(md) = scheme.message_digest()? {
+        ctx.set_signature_md(md)?;
+    }
+    set_rsa_padding(ctx, scheme)
+}
+
+/// Set the padding of an RSA signature, shared by the signature and the verification
+fn set_rsa_padding<T>(ctx: &mut PkeyCtxRef<T>, scheme: SignatureScheme) -> Result<(), KmipError> {
+    match scheme.padding_method {
+        Some(PaddingMethod::PSS) => {
+            ctx.set_rsa_padding(Padding::PKCS1_PSS)?;
+            ctx.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+            if let Some(md) = scheme.message_digest()? {
+                ctx.set_rsa_mgf1_md(md)?;
+            }
+        }
+        Some(_) => ctx.set_rsa_padding(Padding::PKCS1)?,
+        None => {}
+    }
+    Ok(())
+}
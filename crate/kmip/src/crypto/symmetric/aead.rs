@@ -85,8 +85,8 @@ impl AeadCipher {
         match algorithm {
             CryptographicAlgorithm::AES => {
                 if block_cipher_mode.is_some()
-                    && (Some(BlockCipherMode::GCM) != block_cipher_mode
-                        || Some(BlockCipherMode::AEAD) != block_cipher_mode)
+                    && Some(BlockCipherMode::GCM) != block_cipher_mode
+                    && Some(BlockCipherMode::AEAD) != block_cipher_mode
                 {
                     kmip_bail!(KmipError::NotSupported(
                         "AES is only supported with GCM mode".to_owned()
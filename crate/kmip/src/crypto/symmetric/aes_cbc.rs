@@ -0,0 +1,105 @@
+//! AES in CBC mode with PKCS#5 padding.
+//!
+//! This is the `CKM_AES_CBC_PAD` mechanism of PKCS#11: the IV is a full
+//! 16 bytes block and the padding is applied with the block size of AES.
+
+use openssl::{
+    rand::rand_bytes,
+    symm::{decrypt, encrypt, Cipher},
+};
+use zeroize::Zeroizing;
+
+use crate::{error::KmipError, kmip_bail};
+
+/// AES block size in bytes, which is also the size of the CBC IV.
+pub const AES_CBC_IV_LENGTH: usize = 0x10;
+
+/// The AES CBC cipher for a key of `key_size` bytes
+fn aes_cbc_cipher(key_size: usize) -> Result<Cipher, KmipError> {
+    Ok(match key_size {
+        16 => Cipher::aes_128_cbc(),
+        24 => Cipher::aes_192_cbc(),
+        32 => Cipher::aes_256_cbc(),
+        _ => kmip_bail!(KmipError::NotSupported(
+            "AES CBC: the key must be 16, 24 or 32 bytes long".to_owned()
+        )),
+    })
+}
+
+fn check_iv(iv: &[u8]) -> Result<(), KmipError> {
+    if iv.len() != AES_CBC_IV_LENGTH {
+        kmip_bail!(KmipError::InvalidSize(format!(
+            "AES CBC: the IV must be {AES_CBC_IV_LENGTH} bytes long"
+        )))
+    }
+    Ok(())
+}
+
+/// Generate a random IV for AES CBC.
+pub fn random_iv() -> Result<Vec<u8>, KmipError> {
+    let mut iv = vec![0; AES_CBC_IV_LENGTH];
+    rand_bytes(&mut iv)?;
+    Ok(iv)
+}
+
+/// Encrypt the plaintext using AES CBC with PKCS#5 padding.
+pub fn aes_cbc_pad_encrypt(key: &[u8], iv: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KmipError> {
+    check_iv(iv)?;
+    Ok(encrypt(
+        aes_cbc_cipher(key.len())?,
+        key,
+        Some(iv),
+        plaintext,
+    )?)
+}
+
+/// Decrypt the ciphertext using AES CBC and remove the PKCS#5 padding.
+pub fn aes_cbc_pad_decrypt(
+    key: &[u8],
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, KmipError> {
+    check_iv(iv)?;
+    Ok(Zeroizing::from(decrypt(
+        aes_cbc_cipher(key.len())?,
+        key,
+        Some(iv),
+        ciphertext,
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "fips")]
+    use openssl::provider::Provider;
+    use openssl::rand::rand_bytes;
+
+    use crate::crypto::symmetric::aes_cbc::{aes_cbc_pad_decrypt, aes_cbc_pad_encrypt, random_iv};
+
+    #[test]
+    fn test_encrypt_decrypt_aes_cbc_pad() {
+        #[cfg(feature = "fips")]
+        // Load FIPS provider module from OpenSSL.
+        Provider::load(None, "fips").unwrap();
+
+        let mut message = vec![0_u8; 42];
+        rand_bytes(&mut message).unwrap();
+
+        for key_size in [16, 24, 32] {
+            let mut key = vec![0_u8; key_size];
+            rand_bytes(&mut key).unwrap();
+            let iv = random_iv().unwrap();
+
+            let ciphertext = aes_cbc_pad_encrypt(&key, &iv, &message).unwrap();
+            // the padding always adds at least one byte
+            assert_eq!(ciphertext.len(), 48);
+
+            let decrypted_data = aes_cbc_pad_decrypt(&key, &iv, &ciphertext).unwrap();
+            // `to_vec()` conversion because of Zeroizing<>.
+            assert_eq!(decrypted_data.to_vec(), message);
+        }
+
+        // a wrong IV size is rejected
+        assert!(aes_cbc_pad_encrypt(&[0_u8; 32], &[0_u8; 12], &message).is_err());
+    }
+}
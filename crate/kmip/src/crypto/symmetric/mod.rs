@@ -1,5 +1,8 @@
 mod symmetric_key;
-pub use symmetric_key::{create_symmetric_key_kmip_object, symmetric_key_create_request};
+pub use symmetric_key::{
+    create_symmetric_key_kmip_object, default_symmetric_key_usage_mask,
+    symmetric_key_create_request, symmetric_key_rekey_request,
+};
 
 #[cfg(feature = "openssl")]
 mod aes_256_gcm;
@@ -26,6 +29,9 @@ pub const AES_KWP_KEY_LENGTH: usize = 0x20;
 #[cfg(feature = "openssl")]
 pub mod aead;
 
+#[cfg(feature = "openssl")]
+pub mod aes_cbc;
+
 #[cfg(feature = "openssl")]
 pub mod rfc5649;
 
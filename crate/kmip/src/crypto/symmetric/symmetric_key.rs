@@ -5,11 +5,45 @@ use crate::{
     kmip::{
         kmip_data_structures::{KeyBlock, KeyMaterial, KeyValue},
         kmip_objects::{Object, ObjectType},
-        kmip_operations::Create,
-        kmip_types::{Attributes, CryptographicAlgorithm, CryptographicUsageMask, KeyFormatType},
+        kmip_operations::{Create, ReKey},
+        kmip_types::{
+            Attributes, CryptographicAlgorithm, CryptographicUsageMask, KeyFormatType,
+            UniqueIdentifier,
+        },
     },
 };
 
+/// The default usage mask of a symmetric key of the given algorithm:
+/// HMAC keys can only be used to generate and verify MACs,
+/// other keys are used to encrypt, decrypt, wrap, unwrap and derive keys.
+#[must_use]
+pub fn default_symmetric_key_usage_mask(
+    cryptographic_algorithm: CryptographicAlgorithm,
+) -> CryptographicUsageMask {
+    match cryptographic_algorithm {
+        CryptographicAlgorithm::HMACSHA1
+        | CryptographicAlgorithm::HMACSHA224
+        | CryptographicAlgorithm::HMACSHA256
+        | CryptographicAlgorithm::HMACSHA384
+        | CryptographicAlgorithm::HMACSHA512
+        | CryptographicAlgorithm::HMACMD5
+        | CryptographicAlgorithm::HMACSHA3224
+        | CryptographicAlgorithm::HMACSHA3256
+        | CryptographicAlgorithm::HMACSHA3384
+        | CryptographicAlgorithm::HMACSHA3512 => {
+            CryptographicUsageMask::MACGenerate | CryptographicUsageMask::MACVerify
+        }
+        _ => {
+            CryptographicUsageMask::Encrypt
+                | CryptographicUsageMask::Decrypt
+                | CryptographicUsageMask::WrapKey
+                | CryptographicUsageMask::UnwrapKey
+                | CryptographicUsageMask::KeyAgreement
+                | CryptographicUsageMask::DeriveKey
+        }
+    }
+}
+
 /// Create a symmetric key for the given algorithm
 #[must_use]
 pub fn create_symmetric_key_kmip_object(
@@ -23,13 +57,7 @@ pub fn create_symmetric_key_kmip_object(
         object_type: Some(ObjectType::SymmetricKey),
         cryptographic_algorithm: Some(cryptographic_algorithm),
         cryptographic_length: Some(symmetric_key_len),
-        cryptographic_usage_mask: Some(
-            CryptographicUsageMask::Encrypt
-                | CryptographicUsageMask::Decrypt
-                | CryptographicUsageMask::WrapKey
-                | CryptographicUsageMask::UnwrapKey
-                | CryptographicUsageMask::KeyAgreement,
-        ),
+        cryptographic_usage_mask: Some(default_symmetric_key_usage_mask(cryptographic_algorithm)),
         key_format_type: Some(KeyFormatType::TransparentSymmetricKey),
         ..Attributes::default()
     };
@@ -66,13 +94,7 @@ pub fn symmetric_key_create_request<T: IntoIterator<Item = impl AsRef<str>>>(
         cryptographic_algorithm: Some(cryptographic_algorithm),
         cryptographic_length: Some(key_len_in_bits as i32),
         cryptographic_parameters: None,
-        cryptographic_usage_mask: Some(
-            CryptographicUsageMask::Encrypt
-                | CryptographicUsageMask::Decrypt
-                | CryptographicUsageMask::WrapKey
-                | CryptographicUsageMask::UnwrapKey
-                | CryptographicUsageMask::KeyAgreement,
-        ),
+        cryptographic_usage_mask: Some(default_symmetric_key_usage_mask(cryptographic_algorithm)),
         key_format_type: Some(KeyFormatType::TransparentSymmetricKey),
         object_type: Some(ObjectType::SymmetricKey),
         ..Attributes::default()
@@ -84,3 +106,16 @@ pub fn symmetric_key_create_request<T: IntoIterator<Item = impl AsRef<str>>>(
         protection_storage_masks: None,
     })
 }
+
+/// Build a `ReKey` request to replace the symmetric key identified by `unique_identifier`
+///
+/// The replacement key is activated `offset` seconds after its creation, if specified.
+#[must_use]
+pub fn symmetric_key_rekey_request(unique_identifier: &str, offset: Option<i32>) -> ReKey {
+    ReKey {
+        unique_identifier: Some(UniqueIdentifier::TextString(unique_identifier.to_owned())),
+        offset,
+        attributes: None,
+        protection_storage_masks: None,
+    }
+}
@@ -2,7 +2,10 @@ use std::collections::HashSet;
 
 use crate::{
     error::KmipError,
-    kmip::{extra::VENDOR_ID_COSMIAN, kmip_types::Attributes},
+    kmip::{
+        extra::VENDOR_ID_COSMIAN,
+        kmip_types::{Attribute, Attributes, VendorAttribute},
+    },
 };
 
 pub const VENDOR_ATTR_TAG: &str = "tag";
@@ -57,3 +60,31 @@ impl Attributes {
         tags
     }
 }
+
+impl Attribute {
+    /// Build the vendor attribute holding the given tags
+    pub fn tags<T: IntoIterator<Item = impl AsRef<str>>>(tags: T) -> Result<Self, KmipError> {
+        Ok(Self::VendorAttribute(VendorAttribute {
+            vendor_identification: VENDOR_ID_COSMIAN.to_owned(),
+            attribute_name: VENDOR_ATTR_TAG.to_owned(),
+            attribute_value: serde_json::to_vec::<HashSet<String>>(&HashSet::from_iter(
+                tags.into_iter().map(|t| t.as_ref().to_owned()),
+            ))?,
+        }))
+    }
+
+    /// Get the tags if this attribute is the tags vendor attribute
+    pub fn get_tags(&self) -> Result<Option<HashSet<String>>, KmipError> {
+        match self {
+            Self::VendorAttribute(va)
+                if va.vendor_identification == VENDOR_ID_COSMIAN
+                    && va.attribute_name == VENDOR_ATTR_TAG =>
+            {
+                Ok(Some(serde_json::from_slice::<HashSet<String>>(
+                    &va.attribute_value,
+                )?))
+            }
+            _ => Ok(None),
+        }
+    }
+}
@@ -293,12 +293,47 @@ impl<'de> Deserialize<'de> for MessageBatchItem {
                                 OperationEnumeration::GetAttributes => {
                                     Operation::GetAttributes(map.next_value()?)
                                 }
+                                OperationEnumeration::SetAttribute => {
+                                    Operation::SetAttribute(map.next_value()?)
+                                }
+                                OperationEnumeration::AddAttribute => {
+                                    Operation::AddAttribute(map.next_value()?)
+                                }
+                                OperationEnumeration::ModifyAttribute => {
+                                    Operation::ModifyAttribute(map.next_value()?)
+                                }
+                                OperationEnumeration::DeleteAttribute => {
+                                    Operation::DeleteAttribute(map.next_value()?)
+                                }
+                                OperationEnumeration::Activate => {
+                                    Operation::Activate(map.next_value()?)
+                                }
                                 OperationEnumeration::Revoke => {
                                     Operation::Revoke(map.next_value()?)
                                 }
                                 OperationEnumeration::Destroy => {
                                     Operation::Destroy(map.next_value()?)
                                 }
+                                OperationEnumeration::Archive => {
+                                    Operation::Archive(map.next_value()?)
+                                }
+                                OperationEnumeration::Recover => {
+                                    Operation::Recover(map.next_value()?)
+                                }
+                                OperationEnumeration::Query => Operation::Query(map.next_value()?),
+                                OperationEnumeration::DiscoverVersions => {
+                                    Operation::DiscoverVersions(map.next_value()?)
+                                }
+                                OperationEnumeration::RNGRetrieve => {
+                                    Operation::RNGRetrieve(map.next_value()?)
+                                }
+                                OperationEnumeration::RNGSeed => {
+                                    Operation::RNGSeed(map.next_value()?)
+                                }
+                                OperationEnumeration::Poll => Operation::Poll(map.next_value()?),
+                                OperationEnumeration::Cancel => {
+                                    Operation::Cancel(map.next_value()?)
+                                }
                                 OperationEnumeration::Decrypt => {
                                     Operation::Decrypt(map.next_value()?)
                                 }
@@ -308,6 +343,21 @@ impl<'de> Deserialize<'de> for MessageBatchItem {
                                 OperationEnumeration::Export => {
                                     Operation::Export(map.next_value()?)
                                 }
+                                OperationEnumeration::Sign => Operation::Sign(map.next_value()?),
+                                OperationEnumeration::SignatureVerify => {
+                                    Operation::SignatureVerify(map.next_value()?)
+                                }
+                                OperationEnumeration::Validate => {
+                                    Operation::Validate(map.next_value()?)
+                                }
+                                OperationEnumeration::MAC => Operation::MAC(map.next_value()?),
+                                OperationEnumeration::MACVerify => {
+                                    Operation::MACVerify(map.next_value()?)
+                                }
+                                OperationEnumeration::DeriveKey => {
+                                    Operation::DeriveKey(map.next_value()?)
+                                }
+                                OperationEnumeration::Rekey => Operation::ReKey(map.next_value()?),
                                 _ => return Err(de::Error::missing_field("valid enum operation")),
                             });
                         }
@@ -700,12 +750,51 @@ impl<'de> Deserialize<'de> for MessageResponseBatchItem {
                                 OperationEnumeration::GetAttributes => {
                                     Operation::GetAttributesResponse(map.next_value()?)
                                 }
+                                OperationEnumeration::SetAttribute => {
+                                    Operation::SetAttributeResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::AddAttribute => {
+                                    Operation::AddAttributeResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::ModifyAttribute => {
+                                    Operation::ModifyAttributeResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::DeleteAttribute => {
+                                    Operation::DeleteAttributeResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::Activate => {
+                                    Operation::ActivateResponse(map.next_value()?)
+                                }
                                 OperationEnumeration::Revoke => {
                                     Operation::RevokeResponse(map.next_value()?)
                                 }
                                 OperationEnumeration::Destroy => {
                                     Operation::DestroyResponse(map.next_value()?)
                                 }
+                                OperationEnumeration::Archive => {
+                                    Operation::ArchiveResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::Recover => {
+                                    Operation::RecoverResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::Query => {
+                                    Operation::QueryResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::DiscoverVersions => {
+                                    Operation::DiscoverVersionsResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::RNGRetrieve => {
+                                    Operation::RNGRetrieveResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::RNGSeed => {
+                                    Operation::RNGSeedResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::Poll => {
+                                    Operation::PollResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::Cancel => {
+                                    Operation::CancelResponse(map.next_value()?)
+                                }
                                 OperationEnumeration::Decrypt => {
                                     Operation::DecryptResponse(map.next_value()?)
                                 }
@@ -715,6 +804,27 @@ impl<'de> Deserialize<'de> for MessageResponseBatchItem {
                                 OperationEnumeration::Export => {
                                     Operation::ExportResponse(map.next_value()?)
                                 }
+                                OperationEnumeration::Sign => {
+                                    Operation::SignResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::SignatureVerify => {
+                                    Operation::SignatureVerifyResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::Validate => {
+                                    Operation::ValidateResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::MAC => {
+                                    Operation::MACResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::MACVerify => {
+                                    Operation::MACVerifyResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::DeriveKey => {
+                                    Operation::DeriveKeyResponse(map.next_value()?)
+                                }
+                                OperationEnumeration::Rekey => {
+                                    Operation::ReKeyResponse(map.next_value()?)
+                                }
                                 _ => {
                                     return Err(de::Error::missing_field(
                                         "valid enum operation (unsupported operation ?)",
@@ -5,23 +5,24 @@ use serde::{
     de::{self, MapAccess, Visitor},
     Deserialize, Serialize,
 };
-use strum::Display;
+use strum::{Display, EnumIter};
 use zeroize::Zeroizing;
 
 use super::{
     kmip_data_structures::KeyWrappingSpecification,
     kmip_objects::{Object, ObjectType},
     kmip_types::{
-        AttributeReference, Attributes, CertificateRequestType, CryptographicParameters,
-        KeyCompressionType, KeyFormatType, KeyWrapType, ObjectGroupMember, OperationEnumeration,
-        ProtectionStorageMasks, ProtocolVersion, RevocationReason, StorageStatusMask,
-        UniqueIdentifier,
+        Attribute, AttributeReference, Attributes, CancellationResult, CapabilityInformation,
+        CertificateRequestType, CryptographicParameters, KeyCompressionType, KeyFormatType,
+        KeyWrapType, ObjectGroupMember, OperationEnumeration, ProtectionStorageMasks,
+        ProtocolVersion, QueryFunction, RevocationReason, ServerInformation, StorageStatusMask,
+        UniqueIdentifier, ValidityIndicator,
     },
 };
 use crate::error::KmipError;
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Display, Debug, Eq, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Copy, Clone, Display, Debug, Eq, PartialEq, Default, EnumIter)]
 pub enum ErrorReason {
     Item_Not_Found = 0x0000_0001,
     Response_Too_Large = 0x0000_0002,
@@ -121,18 +122,58 @@ pub enum Operation {
     GetResponse(GetResponse),
     GetAttributes(GetAttributes),
     GetAttributesResponse(GetAttributesResponse),
+    SetAttribute(SetAttribute),
+    SetAttributeResponse(SetAttributeResponse),
+    AddAttribute(AddAttribute),
+    AddAttributeResponse(AddAttributeResponse),
+    ModifyAttribute(ModifyAttribute),
+    ModifyAttributeResponse(ModifyAttributeResponse),
+    DeleteAttribute(DeleteAttribute),
+    DeleteAttributeResponse(DeleteAttributeResponse),
     Encrypt(Encrypt),
     EncryptResponse(EncryptResponse),
     Decrypt(Decrypt),
     DecryptResponse(DecryptResponse),
+    Sign(Sign),
+    SignResponse(SignResponse),
+    SignatureVerify(SignatureVerify),
+    SignatureVerifyResponse(SignatureVerifyResponse),
+    Validate(Validate),
+    ValidateResponse(ValidateResponse),
+    MAC(MAC),
+    MACResponse(MACResponse),
+    MACVerify(MACVerify),
+    MACVerifyResponse(MACVerifyResponse),
+    DeriveKey(DeriveKey),
+    DeriveKeyResponse(DeriveKeyResponse),
     Locate(Locate),
     LocateResponse(LocateResponse),
+    Activate(Activate),
+    ActivateResponse(ActivateResponse),
     Revoke(Revoke),
     RevokeResponse(RevokeResponse),
+    ReKey(ReKey),
+    ReKeyResponse(ReKeyResponse),
     ReKeyKeyPair(ReKeyKeyPair),
     ReKeyKeyPairResponse(ReKeyKeyPairResponse),
     Destroy(Destroy),
     DestroyResponse(DestroyResponse),
+    Archive(Archive),
+    ArchiveResponse(ArchiveResponse),
+    Recover(Recover),
+    RecoverResponse(RecoverResponse),
+    Query(Query),
+    QueryResponse(QueryResponse),
+    DiscoverVersions(DiscoverVersions),
+    DiscoverVersionsResponse(DiscoverVersionsResponse),
+    RNGRetrieve(RNGRetrieve),
+    RNGRetrieveResponse(RNGRetrieveResponse),
+    RNGSeed(RNGSeed),
+    RNGSeedResponse(RNGSeedResponse),
+    Poll(Poll),
+    PollResponse(PollResponse),
+    Cancel(Cancel),
+    CancelResponse(CancelResponse),
 }
 
 impl Operation {
@@ -146,12 +187,32 @@ impl Operation {
             | Operation::Export(_)
             | Operation::Get(_)
             | Operation::GetAttributes(_)
+            | Operation::SetAttribute(_)
+            | Operation::AddAttribute(_)
+            | Operation::ModifyAttribute(_)
+            | Operation::DeleteAttribute(_)
             | Operation::Encrypt(_)
             | Operation::Decrypt(_)
+            | Operation::Sign(_)
+            | Operation::SignatureVerify(_)
+            | Operation::Validate(_)
+            | Operation::MAC(_)
+            | Operation::MACVerify(_)
+            | Operation::DeriveKey(_)
             | Operation::Locate(_)
+            | Operation::Activate(_)
             | Operation::Revoke(_)
+            | Operation::ReKey(_)
             | Operation::ReKeyKeyPair(_)
-            | Operation::Destroy(_) => Direction::Request,
+            | Operation::Destroy(_)
+            | Operation::Archive(_)
+            | Operation::Recover(_)
+            | Operation::Query(_)
+            | Operation::DiscoverVersions(_)
+            | Operation::RNGRetrieve(_)
+            | Operation::RNGSeed(_)
+            | Operation::Poll(_)
+            | Operation::Cancel(_) => Direction::Request,
 
             Operation::ImportResponse(_)
             | Operation::CertifyResponse(_)
@@ -160,12 +221,32 @@ impl Operation {
             | Operation::ExportResponse(_)
             | Operation::GetResponse(_)
             | Operation::GetAttributesResponse(_)
+            | Operation::SetAttributeResponse(_)
+            | Operation::AddAttributeResponse(_)
+            | Operation::ModifyAttributeResponse(_)
+            | Operation::DeleteAttributeResponse(_)
             | Operation::EncryptResponse(_)
             | Operation::DecryptResponse(_)
+            | Operation::SignResponse(_)
+            | Operation::SignatureVerifyResponse(_)
+            | Operation::ValidateResponse(_)
+            | Operation::MACResponse(_)
+            | Operation::MACVerifyResponse(_)
+            | Operation::DeriveKeyResponse(_)
             | Operation::LocateResponse(_)
+            | Operation::ActivateResponse(_)
             | Operation::RevokeResponse(_)
+            | Operation::ReKeyResponse(_)
             | Operation::ReKeyKeyPairResponse(_)
-            | Operation::DestroyResponse(_) => Direction::Response,
+            | Operation::DestroyResponse(_)
+            | Operation::ArchiveResponse(_)
+            | Operation::RecoverResponse(_)
+            | Operation::QueryResponse(_)
+            | Operation::DiscoverVersionsResponse(_)
+            | Operation::RNGRetrieveResponse(_)
+            | Operation::RNGSeedResponse(_)
+            | Operation::PollResponse(_)
+            | Operation::CancelResponse(_) => Direction::Response,
         }
     }
 
@@ -183,14 +264,56 @@ impl Operation {
             Operation::GetAttributes(_) | Operation::GetAttributesResponse(_) => {
                 OperationEnumeration::GetAttributes
             }
+            Operation::SetAttribute(_) | Operation::SetAttributeResponse(_) => {
+                OperationEnumeration::SetAttribute
+            }
+            Operation::AddAttribute(_) | Operation::AddAttributeResponse(_) => {
+                OperationEnumeration::AddAttribute
+            }
+            Operation::ModifyAttribute(_) | Operation::ModifyAttributeResponse(_) => {
+                OperationEnumeration::ModifyAttribute
+            }
+            Operation::DeleteAttribute(_) | Operation::DeleteAttributeResponse(_) => {
+                OperationEnumeration::DeleteAttribute
+            }
             Operation::Encrypt(_) | Operation::EncryptResponse(_) => OperationEnumeration::Encrypt,
             Operation::Decrypt(_) | Operation::DecryptResponse(_) => OperationEnumeration::Decrypt,
+            Operation::Sign(_) | Operation::SignResponse(_) => OperationEnumeration::Sign,
+            Operation::SignatureVerify(_) | Operation::SignatureVerifyResponse(_) => {
+                OperationEnumeration::SignatureVerify
+            }
+            Operation::Validate(_) | Operation::ValidateResponse(_) => {
+                OperationEnumeration::Validate
+            }
+            Operation::MAC(_) | Operation::MACResponse(_) => OperationEnumeration::MAC,
+            Operation::MACVerify(_) | Operation::MACVerifyResponse(_) => {
+                OperationEnumeration::MACVerify
+            }
+            Operation::DeriveKey(_) | Operation::DeriveKeyResponse(_) => {
+                OperationEnumeration::DeriveKey
+            }
             Operation::Locate(_) | Operation::LocateResponse(_) => OperationEnumeration::Locate,
+            Operation::Activate(_) | Operation::ActivateResponse(_) => {
+                OperationEnumeration::Activate
+            }
             Operation::Revoke(_) | Operation::RevokeResponse(_) => OperationEnumeration::Revoke,
+            Operation::ReKey(_) | Operation::ReKeyResponse(_) => OperationEnumeration::Rekey,
             Operation::ReKeyKeyPair(_) | Operation::ReKeyKeyPairResponse(_) => {
                 OperationEnumeration::RekeyKeyPair
             }
             Operation::Destroy(_) | Operation::DestroyResponse(_) => OperationEnumeration::Destroy,
+            Operation::Archive(_) | Operation::ArchiveResponse(_) => OperationEnumeration::Archive,
+            Operation::Recover(_) | Operation::RecoverResponse(_) => OperationEnumeration::Recover,
+            Operation::Query(_) | Operation::QueryResponse(_) => OperationEnumeration::Query,
+            Operation::DiscoverVersions(_) | Operation::DiscoverVersionsResponse(_) => {
+                OperationEnumeration::DiscoverVersions
+            }
+            Operation::RNGRetrieve(_) | Operation::RNGRetrieveResponse(_) => {
+                OperationEnumeration::RNGRetrieve
+            }
+            Operation::RNGSeed(_) | Operation::RNGSeedResponse(_) => OperationEnumeration::RNGSeed,
+            Operation::Poll(_) | Operation::PollResponse(_) => OperationEnumeration::Poll,
+            Operation::Cancel(_) | Operation::CancelResponse(_) => OperationEnumeration::Cancel,
         }
     }
 
@@ -502,6 +625,7 @@ pub struct CreateKeyPairResponse {
 }
 
 #[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Copy, Clone, Display, Debug, Eq, PartialEq, EnumIter)]
 pub enum DerivationMethod {
     PBKDF2 = 0x0000_0001,
     HASH = 0x0000_0002,
@@ -516,21 +640,27 @@ pub enum DerivationMethod {
     // Extensions items available at values 8XXX_XXXX.
 }
 
-// TODO - derive key using DeriveKey operation.
-#[allow(dead_code)]
+/// The parameters needed by the derivation method of a `DeriveKey` request
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
 pub struct DerivationParameters {
     /// Depends on the PRF.
-    cryptographic_parameters: Option<CryptographicParameters>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cryptographic_parameters: Option<CryptographicParameters>,
     /// Depends on the PRF and mode of operation: an empty IV is assumed if not
     /// provided.
-    initialization_vector: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initialization_vector: Option<Vec<u8>>,
     /// Mandatory unless the Unique Identifier of a Secret Data object is
     /// provided. May be repeated.
-    derivation_data: Option<Zeroizing<Vec<u8>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_data: Option<Zeroizing<Vec<u8>>>,
     /// Mandatory if Derivation method is PBKDF2.
-    salt: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<Vec<u8>>,
     /// Mandatory if derivation method is PBKDF2.
-    iteration_count: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iteration_count: Option<i32>,
 }
 
 /// This request is used to derive a Symmetric Key or Secret Data object from
@@ -542,6 +672,9 @@ pub struct DerivationParameters {
 /// server SHALL return an error. For all derivation methods, the client SHALL
 /// specify the desired length of the derived key or Secret Data object using
 /// the Cryptographic Length attribute.
+/// `https://docs.oasis-open.org/kmip/kmip-spec/v2.1/os/kmip-spec-v2.1-os.html#_Toc57115745`
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
 pub struct DeriveKey {
     /// Determines the type of object to be created.
     pub object_type: ObjectType,
@@ -561,6 +694,13 @@ pub struct DeriveKey {
     pub attributes: Attributes,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeriveKeyResponse {
+    /// The Unique Identifier of the newly derived key.
+    pub unique_identifier: UniqueIdentifier,
+}
+
 /// This operation requests that the server returns a Managed Object specified by its Unique Identifier,
 /// together with its attributes.
 /// The Key Format Type, Key Wrap Type, Key Compression Type and Key Wrapping Specification
@@ -861,6 +1001,111 @@ pub struct GetAttributesResponse {
     pub attributes: Attributes,
 }
 
+/// This operation requests the server to either add or modify an attribute.
+/// The request contains the Unique Identifier of the Managed Object to which
+/// the attribute pertains, along with the attribute and value. If the object
+/// did not have any instances of the attribute, one is created. If the object
+/// had exactly one instance, then it is modified. If it has more than one
+/// instance an error is raised. Read-Only attributes SHALL NOT be added or
+/// modified using this operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SetAttribute {
+    /// The Unique Identifier of the object. If omitted, then the ID
+    /// Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// Specifies the new value for the attribute associated with the object.
+    pub new_attribute: Attribute,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SetAttributeResponse {
+    /// The Unique Identifier of the object
+    pub unique_identifier: UniqueIdentifier,
+}
+
+/// This operation requests the server to add a new attribute instance to be
+/// associated with a Managed Object and set its value. The request contains
+/// the Unique Identifier of the Managed Object to which the attribute
+/// pertains, along with the attribute and value. For single-instance
+/// attributes, this creates the attribute value. For multi-instance
+/// attributes, this is how the first and subsequent values are created.
+/// Existing attribute values SHALL NOT be changed by this operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AddAttribute {
+    /// The Unique Identifier of the object. If omitted, then the ID
+    /// Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// Specifies the attribute to be added to the object.
+    pub new_attribute: Attribute,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AddAttributeResponse {
+    /// The Unique Identifier of the object
+    pub unique_identifier: UniqueIdentifier,
+}
+
+/// This operation requests the server to modify the value of an existing
+/// attribute instance associated with a Managed Object. The request contains
+/// the Unique Identifier of the Managed Object whose attribute is to be
+/// modified, and optionally the current value of the attribute instance to be
+/// modified, along with the new value. If the Current Attribute is omitted,
+/// the attribute SHALL be single-instance. The attribute instance SHALL exist.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ModifyAttribute {
+    /// The Unique Identifier of the object. If omitted, then the ID
+    /// Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// Specifies the existing value of the attribute instance to be modified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_attribute: Option<Attribute>,
+    /// Specifies the new value for the attribute instance.
+    pub new_attribute: Attribute,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ModifyAttributeResponse {
+    /// The Unique Identifier of the object
+    pub unique_identifier: UniqueIdentifier,
+}
+
+/// This operation requests the server to delete an attribute associated with
+/// a Managed Object. The request contains the Unique Identifier of the Managed
+/// Object whose attribute is to be deleted, and either the Current Attribute
+/// instance to delete, or Attribute References designating all the instances
+/// of the attributes to delete. Attributes that are always required to have a
+/// value SHALL never be deleted by this operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteAttribute {
+    /// The Unique Identifier of the object. If omitted, then the ID
+    /// Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// Specifies the attribute instance to be deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_attribute: Option<Attribute>,
+    /// Designates the attributes whose instances are all deleted.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "AttributeReference")]
+    pub attribute_references: Option<Vec<AttributeReference>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteAttributeResponse {
+    /// The Unique Identifier of the object
+    pub unique_identifier: UniqueIdentifier,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Encrypt {
@@ -890,7 +1135,7 @@ pub struct Encrypt {
     pub data: Option<Zeroizing<Vec<u8>>>,
     /// The initialization vector, counter or
     /// nonce to be used (where appropriate).
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", alias = "IVCounterNonce")]
     pub iv_counter_nonce: Option<Vec<u8>>,
     /// Specifies the existing stream or by-
     /// parts cryptographic operation (as
@@ -927,7 +1172,7 @@ pub struct EncryptResponse {
     /// provided in the request and the
     /// algorithm requires the provision of an
     /// IV/Counter/Nonce.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", alias = "IVCounterNonce")]
     pub iv_counter_nonce: Option<Vec<u8>>,
     /// Specifies the stream or by-parts value
     /// to be provided in subsequent calls to
@@ -973,7 +1218,7 @@ pub struct Decrypt {
     pub data: Option<Vec<u8>>,
     /// The initialization vector, counter or
     /// nonce to be used (where appropriate)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", alias = "IVCounterNonce")]
     pub iv_counter_nonce: Option<Vec<u8>>,
     /// Specifies the existing stream or by-
     /// parts cryptographic operation (as
@@ -1071,6 +1316,349 @@ pub struct DecryptResponse {
     pub correlation_value: Option<Vec<u8>>,
 }
 
+/// This operation requests the server to perform a signature operation on the
+/// provided data using a Managed Cryptographic Object as the key for the
+/// signature operation.
+///
+/// The request contains information about the cryptographic parameters
+/// (digital signature algorithm or cryptographic algorithm and hash algorithm)
+/// and the data to be signed. The cryptographic parameters MAY be omitted from
+/// the request as they can be specified as associated attributes of the Managed
+/// Cryptographic Object.
+///
+/// If the Managed Cryptographic Object referenced has a Usage Limits attribute
+/// then the server SHALL obtain an allocation from the current Usage Limits
+/// value prior to performing the signing operation.
+///
+/// The response contains the Unique Identifier of the Managed Cryptographic
+/// Object used as the key and the result of the signature operation.
+/// `https://docs.oasis-open.org/kmip/kmip-spec/v2.1/os/kmip-spec-v2.1-os.html#_Toc57115758`
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Sign {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that is the key to
+    /// use for the signature operation. If
+    /// omitted, then the ID Placeholder value
+    /// SHALL be used by the server as the
+    /// Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// The Cryptographic Parameters (Digital
+    /// Signature Algorithm or Cryptographic
+    /// Algorithm and Hashing Algorithm)
+    /// corresponding to the particular
+    /// signature generation method
+    /// requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cryptographic_parameters: Option<CryptographicParameters>,
+    /// The data to be signed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Zeroizing<Vec<u8>>>,
+    /// The digested data to be signed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digested_data: Option<Vec<u8>>,
+    /// Specifies the existing stream or by-
+    /// parts cryptographic operation (as
+    /// returned from a previous call to this
+    /// operation)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+    /// Initial operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_indicator: Option<bool>,
+    /// Final operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_indicator: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SignResponse {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that was the key
+    /// used for the signature operation.
+    pub unique_identifier: UniqueIdentifier,
+    /// The signed data (as a Byte String).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_data: Option<Vec<u8>>,
+    /// Specifies the stream or by-parts value
+    /// to be provided in subsequent calls to
+    /// this operation for performing
+    /// cryptographic operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+}
+
+/// This operation requests the server to perform a signature verify operation
+/// on the provided data using a Managed Cryptographic Object as the key for the
+/// signature verification operation.
+///
+/// The request contains information about the cryptographic parameters
+/// (digital signature algorithm or cryptographic algorithm and hash algorithm)
+/// and the signature to be verified and MAY contain the data that was passed to
+/// the signing operation (for those algorithms which need the original data to
+/// verify a signature).
+///
+/// The cryptographic parameters MAY be omitted from the request as they can be
+/// specified as associated attributes of the Managed Cryptographic Object.
+///
+/// The response contains the Unique Identifier of the Managed Cryptographic
+/// Object used as the key and the OPTIONAL data recovered from the signature
+/// (for those signature algorithms where data recovery from the signature is
+/// supported). The validity of the signature is indicated by the Validity
+/// Indicator field.
+/// `https://docs.oasis-open.org/kmip/kmip-spec/v2.1/os/kmip-spec-v2.1-os.html#_Toc57115759`
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SignatureVerify {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that is the key to
+    /// use for the signature verify operation.
+    /// If omitted, then the ID Placeholder
+    /// value SHALL be used by the server as
+    /// the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// The Cryptographic Parameters (Digital
+    /// Signature Algorithm or Cryptographic
+    /// Algorithm and Hashing Algorithm)
+    /// corresponding to the particular
+    /// signature verification method
+    /// requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cryptographic_parameters: Option<CryptographicParameters>,
+    /// The data that was signed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<u8>>,
+    /// The digested data to be verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digested_data: Option<Vec<u8>>,
+    /// The signature to be verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_data: Option<Vec<u8>>,
+    /// Specifies the existing stream or by-
+    /// parts cryptographic operation (as
+    /// returned from a previous call to this
+    /// operation)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+    /// Initial operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_indicator: Option<bool>,
+    /// Final operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_indicator: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SignatureVerifyResponse {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that is the key
+    /// used for the verification operation.
+    pub unique_identifier: UniqueIdentifier,
+    /// An Enumeration object indicating
+    /// whether the signature is valid, invalid,
+    /// or unknown.
+    pub validity_indicator: ValidityIndicator,
+    /// The OPTIONAL recovered data (as a
+    /// Byte String) for those signature
+    /// algorithms where data recovery from
+    /// the signature is supported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<u8>>,
+    /// Specifies the stream or by-parts value
+    /// to be provided in subsequent calls to
+    /// this operation for performing
+    /// cryptographic operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+}
+
+/// This operation requests the server to validate a certificate chain and
+/// return information on its validity.
+///
+/// Only a single certificate chain SHALL be included in each request.
+/// The request contains a list of Unique Identifiers that identify Managed
+/// Certificate objects: the first one is that of the leaf certificate, and the
+/// chain is completed up to a trusted root with the certificates held by the
+/// server. The request MAY also contain a date for which the certificate chain
+/// is REQUIRED to be valid.
+///
+/// The method or policy by which validation is conducted is a decision of the
+/// server and is outside of the scope of this protocol. Likewise, the order in
+/// which the supplied certificate chain is validated and the specification of
+/// trust anchors used to terminate validation are also controlled by the server.
+/// `https://docs.oasis-open.org/kmip/kmip-spec/v2.1/os/kmip-spec-v2.1-os.html#_Toc57115814`
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Validate {
+    /// One or more Unique Identifiers of Certificate Objects,
+    /// starting with the leaf certificate.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "UniqueIdentifier")]
+    pub unique_identifiers: Option<Vec<UniqueIdentifier>>,
+    /// A Date-Time object indicating when the certificate chain needs to be
+    /// valid. If omitted, the current date and time SHALL be assumed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validity_date: Option<u64>, // epoch millis
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ValidateResponse {
+    /// An Enumeration object indicating whether the certificate chain is valid,
+    /// invalid, or unknown.
+    pub validity_indicator: ValidityIndicator,
+    /// The first reason the certificate chain is not valid, if any.
+    /// This is an extension to the KMIP specification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_message: Option<String>,
+}
+
+/// This operation requests the server to perform message authentication code
+/// (MAC) operation on the provided data using a Managed Cryptographic Object as
+/// the key for the MAC operation.
+///
+/// The request contains information about the cryptographic parameters
+/// (cryptographic algorithm) and the data to be MACed. The cryptographic
+/// parameters MAY be omitted from the request as they can be specified as
+/// associated attributes of the Managed Cryptographic Object.
+///
+/// The response contains the Unique Identifier of the Managed Cryptographic
+/// Object used as the key and the result of the MAC operation.
+///
+/// The success or failure of the operation is indicated by the Result Status
+/// (and if failure the Result Reason) in the response header.
+/// `https://docs.oasis-open.org/kmip/kmip-spec/v2.1/os/kmip-spec-v2.1-os.html#_Toc57115760`
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MAC {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that is the key to
+    /// use for the MAC operation. If omitted,
+    /// then the ID Placeholder value SHALL be
+    /// used by the server as the Unique
+    /// Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// The Cryptographic Parameters
+    /// (Cryptographic Algorithm)
+    /// corresponding to the particular MAC
+    /// method requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cryptographic_parameters: Option<CryptographicParameters>,
+    /// The data to be MACed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<u8>>,
+    /// Specifies the existing stream or by-
+    /// parts cryptographic operation (as
+    /// returned from a previous call to this
+    /// operation)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+    /// Initial operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_indicator: Option<bool>,
+    /// Final operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_indicator: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MACResponse {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that is the key
+    /// used for the MAC operation.
+    pub unique_identifier: UniqueIdentifier,
+    /// The data MACed (as a Byte String).
+    /// Only returned on the completion of
+    /// the MAC operation.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "MACData")]
+    pub mac_data: Option<Vec<u8>>,
+    /// Specifies the stream or by-parts value
+    /// to be provided in subsequent calls to
+    /// this operation for performing
+    /// cryptographic operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+}
+
+/// This operation requests the server to perform message authentication code
+/// (MAC) verify operation on the provided data using a Managed Cryptographic
+/// Object as the key for the MAC verify operation.
+///
+/// The request contains information about the cryptographic parameters
+/// (cryptographic algorithm) and the data to be MAC verified and MAY contain
+/// the data that was passed to the MAC operation (for those algorithms which
+/// need the original data to verify a MAC). The cryptographic parameters MAY
+/// be omitted from the request as they can be specified as associated
+/// attributes of the Managed Cryptographic Object.
+///
+/// The response contains the Unique Identifier of the Managed Cryptographic
+/// Object that was used as the key and the result of the MAC verify operation.
+/// The validity of the MAC is indicated by the Validity Indicator field.
+/// `https://docs.oasis-open.org/kmip/kmip-spec/v2.1/os/kmip-spec-v2.1-os.html#_Toc57115761`
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MACVerify {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that is the key to
+    /// use for the MAC verify operation. If
+    /// omitted, then the ID Placeholder value
+    /// SHALL be used by the server as the
+    /// Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// The Cryptographic Parameters
+    /// (Cryptographic Algorithm)
+    /// corresponding to the particular MAC
+    /// method requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cryptographic_parameters: Option<CryptographicParameters>,
+    /// The data that was MACed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<u8>>,
+    /// The data to be MAC verified (as a
+    /// Byte String)
+    #[serde(skip_serializing_if = "Option::is_none", rename = "MACData")]
+    pub mac_data: Option<Vec<u8>>,
+    /// Specifies the existing stream or by-
+    /// parts cryptographic operation (as
+    /// returned from a previous call to this
+    /// operation)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+    /// Initial operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_indicator: Option<bool>,
+    /// Final operation as Boolean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_indicator: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MACVerifyResponse {
+    /// The Unique Identifier of the Managed
+    /// Cryptographic Object that is the key
+    /// used for the verification operation.
+    pub unique_identifier: UniqueIdentifier,
+    /// An Enumeration object indicating
+    /// whether the MAC is valid, invalid, or
+    /// unknown. Only returned on the
+    /// completion of the MAC verify operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validity_indicator: Option<ValidityIndicator>,
+    /// Specifies the stream or by-parts value
+    /// to be provided in subsequent calls to
+    /// this operation for performing
+    /// cryptographic operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_value: Option<Vec<u8>>,
+}
+
 /// This operation requests that the server search for one or more Managed
 /// Objects, depending on the attributes specified in the request. All attributes
 /// are allowed to be used. The request MAY contain a Maximum Items field, which
@@ -1194,6 +1782,26 @@ pub struct LocateResponse {
     pub unique_identifiers: Option<Vec<UniqueIdentifier>>,
 }
 
+/// This operation requests the server to activate a Managed Cryptographic
+/// Object. The operation SHALL only be performed on an object in the
+/// Pre-Active state and has the effect of changing its state to Active, and
+/// setting its Activation Date to the current date and time.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Activate {
+    /// Determines the object being activated. If omitted, then the ID
+    /// Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActivateResponse {
+    /// The Unique Identifier of the object.
+    pub unique_identifier: UniqueIdentifier,
+}
+
 /// This operation requests the server to revoke a Managed Cryptographic Object
 /// or an Opaque Object. The request contains a reason for the revocation (e.g.,
 /// "key compromise", "cessation of operation", etc.). The operation has one of
@@ -1232,6 +1840,53 @@ pub struct RevokeResponse {
     pub unique_identifier: UniqueIdentifier,
 }
 
+/// This request is used to generate a replacement key for an existing symmetric
+/// key. It is analogous to the Create operation, except that attributes of the
+/// replacement key are copied from the existing key, with the exception of the
+/// attributes listed in Re-key Attribute Requirements.
+///
+/// As the replacement key takes over the name attribute of the existing key,
+/// Re-key SHOULD only be performed once on a given key.
+///
+/// The server SHALL copy the Unique Identifier of the replacement key returned
+/// by this operation into the ID Placeholder variable.
+///
+/// For the existing key, the server SHALL create a Link attribute of Link Type
+/// Replacement Object pointing to the replacement key. For the replacement key,
+/// the server SHALL create a Link attribute of Link Type Replaced Key pointing
+/// to the existing key.
+///
+/// An Offset MAY be used to indicate the difference between the Initial Date
+/// and the Activation Date of the replacement key. If no Offset is specified,
+/// the Activation Date, Process Start Date, Protect Stop Date and Deactivation
+/// Date values are copied from the existing key.
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReKey {
+    /// Determines the existing Symmetric Key being re-keyed. If omitted, then
+    /// the ID Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+    /// An Interval object indicating the difference between the Initial Date
+    /// and the Activation Date of the replacement key to be created, in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i32>,
+    /// Specifies desired attributes to be associated with the new object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Attributes>,
+    /// Specifies all permissible Protection Storage Mask selections for the
+    /// new object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protection_storage_masks: Option<ProtectionStorageMasks>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReKeyResponse {
+    /// The Unique Identifier of the newly created replacement Symmetric Key.
+    pub unique_identifier: UniqueIdentifier,
+}
+
 /// This request is used to generate a replacement key pair for an existing
 /// public/private key pair. It is analogous to the Create Key Pair operation,
 /// except that attributes of the replacement key pair are copied from the
@@ -1322,3 +1977,232 @@ pub struct DestroyResponse {
     /// The Unique Identifier of the object.
     pub unique_identifier: UniqueIdentifier,
 }
+
+/// This operation is used to specify that a Managed Object MAY be archived.
+/// The actual time when the object is archived, the location of the archive,
+/// or level of protection of the archive, are server dependent. Objects that
+/// have been archived are not available for any operation, other than
+/// Recover, until they are recovered.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Archive {
+    /// Determines the object being archived. If omitted, then the ID
+    /// Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ArchiveResponse {
+    /// The Unique Identifier of the object.
+    pub unique_identifier: UniqueIdentifier,
+}
+
+/// This operation is used to obtain access to a Managed Object that has been
+/// archived. This request MAY need asynchronous polling to obtain the
+/// response due to delays caused by retrieving the object from the archive.
+/// Once the response is received, the object is now on-line, and MAY be
+/// obtained (e.g., via a Get operation).
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Recover {
+    /// Determines the object being recovered. If omitted, then the ID
+    /// Placeholder value is used by the server as the Unique Identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_identifier: Option<UniqueIdentifier>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecoverResponse {
+    /// The Unique Identifier of the object.
+    pub unique_identifier: UniqueIdentifier,
+}
+
+/// This operation is used by the client to interrogate the server to determine
+/// its capabilities and/or protocol mechanisms. The Query operation SHOULD be
+/// invocable by unauthenticated clients to interrogate server features and
+/// functions. The Query Function field in the request SHALL contain one or
+/// more of the following items:
+/// - Query Operations
+/// - Query Objects
+/// - Query Server Information
+/// - Query Application Namespaces
+/// - Query Extension List
+/// - Query Extension Map
+/// - Query Attestation Types
+/// - Query RNGs
+/// - Query Validations
+/// - Query Profiles
+/// - Query Capabilities
+/// - Query Client Registration Methods
+/// - Query Defaults Information
+/// - Query Storage Protection Masks
+///
+/// The Operation fields in the response contain Operation enumerated values,
+/// which SHALL list all the operations that the server supports. If the
+/// request contains a Query Operations value in the Query Function field,
+/// then these fields SHALL be returned in the response.
+///
+/// The Object Type fields in the response contain Object Type enumerated
+/// values, which SHALL list all the object types that the server supports.
+/// If the request contains a Query Objects value in the Query Function field,
+/// then these fields SHALL be returned in the response.
+///
+/// The Server Information field in the response is a structure containing
+/// vendor-specific fields and/or substructures. If the request contains a
+/// Query Server Information value in the Query Function field, then this
+/// field SHALL be returned in the response.
+///
+/// The Capability Information fields in the response contain details of the
+/// capabilities of the server. If the request contains a Query Capabilities
+/// value in the Query Function field, then these fields SHALL be returned in
+/// the response.
+///
+/// Note that the response payload is empty if there are no values to return.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct Query {
+    /// Determines the information being queried
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_function: Option<Vec<QueryFunction>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct QueryResponse {
+    /// Specifies an Operation that is supported by the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<Vec<OperationEnumeration>>,
+    /// Specifies a Managed Object Type that is supported by the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<Vec<ObjectType>>,
+    /// SHALL be returned if Query Server Information is requested.
+    /// The Vendor Identification SHALL be a text string that uniquely
+    /// identifies the vendor.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor_identification: Option<String>,
+    /// Contains vendor-specific information possibly be of interest to the client.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_information: Option<ServerInformation>,
+    /// Specifies the capabilities of the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capability_information: Option<Vec<CapabilityInformation>>,
+}
+
+/// This operation is used by the client to determine a list of protocol
+/// versions that is supported by the server. The request payload contains an
+/// OPTIONAL list of protocol versions that is supported by the client. The
+/// protocol versions SHALL be ranked in decreasing order of preference.
+///
+/// The response payload contains a list of protocol versions that are
+/// supported by the server. The protocol versions are ranked in decreasing
+/// order of preference. If the client provides the server with a list of
+/// supported protocol versions in the request payload, the server SHALL return
+/// only the protocol versions that are supported by both the client and
+/// server. The server SHOULD list all the protocol versions supported by both
+/// client and server. If the protocol version specified in the request header
+/// is not specified in the request payload and the server does not support
+/// any protocol version specified in the request payload, the server SHALL
+/// return an empty list in the response payload. If no protocol versions are
+/// specified in the request payload, the server SHOULD return all the protocol
+/// versions that are supported by the server.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct DiscoverVersions {
+    /// The list of protocol versions supported by the client,
+    /// ranked in decreasing order of preference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<Vec<ProtocolVersion>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct DiscoverVersionsResponse {
+    /// The list of protocol versions supported by the server,
+    /// ranked in decreasing order of preference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<Vec<ProtocolVersion>>,
+}
+
+/// This operation requests the server to return output from a Random Number
+/// Generator (RNG).
+///
+/// The request SHALL contain the length of the random data to return. The
+/// server MAY reject the request if the requested length exceeds the maximum
+/// length it is willing to return.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct RNGRetrieve {
+    /// The amount of random data to return, in bytes.
+    pub data_length: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RNGRetrieveResponse {
+    /// The random data.
+    pub data: Vec<u8>,
+}
+
+/// This operation requests the server to seed a Random Number Generator.
+///
+/// The response SHALL contain the amount of seed data that was used by the
+/// server. The server MAY ignore the seed data, in which case the returned
+/// length is zero.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct RNGSeed {
+    /// The data to be provided as a seed to the random number generator.
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RNGSeedResponse {
+    /// The amount of seed data accepted by the server, in bytes.
+    pub data_length: i32,
+}
+
+/// This operation is used to poll the server in order to obtain the status of
+/// an outstanding asynchronous operation.
+///
+/// The correlation value of the original operation SHALL be specified in the
+/// request. If the operation completed, the server returns the response of the
+/// original operation in place of the Poll response. If it is still pending,
+/// the result status of the batch item is Operation Pending.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct Poll {
+    /// The correlation value returned by the server for the pending operation.
+    pub asynchronous_correlation_value: Vec<u8>,
+}
+
+/// The Poll response carries no payload: the response of the polled operation
+/// is returned instead once it completed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct PollResponse {}
+
+/// This operation requests the server to cancel an outstanding asynchronous
+/// operation.
+///
+/// The correlation value of the original operation SHALL be specified in the
+/// request. The server SHALL respond with a Cancellation Result that contains
+/// the status of the cancellation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct Cancel {
+    /// The correlation value returned by the server for the pending operation.
+    pub asynchronous_correlation_value: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CancelResponse {
+    /// The correlation value of the canceled operation.
+    pub asynchronous_correlation_value: Vec<u8>,
+    /// The status of the cancellation.
+    pub cancellation_result: CancellationResult,
+}
@@ -37,33 +37,33 @@ use crate::{
 /// The Certificate Type value SHALL be set by the server when the certificate
 /// is created or registered and then SHALL NOT be changed or deleted before the
 /// object is destroyed.
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum CertificateType {
     X509 = 0x01,
     PGP = 0x02,
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum CertificateRequestType {
     CRMF = 0x01,
     PKCS10 = 0x02,
     PEM = 0x03,
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 #[allow(clippy::enum_clike_unportable_variant)]
 pub enum OpaqueDataType {
     Unknown = 0x8000_0001,
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 #[allow(clippy::enum_clike_unportable_variant)]
 pub enum SecretDataType {
     Password = 0x01,
     Seed = 0x02,
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum SplitKeyMethod {
     XOR = 0x0000_0001,
     PolynomialSharingGf216 = 0x0000_0002,
@@ -235,7 +235,7 @@ impl Default for CryptographicDomainParameters {
 
 #[allow(non_camel_case_types)]
 #[allow(clippy::enum_clike_unportable_variant)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
 pub enum RecommendedCurve {
     P192 = 0x0000_0001,
     K163 = 0x0000_0002,
@@ -324,7 +324,7 @@ impl Default for RecommendedCurve {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum KeyCompressionType {
     ECPublicKeyTypeUncompressed = 0x0000_0001,
     ECPublicKeyTypeX962CompressedPrime = 0x0000_0002,
@@ -525,7 +525,7 @@ impl<'de> Deserialize<'de> for ProtectionStorageMasks {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 #[serde(rename_all = "PascalCase")]
 pub enum ObjectGroupMember {
     Group_Member_Fresh = 0x0000_0001,
@@ -602,7 +602,7 @@ impl<'de> Deserialize<'de> for StorageStatusMask {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
 pub enum LinkType {
     /// For Certificate objects: the parent certificate for a certificate in a
     /// certificate chain. For Public Key objects: the corresponding
@@ -654,7 +654,7 @@ pub enum LinkType {
 /// operation is repeated for each of them. If an operation appears
 /// multiple times in a request, it is the most recent that is referred to.
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
 pub enum UniqueIdentifierEnumeration {
     IDPlaceholder = 0x0000_0001,
     Certify = 0x0000_0002,
@@ -690,7 +690,7 @@ pub enum LinkedObjectIdentifier {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
 pub enum RevocationReasonEnumeration {
     Unspecified = 0x0000_0001,
     KeyCompromise = 0x0000_0002,
@@ -777,6 +777,52 @@ pub struct VendorAttribute {
     pub attribute_value: Vec<u8>,
 }
 
+/// The Name attribute is a structure used to identify and locate an object.
+/// This attribute is assigned by the client, and the Name Value is intended
+/// to be in a form that humans are able to interpret. The key management
+/// system MAY specify rules by which the client creates valid names.
+/// Clients are informed of such rules by a mechanism that is not specified by
+/// this standard. Names SHALL be unique within a given key management domain,
+/// but are NOT REQUIRED to be globally unique.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Name {
+    pub name_value: String,
+    pub name_type: NameType,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
+pub enum NameType {
+    /// Human-readable text not interpreted by the key management system
+    UninterpretedTextString = 0x0000_0001,
+    /// A Uniform Resource Identifier
+    URI = 0x0000_0002,
+}
+
+/// The Usage Limits attribute is a mechanism for limiting the usage of a
+/// Managed Cryptographic Object. It only applies to Managed Cryptographic
+/// Objects that are able to be used for applying cryptographic protection and
+/// it SHALL only reflect their usage for applying that protection (e.g.,
+/// encryption, signing, etc.).
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct UsageLimits {
+    /// The total number of Usage Limits Units allowed to be protected
+    pub usage_limits_total: i64,
+    /// The currently remaining number of Usage Limits Units allowed to be protected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_limits_count: Option<i64>,
+    pub usage_limits_unit: UsageLimitsUnit,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
+pub enum UsageLimitsUnit {
+    Byte = 0x0000_0001,
+    Object = 0x0000_0002,
+}
+
 /// The following subsections describe the attributes that are associated with
 /// Managed Objects. Attributes that an object MAY have multiple instances of
 /// are referred to as multi-instance attributes. All instances of an attribute
@@ -799,7 +845,6 @@ pub struct VendorAttribute {
 /// from a client until the object is destroyed. After an object is destroyed,
 /// the server MAY retain all, some or none of the object attributes,
 /// depending on the object type and server policy.
-// TODO: there are 56 attributes in the specs. Only a handful are implemented here
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
 #[serde(rename_all = "PascalCase")]
 pub struct Attributes {
@@ -830,6 +875,28 @@ pub struct Attributes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub certificate_length: Option<i32>,
 
+    /// The Comment attribute is used for descriptive purposes only.
+    /// It is not used for policy enforcement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    /// The Contact Information attribute is used for descriptive purposes only,
+    /// such as the name of the person or team to contact about the object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_information: Option<String>,
+
+    /// The Compromise Date is the date and time when the Managed Object
+    /// entered into the compromised state. It is set by the server when the
+    /// object is revoked with a compromise reason.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compromise_date: Option<u64>, // epoch millis
+
+    /// The Compromise Occurrence Date is the date and time when the Managed
+    /// Object was first believed to be compromised, as provided by the client
+    /// in the Revoke request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compromise_occurrence_date: Option<u64>, // epoch millis
+
     /// The Cryptographic Algorithm of an object. The Cryptographic Algorithm of
     /// a Certificate object identifies the algorithm for the public key
     /// contained within the Certificate. The digital signature algorithm used
@@ -871,6 +938,41 @@ pub struct Attributes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cryptographic_usage_mask: Option<CryptographicUsageMask>,
 
+    /// The Deactivation Date is the date and time when the Managed Object
+    /// SHALL NOT be used for any purpose, except for decryption, signature
+    /// verification, or unwrapping. It is set by the server when the object
+    /// is revoked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deactivation_date: Option<u64>, // epoch millis
+
+    /// The Description attribute is a string containing a description of
+    /// the object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The Destroy Date is the date and time when the Managed Object was
+    /// destroyed. It is set by the server when the object is destroyed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destroy_date: Option<u64>, // epoch millis
+
+    /// If False then the server SHALL prevent the object value being retrieved
+    /// (in clear or wrapped). It defaults to True when the object is
+    /// created or registered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extractable: Option<bool>,
+
+    /// The Fresh attribute indicates whether the object has been exported
+    /// at least once (in clear or wrapped). It is set to True by the server when
+    /// the object is created or registered and to False on its first export.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fresh: Option<bool>,
+
+    /// The Initial Date is the date and time when the Managed Object was first
+    /// created or registered at the server. It is set by the server and
+    /// SHALL NOT be changed or deleted before the object is destroyed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_date: Option<u64>, // epoch millis
+
     /// 4.26 The Key Format Type attribute is a required attribute of a
     /// Cryptographic Object. It is set by the server, but a particular Key
     /// Format Type MAY be requested by the client if the cryptographic material
@@ -885,6 +987,12 @@ pub struct Attributes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key_format_type: Option<KeyFormatType>,
 
+    /// The Last Change Date is the date and time of the last change relevant
+    /// to the client of the Managed Object, its attributes or its state.
+    /// It is maintained by the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_change_date: Option<u64>, // epoch millis
+
     /// The Link attribute is a structure used to create a link from one Managed
     /// Cryptographic Object to another, closely related target Managed
     /// Cryptographic Object. The link has a type, and the allowed types differ,
@@ -911,6 +1019,21 @@ pub struct Attributes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub link: Option<Vec<Link>>,
 
+    /// The Names of the object, see `Name`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Vec<Name>>,
+
+    /// The Never Extractable attribute is set to True by the server when the
+    /// Extractable attribute of the object has never been True.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub never_extractable: Option<bool>,
+
+    /// An object MAY be part of a group of objects. An object MAY belong to
+    /// more than one group of objects. The Object Group is a text string
+    /// assigned by the client or by the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_group: Option<String>,
+
     /// The Object Typeof a Managed Object (e.g., public key, private key,
     /// symmetric key, etc.) SHALL be set by the server when the object is
     /// created or registered and then SHALL NOT be changed or deleted before
@@ -918,6 +1041,39 @@ pub struct Attributes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_type: Option<ObjectType>,
 
+    /// The Original Creation Date is the date and time when the object was
+    /// originally created, which can be different from the Initial Date
+    /// if the object was generated outside the server and then registered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_creation_date: Option<u64>, // epoch millis
+
+    /// The Process Start Date is the date and time when a Managed Symmetric
+    /// Key object MAY begin to be used to process cryptographically
+    /// protected information (e.g., decryption or unwrapping).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_start_date: Option<u64>, // epoch millis
+
+    /// The Protect Stop Date is the date and time when a Managed Symmetric
+    /// Key object SHALL NOT be used for applying cryptographic protection
+    /// (e.g., encryption or wrapping).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protect_stop_date: Option<u64>, // epoch millis
+
+    /// The Revocation Reason is the reason given in the Revoke request
+    /// that moved the object to the deactivated or compromised state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revocation_reason: Option<RevocationReason>,
+
+    /// If True then the server SHALL prevent the object value being retrieved
+    /// in clear, i.e. the object can only be exported wrapped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+
+    /// The State of the object as known to the key management server.
+    /// It is maintained by the server and SHALL NOT be changed by the client.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<StateEnumeration>,
+
     /// The Unique Identifier is generated by the key management system
     /// to uniquely identify a Managed Object. It is only REQUIRED to be unique
     /// within the identifier space managed by a single key management system,
@@ -929,6 +1085,10 @@ pub struct Attributes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unique_identifier: Option<UniqueIdentifier>,
 
+    /// The Usage Limits of the object, see `UsageLimits`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_limits: Option<UsageLimits>,
+
     /// A vendor specific Attribute is a structure used for sending and
     /// receiving a Managed Object attribute. The Vendor Identification and
     /// Attribute Name are text-strings that are used to identify the attribute.
@@ -1256,9 +1416,190 @@ impl AttributeReference {
     }
 }
 
+/// A single attribute of a Managed Object, as used by the Set Attribute,
+/// Add Attribute, Modify Attribute and Delete Attribute operations.
+///
+/// Only the attributes that a client may change are listed: the other
+/// attributes are set by the server when the object is created or registered
+/// and SHALL NOT be changed or deleted before the object is destroyed.
+///
+/// The tags of an object are carried by the Cosmian `tag` vendor attribute.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Attribute {
+    ActivationDate(u64), // epoch millis
+    Comment(String),
+    ContactInformation(String),
+    CryptographicParameters(CryptographicParameters),
+    CryptographicUsageMask(CryptographicUsageMask),
+    Description(String),
+    Extractable(bool),
+    Link(Link),
+    Name(Name),
+    ObjectGroup(String),
+    ProcessStartDate(u64), // epoch millis
+    ProtectStopDate(u64),  // epoch millis
+    Sensitive(bool),
+    UsageLimits(UsageLimits),
+    VendorAttribute(VendorAttribute),
+}
+
+impl Attribute {
+    /// The reference designating all the instances of this attribute
+    #[must_use]
+    pub fn reference(&self) -> AttributeReference {
+        match self {
+            Self::ActivationDate(_) => AttributeReference::Standard(Tag::ActivationDate),
+            Self::CryptographicParameters(_) => {
+                AttributeReference::Standard(Tag::CryptographicParameters)
+            }
+            Self::CryptographicUsageMask(_) => {
+                AttributeReference::Standard(Tag::CryptographicUsageMask)
+            }
+            Self::Comment(_) => AttributeReference::Standard(Tag::Comment),
+            Self::ContactInformation(_) => AttributeReference::Standard(Tag::ContactInformation),
+            Self::Description(_) => AttributeReference::Standard(Tag::Description),
+            Self::Extractable(_) => AttributeReference::Standard(Tag::Extractable),
+            Self::Link(_) => AttributeReference::Standard(Tag::Link),
+            Self::Name(_) => AttributeReference::Standard(Tag::Name),
+            Self::ObjectGroup(_) => AttributeReference::Standard(Tag::ObjectGroup),
+            Self::ProcessStartDate(_) => AttributeReference::Standard(Tag::ProcessStartDate),
+            Self::ProtectStopDate(_) => AttributeReference::Standard(Tag::ProtectStopDate),
+            Self::Sensitive(_) => AttributeReference::Standard(Tag::Sensitive),
+            Self::UsageLimits(_) => AttributeReference::Standard(Tag::UsageLimits),
+            Self::VendorAttribute(va) => AttributeReference::Vendor(VendorAttributeReference {
+                vendor_identification: va.vendor_identification.clone(),
+                attribute_name: va.attribute_name.clone(),
+            }),
+        }
+    }
+}
+
+impl Serialize for Attribute {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut st = serializer.serialize_struct("Attribute", 1)?;
+        match self {
+            Self::ActivationDate(value) => st.serialize_field("ActivationDate", value)?,
+            Self::CryptographicParameters(value) => {
+                st.serialize_field("CryptographicParameters", value)?;
+            }
+            Self::CryptographicUsageMask(value) => {
+                st.serialize_field("CryptographicUsageMask", value)?;
+            }
+            Self::Comment(value) => st.serialize_field("Comment", value)?,
+            Self::ContactInformation(value) => st.serialize_field("ContactInformation", value)?,
+            Self::Description(value) => st.serialize_field("Description", value)?,
+            Self::Extractable(value) => st.serialize_field("Extractable", value)?,
+            Self::Link(value) => st.serialize_field("Link", value)?,
+            Self::Name(value) => st.serialize_field("Name", value)?,
+            Self::ObjectGroup(value) => st.serialize_field("ObjectGroup", value)?,
+            Self::ProcessStartDate(value) => st.serialize_field("ProcessStartDate", value)?,
+            Self::ProtectStopDate(value) => st.serialize_field("ProtectStopDate", value)?,
+            Self::Sensitive(value) => st.serialize_field("Sensitive", value)?,
+            Self::UsageLimits(value) => st.serialize_field("UsageLimits", value)?,
+            Self::VendorAttribute(value) => st.serialize_field("VendorAttribute", value)?,
+        }
+        st.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Attribute {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(field_identifier)]
+        enum Field {
+            ActivationDate,
+            Comment,
+            ContactInformation,
+            CryptographicParameters,
+            CryptographicUsageMask,
+            Description,
+            Extractable,
+            Link,
+            Name,
+            ObjectGroup,
+            ProcessStartDate,
+            ProtectStopDate,
+            Sensitive,
+            UsageLimits,
+            VendorAttribute,
+        }
+
+        struct AttributeVisitor;
+
+        impl<'de> Visitor<'de> for AttributeVisitor {
+            type Value = Attribute;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("struct Attribute")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+            where
+                V: MapAccess<'de>,
+            {
+                let attribute = match map.next_key()? {
+                    Some(Field::ActivationDate) => Attribute::ActivationDate(map.next_value()?),
+                    Some(Field::CryptographicParameters) => {
+                        Attribute::CryptographicParameters(map.next_value()?)
+                    }
+                    Some(Field::CryptographicUsageMask) => {
+                        Attribute::CryptographicUsageMask(map.next_value()?)
+                    }
+                    Some(Field::Comment) => Attribute::Comment(map.next_value()?),
+                    Some(Field::ContactInformation) => {
+                        Attribute::ContactInformation(map.next_value()?)
+                    }
+                    Some(Field::Description) => Attribute::Description(map.next_value()?),
+                    Some(Field::Extractable) => Attribute::Extractable(map.next_value()?),
+                    Some(Field::Link) => Attribute::Link(map.next_value()?),
+                    Some(Field::Name) => Attribute::Name(map.next_value()?),
+                    Some(Field::ObjectGroup) => Attribute::ObjectGroup(map.next_value()?),
+                    Some(Field::ProcessStartDate) => Attribute::ProcessStartDate(map.next_value()?),
+                    Some(Field::ProtectStopDate) => Attribute::ProtectStopDate(map.next_value()?),
+                    Some(Field::Sensitive) => Attribute::Sensitive(map.next_value()?),
+                    Some(Field::UsageLimits) => Attribute::UsageLimits(map.next_value()?),
+                    Some(Field::VendorAttribute) => Attribute::VendorAttribute(map.next_value()?),
+                    None => return Err(de::Error::custom("an attribute must have a value")),
+                };
+                if map.next_key::<Field>()?.is_some() {
+                    return Err(de::Error::custom("an attribute must have a single value"))
+                }
+                Ok(attribute)
+            }
+        }
+
+        const FIELDS: &[&str] = &[
+            "ActivationDate",
+            "Comment",
+            "ContactInformation",
+            "CryptographicParameters",
+            "CryptographicUsageMask",
+            "Description",
+            "Extractable",
+            "Link",
+            "Name",
+            "ObjectGroup",
+            "ProcessStartDate",
+            "ProtectStopDate",
+            "Sensitive",
+            "UsageLimits",
+            "VendorAttribute",
+        ];
+        deserializer.deserialize_struct("Attribute", FIELDS, AttributeVisitor)
+    }
+}
+
 #[allow(non_camel_case_types)]
 #[allow(clippy::enum_variant_names)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumString, Display)]
+#[derive(
+    Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumString, Display, EnumIter,
+)]
 pub enum Tag {
     ActivationDate = 0x42_0001,
     ApplicationData = 0x42_0002,
@@ -1268,6 +1609,7 @@ pub enum Tag {
     AsynchronousCorrelation_Value = 0x42_0006,
     AsynchronousIndicator = 0x42_0007,
     Attribute = 0x42_0008,
+    AttributeIndex = 0x42_0009,
     AttributeName = 0x42_000A,
     AttributeValue = 0x42_000B,
     Authentication = 0x42_000C,
@@ -1282,6 +1624,7 @@ pub enum Tag {
     CertificateRequestType = 0x42_0019,
     CertificateType = 0x42_001D,
     CertificateValue = 0x42_001E,
+    CommonTemplateAttribute = 0x42_001F,
     CompromiseDate = 0x42_0020,
     CompromiseOccurrenceDate = 0x42_0021,
     ContactInformation = 0x42_0022,
@@ -1348,6 +1691,7 @@ pub enum Tag {
     PrimeFieldSize = 0x42_0062,
     PrivateExponent = 0x42_0063,
     PrivateKey = 0x42_0064,
+    PrivateKeyTemplateAttribute = 0x42_0065,
     PrivateKeyUniqueIdentifier = 0x42_0066,
     ProcessStartDate = 0x42_0067,
     ProtectStopDate = 0x42_0068,
@@ -1356,6 +1700,7 @@ pub enum Tag {
     ProtocolVersionMinor = 0x42_006B,
     PublicExponent = 0x42_006C,
     PublicKey = 0x42_006D,
+    PublicKeyTemplateAttribute = 0x42_006E,
     PublicKeyUniqueIdentifier = 0x42_006F,
     PutFunction = 0x42_0070,
     Q = 0x42_0071,
@@ -1388,6 +1733,7 @@ pub enum Tag {
     State = 0x42_008D,
     StorageStatusMask = 0x42_008E,
     SymmetricKey = 0x42_008F,
+    TemplateAttribute = 0x42_0091,
     TimeStamp = 0x42_0092,
     UniqueBatchItemID = 0x42_0093,
     UniqueIdentifier = 0x42_0094,
@@ -1603,7 +1949,7 @@ pub enum Tag {
 
 /// Indicates the method used to wrap the Key Value.
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum WrappingMethod {
     Encrypt = 0x0000_0001,
     MACSign = 0x0000_0002,
@@ -1618,7 +1964,7 @@ impl Default for WrappingMethod {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum BlockCipherMode {
     CBC = 0x0000_0001,
     ECB = 0x0000_0002,
@@ -1631,6 +1977,8 @@ pub enum BlockCipherMode {
     GCM = 0x0000_0009,
     CBCMAC = 0x0000_000A,
     XTS = 0x0000_000B,
+    AESKeyWrapPadding = 0x0000_000C,
+    NISTKeyWrap = 0x0000_000D,
     X9102AESKW = 0x0000_000E,
     X9102TDKW = 0x0000_000F,
     X9102AKW1 = 0x0000_0010,
@@ -1639,7 +1987,7 @@ pub enum BlockCipherMode {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum PaddingMethod {
     None = 0x0000_0001,
     OAEP = 0x0000_0002,
@@ -1654,7 +2002,7 @@ pub enum PaddingMethod {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum HashingAlgorithm {
     MD2 = 0x0000_0001,
     MD4 = 0x0000_0002,
@@ -1720,7 +2068,7 @@ impl TryFrom<HashingAlgorithm> for MessageDigest {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum KeyRoleType {
     BDK = 0x0000_0001,
     CVK = 0x0000_0002,
@@ -1749,7 +2097,7 @@ pub enum KeyRoleType {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum DigitalSignatureAlgorithm {
     MD2WithRSAEncryption = 0x0000_0001,
     MD5WithRSAEncryption = 0x0000_0002,
@@ -1772,8 +2120,19 @@ pub enum DigitalSignatureAlgorithm {
     SHA3512WithRSAEncryption = 0x0000_0013,
 }
 
+/// The Validity Indicator is returned by the Signature Verify and Validate
+/// operations to indicate whether the signature or the certificate chain
+/// is valid.
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
+pub enum ValidityIndicator {
+    Valid = 0x0000_0001,
+    Invalid = 0x0000_0002,
+    Unknown = 0x0000_0003,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum MaskGenerator {
     MFG1 = 0x0000_0001,
 }
@@ -1877,7 +2236,7 @@ pub struct MacSignatureKeyInformation {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum EncodingOption {
     /// the wrapped un-encoded value of the Byte String Key Material field in
     /// the Key Value structure
@@ -1887,7 +2246,7 @@ pub enum EncodingOption {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum KeyWrapType {
     NotWrapped = 0x0000_0001,
     AsRegistered = 0x0000_0002,
@@ -1901,7 +2260,7 @@ pub enum KeyWrapType {
 ///
 /// Note: The states correspond to those described in [SP800-57-1].
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
 pub enum StateEnumeration {
     /// Pre-Active: The object exists and SHALL NOT be used for any cryptographic purpose.
     PreActive = 0x0000_0001,
@@ -2014,7 +2373,7 @@ impl fmt::Display for ProtocolVersion {
 /// If not present in a request, then Prohibited is assumed.
 ///
 /// If the value is Prohibited, the server SHALL process the request synchronously.
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum AsynchronousIndicator {
     /// The server SHALL process all batch items in the request asynchronously
     /// (returning an Asynchronous Correlation Value for each batch item).
@@ -2030,9 +2389,24 @@ pub enum AsynchronousIndicator {
     Prohibited = 0x0000_0003,
 }
 
+/// The result of a Cancel operation
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
+pub enum CancellationResult {
+    /// The operation was canceled
+    Canceled = 0x0000_0001,
+    /// The operation could not be canceled
+    UnableToCancel = 0x0000_0002,
+    /// The operation completed before it could be canceled
+    Completed = 0x0000_0003,
+    /// The operation failed before it could be canceled
+    Failed = 0x0000_0004,
+    /// The operation is no longer available
+    Unavailable = 0x0000_0005,
+}
+
 /// Types of attestation supported by the server
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum AttestationType {
     TPM_Quote = 0x0000_0001,
     TCG_Integrity_Report = 0x0000_0002,
@@ -2428,7 +2802,7 @@ impl<'de> Deserialize<'de> for Credential {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum TicketType {
     Login = 0x0000_0001,
 }
@@ -2448,16 +2822,16 @@ pub struct Nonce {
 /// This option SHALL have one of three values (Undo, Stop or Continue).
 /// If not specified, then Stop is assumed.
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, EnumIter)]
 pub enum BatchErrorContinuationOption {
     /// If any operation in the request fails, then the server SHALL undo all the previous operations.
     ///
     /// Batch item fails and Result Status is set to Operation Failed.
     /// Responses to batch items that have already been processed are returned normally.
     /// Responses to batch items that have not been processed are not returned.
-    Undo,
-    Stop,
-    Continue,
+    Undo = 0x0000_0001,
+    Stop = 0x0000_0002,
+    Continue = 0x0000_0003,
 }
 
 /// The Message Extension is an OPTIONAL structure that MAY be appended to any Batch Item.
@@ -2491,7 +2865,7 @@ pub struct MessageExtension {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
 pub enum OperationEnumeration {
     Create = 0x0000_0001,
     CreateKeyPair = 0x0000_0002,
@@ -2555,10 +2929,76 @@ pub enum OperationEnumeration {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
 pub enum ResultStatusEnumeration {
     Success = 0x0000_0000,
     OperationFailed = 0x0000_0001,
     OperationPending = 0x0000_0002,
     OperationUndone = 0x0000_0003,
 }
+
+/// The Query Function determines the information the client wants
+/// to obtain from the server in a `Query` operation.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
+pub enum QueryFunction {
+    QueryOperations = 0x0000_0001,
+    QueryObjects = 0x0000_0002,
+    QueryServerInformation = 0x0000_0003,
+    QueryApplicationNamespaces = 0x0000_0004,
+    QueryExtensionList = 0x0000_0005,
+    QueryExtensionMap = 0x0000_0006,
+    QueryAttestationTypes = 0x0000_0007,
+    QueryRNGs = 0x0000_0008,
+    QueryValidations = 0x0000_0009,
+    QueryProfiles = 0x0000_000A,
+    QueryCapabilities = 0x0000_000B,
+    QueryClientRegistrationMethods = 0x0000_000C,
+    QueryDefaultsInformation = 0x0000_000D,
+    QueryStorageProtectionMasks = 0x0000_000E,
+}
+
+/// The Server Information is a structure containing vendor-specific
+/// fields and/or substructures, describing the server.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ServerInformation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_name: Option<String>,
+    /// The build level of the server, i.e. whether the server
+    /// was built in FIPS mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_level: Option<String>,
+    /// The cluster information, i.e. the database backend
+    /// shared by the servers of the cluster
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster_info: Option<String>,
+}
+
+/// The Capability Information base object is a structure that contains
+/// details of the supported capabilities of the server.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct CapabilityInformation {
+    /// Whether the server supports streaming (multi-part) operations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streaming_capability: Option<bool>,
+    /// Whether the server supports asynchronous operations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asynchronous_capability: Option<bool>,
+    /// Whether the server supports attestation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attestation_capability: Option<bool>,
+    /// Whether the server supports the `Undo` batch error continuation option
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_undo_capability: Option<bool>,
+    /// Whether the server supports the `Continue` batch error continuation option
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_continue_capability: Option<bool>,
+    /// Whether the server supports quantum safe algorithms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantum_safe_capability: Option<bool>,
+}
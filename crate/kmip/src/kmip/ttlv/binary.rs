@@ -0,0 +1,524 @@
+//! Binary TTLV encoding of KMIP messages as specified in section 9.1 of the KMIP 2.1 specification
+//! and used by the KMIP native transport (port 5696).
+//!
+//! The `TTLV` tree produced by the `TTLV` serializer of this crate uses the serde names of the
+//! fields as tags, represents arrays as a structure of items tagged like the structure itself
+//! and enumerations by their name. This module converts from and to the binary representation
+//! where tags are 3-byte codes, arrays are repeated items and enumerations are 32-bit values.
+//!
+//! Some tags of this crate differ from the KMIP tag names:
+//!  - the `Header` and `Items` of a `Message` are encoded as `RequestHeader`/`ResponseHeader`
+//!    and as repeated `BatchItem`s,
+//!  - the `Object` of a request or response payload is encoded using the tag of its object type,
+//!  - the `VendorAttributes` of `Attributes` are encoded as repeated `Attribute`s,
+//!  - tags which only differ by their case (e.g. `UniqueBatchItemId`) are mapped to the KMIP tag.
+
+use std::str::FromStr;
+
+use num_bigint_dig::BigUint;
+use strum::IntoEnumIterator;
+use time::OffsetDateTime;
+
+use super::{error::TtlvError, ItemTypeEnumeration, TTLVEnumeration, TTLValue, TTLV};
+use crate::kmip::{
+    kmip_objects::ObjectType,
+    kmip_operations::{DerivationMethod, ErrorReason},
+    kmip_types::{
+        AsynchronousIndicator, AttestationType, BatchErrorContinuationOption, BlockCipherMode,
+        CancellationResult, CertificateRequestType, CertificateType, CryptographicAlgorithm,
+        DigitalSignatureAlgorithm, EncodingOption, HashingAlgorithm, KeyCompressionType,
+        KeyFormatType, KeyRoleType, KeyWrapType, LinkType, MaskGenerator, NameType,
+        ObjectGroupMember, OpaqueDataType, OperationEnumeration, PaddingMethod, QueryFunction,
+        RecommendedCurve, ResultStatusEnumeration, RevocationReasonEnumeration, SecretDataType,
+        SplitKeyMethod, StateEnumeration, Tag, TicketType, UniqueIdentifierEnumeration,
+        UsageLimitsUnit, ValidityIndicator, WrappingMethod,
+    },
+};
+
+type Result<T> = std::result::Result<T, TtlvError>;
+
+/// The length of the Tag, Type and Length header of a binary TTLV item
+pub const TTLV_HEADER_LENGTH: usize = 8;
+
+/// Tags of this crate which only differ from the KMIP tags by their case
+/// or by a non-alphanumeric character
+const TAG_ALIASES: [(&str, &str); 3] = [
+    (
+        "AsynchronousCorrelationValue",
+        "AsynchronousCorrelation_Value",
+    ),
+    ("Timestamp", "TimeStamp"),
+    ("UniqueBatchItemId", "UniqueBatchItemID"),
+];
+
+/// Associates the tags holding an enumeration to the Rust enumeration
+/// used to convert the enumeration names to their KMIP values and back
+macro_rules! enumerations {
+    ($($tag:literal => $enumeration:ty),* $(,)?) => {
+        /// Returns the KMIP value of the enumeration `name` held by an item tagged `tag`
+        pub(crate) fn enumeration_value(tag: &str, name: &str) -> Option<u32> {
+            match tag {
+                $($tag => <$enumeration>::iter()
+                    .find(|v| variant_name(v).as_deref() == Some(name))
+                    .map(|v| v as u32),)*
+                _ => None,
+            }
+        }
+
+        /// Returns the name of the enumeration `value` held by an item tagged `tag`
+        pub(crate) fn enumeration_name(tag: &str, value: u32) -> Option<String> {
+            match tag {
+                $($tag => <$enumeration>::iter()
+                    .find(|v| v.clone() as u32 == value)
+                    .and_then(|v| variant_name(&v)),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+enumerations! {
+    "AsynchronousIndicator" => AsynchronousIndicator,
+    "AttestationType" => AttestationType,
+    "BatchErrorContinuationOption" => BatchErrorContinuationOption,
+    "BlockCipherMode" => BlockCipherMode,
+    "CancellationResult" => CancellationResult,
+    "CertificateRequestType" => CertificateRequestType,
+    "CertificateType" => CertificateType,
+    "CryptographicAlgorithm" => CryptographicAlgorithm,
+    "DerivationMethod" => DerivationMethod,
+    "DigitalSignatureAlgorithm" => DigitalSignatureAlgorithm,
+    "EncodingOption" => EncodingOption,
+    "HashingAlgorithm" => HashingAlgorithm,
+    "KeyCompressionType" => KeyCompressionType,
+    "KeyFormatType" => KeyFormatType,
+    "KeyRoleType" => KeyRoleType,
+    "KeyWrapType" => KeyWrapType,
+    "LinkType" => LinkType,
+    "MaskGenerator" => MaskGenerator,
+    "MaskGeneratorHashingAlgorithm" => HashingAlgorithm,
+    "NameType" => NameType,
+    "ObjectGroupMember" => ObjectGroupMember,
+    "ObjectType" => ObjectType,
+    "OpaqueDataType" => OpaqueDataType,
+    "Operation" => OperationEnumeration,
+    "PaddingMethod" => PaddingMethod,
+    "QueryFunction" => QueryFunction,
+    "RecommendedCurve" => RecommendedCurve,
+    "ResultReason" => ErrorReason,
+    "ResultStatus" => ResultStatusEnumeration,
+    "RevocationReason" => RevocationReasonEnumeration,
+    "RevocationReasonCode" => RevocationReasonEnumeration,
+    "SecretDataType" => SecretDataType,
+    "SplitKeyMethod" => SplitKeyMethod,
+    "State" => StateEnumeration,
+    "TicketType" => TicketType,
+    "UniqueIdentifier" => UniqueIdentifierEnumeration,
+    "UsageLimitsUnit" => UsageLimitsUnit,
+    "ValidityIndicator" => ValidityIndicator,
+    "WrappingMethod" => WrappingMethod,
+}
+
+/// The serde name of an enumeration variant
+fn variant_name<T: serde::Serialize>(variant: &T) -> Option<String> {
+    serde_json::to_value(variant)
+        .ok()
+        .and_then(|v| v.as_str().map(ToOwned::to_owned))
+}
+
+impl TryFrom<u8> for ItemTypeEnumeration {
+    type Error = TtlvError;
+
+    fn try_from(value: u8) -> Result<Self> {
+        Ok(match value {
+            0x01 => Self::Structure,
+            0x02 => Self::Integer,
+            0x03 => Self::LongInteger,
+            0x04 => Self::BigInteger,
+            0x05 => Self::Enumeration,
+            0x06 => Self::Boolean,
+            0x07 => Self::TextString,
+            0x08 => Self::ByteString,
+            0x09 => Self::DateTime,
+            0x0A => Self::Interval,
+            0x0B => Self::DateTimeExtended,
+            x => {
+                return Err(TtlvError::new(&format!(
+                    "unknown TTLV item type: 0x{x:02X}"
+                )))
+            }
+        })
+    }
+}
+
+/// Encode a `TTLV` (usually a KMIP `Message`) to its binary representation
+pub fn to_bytes(ttlv: &TTLV) -> Result<Vec<u8>> {
+    let is_response = contains_tag(ttlv, "ResultStatus");
+    let mut items = to_wire(ttlv, None, &[], is_response)?;
+    if items.len() != 1 {
+        return Err(TtlvError::new(
+            "the root of a binary TTLV must be a single item",
+        ))
+    }
+    let mut bytes = Vec::new();
+    write_item(&items.remove(0), &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Decode a `TTLV` from its binary representation
+///
+/// The bytes must contain exactly one TTLV item
+pub fn from_bytes(bytes: &[u8]) -> Result<TTLV> {
+    let (wire, length) = read_item(bytes)?;
+    if length != bytes.len() {
+        return Err(TtlvError::new(&format!(
+            "{} trailing bytes after the binary TTLV item",
+            bytes.len() - length
+        )))
+    }
+    from_wire(&wire, None, &[])
+}
+
+/// Returns the total length of the binary TTLV item starting with `header`
+/// i.e. the length of the header plus the length of the value and its padding
+///
+/// This is used by stream readers to know how many bytes to read after the header
+pub fn item_length(header: &[u8; TTLV_HEADER_LENGTH]) -> Result<usize> {
+    let length = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let item_type = ItemTypeEnumeration::try_from(header[3])?;
+    let padded_length = match item_type {
+        ItemTypeEnumeration::Structure => length,
+        _ => length + padding(length),
+    };
+    Ok(TTLV_HEADER_LENGTH + padded_length)
+}
+
+/// The number of bytes required to pad `length` to a multiple of 8
+const fn padding(length: usize) -> usize {
+    (8 - length % 8) % 8
+}
+
+fn contains_tag(ttlv: &TTLV, tag: &str) -> bool {
+    ttlv.tag == tag
+        || match &ttlv.value {
+            TTLValue::Structure(children) => children.iter().any(|c| contains_tag(c, tag)),
+            _ => false,
+        }
+}
+
+/// An array is serialized by this crate as a structure
+/// which children are all tagged like the structure
+pub(crate) fn is_array(ttlv: &TTLV) -> bool {
+    match &ttlv.value {
+        TTLValue::Structure(children) => {
+            !children.is_empty() && children.iter().all(|c| c.tag == ttlv.tag)
+        }
+        _ => false,
+    }
+}
+
+/// Find the KMIP tag matching a tag of this crate
+fn kmip_tag(tag: &str) -> Result<Tag> {
+    let tag = TAG_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == tag)
+        .map_or(tag, |(_, kmip)| kmip);
+    Tag::from_str(tag)
+        .ok()
+        .or_else(|| Tag::iter().find(|t| t.to_string().eq_ignore_ascii_case(tag)))
+        .ok_or_else(|| TtlvError::new(&format!("no KMIP tag for the TTLV tag: {tag}")))
+}
+
+/// The KMIP tag of an item given its parent and siblings
+fn wire_tag(
+    ttlv: &TTLV,
+    parent: Option<&str>,
+    siblings: &[TTLV],
+    is_response: bool,
+) -> Result<Tag> {
+    Ok(match (parent, ttlv.tag.as_str()) {
+        (None, "Message") if is_response => Tag::ResponseMessage,
+        (None, "Message") => Tag::RequestMessage,
+        (Some("Message"), "Header") if is_response => Tag::ResponseHeader,
+        (Some("Message"), "Header") => Tag::RequestHeader,
+        (Some("Message"), "Items") => Tag::BatchItem,
+        (Some("Attributes"), "VendorAttributes") => Tag::Attribute,
+        (_, "Object") => {
+            let object_type = siblings
+                .iter()
+                .find_map(|s| match (&s.tag[..], &s.value) {
+                    ("ObjectType", TTLValue::Enumeration(TTLVEnumeration::Name(name))) => {
+                        Some(name.as_str())
+                    }
+                    _ => None,
+                })
+                .ok_or_else(|| {
+                    TtlvError::new("an Object must have an ObjectType sibling to be encoded")
+                })?;
+            kmip_tag(object_type)?
+        }
+        (_, tag) => kmip_tag(tag)?,
+    })
+}
+
+/// Convert a `TTLV` of this crate to the items of the binary encoding
+fn to_wire(
+    ttlv: &TTLV,
+    parent: Option<&str>,
+    siblings: &[TTLV],
+    is_response: bool,
+) -> Result<Vec<TTLV>> {
+    if let (true, TTLValue::Structure(elements)) = (is_array(ttlv), &ttlv.value) {
+        // arrays are flattened to repeated items
+        let mut items = Vec::with_capacity(elements.len());
+        for element in elements {
+            items.extend(to_wire(element, parent, siblings, is_response)?);
+        }
+        return Ok(items)
+    }
+    let tag = wire_tag(ttlv, parent, siblings, is_response)?;
+    let value = match (&ttlv.value, tag) {
+        (TTLValue::Structure(children), _) => {
+            let mut items = Vec::with_capacity(children.len());
+            for child in children {
+                items.extend(to_wire(child, Some(&ttlv.tag), children, is_response)?);
+            }
+            TTLValue::Structure(items)
+        }
+        (TTLValue::Enumeration(TTLVEnumeration::Name(name)), _) => {
+            let value = enumeration_value(&ttlv.tag, name).ok_or_else(|| {
+                TtlvError::new(&format!(
+                    "unknown enumeration value {name} for the tag {}",
+                    ttlv.tag
+                ))
+            })?;
+            TTLValue::Enumeration(TTLVEnumeration::Integer(value as i32))
+        }
+        (TTLValue::LongInteger(v), Tag::TimeStamp) => {
+            TTLValue::DateTime(OffsetDateTime::from_unix_timestamp(*v).map_err(time::Error::from)?)
+        }
+        (TTLValue::Integer(v), Tag::UniqueBatchItemID) => {
+            TTLValue::ByteString(v.to_be_bytes().to_vec())
+        }
+        (v, _) => v.clone(),
+    };
+    Ok(vec![TTLV {
+        tag: tag.to_string(),
+        value,
+    }])
+}
+
+/// The tag of this crate for a KMIP tag given its parent and siblings
+fn repo_tag(tag: &str, parent: Option<&str>, siblings: &[TTLV]) -> String {
+    match (parent, tag) {
+        (None, "RequestMessage" | "ResponseMessage") => "Message".to_owned(),
+        (Some("Message"), "RequestHeader" | "ResponseHeader") => "Header".to_owned(),
+        (Some("Message"), "BatchItem") => "Items".to_owned(),
+        (Some("Attributes"), "Attribute") => "VendorAttributes".to_owned(),
+        (_, tag)
+            if ObjectType::try_from(tag).is_ok()
+                && siblings.iter().any(|s| s.tag == "ObjectType") =>
+        {
+            "Object".to_owned()
+        }
+        (_, tag) => TAG_ALIASES
+            .iter()
+            .find(|(_, kmip)| *kmip == tag)
+            .map_or(tag, |(alias, _)| alias)
+            .to_owned(),
+    }
+}
+
+/// Convert an item of the binary encoding to a `TTLV` of this crate
+fn from_wire(ttlv: &TTLV, parent: Option<&str>, siblings: &[TTLV]) -> Result<TTLV> {
+    let tag = repo_tag(&ttlv.tag, parent, siblings);
+    let value = match &ttlv.value {
+        TTLValue::Structure(children) => {
+            let mut items: Vec<TTLV> = Vec::with_capacity(children.len());
+            for child in children {
+                items.push(from_wire(child, Some(&tag), children)?);
+            }
+            TTLValue::Structure(group_arrays(items))
+        }
+        TTLValue::Enumeration(TTLVEnumeration::Integer(i)) => TTLValue::Enumeration(
+            enumeration_name(&ttlv.tag, *i as u32)
+                .map_or(TTLVEnumeration::Integer(*i), TTLVEnumeration::Name),
+        ),
+        TTLValue::ByteString(bytes) if ttlv.tag == Tag::UniqueBatchItemID.to_string() => {
+            if bytes.len() > 4 {
+                return Err(TtlvError::new(
+                    "unique batch item IDs longer than 4 bytes are not supported",
+                ))
+            }
+            let mut be_bytes = [0_u8; 4];
+            be_bytes[4 - bytes.len()..].copy_from_slice(bytes);
+            TTLValue::Integer(i32::from_be_bytes(be_bytes))
+        }
+        v => v.clone(),
+    };
+    Ok(TTLV { tag, value })
+}
+
+/// Group the repeated items of a structure into arrays
+pub(crate) fn group_arrays(items: Vec<TTLV>) -> Vec<TTLV> {
+    let mut grouped: Vec<TTLV> = Vec::with_capacity(items.len());
+    let mut run: Vec<TTLV> = Vec::new();
+    for item in items {
+        if run.last().is_some_and(|last| last.tag != item.tag) {
+            grouped.push(close_run(std::mem::take(&mut run)));
+        }
+        run.push(item);
+    }
+    if !run.is_empty() {
+        grouped.push(close_run(run));
+    }
+    grouped
+}
+
+pub(crate) fn close_run(mut run: Vec<TTLV>) -> TTLV {
+    if run.len() == 1 {
+        return run.remove(0)
+    }
+    TTLV {
+        tag: run[0].tag.clone(),
+        value: TTLValue::Structure(run),
+    }
+}
+
+fn write_item(ttlv: &TTLV, bytes: &mut Vec<u8>) -> Result<()> {
+    let tag = Tag::from_str(&ttlv.tag)
+        .map_err(|_e| TtlvError::new(&format!("unknown KMIP tag: {}", ttlv.tag)))?;
+    bytes.extend_from_slice(&(tag as u32).to_be_bytes()[1..]);
+    let (item_type, value) = match &ttlv.value {
+        TTLValue::Structure(children) => {
+            let mut value = Vec::new();
+            for child in children {
+                write_item(child, &mut value)?;
+            }
+            (ItemTypeEnumeration::Structure, value)
+        }
+        TTLValue::Integer(v) => (ItemTypeEnumeration::Integer, v.to_be_bytes().to_vec()),
+        TTLValue::BitMask(v) => (ItemTypeEnumeration::Integer, v.to_be_bytes().to_vec()),
+        TTLValue::LongInteger(v) => (ItemTypeEnumeration::LongInteger, v.to_be_bytes().to_vec()),
+        TTLValue::BigInteger(v) => {
+            // big integers are sign extended to a multiple of 8 bytes
+            let mut value = v.to_bytes_be();
+            let mut pad = padding(value.len());
+            if pad == 0 && value[0] & 0x80 != 0 {
+                pad = 8;
+            }
+            value.splice(0..0, std::iter::repeat(0).take(pad));
+            (ItemTypeEnumeration::BigInteger, value)
+        }
+        TTLValue::Enumeration(TTLVEnumeration::Integer(v)) => {
+            (ItemTypeEnumeration::Enumeration, v.to_be_bytes().to_vec())
+        }
+        TTLValue::Enumeration(TTLVEnumeration::Name(name)) => {
+            return Err(TtlvError::new(&format!(
+                "the enumeration {name} of {} has no KMIP value",
+                ttlv.tag
+            )))
+        }
+        TTLValue::Boolean(v) => (
+            ItemTypeEnumeration::Boolean,
+            u64::from(*v).to_be_bytes().to_vec(),
+        ),
+        TTLValue::TextString(v) => (ItemTypeEnumeration::TextString, v.as_bytes().to_vec()),
+        TTLValue::ByteString(v) => (ItemTypeEnumeration::ByteString, v.clone()),
+        TTLValue::DateTime(v) => (
+            ItemTypeEnumeration::DateTime,
+            v.unix_timestamp().to_be_bytes().to_vec(),
+        ),
+        TTLValue::Interval(v) => (ItemTypeEnumeration::Interval, v.to_be_bytes().to_vec()),
+        TTLValue::DateTimeExtended(v) => (
+            ItemTypeEnumeration::DateTimeExtended,
+            ((v.unix_timestamp_nanos() / 1000) as i64)
+                .to_be_bytes()
+                .to_vec(),
+        ),
+    };
+    bytes.push(item_type as u8);
+    let length = u32::try_from(value.len())
+        .map_err(|_e| TtlvError::new(&format!("the value of {} is too long", ttlv.tag)))?;
+    bytes.extend_from_slice(&length.to_be_bytes());
+    bytes.extend_from_slice(&value);
+    bytes.resize(bytes.len() + padding(value.len()), 0);
+    Ok(())
+}
+
+/// Read a fixed size value
+fn fixed<const N: usize>(tag: Tag, value: &[u8]) -> Result<[u8; N]> {
+    value.try_into().map_err(|_e| {
+        TtlvError::new(&format!(
+            "invalid length {} for the value of {tag}, expected {N}",
+            value.len()
+        ))
+    })
+}
+
+/// Read a binary TTLV item; returns the item and the number of bytes read
+fn read_item(bytes: &[u8]) -> Result<(TTLV, usize)> {
+    let header: &[u8; TTLV_HEADER_LENGTH] = bytes
+        .get(..TTLV_HEADER_LENGTH)
+        .and_then(|h| h.try_into().ok())
+        .ok_or_else(|| TtlvError::new("truncated binary TTLV item header"))?;
+    let code = u32::from_be_bytes([0, header[0], header[1], header[2]]);
+    let tag = Tag::iter()
+        .find(|t| *t as u32 == code)
+        .ok_or_else(|| TtlvError::new(&format!("unknown KMIP tag: 0x{code:06X}")))?;
+    let item_length = item_length(header)?;
+    let length = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    if bytes.len() < item_length {
+        return Err(TtlvError::new(&format!(
+            "truncated binary TTLV item {tag}: expected {item_length} bytes, got {}",
+            bytes.len()
+        )))
+    }
+    let value = &bytes[TTLV_HEADER_LENGTH..TTLV_HEADER_LENGTH + length];
+    let value = match ItemTypeEnumeration::try_from(header[3])? {
+        ItemTypeEnumeration::Structure => {
+            let mut children = Vec::new();
+            let mut position = 0;
+            while position < value.len() {
+                let (child, read) = read_item(&value[position..])?;
+                children.push(child);
+                position += read;
+            }
+            TTLValue::Structure(children)
+        }
+        ItemTypeEnumeration::Integer => TTLValue::Integer(i32::from_be_bytes(fixed(tag, value)?)),
+        ItemTypeEnumeration::LongInteger => {
+            TTLValue::LongInteger(i64::from_be_bytes(fixed(tag, value)?))
+        }
+        ItemTypeEnumeration::BigInteger => TTLValue::BigInteger(BigUint::from_bytes_be(value)),
+        ItemTypeEnumeration::Enumeration => TTLValue::Enumeration(TTLVEnumeration::Integer(
+            i32::from_be_bytes(fixed(tag, value)?),
+        )),
+        ItemTypeEnumeration::Boolean => {
+            TTLValue::Boolean(u64::from_be_bytes(fixed(tag, value)?) != 0)
+        }
+        ItemTypeEnumeration::TextString => TTLValue::TextString(
+            String::from_utf8(value.to_vec())
+                .map_err(|e| TtlvError::new(&format!("invalid text string for {tag}: {e}")))?,
+        ),
+        ItemTypeEnumeration::ByteString => TTLValue::ByteString(value.to_vec()),
+        ItemTypeEnumeration::DateTime => TTLValue::DateTime(
+            OffsetDateTime::from_unix_timestamp(i64::from_be_bytes(fixed(tag, value)?))
+                .map_err(time::Error::from)?,
+        ),
+        ItemTypeEnumeration::Interval => TTLValue::Interval(u32::from_be_bytes(fixed(tag, value)?)),
+        ItemTypeEnumeration::DateTimeExtended => TTLValue::DateTimeExtended(
+            OffsetDateTime::from_unix_timestamp_nanos(
+                i128::from(i64::from_be_bytes(fixed(tag, value)?)) * 1000,
+            )
+            .map_err(time::Error::from)?,
+        ),
+    };
+    Ok((
+        TTLV {
+            tag: tag.to_string(),
+            value,
+        },
+        item_length,
+    ))
+}
@@ -37,6 +37,9 @@ pub struct TtlvDeserializer<'de> {
     index: usize,
     /// the inputs being deserialized, index gives the current input
     inputs: Inputs<'de>,
+    /// whether the TTLV was decoded from the binary encoding, in which
+    /// an array of a single item cannot be told apart from the item itself
+    binary: bool,
 }
 
 impl<'de> TtlvDeserializer<'de> {
@@ -46,6 +49,17 @@ impl<'de> TtlvDeserializer<'de> {
             deserializing: Deserializing::StructureValue,
             inputs: Inputs::Structure(vec![root]),
             index: 1,
+            binary: false,
+        }
+    }
+
+    /// A deserializer of a TTLV decoded from the binary encoding
+    /// (see `binary::from_bytes`)
+    #[must_use]
+    pub fn from_binary_ttlv(root: &'de TTLV) -> Self {
+        TtlvDeserializer {
+            binary: true,
+            ..Self::from_ttlv(root)
         }
     }
 
@@ -91,7 +105,25 @@ pub fn from_ttlv<'a, T>(s: &'a TTLV) -> Result<T>
 where
     T: Deserialize<'a>,
 {
-    let mut deserializer = TtlvDeserializer::from_ttlv(s);
+    deserialize_root(TtlvDeserializer::from_ttlv(s), s)
+}
+
+/// Parse a KMIP structure from a TTLV decoded from the binary encoding (see `binary::from_bytes`).
+///
+/// In the binary encoding, an array is a repetition of items: when a sequence is expected,
+/// a single item is accepted as an array of one item, and a structure is an array only when
+/// its children are tagged like it. The TTLVs of the JSON encoding must use `from_ttlv`.
+pub fn from_binary_ttlv<'a, T>(s: &'a TTLV) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    deserialize_root(TtlvDeserializer::from_binary_ttlv(s), s)
+}
+
+fn deserialize_root<'a, T>(mut deserializer: TtlvDeserializer<'a>, s: &'a TTLV) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
     let value = T::deserialize(&mut deserializer)?;
 
     // postfix the TTLV if it is a root object
@@ -154,6 +186,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
                     inputs: Inputs::Structure(elements.iter().collect::<Vec<&TTLV>>()), // can probably do better
                     // start at 0 because the Visit Map is going to increment first
                     index: 0,
+                    binary: self.binary,
                 };
                 if elements.is_empty() || child_tag == &elements[0].tag {
                     // in TTLV when the elements tags are identical to the parent tag,
@@ -175,12 +208,14 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
                 inputs: Inputs::Bytes(b),
                 // start at 0 because the Visit Map is going to increment first
                 index: 0,
+                binary: self.binary,
             }),
             TTLValue::BigInteger(e) => visitor.visit_seq(TtlvDeserializer {
                 deserializing: Deserializing::BigInt,
                 inputs: Inputs::BigInt(to_u32_digits(e)),
                 // start at 0 because the Visit Map is going to increment first
                 index: 0,
+                binary: self.binary,
             }),
             TTLValue::TextString(s) => visitor.visit_str(s),
             TTLValue::Boolean(b) => visitor.visit_bool(*b),
@@ -253,6 +288,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
         let child = &self.get_structure()?[self.index - 1].value;
         visitor.visit_i64(match child {
             TTLValue::LongInteger(v) => *v,
+            TTLValue::DateTime(v) => v.unix_timestamp(),
             x => return Err(TtlvError::custom(format!("Invalid type for i64: {x:?}"))),
         })
     }
@@ -330,6 +366,10 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
             TTLValue::LongInteger(v) => (*v)
                 .try_into()
                 .map_err(|_e| TtlvError::custom(format!("Invalid type for u64: {v:?}")))?,
+            TTLValue::DateTime(v) => v
+                .unix_timestamp()
+                .try_into()
+                .map_err(|_e| TtlvError::custom(format!("Invalid type for u64: {v:?}")))?,
             x => return Err(TtlvError::custom(format!("Invalid type for u64: {x:?}"))),
         })
     }
@@ -486,7 +526,8 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
                     .to_string(),
             )),
             Deserializing::StructureValue => {
-                let value = &self.get_structure()?[self.index - 1].value;
+                let child: &'de TTLV = self.get_structure()?[self.index - 1];
+                let value = &child.value;
                 match value {
                     TTLValue::ByteString(array) =>
                     // go down one level by deserializing the inner structure
@@ -496,6 +537,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
                             inputs: Inputs::Bytes(array),
                             // start at 0 because the Visit Map is going to increment first
                             index: 0,
+                            binary: self.binary,
                         })
                     }
                     TTLValue::BigInteger(big_int) =>
@@ -505,20 +547,35 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
                             deserializing: Deserializing::BigInt,
                             inputs: Inputs::BigInt(to_u32_digits(big_int)),
                             index: 0,
+                            binary: self.binary,
                         })
                     }
-                    TTLValue::Structure(array) => {
+                    TTLValue::Structure(array)
+                        if !self.binary
+                            || array.is_empty()
+                            || array.iter().any(|c| c.tag == child.tag) =>
+                    {
                         // go down one level by deserializing the inner structure
                         visitor.visit_seq(TtlvDeserializer {
                             deserializing: Deserializing::StructureValue,
                             inputs: Inputs::Structure(array.iter().collect::<Vec<&TTLV>>()), // can probably do better
                             // start at 0 because the Visit Map is going to increment first
                             index: 0,
+                            binary: self.binary,
                         })
                     }
-                    x => Err(TtlvError::custom(format!(
+                    x if !self.binary => Err(TtlvError::custom(format!(
                         "deserialize_seq. Invalid type for value: {x:?}"
                     ))),
+                    // A single item which is not wrapped in an array structure:
+                    // this is how the binary TTLV encoding represents
+                    // a sequence of one element
+                    _ => visitor.visit_seq(TtlvDeserializer {
+                        deserializing: Deserializing::StructureValue,
+                        inputs: Inputs::Structure(vec![child]),
+                        index: 0,
+                        binary: self.binary,
+                    }),
                 }
             }
             Deserializing::ByteString => {
@@ -571,6 +628,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut TtlvDeserializer<'de> {
                             inputs: Inputs::Structure(array.iter().collect::<Vec<&TTLV>>()), // can probably do better
                             // start at 0 because the Visit Map is going to increment first
                             index: 0,
+                            binary: self.binary,
                         })
                     }
                     x => Err(TtlvError::custom(format!(
@@ -0,0 +1,408 @@
+//! Conversion of KMIP 1.x (1.0 to 1.4) messages from and to the KMIP 2.1 `TTLV` representation
+//! of this crate.
+//!
+//! KMIP 1.x messages share their structure with KMIP 2.1 messages but carry attributes differently:
+//!  - the attributes of the Create, Create Key Pair, Register and Re-Key operations are held by
+//!    `TemplateAttribute`, `CommonTemplateAttribute`, `PrivateKeyTemplateAttribute` and
+//!    `PublicKeyTemplateAttribute` structures instead of `Attributes`, `CommonAttributes`,
+//!    `PrivateKeyAttributes` and `PublicKeyAttributes`,
+//!  - every attribute is an `Attribute` structure made of its name (e.g. `Cryptographic Algorithm`),
+//!    an optional index and its value; the Locate request, the Get Attributes response and key
+//!    values list these `Attribute`s directly,
+//!  - custom attributes are named `x-...` (or `y-...` when set by the server) where KMIP 2.1 uses
+//!    vendor attributes; those of the `cosmian` vendor are named `x-cosmian-...`,
+//!  - Get Attributes and Delete Attribute requests designate attributes by their `AttributeName`,
+//!  - attribute dates are `DateTime`s where this crate uses milliseconds since the epoch.
+//!
+//! Requests are upgraded to KMIP 2.1 before they are deserialized and responses
+//! are downgraded after they are serialized, so that a single server serves both generations.
+
+use time::OffsetDateTime;
+
+use super::{
+    binary::{close_run, enumeration_name, enumeration_value, group_arrays, is_array},
+    error::TtlvError,
+    TTLVEnumeration, TTLValue, TTLV,
+};
+use crate::kmip::extra::VENDOR_ID_COSMIAN;
+
+type Result<T> = std::result::Result<T, TtlvError>;
+
+/// The KMIP 1.x template structures and the KMIP 2.1 structures replacing them
+const TEMPLATE_ATTRIBUTES: [(&str, &str); 4] = [
+    ("TemplateAttribute", "Attributes"),
+    ("CommonTemplateAttribute", "CommonAttributes"),
+    ("PrivateKeyTemplateAttribute", "PrivateKeyAttributes"),
+    ("PublicKeyTemplateAttribute", "PublicKeyAttributes"),
+];
+
+/// The attributes which value is a date
+const DATE_ATTRIBUTES: [&str; 11] = [
+    "ActivationDate",
+    "ArchiveDate",
+    "CompromiseDate",
+    "CompromiseOccurrenceDate",
+    "DeactivationDate",
+    "DestroyDate",
+    "InitialDate",
+    "LastChangeDate",
+    "OriginalCreationDate",
+    "ProcessStartDate",
+    "ProtectStopDate",
+];
+
+/// The KMIP 1.x attribute names which are not derived from the tag by splitting it on capitals
+const ATTRIBUTE_NAMES: [(&str, &str); 3] = [
+    ("X509CertificateIdentifier", "X.509 Certificate Identifier"),
+    ("X509CertificateIssuer", "X.509 Certificate Issuer"),
+    ("X509CertificateSubject", "X.509 Certificate Subject"),
+];
+
+/// Upgrade a KMIP 1.x request to the KMIP 2.1 `TTLV` representation of this crate
+pub fn to_kmip_2_1(ttlv: &TTLV) -> Result<TTLV> {
+    upgrade(ttlv, None)
+}
+
+/// Downgrade a KMIP 2.1 `TTLV` response of this crate to its KMIP 1.x representation
+pub fn to_kmip_1_x(ttlv: &TTLV) -> Result<TTLV> {
+    let TTLValue::Structure(children) = &ttlv.value else {
+        return Ok(ttlv.clone())
+    };
+    let mut items = Vec::with_capacity(children.len());
+    for child in children {
+        if child.tag == "Attributes" {
+            // the attributes are listed in their parent structure
+            items.extend(downgrade_attributes(child)?);
+        } else if let Some((template, _)) = TEMPLATE_ATTRIBUTES
+            .iter()
+            .find(|(_, attributes)| *attributes == child.tag)
+        {
+            items.push(TTLV {
+                tag: (*template).to_owned(),
+                value: TTLValue::Structure(downgrade_attributes(child)?),
+            });
+        } else {
+            items.push(to_kmip_1_x(child)?);
+        }
+    }
+    Ok(TTLV {
+        tag: ttlv.tag.clone(),
+        value: TTLValue::Structure(items),
+    })
+}
+
+fn upgrade(ttlv: &TTLV, operation: Option<&str>) -> Result<TTLV> {
+    let TTLValue::Structure(children) = &ttlv.value else {
+        return Ok(ttlv.clone())
+    };
+    if let Some((_, tag)) = TEMPLATE_ATTRIBUTES
+        .iter()
+        .find(|(template, _)| *template == ttlv.tag)
+    {
+        // the names of templates, deprecated in KMIP 1.3, are not supported
+        let attributes = flatten(children)
+            .filter(|child| child.tag == "Attribute")
+            .map(upgrade_attribute)
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(TTLV {
+            tag: (*tag).to_owned(),
+            value: TTLValue::Structure(group_attributes(attributes)),
+        })
+    }
+
+    // the operation of a batch item drives the conversion of its payload
+    let operation = children
+        .iter()
+        .find_map(|child| match (child.tag.as_str(), &child.value) {
+            ("Operation", TTLValue::Enumeration(TTLVEnumeration::Name(name))) => {
+                Some(name.as_str())
+            }
+            _ => None,
+        })
+        .or(operation);
+    let is_payload = ttlv.tag == "RequestPayload";
+
+    let mut items = Vec::with_capacity(children.len());
+    let mut attributes = Vec::new();
+    let mut attributes_position = None;
+    for child in flatten(children) {
+        match child.tag.as_str() {
+            "Attribute"
+                if is_payload && matches!(operation, Some("AddAttribute" | "ModifyAttribute")) =>
+            {
+                let mut attribute = upgrade_attribute(child)?;
+                if attribute.tag == "VendorAttributes" {
+                    attribute.tag = "VendorAttribute".to_owned();
+                }
+                items.push(TTLV {
+                    tag: "NewAttribute".to_owned(),
+                    value: TTLValue::Structure(vec![attribute]),
+                });
+            }
+            "Attribute" => {
+                attributes_position.get_or_insert(items.len());
+                attributes.push(upgrade_attribute(child)?);
+            }
+            "AttributeName" if is_payload => items.push(attribute_reference(child)?),
+            // all the instances of the attribute are designated
+            "AttributeIndex" if is_payload => {}
+            _ => items.push(upgrade(child, operation)?),
+        }
+    }
+    if let Some(position) = attributes_position {
+        items.insert(
+            position,
+            TTLV {
+                tag: "Attributes".to_owned(),
+                value: TTLValue::Structure(group_attributes(attributes)),
+            },
+        );
+    }
+    Ok(TTLV {
+        tag: ttlv.tag.clone(),
+        value: TTLValue::Structure(group_arrays(items)),
+    })
+}
+
+/// The items of a structure, with the elements of its arrays listed individually
+fn flatten(children: &[TTLV]) -> impl Iterator<Item = &TTLV> {
+    children
+        .iter()
+        .flat_map(|child| match (&child.value, is_array(child)) {
+            (TTLValue::Structure(elements), true) => elements.iter().collect::<Vec<_>>(),
+            _ => vec![child],
+        })
+}
+
+/// Group the instances of the same attribute into an array
+fn group_attributes(attributes: Vec<TTLV>) -> Vec<TTLV> {
+    let mut grouped: Vec<Vec<TTLV>> = Vec::with_capacity(attributes.len());
+    for attribute in attributes {
+        match grouped.iter_mut().find(|run| run[0].tag == attribute.tag) {
+            Some(run) => run.push(attribute),
+            None => grouped.push(vec![attribute]),
+        }
+    }
+    grouped.into_iter().map(close_run).collect()
+}
+
+/// Convert a KMIP 1.x `Attribute` to the item of the KMIP 2.1 `Attributes` structure
+fn upgrade_attribute(attribute: &TTLV) -> Result<TTLV> {
+    let TTLValue::Structure(fields) = &attribute.value else {
+        return Err(TtlvError::new("a KMIP 1.x attribute must be a structure"))
+    };
+    let mut name = None;
+    let mut value = None;
+    for field in fields {
+        match (field.tag.as_str(), &field.value) {
+            ("AttributeName", TTLValue::TextString(n)) => name = Some(n.as_str()),
+            ("AttributeValue", v) => value = Some(v),
+            _ => {}
+        }
+    }
+    let name =
+        name.ok_or_else(|| TtlvError::new("a KMIP 1.x attribute must have an attribute name"))?;
+    let value = value
+        .ok_or_else(|| TtlvError::new(&format!("the KMIP 1.x attribute {name} has no value")))?;
+
+    if let Some((vendor_identification, attribute_name)) = vendor_attribute_name(name) {
+        let attribute_value = match value {
+            TTLValue::TextString(s) => s.as_bytes().to_vec(),
+            TTLValue::ByteString(bytes) => bytes.clone(),
+            v => {
+                return Err(TtlvError::new(&format!(
+                    "unsupported value for the custom attribute {name}: {v:?}"
+                )))
+            }
+        };
+        return Ok(TTLV {
+            tag: "VendorAttributes".to_owned(),
+            value: TTLValue::Structure(vec![
+                TTLV {
+                    tag: "VendorIdentification".to_owned(),
+                    value: TTLValue::TextString(vendor_identification.to_owned()),
+                },
+                TTLV {
+                    tag: "AttributeName".to_owned(),
+                    value: TTLValue::TextString(attribute_name.to_owned()),
+                },
+                TTLV {
+                    tag: "AttributeValue".to_owned(),
+                    value: TTLValue::ByteString(attribute_value),
+                },
+            ]),
+        })
+    }
+
+    let tag = attribute_tag(name);
+    let value = match value {
+        TTLValue::Enumeration(TTLVEnumeration::Integer(i)) => TTLValue::Enumeration(
+            enumeration_name(&tag, *i as u32)
+                .map_or(TTLVEnumeration::Integer(*i), TTLVEnumeration::Name),
+        ),
+        TTLValue::DateTime(date) => TTLValue::LongInteger(date.unix_timestamp() * 1000),
+        v => v.clone(),
+    };
+    Ok(TTLV { tag, value })
+}
+
+/// Convert a KMIP 1.x `AttributeName` to a KMIP 2.1 `AttributeReference`
+fn attribute_reference(attribute_name: &TTLV) -> Result<TTLV> {
+    let TTLValue::TextString(name) = &attribute_name.value else {
+        return Err(TtlvError::new("an attribute name must be a text string"))
+    };
+    let value = match vendor_attribute_name(name) {
+        Some((vendor_identification, attribute_name)) => TTLValue::Structure(vec![
+            TTLV {
+                tag: "VendorIdentification".to_owned(),
+                value: TTLValue::TextString(vendor_identification.to_owned()),
+            },
+            TTLV {
+                tag: "AttributeName".to_owned(),
+                value: TTLValue::TextString(attribute_name.to_owned()),
+            },
+        ]),
+        None => TTLValue::Enumeration(TTLVEnumeration::Name(attribute_tag(name))),
+    };
+    Ok(TTLV {
+        tag: "AttributeReference".to_owned(),
+        value,
+    })
+}
+
+/// Convert the items of a KMIP 2.1 `Attributes` structure to KMIP 1.x `Attribute`s
+fn downgrade_attributes(attributes: &TTLV) -> Result<Vec<TTLV>> {
+    let TTLValue::Structure(children) = &attributes.value else {
+        return Err(TtlvError::new(&format!(
+            "{} must be a structure",
+            attributes.tag
+        )))
+    };
+    let mut names: Vec<String> = Vec::with_capacity(children.len());
+    let mut items = Vec::with_capacity(children.len());
+    for attribute in flatten(children) {
+        let (name, value) = downgrade_attribute(attribute)?;
+        // the index distinguishes the instances of a multi-instance attribute
+        let index = names.iter().filter(|n| **n == name).count();
+        let mut fields = vec![TTLV {
+            tag: "AttributeName".to_owned(),
+            value: TTLValue::TextString(name.clone()),
+        }];
+        if index > 0 {
+            fields.push(TTLV {
+                tag: "AttributeIndex".to_owned(),
+                value: TTLValue::Integer(index as i32),
+            });
+        }
+        fields.push(TTLV {
+            tag: "AttributeValue".to_owned(),
+            value,
+        });
+        names.push(name);
+        items.push(TTLV {
+            tag: "Attribute".to_owned(),
+            value: TTLValue::Structure(fields),
+        });
+    }
+    Ok(items)
+}
+
+/// The KMIP 1.x name and value of an item of the KMIP 2.1 `Attributes` structure
+fn downgrade_attribute(attribute: &TTLV) -> Result<(String, TTLValue)> {
+    if attribute.tag == "VendorAttributes" {
+        let mut vendor_identification = None;
+        let mut attribute_name = None;
+        let mut attribute_value = None;
+        if let TTLValue::Structure(fields) = &attribute.value {
+            for field in fields {
+                match (field.tag.as_str(), &field.value) {
+                    ("VendorIdentification", TTLValue::TextString(v)) => {
+                        vendor_identification = Some(v.as_str());
+                    }
+                    ("AttributeName", TTLValue::TextString(n)) => attribute_name = Some(n.as_str()),
+                    ("AttributeValue", TTLValue::ByteString(bytes)) => {
+                        attribute_value = Some(bytes);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let (Some(vendor_identification), Some(attribute_name), Some(attribute_value)) =
+            (vendor_identification, attribute_name, attribute_value)
+        else {
+            return Err(TtlvError::new(&format!(
+                "invalid vendor attribute: {attribute:?}"
+            )))
+        };
+        let name = match vendor_identification {
+            "x" | "y" => format!("{vendor_identification}-{attribute_name}"),
+            _ => format!("x-{vendor_identification}-{attribute_name}"),
+        };
+        let value = String::from_utf8(attribute_value.clone()).map_or_else(
+            |_| TTLValue::ByteString(attribute_value.clone()),
+            TTLValue::TextString,
+        );
+        return Ok((name, value))
+    }
+
+    let value = match &attribute.value {
+        TTLValue::Enumeration(TTLVEnumeration::Name(name)) => {
+            let value = enumeration_value(&attribute.tag, name).ok_or_else(|| {
+                TtlvError::new(&format!(
+                    "unknown enumeration value {name} for the attribute {}",
+                    attribute.tag
+                ))
+            })?;
+            TTLValue::Enumeration(TTLVEnumeration::Integer(value as i32))
+        }
+        TTLValue::LongInteger(millis) if DATE_ATTRIBUTES.contains(&attribute.tag.as_str()) => {
+            TTLValue::DateTime(
+                OffsetDateTime::from_unix_timestamp(millis / 1000).map_err(time::Error::from)?,
+            )
+        }
+        v => v.clone(),
+    };
+    Ok((attribute_name(&attribute.tag), value))
+}
+
+/// The vendor identification and attribute name of a KMIP 1.x custom attribute name
+fn vendor_attribute_name(name: &str) -> Option<(&str, &str)> {
+    let (prefix, attribute_name) = name.split_once('-')?;
+    if !matches!(prefix, "x" | "y") {
+        return None
+    }
+    Some(
+        attribute_name
+            .strip_prefix(VENDOR_ID_COSMIAN)
+            .and_then(|name| name.strip_prefix('-'))
+            .map_or((prefix, attribute_name), |name| (VENDOR_ID_COSMIAN, name)),
+    )
+}
+
+/// The KMIP 2.1 tag of a KMIP 1.x attribute name, e.g. `CryptographicAlgorithm`
+/// for `Cryptographic Algorithm`
+fn attribute_tag(name: &str) -> String {
+    ATTRIBUTE_NAMES
+        .iter()
+        .find(|(_, attribute_name)| *attribute_name == name)
+        .map_or_else(
+            || name.chars().filter(char::is_ascii_alphanumeric).collect(),
+            |(tag, _)| (*tag).to_owned(),
+        )
+}
+
+/// The KMIP 1.x attribute name of a KMIP 2.1 tag, e.g. `Cryptographic Algorithm`
+/// for `CryptographicAlgorithm`
+fn attribute_name(tag: &str) -> String {
+    if let Some((_, name)) = ATTRIBUTE_NAMES.iter().find(|(t, _)| *t == tag) {
+        return (*name).to_owned()
+    }
+    let mut name = String::with_capacity(tag.len() + 4);
+    for (i, c) in tag.chars().filter(|c| *c != '_').enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            name.push(' ');
+        }
+        name.push(c);
+    }
+    name
+}
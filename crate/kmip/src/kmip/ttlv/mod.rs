@@ -1,5 +1,7 @@
+pub mod binary;
 pub mod deserializer;
 pub mod error;
+pub mod kmip_1_x;
 pub mod serializer;
 
 #[cfg(test)]
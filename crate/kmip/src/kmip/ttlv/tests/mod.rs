@@ -14,16 +14,26 @@ use crate::{
         },
         kmip_objects::{Object, ObjectType},
         kmip_operations::{
-            Create, DecryptResponse, Encrypt, ErrorReason, Import, ImportResponse, Locate,
-            LocateResponse, Operation,
+            Create, DecryptResponse, DeleteAttribute, DiscoverVersionsResponse, Encrypt,
+            ErrorReason, GetAttributesResponse, Import, ImportResponse, Locate, LocateResponse,
+            Operation, Query, QueryResponse, SetAttribute, Validate, ValidateResponse,
         },
         kmip_types::{
-            AsynchronousIndicator, AttestationType, Attributes, BatchErrorContinuationOption,
-            Credential, CryptographicAlgorithm, CryptographicUsageMask, KeyFormatType, Link,
-            LinkedObjectIdentifier, MessageExtension, Nonce, OperationEnumeration, ProtocolVersion,
-            ResultStatusEnumeration, UniqueIdentifier,
+            AsynchronousIndicator, AttestationType, Attribute, AttributeReference, Attributes,
+            BatchErrorContinuationOption, CapabilityInformation, Credential,
+            CryptographicAlgorithm, CryptographicUsageMask, KeyFormatType, Link, LinkType,
+            LinkedObjectIdentifier, MessageExtension, Name, NameType, Nonce, OperationEnumeration,
+            ProtocolVersion, QueryFunction, ResultStatusEnumeration, ServerInformation,
+            StateEnumeration, Tag, UniqueIdentifier, UsageLimits, UsageLimitsUnit,
+            ValidityIndicator, VendorAttribute,
+        },
+        ttlv::{
+            binary::{from_bytes, item_length, to_bytes},
+            deserializer::{from_binary_ttlv, from_ttlv},
+            kmip_1_x::{to_kmip_1_x, to_kmip_2_1},
+            serializer::to_ttlv,
+            TTLVEnumeration, TTLValue, TTLV,
         },
-        ttlv::{deserializer::from_ttlv, serializer::to_ttlv, TTLVEnumeration, TTLValue, TTLV},
     },
 };
 
@@ -749,6 +759,217 @@ fn test_issue_deserialize_object_with_empty_attributes() {
     }
 }
 
+#[test]
+fn test_attribute_operations() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let attributes = vec![
+        Attribute::ActivationDate(1_697_201_574_000),
+        Attribute::CryptographicUsageMask(
+            CryptographicUsageMask::Encrypt | CryptographicUsageMask::Decrypt,
+        ),
+        Attribute::Link(Link {
+            link_type: LinkType::ParentLink,
+            linked_object_identifier: LinkedObjectIdentifier::TextString("parent".to_owned()),
+        }),
+        Attribute::tags(["tag1"]).unwrap(),
+    ];
+    for attribute in attributes {
+        let set_attribute = SetAttribute {
+            unique_identifier: Some(UniqueIdentifier::TextString("uid".to_owned())),
+            new_attribute: attribute.clone(),
+        };
+        let set_attribute_: SetAttribute = serialize_deserialize(set_attribute).unwrap();
+        assert_eq!(set_attribute_.new_attribute, attribute);
+    }
+
+    let attribute_references = vec![
+        AttributeReference::Standard(Tag::ActivationDate),
+        AttributeReference::tags_reference(),
+    ];
+    let delete_attribute = DeleteAttribute {
+        unique_identifier: Some(UniqueIdentifier::TextString("uid".to_owned())),
+        current_attribute: None,
+        attribute_references: Some(attribute_references.clone()),
+    };
+    let delete_attribute_: DeleteAttribute = serialize_deserialize(delete_attribute).unwrap();
+    assert_eq!(
+        delete_attribute_.attribute_references,
+        Some(attribute_references)
+    );
+}
+
+#[test]
+fn test_lifecycle_attributes() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let attributes = Attributes {
+        object_type: Some(ObjectType::SymmetricKey),
+        comment: Some("a comment".to_owned()),
+        contact_information: Some("security@example.com".to_owned()),
+        description: Some("the database key".to_owned()),
+        destroy_date: Some(1_697_201_576_000),
+        extractable: Some(false),
+        fresh: Some(true),
+        initial_date: Some(1_697_201_574_000),
+        last_change_date: Some(1_697_201_575_000),
+        name: Some(vec![
+            Name {
+                name_value: "db-key".to_owned(),
+                name_type: NameType::UninterpretedTextString,
+            },
+            Name {
+                name_value: "https://example.com/keys/db".to_owned(),
+                name_type: NameType::URI,
+            },
+        ]),
+        never_extractable: Some(true),
+        object_group: Some("databases".to_owned()),
+        original_creation_date: Some(1_697_201_573_000),
+        process_start_date: Some(1_697_201_574_000),
+        protect_stop_date: Some(1_697_201_577_000),
+        sensitive: Some(true),
+        state: Some(StateEnumeration::Active),
+        usage_limits: Some(UsageLimits {
+            usage_limits_total: 1_000,
+            usage_limits_count: Some(10),
+            usage_limits_unit: UsageLimitsUnit::Object,
+        }),
+        ..Attributes::default()
+    };
+    let attributes_: Attributes = serialize_deserialize(attributes.clone()).unwrap();
+    assert_eq!(attributes_, attributes);
+    // the attributes may be sent over the binary protocol
+    let bytes = to_bytes(&to_ttlv(&attributes).unwrap()).unwrap();
+    let attributes_: Attributes = from_binary_ttlv(&from_bytes(&bytes).unwrap()).unwrap();
+    assert_eq!(attributes_, attributes);
+
+    for attribute in [
+        Attribute::Comment("a comment".to_owned()),
+        Attribute::ContactInformation("security@example.com".to_owned()),
+        Attribute::Description("the database key".to_owned()),
+        Attribute::Extractable(false),
+        Attribute::Name(Name {
+            name_value: "db-key".to_owned(),
+            name_type: NameType::UninterpretedTextString,
+        }),
+        Attribute::ObjectGroup("databases".to_owned()),
+        Attribute::ProcessStartDate(1_697_201_574_000),
+        Attribute::ProtectStopDate(1_697_201_577_000),
+        Attribute::Sensitive(true),
+        Attribute::UsageLimits(UsageLimits {
+            usage_limits_total: 1_000,
+            usage_limits_count: None,
+            usage_limits_unit: UsageLimitsUnit::Byte,
+        }),
+    ] {
+        let set_attribute = SetAttribute {
+            unique_identifier: Some(UniqueIdentifier::TextString("uid".to_owned())),
+            new_attribute: attribute.clone(),
+        };
+        let set_attribute_: SetAttribute = serialize_deserialize(set_attribute).unwrap();
+        assert_eq!(set_attribute_.new_attribute, attribute);
+    }
+}
+
+#[test]
+fn test_validate_operation() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let validate = Validate {
+        unique_identifiers: Some(vec![
+            UniqueIdentifier::TextString("leaf".to_owned()),
+            UniqueIdentifier::TextString("intermediate".to_owned()),
+        ]),
+        validity_date: Some(1_697_201_574_000),
+    };
+    let validate_: Validate = serialize_deserialize(validate).unwrap();
+    assert_eq!(
+        validate_.unique_identifiers,
+        Some(vec![
+            UniqueIdentifier::TextString("leaf".to_owned()),
+            UniqueIdentifier::TextString("intermediate".to_owned()),
+        ])
+    );
+    assert_eq!(validate_.validity_date, Some(1_697_201_574_000));
+
+    let response = ValidateResponse {
+        validity_indicator: ValidityIndicator::Invalid,
+        result_message: Some("the certificate leaf is revoked".to_owned()),
+    };
+    // the response may be sent over the binary protocol
+    let bytes = to_bytes(&to_ttlv(&response).unwrap()).unwrap();
+    let response_: ValidateResponse = from_binary_ttlv(&from_bytes(&bytes).unwrap()).unwrap();
+    assert_eq!(response_, response);
+}
+
+#[test]
+fn test_query_operations() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let query = Query {
+        query_function: Some(vec![
+            QueryFunction::QueryOperations,
+            QueryFunction::QueryServerInformation,
+        ]),
+    };
+    let query_: Query = serialize_deserialize(query).unwrap();
+    assert_eq!(
+        query_.query_function,
+        Some(vec![
+            QueryFunction::QueryOperations,
+            QueryFunction::QueryServerInformation,
+        ])
+    );
+
+    let query_response = QueryResponse {
+        operation: Some(vec![
+            OperationEnumeration::Create,
+            OperationEnumeration::Get,
+        ]),
+        object_type: Some(vec![ObjectType::SymmetricKey, ObjectType::Certificate]),
+        vendor_identification: Some("Cosmian".to_owned()),
+        server_information: Some(ServerInformation {
+            server_name: Some("Cosmian KMS".to_owned()),
+            build_level: Some("FIPS".to_owned()),
+            ..ServerInformation::default()
+        }),
+        capability_information: Some(vec![CapabilityInformation {
+            streaming_capability: Some(true),
+            asynchronous_capability: Some(false),
+            ..CapabilityInformation::default()
+        }]),
+    };
+    let query_response_: QueryResponse = serialize_deserialize(query_response).unwrap();
+    assert_eq!(
+        query_response_.operation,
+        Some(vec![
+            OperationEnumeration::Create,
+            OperationEnumeration::Get
+        ])
+    );
+    assert_eq!(
+        query_response_.object_type,
+        Some(vec![ObjectType::SymmetricKey, ObjectType::Certificate])
+    );
+    assert_eq!(
+        query_response_
+            .server_information
+            .and_then(|si| si.build_level),
+        Some("FIPS".to_owned())
+    );
+
+    let discover_versions_response = DiscoverVersionsResponse {
+        protocol_version: Some(vec![ProtocolVersion::default()]),
+    };
+    let discover_versions_response_: DiscoverVersionsResponse =
+        serialize_deserialize(discover_versions_response).unwrap();
+    assert_eq!(
+        discover_versions_response_.protocol_version,
+        Some(vec![ProtocolVersion::default()])
+    );
+}
+
 fn serialize_deserialize<T: DeserializeOwned + Serialize>(object: T) -> Result<T, KmipError> {
     // serialize
     let object_ttlv = to_ttlv(&object)?;
@@ -1156,3 +1377,445 @@ pub fn test_message_enforce_enum() {
         "response payload operation is not a response type operation (`Request`)".to_string()
     );
 }
+
+#[test]
+fn test_des_sequence_forms() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    struct Test {
+        ints: Vec<i32>,
+    }
+
+    fn test_ttlv(ints: TTLV) -> TTLV {
+        TTLV {
+            tag: "Test".to_string(),
+            value: TTLValue::Structure(vec![ints]),
+        }
+    }
+    fn int(tag: &str, i: i32) -> TTLV {
+        TTLV {
+            tag: tag.to_string(),
+            value: TTLValue::Integer(i),
+        }
+    }
+
+    // the JSON form of an array: a structure of items tagged like it
+    let array = test_ttlv(TTLV {
+        tag: "Ints".to_string(),
+        value: TTLValue::Structure(vec![int("Ints", 1), int("Ints", 2)]),
+    });
+    let expected = Test { ints: vec![1, 2] };
+    assert_eq!(from_ttlv::<Test>(&array).unwrap(), expected);
+    assert_eq!(from_binary_ttlv::<Test>(&array).unwrap(), expected);
+
+    // the JSON form also accepts a structure of items tagged differently
+    let untagged_array = test_ttlv(TTLV {
+        tag: "Ints".to_string(),
+        value: TTLValue::Structure(vec![int("Int", 1), int("Int", 2)]),
+    });
+    assert_eq!(from_ttlv::<Test>(&untagged_array).unwrap(), expected);
+
+    // a single item is an array of one item in the binary form only
+    let single_item = test_ttlv(int("Ints", 1));
+    assert!(from_ttlv::<Test>(&single_item).is_err());
+    assert_eq!(
+        from_binary_ttlv::<Test>(&single_item).unwrap(),
+        Test { ints: vec![1] }
+    );
+}
+
+#[test]
+fn test_binary_ttlv() {
+    // the protocol version structure of section 9.1.2 of the KMIP specification
+    let ttlv = TTLV {
+        tag: "ProtocolVersion".to_string(),
+        value: TTLValue::Structure(vec![
+            TTLV {
+                tag: "ProtocolVersionMajor".to_string(),
+                value: TTLValue::Integer(1),
+            },
+            TTLV {
+                tag: "ProtocolVersionMinor".to_string(),
+                value: TTLValue::Integer(4),
+            },
+        ]),
+    };
+    let bytes = to_bytes(&ttlv).unwrap();
+    assert_eq!(
+        hex::encode_upper(&bytes),
+        "420069010000002042006A0200000004000000010000000042006B02000000040000000400000000"
+    );
+    assert_eq!(from_bytes(&bytes).unwrap(), ttlv);
+    assert_eq!(
+        item_length(&bytes[..8].try_into().unwrap()).unwrap(),
+        bytes.len()
+    );
+
+    // truncated input
+    from_bytes(&bytes[..bytes.len() - 8]).unwrap_err();
+}
+
+#[test]
+fn test_binary_message_request() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let req = Message {
+        header: MessageHeader {
+            protocol_version: ProtocolVersion::default(),
+            batch_count: 3,
+            timestamp: Some(1_697_201_574),
+            ..Default::default()
+        },
+        items: vec![
+            MessageBatchItem::new(Operation::Query(Query {
+                query_function: Some(vec![QueryFunction::QueryOperations]),
+            })),
+            MessageBatchItem::new(Operation::Locate(Locate {
+                attributes: Attributes {
+                    cryptographic_algorithm: Some(CryptographicAlgorithm::AES),
+                    object_type: Some(ObjectType::SymmetricKey),
+                    ..Attributes::default()
+                },
+                ..Locate::default()
+            })),
+            MessageBatchItem::new(Operation::Import(Import {
+                unique_identifier: UniqueIdentifier::TextString("imported".to_string()),
+                object_type: ObjectType::SymmetricKey,
+                replace_existing: Some(true),
+                key_wrap_type: None,
+                attributes: Attributes::default(),
+                object: aes_key(&[42_u8; 32]),
+            })),
+        ],
+    };
+    let bytes = to_bytes(&to_ttlv(&req).unwrap()).unwrap();
+    // a KMIP request message structure
+    assert_eq!(&bytes[..4], &[0x42, 0x00, 0x78, 0x01]);
+    let req_: Message = from_binary_ttlv(&from_bytes(&bytes).unwrap()).unwrap();
+    assert_eq!(req_.header.timestamp, Some(1_697_201_574));
+    assert_eq!(req_.items.len(), 3);
+    let Operation::Query(query) = &req_.items[0].request_payload else {
+        panic!("not a query request payload: {:?}", req_.items[0]);
+    };
+    assert_eq!(
+        query.query_function,
+        Some(vec![QueryFunction::QueryOperations])
+    );
+    let Operation::Locate(locate) = &req_.items[1].request_payload else {
+        panic!("not a locate request payload: {:?}", req_.items[1]);
+    };
+    assert_eq!(
+        locate.attributes.cryptographic_algorithm,
+        Some(CryptographicAlgorithm::AES)
+    );
+    let Operation::Import(import) = &req_.items[2].request_payload else {
+        panic!("not an import request payload: {:?}", req_.items[2]);
+    };
+    assert_eq!(import.object, aes_key(&[42_u8; 32]));
+}
+
+#[test]
+fn test_binary_message_response() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let res = MessageResponse {
+        header: MessageResponseHeader {
+            timestamp: 1_697_201_574,
+            batch_count: 2,
+            ..Default::default()
+        },
+        items: vec![
+            MessageResponseBatchItem {
+                operation: Some(OperationEnumeration::Locate),
+                unique_batch_item_id: Some(1234),
+                response_payload: Some(Operation::LocateResponse(LocateResponse {
+                    located_items: Some(2),
+                    unique_identifiers: Some(vec![
+                        UniqueIdentifier::TextString("id_1".to_string()),
+                        UniqueIdentifier::TextString("id_2".to_string()),
+                    ]),
+                })),
+                message_extension: None,
+                result_status: ResultStatusEnumeration::Success,
+                result_reason: None,
+                result_message: None,
+                asynchronous_correlation_value: None,
+            },
+            MessageResponseBatchItem {
+                operation: Some(OperationEnumeration::Get),
+                unique_batch_item_id: None,
+                response_payload: None,
+                message_extension: None,
+                result_status: ResultStatusEnumeration::OperationFailed,
+                result_reason: Some(ErrorReason::Item_Not_Found),
+                result_message: Some("not found".to_string()),
+                asynchronous_correlation_value: None,
+            },
+        ],
+    };
+    let bytes = to_bytes(&to_ttlv(&res).unwrap()).unwrap();
+    // a KMIP response message structure
+    assert_eq!(&bytes[..4], &[0x42, 0x00, 0x7B, 0x01]);
+    let res_: MessageResponse = from_binary_ttlv(&from_bytes(&bytes).unwrap()).unwrap();
+    assert_eq!(res, res_);
+}
+
+/// A KMIP 1.x `Attribute` structure
+fn kmip_1_x_attribute(name: &str, value: TTLValue) -> TTLV {
+    TTLV {
+        tag: "Attribute".to_string(),
+        value: TTLValue::Structure(vec![
+            TTLV {
+                tag: "AttributeName".to_string(),
+                value: TTLValue::TextString(name.to_string()),
+            },
+            TTLV {
+                tag: "AttributeValue".to_string(),
+                value,
+            },
+        ]),
+    }
+}
+
+/// A KMIP 1.x batch item
+fn kmip_1_x_batch_item(operation: &str, payload: Vec<TTLV>) -> TTLV {
+    TTLV {
+        tag: "Items".to_string(),
+        value: TTLValue::Structure(vec![
+            TTLV {
+                tag: "Operation".to_string(),
+                value: TTLValue::Enumeration(TTLVEnumeration::Name(operation.to_string())),
+            },
+            TTLV {
+                tag: "RequestPayload".to_string(),
+                value: TTLValue::Structure(payload),
+            },
+        ]),
+    }
+}
+
+#[test]
+fn test_kmip_1_x_request() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let create = kmip_1_x_batch_item(
+        "Create",
+        vec![
+            TTLV {
+                tag: "ObjectType".to_string(),
+                value: TTLValue::Enumeration(TTLVEnumeration::Name("SymmetricKey".to_string())),
+            },
+            TTLV {
+                tag: "TemplateAttribute".to_string(),
+                value: TTLValue::Structure(vec![
+                    // AES
+                    kmip_1_x_attribute(
+                        "Cryptographic Algorithm",
+                        TTLValue::Enumeration(TTLVEnumeration::Integer(3)),
+                    ),
+                    kmip_1_x_attribute("Cryptographic Length", TTLValue::Integer(256)),
+                    // Encrypt | Decrypt
+                    kmip_1_x_attribute("Cryptographic Usage Mask", TTLValue::Integer(12)),
+                    kmip_1_x_attribute(
+                        "Activation Date",
+                        TTLValue::DateTime(
+                            OffsetDateTime::from_unix_timestamp(1_697_201_574).unwrap(),
+                        ),
+                    ),
+                    kmip_1_x_attribute("x-department", TTLValue::TextString("finance".to_string())),
+                ]),
+            },
+        ],
+    );
+    let get_attributes = kmip_1_x_batch_item(
+        "GetAttributes",
+        vec![
+            TTLV {
+                tag: "UniqueIdentifier".to_string(),
+                value: TTLValue::TextString("uid".to_string()),
+            },
+            TTLV {
+                tag: "AttributeName".to_string(),
+                value: TTLValue::TextString("Cryptographic Algorithm".to_string()),
+            },
+            TTLV {
+                tag: "AttributeName".to_string(),
+                value: TTLValue::TextString("x-cosmian-tag".to_string()),
+            },
+        ],
+    );
+    let request = TTLV {
+        tag: "Message".to_string(),
+        value: TTLValue::Structure(vec![
+            TTLV {
+                tag: "Header".to_string(),
+                value: TTLValue::Structure(vec![
+                    to_ttlv(&ProtocolVersion {
+                        protocol_version_major: 1,
+                        protocol_version_minor: 4,
+                    })
+                    .unwrap(),
+                    TTLV {
+                        tag: "BatchCount".to_string(),
+                        value: TTLValue::Integer(2),
+                    },
+                ]),
+            },
+            create,
+            get_attributes,
+        ]),
+    };
+
+    // through the binary encoding used by KMIP 1.x clients
+    let request = from_bytes(&to_bytes(&request).unwrap()).unwrap();
+    let message: Message = from_binary_ttlv(&to_kmip_2_1(&request).unwrap()).unwrap();
+    assert_eq!(message.header.protocol_version.protocol_version_major, 1);
+    assert_eq!(message.items.len(), 2);
+
+    let Operation::Create(create) = &message.items[0].request_payload else {
+        panic!("not a create request payload: {:?}", message.items[0]);
+    };
+    assert_eq!(create.object_type, ObjectType::SymmetricKey);
+    assert_eq!(
+        create.attributes.cryptographic_algorithm,
+        Some(CryptographicAlgorithm::AES)
+    );
+    assert_eq!(create.attributes.cryptographic_length, Some(256));
+    assert_eq!(
+        create.attributes.cryptographic_usage_mask,
+        Some(CryptographicUsageMask::Encrypt | CryptographicUsageMask::Decrypt)
+    );
+    assert_eq!(create.attributes.activation_date, Some(1_697_201_574_000));
+    assert_eq!(
+        create
+            .attributes
+            .get_vendor_attribute_value("x", "department"),
+        Some(b"finance".as_slice())
+    );
+
+    let Operation::GetAttributes(get_attributes) = &message.items[1].request_payload else {
+        panic!(
+            "not a get attributes request payload: {:?}",
+            message.items[1]
+        );
+    };
+    assert_eq!(
+        get_attributes.attribute_references,
+        Some(vec![
+            AttributeReference::Standard(Tag::CryptographicAlgorithm),
+            AttributeReference::tags_reference(),
+        ])
+    );
+}
+
+#[test]
+fn test_kmip_1_x_response() {
+    //log_init("info,hyper=info,reqwest=info");
+
+    let mut attributes = Attributes {
+        cryptographic_algorithm: Some(CryptographicAlgorithm::AES),
+        activation_date: Some(1_697_201_574_000),
+        link: Some(vec![
+            Link {
+                link_type: LinkType::PublicKeyLink,
+                linked_object_identifier: LinkedObjectIdentifier::TextString("pk".to_string()),
+            },
+            Link {
+                link_type: LinkType::ParentLink,
+                linked_object_identifier: LinkedObjectIdentifier::TextString("parent".to_string()),
+            },
+        ]),
+        ..Attributes::default()
+    };
+    attributes.add_vendor_attribute(VendorAttribute {
+        vendor_identification: "cosmian".to_string(),
+        attribute_name: "tag".to_string(),
+        attribute_value: b"[\"tag\"]".to_vec(),
+    });
+    let res = MessageResponse {
+        header: MessageResponseHeader {
+            protocol_version: ProtocolVersion {
+                protocol_version_major: 1,
+                protocol_version_minor: 2,
+            },
+            timestamp: 1_697_201_574,
+            batch_count: 1,
+            ..Default::default()
+        },
+        items: vec![MessageResponseBatchItem {
+            operation: Some(OperationEnumeration::GetAttributes),
+            unique_batch_item_id: None,
+            response_payload: Some(Operation::GetAttributesResponse(GetAttributesResponse {
+                unique_identifier: UniqueIdentifier::TextString("uid".to_string()),
+                attributes,
+            })),
+            message_extension: None,
+            result_status: ResultStatusEnumeration::Success,
+            result_reason: None,
+            result_message: None,
+            asynchronous_correlation_value: None,
+        }],
+    };
+    let ttlv = to_kmip_1_x(&to_ttlv(&res).unwrap()).unwrap();
+    let bytes = to_bytes(&ttlv).unwrap();
+    // a KMIP response message structure
+    assert_eq!(&bytes[..4], &[0x42, 0x00, 0x7B, 0x01]);
+
+    // the attributes are listed in the payload
+    let ttlv = from_bytes(&bytes).unwrap();
+    let TTLValue::Structure(message) = &ttlv.value else {
+        panic!("not a message: {ttlv:?}");
+    };
+    let TTLValue::Structure(item) = &message[1].value else {
+        panic!("not a batch item: {:?}", message[1]);
+    };
+    let payload = item
+        .iter()
+        .find(|t| t.tag == "ResponsePayload")
+        .expect("no response payload");
+    let TTLValue::Structure(payload) = &payload.value else {
+        panic!("not a response payload: {payload:?}");
+    };
+    assert_eq!(payload[0].tag, "UniqueIdentifier");
+    let TTLValue::Structure(attributes) = &payload[1].value else {
+        panic!("not an array of attributes: {:?}", payload[1]);
+    };
+    let names = attributes
+        .iter()
+        .map(|attribute| {
+            let TTLValue::Structure(fields) = &attribute.value else {
+                panic!("not an attribute: {attribute:?}");
+            };
+            let index = fields.iter().find_map(|f| match &f.value {
+                TTLValue::Integer(i) if f.tag == "AttributeIndex" => Some(*i),
+                _ => None,
+            });
+            match (&fields[0].value, &fields.last().unwrap().value) {
+                (TTLValue::TextString(name), value) => (name.clone(), index, value.clone()),
+                _ => panic!("not an attribute: {attribute:?}"),
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            (
+                "Activation Date".to_string(),
+                None,
+                TTLValue::DateTime(OffsetDateTime::from_unix_timestamp(1_697_201_574).unwrap())
+            ),
+            (
+                "Cryptographic Algorithm".to_string(),
+                None,
+                TTLValue::Enumeration(TTLVEnumeration::Integer(3))
+            ),
+            ("Link".to_string(), None, names[2].2.clone()),
+            ("Link".to_string(), Some(1), names[3].2.clone()),
+            (
+                "x-cosmian-tag".to_string(),
+                None,
+                TTLValue::TextString("[\"tag\"]".to_string())
+            ),
+        ]
+    );
+}
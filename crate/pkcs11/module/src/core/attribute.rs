@@ -22,14 +22,14 @@ use std::ffi::CString;
 
 use pkcs11_sys::{
     CKA_ALWAYS_AUTHENTICATE, CKA_ALWAYS_SENSITIVE, CKA_APPLICATION, CKA_CERTIFICATE_CATEGORY,
-    CKA_CERTIFICATE_TYPE, CKA_CLASS, CKA_COEFFICIENT, CKA_DECRYPT, CKA_EC_PARAMS, CKA_EC_POINT,
-    CKA_ENCRYPT, CKA_EXPONENT_1, CKA_EXPONENT_2, CKA_EXTRACTABLE, CKA_ID, CKA_ISSUER, CKA_KEY_TYPE,
-    CKA_LABEL, CKA_MODULUS, CKA_MODULUS_BITS, CKA_NEVER_EXTRACTABLE, CKA_PRIME_1, CKA_PRIME_2,
-    CKA_PRIVATE, CKA_PRIVATE_EXPONENT, CKA_PROFILE_ID, CKA_PUBLIC_EXPONENT, CKA_SENSITIVE,
-    CKA_SERIAL_NUMBER, CKA_SIGN, CKA_SIGN_RECOVER, CKA_SUBJECT, CKA_TOKEN, CKA_TRUSTED, CKA_UNWRAP,
-    CKA_VALUE, CKA_VALUE_LEN, CKA_VERIFY, CKA_VERIFY_RECOVER, CKA_WRAP, CKC_X_509, CK_ATTRIBUTE,
-    CK_ATTRIBUTE_TYPE, CK_BBOOL, CK_CERTIFICATE_CATEGORY, CK_CERTIFICATE_TYPE, CK_FALSE,
-    CK_KEY_TYPE, CK_OBJECT_CLASS, CK_PROFILE_ID, CK_TRUE, CK_ULONG,
+    CKA_CERTIFICATE_TYPE, CKA_CLASS, CKA_COEFFICIENT, CKA_DECRYPT, CKA_DERIVE, CKA_EC_PARAMS,
+    CKA_EC_POINT, CKA_ENCRYPT, CKA_EXPONENT_1, CKA_EXPONENT_2, CKA_EXTRACTABLE, CKA_ID, CKA_ISSUER,
+    CKA_KEY_TYPE, CKA_LABEL, CKA_MODULUS, CKA_MODULUS_BITS, CKA_NEVER_EXTRACTABLE, CKA_PRIME_1,
+    CKA_PRIME_2, CKA_PRIVATE, CKA_PRIVATE_EXPONENT, CKA_PROFILE_ID, CKA_PUBLIC_EXPONENT,
+    CKA_SENSITIVE, CKA_SERIAL_NUMBER, CKA_SIGN, CKA_SIGN_RECOVER, CKA_SUBJECT, CKA_TOKEN,
+    CKA_TRUSTED, CKA_UNWRAP, CKA_VALUE, CKA_VALUE_LEN, CKA_VERIFY, CKA_VERIFY_RECOVER, CKA_WRAP,
+    CKC_X_509, CK_ATTRIBUTE, CK_ATTRIBUTE_TYPE, CK_BBOOL, CK_CERTIFICATE_CATEGORY,
+    CK_CERTIFICATE_TYPE, CK_FALSE, CK_KEY_TYPE, CK_OBJECT_CLASS, CK_PROFILE_ID, CK_TRUE, CK_ULONG,
 };
 use strum_macros::Display;
 use tracing::{debug, trace};
@@ -46,6 +46,7 @@ pub enum AttributeType {
     Class,
     Coefficient,
     Decrypt,
+    Derive,
     EcParams,
     EcPoint,
     Encrypt,
@@ -93,6 +94,7 @@ impl TryFrom<CK_ATTRIBUTE_TYPE> for AttributeType {
             CKA_CLASS => Ok(AttributeType::Class),
             CKA_COEFFICIENT => Ok(AttributeType::Coefficient),
             CKA_DECRYPT => Ok(AttributeType::Decrypt),
+            CKA_DERIVE => Ok(AttributeType::Derive),
             CKA_EC_PARAMS => Ok(AttributeType::EcParams),
             CKA_EC_POINT => Ok(AttributeType::EcPoint),
             CKA_ENCRYPT => Ok(AttributeType::Encrypt),
@@ -140,6 +142,7 @@ pub enum Attribute {
     Class(CK_OBJECT_CLASS),
     Coefficient(Vec<u8>),
     Decrypt(bool),
+    Derive(bool),
     EcParams(Vec<u8>),
     EcPoint(Vec<u8>),
     Encrypt(bool),
@@ -186,6 +189,7 @@ impl Attribute {
             Attribute::Class(_) => AttributeType::Class,
             Attribute::Coefficient(_) => AttributeType::Coefficient,
             Attribute::Decrypt(_) => AttributeType::Decrypt,
+            Attribute::Derive(_) => AttributeType::Derive,
             Attribute::EcParams(_) => AttributeType::EcParams,
             Attribute::EcPoint(_) => AttributeType::EcPoint,
             Attribute::Encrypt(_) => AttributeType::Encrypt,
@@ -227,6 +231,7 @@ impl Attribute {
             Attribute::AlwaysAuthenticate(bool)
             | Attribute::AlwaysSensitive(bool)
             | Attribute::Decrypt(bool)
+            | Attribute::Derive(bool)
             | Attribute::Encrypt(bool)
             | Attribute::Extractable(bool)
             | Attribute::NeverExtractable(bool)
@@ -311,6 +316,7 @@ impl TryFrom<CK_ATTRIBUTE> for Attribute {
             ))),
             AttributeType::Coefficient => Ok(Attribute::Coefficient(val.to_vec())),
             AttributeType::Decrypt => Ok(Attribute::Decrypt(try_u8_into_bool(val)?)),
+            AttributeType::Derive => Ok(Attribute::Derive(try_u8_into_bool(val)?)),
             AttributeType::EcParams => Ok(Attribute::EcParams(val.to_vec())),
             AttributeType::EcPoint => Ok(Attribute::EcPoint(val.to_vec())),
             AttributeType::Encrypt => Ok(Attribute::Encrypt(try_u8_into_bool(val)?)),
@@ -368,6 +374,12 @@ fn try_u8_into_bool(slice: &[u8]) -> MResult<bool> {
     Ok(!matches!(as_byte, 0u8))
 }
 
+/// DER encoded OIDs of the NIST curves
+const EC_PARAMS_P224: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x21];
+const EC_PARAMS_P256: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+const EC_PARAMS_P384: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22];
+const EC_PARAMS_P521: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x23];
+
 #[derive(Debug, Clone)]
 pub struct Attributes(Vec<Attribute>);
 
@@ -387,6 +399,52 @@ impl Attributes {
         }
     }
 
+    #[must_use]
+    pub fn get_key_type(&self) -> Option<CK_KEY_TYPE> {
+        match self.get(AttributeType::KeyType) {
+            Some(Attribute::KeyType(key_type)) => Some(*key_type),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn get_label(&self) -> Option<String> {
+        match self.get(AttributeType::Label) {
+            Some(Attribute::Label(label)) => Some(label.clone()),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn get_modulus_bits(&self) -> Option<CK_ULONG> {
+        match self.get(AttributeType::ModulusBits) {
+            Some(Attribute::ModulusBits(bits)) => Some(*bits),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn get_value(&self) -> Option<&[u8]> {
+        match self.get(AttributeType::Value) {
+            Some(Attribute::Value(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The size in bits of the NIST curve designated by the `CKA_EC_PARAMS` named curve OID
+    pub fn get_ec_curve_bits(&self) -> MResult<Option<usize>> {
+        match self.get(AttributeType::EcParams) {
+            Some(Attribute::EcParams(params)) => match params.as_slice() {
+                EC_PARAMS_P224 => Ok(Some(224)),
+                EC_PARAMS_P256 => Ok(Some(256)),
+                EC_PARAMS_P384 => Ok(Some(384)),
+                EC_PARAMS_P521 => Ok(Some(521)),
+                _ => Err(MError::AttributeValueInvalid(AttributeType::EcParams)),
+            },
+            _ => Ok(None),
+        }
+    }
+
     /// Ensure that the attributes contain a `CKC_X_509` certificate request or None.
     pub fn ensure_X509_or_none(&self) -> MResult<()> {
         match self.get(AttributeType::CertificateType) {
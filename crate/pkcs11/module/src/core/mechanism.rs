@@ -18,10 +18,15 @@
 // limitations under the License.
 
 use pkcs11_sys::{
-    CKG_MGF1_SHA1, CKG_MGF1_SHA224, CKG_MGF1_SHA256, CKG_MGF1_SHA384, CKG_MGF1_SHA512, CKM_ECDSA,
-    CKM_RSA_PKCS, CKM_RSA_PKCS_PSS, CKM_SHA1_RSA_PKCS, CKM_SHA224, CKM_SHA256, CKM_SHA256_RSA_PKCS,
-    CKM_SHA384, CKM_SHA384_RSA_PKCS, CKM_SHA512, CKM_SHA512_RSA_PKCS, CKM_SHA_1, CK_MECHANISM,
-    CK_MECHANISM_TYPE, CK_RSA_PKCS_PSS_PARAMS,
+    CKF_DECRYPT, CKF_EC_F_P, CKF_EC_NAMEDCURVE, CKF_EC_UNCOMPRESS, CKF_ENCRYPT,
+    CKF_GENERATE_KEY_PAIR, CKF_SIGN, CKF_UNWRAP, CKF_VERIFY, CKF_WRAP, CKG_MGF1_SHA1,
+    CKG_MGF1_SHA224, CKG_MGF1_SHA256, CKG_MGF1_SHA384, CKG_MGF1_SHA512, CKM_AES_CBC_PAD,
+    CKM_AES_GCM, CKM_AES_KEY_WRAP_KWP, CKM_ECDSA, CKM_EC_KEY_PAIR_GEN, CKM_RSA_AES_KEY_WRAP,
+    CKM_RSA_PKCS, CKM_RSA_PKCS_KEY_PAIR_GEN, CKM_RSA_PKCS_OAEP, CKM_RSA_PKCS_PSS,
+    CKM_SHA1_RSA_PKCS, CKM_SHA224, CKM_SHA256, CKM_SHA256_RSA_PKCS, CKM_SHA384,
+    CKM_SHA384_RSA_PKCS, CKM_SHA512, CKM_SHA512_RSA_PKCS, CKM_SHA_1, CK_FLAGS, CK_GCM_PARAMS,
+    CK_MECHANISM, CK_MECHANISM_TYPE, CK_RSA_AES_KEY_WRAP_PARAMS, CK_RSA_PKCS_MGF_TYPE,
+    CK_RSA_PKCS_OAEP_PARAMS, CK_RSA_PKCS_PSS_PARAMS,
 };
 
 use crate::{
@@ -39,10 +44,68 @@ pub const SUPPORTED_SIGNATURE_MECHANISMS: &[CK_MECHANISM_TYPE] = &[
     CKM_RSA_PKCS_PSS,
 ];
 
+/// All the mechanisms returned by `C_GetMechanismList`
+pub const SUPPORTED_MECHANISMS: &[CK_MECHANISM_TYPE] = &[
+    CKM_RSA_PKCS_KEY_PAIR_GEN,
+    CKM_RSA_PKCS,
+    CKM_SHA1_RSA_PKCS,
+    CKM_SHA256_RSA_PKCS,
+    CKM_SHA384_RSA_PKCS,
+    CKM_SHA512_RSA_PKCS,
+    CKM_RSA_PKCS_PSS,
+    CKM_RSA_PKCS_OAEP,
+    CKM_RSA_AES_KEY_WRAP,
+    CKM_EC_KEY_PAIR_GEN,
+    CKM_ECDSA,
+    CKM_AES_GCM,
+    CKM_AES_CBC_PAD,
+    CKM_AES_KEY_WRAP_KWP,
+];
+
+/// The flags returned by `C_GetMechanismInfo` for a supported mechanism
+#[must_use]
+pub fn mechanism_flags(mechanism: CK_MECHANISM_TYPE) -> Option<CK_FLAGS> {
+    let ec_flags = CKF_EC_F_P | CKF_EC_NAMEDCURVE | CKF_EC_UNCOMPRESS;
+    match mechanism {
+        CKM_RSA_PKCS_KEY_PAIR_GEN => Some(CKF_GENERATE_KEY_PAIR),
+        CKM_RSA_PKCS => Some(CKF_ENCRYPT | CKF_DECRYPT | CKF_SIGN | CKF_VERIFY),
+        CKM_SHA1_RSA_PKCS | CKM_SHA256_RSA_PKCS | CKM_SHA384_RSA_PKCS | CKM_SHA512_RSA_PKCS
+        | CKM_RSA_PKCS_PSS => Some(CKF_SIGN | CKF_VERIFY),
+        CKM_RSA_PKCS_OAEP => Some(CKF_ENCRYPT | CKF_DECRYPT | CKF_WRAP | CKF_UNWRAP),
+        CKM_RSA_AES_KEY_WRAP => Some(CKF_WRAP | CKF_UNWRAP),
+        CKM_EC_KEY_PAIR_GEN => Some(CKF_GENERATE_KEY_PAIR | ec_flags),
+        CKM_ECDSA => Some(CKF_SIGN | CKF_VERIFY | ec_flags),
+        CKM_AES_GCM | CKM_AES_CBC_PAD => Some(CKF_ENCRYPT | CKF_DECRYPT),
+        // the KMS wraps keys with AES keys using RFC 5649 only
+        CKM_AES_KEY_WRAP_KWP => Some(CKF_ENCRYPT | CKF_DECRYPT | CKF_WRAP | CKF_UNWRAP),
+        _ => None,
+    }
+}
+
 #[derive(Debug)]
 pub enum Mechanism {
+    AesCbcPad {
+        iv: [u8; 16],
+    },
+    AesGcm {
+        iv: Vec<u8>,
+        aad: Vec<u8>,
+        tag_bits: u64,
+    },
+    AesKeyWrapPad,
     Ecdsa,
+    EcKeyPairGen,
+    RsaAesKeyWrap {
+        aes_key_bits: u64,
+        digest_algorithm: DigestType,
+        mask_generation_function: DigestType,
+    },
     RsaPkcs,
+    RsaPkcsKeyPairGen,
+    RsaPkcsOaep {
+        digest_algorithm: DigestType,
+        mask_generation_function: DigestType,
+    },
     RsaPkcsSha1,
     RsaPkcsSha256,
     RsaPkcsSha384,
@@ -57,77 +120,186 @@ pub enum Mechanism {
 #[allow(clippy::missing_safety_doc)]
 pub unsafe fn parse_mechanism(mechanism: CK_MECHANISM) -> Result<Mechanism, MError> {
     match mechanism.mechanism {
+        CKM_AES_CBC_PAD => {
+            let iv: [u8; 16] = unsafe { read_parameter(&mechanism) }?;
+            Ok(Mechanism::AesCbcPad { iv })
+        }
+        CKM_AES_GCM => {
+            let params: CK_GCM_PARAMS = unsafe { read_parameter(&mechanism) }?;
+            let iv_ptr = params.pIv;
+            let iv_len = params.ulIvLen as usize;
+            let aad_ptr = params.pAAD;
+            let aad_len = params.ulAADLen as usize;
+            let tag_bits = params.ulTagBits;
+            if iv_ptr.is_null() || iv_len == 0 {
+                tracing::error!("AES GCM: pIv null or empty");
+                return Err(MError::MechanismInvalid(mechanism.mechanism));
+            }
+            let iv = unsafe { std::slice::from_raw_parts(iv_ptr, iv_len) }.to_vec();
+            let aad = if aad_ptr.is_null() || aad_len == 0 {
+                vec![]
+            } else {
+                unsafe { std::slice::from_raw_parts(aad_ptr, aad_len) }.to_vec()
+            };
+            #[allow(clippy::unnecessary_cast)]
+            Ok(Mechanism::AesGcm {
+                iv,
+                aad,
+                //  Cast needed on windows
+                tag_bits: tag_bits as u64,
+            })
+        }
+        CKM_AES_KEY_WRAP_KWP => {
+            // the alternative initial value of RFC 5649 is not supported
+            let parameter_ptr = mechanism.pParameter;
+            if !parameter_ptr.is_null() {
+                tracing::error!("AES KWP: pParameter not supported");
+                return Err(MError::MechanismInvalid(mechanism.mechanism));
+            }
+            Ok(Mechanism::AesKeyWrapPad)
+        }
         CKM_ECDSA => Ok(Mechanism::Ecdsa),
+        CKM_EC_KEY_PAIR_GEN => Ok(Mechanism::EcKeyPairGen),
         CKM_RSA_PKCS => Ok(Mechanism::RsaPkcs),
+        CKM_RSA_PKCS_KEY_PAIR_GEN => Ok(Mechanism::RsaPkcsKeyPairGen),
         CKM_SHA1_RSA_PKCS => Ok(Mechanism::RsaPkcsSha1),
         CKM_SHA256_RSA_PKCS => Ok(Mechanism::RsaPkcsSha256),
         CKM_SHA384_RSA_PKCS => Ok(Mechanism::RsaPkcsSha384),
         CKM_SHA512_RSA_PKCS => Ok(Mechanism::RsaPkcsSha512),
         CKM_RSA_PKCS_PSS => {
-            //  Bind to locals to prevent unaligned reads https://github.com/rust-lang/rust/issues/82523
-            let mechanism_type = mechanism.mechanism;
-            let parameter_ptr = mechanism.pParameter;
-            let parameter_len = mechanism.ulParameterLen;
-            if parameter_ptr.is_null() {
-                tracing::error!("pParameter null");
-                return Err(MError::MechanismInvalid(mechanism_type));
-            }
-            if (parameter_len as usize) != std::mem::size_of::<CK_RSA_PKCS_PSS_PARAMS>() {
-                tracing::error!(
-                    "pParameter incorrect: {} != {}",
-                    parameter_len,
-                    std::mem::size_of::<CK_RSA_PKCS_PSS_PARAMS>()
-                );
-                return Err(MError::MechanismInvalid(mechanism_type));
-            }
-            //  TODO(kcking): check alignment as well?
-            let params: CK_RSA_PKCS_PSS_PARAMS =
-                unsafe { parameter_ptr.cast::<CK_RSA_PKCS_PSS_PARAMS>().read() };
+            let params: CK_RSA_PKCS_PSS_PARAMS = unsafe { read_parameter(&mechanism) }?;
             let mgf = params.mgf;
             let hash_alg = params.hashAlg;
             let salt_len = params.sLen;
 
-            let mgf = match mgf {
-                CKG_MGF1_SHA1 => DigestType::Sha1,
-                CKG_MGF1_SHA224 => DigestType::Sha224,
-                CKG_MGF1_SHA256 => DigestType::Sha256,
-                CKG_MGF1_SHA384 => DigestType::Sha384,
-                CKG_MGF1_SHA512 => DigestType::Sha512,
-                _ => {
-                    tracing::error!("Unsupported mgf: {}", mgf);
-                    return Err(MError::MechanismInvalid(mechanism_type));
-                }
-            };
-
-            let hash_alg = match hash_alg {
-                CKM_SHA_1 => DigestType::Sha1,
-                CKM_SHA224 => DigestType::Sha224,
-                CKM_SHA256 => DigestType::Sha256,
-                CKM_SHA384 => DigestType::Sha384,
-                CKM_SHA512 => DigestType::Sha512,
-                _ => {
-                    tracing::error!("Unsupported hashAlg: {}", hash_alg);
-                    return Err(MError::MechanismInvalid(mechanism_type));
-                }
-            };
-
             #[allow(clippy::unnecessary_cast)]
             Ok(Mechanism::RsaPss {
-                digest_algorithm: hash_alg,
-                mask_generation_function: mgf,
+                digest_algorithm: parse_hash_algorithm(mechanism.mechanism, hash_alg)?,
+                mask_generation_function: parse_mgf(mechanism.mechanism, mgf)?,
                 //  Cast needed on windows
                 salt_length: salt_len as u64,
             })
         }
+        CKM_RSA_PKCS_OAEP => {
+            let params: CK_RSA_PKCS_OAEP_PARAMS = unsafe { read_parameter(&mechanism) }?;
+            let (digest_algorithm, mask_generation_function) =
+                parse_oaep_params(mechanism.mechanism, &params)?;
+            Ok(Mechanism::RsaPkcsOaep {
+                digest_algorithm,
+                mask_generation_function,
+            })
+        }
+        CKM_RSA_AES_KEY_WRAP => {
+            let params: CK_RSA_AES_KEY_WRAP_PARAMS = unsafe { read_parameter(&mechanism) }?;
+            let aes_key_bits = params.ulAESKeyBits;
+            let oaep_params_ptr = params.pOAEPParams;
+            if oaep_params_ptr.is_null() {
+                tracing::error!("pOAEPParams null");
+                return Err(MError::MechanismInvalid(mechanism.mechanism));
+            }
+            let oaep_params = unsafe { oaep_params_ptr.read_unaligned() };
+            let (digest_algorithm, mask_generation_function) =
+                parse_oaep_params(mechanism.mechanism, &oaep_params)?;
+            #[allow(clippy::unnecessary_cast)]
+            Ok(Mechanism::RsaAesKeyWrap {
+                //  Cast needed on windows
+                aes_key_bits: aes_key_bits as u64,
+                digest_algorithm,
+                mask_generation_function,
+            })
+        }
         _ => Err(MError::MechanismInvalid(mechanism.mechanism)),
     }
 }
 
+/// Read the parameter of the mechanism, checking that it has the size of `T`
+unsafe fn read_parameter<T>(mechanism: &CK_MECHANISM) -> Result<T, MError> {
+    //  Bind to locals to prevent unaligned reads https://github.com/rust-lang/rust/issues/82523
+    let mechanism_type = mechanism.mechanism;
+    let parameter_ptr = mechanism.pParameter;
+    let parameter_len = mechanism.ulParameterLen;
+    if parameter_ptr.is_null() {
+        tracing::error!("pParameter null");
+        return Err(MError::MechanismInvalid(mechanism_type));
+    }
+    if (parameter_len as usize) != std::mem::size_of::<T>() {
+        tracing::error!(
+            "pParameter incorrect: {} != {}",
+            parameter_len,
+            std::mem::size_of::<T>()
+        );
+        return Err(MError::MechanismInvalid(mechanism_type));
+    }
+    Ok(unsafe { parameter_ptr.cast::<T>().read_unaligned() })
+}
+
+/// Parse the hash and mask generation functions of the OAEP parameters.
+///
+/// Encoding parameters (a.k.a. OAEP label) are not supported.
+fn parse_oaep_params(
+    mechanism_type: CK_MECHANISM_TYPE,
+    params: &CK_RSA_PKCS_OAEP_PARAMS,
+) -> Result<(DigestType, DigestType), MError> {
+    let source_data_len = params.ulSourceDataLen;
+    if source_data_len != 0 {
+        tracing::error!(
+            "Unsupported OAEP source data of length: {}",
+            source_data_len
+        );
+        return Err(MError::MechanismInvalid(mechanism_type));
+    }
+    Ok((
+        parse_hash_algorithm(mechanism_type, params.hashAlg)?,
+        parse_mgf(mechanism_type, params.mgf)?,
+    ))
+}
+
+fn parse_hash_algorithm(
+    mechanism_type: CK_MECHANISM_TYPE,
+    hash_alg: CK_MECHANISM_TYPE,
+) -> Result<DigestType, MError> {
+    match hash_alg {
+        CKM_SHA_1 => Ok(DigestType::Sha1),
+        CKM_SHA224 => Ok(DigestType::Sha224),
+        CKM_SHA256 => Ok(DigestType::Sha256),
+        CKM_SHA384 => Ok(DigestType::Sha384),
+        CKM_SHA512 => Ok(DigestType::Sha512),
+        _ => {
+            tracing::error!("Unsupported hashAlg: {}", hash_alg);
+            Err(MError::MechanismInvalid(mechanism_type))
+        }
+    }
+}
+
+fn parse_mgf(
+    mechanism_type: CK_MECHANISM_TYPE,
+    mgf: CK_RSA_PKCS_MGF_TYPE,
+) -> Result<DigestType, MError> {
+    match mgf {
+        CKG_MGF1_SHA1 => Ok(DigestType::Sha1),
+        CKG_MGF1_SHA224 => Ok(DigestType::Sha224),
+        CKG_MGF1_SHA256 => Ok(DigestType::Sha256),
+        CKG_MGF1_SHA384 => Ok(DigestType::Sha384),
+        CKG_MGF1_SHA512 => Ok(DigestType::Sha512),
+        _ => {
+            tracing::error!("Unsupported mgf: {}", mgf);
+            Err(MError::MechanismInvalid(mechanism_type))
+        }
+    }
+}
+
 impl From<Mechanism> for CK_MECHANISM_TYPE {
     fn from(mechanism: Mechanism) -> Self {
         match mechanism {
+            Mechanism::AesCbcPad { .. } => CKM_AES_CBC_PAD,
+            Mechanism::AesGcm { .. } => CKM_AES_GCM,
+            Mechanism::AesKeyWrapPad => CKM_AES_KEY_WRAP_KWP,
             Mechanism::Ecdsa => CKM_ECDSA,
+            Mechanism::EcKeyPairGen => CKM_EC_KEY_PAIR_GEN,
+            Mechanism::RsaAesKeyWrap { .. } => CKM_RSA_AES_KEY_WRAP,
             Mechanism::RsaPkcs => CKM_RSA_PKCS,
+            Mechanism::RsaPkcsKeyPairGen => CKM_RSA_PKCS_KEY_PAIR_GEN,
+            Mechanism::RsaPkcsOaep { .. } => CKM_RSA_PKCS_OAEP,
             Mechanism::RsaPkcsSha1 => CKM_SHA1_RSA_PKCS,
             Mechanism::RsaPkcsSha256 => CKM_SHA256_RSA_PKCS,
             Mechanism::RsaPkcsSha384 => CKM_SHA384_RSA_PKCS,
@@ -137,33 +309,59 @@ impl From<Mechanism> for CK_MECHANISM_TYPE {
     }
 }
 
-impl From<Mechanism> for SignatureAlgorithm {
-    fn from(mechanism: Mechanism) -> Self {
+impl TryFrom<Mechanism> for SignatureAlgorithm {
+    type Error = MError;
+
+    fn try_from(mechanism: Mechanism) -> Result<Self, Self::Error> {
         match mechanism {
-            Mechanism::Ecdsa => SignatureAlgorithm::Ecdsa,
-            Mechanism::RsaPkcs => SignatureAlgorithm::RsaPkcs1v15Raw,
-            Mechanism::RsaPkcsSha1 => SignatureAlgorithm::RsaPkcs1v15Sha1,
-            Mechanism::RsaPkcsSha256 => SignatureAlgorithm::RsaPkcs1v15Sha256,
-            Mechanism::RsaPkcsSha384 => SignatureAlgorithm::RsaPkcs1v15Sha512,
-            Mechanism::RsaPkcsSha512 => SignatureAlgorithm::RsaPkcs1v15Sha384,
+            Mechanism::Ecdsa => Ok(SignatureAlgorithm::Ecdsa),
+            Mechanism::RsaPkcs => Ok(SignatureAlgorithm::RsaPkcs1v15Raw),
+            Mechanism::RsaPkcsSha1 => Ok(SignatureAlgorithm::RsaPkcs1v15Sha1),
+            Mechanism::RsaPkcsSha256 => Ok(SignatureAlgorithm::RsaPkcs1v15Sha256),
+            Mechanism::RsaPkcsSha384 => Ok(SignatureAlgorithm::RsaPkcs1v15Sha384),
+            Mechanism::RsaPkcsSha512 => Ok(SignatureAlgorithm::RsaPkcs1v15Sha512),
             Mechanism::RsaPss {
                 digest_algorithm,
                 mask_generation_function,
                 salt_length,
-            } => SignatureAlgorithm::RsaPss {
+            } => Ok(SignatureAlgorithm::RsaPss {
                 digest: digest_algorithm,
                 mask_generation_function,
                 salt_length,
-            },
+            }),
+            x => Err(MError::MechanismInvalid(x.into())),
         }
     }
 }
 
-impl From<Mechanism> for EncryptionAlgorithm {
-    fn from(mechanism: Mechanism) -> Self {
+impl TryFrom<Mechanism> for EncryptionAlgorithm {
+    type Error = MError;
+
+    fn try_from(mechanism: Mechanism) -> Result<Self, Self::Error> {
         match mechanism {
-            Mechanism::RsaPkcs => EncryptionAlgorithm::RsaPkcs1v15,
-            x => panic!("Unsupported encryption algorithm: {x:?}"),
+            Mechanism::AesCbcPad { iv } => Ok(EncryptionAlgorithm::AesCbcPad { iv }),
+            Mechanism::AesGcm { iv, aad, tag_bits } => {
+                Ok(EncryptionAlgorithm::AesGcm { iv, aad, tag_bits })
+            }
+            Mechanism::AesKeyWrapPad => Ok(EncryptionAlgorithm::AesKeyWrapPad),
+            Mechanism::RsaPkcs => Ok(EncryptionAlgorithm::RsaPkcs1v15),
+            Mechanism::RsaPkcsOaep {
+                digest_algorithm,
+                mask_generation_function,
+            } => Ok(EncryptionAlgorithm::RsaOaep {
+                digest: digest_algorithm,
+                mask_generation_function,
+            }),
+            Mechanism::RsaAesKeyWrap {
+                aes_key_bits,
+                digest_algorithm,
+                mask_generation_function,
+            } => Ok(EncryptionAlgorithm::RsaAesKeyWrap {
+                aes_key_bits,
+                digest: digest_algorithm,
+                mask_generation_function,
+            }),
+            x => Err(MError::MechanismInvalid(x.into())),
         }
     }
 }
@@ -25,8 +25,8 @@ use p256::pkcs8::{
 };
 use pkcs1::{der::Decode, RsaPublicKey};
 use pkcs11_sys::{
-    CKC_X_509, CKK_EC, CKK_RSA, CKO_CERTIFICATE, CKO_DATA, CKO_PRIVATE_KEY, CKO_PROFILE,
-    CKO_PUBLIC_KEY, CK_CERTIFICATE_CATEGORY_UNSPECIFIED, CK_PROFILE_ID,
+    CKC_X_509, CKK_AES, CKK_EC, CKK_RSA, CKO_CERTIFICATE, CKO_DATA, CKO_PRIVATE_KEY, CKO_PROFILE,
+    CKO_PUBLIC_KEY, CKO_SECRET_KEY, CK_CERTIFICATE_CATEGORY_UNSPECIFIED, CK_PROFILE_ID, CK_ULONG,
 };
 use tracing::debug;
 
@@ -34,6 +34,7 @@ use crate::{
     core::attribute::{Attribute, AttributeType, Attributes},
     traits::{
         backend, Certificate, DataObject, KeyAlgorithm, PrivateKey, PublicKey, RemoteObjectId,
+        RemoteObjectType, SymmetricKey,
     },
 };
 
@@ -47,6 +48,7 @@ pub enum Object {
     PublicKey(Arc<dyn PublicKey>),
     DataObject(Arc<dyn DataObject>),
     RemoteObjectId(Arc<dyn RemoteObjectId>),
+    SymmetricKey(Arc<dyn SymmetricKey>),
 }
 
 //  #[derive(PartialEq)] fails to compile because it tries to move the Box<_>ed
@@ -61,13 +63,15 @@ impl PartialEq for Object {
             (Self::PublicKey(l0), Self::PublicKey(r0)) => l0 == r0,
             (Self::DataObject(l0), Self::DataObject(r0)) => l0 == r0,
             (Self::RemoteObjectId(l0), Self::RemoteObjectId(r0)) => l0 == r0,
+            (Self::SymmetricKey(l0), Self::SymmetricKey(r0)) => l0 == r0,
             (
                 Self::Certificate(_)
                 | Self::PrivateKey(_)
                 | Self::Profile(_)
                 | Self::PublicKey(_)
                 | Self::DataObject(_)
-                | Self::RemoteObjectId(_),
+                | Self::RemoteObjectId(_)
+                | Self::SymmetricKey(_),
                 _,
             ) => false,
         }
@@ -121,6 +125,7 @@ impl Object {
                     .ok()?,
                 )),
                 AttributeType::KeyType => Some(Attribute::KeyType(match private_key.algorithm() {
+                    KeyAlgorithm::Aes => CKK_AES,
                     KeyAlgorithm::Rsa => CKK_RSA,
                     KeyAlgorithm::Ecc => CKK_EC,
                 })),
@@ -187,6 +192,7 @@ impl Object {
                     Some(Attribute::Modulus(key.public_exponent.as_bytes().to_vec()))
                 }
                 AttributeType::KeyType => Some(Attribute::KeyType(match pk.algorithm() {
+                    KeyAlgorithm::Aes => CKK_AES,
                     KeyAlgorithm::Rsa => CKK_RSA,
                     KeyAlgorithm::Ecc => CKK_EC,
                 })),
@@ -232,14 +238,58 @@ impl Object {
                 }
             },
             Object::RemoteObjectId(remote_object_id) => match type_ {
+                AttributeType::Class => {
+                    Some(Attribute::Class(match remote_object_id.remote_type() {
+                        RemoteObjectType::Certificate => CKO_CERTIFICATE,
+                        RemoteObjectType::PrivateKey => CKO_PRIVATE_KEY,
+                        RemoteObjectType::PublicKey => CKO_PUBLIC_KEY,
+                        RemoteObjectType::SymmetricKey => CKO_SECRET_KEY,
+                    }))
+                }
                 AttributeType::Id => Some(Attribute::Id(
                     remote_object_id.remote_id().as_bytes().to_vec(),
                 )),
+                AttributeType::KeyType => match remote_object_id.remote_type() {
+                    RemoteObjectType::SymmetricKey => Some(Attribute::KeyType(CKK_AES)),
+                    _ => None,
+                },
+                AttributeType::Token => Some(Attribute::Token(true)),
                 _ => {
                     debug!("Remote object id: type_ unimplemented: {:?}", type_);
                     None
                 }
             },
+            Object::SymmetricKey(key) => match type_ {
+                AttributeType::AlwaysSensitive => Some(Attribute::AlwaysSensitive(true)),
+                AttributeType::Class => Some(Attribute::Class(CKO_SECRET_KEY)),
+                AttributeType::Decrypt => Some(Attribute::Decrypt(true)),
+                AttributeType::Derive => Some(Attribute::Derive(false)),
+                AttributeType::Encrypt => Some(Attribute::Encrypt(true)),
+                AttributeType::Extractable => Some(Attribute::Extractable(false)),
+                AttributeType::Id => Some(Attribute::Id(key.remote_id().as_bytes().to_vec())),
+                AttributeType::KeyType => Some(Attribute::KeyType(match key.algorithm() {
+                    KeyAlgorithm::Aes => CKK_AES,
+                    KeyAlgorithm::Rsa => CKK_RSA,
+                    KeyAlgorithm::Ecc => CKK_EC,
+                })),
+                AttributeType::Label => Some(Attribute::Label(key.label())),
+                AttributeType::NeverExtractable => Some(Attribute::NeverExtractable(true)),
+                AttributeType::Private => Some(Attribute::Private(true)),
+                AttributeType::Sensitive => Some(Attribute::Sensitive(true)),
+                AttributeType::Sign => Some(Attribute::Sign(false)),
+                AttributeType::Token => Some(Attribute::Token(true)),
+                AttributeType::Unwrap => Some(Attribute::Unwrap(true)),
+                // CKA_VALUE_LEN is expressed in bytes
+                AttributeType::ValueLen => Some(Attribute::ValueLen(
+                    CK_ULONG::try_from(key.key_size() / 8).ok()?,
+                )),
+                AttributeType::Verify => Some(Attribute::Verify(false)),
+                AttributeType::Wrap => Some(Attribute::Wrap(true)),
+                _ => {
+                    debug!("symmetric key: type_ unimplemented: {:?}", type_);
+                    None
+                }
+            },
         };
         debug!("attribute: {:?} => {:?}", type_, attribute);
         attribute
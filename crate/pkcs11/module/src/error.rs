@@ -20,11 +20,15 @@ use pkcs11_sys::{
     CKR_ARGUMENTS_BAD, CKR_ATTRIBUTE_TYPE_INVALID, CKR_ATTRIBUTE_VALUE_INVALID,
     CKR_BUFFER_TOO_SMALL, CKR_CRYPTOKI_ALREADY_INITIALIZED, CKR_CRYPTOKI_NOT_INITIALIZED,
     CKR_FUNCTION_NOT_PARALLEL, CKR_FUNCTION_NOT_SUPPORTED, CKR_GENERAL_ERROR,
-    CKR_KEY_HANDLE_INVALID, CKR_MECHANISM_INVALID, CKR_NEED_TO_CREATE_THREADS,
-    CKR_OBJECT_HANDLE_INVALID, CKR_OPERATION_NOT_INITIALIZED, CKR_RANDOM_NO_RNG,
-    CKR_SESSION_HANDLE_INVALID, CKR_SESSION_PARALLEL_NOT_SUPPORTED, CKR_SLOT_ID_INVALID,
-    CKR_TOKEN_WRITE_PROTECTED, CK_ATTRIBUTE_TYPE, CK_MECHANISM_TYPE, CK_OBJECT_HANDLE, CK_RV,
-    CK_SESSION_HANDLE, CK_SLOT_ID,
+    CKR_KEY_HANDLE_INVALID, CKR_KEY_TYPE_INCONSISTENT, CKR_MECHANISM_INVALID,
+    CKR_NEED_TO_CREATE_THREADS, CKR_OBJECT_HANDLE_INVALID, CKR_OPERATION_NOT_INITIALIZED,
+    CKR_PIN_INCORRECT, CKR_RANDOM_NO_RNG, CKR_SESSION_HANDLE_INVALID,
+    CKR_SESSION_PARALLEL_NOT_SUPPORTED, CKR_SESSION_READ_ONLY, CKR_SIGNATURE_INVALID,
+    CKR_SLOT_ID_INVALID, CKR_TEMPLATE_INCOMPLETE, CKR_TEMPLATE_INCONSISTENT,
+    CKR_TOKEN_WRITE_PROTECTED, CKR_UNWRAPPING_KEY_HANDLE_INVALID, CKR_USER_ALREADY_LOGGED_IN,
+    CKR_USER_NOT_LOGGED_IN, CKR_USER_PIN_NOT_INITIALIZED, CKR_USER_TYPE_INVALID,
+    CKR_WRAPPING_KEY_HANDLE_INVALID, CK_ATTRIBUTE_TYPE, CK_MECHANISM_TYPE, CK_OBJECT_HANDLE, CK_RV,
+    CK_SESSION_HANDLE, CK_SLOT_ID, CK_USER_TYPE,
 };
 use thiserror::Error;
 
@@ -62,6 +66,9 @@ pub enum MError {
     #[error("key handle {0} is invalid")]
     KeyHandleInvalid(CK_OBJECT_HANDLE),
 
+    #[error("key type is inconsistent with the template or the mechanism")]
+    KeyTypeInconsistent,
+
     #[error("module cannot function without being able to spawn threads")]
     NeedToCreateThreads,
 
@@ -74,6 +81,9 @@ pub enum MError {
     #[error("operation has not been initialized")]
     OperationNotInitialized,
 
+    #[error("PIN is incorrect")]
+    PinIncorrect,
+
     #[error("no random number generator")]
     RandomNoRng,
 
@@ -83,12 +93,42 @@ pub enum MError {
     #[error("token does not support parallel sessions")]
     SessionParallelNotSupported,
 
+    #[error("session is read only")]
+    SessionReadOnly,
+
+    #[error("signature is invalid")]
+    SignatureInvalid,
+
     #[error("slot id {0} is invalid")]
     SlotIdInvalid(CK_SLOT_ID),
 
+    #[error("template is incomplete: {0}")]
+    TemplateIncomplete(String),
+
+    #[error("template is inconsistent: {0}")]
+    TemplateInconsistent(String),
+
     #[error("token is write protected")]
     TokenWriteProtected,
 
+    #[error("unwrapping key handle {0} is invalid")]
+    UnwrappingKeyHandleInvalid(CK_OBJECT_HANDLE),
+
+    #[error("user is already logged in")]
+    UserAlreadyLoggedIn,
+
+    #[error("user is not logged in")]
+    UserNotLoggedIn,
+
+    #[error("user PIN is not initialized: {0}")]
+    UserPinNotInitialized(String),
+
+    #[error("user type {0} is invalid")]
+    UserTypeInvalid(CK_USER_TYPE),
+
+    #[error("wrapping key handle {0} is invalid")]
+    WrappingKeyHandleInvalid(CK_OBJECT_HANDLE),
+
     // Other errors.
     #[error("{0}")]
     FromUtf8(#[from] std::string::FromUtf8Error),
@@ -128,15 +168,27 @@ impl From<MError> for CK_RV {
             MError::FunctionNotParallel => CKR_FUNCTION_NOT_PARALLEL,
             MError::FunctionNotSupported => CKR_FUNCTION_NOT_SUPPORTED,
             MError::KeyHandleInvalid(_) => CKR_KEY_HANDLE_INVALID,
+            MError::KeyTypeInconsistent => CKR_KEY_TYPE_INCONSISTENT,
             MError::MechanismInvalid(_) => CKR_MECHANISM_INVALID,
             MError::NeedToCreateThreads => CKR_NEED_TO_CREATE_THREADS,
             MError::ObjectHandleInvalid(_) => CKR_OBJECT_HANDLE_INVALID,
             MError::OperationNotInitialized => CKR_OPERATION_NOT_INITIALIZED,
+            MError::PinIncorrect => CKR_PIN_INCORRECT,
             MError::RandomNoRng => CKR_RANDOM_NO_RNG,
             MError::SessionHandleInvalid(_) => CKR_SESSION_HANDLE_INVALID,
             MError::SessionParallelNotSupported => CKR_SESSION_PARALLEL_NOT_SUPPORTED,
+            MError::SessionReadOnly => CKR_SESSION_READ_ONLY,
+            MError::SignatureInvalid => CKR_SIGNATURE_INVALID,
             MError::SlotIdInvalid(_) => CKR_SLOT_ID_INVALID,
+            MError::TemplateIncomplete(_) => CKR_TEMPLATE_INCOMPLETE,
+            MError::TemplateInconsistent(_) => CKR_TEMPLATE_INCONSISTENT,
             MError::TokenWriteProtected => CKR_TOKEN_WRITE_PROTECTED,
+            MError::UnwrappingKeyHandleInvalid(_) => CKR_UNWRAPPING_KEY_HANDLE_INVALID,
+            MError::UserAlreadyLoggedIn => CKR_USER_ALREADY_LOGGED_IN,
+            MError::UserNotLoggedIn => CKR_USER_NOT_LOGGED_IN,
+            MError::UserPinNotInitialized(_) => CKR_USER_PIN_NOT_INITIALIZED,
+            MError::UserTypeInvalid(_) => CKR_USER_TYPE_INVALID,
+            MError::WrappingKeyHandleInvalid(_) => CKR_WRAPPING_KEY_HANDLE_INVALID,
 
             MError::Backend(_)
             | MError::Bincode(_)
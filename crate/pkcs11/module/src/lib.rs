@@ -25,7 +25,7 @@
 
 use core::{
     attribute::{Attribute, Attributes},
-    mechanism::{parse_mechanism, SUPPORTED_SIGNATURE_MECHANISMS},
+    mechanism::{mechanism_flags, parse_mechanism, SUPPORTED_MECHANISMS},
     object::Object,
 };
 use std::{
@@ -35,29 +35,37 @@ use std::{
 
 use log::debug;
 use pkcs11_sys::{
-    CKF_HW_SLOT, CKF_PROTECTED_AUTHENTICATION_PATH, CKF_RNG, CKF_RW_SESSION, CKF_SERIAL_SESSION,
-    CKF_SIGN, CKF_TOKEN_INITIALIZED, CKF_TOKEN_PRESENT, CKF_USER_PIN_INITIALIZED,
-    CKF_WRITE_PROTECTED, CKS_RO_USER_FUNCTIONS, CKS_RW_USER_FUNCTIONS, CK_ATTRIBUTE_PTR, CK_BBOOL,
-    CK_BYTE_PTR, CK_C_INITIALIZE_ARGS_PTR, CK_FLAGS, CK_FUNCTION_LIST, CK_INFO, CK_INFO_PTR,
-    CK_MECHANISM_INFO, CK_MECHANISM_INFO_PTR, CK_MECHANISM_PTR, CK_MECHANISM_TYPE,
-    CK_MECHANISM_TYPE_PTR, CK_NOTIFY, CK_OBJECT_HANDLE, CK_OBJECT_HANDLE_PTR, CK_SESSION_HANDLE,
-    CK_SESSION_HANDLE_PTR, CK_SESSION_INFO, CK_SESSION_INFO_PTR, CK_SLOT_ID, CK_SLOT_ID_PTR,
-    CK_SLOT_INFO, CK_SLOT_INFO_PTR, CK_TOKEN_INFO, CK_TOKEN_INFO_PTR, CK_ULONG, CK_ULONG_PTR,
-    CK_UNAVAILABLE_INFORMATION, CK_USER_TYPE, CK_UTF8CHAR_PTR, CK_VERSION, CK_VOID_PTR,
-    CRYPTOKI_VERSION_MAJOR, CRYPTOKI_VERSION_MINOR,
+    CKF_HW_SLOT, CKF_LOGIN_REQUIRED, CKF_PROTECTED_AUTHENTICATION_PATH, CKF_RNG, CKF_RW_SESSION,
+    CKF_SERIAL_SESSION, CKF_TOKEN_INITIALIZED, CKF_TOKEN_PRESENT, CKF_USER_PIN_INITIALIZED,
+    CKK_AES, CKK_RSA, CKO_CERTIFICATE, CKO_PRIVATE_KEY, CKO_SECRET_KEY, CKS_RO_PUBLIC_SESSION,
+    CKS_RO_USER_FUNCTIONS, CKS_RW_PUBLIC_SESSION, CKS_RW_USER_FUNCTIONS, CKU_USER,
+    CK_ATTRIBUTE_PTR, CK_BBOOL, CK_BYTE_PTR, CK_C_INITIALIZE_ARGS_PTR, CK_FLAGS, CK_FUNCTION_LIST,
+    CK_INFO, CK_INFO_PTR, CK_MECHANISM_INFO, CK_MECHANISM_INFO_PTR, CK_MECHANISM_PTR,
+    CK_MECHANISM_TYPE, CK_MECHANISM_TYPE_PTR, CK_NOTIFY, CK_OBJECT_HANDLE, CK_OBJECT_HANDLE_PTR,
+    CK_SESSION_HANDLE, CK_SESSION_HANDLE_PTR, CK_SESSION_INFO, CK_SESSION_INFO_PTR, CK_SLOT_ID,
+    CK_SLOT_ID_PTR, CK_SLOT_INFO, CK_SLOT_INFO_PTR, CK_TOKEN_INFO, CK_TOKEN_INFO_PTR, CK_ULONG,
+    CK_ULONG_PTR, CK_UNAVAILABLE_INFORMATION, CK_USER_TYPE, CK_UTF8CHAR_PTR, CK_VERSION,
+    CK_VOID_PTR, CRYPTOKI_VERSION_MAJOR, CRYPTOKI_VERSION_MINOR,
 };
 pub use pkcs11_sys::{CKR_OK, CK_FUNCTION_LIST_PTR_PTR, CK_RV};
 use rand::RngCore;
 use tracing::{info, trace};
 
-use crate::{sessions::SignContext, traits::backend};
+use crate::{
+    sessions::{EncryptContext, SignContext, VerifyContext},
+    traits::{backend, backend_for_slot, slot_ids, EncryptionAlgorithm, KeyAlgorithm},
+};
 
 pub mod core;
 mod error;
 
 pub use error::{MError, MResult};
 
-use crate::{core::attribute::AttributeType, sessions::DecryptContext, traits::RemoteObjectType};
+use crate::{
+    core::{attribute::AttributeType, mechanism::Mechanism},
+    sessions::DecryptContext,
+    traits::RemoteObjectType,
+};
 
 mod sessions;
 #[cfg(test)]
@@ -66,7 +74,6 @@ pub mod traits;
 
 const SLOT_DESCRIPTION: &[u8; 64] =
     b"Platform Cryptography Support                                   ";
-const SLOT_ID: CK_SLOT_ID = 1;
 
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
@@ -134,12 +141,36 @@ macro_rules! valid_session {
 
 macro_rules! valid_slot {
     ($id:expr) => {
-        if $id != SLOT_ID {
+        if !slot_ids().contains(&$id) {
             return Err(MError::SlotIdInvalid($id));
         }
     };
 }
 
+/// The PIN passed to the PIN management functions:
+/// a null PIN is treated as an empty one.
+unsafe fn pin<'a>(pPin: CK_UTF8CHAR_PTR, ulPinLen: CK_ULONG) -> &'a [u8] {
+    if pPin.is_null() || ulPinLen == 0 {
+        return &[];
+    }
+    unsafe { slice::from_raw_parts(pPin, ulPinLen as usize) }
+}
+
+/// Parse the attributes of a template
+unsafe fn parse_template(pTemplate: CK_ATTRIBUTE_PTR, ulCount: CK_ULONG) -> MResult<Attributes> {
+    if ulCount == 0 {
+        return Ok(Vec::<Attribute>::new().into());
+    }
+    not_null!(pTemplate);
+    Ok(
+        unsafe { slice::from_raw_parts(pTemplate, ulCount as usize) }
+            .iter()
+            .map(|attr| (*attr).try_into())
+            .collect::<MResult<Vec<Attribute>>>()?
+            .into(),
+    )
+}
+
 pub static mut FUNC_LIST: CK_FUNCTION_LIST = CK_FUNCTION_LIST {
     // In this structure 'version' is the cryptoki specification version number. The major and minor
     // versions must be set to 0x02 and 0x28 indicating a version 2.40 compatible structure.
@@ -271,14 +302,16 @@ cryptoki_fn!(
     ) {
         initialized!();
         not_null!(pulCount);
+        let slot_ids = slot_ids();
         if !pSlotList.is_null() {
-            if unsafe { *pulCount } < 1 {
+            if (unsafe { *pulCount } as usize) < slot_ids.len() {
+                unsafe { *pulCount = slot_ids.len() as CK_ULONG };
                 return Err(MError::BufferTooSmall);
             }
-            // TODO: this should be an array.
-            unsafe { *pSlotList = SLOT_ID };
+            unsafe { slice::from_raw_parts_mut(pSlotList, slot_ids.len()) }
+                .copy_from_slice(&slot_ids);
         }
-        unsafe { *pulCount = 1 };
+        unsafe { *pulCount = slot_ids.len() as CK_ULONG };
         Ok(())
     }
 );
@@ -288,7 +321,7 @@ cryptoki_fn!(
         initialized!();
         valid_slot!(slotID);
         not_null!(pInfo);
-        let backend = backend();
+        let backend = backend_for_slot(slotID)?;
         let info = CK_SLOT_INFO {
             slotDescription: *SLOT_DESCRIPTION,
             manufacturerID: backend.token_manufacturer_id(),
@@ -313,7 +346,13 @@ cryptoki_fn!(
         valid_slot!(slotID);
         not_null!(pInfo);
 
-        let backend = backend();
+        let backend = backend_for_slot(slotID)?;
+        // without login, the token authenticates to the KMS on its own
+        let authentication_flag = if backend.login_required() {
+            CKF_LOGIN_REQUIRED
+        } else {
+            CKF_PROTECTED_AUTHENTICATION_PATH
+        };
 
         let info = CK_TOKEN_INFO {
             label: backend.token_label(),
@@ -321,8 +360,7 @@ cryptoki_fn!(
             model: backend.token_model(),
             serialNumber: backend.token_serial_number(),
             flags: CKF_TOKEN_INITIALIZED
-                | CKF_PROTECTED_AUTHENTICATION_PATH
-                | CKF_WRITE_PROTECTED
+                | authentication_flag
                 | CKF_USER_PIN_INITIALIZED
                 | CKF_RNG
                 | CKF_HW_SLOT, /* systemd-cryptenroll() requires this to be an hardware slot to
@@ -353,16 +391,14 @@ cryptoki_fn!(
         not_null!(pulCount);
         valid_slot!(slotID);
         if !pMechanismList.is_null() {
-            if (unsafe { *pulCount } as usize) < SUPPORTED_SIGNATURE_MECHANISMS.len() {
-                unsafe { *pulCount = SUPPORTED_SIGNATURE_MECHANISMS.len() as CK_ULONG };
+            if (unsafe { *pulCount } as usize) < SUPPORTED_MECHANISMS.len() {
+                unsafe { *pulCount = SUPPORTED_MECHANISMS.len() as CK_ULONG };
                 return Err(MError::BufferTooSmall);
             }
-            unsafe {
-                slice::from_raw_parts_mut(pMechanismList, SUPPORTED_SIGNATURE_MECHANISMS.len())
-            }
-            .copy_from_slice(SUPPORTED_SIGNATURE_MECHANISMS);
+            unsafe { slice::from_raw_parts_mut(pMechanismList, SUPPORTED_MECHANISMS.len()) }
+                .copy_from_slice(SUPPORTED_MECHANISMS);
         }
-        unsafe { *pulCount = SUPPORTED_SIGNATURE_MECHANISMS.len() as CK_ULONG };
+        unsafe { *pulCount = SUPPORTED_MECHANISMS.len() as CK_ULONG };
         Ok(())
     }
 );
@@ -376,11 +412,9 @@ cryptoki_fn!(
         initialized!();
         valid_slot!(slotID);
         not_null!(pInfo);
-        if !SUPPORTED_SIGNATURE_MECHANISMS.contains(&mechType) {
-            return Err(MError::MechanismInvalid(mechType));
-        }
+        let flags = mechanism_flags(mechType).ok_or(MError::MechanismInvalid(mechType))?;
         let info = CK_MECHANISM_INFO {
-            flags: CKF_SIGN,
+            flags,
             ..Default::default()
         };
         unsafe { *pInfo = info };
@@ -402,24 +436,33 @@ cryptoki_fn!(
 );
 
 cryptoki_fn!(
-    fn C_InitPIN(hSession: CK_SESSION_HANDLE, _pPin: CK_UTF8CHAR_PTR, _ulPinLen: CK_ULONG) {
+    unsafe fn C_InitPIN(hSession: CK_SESSION_HANDLE, pPin: CK_UTF8CHAR_PTR, ulPinLen: CK_ULONG) {
         initialized!();
         valid_session!(hSession);
-        Err(MError::TokenWriteProtected)
+        if sessions::flags(hSession) & CKF_RW_SESSION == 0 {
+            return Err(MError::SessionReadOnly);
+        }
+        let pin = unsafe { pin(pPin, ulPinLen) };
+        backend_for_slot(sessions::slot_id(hSession))?.init_pin(pin)
     }
 );
 
 cryptoki_fn!(
-    fn C_SetPIN(
+    unsafe fn C_SetPIN(
         hSession: CK_SESSION_HANDLE,
-        _pOldPin: CK_UTF8CHAR_PTR,
-        _ulOldLen: CK_ULONG,
-        _pNewPin: CK_UTF8CHAR_PTR,
-        _ulNewLen: CK_ULONG,
+        pOldPin: CK_UTF8CHAR_PTR,
+        ulOldLen: CK_ULONG,
+        pNewPin: CK_UTF8CHAR_PTR,
+        ulNewLen: CK_ULONG,
     ) {
         initialized!();
         valid_session!(hSession);
-        Err(MError::TokenWriteProtected)
+        if sessions::flags(hSession) & CKF_RW_SESSION == 0 {
+            return Err(MError::SessionReadOnly);
+        }
+        let old_pin = unsafe { pin(pOldPin, ulOldLen) };
+        let new_pin = unsafe { pin(pNewPin, ulNewLen) };
+        backend_for_slot(sessions::slot_id(hSession))?.set_pin(old_pin, new_pin)
     }
 );
 
@@ -437,7 +480,7 @@ cryptoki_fn!(
         if flags & CKF_SERIAL_SESSION == 0 {
             return Err(MError::SessionParallelNotSupported);
         }
-        unsafe { *phSession = sessions::create(flags) };
+        unsafe { *phSession = sessions::create(slotID, flags) };
         Ok(())
     }
 );
@@ -456,7 +499,7 @@ cryptoki_fn!(
     fn C_CloseAllSessions(slotID: CK_SLOT_ID) {
         initialized!();
         valid_slot!(slotID);
-        sessions::close_all();
+        sessions::close_all(slotID);
         Ok(())
     }
 );
@@ -467,13 +510,17 @@ cryptoki_fn!(
         valid_session!(hSession);
         not_null!(pInfo);
         let flags = sessions::flags(hSession);
-        let state = if flags & CKF_RW_SESSION == 0 {
-            CKS_RO_USER_FUNCTIONS
-        } else {
-            CKS_RW_USER_FUNCTIONS
+        let slot_id = sessions::slot_id(hSession);
+        let logged_in =
+            sessions::is_logged_in(slot_id) || !backend_for_slot(slot_id)?.login_required();
+        let state = match (flags & CKF_RW_SESSION == 0, logged_in) {
+            (true, true) => CKS_RO_USER_FUNCTIONS,
+            (true, false) => CKS_RO_PUBLIC_SESSION,
+            (false, true) => CKS_RW_USER_FUNCTIONS,
+            (false, false) => CKS_RW_PUBLIC_SESSION,
         };
         let info = CK_SESSION_INFO {
-            slotID: SLOT_ID,
+            slotID: slot_id,
             state,
             flags,
             ulDeviceError: 0,
@@ -500,14 +547,25 @@ cryptoki_fn_not_supported!(
 );
 
 cryptoki_fn!(
-    fn C_Login(
+    unsafe fn C_Login(
         hSession: CK_SESSION_HANDLE,
-        _userType: CK_USER_TYPE,
-        _pPin: CK_UTF8CHAR_PTR,
-        _ulPinLen: CK_ULONG,
+        userType: CK_USER_TYPE,
+        pPin: CK_UTF8CHAR_PTR,
+        ulPinLen: CK_ULONG,
     ) {
         initialized!();
         valid_session!(hSession);
+        // the Security Officer has nothing to administer on the token
+        if userType != CKU_USER {
+            return Err(MError::UserTypeInvalid(userType));
+        }
+        let slot_id = sessions::slot_id(hSession);
+        if sessions::is_logged_in(slot_id) {
+            return Err(MError::UserAlreadyLoggedIn);
+        }
+        let pin = unsafe { pin(pPin, ulPinLen) };
+        backend_for_slot(slot_id)?.login(pin)?;
+        sessions::login(slot_id);
         Ok(())
     }
 );
@@ -516,16 +574,62 @@ cryptoki_fn!(
     fn C_Logout(hSession: CK_SESSION_HANDLE) {
         initialized!();
         valid_session!(hSession);
-        Ok(())
+        let slot_id = sessions::slot_id(hSession);
+        if !sessions::logout(slot_id) {
+            return Err(MError::UserNotLoggedIn);
+        }
+        backend_for_slot(slot_id)?.logout()
     }
 );
 
-cryptoki_fn_not_supported!(
-    C_CreateObject,
-    hSession: CK_SESSION_HANDLE,
-    pTemplate: CK_ATTRIBUTE_PTR,
-    ulCount: CK_ULONG,
-    phObject: CK_OBJECT_HANDLE_PTR
+cryptoki_fn!(
+    unsafe fn C_CreateObject(
+        hSession: CK_SESSION_HANDLE,
+        pTemplate: CK_ATTRIBUTE_PTR,
+        ulCount: CK_ULONG,
+        phObject: CK_OBJECT_HANDLE_PTR,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(phObject);
+        let template = unsafe { parse_template(pTemplate, ulCount) }?;
+        let remote_type = match template.get_class()? {
+            CKO_CERTIFICATE => {
+                template.ensure_X509_or_none()?;
+                RemoteObjectType::Certificate
+            }
+            CKO_SECRET_KEY => {
+                if !matches!(template.get_key_type(), None | Some(CKK_AES)) {
+                    return Err(MError::TemplateInconsistent(
+                        "only AES secret keys can be created".to_string(),
+                    ));
+                }
+                RemoteObjectType::SymmetricKey
+            }
+            class => {
+                return Err(MError::TemplateInconsistent(format!(
+                    "objects of class {class} cannot be created"
+                )))
+            }
+        };
+        let value = template
+            .get_value()
+            .ok_or_else(|| MError::TemplateIncomplete("CKA_VALUE is missing".to_string()))?;
+        let label = template.get_label();
+        debug!(
+            "C_CreateObject: session: {:?}, type: {:?}, label: {:?}",
+            hSession, remote_type, label
+        );
+        let remote_object = backend_for_slot(sessions::slot_id(hSession))?.create_object(
+            remote_type,
+            value,
+            label.as_deref(),
+        )?;
+        sessions::session(hSession, |session| -> MResult<()> {
+            unsafe { *phObject = session.add_object(Object::RemoteObjectId(remote_object)) };
+            Ok(())
+        })
+    }
 );
 
 cryptoki_fn_not_supported!(
@@ -566,17 +670,7 @@ cryptoki_fn!(
             hObject
         );
         sessions::session(hSession, |session| -> MResult<()> {
-            let object_store = &session
-                .find_ctx
-                .as_ref()
-                .ok_or_else(|| MError::OperationNotInitialized)?
-                .objects;
-            let object = match object_store.get(hObject as usize) {
-                Some(object) => object,
-                None => {
-                    return Err(MError::ObjectHandleInvalid(hObject));
-                }
-            };
+            let object = session.object(hObject)?;
             let template = if ulCount > 0 {
                 if pTemplate.is_null() {
                     return Err(MError::ArgumentsBad);
@@ -636,11 +730,7 @@ cryptoki_fn!(
         initialized!();
         valid_session!(hSession);
 
-        let template: Attributes = unsafe { slice::from_raw_parts(pTemplate, ulCount as usize) }
-            .iter()
-            .map(|attr| (*attr).try_into())
-            .collect::<MResult<Vec<Attribute>>>()?
-            .into();
+        let template = unsafe { parse_template(pTemplate, ulCount) }?;
 
         sessions::session(hSession, |session| -> MResult<()> {
             session.load_find_context(template)
@@ -669,16 +759,16 @@ cryptoki_fn!(
             };
             debug!(
                 "C_FindObjects: objects still available: {:?}",
-                find_ctx.unread_indexes
+                find_ctx.unread_handles
             );
-            if find_ctx.unread_indexes.is_empty() {
+            if find_ctx.unread_handles.is_empty() {
                 info!("C_FindObjects: no more objects to return");
                 unsafe { *pulObjectCount = 0 };
                 return Ok(());
             }
-            let max_objects = cmp::min(find_ctx.objects.len(), ulMaxObjectCount as usize);
+            let max_objects = cmp::min(find_ctx.unread_handles.len(), ulMaxObjectCount as usize);
             let handles = find_ctx
-                .unread_indexes
+                .unread_handles
                 .drain(0..max_objects)
                 .collect::<Vec<_>>();
             info!("C_FindObjects: returning {} objects", handles.len());
@@ -695,36 +785,76 @@ cryptoki_fn!(
         initialized!();
         valid_session!(hSession);
         sessions::session(hSession, |session| -> MResult<()> {
-            // re-initialize the find context unread indexes
+            // re-initialize the find context unread handles
             let find_ctx = session
                 .find_ctx
                 .as_mut()
                 .ok_or(MError::OperationNotInitialized)?;
-            find_ctx.unread_indexes = find_ctx
-                .objects
-                .iter()
-                .enumerate()
-                .map(|(i, _)| i as CK_OBJECT_HANDLE)
-                .collect();
+            find_ctx.unread_handles = find_ctx.handles.clone();
             Ok(())
         })
     }
 );
 
-cryptoki_fn_not_supported!(
-    C_EncryptInit,
-    hSession: CK_SESSION_HANDLE,
-    pMechanism: CK_MECHANISM_PTR,
-    hKey: CK_OBJECT_HANDLE
+cryptoki_fn!(
+    unsafe fn C_EncryptInit(
+        hSession: CK_SESSION_HANDLE,
+        pMechanism: CK_MECHANISM_PTR,
+        hKey: CK_OBJECT_HANDLE,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pMechanism);
+        sessions::session(hSession, |session| -> MResult<()> {
+            let mechanism = unsafe { parse_mechanism(pMechanism.read()) }?;
+            let remote_object = session
+                .remote_object(hKey)
+                .ok_or(MError::KeyHandleInvalid(hKey))?;
+            match remote_object.remote_type() {
+                RemoteObjectType::PrivateKey => Err(MError::KeyHandleInvalid(hKey)),
+                RemoteObjectType::PublicKey
+                | RemoteObjectType::Certificate
+                | RemoteObjectType::SymmetricKey => {
+                    debug!(
+                        "C_EncryptInit: session: {:?}, remote_object: {:?}, mechanism: {:?}",
+                        hSession, &remote_object, &mechanism
+                    );
+                    session.encrypt_ctx = Some(EncryptContext {
+                        remote_object,
+                        algorithm: mechanism.try_into()?,
+                    });
+                    Ok(())
+                }
+            }
+        })
+    }
 );
 
-cryptoki_fn_not_supported!(
-    C_Encrypt,
-    hSession: CK_SESSION_HANDLE,
-    pData: CK_BYTE_PTR,
-    ulDataLen: CK_ULONG,
-    pEncryptedData: CK_BYTE_PTR,
-    pulEncryptedDataLen: CK_ULONG_PTR
+cryptoki_fn!(
+    unsafe fn C_Encrypt(
+        hSession: CK_SESSION_HANDLE,
+        pData: CK_BYTE_PTR,
+        ulDataLen: CK_ULONG,
+        pEncryptedData: CK_BYTE_PTR,
+        pulEncryptedDataLen: CK_ULONG_PTR,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        if ulDataLen == 0 {
+            return Err(MError::ArgumentsBad);
+        }
+        not_null!(pData);
+        not_null!(pulEncryptedDataLen);
+        sessions::session(hSession, |session| -> MResult<()> {
+            let data = unsafe { slice::from_raw_parts(pData, ulDataLen as usize) };
+            debug!(
+                "C_Encrypt: session: {:?}, data_len: {:?}",
+                hSession,
+                data.len()
+            );
+            unsafe { session.encrypt(data.to_vec(), pEncryptedData, pulEncryptedDataLen) }
+        })
+    }
 );
 
 cryptoki_fn_not_supported!(
@@ -754,30 +884,25 @@ cryptoki_fn!(
         not_null!(pMechanism);
         sessions::session(hSession, |session| -> MResult<()> {
             let mechanism = unsafe { parse_mechanism(pMechanism.read()) }?;
-            let object_store = &session
-                .find_ctx
-                .as_ref()
-                .ok_or(MError::OperationNotInitialized)?
-                .objects;
-            match object_store.get(hKey as usize) {
-                Some(Object::RemoteObjectId(remote_object)) => match remote_object.remote_type() {
-                    RemoteObjectType::PublicKey | RemoteObjectType::Certificate => {
-                        Err(MError::KeyHandleInvalid(hKey))
-                    }
-                    RemoteObjectType::PrivateKey | RemoteObjectType::SymmetricKey => {
-                        debug!(
-                            "C_DecryptInit: session: {:?}, remote_object: {:?}, mechanism: {:?}",
-                            hSession, &remote_object, &mechanism
-                        );
-                        session.decrypt_ctx = Some(DecryptContext {
-                            remote_object: remote_object.clone(),
-                            algorithm: mechanism.into(),
-                            ciphertext: None,
-                        });
-                        Ok(())
-                    }
-                },
-                Some(_) | None => Err(MError::KeyHandleInvalid(hKey)),
+            let remote_object = session
+                .remote_object(hKey)
+                .ok_or(MError::KeyHandleInvalid(hKey))?;
+            match remote_object.remote_type() {
+                RemoteObjectType::PublicKey | RemoteObjectType::Certificate => {
+                    Err(MError::KeyHandleInvalid(hKey))
+                }
+                RemoteObjectType::PrivateKey | RemoteObjectType::SymmetricKey => {
+                    debug!(
+                        "C_DecryptInit: session: {:?}, remote_object: {:?}, mechanism: {:?}",
+                        hSession, &remote_object, &mechanism
+                    );
+                    session.decrypt_ctx = Some(DecryptContext {
+                        remote_object,
+                        algorithm: mechanism.try_into()?,
+                        ciphertext: None,
+                    });
+                    Ok(())
+                }
             }
         })
     }
@@ -897,19 +1022,14 @@ cryptoki_fn!(
         valid_session!(hSession);
         not_null!(pMechanism);
         sessions::session(hSession, |session| -> MResult<()> {
-            let object_store = &session
-                .find_ctx
-                .as_ref()
-                .ok_or(MError::OperationNotInitialized)?
-                .objects;
-            let private_key = match object_store.get(hKey as usize) {
-                Some(Object::PrivateKey(private_key)) => private_key,
-                Some(_) | None => return Err(MError::KeyHandleInvalid(hKey)),
-            };
+            let remote_object = session
+                .remote_object(hKey)
+                .filter(|key| matches!(key.remote_type(), RemoteObjectType::PrivateKey))
+                .ok_or(MError::KeyHandleInvalid(hKey))?;
             let mechanism = unsafe { parse_mechanism(pMechanism.read()) }?;
             session.sign_ctx = Some(SignContext {
-                algorithm: mechanism.into(),
-                private_key: private_key.clone(),
+                remote_object,
+                algorithm: mechanism.try_into()?,
                 payload: None,
             });
             Ok(())
@@ -989,34 +1109,89 @@ cryptoki_fn_not_supported!(
     pulSignatureLen: CK_ULONG_PTR
 );
 
-cryptoki_fn_not_supported!(
-    C_VerifyInit,
-    hSession: CK_SESSION_HANDLE,
-    pMechanism: CK_MECHANISM_PTR,
-    hKey: CK_OBJECT_HANDLE
+cryptoki_fn!(
+    unsafe fn C_VerifyInit(
+        hSession: CK_SESSION_HANDLE,
+        pMechanism: CK_MECHANISM_PTR,
+        hKey: CK_OBJECT_HANDLE,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pMechanism);
+        sessions::session(hSession, |session| -> MResult<()> {
+            let remote_object = session
+                .remote_object(hKey)
+                .filter(|key| {
+                    matches!(
+                        key.remote_type(),
+                        RemoteObjectType::PublicKey | RemoteObjectType::Certificate
+                    )
+                })
+                .ok_or(MError::KeyHandleInvalid(hKey))?;
+            let mechanism = unsafe { parse_mechanism(pMechanism.read()) }?;
+            session.verify_ctx = Some(VerifyContext {
+                remote_object,
+                algorithm: mechanism.try_into()?,
+                payload: None,
+            });
+            Ok(())
+        })
+    }
 );
 
-cryptoki_fn_not_supported!(
-    C_Verify,
-    hSession: CK_SESSION_HANDLE,
-    pData: CK_BYTE_PTR,
-    ulDataLen: CK_ULONG,
-    pSignature: CK_BYTE_PTR,
-    ulSignatureLen: CK_ULONG
+cryptoki_fn!(
+    unsafe fn C_Verify(
+        hSession: CK_SESSION_HANDLE,
+        pData: CK_BYTE_PTR,
+        ulDataLen: CK_ULONG,
+        pSignature: CK_BYTE_PTR,
+        ulSignatureLen: CK_ULONG,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pData);
+        not_null!(pSignature);
+        sessions::session(hSession, |session| -> MResult<()> {
+            let data = unsafe { slice::from_raw_parts(pData, ulDataLen as usize) };
+            let signature = unsafe { slice::from_raw_parts(pSignature, ulSignatureLen as usize) };
+            session.verify(Some(data), signature)
+        })
+    }
 );
 
-cryptoki_fn_not_supported!(
-    C_VerifyUpdate,
-    hSession: CK_SESSION_HANDLE,
-    pPart: CK_BYTE_PTR,
-    ulPartLen: CK_ULONG
+cryptoki_fn!(
+    unsafe fn C_VerifyUpdate(hSession: CK_SESSION_HANDLE, pPart: CK_BYTE_PTR, ulPartLen: CK_ULONG) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pPart);
+        sessions::session(hSession, |session| -> MResult<()> {
+            let verify_ctx = match session.verify_ctx.as_mut() {
+                None => return Err(MError::OperationNotInitialized),
+                Some(verify_ctx) => verify_ctx,
+            };
+            verify_ctx
+                .payload
+                .get_or_insert(vec![])
+                .extend_from_slice(unsafe { slice::from_raw_parts(pPart, ulPartLen as usize) });
+            Ok(())
+        })
+    }
 );
 
-cryptoki_fn_not_supported!(
-    C_VerifyFinal,
-    hSession: CK_SESSION_HANDLE,
-    pSignature: CK_BYTE_PTR,
-    ulSignatureLen: CK_ULONG
+cryptoki_fn!(
+    unsafe fn C_VerifyFinal(
+        hSession: CK_SESSION_HANDLE,
+        pSignature: CK_BYTE_PTR,
+        ulSignatureLen: CK_ULONG,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pSignature);
+        sessions::session(hSession, |session| -> MResult<()> {
+            let signature = unsafe { slice::from_raw_parts(pSignature, ulSignatureLen as usize) };
+            session.verify(None, signature)
+        })
+    }
 );
 
 cryptoki_fn_not_supported!(
@@ -1080,38 +1255,185 @@ cryptoki_fn_not_supported!(
     phKey: CK_OBJECT_HANDLE_PTR
 );
 
-cryptoki_fn_not_supported!(
-    C_GenerateKeyPair,
-    hSession: CK_SESSION_HANDLE,
-    pMechanism: CK_MECHANISM_PTR,
-    pPublicKeyTemplate: CK_ATTRIBUTE_PTR,
-    ulPublicKeyAttributeCount: CK_ULONG,
-    pPrivateKeyTemplate: CK_ATTRIBUTE_PTR,
-    ulPrivateKeyAttributeCount: CK_ULONG,
-    phPublicKey: CK_OBJECT_HANDLE_PTR,
-    phPrivateKey: CK_OBJECT_HANDLE_PTR
+cryptoki_fn!(
+    unsafe fn C_GenerateKeyPair(
+        hSession: CK_SESSION_HANDLE,
+        pMechanism: CK_MECHANISM_PTR,
+        pPublicKeyTemplate: CK_ATTRIBUTE_PTR,
+        ulPublicKeyAttributeCount: CK_ULONG,
+        pPrivateKeyTemplate: CK_ATTRIBUTE_PTR,
+        ulPrivateKeyAttributeCount: CK_ULONG,
+        phPublicKey: CK_OBJECT_HANDLE_PTR,
+        phPrivateKey: CK_OBJECT_HANDLE_PTR,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pMechanism);
+        not_null!(phPublicKey);
+        not_null!(phPrivateKey);
+        let mechanism = unsafe { parse_mechanism(pMechanism.read()) }?;
+        let public_template =
+            unsafe { parse_template(pPublicKeyTemplate, ulPublicKeyAttributeCount) }?;
+        let private_template =
+            unsafe { parse_template(pPrivateKeyTemplate, ulPrivateKeyAttributeCount) }?;
+        let (algorithm, key_length) = match mechanism {
+            Mechanism::RsaPkcsKeyPairGen => {
+                let modulus_bits = public_template
+                    .get_modulus_bits()
+                    .or_else(|| private_template.get_modulus_bits())
+                    .ok_or_else(|| {
+                        MError::TemplateIncomplete("CKA_MODULUS_BITS is missing".to_string())
+                    })?;
+                (KeyAlgorithm::Rsa, usize::try_from(modulus_bits)?)
+            }
+            Mechanism::EcKeyPairGen => {
+                let curve_bits = match public_template.get_ec_curve_bits()? {
+                    Some(curve_bits) => curve_bits,
+                    None => private_template.get_ec_curve_bits()?.ok_or_else(|| {
+                        MError::TemplateIncomplete("CKA_EC_PARAMS is missing".to_string())
+                    })?,
+                };
+                (KeyAlgorithm::Ecc, curve_bits)
+            }
+            other => return Err(MError::MechanismInvalid(other.into())),
+        };
+        let label = private_template
+            .get_label()
+            .or_else(|| public_template.get_label());
+        debug!(
+            "C_GenerateKeyPair: session: {:?}, algorithm: {:?}, key length: {}, label: {:?}",
+            hSession, algorithm, key_length, label
+        );
+        let (public_key, private_key) = backend_for_slot(sessions::slot_id(hSession))?
+            .generate_key_pair(algorithm, key_length, label.as_deref())?;
+        sessions::session(hSession, |session| -> MResult<()> {
+            unsafe {
+                *phPublicKey = session.add_object(Object::RemoteObjectId(public_key));
+                *phPrivateKey = session.add_object(Object::RemoteObjectId(private_key));
+            }
+            Ok(())
+        })
+    }
 );
 
-cryptoki_fn_not_supported!(
-    C_WrapKey,
-    hSession: CK_SESSION_HANDLE,
-    _pMechanism: CK_MECHANISM_PTR,
-    _hWrappingKey: CK_OBJECT_HANDLE,
-    _hKey: CK_OBJECT_HANDLE,
-    _pWrappedKey: CK_BYTE_PTR,
-    _pulWrappedKeyLen: CK_ULONG_PTR
+cryptoki_fn!(
+    unsafe fn C_WrapKey(
+        hSession: CK_SESSION_HANDLE,
+        pMechanism: CK_MECHANISM_PTR,
+        hWrappingKey: CK_OBJECT_HANDLE,
+        hKey: CK_OBJECT_HANDLE,
+        pWrappedKey: CK_BYTE_PTR,
+        pulWrappedKeyLen: CK_ULONG_PTR,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pMechanism);
+        not_null!(pulWrappedKeyLen);
+        sessions::session(hSession, |session| -> MResult<()> {
+            let algorithm: EncryptionAlgorithm =
+                unsafe { parse_mechanism(pMechanism.read()) }?.try_into()?;
+            let wrapping_key = session
+                .remote_object(hWrappingKey)
+                .filter(|key| !matches!(key.remote_type(), RemoteObjectType::PrivateKey))
+                .ok_or(MError::WrappingKeyHandleInvalid(hWrappingKey))?;
+            let key = session
+                .remote_object(hKey)
+                .filter(|key| {
+                    matches!(
+                        key.remote_type(),
+                        RemoteObjectType::PrivateKey | RemoteObjectType::SymmetricKey
+                    )
+                })
+                .ok_or(MError::KeyHandleInvalid(hKey))?;
+            debug!(
+                "C_WrapKey: session: {:?}, wrapping key: {:?}, key: {:?}, algorithm: {:?}",
+                hSession, &wrapping_key, &key, algorithm
+            );
+            let wrapped_key = session.backend()?.wrap_key(wrapping_key, algorithm, key)?;
+            if !pWrappedKey.is_null() {
+                if (unsafe { *pulWrappedKeyLen } as usize) < wrapped_key.len() {
+                    unsafe { *pulWrappedKeyLen = wrapped_key.len() as CK_ULONG };
+                    return Err(MError::BufferTooSmall);
+                }
+                unsafe { slice::from_raw_parts_mut(pWrappedKey, wrapped_key.len()) }
+                    .copy_from_slice(&wrapped_key);
+            }
+            unsafe { *pulWrappedKeyLen = wrapped_key.len() as CK_ULONG };
+            Ok(())
+        })
+    }
 );
 
-cryptoki_fn_not_supported!(
-    C_UnwrapKey,
-    hSession: CK_SESSION_HANDLE,
-    pMechanism: CK_MECHANISM_PTR,
-    hUnwrappingKey: CK_OBJECT_HANDLE,
-    pWrappedKey: CK_BYTE_PTR,
-    ulWrappedKeyLen: CK_ULONG,
-    pTemplate: CK_ATTRIBUTE_PTR,
-    ulAttributeCount: CK_ULONG,
-    phKey: CK_OBJECT_HANDLE_PTR
+cryptoki_fn!(
+    unsafe fn C_UnwrapKey(
+        hSession: CK_SESSION_HANDLE,
+        pMechanism: CK_MECHANISM_PTR,
+        hUnwrappingKey: CK_OBJECT_HANDLE,
+        pWrappedKey: CK_BYTE_PTR,
+        ulWrappedKeyLen: CK_ULONG,
+        pTemplate: CK_ATTRIBUTE_PTR,
+        ulAttributeCount: CK_ULONG,
+        phKey: CK_OBJECT_HANDLE_PTR,
+    ) {
+        initialized!();
+        valid_session!(hSession);
+        not_null!(pMechanism);
+        not_null!(pWrappedKey);
+        not_null!(phKey);
+        let template = unsafe { parse_template(pTemplate, ulAttributeCount) }?;
+        // the KMS imports unwrapped private keys from their PKCS#1 encoding
+        let key_type = match template.get_class()? {
+            CKO_SECRET_KEY => {
+                if !matches!(template.get_key_type(), None | Some(CKK_AES)) {
+                    return Err(MError::TemplateInconsistent(
+                        "only AES secret keys can be unwrapped".to_string(),
+                    ));
+                }
+                RemoteObjectType::SymmetricKey
+            }
+            CKO_PRIVATE_KEY => {
+                if !matches!(template.get_key_type(), None | Some(CKK_RSA)) {
+                    return Err(MError::TemplateInconsistent(
+                        "only RSA private keys can be unwrapped".to_string(),
+                    ));
+                }
+                RemoteObjectType::PrivateKey
+            }
+            class => {
+                return Err(MError::TemplateInconsistent(format!(
+                    "objects of class {class} cannot be unwrapped"
+                )))
+            }
+        };
+        let label = template.get_label();
+        let wrapped_key = unsafe { slice::from_raw_parts(pWrappedKey, ulWrappedKeyLen as usize) };
+        sessions::session(hSession, |session| -> MResult<()> {
+            let algorithm: EncryptionAlgorithm =
+                unsafe { parse_mechanism(pMechanism.read()) }?.try_into()?;
+            let unwrapping_key = session
+                .remote_object(hUnwrappingKey)
+                .filter(|key| {
+                    matches!(
+                        key.remote_type(),
+                        RemoteObjectType::PrivateKey | RemoteObjectType::SymmetricKey
+                    )
+                })
+                .ok_or(MError::UnwrappingKeyHandleInvalid(hUnwrappingKey))?;
+            debug!(
+                "C_UnwrapKey: session: {:?}, unwrapping key: {:?}, key type: {:?}, algorithm: {:?}",
+                hSession, &unwrapping_key, key_type, algorithm
+            );
+            let key = session.backend()?.unwrap_key(
+                unwrapping_key,
+                algorithm,
+                wrapped_key,
+                key_type,
+                label.as_deref(),
+            )?;
+            unsafe { *phKey = session.add_object(Object::RemoteObjectId(key)) };
+            Ok(())
+        })
+    }
 );
 
 cryptoki_fn_not_supported!(
@@ -18,25 +18,26 @@
 // limitations under the License.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{self, atomic::Ordering, Arc},
 };
 
 use once_cell::sync::Lazy;
 use pkcs11_sys::{
-    CK_BYTE_PTR, CK_FLAGS, CK_OBJECT_HANDLE, CK_SESSION_HANDLE, CK_ULONG, CK_ULONG_PTR,
+    CK_BYTE_PTR, CK_FLAGS, CK_OBJECT_HANDLE, CK_SESSION_HANDLE, CK_SLOT_ID, CK_ULONG, CK_ULONG_PTR,
 };
 use tracing::{debug, error, info};
 
 use crate::{
-    core::{attribute::Attributes, object::Object},
-    traits::{backend, EncryptionAlgorithm, RemoteObjectId, SearchOptions},
-};
-use crate::{
-    // object_store::ObjectStore,
-    traits::{PrivateKey, SignatureAlgorithm},
-    MError,
-    MResult,
+    core::{
+        attribute::{Attribute, Attributes},
+        object::Object,
+    },
+    traits::{
+        backend_for_slot, Backend, EncryptionAlgorithm, RemoteObjectId, SearchOptions,
+        SignatureAlgorithm,
+    },
+    MError, MResult,
 };
 
 // "Valid session handles in Cryptoki always have nonzero values."
@@ -48,24 +49,41 @@ static NEXT_SESSION_HANDLE: sync::atomic::AtomicU32 = sync::atomic::AtomicU32::n
 type SessionMap = HashMap<CK_SESSION_HANDLE, Session>;
 
 static SESSIONS: Lazy<sync::Mutex<SessionMap>> = Lazy::new(Default::default);
-// pub static OBJECT_STORE: Lazy<sync::Mutex<ObjectStore>> = Lazy::new(Default::default);
+
+/// The slots on which the user is logged in.
+/// "When a user logs in, all sessions of the application with the token are logged in."
+static LOGGED_IN_SLOTS: Lazy<sync::Mutex<HashSet<CK_SLOT_ID>>> = Lazy::new(Default::default);
 
 #[derive(Debug)]
 pub struct FindContext {
-    /// The PKCS#11 objects manipulated by this context.
-    pub objects: Vec<Object>,
-    /// The indexes that have not yet been read by C_FindObjects
-    pub unread_indexes: Vec<CK_OBJECT_HANDLE>,
+    /// The handles of the objects matching the search template.
+    pub handles: Vec<CK_OBJECT_HANDLE>,
+    /// The handles that have not yet been read by C_FindObjects
+    pub unread_handles: Vec<CK_OBJECT_HANDLE>,
 }
 
 #[derive(Debug)]
 pub struct SignContext {
+    pub remote_object: Arc<dyn RemoteObjectId>,
     pub algorithm: SignatureAlgorithm,
-    pub private_key: Arc<dyn PrivateKey>,
     /// Payload stored for multipart C_SignUpdate operations.
     pub payload: Option<Vec<u8>>,
 }
 
+#[derive(Debug)]
+pub struct VerifyContext {
+    pub remote_object: Arc<dyn RemoteObjectId>,
+    pub algorithm: SignatureAlgorithm,
+    /// Payload stored for multipart C_VerifyUpdate operations.
+    pub payload: Option<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub struct EncryptContext {
+    pub remote_object: Arc<dyn RemoteObjectId>,
+    pub algorithm: EncryptionAlgorithm,
+}
+
 #[derive(Debug)]
 pub struct DecryptContext {
     pub remote_object: Arc<dyn RemoteObjectId>,
@@ -82,6 +100,7 @@ impl Session {
         pSignature: CK_BYTE_PTR,
         pulSignatureLen: CK_ULONG_PTR,
     ) -> MResult<()> {
+        let backend = self.backend()?;
         let sign_ctx = match self.sign_ctx.as_mut() {
             Some(sign_ctx) => sign_ctx,
             None => return Err(MError::OperationNotInitialized),
@@ -89,13 +108,7 @@ impl Session {
         let data = data
             .or(sign_ctx.payload.as_deref())
             .ok_or(MError::OperationNotInitialized)?;
-        let signature = match sign_ctx.private_key.sign(&sign_ctx.algorithm, data) {
-            Ok(sig) => sig,
-            Err(e) => {
-                tracing::error!("signature failed: {e:?}");
-                return Err(MError::ArgumentsBad);
-            }
-        };
+        let signature = backend.sign(sign_ctx.remote_object.clone(), &sign_ctx.algorithm, data)?;
         if !pSignature.is_null() {
             // TODO(bweeks): This will cause a second sign call when this function is
             // called again with an appropriately-sized buffer. Do we really need to
@@ -112,19 +125,65 @@ impl Session {
         Ok(())
     }
 
+    /// Verify the signature of the provided data, or stored payload if data is not provided.
+    pub fn verify(&mut self, data: Option<&[u8]>, signature: &[u8]) -> MResult<()> {
+        let verify_ctx = self
+            .verify_ctx
+            .take()
+            .ok_or(MError::OperationNotInitialized)?;
+        let data = data
+            .or(verify_ctx.payload.as_deref())
+            .ok_or(MError::OperationNotInitialized)?;
+        self.backend()?.verify(
+            verify_ctx.remote_object.clone(),
+            &verify_ctx.algorithm,
+            data,
+            signature,
+        )
+    }
+
+    pub unsafe fn encrypt(
+        &mut self,
+        cleartext: Vec<u8>,
+        pEncryptedData: CK_BYTE_PTR,
+        pulEncryptedDataLen: CK_ULONG_PTR,
+    ) -> MResult<()> {
+        let backend = self.backend()?;
+        let encrypt_ctx = match self.encrypt_ctx.as_mut() {
+            Some(encrypt_ctx) => encrypt_ctx,
+            None => return Err(MError::OperationNotInitialized),
+        };
+        let ciphertext = backend.encrypt(
+            encrypt_ctx.remote_object.clone(),
+            encrypt_ctx.algorithm.clone(),
+            cleartext,
+        )?;
+        if !pEncryptedData.is_null() {
+            if (unsafe { *pulEncryptedDataLen } as usize) < ciphertext.len() {
+                return Err(MError::BufferTooSmall);
+            }
+            unsafe { std::slice::from_raw_parts_mut(pEncryptedData, ciphertext.len()) }
+                .copy_from_slice(&ciphertext);
+            self.encrypt_ctx = None;
+        }
+        unsafe { *pulEncryptedDataLen = ciphertext.len() as CK_ULONG };
+        Ok(())
+    }
+
     pub unsafe fn decrypt(
         &mut self,
         ciphertext: Vec<u8>,
         pData: CK_BYTE_PTR,
         pulDataLen: CK_ULONG_PTR,
     ) -> MResult<()> {
+        let backend = self.backend()?;
         let decrypt_ctx = match self.decrypt_ctx.as_mut() {
             Some(decrypt_ctx) => decrypt_ctx,
             None => return Err(MError::OperationNotInitialized),
         };
-        let cleartext = backend().decrypt(
+        let cleartext = backend.decrypt(
             decrypt_ctx.remote_object.clone(),
-            decrypt_ctx.algorithm,
+            decrypt_ctx.algorithm.clone(),
             ciphertext,
         )?;
         if !pData.is_null() {
@@ -142,13 +201,55 @@ impl Session {
 
 #[derive(Default, Debug)]
 pub struct Session {
+    slot_id: CK_SLOT_ID,
     flags: CK_FLAGS,
+    /// The PKCS#11 objects known to this session:
+    /// the handle of an object is its index in this list, plus one,
+    /// since handles must be nonzero.
+    objects: Vec<Object>,
     pub find_ctx: Option<FindContext>,
     pub sign_ctx: Option<SignContext>,
+    pub verify_ctx: Option<VerifyContext>,
+    pub encrypt_ctx: Option<EncryptContext>,
     pub decrypt_ctx: Option<DecryptContext>,
 }
 
 impl Session {
+    /// The backend of the slot this session is opened on
+    pub fn backend(&self) -> MResult<&'static dyn Backend> {
+        backend_for_slot(self.slot_id)
+    }
+
+    /// The object designated by the handle
+    pub fn object(&self, handle: CK_OBJECT_HANDLE) -> MResult<&Object> {
+        (handle as usize)
+            .checked_sub(1)
+            .and_then(|index| self.objects.get(index))
+            .ok_or(MError::ObjectHandleInvalid(handle))
+    }
+
+    /// The remote object designated by the handle, if it is one
+    pub fn remote_object(&self, handle: CK_OBJECT_HANDLE) -> Option<Arc<dyn RemoteObjectId>> {
+        match self.object(handle) {
+            Ok(Object::RemoteObjectId(remote_object)) => Some(remote_object.clone()),
+            Ok(Object::SymmetricKey(key)) => Some(Arc::new(key.clone())),
+            _ => None,
+        }
+    }
+
+    /// Add the object to the session and return its handle.
+    /// An object already known to the session keeps its handle.
+    pub fn add_object(&mut self, object: Object) -> CK_OBJECT_HANDLE {
+        let index = match self.objects.iter().position(|o| *o == object) {
+            Some(index) => index,
+            None => {
+                self.objects.push(object);
+                self.objects.len() - 1
+            }
+        };
+        (index + 1) as CK_OBJECT_HANDLE
+    }
+
     pub fn load_find_context(&mut self, template: Attributes) -> MResult<()> {
         if template.is_empty() {
             error!("load_find_context: empty template");
@@ -160,66 +261,85 @@ impl Session {
             "load_find_context: loading for class: {:?} and options: {:?} from template {:?}",
             search_class, search_options, template
         );
-        match search_options {
-            SearchOptions::All => {
-                let objects: Vec<Object> = match search_class {
-                    pkcs11_sys::CKO_CERTIFICATE => {
-                        template.ensure_X509_or_none()?;
-                        backend()
-                            .find_all_certificates()?
-                            .into_iter()
-                            .map(Object::Certificate)
-                            .collect()
-                    }
-                    pkcs11_sys::CKO_PUBLIC_KEY => backend()
-                        .find_all_public_keys()?
-                        .into_iter()
-                        .map(Object::PublicKey)
-                        .collect(),
-                    pkcs11_sys::CKO_PRIVATE_KEY => backend()
-                        .find_all_private_keys()?
-                        .into_iter()
-                        .map(Object::RemoteObjectId)
-                        .collect(),
-                    pkcs11_sys::CKO_DATA => backend()
-                        .find_all_data_objects()?
-                        .into_iter()
-                        .map(Object::DataObject)
-                        .collect(),
-                    o => return Err(MError::Todo(format!("Object not supported: {o}"))),
-                };
-                info!(
-                    "load_find_context: found {} objects for search class {}",
-                    objects.len(),
-                    search_class
-                );
-                let indexes = objects
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| i as CK_OBJECT_HANDLE)
-                    .collect();
-                self.find_ctx = Some(FindContext {
-                    objects,
-                    unread_indexes: indexes,
-                });
-            }
-            SearchOptions::Label(_) => {
-                todo!("load_find_context: search by label")
-            }
-            SearchOptions::Id(_) => {
-                todo!("load_find_context: search by id")
+        let backend = self.backend()?;
+        let objects: Vec<Object> = match search_class {
+            pkcs11_sys::CKO_CERTIFICATE => {
+                template.ensure_X509_or_none()?;
+                backend
+                    .find_all_certificates()?
+                    .into_iter()
+                    .map(Object::Certificate)
+                    .collect()
             }
-        }
+            pkcs11_sys::CKO_PUBLIC_KEY => backend
+                .find_all_public_keys()?
+                .into_iter()
+                .map(Object::PublicKey)
+                .collect(),
+            pkcs11_sys::CKO_PRIVATE_KEY => backend
+                .find_all_private_keys()?
+                .into_iter()
+                .map(Object::RemoteObjectId)
+                .collect(),
+            pkcs11_sys::CKO_DATA => backend
+                .find_all_data_objects()?
+                .into_iter()
+                .map(Object::DataObject)
+                .collect(),
+            pkcs11_sys::CKO_SECRET_KEY => match &search_options {
+                SearchOptions::All => backend
+                    .find_all_symmetric_keys()?
+                    .into_iter()
+                    .map(Object::SymmetricKey)
+                    .collect(),
+                // the secret keys are looked up by the backend
+                query => backend
+                    .find_symmetric_key(query.clone())?
+                    .into_iter()
+                    .map(Object::SymmetricKey)
+                    .collect(),
+            },
+            o => return Err(MError::Todo(format!("Object not supported: {o}"))),
+        };
+        // the label and the id are matched against those the objects expose
+        let searched = match search_options {
+            SearchOptions::All => None,
+            SearchOptions::Label(label) => Some(Attribute::Label(label)),
+            SearchOptions::Id(id) => Some(Attribute::Id(id)),
+        };
+        let objects: Vec<Object> = match searched {
+            None => objects,
+            Some(searched) => objects
+                .into_iter()
+                .filter(|object| {
+                    object.attribute(searched.attribute_type()).as_ref() == Some(&searched)
+                })
+                .collect(),
+        };
+        info!(
+            "load_find_context: found {} objects for search class {}",
+            objects.len(),
+            search_class
+        );
+        let handles: Vec<CK_OBJECT_HANDLE> = objects
+            .into_iter()
+            .map(|object| self.add_object(object))
+            .collect();
+        self.find_ctx = Some(FindContext {
+            unread_handles: handles.clone(),
+            handles,
+        });
 
         Ok(())
     }
 }
 
-pub fn create(flags: CK_FLAGS) -> CK_SESSION_HANDLE {
+pub fn create(slot_id: CK_SLOT_ID, flags: CK_FLAGS) -> CK_SESSION_HANDLE {
     let handle = NEXT_SESSION_HANDLE.fetch_add(1, Ordering::SeqCst);
     SESSIONS.lock().unwrap().insert(
         handle,
         Session {
+            slot_id,
             flags,
             ..Default::default()
         },
@@ -235,6 +355,10 @@ pub fn flags(handle: CK_SESSION_HANDLE) -> CK_FLAGS {
     SESSIONS.lock().unwrap().get(&handle).unwrap().flags
 }
 
+pub fn slot_id(handle: CK_SESSION_HANDLE) -> CK_SLOT_ID {
+    SESSIONS.lock().unwrap().get(&handle).unwrap().slot_id
+}
+
 pub fn session<F>(h: CK_SESSION_HANDLE, callback: F) -> MResult<()>
 where
     F: FnOnce(&mut Session) -> MResult<()>,
@@ -245,9 +369,43 @@ where
 }
 
 pub fn close(handle: CK_SESSION_HANDLE) -> bool {
-    SESSIONS.lock().unwrap().remove(&handle).is_some()
+    let mut session_map = SESSIONS.lock().unwrap();
+    let closed = session_map.remove(&handle);
+    // "When the last session of a token is closed, the user is logged out."
+    if let Some(session) = &closed {
+        if !session_map.values().any(|s| s.slot_id == session.slot_id) {
+            logout_on_close(session.slot_id);
+        }
+    }
+    closed.is_some()
+}
+
+pub fn close_all(slot_id: CK_SLOT_ID) {
+    SESSIONS
+        .lock()
+        .unwrap()
+        .retain(|_, session| session.slot_id != slot_id);
+    logout_on_close(slot_id);
+}
+
+/// Log the user out of the backend when the last session of the slot is closed
+fn logout_on_close(slot_id: CK_SLOT_ID) {
+    if logout(slot_id) {
+        if let Err(e) = backend_for_slot(slot_id).and_then(|backend| backend.logout()) {
+            error!("failed logging out of slot {slot_id}: {e}");
+        }
+    }
+}
+
+pub fn is_logged_in(slot_id: CK_SLOT_ID) -> bool {
+    LOGGED_IN_SLOTS.lock().unwrap().contains(&slot_id)
+}
+
+pub fn login(slot_id: CK_SLOT_ID) {
+    LOGGED_IN_SLOTS.lock().unwrap().insert(slot_id);
 }
 
-pub fn close_all() {
-    SESSIONS.lock().unwrap().clear();
+/// Mark the slot as logged out, returning whether the user was logged in
+pub fn logout(slot_id: CK_SLOT_ID) -> bool {
+    LOGGED_IN_SLOTS.lock().unwrap().remove(&slot_id)
 }
@@ -5,11 +5,14 @@ use std::{
 };
 
 use pkcs11_sys::{
-    CKA_CLASS, CKM_DSA, CKO_PRIVATE_KEY, CKR_ARGUMENTS_BAD, CKR_BUFFER_TOO_SMALL,
-    CKR_CRYPTOKI_ALREADY_INITIALIZED, CKR_CRYPTOKI_NOT_INITIALIZED, CKR_FUNCTION_NOT_PARALLEL,
-    CKR_MECHANISM_INVALID, CKR_OPERATION_NOT_INITIALIZED, CKR_SESSION_HANDLE_INVALID,
-    CKR_SESSION_PARALLEL_NOT_SUPPORTED, CKR_SLOT_ID_INVALID, CK_ATTRIBUTE, CK_C_INITIALIZE_ARGS,
-    CK_FALSE, CK_INVALID_HANDLE,
+    CKA_CLASS, CKA_ID, CKA_KEY_TYPE, CKA_LABEL, CKA_VALUE_LEN, CKF_DECRYPT, CKF_ENCRYPT,
+    CKF_UNWRAP, CKF_WRAP, CKK_AES, CKM_AES_GCM, CKM_DSA, CKM_RSA_PKCS_OAEP, CKO_PRIVATE_KEY,
+    CKO_SECRET_KEY, CKR_ARGUMENTS_BAD, CKR_BUFFER_TOO_SMALL, CKR_CRYPTOKI_ALREADY_INITIALIZED,
+    CKR_CRYPTOKI_NOT_INITIALIZED, CKR_FUNCTION_NOT_PARALLEL, CKR_MECHANISM_INVALID,
+    CKR_OBJECT_HANDLE_INVALID, CKR_PIN_INCORRECT, CKR_SESSION_HANDLE_INVALID,
+    CKR_SESSION_PARALLEL_NOT_SUPPORTED, CKR_SESSION_READ_ONLY, CKR_SLOT_ID_INVALID,
+    CKR_USER_ALREADY_LOGGED_IN, CKR_USER_NOT_LOGGED_IN, CKR_USER_TYPE_INVALID, CKU_SO,
+    CK_ATTRIBUTE, CK_C_INITIALIZE_ARGS, CK_FALSE, CK_INVALID_HANDLE,
 };
 use serial_test::serial;
 use tracing::Level;
@@ -19,9 +22,13 @@ use zeroize::Zeroizing;
 use super::*;
 use crate::traits::{
     register_backend, Backend, Certificate, DataObject, EncryptionAlgorithm, KeyAlgorithm,
-    PrivateKey, PublicKey, RemoteObjectId, SearchOptions, Version,
+    PublicKey, RemoteObjectId, RemoteObjectType, SearchOptions, SignatureAlgorithm, SymmetricKey,
+    Version,
 };
 
+const SLOT_ID: CK_SLOT_ID = 1;
+const TEST_PIN: &[u8] = b"1234";
+
 static TRACING_INIT: Once = Once::new();
 pub fn initialize_logging() {
     TRACING_INIT.call_once(|| {
@@ -36,6 +43,26 @@ pub fn initialize_logging() {
 
 struct TestBackend {}
 
+struct TestSymmetricKey {}
+
+impl SymmetricKey for TestSymmetricKey {
+    fn remote_id(&self) -> String {
+        "test_aes_key".to_owned()
+    }
+
+    fn label(&self) -> String {
+        "test AES key".to_owned()
+    }
+
+    fn algorithm(&self) -> KeyAlgorithm {
+        KeyAlgorithm::Aes
+    }
+
+    fn key_size(&self) -> usize {
+        256
+    }
+}
+
 impl Backend for TestBackend {
     fn token_label(&self) -> [u8; 32] {
         *b"Foo software token              "
@@ -61,6 +88,29 @@ impl Backend for TestBackend {
         Version { major: 1, minor: 0 }
     }
 
+    fn login_required(&self) -> bool {
+        false
+    }
+
+    fn login(&self, pin: &[u8]) -> MResult<()> {
+        if pin != TEST_PIN {
+            return Err(MError::PinIncorrect);
+        }
+        Ok(())
+    }
+
+    fn logout(&self) -> MResult<()> {
+        Ok(())
+    }
+
+    fn init_pin(&self, _pin: &[u8]) -> MResult<()> {
+        Ok(())
+    }
+
+    fn set_pin(&self, old_pin: &[u8], _new_pin: &[u8]) -> MResult<()> {
+        self.login(old_pin)
+    }
+
     fn find_certificate(&self, _query: SearchOptions) -> MResult<Option<Arc<dyn Certificate>>> {
         Ok(None)
     }
@@ -93,14 +143,66 @@ impl Backend for TestBackend {
         Ok(vec![])
     }
 
-    fn generate_key(
+    fn find_symmetric_key(&self, query: SearchOptions) -> MResult<Option<Arc<dyn SymmetricKey>>> {
+        let key = TestSymmetricKey {};
+        let found = match query {
+            SearchOptions::All => true,
+            SearchOptions::Label(label) => label == key.label(),
+            SearchOptions::Id(id) => id == key.remote_id().as_bytes(),
+        };
+        Ok(found.then(|| Arc::new(key) as Arc<dyn SymmetricKey>))
+    }
+
+    fn find_all_symmetric_keys(&self) -> MResult<Vec<Arc<dyn SymmetricKey>>> {
+        Ok(vec![Arc::new(TestSymmetricKey {})])
+    }
+
+    fn generate_key_pair(
         &self,
         _algorithm: KeyAlgorithm,
+        _key_length: usize,
+        _label: Option<&str>,
+    ) -> MResult<(Arc<dyn RemoteObjectId>, Arc<dyn RemoteObjectId>)> {
+        todo!()
+    }
+
+    fn create_object(
+        &self,
+        _remote_type: RemoteObjectType,
+        _value: &[u8],
         _label: Option<&str>,
-    ) -> MResult<Arc<dyn PrivateKey>> {
+    ) -> MResult<Arc<dyn RemoteObjectId>> {
         todo!()
     }
 
+    fn sign(
+        &self,
+        _remote_object: Arc<dyn RemoteObjectId>,
+        _algorithm: &SignatureAlgorithm,
+        _data: &[u8],
+    ) -> MResult<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn verify(
+        &self,
+        _remote_object: Arc<dyn RemoteObjectId>,
+        _algorithm: &SignatureAlgorithm,
+        _data: &[u8],
+        _signature: &[u8],
+    ) -> MResult<()> {
+        Ok(())
+    }
+
+    fn encrypt(
+        &self,
+        _remote_object: Arc<dyn RemoteObjectId>,
+        _algorithm: EncryptionAlgorithm,
+        _cleartext: Vec<u8>,
+    ) -> MResult<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
     fn decrypt(
         &self,
         _remote_object: Arc<dyn RemoteObjectId>,
@@ -109,6 +211,26 @@ impl Backend for TestBackend {
     ) -> MResult<Zeroizing<Vec<u8>>> {
         Ok(Zeroizing::new(Vec::new()))
     }
+
+    fn wrap_key(
+        &self,
+        _wrapping_key: Arc<dyn RemoteObjectId>,
+        _algorithm: EncryptionAlgorithm,
+        _key: Arc<dyn RemoteObjectId>,
+    ) -> MResult<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn unwrap_key(
+        &self,
+        _unwrapping_key: Arc<dyn RemoteObjectId>,
+        _algorithm: EncryptionAlgorithm,
+        _wrapped_key: &[u8],
+        _key_type: RemoteObjectType,
+        _label: Option<&str>,
+    ) -> MResult<Arc<dyn RemoteObjectId>> {
+        todo!()
+    }
 }
 
 cryptoki_fn!(
@@ -310,7 +432,7 @@ fn get_mechanism_list() {
     unsafe {
         mechanisms.set_len(count as usize);
     }
-    assert_eq!(mechanisms, *SUPPORTED_SIGNATURE_MECHANISMS);
+    assert_eq!(mechanisms, *SUPPORTED_MECHANISMS);
     // Expect CKR_SLOT_ID_INVALID if slotID references a nonexistent slot.
     assert_eq!(
         unsafe { C_GetMechanismList(SLOT_ID + 1, ptr::null_mut(), &mut count) },
@@ -342,9 +464,22 @@ fn get_mechanism_info() {
     assert_eq!({ C_Initialize(ptr::null_mut()) }, CKR_OK);
     let mut info = CK_MECHANISM_INFO::default();
     assert_eq!(
-        unsafe { C_GetMechanismInfo(SLOT_ID, SUPPORTED_SIGNATURE_MECHANISMS[0], &mut info,) },
+        unsafe { C_GetMechanismInfo(SLOT_ID, SUPPORTED_MECHANISMS[0], &mut info,) },
+        CKR_OK
+    );
+    assert_eq!(
+        unsafe { C_GetMechanismInfo(SLOT_ID, CKM_RSA_PKCS_OAEP, &mut info) },
         CKR_OK
     );
+    assert_eq!(
+        info.flags,
+        CKF_ENCRYPT | CKF_DECRYPT | CKF_WRAP | CKF_UNWRAP
+    );
+    assert_eq!(
+        unsafe { C_GetMechanismInfo(SLOT_ID, CKM_AES_GCM, &mut info) },
+        CKR_OK
+    );
+    assert_eq!(info.flags, CKF_ENCRYPT | CKF_DECRYPT);
     // Expect CKR_MECHANISM_INVALID if type is an unsupported mechanism.
     assert_eq!(
         unsafe { C_GetMechanismInfo(SLOT_ID, CKM_DSA, &mut info) },
@@ -352,13 +487,13 @@ fn get_mechanism_info() {
     );
     // Expect CKR_ARGUMENTS_BAD if pInfo is null.
     assert_eq!(
-        unsafe { C_GetMechanismInfo(SLOT_ID, SUPPORTED_SIGNATURE_MECHANISMS[0], ptr::null_mut(),) },
+        unsafe { C_GetMechanismInfo(SLOT_ID, SUPPORTED_MECHANISMS[0], ptr::null_mut(),) },
         CKR_ARGUMENTS_BAD
     );
     // Expect CKR_CRYPTOKI_NOT_INITIALIZED if token is not initialized.
     assert_eq!({ C_Finalize(ptr::null_mut()) }, CKR_OK);
     assert_eq!(
-        unsafe { C_GetMechanismInfo(SLOT_ID, SUPPORTED_SIGNATURE_MECHANISMS[0], ptr::null_mut(),) },
+        unsafe { C_GetMechanismInfo(SLOT_ID, SUPPORTED_MECHANISMS[0], ptr::null_mut(),) },
         CKR_CRYPTOKI_NOT_INITIALIZED
     );
 }
@@ -460,6 +595,79 @@ fn get_session_info() {
     assert_eq!({ C_Finalize(ptr::null_mut()) }, CKR_OK);
 }
 
+#[test]
+#[serial]
+fn login_logout() {
+    test_init();
+    assert_eq!({ C_Initialize(ptr::null_mut()) }, CKR_OK);
+    let mut handle = CK_INVALID_HANDLE;
+    assert_eq!(
+        unsafe {
+            C_OpenSession(
+                SLOT_ID,
+                CKF_SERIAL_SESSION,
+                ptr::null_mut(),
+                None,
+                &mut handle,
+            )
+        },
+        CKR_OK
+    );
+    let mut pin = TEST_PIN.to_vec();
+    // Expect CKR_USER_NOT_LOGGED_IN if the user is not logged in.
+    assert_eq!({ C_Logout(handle) }, CKR_USER_NOT_LOGGED_IN);
+    // Expect CKR_USER_TYPE_INVALID for the Security Officer.
+    assert_eq!(
+        unsafe { C_Login(handle, CKU_SO, pin.as_mut_ptr(), pin.len() as CK_ULONG) },
+        CKR_USER_TYPE_INVALID
+    );
+    // Expect CKR_PIN_INCORRECT if the PIN is wrong.
+    let mut wrong_pin = b"4321".to_vec();
+    assert_eq!(
+        unsafe {
+            C_Login(
+                handle,
+                CKU_USER,
+                wrong_pin.as_mut_ptr(),
+                wrong_pin.len() as CK_ULONG,
+            )
+        },
+        CKR_PIN_INCORRECT
+    );
+    assert_eq!(
+        unsafe { C_Login(handle, CKU_USER, pin.as_mut_ptr(), pin.len() as CK_ULONG) },
+        CKR_OK
+    );
+    let mut session_info = CK_SESSION_INFO::default();
+    assert_eq!(
+        unsafe { C_GetSessionInfo(handle, &mut session_info) },
+        CKR_OK
+    );
+    assert_eq!(session_info.state, CKS_RO_USER_FUNCTIONS);
+    // Expect CKR_USER_ALREADY_LOGGED_IN on a second login.
+    assert_eq!(
+        unsafe { C_Login(handle, CKU_USER, pin.as_mut_ptr(), pin.len() as CK_ULONG) },
+        CKR_USER_ALREADY_LOGGED_IN
+    );
+    // Expect CKR_SESSION_READ_ONLY when changing the PIN in a read-only session.
+    assert_eq!(
+        unsafe {
+            C_SetPIN(
+                handle,
+                pin.as_mut_ptr(),
+                pin.len() as CK_ULONG,
+                wrong_pin.as_mut_ptr(),
+                wrong_pin.len() as CK_ULONG,
+            )
+        },
+        CKR_SESSION_READ_ONLY
+    );
+    assert_eq!({ C_Logout(handle) }, CKR_OK);
+    assert_eq!({ C_Logout(handle) }, CKR_USER_NOT_LOGGED_IN);
+    assert_eq!({ C_CloseSession(handle) }, CKR_OK);
+    assert_eq!({ C_Finalize(ptr::null_mut()) }, CKR_OK);
+}
+
 #[test]
 #[serial]
 fn get_attribute_value() {
@@ -488,7 +696,7 @@ fn get_attribute_value() {
                 template.len() as CK_ULONG,
             )
         },
-        CKR_OPERATION_NOT_INITIALIZED
+        CKR_OBJECT_HANDLE_INVALID
     );
     assert_eq!({ C_Finalize(ptr::null_mut()) }, CKR_OK);
     assert_eq!(
@@ -572,6 +780,125 @@ fn find_objects() {
     );
 }
 
+#[test]
+#[serial]
+fn find_secret_keys() {
+    test_init();
+    assert_eq!({ C_Initialize(ptr::null_mut()) }, CKR_OK);
+    let mut handle = CK_INVALID_HANDLE;
+    assert_eq!(
+        unsafe {
+            C_OpenSession(
+                SLOT_ID,
+                CKF_SERIAL_SESSION,
+                ptr::null_mut(),
+                None,
+                &mut handle,
+            )
+        },
+        CKR_OK
+    );
+    let mut template = vec![CK_ATTRIBUTE {
+        type_: CKA_CLASS,
+        pValue: &CKO_SECRET_KEY as *const CK_ULONG as CK_VOID_PTR,
+        ulValueLen: std::mem::size_of_val(&CKO_SECRET_KEY) as CK_ULONG,
+    }];
+    assert_eq!(
+        unsafe { C_FindObjectsInit(handle, template.as_mut_ptr(), template.len() as CK_ULONG) },
+        CKR_OK
+    );
+    let mut objects = vec![CK_OBJECT_HANDLE::default()];
+    let mut count = 0;
+    assert_eq!(
+        unsafe { C_FindObjects(handle, objects.as_mut_ptr(), 1, &mut count) },
+        CKR_OK
+    );
+    assert_eq!(count, 1);
+    assert_eq!({ C_FindObjectsFinal(handle) }, CKR_OK);
+
+    let mut key_type: CK_ULONG = 0;
+    let mut value_len: CK_ULONG = 0;
+    let mut template = vec![
+        CK_ATTRIBUTE {
+            type_: CKA_KEY_TYPE,
+            pValue: addr_of_mut!(key_type) as CK_VOID_PTR,
+            ulValueLen: std::mem::size_of::<CK_ULONG>() as CK_ULONG,
+        },
+        CK_ATTRIBUTE {
+            type_: CKA_VALUE_LEN,
+            pValue: addr_of_mut!(value_len) as CK_VOID_PTR,
+            ulValueLen: std::mem::size_of::<CK_ULONG>() as CK_ULONG,
+        },
+    ];
+    assert_eq!(
+        unsafe {
+            C_GetAttributeValue(
+                handle,
+                objects[0],
+                template.as_mut_ptr(),
+                template.len() as CK_ULONG,
+            )
+        },
+        CKR_OK
+    );
+    assert_eq!(key_type, CKK_AES);
+    // CKA_VALUE_LEN is in bytes
+    assert_eq!(value_len, 32);
+    assert_eq!({ C_Finalize(ptr::null_mut()) }, CKR_OK);
+}
+
+#[test]
+#[serial]
+fn find_secret_keys_by_label_and_id() {
+    test_init();
+    assert_eq!({ C_Initialize(ptr::null_mut()) }, CKR_OK);
+    let mut handle = CK_INVALID_HANDLE;
+    assert_eq!(
+        unsafe {
+            C_OpenSession(
+                SLOT_ID,
+                CKF_SERIAL_SESSION,
+                ptr::null_mut(),
+                None,
+                &mut handle,
+            )
+        },
+        CKR_OK
+    );
+    for (type_, value, expected_count) in [
+        (CKA_LABEL, &b"test AES key"[..], 1),
+        (CKA_LABEL, &b"another key"[..], 0),
+        (CKA_ID, &b"test_aes_key"[..], 1),
+        (CKA_ID, &b"another_key"[..], 0),
+    ] {
+        let mut template = vec![
+            CK_ATTRIBUTE {
+                type_: CKA_CLASS,
+                pValue: &CKO_SECRET_KEY as *const CK_ULONG as CK_VOID_PTR,
+                ulValueLen: std::mem::size_of_val(&CKO_SECRET_KEY) as CK_ULONG,
+            },
+            CK_ATTRIBUTE {
+                type_,
+                pValue: value.as_ptr() as CK_VOID_PTR,
+                ulValueLen: value.len() as CK_ULONG,
+            },
+        ];
+        assert_eq!(
+            unsafe { C_FindObjectsInit(handle, template.as_mut_ptr(), template.len() as CK_ULONG) },
+            CKR_OK
+        );
+        let mut objects = vec![CK_OBJECT_HANDLE::default()];
+        let mut count = 0;
+        assert_eq!(
+            unsafe { C_FindObjects(handle, objects.as_mut_ptr(), 1, &mut count) },
+            CKR_OK
+        );
+        assert_eq!(count, expected_count);
+        assert_eq!({ C_FindObjectsFinal(handle) }, CKR_OK);
+    }
+    assert_eq!({ C_Finalize(ptr::null_mut()) }, CKR_OK);
+}
+
 #[test]
 #[serial]
 fn find_objects_final() {
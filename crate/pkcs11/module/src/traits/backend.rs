@@ -1,29 +1,50 @@
 use std::sync::{Arc, RwLock};
 
 use once_cell::sync::Lazy;
+use pkcs11_sys::CK_SLOT_ID;
 use zeroize::Zeroizing;
 
 use crate::{
     traits::{
-        Certificate, DataObject, EncryptionAlgorithm, KeyAlgorithm, PrivateKey, PublicKey,
-        RemoteObjectId, SearchOptions, Version,
+        Certificate, DataObject, EncryptionAlgorithm, KeyAlgorithm, PublicKey, RemoteObjectId,
+        RemoteObjectType, SearchOptions, SignatureAlgorithm, SymmetricKey, Version,
     },
-    MResult,
+    MError, MResult,
 };
 
-//  The Backend is first staged so it can be stored in a Box<dyn Backend>. This
-//  allows the Backend to be reference with `&'static`.
-static STAGED_BACKEND: RwLock<Option<Box<dyn Backend>>> = RwLock::new(None);
-static BACKEND: Lazy<Box<dyn Backend>> =
-    Lazy::new(|| STAGED_BACKEND.write().unwrap().take().unwrap());
+//  The Backends are first staged so they can be stored in a Vec<Box<dyn Backend>>. This
+//  allows the Backends to be referenced with `&'static`.
+static STAGED_BACKENDS: RwLock<Vec<Box<dyn Backend>>> = RwLock::new(Vec::new());
+static BACKENDS: Lazy<Vec<Box<dyn Backend>>> =
+    Lazy::new(|| std::mem::take(&mut *STAGED_BACKENDS.write().unwrap()));
 
 /// Stores a backend to later be returned by all calls `crate::backend()`.
 pub fn register_backend(backend: Box<dyn Backend>) {
-    *STAGED_BACKEND.write().unwrap() = Some(backend);
+    register_backends(vec![backend]);
 }
 
+/// Stores one backend per slot: the slot ids are 1, 2, ... in the order of the backends.
+pub fn register_backends(backends: Vec<Box<dyn Backend>>) {
+    *STAGED_BACKENDS.write().unwrap() = backends;
+}
+
+/// The backend of the first slot
 pub fn backend() -> &'static dyn Backend {
-    BACKEND.as_ref()
+    BACKENDS[0].as_ref()
+}
+
+/// The ids of the slots, one per registered backend
+pub fn slot_ids() -> Vec<CK_SLOT_ID> {
+    (1..=BACKENDS.len() as CK_SLOT_ID).collect()
+}
+
+/// The backend of the slot `slot_id`
+pub fn backend_for_slot(slot_id: CK_SLOT_ID) -> MResult<&'static dyn Backend> {
+    (slot_id as usize)
+        .checked_sub(1)
+        .and_then(|index| BACKENDS.get(index))
+        .map(|backend| backend.as_ref())
+        .ok_or(MError::SlotIdInvalid(slot_id))
 }
 
 pub trait Backend: Send + Sync {
@@ -42,6 +63,18 @@ pub trait Backend: Send + Sync {
     /// The version of this library
     fn library_version(&self) -> Version;
 
+    /// Whether the user must log in with a PIN before using the token
+    fn login_required(&self) -> bool;
+    /// Log the user in: the PIN unlocks the credential used to connect to the KMS.
+    /// Fails with `MError::PinIncorrect` if the PIN cannot unlock the credential.
+    fn login(&self, pin: &[u8]) -> MResult<()>;
+    /// Log the user out, forgetting the unlocked credential
+    fn logout(&self) -> MResult<()>;
+    /// Protect the credential of the token with the PIN
+    fn init_pin(&self, pin: &[u8]) -> MResult<()>;
+    /// Protect the credential of the token with a new PIN
+    fn set_pin(&self, old_pin: &[u8], new_pin: &[u8]) -> MResult<()>;
+
     fn find_certificate(&self, query: SearchOptions) -> MResult<Option<Arc<dyn Certificate>>>;
     fn find_all_certificates(&self) -> MResult<Vec<Arc<dyn Certificate>>>;
     fn find_private_key(&self, query: SearchOptions) -> MResult<Option<Arc<dyn RemoteObjectId>>>;
@@ -50,11 +83,51 @@ pub trait Backend: Send + Sync {
     fn find_all_public_keys(&self) -> MResult<Vec<Arc<dyn PublicKey>>>;
     fn find_data_object(&self, query: SearchOptions) -> MResult<Option<Arc<dyn DataObject>>>;
     fn find_all_data_objects(&self) -> MResult<Vec<Arc<dyn DataObject>>>;
-    fn generate_key(
+    fn find_symmetric_key(&self, query: SearchOptions) -> MResult<Option<Arc<dyn SymmetricKey>>>;
+    fn find_all_symmetric_keys(&self) -> MResult<Vec<Arc<dyn SymmetricKey>>>;
+    /// Generate a key pair and return the (public key, private key) remote objects
+    ///
+    /// `key_length` is the modulus size in bits for RSA
+    /// and the size of the curve in bits for ECC.
+    fn generate_key_pair(
         &self,
         algorithm: KeyAlgorithm,
+        key_length: usize,
         label: Option<&str>,
-    ) -> MResult<Arc<dyn PrivateKey>>;
+    ) -> MResult<(Arc<dyn RemoteObjectId>, Arc<dyn RemoteObjectId>)>;
+
+    /// Import an object from its value:
+    /// the DER encoding of a certificate or the bytes of a symmetric key
+    fn create_object(
+        &self,
+        remote_type: RemoteObjectType,
+        value: &[u8],
+        label: Option<&str>,
+    ) -> MResult<Arc<dyn RemoteObjectId>>;
+
+    fn sign(
+        &self,
+        remote_object: Arc<dyn RemoteObjectId>,
+        algorithm: &SignatureAlgorithm,
+        data: &[u8],
+    ) -> MResult<Vec<u8>>;
+
+    /// Verify the signature of `data`,
+    /// failing with `MError::SignatureInvalid` if it is not valid
+    fn verify(
+        &self,
+        remote_object: Arc<dyn RemoteObjectId>,
+        algorithm: &SignatureAlgorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> MResult<()>;
+
+    fn encrypt(
+        &self,
+        remote_object: Arc<dyn RemoteObjectId>,
+        algorithm: EncryptionAlgorithm,
+        cleartext: Vec<u8>,
+    ) -> MResult<Vec<u8>>;
 
     fn decrypt(
         &self,
@@ -62,4 +135,23 @@ pub trait Backend: Send + Sync {
         algorithm: EncryptionAlgorithm,
         ciphertext: Vec<u8>,
     ) -> MResult<Zeroizing<Vec<u8>>>;
+
+    /// Export the `key` wrapped by the `wrapping_key`
+    fn wrap_key(
+        &self,
+        wrapping_key: Arc<dyn RemoteObjectId>,
+        algorithm: EncryptionAlgorithm,
+        key: Arc<dyn RemoteObjectId>,
+    ) -> MResult<Vec<u8>>;
+
+    /// Import the `wrapped_key` unwrapped by the `unwrapping_key`
+    /// as a key of type `key_type`
+    fn unwrap_key(
+        &self,
+        unwrapping_key: Arc<dyn RemoteObjectId>,
+        algorithm: EncryptionAlgorithm,
+        wrapped_key: &[u8],
+        key_type: RemoteObjectType,
+        label: Option<&str>,
+    ) -> MResult<Arc<dyn RemoteObjectId>>;
 }
@@ -1,5 +1,30 @@
-#[derive(Debug, Clone, Copy)]
+use crate::traits::DigestType;
+
+#[derive(Debug, Clone)]
 pub enum EncryptionAlgorithm {
     // CKM_RSA_PKCS
     RsaPkcs1v15,
+    // CKM_RSA_PKCS_OAEP
+    RsaOaep {
+        digest: DigestType,
+        mask_generation_function: DigestType,
+    },
+    // CKM_AES_CBC_PAD
+    AesCbcPad {
+        iv: [u8; 16],
+    },
+    // CKM_AES_GCM
+    AesGcm {
+        iv: Vec<u8>,
+        aad: Vec<u8>,
+        tag_bits: u64,
+    },
+    // CKM_AES_KEY_WRAP_KWP
+    AesKeyWrapPad,
+    // CKM_RSA_AES_KEY_WRAP
+    RsaAesKeyWrap {
+        aes_key_bits: u64,
+        digest: DigestType,
+        mask_generation_function: DigestType,
+    },
 }
@@ -19,7 +19,9 @@
 
 use std::{any::Any, hash::Hash, sync::Arc};
 
-pub use backend::{backend, register_backend, Backend};
+pub use backend::{
+    backend, backend_for_slot, register_backend, register_backends, slot_ids, Backend,
+};
 pub use certificate::Certificate;
 pub use data_object::DataObject;
 pub use encryption_algorithms::EncryptionAlgorithm;
@@ -27,6 +29,7 @@ pub use once_cell;
 pub use private_key::PrivateKey;
 pub use remote_object_id::{RemoteObjectId, RemoteObjectType};
 pub use signature_algorithm::SignatureAlgorithm;
+pub use symmetric_key::SymmetricKey;
 
 use crate::{
     core::attribute::{Attribute, AttributeType, Attributes},
@@ -40,10 +43,11 @@ mod encryption_algorithms;
 mod private_key;
 mod remote_object_id;
 mod signature_algorithm;
+mod symmetric_key;
 
 pub type Digest = [u8; 20];
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum DigestType {
     Sha1,
     Sha224,
@@ -90,7 +94,7 @@ impl Hash for dyn PublicKey {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SearchOptions {
     All,
     Label(String),
@@ -117,6 +121,7 @@ impl TryFrom<&Attributes> for SearchOptions {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KeyAlgorithm {
+    Aes,
     Rsa,
     Ecc,
 }
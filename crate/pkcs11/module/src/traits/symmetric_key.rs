@@ -0,0 +1,55 @@
+// Copyright 2024 Cosmian Tech SAS
+// Changes made to the original code are
+// licensed under the Business Source License version 1.1.
+
+//! `CKO_SECRET_KEY` object as defined in PKCS#11 2.40 4.10
+//! [PKCS#11 2.40 ~ 4.10](http://docs.oasis-open.org/pkcs11/pkcs11-base/v2.40/os/pkcs11-base-v2.40-os.html#_Toc416959720)
+//!
+//! The key material never leaves the KMS: the key is only referenced by its remote id.
+
+use std::{any::Any, hash::Hash, sync::Arc};
+
+use crate::traits::{KeyAlgorithm, RemoteObjectId, RemoteObjectType};
+
+pub trait SymmetricKey: Send + Sync {
+    /// The unique identifier of the key on the remote server
+    fn remote_id(&self) -> String;
+    fn label(&self) -> String;
+    fn algorithm(&self) -> KeyAlgorithm;
+    /// The size of the key in bits
+    fn key_size(&self) -> usize;
+}
+
+impl std::fmt::Debug for dyn SymmetricKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SymmetricKey")
+            .field("id", &self.remote_id())
+            .field("label", &self.label())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for dyn SymmetricKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.remote_id() == other.remote_id()
+    }
+}
+
+impl Eq for dyn SymmetricKey {}
+
+impl Hash for dyn SymmetricKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.type_id().hash(state);
+        self.remote_id().hash(state);
+    }
+}
+
+impl RemoteObjectId for Arc<dyn SymmetricKey> {
+    fn remote_id(&self) -> String {
+        self.as_ref().remote_id()
+    }
+
+    fn remote_type(&self) -> RemoteObjectType {
+        RemoteObjectType::SymmetricKey
+    }
+}
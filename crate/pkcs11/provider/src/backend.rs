@@ -1,38 +1,264 @@
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use cosmian_kmip::kmip::kmip_types::KeyFormatType;
-use cosmian_kms_client::KmsClient;
-use cosmian_pkcs11_module::traits::{
-    Backend, Certificate, DataObject, EncryptionAlgorithm, KeyAlgorithm, PrivateKey, PublicKey,
-    RemoteObjectId, RemoteObjectType, SearchOptions, SignatureAlgorithm, Version,
+use base64::{engine::general_purpose, Engine};
+use cosmian_kmip::kmip::kmip_types::{CryptographicAlgorithm, KeyFormatType};
+use cosmian_kms_client::{ClientConf, KmsClient, Oauth2Conf, Pkcs11SlotConf};
+use cosmian_pkcs11_module::{
+    traits::{
+        Backend, Certificate, DataObject, EncryptionAlgorithm, KeyAlgorithm, PublicKey,
+        RemoteObjectId, RemoteObjectType, SearchOptions, SignatureAlgorithm, SymmetricKey, Version,
+    },
+    MError, MResult,
 };
 use tracing::{debug, trace};
 use zeroize::Zeroizing;
 
 use crate::{
     error::Pkcs11Error,
-    kms_object::{get_kms_objects, kms_decrypt, locate_kms_objects},
+    kms_object::{
+        block_on, get_kms_object_attributes, get_kms_objects, get_kms_objects_attributes,
+        kms_create_key_pair, kms_create_object, kms_decrypt, kms_encrypt, kms_sign, kms_unwrap_key,
+        kms_verify, kms_wrap_key, locate_kms_objects,
+    },
+    pin::{check_pkcs12_password, pin_str, protect_with_pin, reprotect_pkcs12, unprotect_with_pin},
     pkcs11_certificate::Pkcs11Certificate,
     pkcs11_data_object::Pkcs11DataObject,
     pkcs11_private_key::Pkcs11PrivateKey,
+    pkcs11_symmetric_key::Pkcs11SymmetricKey,
 };
 
 const COSMIAN_PKCS11_DISK_ENCRYPTION_TAG: &str = "disk-encryption";
 
+/// The tag of the KMS objects exposed by the provider
+fn disk_encryption_tag() -> String {
+    std::env::var("COSMIAN_PKCS11_DISK_ENCRYPTION_TAG")
+        .unwrap_or(COSMIAN_PKCS11_DISK_ENCRYPTION_TAG.to_string())
+}
+
+/// The tags of an object created by the provider:
+/// the disk encryption tag and the label, if any
+fn object_tags(label: Option<&str>) -> Vec<String> {
+    let mut tags = vec![disk_encryption_tag()];
+    tags.extend(label.map(ToString::to_string));
+    tags
+}
+
+/// The JWT is refreshed when it expires within this number of seconds
+const JWT_REFRESH_MARGIN: u64 = 30;
+
+/// The current UNIX timestamp in seconds
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The expiration of a JWT: its `exp` claim
+pub(crate) fn jwt_expiry(jwt: &str) -> Option<u64> {
+    let claims = general_purpose::URL_SAFE_NO_PAD
+        .decode(jwt.split('.').nth(1)?)
+        .ok()?;
+    serde_json::from_slice::<serde_json::Value>(&claims)
+        .ok()?
+        .get("exp")?
+        .as_u64()
+}
+
+/// The `OAuth2` session of the logged in user
+struct OAuth2Session {
+    /// The PIN, protecting the refresh tokens rotated by the identity provider
+    pin: Zeroizing<Vec<u8>>,
+    refresh_token: Zeroizing<String>,
+    /// The UNIX timestamp at which the JWT expires, if known
+    expires_at: Option<u64>,
+}
+
+/// A slot whose identity is unlocked by the PIN of the token
+struct Pkcs11Slot {
+    /// The configuration file holding the slot configuration
+    conf_path: PathBuf,
+    /// The index of the slot in the `pkcs11_slots` of the configuration
+    index: usize,
+    conf: Mutex<Pkcs11SlotConf>,
+}
+
 pub struct CkmsBackend {
-    kms_client: KmsClient,
+    /// The client configuration the slot configuration applies to
+    client_conf: ClientConf,
+    /// The slot configuration, if the token requires a login
+    slot: Option<Pkcs11Slot>,
+    /// The KMS client, available once the user is logged in
+    kms_client: RwLock<Option<KmsClient>>,
+    /// The `OAuth2` session, when the user logged in with a refresh token
+    oauth2_session: Mutex<Option<OAuth2Session>>,
 }
 
 impl CkmsBackend {
     /// Instantiate a new `CkmsBackend` using the
     pub fn instantiate(kms_client: KmsClient) -> Result<Self, Pkcs11Error> {
-        Ok(CkmsBackend { kms_client })
+        Ok(CkmsBackend {
+            client_conf: ClientConf::default(),
+            slot: None,
+            kms_client: RwLock::new(Some(kms_client)),
+            oauth2_session: Mutex::new(None),
+        })
+    }
+
+    /// Instantiate a new `CkmsBackend` for the slot at `index` in the `pkcs11_slots`
+    /// of the configuration: the KMS client is created when the user logs in.
+    pub fn instantiate_slot(
+        client_conf: &ClientConf,
+        conf_path: &Path,
+        index: usize,
+    ) -> Result<Self, Pkcs11Error> {
+        let slot_conf = client_conf
+            .pkcs11_slots
+            .as_ref()
+            .and_then(|slots| slots.get(index))
+            .ok_or_else(|| Pkcs11Error::Default(format!("no PKCS#11 slot at index {index}")))?
+            .clone();
+        Ok(CkmsBackend {
+            client_conf: client_conf.clone(),
+            slot: Some(Pkcs11Slot {
+                conf_path: conf_path.to_path_buf(),
+                index,
+                conf: Mutex::new(slot_conf),
+            }),
+            kms_client: RwLock::new(None),
+            oauth2_session: Mutex::new(None),
+        })
+    }
+
+    /// The KMS client of the logged in user, whose JWT is refreshed if it expired
+    fn kms_client(&self) -> MResult<KmsClient> {
+        self.refresh_expired_jwt()?;
+        self.kms_client
+            .read()
+            .map_err(|e| Pkcs11Error::Default(e.to_string()))?
+            .clone()
+            .ok_or(MError::UserNotLoggedIn)
+    }
+
+    /// Connect to the KMS with a new JWT when the one of the `OAuth2` session expires
+    fn refresh_expired_jwt(&self) -> MResult<()> {
+        let Some(slot) = &self.slot else {
+            return Ok(())
+        };
+        let mut oauth2_session = self
+            .oauth2_session
+            .lock()
+            .map_err(|e| Pkcs11Error::Default(e.to_string()))?;
+        let Some(session) = oauth2_session.as_mut() else {
+            return Ok(())
+        };
+        if session
+            .expires_at
+            .map_or(true, |expires_at| expires_at > now() + JWT_REFRESH_MARGIN)
+        {
+            return Ok(())
+        }
+        debug!(
+            "refreshing the expired JWT of token {}",
+            slot.conf().token_label
+        );
+        let mut client_conf = self.client_conf.for_pkcs11_slot(&slot.conf());
+        let oauth2_conf = oauth2_conf(&client_conf)?;
+        client_conf.kms_access_token = Some(slot.refresh_jwt(oauth2_conf, session)?);
+        let kms_client = client_conf
+            .initialize_kms_client()
+            .map_err(Pkcs11Error::from)?;
+        *self
+            .kms_client
+            .write()
+            .map_err(|e| Pkcs11Error::Default(e.to_string()))? = Some(kms_client);
+        Ok(())
+    }
+
+    /// The slot configuration of a token requiring a login:
+    /// without one, there is no PIN to manage
+    fn slot(&self) -> MResult<&Pkcs11Slot> {
+        self.slot.as_ref().ok_or(MError::TokenWriteProtected)
+    }
+}
+
+/// The `OAuth2` configuration to refresh the JWT with
+fn oauth2_conf(client_conf: &ClientConf) -> Result<&Oauth2Conf, Pkcs11Error> {
+    client_conf.oauth2_conf.as_ref().ok_or_else(|| {
+        Pkcs11Error::Default("an OAuth2 configuration is required to refresh the token".to_owned())
+    })
+}
+
+impl Pkcs11Slot {
+    fn conf(&self) -> Pkcs11SlotConf {
+        self.conf
+            .lock()
+            .expect("slot configuration lock poisoned")
+            .clone()
+    }
+
+    /// Store the slot configuration in memory and in the configuration file
+    fn save(&self, slot_conf: Pkcs11SlotConf) -> Result<(), Pkcs11Error> {
+        let mut client_conf = ClientConf::load(&self.conf_path)?;
+        let slot = client_conf
+            .pkcs11_slots
+            .as_mut()
+            .and_then(|slots| slots.get_mut(self.index))
+            .ok_or_else(|| {
+                Pkcs11Error::Default(format!(
+                    "the PKCS#11 slot at index {} is no longer configured",
+                    self.index
+                ))
+            })?;
+        *slot = slot_conf.clone();
+        client_conf.save(&self.conf_path)?;
+        *self.conf.lock().expect("slot configuration lock poisoned") = slot_conf;
+        Ok(())
+    }
+
+    /// Obtain a JWT with the refresh token of the session: the refresh token
+    /// rotated by the identity provider replaces it, protected by the PIN
+    fn refresh_jwt(
+        &self,
+        oauth2_conf: &Oauth2Conf,
+        session: &mut OAuth2Session,
+    ) -> Result<String, Pkcs11Error> {
+        let tokens = block_on(oauth2_conf.refresh_jwt(&session.refresh_token))??;
+        if let Some(refresh_token) = tokens.refresh_token {
+            if refresh_token != *session.refresh_token {
+                let mut slot_conf = self.conf();
+                slot_conf.pin_protected_oauth2_refresh_token =
+                    Some(protect_with_pin(&session.pin, refresh_token.as_bytes())?);
+                self.save(slot_conf)?;
+                session.refresh_token = Zeroizing::new(refresh_token);
+            }
+        }
+        session.expires_at = jwt_expiry(&tokens.jwt)
+            .or_else(|| tokens.expires_in.map(|expires_in| now() + expires_in));
+        Ok(tokens.jwt)
+    }
+
+    /// The PKCS#12 client certificate of the slot, if any
+    fn pkcs12_path(&self) -> Option<PathBuf> {
+        self.conf().ssl_client_pkcs12_path.map(PathBuf::from)
     }
 }
 
 impl Backend for CkmsBackend {
     fn token_label(&self) -> [u8; 32] {
-        *b"Cosmian-KMS                     "
+        let Some(slot) = &self.slot else {
+            return *b"Cosmian-KMS                     "
+        };
+        let token_label = slot.conf().token_label;
+        let label = token_label.as_bytes();
+        let len = label.len().min(32);
+        let mut padded = [0x20; 32];
+        padded[0..len].copy_from_slice(&label[..len]);
+        padded
     }
 
     fn token_manufacturer_id(&self) -> [u8; 32] {
@@ -63,6 +289,108 @@ impl Backend for CkmsBackend {
         Version { major, minor }
     }
 
+    fn login_required(&self) -> bool {
+        self.slot.is_some()
+    }
+
+    fn login(&self, pin: &[u8]) -> MResult<()> {
+        let Some(slot) = &self.slot else {
+            // the KMS client is already authenticated
+            return Ok(())
+        };
+        let slot_conf = slot.conf();
+        let mut client_conf = self.client_conf.for_pkcs11_slot(&slot_conf);
+        let mut oauth2_session = None;
+        if let Some(protected_token) = &slot_conf.pin_protected_oauth2_refresh_token {
+            let refresh_token =
+                String::from_utf8(unprotect_with_pin(pin, protected_token)?.to_vec())
+                    .map_err(Pkcs11Error::from)?;
+            let mut session = OAuth2Session {
+                pin: Zeroizing::new(pin.to_vec()),
+                refresh_token: Zeroizing::new(refresh_token),
+                expires_at: None,
+            };
+            client_conf.kms_access_token =
+                Some(slot.refresh_jwt(oauth2_conf(&client_conf)?, &mut session)?);
+            oauth2_session = Some(session);
+        } else if let (Some(p12_path), None) = (
+            &slot_conf.ssl_client_pkcs12_path,
+            &slot_conf.ssl_client_pkcs12_password,
+        ) {
+            // the PIN is the password of the PKCS#12
+            let pin = pin_str(pin)?;
+            check_pkcs12_password(Path::new(p12_path), pin)?;
+            client_conf.ssl_client_pkcs12_password = Some(pin.to_owned());
+        } else {
+            return Err(MError::UserPinNotInitialized(slot_conf.token_label))
+        }
+        let kms_client = client_conf
+            .initialize_kms_client()
+            .map_err(Pkcs11Error::from)?;
+        *self
+            .kms_client
+            .write()
+            .map_err(|e| Pkcs11Error::Default(e.to_string()))? = Some(kms_client);
+        *self
+            .oauth2_session
+            .lock()
+            .map_err(|e| Pkcs11Error::Default(e.to_string()))? = oauth2_session;
+        Ok(())
+    }
+
+    fn logout(&self) -> MResult<()> {
+        if self.slot.is_some() {
+            *self
+                .kms_client
+                .write()
+                .map_err(|e| Pkcs11Error::Default(e.to_string()))? = None;
+            *self
+                .oauth2_session
+                .lock()
+                .map_err(|e| Pkcs11Error::Default(e.to_string()))? = None;
+        }
+        Ok(())
+    }
+
+    fn init_pin(&self, pin: &[u8]) -> MResult<()> {
+        let slot = self.slot()?;
+        let mut slot_conf = slot.conf();
+        if let Some(refresh_token) = slot_conf.oauth2_refresh_token.take() {
+            slot_conf.pin_protected_oauth2_refresh_token =
+                Some(protect_with_pin(pin, refresh_token.as_bytes())?);
+        } else if let (Some(p12_path), Some(password)) = (
+            slot.pkcs12_path(),
+            slot_conf.ssl_client_pkcs12_password.take(),
+        ) {
+            reprotect_pkcs12(&p12_path, &password, pin_str(pin)?)?;
+        } else {
+            return Err(MError::UserPinNotInitialized(format!(
+                "no credential to protect for token {}",
+                slot_conf.token_label
+            )))
+        }
+        slot.save(slot_conf)?;
+        Ok(())
+    }
+
+    fn set_pin(&self, old_pin: &[u8], new_pin: &[u8]) -> MResult<()> {
+        let slot = self.slot()?;
+        let mut slot_conf = slot.conf();
+        if let Some(protected_token) = &slot_conf.pin_protected_oauth2_refresh_token {
+            let refresh_token = unprotect_with_pin(old_pin, protected_token)?;
+            slot_conf.pin_protected_oauth2_refresh_token =
+                Some(protect_with_pin(new_pin, &refresh_token)?);
+        } else if let (Some(p12_path), None) =
+            (slot.pkcs12_path(), &slot_conf.ssl_client_pkcs12_password)
+        {
+            reprotect_pkcs12(&p12_path, pin_str(old_pin)?, pin_str(new_pin)?)?;
+        } else {
+            return Err(MError::UserPinNotInitialized(slot_conf.token_label))
+        }
+        slot.save(slot_conf)?;
+        Ok(())
+    }
+
     fn find_certificate(
         &self,
         _query: SearchOptions,
@@ -73,10 +401,9 @@ impl Backend for CkmsBackend {
 
     fn find_all_certificates(&self) -> cosmian_pkcs11_module::MResult<Vec<Arc<dyn Certificate>>> {
         trace!("find_all_certificates");
-        let disk_encryption_tag = std::env::var("COSMIAN_PKCS11_DISK_ENCRYPTION_TAG")
-            .unwrap_or(COSMIAN_PKCS11_DISK_ENCRYPTION_TAG.to_string());
+        let disk_encryption_tag = disk_encryption_tag();
         let kms_objects = get_kms_objects(
-            &self.kms_client,
+            &self.kms_client()?,
             &[disk_encryption_tag, "_cert".to_string()],
             KeyFormatType::X509,
         )?;
@@ -108,17 +435,17 @@ impl Backend for CkmsBackend {
         &self,
     ) -> cosmian_pkcs11_module::MResult<Vec<Arc<dyn RemoteObjectId>>> {
         trace!("find_all_private_keys");
-        let disk_encryption_tag = std::env::var("COSMIAN_PKCS11_DISK_ENCRYPTION_TAG")
-            .unwrap_or(COSMIAN_PKCS11_DISK_ENCRYPTION_TAG.to_string());
-        Ok(
-            locate_kms_objects(&self.kms_client, &[disk_encryption_tag, "_sk".to_string()])?
-                .into_iter()
-                .map(|id| {
-                    Arc::new(Pkcs11PrivateKey::new(id, RemoteObjectType::PrivateKey))
-                        as Arc<dyn RemoteObjectId>
-                })
-                .collect(),
-        )
+        let disk_encryption_tag = disk_encryption_tag();
+        Ok(locate_kms_objects(
+            &self.kms_client()?,
+            &[disk_encryption_tag, "_sk".to_string()],
+        )?
+        .into_iter()
+        .map(|id| {
+            Arc::new(Pkcs11PrivateKey::new(id, RemoteObjectType::PrivateKey))
+                as Arc<dyn RemoteObjectId>
+        })
+        .collect())
     }
 
     fn find_all_public_keys(&self) -> cosmian_pkcs11_module::MResult<Vec<Arc<dyn PublicKey>>> {
@@ -136,10 +463,9 @@ impl Backend for CkmsBackend {
 
     fn find_all_data_objects(&self) -> cosmian_pkcs11_module::MResult<Vec<Arc<dyn DataObject>>> {
         trace!("find_all_data_objects");
-        let disk_encryption_tag = std::env::var("COSMIAN_PKCS11_DISK_ENCRYPTION_TAG")
-            .unwrap_or(COSMIAN_PKCS11_DISK_ENCRYPTION_TAG.to_string());
+        let disk_encryption_tag = disk_encryption_tag();
         let kms_objects = get_kms_objects(
-            &self.kms_client,
+            &self.kms_client()?,
             &[disk_encryption_tag, "_kk".to_string()],
             KeyFormatType::Raw,
         )?;
@@ -151,13 +477,182 @@ impl Backend for CkmsBackend {
         Ok(result)
     }
 
-    fn generate_key(
+    fn find_all_symmetric_keys(
+        &self,
+    ) -> cosmian_pkcs11_module::MResult<Vec<Arc<dyn SymmetricKey>>> {
+        trace!("find_all_symmetric_keys");
+        let tags = [disk_encryption_tag(), "_kk".to_string()];
+        let mut result = vec![];
+        for (id, attributes) in get_kms_objects_attributes(&self.kms_client()?, &tags)? {
+            if attributes.cryptographic_algorithm != Some(CryptographicAlgorithm::AES) {
+                debug!("find_all_symmetric_keys: skipping non AES key: {id}");
+                continue
+            }
+            let key: Arc<dyn SymmetricKey> = Arc::new(Pkcs11SymmetricKey::try_from_attributes(
+                id,
+                &attributes,
+                &tags,
+            )?);
+            result.push(key);
+        }
+        Ok(result)
+    }
+
+    fn find_symmetric_key(
+        &self,
+        query: SearchOptions,
+    ) -> cosmian_pkcs11_module::MResult<Option<Arc<dyn SymmetricKey>>> {
+        trace!("find_symmetric_key: {:?}", query);
+        let tags = [disk_encryption_tag(), "_kk".to_string()];
+        let key = match query {
+            SearchOptions::All => return Ok(self.find_all_symmetric_keys()?.into_iter().next()),
+            // the id is the unique identifier of the key in the KMS
+            SearchOptions::Id(id) => {
+                let id = String::from_utf8(id)?;
+                let attributes = match get_kms_object_attributes(&self.kms_client()?, &id) {
+                    Ok(attributes) => attributes,
+                    Err(e) => {
+                        debug!("find_symmetric_key: no key {id}: {e}");
+                        return Ok(None)
+                    }
+                };
+                let key_tags = attributes.get_tags();
+                if !tags.iter().all(|tag| key_tags.contains(tag))
+                    || attributes.cryptographic_algorithm != Some(CryptographicAlgorithm::AES)
+                {
+                    return Ok(None)
+                }
+                Pkcs11SymmetricKey::try_from_attributes(id, &attributes, &tags)?
+            }
+            // the label is made of the other tags of the key
+            SearchOptions::Label(label) => {
+                let mut label_tags = tags.to_vec();
+                label_tags.extend(label.split(',').map(ToOwned::to_owned));
+                let key = get_kms_objects_attributes(&self.kms_client()?, &label_tags)?
+                    .into_iter()
+                    .filter(|(_, attributes)| {
+                        attributes.cryptographic_algorithm == Some(CryptographicAlgorithm::AES)
+                    })
+                    .map(|(id, attributes)| {
+                        Pkcs11SymmetricKey::try_from_attributes(id, &attributes, &tags)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .find(|key| key.label() == label);
+                let Some(key) = key else { return Ok(None) };
+                key
+            }
+        };
+        Ok(Some(Arc::new(key)))
+    }
+
+    fn generate_key_pair(
         &self,
         algorithm: KeyAlgorithm,
+        key_length: usize,
+        label: Option<&str>,
+    ) -> cosmian_pkcs11_module::MResult<(Arc<dyn RemoteObjectId>, Arc<dyn RemoteObjectId>)> {
+        trace!(
+            "generate_key_pair: {:?}, {}, {:?}",
+            algorithm,
+            key_length,
+            label
+        );
+        let (public_key_id, private_key_id) = kms_create_key_pair(
+            &self.kms_client()?,
+            algorithm,
+            key_length,
+            &object_tags(label),
+        )?;
+        Ok((
+            Arc::new(Pkcs11PrivateKey::new(
+                public_key_id,
+                RemoteObjectType::PublicKey,
+            )),
+            Arc::new(Pkcs11PrivateKey::new(
+                private_key_id,
+                RemoteObjectType::PrivateKey,
+            )),
+        ))
+    }
+
+    fn create_object(
+        &self,
+        remote_type: RemoteObjectType,
+        value: &[u8],
         label: Option<&str>,
-    ) -> cosmian_pkcs11_module::MResult<Arc<dyn PrivateKey>> {
-        trace!("generate_key: {:?}, {:?}", algorithm, label);
-        Ok(Arc::new(EmptyPrivateKeyImpl {}))
+    ) -> cosmian_pkcs11_module::MResult<Arc<dyn RemoteObjectId>> {
+        trace!("create_object: {:?}, {:?}", remote_type, label);
+        let id = kms_create_object(
+            &self.kms_client()?,
+            remote_type.clone(),
+            value,
+            &object_tags(label),
+        )?;
+        Ok(Arc::new(Pkcs11PrivateKey::new(id, remote_type)))
+    }
+
+    fn sign(
+        &self,
+        remote_object: Arc<dyn RemoteObjectId>,
+        algorithm: &SignatureAlgorithm,
+        data: &[u8],
+    ) -> cosmian_pkcs11_module::MResult<Vec<u8>> {
+        debug!(
+            "sign: {:?}, {:?}, data length: {}",
+            remote_object,
+            algorithm,
+            data.len()
+        );
+        kms_sign(
+            &self.kms_client()?,
+            remote_object.remote_id(),
+            algorithm,
+            data,
+        )
+        .map_err(Into::into)
+    }
+
+    fn verify(
+        &self,
+        remote_object: Arc<dyn RemoteObjectId>,
+        algorithm: &SignatureAlgorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> cosmian_pkcs11_module::MResult<()> {
+        debug!(
+            "verify: {:?}, {:?}, data length: {}",
+            remote_object,
+            algorithm,
+            data.len()
+        );
+        kms_verify(
+            &self.kms_client()?,
+            remote_object.remote_id(),
+            algorithm,
+            data,
+            signature,
+        )
+    }
+
+    fn encrypt(
+        &self,
+        remote_object: Arc<dyn RemoteObjectId>,
+        algorithm: EncryptionAlgorithm,
+        cleartext: Vec<u8>,
+    ) -> cosmian_pkcs11_module::MResult<Vec<u8>> {
+        debug!(
+            "encrypt: {:?}, clear text length: {}",
+            remote_object,
+            cleartext.len()
+        );
+        kms_encrypt(
+            &self.kms_client()?,
+            remote_object.remote_id(),
+            algorithm,
+            cleartext,
+        )
+        .map_err(Into::into)
     }
 
     fn decrypt(
@@ -172,35 +667,50 @@ impl Backend for CkmsBackend {
             ciphertext.len()
         );
         kms_decrypt(
-            &self.kms_client,
+            &self.kms_client()?,
             remote_object.remote_id(),
             algorithm,
             ciphertext,
         )
         .map_err(Into::into)
     }
-}
-
-pub(crate) struct EmptyPrivateKeyImpl;
-
-impl PrivateKey for EmptyPrivateKeyImpl {
-    fn public_key_id(&self) -> Vec<u8> {
-        vec![]
-    }
-
-    fn label(&self) -> String {
-        "PrivateKeyImpl".to_string()
-    }
 
-    fn sign(
+    fn wrap_key(
         &self,
-        _algorithm: &SignatureAlgorithm,
-        _data: &[u8],
+        wrapping_key: Arc<dyn RemoteObjectId>,
+        algorithm: EncryptionAlgorithm,
+        key: Arc<dyn RemoteObjectId>,
     ) -> cosmian_pkcs11_module::MResult<Vec<u8>> {
-        Ok(vec![])
+        debug!("wrap_key: {:?}, {:?}, {:?}", wrapping_key, algorithm, key);
+        kms_wrap_key(
+            &self.kms_client()?,
+            wrapping_key.remote_id(),
+            algorithm,
+            key.remote_id(),
+        )
+        .map_err(Into::into)
     }
 
-    fn algorithm(&self) -> KeyAlgorithm {
-        KeyAlgorithm::Rsa
+    fn unwrap_key(
+        &self,
+        unwrapping_key: Arc<dyn RemoteObjectId>,
+        algorithm: EncryptionAlgorithm,
+        wrapped_key: &[u8],
+        key_type: RemoteObjectType,
+        label: Option<&str>,
+    ) -> cosmian_pkcs11_module::MResult<Arc<dyn RemoteObjectId>> {
+        debug!(
+            "unwrap_key: {:?}, {:?}, {:?}, {:?}",
+            unwrapping_key, algorithm, key_type, label
+        );
+        let id = kms_unwrap_key(
+            &self.kms_client()?,
+            unwrapping_key.remote_id(),
+            algorithm,
+            wrapped_key,
+            key_type.clone(),
+            &object_tags(label),
+        )?;
+        Ok(Arc::new(Pkcs11PrivateKey::new(id, key_type)))
     }
 }
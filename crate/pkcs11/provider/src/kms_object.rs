@@ -1,13 +1,31 @@
-use cosmian_kmip::kmip::{
-    kmip_objects::Object,
-    kmip_operations::{Decrypt, Locate},
-    kmip_types::{
-        Attributes, CryptographicAlgorithm, CryptographicParameters, KeyFormatType, PaddingMethod,
-        UniqueIdentifier,
+use std::{future::Future, sync::OnceLock};
+
+use cosmian_kmip::{
+    crypto::{
+        elliptic_curves::kmip_requests::create_ec_key_pair_request,
+        rsa::kmip_requests::create_rsa_key_pair_request,
+        symmetric::create_symmetric_key_kmip_object,
+    },
+    kmip::{
+        kmip_data_structures::{
+            KeyBlock, KeyMaterial, KeyValue, KeyWrappingData, KeyWrappingSpecification,
+        },
+        kmip_objects::Object,
+        kmip_operations::{Decrypt, Encrypt, Get, GetAttributes, Locate, Sign, SignatureVerify},
+        kmip_types::{
+            Attributes, BlockCipherMode, CertificateType, CryptographicAlgorithm,
+            CryptographicParameters, DigitalSignatureAlgorithm, EncodingOption,
+            EncryptionKeyInformation, HashingAlgorithm, KeyFormatType, PaddingMethod,
+            RecommendedCurve, UniqueIdentifier, ValidityIndicator, WrappingMethod,
+        },
     },
 };
-use cosmian_kms_client::{batch_export_objects, ClientConf, KmsClient};
-use cosmian_pkcs11_module::traits::EncryptionAlgorithm;
+use cosmian_kms_client::{batch_export_objects, import_object, KmsClient};
+use cosmian_pkcs11_module::{
+    traits::{DigestType, EncryptionAlgorithm, KeyAlgorithm, RemoteObjectType, SignatureAlgorithm},
+    MError,
+};
+use tokio::runtime::Runtime;
 use tracing::{debug, trace};
 use zeroize::Zeroizing;
 
@@ -21,18 +39,24 @@ pub struct KmsObject {
     pub other_tags: Vec<String>,
 }
 
-pub fn get_kms_client() -> Result<KmsClient, Pkcs11Error> {
-    let conf_path = ClientConf::location(None)?;
-    let conf = ClientConf::load(&conf_path)?;
-    let kms_client = conf.initialize_kms_client()?;
-    Ok(kms_client)
+/// Run a future to completion on the runtime of the provider
+pub(crate) fn block_on<F: Future>(future: F) -> Result<F::Output, Pkcs11Error> {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    let runtime = match RUNTIME.get() {
+        Some(runtime) => runtime,
+        None => {
+            let runtime = Runtime::new()?;
+            RUNTIME.get_or_init(|| runtime)
+        }
+    };
+    Ok(runtime.block_on(future))
 }
 
 pub fn locate_kms_objects(
     kms_client: &KmsClient,
     tags: &[String],
 ) -> Result<Vec<String>, Pkcs11Error> {
-    tokio::runtime::Runtime::new()?.block_on(locate_kms_objects_async(kms_client, tags))
+    block_on(locate_kms_objects_async(kms_client, tags))?
 }
 
 pub(crate) async fn locate_kms_objects_async(
@@ -47,11 +71,7 @@ pub fn get_kms_objects(
     tags: &[String],
     key_format_type: KeyFormatType,
 ) -> Result<Vec<KmsObject>, Pkcs11Error> {
-    tokio::runtime::Runtime::new()?.block_on(get_kms_objects_async(
-        kms_client,
-        tags,
-        key_format_type,
-    ))
+    block_on(get_kms_objects_async(kms_client, tags, key_format_type))?
 }
 
 pub(crate) async fn get_kms_objects_async(
@@ -80,6 +100,40 @@ pub(crate) async fn get_kms_objects_async(
     Ok(results)
 }
 
+/// Locate the objects with the `tags` and fetch their attributes,
+/// without exporting the objects themselves.
+pub fn get_kms_objects_attributes(
+    kms_client: &KmsClient,
+    tags: &[String],
+) -> Result<Vec<(String, Attributes)>, Pkcs11Error> {
+    block_on(get_kms_objects_attributes_async(kms_client, tags))?
+}
+
+pub(crate) async fn get_kms_objects_attributes_async(
+    kms_client: &KmsClient,
+    tags: &[String],
+) -> Result<Vec<(String, Attributes)>, Pkcs11Error> {
+    let mut results = vec![];
+    for id in locate_objects(kms_client, tags).await? {
+        let response = kms_client.get_attributes(GetAttributes::from(&id)).await?;
+        results.push((id, response.attributes));
+    }
+    Ok(results)
+}
+
+/// The attributes of the KMS object with the given `id`
+pub fn get_kms_object_attributes(
+    kms_client: &KmsClient,
+    id: &str,
+) -> Result<Attributes, Pkcs11Error> {
+    block_on(async {
+        Ok(kms_client
+            .get_attributes(GetAttributes::from(id))
+            .await?
+            .attributes)
+    })?
+}
+
 async fn locate_objects(
     kms_client: &KmsClient,
     tags: &[String],
@@ -112,12 +166,12 @@ pub fn kms_decrypt(
     encryption_algorithm: EncryptionAlgorithm,
     data: Vec<u8>,
 ) -> Result<Zeroizing<Vec<u8>>, Pkcs11Error> {
-    tokio::runtime::Runtime::new()?.block_on(kms_decrypt_async(
+    block_on(kms_decrypt_async(
         kms_client,
         key_id,
         encryption_algorithm,
         data,
-    ))
+    ))?
 }
 
 pub(crate) async fn kms_decrypt_async(
@@ -126,17 +180,31 @@ pub(crate) async fn kms_decrypt_async(
     encryption_algorithm: EncryptionAlgorithm,
     data: Vec<u8>,
 ) -> Result<Zeroizing<Vec<u8>>, Pkcs11Error> {
-    let cryptographic_parameters = match encryption_algorithm {
-        EncryptionAlgorithm::RsaPkcs1v15 => CryptographicParameters {
-            cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
-            padding_method: Some(PaddingMethod::PKCS1v15),
-            ..Default::default()
-        },
+    let (iv_counter_nonce, authenticated_encryption_additional_data) =
+        aes_iv_and_aad(&encryption_algorithm);
+    // PKCS#11 appends the AES GCM tag to the ciphertext
+    let (data, authenticated_encryption_tag) = match &encryption_algorithm {
+        EncryptionAlgorithm::AesGcm { tag_bits, .. } => {
+            let tag_len =
+                usize::try_from(*tag_bits / 8).map_err(|e| Pkcs11Error::Default(e.to_string()))?;
+            if data.len() < tag_len {
+                return Err(Pkcs11Error::Default(
+                    "AES GCM: the ciphertext is shorter than the tag".to_string(),
+                ))
+            }
+            let mut ciphertext = data;
+            let tag = ciphertext.split_off(ciphertext.len() - tag_len);
+            (ciphertext, Some(tag))
+        }
+        _ => (data, None),
     };
     let decryption_request = Decrypt {
         unique_identifier: Some(UniqueIdentifier::TextString(key_id)),
-        cryptographic_parameters: Some(cryptographic_parameters),
+        cryptographic_parameters: Some(encryption_parameters(&encryption_algorithm)?),
         data: Some(data),
+        iv_counter_nonce,
+        authenticated_encryption_additional_data,
+        authenticated_encryption_tag,
         ..Default::default()
     };
     let response = kms_client.decrypt(decryption_request).await?;
@@ -144,3 +212,710 @@ pub(crate) async fn kms_decrypt_async(
         Pkcs11Error::ServerError("Decryption response does not contain data".to_string())
     })
 }
+
+pub fn kms_encrypt(
+    kms_client: &KmsClient,
+    key_id: String,
+    encryption_algorithm: EncryptionAlgorithm,
+    data: Vec<u8>,
+) -> Result<Vec<u8>, Pkcs11Error> {
+    block_on(kms_encrypt_async(
+        kms_client,
+        key_id,
+        encryption_algorithm,
+        data,
+    ))?
+}
+
+pub(crate) async fn kms_encrypt_async(
+    kms_client: &KmsClient,
+    key_id: String,
+    encryption_algorithm: EncryptionAlgorithm,
+    data: Vec<u8>,
+) -> Result<Vec<u8>, Pkcs11Error> {
+    let (iv_counter_nonce, authenticated_encryption_additional_data) =
+        aes_iv_and_aad(&encryption_algorithm);
+    let encryption_request = Encrypt {
+        unique_identifier: Some(UniqueIdentifier::TextString(key_id)),
+        cryptographic_parameters: Some(encryption_parameters(&encryption_algorithm)?),
+        data: Some(Zeroizing::new(data)),
+        iv_counter_nonce,
+        authenticated_encryption_additional_data,
+        ..Default::default()
+    };
+    let response = kms_client.encrypt(encryption_request).await?;
+    let mut ciphertext = response.data.ok_or_else(|| {
+        Pkcs11Error::ServerError("Encryption response does not contain data".to_string())
+    })?;
+    // PKCS#11 appends the AES GCM tag to the ciphertext
+    if let EncryptionAlgorithm::AesGcm { .. } = encryption_algorithm {
+        let tag = response.authenticated_encryption_tag.ok_or_else(|| {
+            Pkcs11Error::ServerError("Encryption response does not contain a tag".to_string())
+        })?;
+        ciphertext.extend_from_slice(&tag);
+    }
+    Ok(ciphertext)
+}
+
+pub fn kms_sign(
+    kms_client: &KmsClient,
+    key_id: String,
+    signature_algorithm: &SignatureAlgorithm,
+    data: &[u8],
+) -> Result<Vec<u8>, Pkcs11Error> {
+    block_on(kms_sign_async(
+        kms_client,
+        key_id,
+        signature_algorithm,
+        data,
+    ))?
+}
+
+pub(crate) async fn kms_sign_async(
+    kms_client: &KmsClient,
+    key_id: String,
+    signature_algorithm: &SignatureAlgorithm,
+    data: &[u8],
+) -> Result<Vec<u8>, Pkcs11Error> {
+    let (cryptographic_parameters, payload) = signature_parameters(signature_algorithm, data)?;
+    let (data, digested_data) = match payload {
+        SignaturePayload::Data(data) => (Some(Zeroizing::new(data.to_vec())), None),
+        SignaturePayload::Digest(digest) => (None, Some(digest.to_vec())),
+    };
+    let sign_request = Sign {
+        unique_identifier: Some(UniqueIdentifier::TextString(key_id)),
+        cryptographic_parameters: Some(cryptographic_parameters),
+        data,
+        digested_data,
+        ..Default::default()
+    };
+    let signature = kms_client
+        .sign(sign_request)
+        .await?
+        .signature_data
+        .ok_or_else(|| {
+            Pkcs11Error::ServerError("Sign response does not contain a signature".to_string())
+        })?;
+    match signature_algorithm {
+        // PKCS#11 ECDSA signatures are the raw concatenation of r and s
+        SignatureAlgorithm::Ecdsa => ecdsa_der_to_raw(&signature),
+        _ => Ok(signature),
+    }
+}
+
+/// Verify a signature, returning `MError::SignatureInvalid` if it is not valid
+pub fn kms_verify(
+    kms_client: &KmsClient,
+    key_id: String,
+    signature_algorithm: &SignatureAlgorithm,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<(), MError> {
+    block_on(kms_verify_async(
+        kms_client,
+        key_id,
+        signature_algorithm,
+        data,
+        signature,
+    ))?
+}
+
+pub(crate) async fn kms_verify_async(
+    kms_client: &KmsClient,
+    key_id: String,
+    signature_algorithm: &SignatureAlgorithm,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<(), MError> {
+    let (cryptographic_parameters, payload) = signature_parameters(signature_algorithm, data)?;
+    let (data, digested_data) = match payload {
+        SignaturePayload::Data(data) => (Some(data.to_vec()), None),
+        SignaturePayload::Digest(digest) => (None, Some(digest.to_vec())),
+    };
+    let signature_data = match signature_algorithm {
+        SignatureAlgorithm::Ecdsa => ecdsa_raw_to_der(signature)?,
+        _ => signature.to_vec(),
+    };
+    let verify_request = SignatureVerify {
+        unique_identifier: Some(UniqueIdentifier::TextString(key_id)),
+        cryptographic_parameters: Some(cryptographic_parameters),
+        data,
+        digested_data,
+        signature_data: Some(signature_data),
+        ..Default::default()
+    };
+    let response = kms_client
+        .signature_verify(verify_request)
+        .await
+        .map_err(Pkcs11Error::from)?;
+    match response.validity_indicator {
+        ValidityIndicator::Valid => Ok(()),
+        ValidityIndicator::Invalid | ValidityIndicator::Unknown => Err(MError::SignatureInvalid),
+    }
+}
+
+/// Create a key pair in the KMS and return the (public key, private key) identifiers
+///
+/// `key_length` is the modulus size in bits for RSA
+/// and the size of the curve in bits for ECC.
+pub fn kms_create_key_pair(
+    kms_client: &KmsClient,
+    algorithm: KeyAlgorithm,
+    key_length: usize,
+    tags: &[String],
+) -> Result<(String, String), Pkcs11Error> {
+    block_on(kms_create_key_pair_async(
+        kms_client, algorithm, key_length, tags,
+    ))?
+}
+
+pub(crate) async fn kms_create_key_pair_async(
+    kms_client: &KmsClient,
+    algorithm: KeyAlgorithm,
+    key_length: usize,
+    tags: &[String],
+) -> Result<(String, String), Pkcs11Error> {
+    let request = match algorithm {
+        KeyAlgorithm::Aes => {
+            return Err(Pkcs11Error::NotSupported(
+                "AES keys are not generated as key pairs".to_string(),
+            ))
+        }
+        KeyAlgorithm::Rsa => create_rsa_key_pair_request(tags, key_length)?,
+        KeyAlgorithm::Ecc => {
+            let curve = match key_length {
+                224 => RecommendedCurve::P224,
+                256 => RecommendedCurve::P256,
+                384 => RecommendedCurve::P384,
+                521 => RecommendedCurve::P521,
+                _ => {
+                    return Err(Pkcs11Error::NotSupported(format!(
+                        "elliptic curves of {key_length} bits are not supported"
+                    )))
+                }
+            };
+            create_ec_key_pair_request(tags, curve)?
+        }
+    };
+    let response = kms_client.create_key_pair(request).await?;
+    debug!(
+        "Created key pair: public key: {}, private key: {}",
+        response.public_key_unique_identifier, response.private_key_unique_identifier
+    );
+    Ok((
+        response.public_key_unique_identifier.to_string(),
+        response.private_key_unique_identifier.to_string(),
+    ))
+}
+
+/// Import a certificate from its DER encoding or an AES key from its bytes
+pub fn kms_create_object(
+    kms_client: &KmsClient,
+    remote_type: RemoteObjectType,
+    value: &[u8],
+    tags: &[String],
+) -> Result<String, Pkcs11Error> {
+    block_on(kms_create_object_async(
+        kms_client,
+        remote_type,
+        value,
+        tags,
+    ))?
+}
+
+pub(crate) async fn kms_create_object_async(
+    kms_client: &KmsClient,
+    remote_type: RemoteObjectType,
+    value: &[u8],
+    tags: &[String],
+) -> Result<String, Pkcs11Error> {
+    let object = match remote_type {
+        RemoteObjectType::Certificate => Object::Certificate {
+            certificate_type: CertificateType::X509,
+            certificate_value: value.to_vec(),
+        },
+        RemoteObjectType::SymmetricKey => {
+            create_symmetric_key_kmip_object(value, CryptographicAlgorithm::AES)
+        }
+        RemoteObjectType::PrivateKey | RemoteObjectType::PublicKey => {
+            return Err(Pkcs11Error::NotSupported(
+                "private and public keys cannot be created from their value".to_string(),
+            ))
+        }
+    };
+    Ok(import_object(kms_client, None, object, None, false, false, tags).await?)
+}
+
+/// Export the key `key_id` wrapped by the key `wrapping_key_id`
+pub fn kms_wrap_key(
+    kms_client: &KmsClient,
+    wrapping_key_id: String,
+    encryption_algorithm: EncryptionAlgorithm,
+    key_id: String,
+) -> Result<Vec<u8>, Pkcs11Error> {
+    block_on(kms_wrap_key_async(
+        kms_client,
+        wrapping_key_id,
+        encryption_algorithm,
+        key_id,
+    ))?
+}
+
+pub(crate) async fn kms_wrap_key_async(
+    kms_client: &KmsClient,
+    wrapping_key_id: String,
+    encryption_algorithm: EncryptionAlgorithm,
+    key_id: String,
+) -> Result<Vec<u8>, Pkcs11Error> {
+    let get_request = Get {
+        unique_identifier: Some(UniqueIdentifier::TextString(key_id)),
+        key_wrapping_specification: Some(KeyWrappingSpecification {
+            wrapping_method: WrappingMethod::Encrypt,
+            encryption_key_information: Some(EncryptionKeyInformation {
+                unique_identifier: UniqueIdentifier::TextString(wrapping_key_id),
+                cryptographic_parameters: Some(Box::new(key_wrapping_parameters(
+                    &encryption_algorithm,
+                )?)),
+            }),
+            encoding_option: Some(EncodingOption::NoEncoding),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let response = kms_client.get(get_request).await?;
+    Ok(response.object.key_block()?.key_bytes()?.to_vec())
+}
+
+/// Import the `wrapped_key` unwrapped by the key `unwrapping_key_id`
+/// and return the identifier of the imported key.
+///
+/// Unwrapped private keys are expected to be RSA keys in PKCS#1 format.
+pub fn kms_unwrap_key(
+    kms_client: &KmsClient,
+    unwrapping_key_id: String,
+    encryption_algorithm: EncryptionAlgorithm,
+    wrapped_key: &[u8],
+    key_type: RemoteObjectType,
+    tags: &[String],
+) -> Result<String, Pkcs11Error> {
+    block_on(kms_unwrap_key_async(
+        kms_client,
+        unwrapping_key_id,
+        encryption_algorithm,
+        wrapped_key,
+        key_type,
+        tags,
+    ))?
+}
+
+pub(crate) async fn kms_unwrap_key_async(
+    kms_client: &KmsClient,
+    unwrapping_key_id: String,
+    encryption_algorithm: EncryptionAlgorithm,
+    wrapped_key: &[u8],
+    key_type: RemoteObjectType,
+    tags: &[String],
+) -> Result<String, Pkcs11Error> {
+    let key_block = |key_format_type, cryptographic_algorithm| -> Result<KeyBlock, Pkcs11Error> {
+        Ok(KeyBlock {
+            key_format_type,
+            key_compression_type: None,
+            key_value: KeyValue {
+                key_material: KeyMaterial::ByteString(Zeroizing::new(wrapped_key.to_vec())),
+                attributes: None,
+            },
+            cryptographic_algorithm: Some(cryptographic_algorithm),
+            cryptographic_length: None,
+            key_wrapping_data: Some(Box::new(KeyWrappingData {
+                wrapping_method: WrappingMethod::Encrypt,
+                encryption_key_information: Some(EncryptionKeyInformation {
+                    unique_identifier: UniqueIdentifier::TextString(unwrapping_key_id.clone()),
+                    cryptographic_parameters: Some(Box::new(key_wrapping_parameters(
+                        &encryption_algorithm,
+                    )?)),
+                }),
+                encoding_option: Some(EncodingOption::NoEncoding),
+                ..Default::default()
+            })),
+        })
+    };
+    let object = match key_type {
+        RemoteObjectType::SymmetricKey => Object::SymmetricKey {
+            key_block: key_block(
+                KeyFormatType::TransparentSymmetricKey,
+                CryptographicAlgorithm::AES,
+            )?,
+        },
+        RemoteObjectType::PrivateKey => Object::PrivateKey {
+            key_block: key_block(KeyFormatType::PKCS1, CryptographicAlgorithm::RSA)?,
+        },
+        RemoteObjectType::PublicKey | RemoteObjectType::Certificate => {
+            return Err(Pkcs11Error::NotSupported(
+                "only symmetric and private keys can be unwrapped".to_string(),
+            ))
+        }
+    };
+    Ok(import_object(kms_client, None, object, None, true, false, tags).await?)
+}
+
+fn hashing_algorithm(digest: DigestType) -> HashingAlgorithm {
+    match digest {
+        DigestType::Sha1 => HashingAlgorithm::SHA1,
+        DigestType::Sha224 => HashingAlgorithm::SHA224,
+        DigestType::Sha256 => HashingAlgorithm::SHA256,
+        DigestType::Sha384 => HashingAlgorithm::SHA384,
+        DigestType::Sha512 => HashingAlgorithm::SHA512,
+    }
+}
+
+/// The KMS uses the OAEP hash for the MGF1 mask generation function
+fn ensure_mgf1_matches(digest: DigestType, mgf: DigestType) -> Result<(), Pkcs11Error> {
+    if digest != mgf {
+        return Err(Pkcs11Error::NotSupported(format!(
+            "MGF1 with {mgf:?} is not supported with {digest:?}: the MGF1 hash must be the digest \
+             hash"
+        )));
+    }
+    Ok(())
+}
+
+/// The IV and the additional authenticated data of the AES mechanisms
+fn aes_iv_and_aad(
+    encryption_algorithm: &EncryptionAlgorithm,
+) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    match encryption_algorithm {
+        EncryptionAlgorithm::AesCbcPad { iv } => (Some(iv.to_vec()), None),
+        EncryptionAlgorithm::AesGcm { iv, aad, .. } => {
+            (Some(iv.clone()), (!aad.is_empty()).then(|| aad.clone()))
+        }
+        _ => (None, None),
+    }
+}
+
+/// The KMS cryptographic parameters of a PKCS#11 key wrapping mechanism.
+///
+/// The KMS wraps keys with AES keys using RFC 5649 only.
+fn key_wrapping_parameters(
+    encryption_algorithm: &EncryptionAlgorithm,
+) -> Result<CryptographicParameters, Pkcs11Error> {
+    if let EncryptionAlgorithm::AesCbcPad { .. } | EncryptionAlgorithm::AesGcm { .. } =
+        encryption_algorithm
+    {
+        return Err(Pkcs11Error::NotSupported(
+            "AES key wrapping is only supported with CKM_AES_KEY_WRAP_KWP".to_string(),
+        ))
+    }
+    encryption_parameters(encryption_algorithm)
+}
+
+/// The KMS cryptographic parameters of a PKCS#11 encryption or key wrapping mechanism
+fn encryption_parameters(
+    encryption_algorithm: &EncryptionAlgorithm,
+) -> Result<CryptographicParameters, Pkcs11Error> {
+    Ok(match *encryption_algorithm {
+        EncryptionAlgorithm::AesCbcPad { .. } => CryptographicParameters {
+            cryptographic_algorithm: Some(CryptographicAlgorithm::AES),
+            block_cipher_mode: Some(BlockCipherMode::CBC),
+            padding_method: Some(PaddingMethod::PKCS5),
+            ..Default::default()
+        },
+        EncryptionAlgorithm::AesGcm { tag_bits, .. } => {
+            if tag_bits != 128 {
+                return Err(Pkcs11Error::NotSupported(format!(
+                    "AES GCM with a {tag_bits} bits tag is not supported: the tag must be 128 bits"
+                )));
+            }
+            CryptographicParameters {
+                cryptographic_algorithm: Some(CryptographicAlgorithm::AES),
+                block_cipher_mode: Some(BlockCipherMode::GCM),
+                tag_length: Some(tag_bits / 8),
+                ..Default::default()
+            }
+        }
+        EncryptionAlgorithm::AesKeyWrapPad => CryptographicParameters {
+            cryptographic_algorithm: Some(CryptographicAlgorithm::AES),
+            block_cipher_mode: Some(BlockCipherMode::AESKeyWrapPadding),
+            ..Default::default()
+        },
+        EncryptionAlgorithm::RsaPkcs1v15 => CryptographicParameters {
+            cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
+            padding_method: Some(PaddingMethod::PKCS1v15),
+            ..Default::default()
+        },
+        EncryptionAlgorithm::RsaOaep {
+            digest,
+            mask_generation_function,
+        } => {
+            ensure_mgf1_matches(digest, mask_generation_function)?;
+            CryptographicParameters {
+                cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
+                padding_method: Some(PaddingMethod::OAEP),
+                hashing_algorithm: Some(hashing_algorithm(digest)),
+                ..Default::default()
+            }
+        }
+        EncryptionAlgorithm::RsaAesKeyWrap {
+            aes_key_bits,
+            digest,
+            mask_generation_function,
+        } => {
+            if aes_key_bits != 256 {
+                return Err(Pkcs11Error::NotSupported(format!(
+                    "RSA AES key wrap with a {aes_key_bits} bits AES key is not supported: the \
+                     AES key must be 256 bits"
+                )));
+            }
+            ensure_mgf1_matches(digest, mask_generation_function)?;
+            CryptographicParameters {
+                cryptographic_algorithm: Some(CryptographicAlgorithm::AES),
+                padding_method: Some(PaddingMethod::OAEP),
+                hashing_algorithm: Some(hashing_algorithm(digest)),
+                ..Default::default()
+            }
+        }
+    })
+}
+
+/// What the KMS signs: the data itself or its digest
+enum SignaturePayload<'a> {
+    Data(&'a [u8]),
+    Digest(&'a [u8]),
+}
+
+/// The DER prefixes of the PKCS#1 v1.5 `DigestInfo` structures
+const DIGEST_INFO_PREFIXES: [(DigestType, &[u8]); 5] = [
+    (
+        DigestType::Sha1,
+        &[
+            0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04,
+            0x14,
+        ],
+    ),
+    (
+        DigestType::Sha224,
+        &[
+            0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+            0x04, 0x05, 0x00, 0x04, 0x1c,
+        ],
+    ),
+    (
+        DigestType::Sha256,
+        &[
+            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+            0x01, 0x05, 0x00, 0x04, 0x20,
+        ],
+    ),
+    (
+        DigestType::Sha384,
+        &[
+            0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+            0x02, 0x05, 0x00, 0x04, 0x30,
+        ],
+    ),
+    (
+        DigestType::Sha512,
+        &[
+            0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+            0x03, 0x05, 0x00, 0x04, 0x40,
+        ],
+    ),
+];
+
+/// The KMS cryptographic parameters of a PKCS#11 signature mechanism
+/// and the payload to send for the `data` provided to the mechanism
+fn signature_parameters<'a>(
+    signature_algorithm: &SignatureAlgorithm,
+    data: &'a [u8],
+) -> Result<(CryptographicParameters, SignaturePayload<'a>), Pkcs11Error> {
+    let rsa_pkcs1v15 = |digital_signature_algorithm| CryptographicParameters {
+        cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
+        padding_method: Some(PaddingMethod::PKCS1v15),
+        digital_signature_algorithm: Some(digital_signature_algorithm),
+        ..Default::default()
+    };
+    Ok(match signature_algorithm {
+        SignatureAlgorithm::RsaPkcs1v15Sha1 => (
+            rsa_pkcs1v15(DigitalSignatureAlgorithm::SHA1WithRSAEncryption),
+            SignaturePayload::Data(data),
+        ),
+        SignatureAlgorithm::RsaPkcs1v15Sha256 => (
+            rsa_pkcs1v15(DigitalSignatureAlgorithm::SHA256WithRSAEncryption),
+            SignaturePayload::Data(data),
+        ),
+        SignatureAlgorithm::RsaPkcs1v15Sha384 => (
+            rsa_pkcs1v15(DigitalSignatureAlgorithm::SHA384WithRSAEncryption),
+            SignaturePayload::Data(data),
+        ),
+        SignatureAlgorithm::RsaPkcs1v15Sha512 => (
+            rsa_pkcs1v15(DigitalSignatureAlgorithm::SHA512WithRSAEncryption),
+            SignaturePayload::Data(data),
+        ),
+        // CKM_RSA_PKCS signs a DER encoded DigestInfo: the KMS is sent the digest
+        SignatureAlgorithm::RsaPkcs1v15Raw => {
+            let (digest, digest_value) = DIGEST_INFO_PREFIXES
+                .iter()
+                .find_map(|(digest, prefix)| {
+                    data.strip_prefix(*prefix)
+                        .filter(|value| value.len() == digest.digest_len())
+                        .map(|value| (*digest, value))
+                })
+                .ok_or_else(|| {
+                    Pkcs11Error::NotSupported(
+                        "CKM_RSA_PKCS signatures are only supported on a SHA-1 or SHA-2 DigestInfo"
+                            .to_string(),
+                    )
+                })?;
+            (
+                CryptographicParameters {
+                    cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
+                    padding_method: Some(PaddingMethod::PKCS1v15),
+                    hashing_algorithm: Some(hashing_algorithm(digest)),
+                    ..Default::default()
+                },
+                SignaturePayload::Digest(digest_value),
+            )
+        }
+        SignatureAlgorithm::RsaPss {
+            digest,
+            mask_generation_function,
+            salt_length,
+        } => {
+            ensure_mgf1_matches(*digest, *mask_generation_function)?;
+            if *salt_length != digest.digest_len() as u64 {
+                return Err(Pkcs11Error::NotSupported(format!(
+                    "RSA PSS with a salt length of {salt_length} is not supported: the salt \
+                     length must be the digest length"
+                )));
+            }
+            (
+                CryptographicParameters {
+                    cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
+                    padding_method: Some(PaddingMethod::PSS),
+                    digital_signature_algorithm: Some(DigitalSignatureAlgorithm::RSASSAPSS),
+                    hashing_algorithm: Some(hashing_algorithm(*digest)),
+                    ..Default::default()
+                },
+                SignaturePayload::Digest(data),
+            )
+        }
+        // CKM_ECDSA signs a digest whose algorithm is inferred from its length
+        SignatureAlgorithm::Ecdsa => {
+            let digital_signature_algorithm = match data.len() {
+                20 => DigitalSignatureAlgorithm::ECDSAWithSHA1,
+                28 => DigitalSignatureAlgorithm::ECDSAWithSHA224,
+                32 => DigitalSignatureAlgorithm::ECDSAWithSHA256,
+                48 => DigitalSignatureAlgorithm::ECDSAWithSHA384,
+                64 => DigitalSignatureAlgorithm::ECDSAWithSHA512,
+                len => {
+                    return Err(Pkcs11Error::NotSupported(format!(
+                        "CKM_ECDSA signatures of {len} bytes digests are not supported"
+                    )))
+                }
+            };
+            (
+                CryptographicParameters {
+                    cryptographic_algorithm: Some(CryptographicAlgorithm::ECDSA),
+                    digital_signature_algorithm: Some(digital_signature_algorithm),
+                    ..Default::default()
+                },
+                SignaturePayload::Digest(data),
+            )
+        }
+        SignatureAlgorithm::RsaRaw => {
+            return Err(Pkcs11Error::NotSupported(
+                "raw RSA signatures are not supported".to_string(),
+            ))
+        }
+    })
+}
+
+/// The sizes in bytes of `r` and `s` for the supported curves: P-224, P-256, P-384 and P-521
+const ECDSA_SCALAR_SIZES: [usize; 4] = [28, 32, 48, 66];
+
+/// Read a DER length and return it with the remaining bytes
+fn der_length(bytes: &[u8]) -> Result<(usize, &[u8]), Pkcs11Error> {
+    match bytes {
+        [len, rest @ ..] if *len < 0x80 => Ok((*len as usize, rest)),
+        [0x81, len, rest @ ..] => Ok((*len as usize, rest)),
+        _ => Err(Pkcs11Error::Conversion(
+            "invalid DER length in ECDSA signature".to_string(),
+        )),
+    }
+}
+
+/// Read a DER INTEGER and return its value without leading zeros with the remaining bytes
+fn der_integer(bytes: &[u8]) -> Result<(&[u8], &[u8]), Pkcs11Error> {
+    let Some((&0x02, bytes)) = bytes.split_first() else {
+        return Err(Pkcs11Error::Conversion(
+            "invalid DER integer in ECDSA signature".to_string(),
+        ));
+    };
+    let (len, bytes) = der_length(bytes)?;
+    if bytes.len() < len {
+        return Err(Pkcs11Error::Conversion(
+            "truncated DER integer in ECDSA signature".to_string(),
+        ));
+    }
+    let (value, rest) = bytes.split_at(len);
+    let first_non_zero = value.iter().position(|b| *b != 0).unwrap_or(value.len());
+    Ok((&value[first_non_zero..], rest))
+}
+
+/// Convert a DER encoded ECDSA signature to the PKCS#11 `r || s` format
+fn ecdsa_der_to_raw(signature: &[u8]) -> Result<Vec<u8>, Pkcs11Error> {
+    let Some((&0x30, bytes)) = signature.split_first() else {
+        return Err(Pkcs11Error::Conversion(
+            "invalid DER ECDSA signature".to_string(),
+        ));
+    };
+    let (_, bytes) = der_length(bytes)?;
+    let (r, bytes) = der_integer(bytes)?;
+    let (s, _) = der_integer(bytes)?;
+    let size = ECDSA_SCALAR_SIZES
+        .into_iter()
+        .find(|size| *size >= r.len().max(s.len()))
+        .ok_or_else(|| Pkcs11Error::Conversion("ECDSA signature is too large".to_string()))?;
+    let mut raw = vec![0_u8; 2 * size];
+    raw[size - r.len()..size].copy_from_slice(r);
+    raw[2 * size - s.len()..].copy_from_slice(s);
+    Ok(raw)
+}
+
+/// Convert a PKCS#11 `r || s` ECDSA signature to its DER encoding
+fn ecdsa_raw_to_der(signature: &[u8]) -> Result<Vec<u8>, Pkcs11Error> {
+    if signature.is_empty() || signature.len() % 2 != 0 {
+        return Err(Pkcs11Error::Conversion(
+            "invalid PKCS#11 ECDSA signature length".to_string(),
+        ));
+    }
+    let length = |len: usize| -> Vec<u8> {
+        if len < 0x80 {
+            vec![len as u8]
+        } else {
+            vec![0x81, len as u8]
+        }
+    };
+    let integer = |value: &[u8]| -> Vec<u8> {
+        let first_non_zero = value
+            .iter()
+            .position(|b| *b != 0)
+            .unwrap_or(value.len() - 1);
+        let value = &value[first_non_zero..];
+        // a leading zero keeps the integer positive
+        let pad = value[0] & 0x80 != 0;
+        let mut der = vec![0x02];
+        der.extend(length(value.len() + usize::from(pad)));
+        if pad {
+            der.push(0);
+        }
+        der.extend_from_slice(value);
+        der
+    };
+    let (r, s) = signature.split_at(signature.len() / 2);
+    let mut content = integer(r);
+    content.extend(integer(s));
+    let mut der = vec![0x30];
+    der.extend(length(content.len()));
+    der.extend(content);
+    Ok(der)
+}
@@ -1,17 +1,39 @@
 use std::{ptr::addr_of_mut, str::FromStr};
 
-use cosmian_pkcs11_module::{CKR_OK, CK_FUNCTION_LIST_PTR_PTR, CK_RV, FUNC_LIST};
+use cosmian_kms_client::ClientConf;
+use cosmian_pkcs11_module::{traits::Backend, CKR_OK, CK_FUNCTION_LIST_PTR_PTR, CK_RV, FUNC_LIST};
 use tracing::Level;
 
-use crate::{kms_object::get_kms_client, logging::initialize_logging};
+use crate::{backend::CkmsBackend, error::Pkcs11Error, logging::initialize_logging};
 
 mod backend;
 mod error;
 mod kms_object;
 mod logging;
+mod pin;
 mod pkcs11_certificate;
 mod pkcs11_data_object;
 mod pkcs11_private_key;
+mod pkcs11_symmetric_key;
+
+/// One backend per PKCS#11 slot of the configuration, each with its own identity.
+/// Without configured slots, a single slot uses the identity of the configuration.
+fn instantiate_backends() -> Result<Vec<Box<dyn Backend>>, Pkcs11Error> {
+    let conf_path = ClientConf::location(None)?;
+    let conf = ClientConf::load(&conf_path)?;
+    let slot_count = conf.pkcs11_slots.as_ref().map_or(0, Vec::len);
+    if slot_count == 0 {
+        return Ok(vec![Box::new(CkmsBackend::instantiate(
+            conf.initialize_kms_client()?,
+        )?)])
+    }
+    (0..slot_count)
+        .map(|index| {
+            CkmsBackend::instantiate_slot(&conf, &conf_path, index)
+                .map(|backend| Box::new(backend) as Box<dyn Backend>)
+        })
+        .collect()
+}
 
 /// # Safety
 /// This function is the first one called by the PKCS#11 library client
@@ -20,14 +42,10 @@ mod pkcs11_private_key;
 pub unsafe extern "C" fn C_GetFunctionList(pp_function_list: CK_FUNCTION_LIST_PTR_PTR) -> CK_RV {
     let debug_level = std::env::var("COSMIAN_PKCS11_LOGGING_LEVEL").unwrap_or("info".to_string());
     initialize_logging("ckms-pkcs11", Level::from_str(&debug_level).ok(), None);
-    // Instantiate a backend with a kms client using the `kms.json` file in the local default directory.
-    cosmian_pkcs11_module::traits::register_backend(Box::new(
-        backend::CkmsBackend::instantiate(
-            get_kms_client()
-                .expect("failed instantiating the KMS client with the current configuration"),
-        )
-        .expect("Failed to instantiate backend."),
-    ));
+    // Instantiate the backends using the `kms.json` file in the local default directory.
+    cosmian_pkcs11_module::traits::register_backends(
+        instantiate_backends().expect("Failed to instantiate backend."),
+    );
     // Update the function list with this PKCS#11 entry function
     FUNC_LIST.C_GetFunctionList = Some(C_GetFunctionList);
     // Return the function list to the client application using the output parameters
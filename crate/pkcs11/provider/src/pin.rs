@@ -0,0 +1,133 @@
+//! Protection of the credentials of a slot with the PIN of the token.
+//!
+//! A refresh token is encrypted with AES-256-GCM under a key derived from the PIN
+//! using PBKDF2-HMAC-SHA512; the protected value is the base64 encoding of
+//! `salt || nonce || ciphertext || tag`.
+//! A PKCS#12 client certificate is re-encrypted with the PIN as its password.
+
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose, Engine};
+use cosmian_pkcs11_module::MError;
+use openssl::{
+    hash::MessageDigest,
+    pkcs12::Pkcs12,
+    pkcs5::pbkdf2_hmac,
+    rand::rand_bytes,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use zeroize::Zeroizing;
+
+use crate::error::Pkcs11Error;
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+/// OWASP recommended iterations for PBKDF2-HMAC-SHA512
+const PBKDF2_ITERATIONS: usize = 210_000;
+
+/// The PIN as a string: PINs are made of UTF-8 characters
+pub(crate) fn pin_str(pin: &[u8]) -> Result<&str, MError> {
+    std::str::from_utf8(pin).map_err(|_| MError::ArgumentsBad)
+}
+
+fn derive_key(pin: &[u8], salt: &[u8]) -> Result<Zeroizing<Vec<u8>>, Pkcs11Error> {
+    let mut key = Zeroizing::new(vec![0_u8; KEY_LENGTH]);
+    pbkdf2_hmac(
+        pin,
+        salt,
+        PBKDF2_ITERATIONS,
+        MessageDigest::sha512(),
+        &mut key,
+    )
+    .map_err(|e| Pkcs11Error::Default(e.to_string()))?;
+    Ok(key)
+}
+
+/// Encrypt the secret under a key derived from the PIN
+pub(crate) fn protect_with_pin(pin: &[u8], secret: &[u8]) -> Result<String, Pkcs11Error> {
+    let mut salt = [0_u8; SALT_LENGTH];
+    let mut nonce = [0_u8; NONCE_LENGTH];
+    rand_bytes(&mut salt).map_err(|e| Pkcs11Error::Default(e.to_string()))?;
+    rand_bytes(&mut nonce).map_err(|e| Pkcs11Error::Default(e.to_string()))?;
+    let key = derive_key(pin, &salt)?;
+    let mut tag = [0_u8; TAG_LENGTH];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(&nonce),
+        &[],
+        secret,
+        &mut tag,
+    )
+    .map_err(|e| Pkcs11Error::Default(e.to_string()))?;
+    Ok(general_purpose::STANDARD.encode([salt.as_slice(), &nonce, &ciphertext, &tag].concat()))
+}
+
+/// Decrypt a secret protected by `protect_with_pin`.
+/// Fails with `MError::PinIncorrect` if the PIN is not the one that protected it.
+pub(crate) fn unprotect_with_pin(
+    pin: &[u8],
+    protected: &str,
+) -> Result<Zeroizing<Vec<u8>>, MError> {
+    let bytes = general_purpose::STANDARD
+        .decode(protected)
+        .map_err(|e| Pkcs11Error::Conversion(format!("invalid PIN protected secret: {e}")))?;
+    if bytes.len() < SALT_LENGTH + NONCE_LENGTH + TAG_LENGTH {
+        return Err(Pkcs11Error::Conversion("PIN protected secret is too short".to_owned()).into())
+    }
+    let (salt, rest) = bytes.split_at(SALT_LENGTH);
+    let (nonce, rest) = rest.split_at(NONCE_LENGTH);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LENGTH);
+    let key = derive_key(pin, salt)?;
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(nonce),
+        &[],
+        ciphertext,
+        tag,
+    )
+    .map(Zeroizing::new)
+    .map_err(|_| MError::PinIncorrect)
+}
+
+/// Check that the password opens the PKCS#12 file.
+/// Fails with `MError::PinIncorrect` if it does not.
+pub(crate) fn check_pkcs12_password(p12_path: &Path, password: &str) -> Result<(), MError> {
+    let der = fs::read(p12_path).map_err(Pkcs11Error::from)?;
+    Pkcs12::from_der(&der)
+        .and_then(|p12| p12.parse2(password))
+        .map_err(|_| MError::PinIncorrect)?;
+    Ok(())
+}
+
+/// Re-encrypt the PKCS#12 file with a new password.
+/// Fails with `MError::PinIncorrect` if the old password does not open it.
+pub(crate) fn reprotect_pkcs12(
+    p12_path: &Path,
+    old_password: &str,
+    new_password: &str,
+) -> Result<(), MError> {
+    let der = fs::read(p12_path).map_err(Pkcs11Error::from)?;
+    let parsed = Pkcs12::from_der(&der)
+        .and_then(|p12| p12.parse2(old_password))
+        .map_err(|_| MError::PinIncorrect)?;
+    let mut builder = Pkcs12::builder();
+    if let Some(pkey) = &parsed.pkey {
+        builder.pkey(pkey);
+    }
+    if let Some(cert) = &parsed.cert {
+        builder.cert(cert);
+    }
+    if let Some(ca) = parsed.ca {
+        builder.ca(ca);
+    }
+    let der = builder
+        .build2(new_password)
+        .and_then(|p12| p12.to_der())
+        .map_err(|e| Pkcs11Error::Default(format!("failed re-encrypting the PKCS#12: {e}")))?;
+    fs::write(p12_path, der).map_err(Pkcs11Error::from)?;
+    Ok(())
+}
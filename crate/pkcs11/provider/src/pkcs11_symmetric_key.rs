@@ -0,0 +1,63 @@
+use cosmian_kmip::kmip::kmip_types::{Attributes, CryptographicAlgorithm};
+use cosmian_pkcs11_module::traits::{KeyAlgorithm, SymmetricKey};
+
+use crate::error::Pkcs11Error;
+
+/// A PKCS11 symmetric key is a `SymmetricKey` that references a KMS key:
+/// the key material is never exported from the KMS.
+#[derive(Debug)]
+pub struct Pkcs11SymmetricKey {
+    remote_id: String,
+    label: String,
+    key_size: usize,
+}
+
+impl Pkcs11SymmetricKey {
+    /// Build the key from its KMS identifier and attributes;
+    /// the label is made of the tags of the key not in `search_tags`
+    pub fn try_from_attributes(
+        remote_id: String,
+        attributes: &Attributes,
+        search_tags: &[String],
+    ) -> Result<Self, Pkcs11Error> {
+        if attributes.cryptographic_algorithm != Some(CryptographicAlgorithm::AES) {
+            return Err(Pkcs11Error::NotSupported(format!(
+                "symmetric key {remote_id}: only AES keys are supported"
+            )))
+        }
+        let key_size = attributes.cryptographic_length.ok_or_else(|| {
+            Pkcs11Error::Default(format!(
+                "symmetric key {remote_id}: missing cryptographic length"
+            ))
+        })?;
+        let mut other_tags = attributes
+            .get_tags()
+            .into_iter()
+            .filter(|t| !t.is_empty() && !search_tags.contains(t) && !t.starts_with('_'))
+            .collect::<Vec<String>>();
+        other_tags.sort();
+        Ok(Self {
+            remote_id,
+            label: other_tags.join(","),
+            key_size: usize::try_from(key_size).map_err(|e| Pkcs11Error::Default(e.to_string()))?,
+        })
+    }
+}
+
+impl SymmetricKey for Pkcs11SymmetricKey {
+    fn remote_id(&self) -> String {
+        self.remote_id.clone()
+    }
+
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    fn algorithm(&self) -> KeyAlgorithm {
+        KeyAlgorithm::Aes
+    }
+
+    fn key_size(&self) -> usize {
+        self.key_size
+    }
+}
@@ -1,16 +1,36 @@
+use std::{fs, path::PathBuf};
+
 use cosmian_kmip::{
     crypto::symmetric::create_symmetric_key_kmip_object,
     kmip::{
         kmip_data_structures::{KeyBlock, KeyMaterial, KeyValue},
         kmip_objects::Object,
-        kmip_types::{CryptographicAlgorithm, KeyFormatType},
+        kmip_operations::Get,
+        kmip_types::{CryptographicAlgorithm, KeyFormatType, UniqueIdentifier},
+    },
+};
+use cosmian_kms_client::{import_object, ClientConf, KmsClient, Pkcs11SlotConf};
+use cosmian_pkcs11_module::{
+    traits::{
+        Backend, DigestType, EncryptionAlgorithm, KeyAlgorithm, RemoteObjectType,
+        SignatureAlgorithm, SymmetricKey,
     },
+    MError,
 };
-use cosmian_kms_client::{import_object, KmsClient};
-use cosmian_pkcs11_module::traits::Backend;
 use kms_test_server::{start_default_test_kms_server, ONCE};
+use openssl::{pkcs12::Pkcs12, pkey::PKey, rsa::Rsa};
 
-use crate::{backend::CkmsBackend, error::Pkcs11Error, kms_object::get_kms_objects_async};
+use crate::{
+    backend::{jwt_expiry, CkmsBackend},
+    error::Pkcs11Error,
+    kms_object::{
+        get_kms_objects_async, get_kms_objects_attributes_async, kms_create_key_pair_async,
+        kms_create_object_async, kms_decrypt_async, kms_encrypt_async, kms_sign_async,
+        kms_unwrap_key_async, kms_verify_async, kms_wrap_key_async,
+    },
+    pin::{protect_with_pin, unprotect_with_pin},
+    pkcs11_symmetric_key::Pkcs11SymmetricKey,
+};
 
 #[tokio::test]
 async fn test_kms_client() -> Result<(), Pkcs11Error> {
@@ -149,3 +169,347 @@ fn test_backend() -> Result<(), Pkcs11Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_rsa_key_pair() -> Result<(), Pkcs11Error> {
+    let ctx = ONCE
+        .get_or_try_init(start_default_test_kms_server)
+        .await
+        .unwrap();
+    let kms_client = ctx.owner_client_conf.initialize_kms_client()?;
+    // do not use the disk encryption tag: the keys must not be listed by the backend
+    let tags = ["pkcs11_rsa_test".to_string()];
+
+    let (public_key_id, private_key_id) =
+        kms_create_key_pair_async(&kms_client, KeyAlgorithm::Rsa, 2048, &tags).await?;
+
+    // sign and verify
+    let data = b"PKCS#11 data to sign";
+    let signature_algorithm = SignatureAlgorithm::RsaPkcs1v15Sha256;
+    let signature = kms_sign_async(
+        &kms_client,
+        private_key_id.clone(),
+        &signature_algorithm,
+        data,
+    )
+    .await?;
+    kms_verify_async(
+        &kms_client,
+        public_key_id.clone(),
+        &signature_algorithm,
+        data,
+        &signature,
+    )
+    .await?;
+    assert!(matches!(
+        kms_verify_async(
+            &kms_client,
+            public_key_id.clone(),
+            &signature_algorithm,
+            b"other data",
+            &signature,
+        )
+        .await,
+        Err(MError::SignatureInvalid)
+    ));
+
+    // encrypt and decrypt
+    let oaep = EncryptionAlgorithm::RsaOaep {
+        digest: DigestType::Sha256,
+        mask_generation_function: DigestType::Sha256,
+    };
+    let ciphertext = kms_encrypt_async(
+        &kms_client,
+        public_key_id.clone(),
+        oaep.clone(),
+        data.to_vec(),
+    )
+    .await?;
+    let cleartext = kms_decrypt_async(
+        &kms_client,
+        private_key_id.clone(),
+        oaep.clone(),
+        ciphertext,
+    )
+    .await?;
+    assert_eq!(cleartext.as_slice(), data);
+
+    // wrap and unwrap an AES key
+    let aes_key_id = kms_create_object_async(
+        &kms_client,
+        RemoteObjectType::SymmetricKey,
+        &[42_u8; 32],
+        &tags,
+    )
+    .await?;
+    let wrapped_key =
+        kms_wrap_key_async(&kms_client, public_key_id, oaep.clone(), aes_key_id.clone()).await?;
+    let unwrapped_key_id = kms_unwrap_key_async(
+        &kms_client,
+        private_key_id,
+        oaep,
+        &wrapped_key,
+        RemoteObjectType::SymmetricKey,
+        &tags,
+    )
+    .await?;
+    let key_bytes = |id: String| {
+        let kms_client = &kms_client;
+        async move {
+            let response = kms_client
+                .get(Get {
+                    unique_identifier: Some(UniqueIdentifier::TextString(id)),
+                    ..Default::default()
+                })
+                .await?;
+            Ok::<_, Pkcs11Error>(response.object.key_block()?.key_bytes()?)
+        }
+    };
+    assert_eq!(
+        key_bytes(unwrapped_key_id).await?,
+        key_bytes(aes_key_id).await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ec_key_pair() -> Result<(), Pkcs11Error> {
+    let ctx = ONCE
+        .get_or_try_init(start_default_test_kms_server)
+        .await
+        .unwrap();
+    let kms_client = ctx.owner_client_conf.initialize_kms_client()?;
+    let tags = ["pkcs11_ec_test".to_string()];
+
+    let (public_key_id, private_key_id) =
+        kms_create_key_pair_async(&kms_client, KeyAlgorithm::Ecc, 256, &tags).await?;
+
+    // CKM_ECDSA signs a digest and returns r || s
+    let digest = [7_u8; 32];
+    let signature = kms_sign_async(
+        &kms_client,
+        private_key_id,
+        &SignatureAlgorithm::Ecdsa,
+        &digest,
+    )
+    .await?;
+    assert_eq!(signature.len(), 64);
+    kms_verify_async(
+        &kms_client,
+        public_key_id,
+        &SignatureAlgorithm::Ecdsa,
+        &digest,
+        &signature,
+    )
+    .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_aes_key() -> Result<(), Pkcs11Error> {
+    let ctx = ONCE
+        .get_or_try_init(start_default_test_kms_server)
+        .await
+        .unwrap();
+    let kms_client = ctx.owner_client_conf.initialize_kms_client()?;
+    let tags = ["pkcs11_aes_test".to_string()];
+
+    let aes_key_id = kms_create_object_async(
+        &kms_client,
+        RemoteObjectType::SymmetricKey,
+        &[7_u8; 32],
+        &tags,
+    )
+    .await?;
+
+    // the key is exposed from its attributes only
+    let keys = get_kms_objects_attributes_async(&kms_client, &tags).await?;
+    assert_eq!(keys.len(), 1);
+    let (id, attributes) = &keys[0];
+    let key = Pkcs11SymmetricKey::try_from_attributes(id.clone(), attributes, &tags)?;
+    assert_eq!(key.remote_id(), aes_key_id);
+    assert_eq!(key.key_size(), 256);
+
+    let data = b"PKCS#11 data to encrypt";
+    for algorithm in [
+        EncryptionAlgorithm::AesGcm {
+            iv: vec![1_u8; 12],
+            aad: b"additional data".to_vec(),
+            tag_bits: 128,
+        },
+        EncryptionAlgorithm::AesCbcPad { iv: [2_u8; 16] },
+        EncryptionAlgorithm::AesKeyWrapPad,
+    ] {
+        let ciphertext = kms_encrypt_async(
+            &kms_client,
+            aes_key_id.clone(),
+            algorithm.clone(),
+            data.to_vec(),
+        )
+        .await?;
+        let cleartext =
+            kms_decrypt_async(&kms_client, aes_key_id.clone(), algorithm, ciphertext).await?;
+        assert_eq!(cleartext.as_slice(), data);
+    }
+
+    // wrap and unwrap an AES key with CKM_AES_KEY_WRAP_KWP
+    let key_to_wrap_id = kms_create_object_async(
+        &kms_client,
+        RemoteObjectType::SymmetricKey,
+        &[9_u8; 16],
+        &tags,
+    )
+    .await?;
+    let wrapped_key = kms_wrap_key_async(
+        &kms_client,
+        aes_key_id.clone(),
+        EncryptionAlgorithm::AesKeyWrapPad,
+        key_to_wrap_id,
+    )
+    .await?;
+    // RFC 5649 adds 8 bytes to the 16 bytes key
+    assert_eq!(wrapped_key.len(), 24);
+    kms_unwrap_key_async(
+        &kms_client,
+        aes_key_id.clone(),
+        EncryptionAlgorithm::AesKeyWrapPad,
+        &wrapped_key,
+        RemoteObjectType::SymmetricKey,
+        &tags,
+    )
+    .await?;
+
+    // AES GCM cannot be used to wrap keys
+    assert!(
+        kms_wrap_key_async(
+            &kms_client,
+            aes_key_id.clone(),
+            EncryptionAlgorithm::AesGcm {
+                iv: vec![1_u8; 12],
+                aad: vec![],
+                tag_bits: 128,
+            },
+            aes_key_id,
+        )
+        .await
+        .is_err()
+    );
+    Ok(())
+}
+
+/// A configuration with a single PKCS#11 slot, saved in a temporary directory
+fn slot_client_conf(name: &str, slot_conf: Pkcs11SlotConf) -> (ClientConf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("ckms_pkcs11_{name}"));
+    fs::create_dir_all(&dir).unwrap();
+    let conf_path = dir.join("kms.json");
+    let client_conf = ClientConf {
+        pkcs11_slots: Some(vec![slot_conf]),
+        ..Default::default()
+    };
+    client_conf.save(&conf_path).unwrap();
+    (client_conf, conf_path)
+}
+
+#[test]
+fn test_pin_protected_refresh_token() -> Result<(), Pkcs11Error> {
+    let protected = protect_with_pin(b"1234", b"refresh token")?;
+    assert_eq!(
+        unprotect_with_pin(b"1234", &protected).unwrap().to_vec(),
+        b"refresh token".to_vec()
+    );
+    assert!(matches!(
+        unprotect_with_pin(b"4321", &protected),
+        Err(MError::PinIncorrect)
+    ));
+
+    let (client_conf, conf_path) = slot_client_conf(
+        "refresh_token",
+        Pkcs11SlotConf {
+            token_label: "tenant-a".to_owned(),
+            oauth2_refresh_token: Some("refresh token".to_owned()),
+            ..Default::default()
+        },
+    );
+    let backend = CkmsBackend::instantiate_slot(&client_conf, &conf_path, 0)?;
+    assert!(backend.login_required());
+    assert_eq!(&backend.token_label()[..8], b"tenant-a");
+    // the KMS is not reachable before login
+    assert!(matches!(
+        backend.find_all_data_objects(),
+        Err(MError::UserNotLoggedIn)
+    ));
+    assert!(matches!(
+        backend.login(b"1234"),
+        Err(MError::UserPinNotInitialized(_))
+    ));
+
+    // the refresh token is stored encrypted under the PIN
+    backend.init_pin(b"1234")?;
+    backend.set_pin(b"1234", b"5678")?;
+    assert!(matches!(
+        backend.set_pin(b"1234", b"5678"),
+        Err(MError::PinIncorrect)
+    ));
+    let slot_conf = ClientConf::load(&conf_path)?.pkcs11_slots.unwrap()[0].clone();
+    assert!(slot_conf.oauth2_refresh_token.is_none());
+    assert_eq!(
+        unprotect_with_pin(
+            b"5678",
+            &slot_conf.pin_protected_oauth2_refresh_token.unwrap()
+        )
+        .unwrap()
+        .to_vec(),
+        b"refresh token".to_vec()
+    );
+    assert!(matches!(backend.login(b"1234"), Err(MError::PinIncorrect)));
+    Ok(())
+}
+
+#[test]
+fn test_jwt_expiry() {
+    // {"alg":"none"} . {"sub":"user","exp":1700000000}
+    let jwt = "eyJhbGciOiJub25lIn0.eyJzdWIiOiJ1c2VyIiwiZXhwIjoxNzAwMDAwMDAwfQ.";
+    assert_eq!(jwt_expiry(jwt), Some(1_700_000_000));
+    assert_eq!(jwt_expiry("not a JWT"), None);
+}
+
+#[test]
+fn test_pin_protected_pkcs12() -> Result<(), Pkcs11Error> {
+    let p12_path = std::env::temp_dir().join("ckms_pkcs11_client.p12");
+    let rsa = Rsa::generate(2048).unwrap();
+    let pkey = PKey::from_rsa(rsa).unwrap();
+    let mut builder = Pkcs12::builder();
+    builder.pkey(&pkey);
+    fs::write(
+        &p12_path,
+        builder.build2("password").unwrap().to_der().unwrap(),
+    )?;
+
+    let (client_conf, conf_path) = slot_client_conf(
+        "pkcs12",
+        Pkcs11SlotConf {
+            token_label: "tenant-b".to_owned(),
+            ssl_client_pkcs12_path: Some(p12_path.to_string_lossy().to_string()),
+            ssl_client_pkcs12_password: Some("password".to_owned()),
+            ..Default::default()
+        },
+    );
+    let backend = CkmsBackend::instantiate_slot(&client_conf, &conf_path, 0)?;
+
+    // the PKCS#12 is re-encrypted with the PIN
+    backend.init_pin(b"1234")?;
+    let slot_conf = ClientConf::load(&conf_path)?.pkcs11_slots.unwrap()[0].clone();
+    assert!(slot_conf.ssl_client_pkcs12_password.is_none());
+    Pkcs12::from_der(&fs::read(&p12_path)?)
+        .unwrap()
+        .parse2("1234")
+        .unwrap();
+
+    assert!(matches!(backend.login(b"4321"), Err(MError::PinIncorrect)));
+    backend.set_pin(b"1234", b"5678")?;
+    Pkcs12::from_der(&fs::read(&p12_path)?)
+        .unwrap()
+        .parse2("5678")
+        .unwrap();
+    Ok(())
+}
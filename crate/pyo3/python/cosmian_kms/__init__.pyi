@@ -10,6 +10,12 @@ class KmsObject:
     def object_type(self) -> str:
         """Get the type of the underlying KMIP object.
 
+        Returns:
+            str
+        """
+    def unique_identifier(self) -> str:
+        """Get the unique identifier of the object.
+
         Returns:
             str
         """
@@ -67,6 +73,248 @@ class KmsEncryptResponse:
             bytes
         """
 
+class KmsUserAccess:
+    """The operations a user has been granted on an object."""
+
+    def user_id(self) -> str:
+        """Get the user the access is granted to.
+
+        Returns:
+            str
+        """
+    def operations(self) -> List[str]:
+        """Get the granted operations, e.g. `get`.
+
+        Returns:
+            List[str]
+        """
+
+class KmsOwnedObject:
+    """An object owned by the user."""
+
+    def unique_identifier(self) -> str:
+        """Get the unique identifier of the object.
+
+        Returns:
+            str
+        """
+    def state(self) -> str:
+        """Get the state of the object, e.g. `Active`.
+
+        Returns:
+            str
+        """
+    def tags(self) -> List[str]:
+        """Get the tags of the object.
+
+        Returns:
+            List[str]
+        """
+    def is_wrapped(self) -> bool:
+        """Whether the object is wrapped.
+
+        Returns:
+            bool
+        """
+
+class KmsAccessRightsObtained:
+    """An object the user has been granted access to."""
+
+    def unique_identifier(self) -> str:
+        """Get the unique identifier of the object.
+
+        Returns:
+            str
+        """
+    def owner_id(self) -> str:
+        """Get the owner of the object.
+
+        Returns:
+            str
+        """
+    def state(self) -> str:
+        """Get the state of the object, e.g. `Active`.
+
+        Returns:
+            str
+        """
+    def operations(self) -> List[str]:
+        """Get the granted operations, e.g. `get`.
+
+        Returns:
+            List[str]
+        """
+
+class KmsRequest:
+    """A KMIP operation to send in a batch with `KmsClient.message`.
+    The request is built when the message is sent, so it can be sent several times.
+    """
+
+    @staticmethod
+    def create_symmetric_key(
+        key_len_in_bits: int,
+        algorithm: str = "AES",
+        tags: Optional[List[str]] = None,
+    ) -> KmsRequest:
+        """Create a symmetric key.
+
+        Args:
+            key_len_in_bits (int): length of the key in bits
+            algorithm (str, optional): "AES" or "ChaCha20". Defaults to "AES"
+            tags (List[str], optional): tags associated with the key
+        """
+    @staticmethod
+    def create_rsa_key_pair(
+        key_size_in_bits: int = 4096,
+        tags: Optional[List[str]] = None,
+    ) -> KmsRequest:
+        """Create an RSA key pair.
+
+        Args:
+            key_size_in_bits (int, optional): size of the modulus in bits. Defaults to 4096
+            tags (List[str], optional): tags associated with the keys
+        """
+    @staticmethod
+    def create_ec_key_pair(
+        curve: str = "nist-p256",
+        tags: Optional[List[str]] = None,
+    ) -> KmsRequest:
+        """Create an elliptic curve key pair.
+
+        Args:
+            curve (str, optional): one of "nist-p192", "nist-p224", "nist-p256", "nist-p384",
+                "nist-p521", "x25519", "ed25519", "x448" and "ed448". Defaults to "nist-p256"
+            tags (List[str], optional): tags associated with the keys
+        """
+    @staticmethod
+    def get(unique_identifier: UidOrTags) -> KmsRequest:
+        """Get an object.
+
+        Args:
+            unique_identifier (Union[str, List[str]]): object unique id or associated tags
+        """
+    @staticmethod
+    def locate(tags: List[str]) -> KmsRequest:
+        """Locate the objects carrying all the tags.
+
+        Args:
+            tags (List[str]): tags of the objects
+        """
+    @staticmethod
+    def encrypt(data: bytes, key_identifier: UidOrTags) -> KmsRequest:
+        """Encrypt data with a symmetric key.
+
+        Args:
+            data (bytes): data to encrypt
+            key_identifier (Union[str, List[str]]): key unique id or associated tags
+        """
+    @staticmethod
+    def decrypt(
+        encrypted_data: bytes,
+        key_identifier: UidOrTags,
+        iv_counter_nonce: Optional[bytes] = None,
+        authentication_encryption_tag: Optional[bytes] = None,
+    ) -> KmsRequest:
+        """Decrypt data with a symmetric key.
+
+        Args:
+            encrypted_data (bytes): ciphertext
+            key_identifier (Union[str, List[str]]): key unique id or associated tags
+            iv_counter_nonce (Optional[bytes]): the IV, counter or nonce
+            authentication_encryption_tag (Optional[bytes]): the authentication tag
+        """
+    @staticmethod
+    def revoke(revocation_reason: str, key_identifier: UidOrTags) -> KmsRequest:
+        """Revoke a key.
+
+        Args:
+            revocation_reason (str): explanation of the revocation
+            key_identifier (Union[str, List[str]]): key unique id or associated tags
+        """
+    @staticmethod
+    def destroy(key_identifier: UidOrTags) -> KmsRequest:
+        """Destroy a key.
+
+        Args:
+            key_identifier (Union[str, List[str]]): key unique id or associated tags
+        """
+    def operation(self) -> str:
+        """Get the KMIP operation of the request, e.g. `Get`.
+
+        Returns:
+            str
+        """
+
+class KmsResponse:
+    """The result of a KMIP operation sent in a batch with `KmsClient.message`."""
+
+    def operation(self) -> Optional[str]:
+        """Get the KMIP operation of the response, e.g. `Get`.
+
+        Returns:
+            Optional[str]
+        """
+    def is_success(self) -> bool:
+        """Whether the operation succeeded.
+
+        Returns:
+            bool
+        """
+    def result_status(self) -> str:
+        """Get the status of the operation, e.g. `OperationFailed`.
+
+        Returns:
+            str
+        """
+    def result_reason(self) -> Optional[str]:
+        """Get the reason of the failure of the operation.
+
+        Returns:
+            Optional[str]
+        """
+    def result_message(self) -> Optional[str]:
+        """Get the message explaining the failure of the operation.
+
+        Returns:
+            Optional[str]
+        """
+    def unique_identifier(self) -> Optional[str]:
+        """Get the unique identifier of the object the operation applied to, or created by the operation.
+
+        Returns:
+            Optional[str]
+        """
+    def key_pair_unique_identifiers(self) -> Optional[Tuple[str, str]]:
+        """Get the unique identifiers of a created key pair.
+
+        Returns:
+            Optional[Tuple[str, str]]: (Public key UID, Private key UID)
+        """
+    def located_unique_identifiers(self) -> Optional[List[str]]:
+        """Get the unique identifiers of the located objects.
+
+        Returns:
+            Optional[List[str]]
+        """
+    def object(self) -> Optional[KmsObject]:
+        """Get the object of a Get operation.
+
+        Returns:
+            Optional[KmsObject]
+        """
+    def encrypt_response(self) -> Optional[KmsEncryptResponse]:
+        """Get the result of an Encrypt operation.
+
+        Returns:
+            Optional[KmsEncryptResponse]
+        """
+    def decrypted_data(self) -> Optional[bytes]:
+        """Get the plaintext of a Decrypt operation.
+
+        Returns:
+            Optional[bytes]
+        """
+
 class KmsClient:
     """Python client for a Key Management System (KMS). The methods return Future object which
     can be used to track and manage the status of the requests asynchronously.
@@ -424,3 +672,174 @@ class KmsClient:
         Returns:
             Future[bytes]: plaintext bytes
         """
+    def create_rsa_key_pair(
+        self,
+        key_size_in_bits: int = 4096,
+        tags: Optional[List[str]] = None,
+    ) -> Future[Tuple[str, str]]:
+        """Create an RSA key pair.
+
+        Args:
+            key_size_in_bits (int, optional): size of the modulus in bits. Defaults to 4096
+            tags (List[str], optional): tags associated with the keys
+
+        Returns:
+            Future[Tuple[str, str]]: (Public key UID, Private key UID)
+        """
+    def create_ec_key_pair(
+        self,
+        curve: str = "nist-p256",
+        tags: Optional[List[str]] = None,
+    ) -> Future[Tuple[str, str]]:
+        """Create an elliptic curve key pair.
+
+        Args:
+            curve (str, optional): one of "nist-p192", "nist-p224", "nist-p256", "nist-p384",
+                "nist-p521", "x25519", "ed25519", "x448" and "ed448". Defaults to "nist-p256"
+            tags (List[str], optional): tags associated with the keys
+
+        Returns:
+            Future[Tuple[str, str]]: (Public key UID, Private key UID)
+        """
+    def certify(
+        self,
+        issuer_private_key_id: Optional[str] = None,
+        issuer_certificate_id: Optional[str] = None,
+        certificate_signing_request: Optional[bytes] = None,
+        certificate_signing_request_format: str = "pem",
+        public_key_id_to_certify: Optional[str] = None,
+        subject_name: Optional[str] = None,
+        number_of_days: int = 365,
+        certificate_id: Optional[str] = None,
+        tags: Optional[List[str]] = None,
+    ) -> Future[str]:
+        """Issue or renew an X509 certificate, either from a certificate signing request
+        or from a public key stored in the KMS.
+
+        Args:
+            issuer_private_key_id (Optional[str]): private key of the issuer
+            issuer_certificate_id (Optional[str]): certificate of the issuer
+            certificate_signing_request (Optional[bytes]): PKCS#10 request
+            certificate_signing_request_format (str, optional): "pem" or "der". Defaults to "pem"
+            public_key_id_to_certify (Optional[str]): public key to certify
+            subject_name (Optional[str]): subject of the certificate, required with a public key,
+                e.g. "CN=John Doe,OU=Org Unit,O=Org Name,L=City,ST=State,C=US"
+            number_of_days (int, optional): validity of the certificate in days. Defaults to 365
+            certificate_id (Optional[str]): unique identifier of the certificate
+            tags (List[str], optional): tags associated with the certificate
+
+        Returns:
+            Future[str]: uid of the certificate
+        """
+    def import_object(
+        self,
+        data: bytes,
+        format: str = "pem",
+        unique_identifier: Optional[str] = None,
+        replace_existing: bool = False,
+        unwrap: bool = False,
+        tags: Optional[List[str]] = None,
+    ) -> Future[str]:
+        """Import a key or a certificate into the KMS.
+
+        Args:
+            data (bytes): encoded object
+            format (str, optional): one of "json-ttlv", "pem", "sec1", "pkcs1-priv", "pkcs1-pub",
+                "pkcs8", "spki" and "x509" (DER certificate). Defaults to "pem"
+            unique_identifier (Optional[str]): unique identifier of the object
+            replace_existing (bool, optional): replace an existing object with the same identifier
+            unwrap (bool, optional): unwrap the object if it is wrapped before storing it
+            tags (List[str], optional): tags associated with the object
+
+        Returns:
+            Future[str]: uid of the object
+        """
+    def export_object(
+        self,
+        unique_identifier: UidOrTags,
+        format: str = "json-ttlv",
+        unwrap: bool = False,
+        wrapping_key_id: Optional[str] = None,
+        allow_revoked: bool = False,
+    ) -> Future[bytes]:
+        """Export a key or a certificate from the KMS.
+
+        Args:
+            unique_identifier (Union[str, List[str]]): object unique id or associated tags
+            format (str, optional): one of "json-ttlv", "sec1-pem", "sec1-der", "pkcs1-pem",
+                "pkcs1-der", "pkcs8-pem", "pkcs8-der", "spki-pem", "spki-der", "raw",
+                "x509-pem" and "x509-der". Defaults to "json-ttlv"
+            unwrap (bool, optional): unwrap the key if it is wrapped
+            wrapping_key_id (Optional[str]): key used to wrap the exported key
+            allow_revoked (bool, optional): allow exporting a revoked or destroyed object
+
+        Returns:
+            Future[bytes]: encoded object
+        """
+    def locate(self, tags: List[str]) -> Future[List[str]]:
+        """Locate the objects carrying all the tags.
+
+        Args:
+            tags (List[str]): tags of the objects
+
+        Returns:
+            Future[List[str]]: uids of the objects
+        """
+    def grant_access(
+        self,
+        user_id: str,
+        operations: List[str],
+        unique_identifier: str,
+    ) -> Future[None]:
+        """Grant a user access to an object.
+
+        Args:
+            user_id (str): user to grant the access to
+            operations (List[str]): operations to grant, e.g. ["get", "encrypt"]
+            unique_identifier (str): uid of the object
+        """
+    def revoke_access(
+        self,
+        user_id: str,
+        operations: List[str],
+        unique_identifier: str,
+    ) -> Future[None]:
+        """Revoke the access of a user to an object.
+
+        Args:
+            user_id (str): user to revoke the access of
+            operations (List[str]): operations to revoke, e.g. ["get", "encrypt"]
+            unique_identifier (str): uid of the object
+        """
+    def list_access(self, unique_identifier: str) -> Future[List[KmsUserAccess]]:
+        """List the accesses granted on an object owned by the user.
+
+        Args:
+            unique_identifier (str): uid of the object
+
+        Returns:
+            Future[List[KmsUserAccess]]
+        """
+    def list_owned_objects(self) -> Future[List[KmsOwnedObject]]:
+        """List the objects owned by the user.
+
+        Returns:
+            Future[List[KmsOwnedObject]]
+        """
+    def list_access_rights_obtained(self) -> Future[List[KmsAccessRightsObtained]]:
+        """List the objects the user has been granted access to.
+
+        Returns:
+            Future[List[KmsAccessRightsObtained]]
+        """
+    def message(self, requests: List[KmsRequest]) -> Future[List[KmsResponse]]:
+        """Send a batch of operations in a single KMIP message.
+        The operations are processed in order and a failed operation
+        does not prevent the following ones from being processed.
+
+        Args:
+            requests (List[KmsRequest]): operations
+
+        Returns:
+            Future[List[KmsResponse]]: results, in the order of the operations
+        """
@@ -1,10 +1,14 @@
 use pyo3::prelude::*;
 
 mod py_kms_client;
+mod py_kms_message;
 mod py_kms_object;
 
 use py_kms_client::KmsClient;
-use py_kms_object::{KmsEncryptResponse, KmsObject};
+use py_kms_message::{KmsRequest, KmsResponse};
+use py_kms_object::{
+    KmsAccessRightsObtained, KmsEncryptResponse, KmsObject, KmsOwnedObject, KmsUserAccess,
+};
 
 /// A Python module implemented in Rust. The name of this function must match
 /// the `lib.name` setting in the `Cargo.toml`, else Python will not be able to
@@ -14,5 +18,10 @@ fn cosmian_kms(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<KmsClient>()?;
     m.add_class::<KmsObject>()?;
     m.add_class::<KmsEncryptResponse>()?;
+    m.add_class::<KmsUserAccess>()?;
+    m.add_class::<KmsOwnedObject>()?;
+    m.add_class::<KmsAccessRightsObtained>()?;
+    m.add_class::<KmsRequest>()?;
+    m.add_class::<KmsResponse>()?;
     Ok(())
 }
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use cloudproof::reexport::{
     cover_crypt::abe_policy::{Attribute, EncryptionHint, Policy},
     crypto_core::bytes_ser_de::Deserializer,
@@ -13,25 +15,46 @@ use cosmian_kmip::{
                 build_import_public_key_request, build_rekey_keypair_request,
             },
         },
+        elliptic_curves::kmip_requests::create_ec_key_pair_request,
         generic::kmip_requests::{
             build_decryption_request, build_encryption_request, build_revoke_key_request,
         },
+        rsa::kmip_requests::create_rsa_key_pair_request,
         symmetric::symmetric_key_create_request,
     },
     kmip::{
-        kmip_operations::Get,
-        kmip_types::{CryptographicAlgorithm, RevocationReason},
+        kmip_data_structures::{KeyBlock, KeyMaterial, KeyValue},
+        kmip_messages::{Message, MessageBatchItem, MessageHeader},
+        kmip_objects::{Object, ObjectType},
+        kmip_operations::{Certify, Get},
+        kmip_types::{
+            Attributes, CertificateAttributes, CertificateRequestType, CertificateType,
+            KeyFormatType, LinkType, LinkedObjectIdentifier, ProtocolVersion, RevocationReason,
+            UniqueIdentifier,
+        },
+        ttlv::serializer::to_ttlv,
     },
 };
-use cosmian_kms_client::KmsClient as RustKmsClient;
+use cosmian_kms_client::{
+    access::{Access, ObjectOperationType},
+    der_to_pem, objects_from_pem, read_object_from_json_ttlv_bytes, KmsClient as RustKmsClient,
+};
 use openssl::x509::X509;
 use pyo3::{
-    exceptions::{PyException, PyTypeError},
+    exceptions::{PyException, PyTypeError, PyValueError},
     prelude::*,
 };
 use rustls::Certificate;
+use zeroize::Zeroizing;
 
-use crate::py_kms_object::{KmsEncryptResponse, KmsObject};
+use crate::{
+    py_kms_message::{
+        locate_request, recommended_curve, symmetric_algorithm, KmsRequest, KmsResponse,
+    },
+    py_kms_object::{
+        KmsAccessRightsObtained, KmsEncryptResponse, KmsObject, KmsOwnedObject, KmsUserAccess,
+    },
+};
 
 /// Create a Rekey Keypair request from `PyO3` arguments
 /// Returns a `PyO3` Future
@@ -54,7 +77,7 @@ macro_rules! rekey_keypair {
     }};
 }
 /// KMS Objects (e.g. keys) can either be referenced by an UID using a single string, or by a list of tags using a list of string.
-pub struct ToUniqueIdentifier(String);
+pub struct ToUniqueIdentifier(pub String);
 
 impl FromPyObject<'_> for ToUniqueIdentifier {
     fn extract(arg: &'_ PyAny) -> PyResult<Self> {
@@ -72,6 +95,73 @@ impl FromPyObject<'_> for ToUniqueIdentifier {
     }
 }
 
+/// Build the KMIP object to import from its encoding
+fn object_from_bytes(data: Vec<u8>, format: &str) -> PyResult<Object> {
+    let key_block = |key_format_type| KeyBlock {
+        key_format_type,
+        key_compression_type: None,
+        key_value: KeyValue {
+            key_material: KeyMaterial::ByteString(Zeroizing::from(data.clone())),
+            attributes: Some(Box::default()),
+        },
+        // the cryptographic algorithm and length are recovered by the server
+        cryptographic_algorithm: None,
+        cryptographic_length: None,
+        key_wrapping_data: None,
+    };
+    Ok(match format {
+        "json-ttlv" => read_object_from_json_ttlv_bytes(&data)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        "pem" => objects_from_pem(&data)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?
+            .pop()
+            .ok_or_else(|| PyValueError::new_err("the PEM does not contain any object"))?,
+        "sec1" => Object::PrivateKey {
+            key_block: key_block(KeyFormatType::ECPrivateKey),
+        },
+        "pkcs1-priv" => Object::PrivateKey {
+            key_block: key_block(KeyFormatType::PKCS1),
+        },
+        "pkcs1-pub" => Object::PublicKey {
+            key_block: key_block(KeyFormatType::PKCS1),
+        },
+        "pkcs8" => Object::PrivateKey {
+            key_block: key_block(KeyFormatType::PKCS8),
+        },
+        "spki" => Object::PublicKey {
+            key_block: key_block(KeyFormatType::PKCS8),
+        },
+        "x509" => Object::Certificate {
+            certificate_type: CertificateType::X509,
+            certificate_value: data,
+        },
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "invalid import format: {format}"
+            )))
+        }
+    })
+}
+
+/// Build an access right from the names of the operations, e.g. `get`
+fn build_access(
+    user_id: String,
+    operations: &[String],
+    unique_identifier: String,
+) -> PyResult<Access> {
+    Ok(Access {
+        unique_identifier: Some(UniqueIdentifier::TextString(unique_identifier)),
+        user_id,
+        operation_types: operations
+            .iter()
+            .map(|operation| {
+                ObjectOperationType::from_str(operation).map_err(PyValueError::new_err)
+            })
+            .collect::<PyResult<Vec<_>>>()?,
+        role: None,
+    })
+}
+
 #[pyclass(subclass)]
 pub struct KmsClient(RustKmsClient);
 
@@ -715,14 +805,9 @@ impl KmsClient {
         tags: Option<Vec<String>>,
         py: Python<'p>,
     ) -> PyResult<&PyAny> {
-        let cryptographic_algorithm = match algorithm {
-            "AES" => Ok(CryptographicAlgorithm::AES),
-            "ChaCha20" => Ok(CryptographicAlgorithm::ChaCha20),
-            _ => Err(PyException::new_err("invalid algorithm")),
-        }?;
         let request = symmetric_key_create_request(
             key_len_in_bits,
-            cryptographic_algorithm,
+            symmetric_algorithm(algorithm)?,
             tags.unwrap_or_default(),
         )
         .map_err(|e| PyException::new_err(e.to_string()))?;
@@ -810,4 +895,511 @@ impl KmsClient {
             }
         })
     }
+
+    /// Create an RSA key pair.
+    ///
+    /// Args:
+    ///     - `key_size_in_bits` (int): the size of the modulus in bits. Defaults to 4096
+    ///     - `tags` (Optional[List[str]]): tags associated with the keys
+    ///
+    /// Returns:
+    ///     Future[Tuple[str, str]]: (Public key UID, Private key UID)
+    #[pyo3(signature = (key_size_in_bits = 4096, tags = None))]
+    pub fn create_rsa_key_pair<'p>(
+        &'p self,
+        key_size_in_bits: usize,
+        tags: Option<Vec<String>>,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let request = create_rsa_key_pair_request(tags.unwrap_or_default(), key_size_in_bits)
+            .map_err(|e| PyException::new_err(e.to_string()))?;
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .create_key_pair(request)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok((
+                response.public_key_unique_identifier.to_string(),
+                response.private_key_unique_identifier.to_string(),
+            ))
+        })
+    }
+
+    /// Create an elliptic curve key pair.
+    ///
+    /// Args:
+    ///     - `curve` (str): one of "nist-p192", "nist-p224", "nist-p256", "nist-p384",
+    /// "nist-p521", "x25519", "ed25519", "x448" and "ed448". Defaults to "nist-p256"
+    ///     - `tags` (Optional[List[str]]): tags associated with the keys
+    ///
+    /// Returns:
+    ///     Future[Tuple[str, str]]: (Public key UID, Private key UID)
+    #[pyo3(signature = (curve = "nist-p256", tags = None))]
+    pub fn create_ec_key_pair<'p>(
+        &'p self,
+        curve: &str,
+        tags: Option<Vec<String>>,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let request =
+            create_ec_key_pair_request(tags.unwrap_or_default(), recommended_curve(curve)?)
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .create_key_pair(request)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok((
+                response.public_key_unique_identifier.to_string(),
+                response.private_key_unique_identifier.to_string(),
+            ))
+        })
+    }
+
+    /// Issue or renew an X509 certificate, either from a certificate signing request
+    /// or from a public key stored in the KMS.
+    ///
+    /// Args:
+    ///     - `issuer_private_key_id` (Optional[str]): the private key of the issuer
+    ///     - `issuer_certificate_id` (Optional[str]): the certificate of the issuer
+    ///     - `certificate_signing_request` (Optional[bytes]): the PKCS#10 request
+    ///     - `certificate_signing_request_format` (str): "pem" or "der". Defaults to "pem"
+    ///     - `public_key_id_to_certify` (Optional[str]): the public key to certify
+    ///     - `subject_name` (Optional[str]): the subject of the certificate, e.g.
+    /// "CN=John Doe,OU=Org Unit,O=Org Name,L=City,ST=State,C=US", required with a public key
+    ///     - `number_of_days` (int): the validity of the certificate in days. Defaults to 365
+    ///     - `certificate_id` (Optional[str]): the unique identifier of the certificate
+    ///     - `tags` (Optional[List[str]]): tags associated with the certificate
+    ///
+    /// Returns:
+    ///     Future[str]: the unique identifier of the certificate
+    #[pyo3(signature = (
+        issuer_private_key_id = None,
+        issuer_certificate_id = None,
+        certificate_signing_request = None,
+        certificate_signing_request_format = "pem",
+        public_key_id_to_certify = None,
+        subject_name = None,
+        number_of_days = 365,
+        certificate_id = None,
+        tags = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn certify<'p>(
+        &'p self,
+        issuer_private_key_id: Option<String>,
+        issuer_certificate_id: Option<String>,
+        certificate_signing_request: Option<Vec<u8>>,
+        certificate_signing_request_format: &str,
+        public_key_id_to_certify: Option<String>,
+        subject_name: Option<&str>,
+        number_of_days: usize,
+        certificate_id: Option<String>,
+        tags: Option<Vec<String>>,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        if certificate_signing_request.is_some() == public_key_id_to_certify.is_some() {
+            return Err(PyValueError::new_err(
+                "either a certificate signing request or a public key to certify must be provided",
+            ))
+        }
+        if issuer_private_key_id.is_none() && issuer_certificate_id.is_none() {
+            return Err(PyValueError::new_err(
+                "either an issuer certificate id or an issuer private key id or both must be \
+                 provided",
+            ))
+        }
+
+        let mut attributes = Attributes {
+            object_type: Some(ObjectType::Certificate),
+            unique_identifier: certificate_id.map(UniqueIdentifier::TextString),
+            ..Attributes::default()
+        };
+        if let Some(issuer_certificate_id) = issuer_certificate_id {
+            attributes.add_link(
+                LinkType::CertificateLink,
+                LinkedObjectIdentifier::TextString(issuer_certificate_id),
+            );
+        }
+        if let Some(issuer_private_key_id) = issuer_private_key_id {
+            attributes.add_link(
+                LinkType::PrivateKeyLink,
+                LinkedObjectIdentifier::TextString(issuer_private_key_id),
+            );
+        }
+        attributes.set_requested_validity_days(number_of_days);
+        attributes
+            .set_tags(tags.unwrap_or_default())
+            .map_err(|e| PyException::new_err(e.to_string()))?;
+
+        let certificate_request_type =
+            certificate_signing_request.as_ref().map(
+                |_| match certificate_signing_request_format {
+                    "der" => CertificateRequestType::PKCS10,
+                    _ => CertificateRequestType::PEM,
+                },
+            );
+        if public_key_id_to_certify.is_some() {
+            let subject_name = subject_name.ok_or_else(|| {
+                PyValueError::new_err("subject name is required when certifying a public key")
+            })?;
+            attributes.certificate_attributes = Some(Box::new(
+                CertificateAttributes::parse_subject_line(subject_name)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            ));
+        }
+
+        let request = Certify {
+            unique_identifier: public_key_id_to_certify.map(UniqueIdentifier::TextString),
+            attributes: Some(attributes),
+            certificate_request_value: certificate_signing_request,
+            certificate_request_type,
+            ..Certify::default()
+        };
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .certify(request)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(response.unique_identifier.to_string())
+        })
+    }
+
+    /// Import a key or a certificate into the KMS.
+    ///
+    /// Args:
+    ///     - `data` (bytes): the encoded object
+    ///     - `format` (str): one of "json-ttlv", "pem", "sec1", "pkcs1-priv", "pkcs1-pub",
+    /// "pkcs8", "spki" and "x509" (DER certificate). Defaults to "pem"
+    ///     - `unique_identifier` (Optional[str]): the unique identifier of the object
+    ///     - `replace_existing` (bool): set to true to replace an existing object with the same identifier
+    ///     - `unwrap` (bool): unwrap the object if it is wrapped before storing it
+    ///     - `tags` (Optional[List[str]]): tags associated with the object
+    ///
+    /// Returns:
+    ///     Future[str]: the unique identifier of the object
+    #[pyo3(signature = (
+        data,
+        format = "pem",
+        unique_identifier = None,
+        replace_existing = false,
+        unwrap = false,
+        tags = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn import_object<'p>(
+        &'p self,
+        data: Vec<u8>,
+        format: &str,
+        unique_identifier: Option<String>,
+        replace_existing: bool,
+        unwrap: bool,
+        tags: Option<Vec<String>>,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let object = object_from_bytes(data, format)?;
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            cosmian_kms_client::import_object(
+                &client,
+                unique_identifier,
+                object,
+                None,
+                unwrap,
+                replace_existing,
+                tags.unwrap_or_default(),
+            )
+            .await
+            .map_err(|e| PyException::new_err(e.to_string()))
+        })
+    }
+
+    /// Export a key or a certificate from the KMS.
+    ///
+    /// Args:
+    ///     - `unique_identifier` (Union[str, List[str]]): object unique id or associated tags
+    ///     - `format` (str): one of "json-ttlv", "sec1-pem", "sec1-der", "pkcs1-pem",
+    /// "pkcs1-der", "pkcs8-pem", "pkcs8-der", "spki-pem", "spki-der", "raw", "x509-pem"
+    /// and "x509-der". Defaults to "json-ttlv"
+    ///     - `unwrap` (bool): unwrap the key if it is wrapped
+    ///     - `wrapping_key_id` (Optional[str]): the key used to wrap the exported key
+    ///     - `allow_revoked` (bool): allow exporting a revoked or destroyed object
+    ///
+    /// Returns:
+    ///     Future[bytes]: the encoded object
+    #[pyo3(signature = (
+        unique_identifier,
+        format = "json-ttlv",
+        unwrap = false,
+        wrapping_key_id = None,
+        allow_revoked = false,
+    ))]
+    pub fn export_object<'p>(
+        &'p self,
+        unique_identifier: ToUniqueIdentifier,
+        format: &str,
+        unwrap: bool,
+        wrapping_key_id: Option<String>,
+        allow_revoked: bool,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let (key_format_type, encode_to_pem) = match format {
+            "json-ttlv" | "raw" | "x509-der" => (None, false),
+            "x509-pem" => (None, true),
+            "sec1-pem" => (Some(KeyFormatType::ECPrivateKey), true),
+            "sec1-der" => (Some(KeyFormatType::ECPrivateKey), false),
+            "pkcs1-pem" => (Some(KeyFormatType::PKCS1), true),
+            "pkcs1-der" => (Some(KeyFormatType::PKCS1), false),
+            "pkcs8-pem" | "spki-pem" => (Some(KeyFormatType::PKCS8), true),
+            "pkcs8-der" | "spki-der" => (Some(KeyFormatType::PKCS8), false),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "invalid export format: {format}"
+                )))
+            }
+        };
+        let format = format.to_owned();
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let (object, _) = cosmian_kms_client::export_object(
+                &client,
+                &unique_identifier.0,
+                unwrap,
+                wrapping_key_id.as_deref(),
+                allow_revoked,
+                key_format_type,
+            )
+            .await
+            .map_err(|e| PyException::new_err(e.to_string()))?;
+            let bytes = match (format.as_str(), &object) {
+                ("json-ttlv", _) => {
+                    let ttlv = to_ttlv(&object).map_err(|e| PyException::new_err(e.to_string()))?;
+                    serde_json::to_vec(&ttlv).map_err(|e| PyException::new_err(e.to_string()))?
+                }
+                (
+                    "x509-pem" | "x509-der",
+                    Object::Certificate {
+                        certificate_value, ..
+                    },
+                ) => {
+                    if encode_to_pem {
+                        X509::from_der(certificate_value)
+                            .and_then(|certificate| certificate.to_pem())
+                            .map_err(|e| PyException::new_err(e.to_string()))?
+                    } else {
+                        certificate_value.clone()
+                    }
+                }
+                ("x509-pem" | "x509-der", _) => {
+                    return Err(PyValueError::new_err(format!(
+                        "the object is not a certificate: {}",
+                        object.object_type()
+                    )))
+                }
+                _ => {
+                    let bytes = object
+                        .key_block()
+                        .and_then(|key_block| key_block.key_bytes())
+                        .map_err(|e| PyException::new_err(e.to_string()))?;
+                    match key_format_type {
+                        Some(key_format_type) if encode_to_pem => {
+                            der_to_pem(&bytes, key_format_type, object.object_type())
+                                .map_err(|e| PyException::new_err(e.to_string()))?
+                                .to_vec()
+                        }
+                        _ => bytes.to_vec(),
+                    }
+                }
+            };
+            Ok(bytes)
+        })
+    }
+
+    /// Locate the objects carrying all the tags.
+    ///
+    /// Args:
+    ///     - `tags` (List[str]): the tags of the objects
+    ///
+    /// Returns:
+    ///     Future[List[str]]: the unique identifiers of the objects
+    pub fn locate<'p>(&'p self, tags: Vec<String>, py: Python<'p>) -> PyResult<&PyAny> {
+        let request = locate_request(&tags)?;
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .locate(request)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(response
+                .unique_identifiers
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>())
+        })
+    }
+
+    /// Grant a user access to an object.
+    ///
+    /// Args:
+    ///     - `user_id` (str): the user to grant the access to
+    ///     - `operations` (List[str]): the operations to grant, e.g. ["get", "encrypt"]
+    ///     - `unique_identifier` (str): the unique identifier of the object
+    ///
+    /// Returns:
+    ///     Future[None]
+    pub fn grant_access<'p>(
+        &'p self,
+        user_id: String,
+        operations: Vec<String>,
+        unique_identifier: String,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let access = build_access(user_id, &operations, unique_identifier)?;
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            client
+                .grant_access(access)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(())
+        })
+    }
+
+    /// Revoke the access of a user to an object.
+    ///
+    /// Args:
+    ///     - `user_id` (str): the user to revoke the access of
+    ///     - `operations` (List[str]): the operations to revoke, e.g. ["get", "encrypt"]
+    ///     - `unique_identifier` (str): the unique identifier of the object
+    ///
+    /// Returns:
+    ///     Future[None]
+    pub fn revoke_access<'p>(
+        &'p self,
+        user_id: String,
+        operations: Vec<String>,
+        unique_identifier: String,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let access = build_access(user_id, &operations, unique_identifier)?;
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            client
+                .revoke_access(access)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(())
+        })
+    }
+
+    /// List the accesses granted on an object owned by the user.
+    ///
+    /// Args:
+    ///     - `unique_identifier` (str): the unique identifier of the object
+    ///
+    /// Returns:
+    ///     Future[List[KmsUserAccess]]
+    pub fn list_access<'p>(
+        &'p self,
+        unique_identifier: String,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .list_access(&unique_identifier)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(response.into_iter().map(KmsUserAccess).collect::<Vec<_>>())
+        })
+    }
+
+    /// List the objects owned by the user.
+    ///
+    /// Returns:
+    ///     Future[List[KmsOwnedObject]]
+    pub fn list_owned_objects<'p>(&'p self, py: Python<'p>) -> PyResult<&PyAny> {
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .list_owned_objects()
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(response.into_iter().map(KmsOwnedObject).collect::<Vec<_>>())
+        })
+    }
+
+    /// List the objects the user has been granted access to.
+    ///
+    /// Returns:
+    ///     Future[List[KmsAccessRightsObtained]]
+    pub fn list_access_rights_obtained<'p>(&'p self, py: Python<'p>) -> PyResult<&PyAny> {
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .list_access_rights_obtained()
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(response
+                .into_iter()
+                .map(KmsAccessRightsObtained)
+                .collect::<Vec<_>>())
+        })
+    }
+
+    /// Send a batch of operations in a single KMIP message.
+    /// The operations are processed in order and a failed operation
+    /// does not prevent the following ones from being processed.
+    ///
+    /// Args:
+    ///     - `requests` (List[KmsRequest]): the operations
+    ///
+    /// Returns:
+    ///     Future[List[KmsResponse]]: the results, in the order of the operations
+    pub fn message<'p>(
+        &'p self,
+        requests: Vec<PyRef<KmsRequest>>,
+        py: Python<'p>,
+    ) -> PyResult<&PyAny> {
+        let operations = requests
+            .iter()
+            .map(|request| (request.0)())
+            .collect::<PyResult<Vec<_>>>()?;
+        let request = Message {
+            header: MessageHeader {
+                protocol_version: ProtocolVersion {
+                    protocol_version_major: 1,
+                    protocol_version_minor: 0,
+                },
+                batch_count: operations.len() as u32,
+                ..Default::default()
+            },
+            items: operations.into_iter().map(MessageBatchItem::new).collect(),
+        };
+
+        let client = self.0.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let response = client
+                .message(request)
+                .await
+                .map_err(|e| PyException::new_err(e.to_string()))?;
+            Ok(response
+                .items
+                .into_iter()
+                .map(KmsResponse)
+                .collect::<Vec<_>>())
+        })
+    }
 }
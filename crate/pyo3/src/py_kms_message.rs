@@ -0,0 +1,384 @@
+use cosmian_kmip::{
+    crypto::{
+        cover_crypt::kmip_requests::build_destroy_key_request,
+        elliptic_curves::kmip_requests::create_ec_key_pair_request,
+        generic::kmip_requests::{
+            build_decryption_request, build_encryption_request, build_revoke_key_request,
+        },
+        rsa::kmip_requests::create_rsa_key_pair_request,
+        symmetric::symmetric_key_create_request,
+    },
+    kmip::{
+        kmip_messages::MessageResponseBatchItem,
+        kmip_operations::{EncryptResponse, Get, GetResponse, Locate, Operation},
+        kmip_types::{
+            Attributes, CryptographicAlgorithm, RecommendedCurve, ResultStatusEnumeration,
+            RevocationReason,
+        },
+    },
+};
+use pyo3::{exceptions::PyException, prelude::*, types::PyBytes};
+
+use crate::{
+    py_kms_client::ToUniqueIdentifier,
+    py_kms_object::{KmsEncryptResponse, KmsObject},
+};
+
+/// The KMIP cryptographic algorithm of a symmetric key
+pub(crate) fn symmetric_algorithm(algorithm: &str) -> PyResult<CryptographicAlgorithm> {
+    match algorithm {
+        "AES" => Ok(CryptographicAlgorithm::AES),
+        "ChaCha20" => Ok(CryptographicAlgorithm::ChaCha20),
+        _ => Err(PyException::new_err("invalid algorithm")),
+    }
+}
+
+/// The KMIP recommended curve of an elliptic curve name
+pub(crate) fn recommended_curve(curve: &str) -> PyResult<RecommendedCurve> {
+    match curve {
+        "nist-p192" => Ok(RecommendedCurve::P192),
+        "nist-p224" => Ok(RecommendedCurve::P224),
+        "nist-p256" => Ok(RecommendedCurve::P256),
+        "nist-p384" => Ok(RecommendedCurve::P384),
+        "nist-p521" => Ok(RecommendedCurve::P521),
+        "x25519" => Ok(RecommendedCurve::CURVE25519),
+        "ed25519" => Ok(RecommendedCurve::CURVEED25519),
+        "x448" => Ok(RecommendedCurve::CURVE448),
+        "ed448" => Ok(RecommendedCurve::CURVEED448),
+        _ => Err(PyException::new_err(format!("invalid curve: {curve}"))),
+    }
+}
+
+/// A Locate request for the objects carrying all the `tags`
+pub(crate) fn locate_request(tags: &[String]) -> PyResult<Locate> {
+    let mut attributes = Attributes::default();
+    attributes
+        .set_tags(tags)
+        .map_err(|e| PyException::new_err(e.to_string()))?;
+    Ok(Locate {
+        maximum_items: None,
+        offset_items: None,
+        storage_status_mask: None,
+        object_group_member: None,
+        attributes,
+    })
+}
+
+type OperationBuilder = Box<dyn Fn() -> PyResult<Operation> + Send>;
+
+/// A KMIP operation to send in a batch with `KmsClient.message`.
+///
+/// The request is built when the message is sent, so it can be sent several times.
+#[pyclass]
+pub struct KmsRequest(pub OperationBuilder);
+
+impl KmsRequest {
+    fn new(builder: impl Fn() -> PyResult<Operation> + Send + 'static) -> Self {
+        Self(Box::new(builder))
+    }
+}
+
+#[pymethods]
+impl KmsRequest {
+    /// Create a symmetric key.
+    ///
+    /// Args:
+    ///     - `key_len_in_bits` (int): the length of the key in bits
+    ///     - `algorithm` (str): "AES" or "ChaCha20"
+    ///     - `tags` (Optional[List[str]]): tags associated with the key
+    #[staticmethod]
+    #[pyo3(signature = (key_len_in_bits, algorithm = "AES", tags = None))]
+    pub fn create_symmetric_key(
+        key_len_in_bits: usize,
+        algorithm: &str,
+        tags: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let algorithm = symmetric_algorithm(algorithm)?;
+        let tags = tags.unwrap_or_default();
+        Ok(Self::new(move || {
+            symmetric_key_create_request(key_len_in_bits, algorithm, &tags)
+                .map(Operation::Create)
+                .map_err(|e| PyException::new_err(e.to_string()))
+        }))
+    }
+
+    /// Create an RSA key pair.
+    ///
+    /// Args:
+    ///     - `key_size_in_bits` (int): the size of the modulus in bits
+    ///     - `tags` (Optional[List[str]]): tags associated with the keys
+    #[staticmethod]
+    #[pyo3(signature = (key_size_in_bits = 4096, tags = None))]
+    pub fn create_rsa_key_pair(key_size_in_bits: usize, tags: Option<Vec<String>>) -> Self {
+        let tags = tags.unwrap_or_default();
+        Self::new(move || {
+            create_rsa_key_pair_request(&tags, key_size_in_bits)
+                .map(Operation::CreateKeyPair)
+                .map_err(|e| PyException::new_err(e.to_string()))
+        })
+    }
+
+    /// Create an elliptic curve key pair.
+    ///
+    /// Args:
+    ///     - `curve` (str): one of "nist-p192", "nist-p224", "nist-p256", "nist-p384",
+    /// "nist-p521", "x25519", "ed25519", "x448" and "ed448"
+    ///     - `tags` (Optional[List[str]]): tags associated with the keys
+    #[staticmethod]
+    #[pyo3(signature = (curve = "nist-p256", tags = None))]
+    pub fn create_ec_key_pair(curve: &str, tags: Option<Vec<String>>) -> PyResult<Self> {
+        let curve = recommended_curve(curve)?;
+        let tags = tags.unwrap_or_default();
+        Ok(Self::new(move || {
+            create_ec_key_pair_request(&tags, curve)
+                .map(Operation::CreateKeyPair)
+                .map_err(|e| PyException::new_err(e.to_string()))
+        }))
+    }
+
+    /// Get an object.
+    ///
+    /// Args:
+    ///     - `unique_identifier` (Union[str, List[str]]): object unique id or associated tags
+    #[staticmethod]
+    pub fn get(unique_identifier: ToUniqueIdentifier) -> Self {
+        Self::new(move || Ok(Operation::Get(Get::from(&unique_identifier.0))))
+    }
+
+    /// Locate the objects carrying all the tags.
+    ///
+    /// Args:
+    ///     - `tags` (List[str]): the tags of the objects
+    #[staticmethod]
+    pub fn locate(tags: Vec<String>) -> Self {
+        Self::new(move || locate_request(&tags).map(Operation::Locate))
+    }
+
+    /// Encrypt data with a symmetric key.
+    ///
+    /// Args:
+    ///     - `data` (bytes): the data to encrypt
+    ///     - `key_identifier` (Union[str, List[str]]): key unique id or associated tags
+    #[staticmethod]
+    pub fn encrypt(data: Vec<u8>, key_identifier: ToUniqueIdentifier) -> Self {
+        Self::new(move || {
+            build_encryption_request(&key_identifier.0, None, data.clone(), None, None, None)
+                .map(Operation::Encrypt)
+                .map_err(|e| PyException::new_err(e.to_string()))
+        })
+    }
+
+    /// Decrypt data with a symmetric key.
+    ///
+    /// Args:
+    ///     - `encrypted_data` (bytes): the ciphertext
+    ///     - `key_identifier` (Union[str, List[str]]): key unique id or associated tags
+    ///     - `iv_counter_nonce` (Optional[bytes]): the IV, counter or nonce
+    ///     - `authentication_encryption_tag` (Optional[bytes]): the authentication tag
+    #[staticmethod]
+    #[pyo3(signature = (
+        encrypted_data,
+        key_identifier,
+        iv_counter_nonce = None,
+        authentication_encryption_tag = None,
+    ))]
+    pub fn decrypt(
+        encrypted_data: Vec<u8>,
+        key_identifier: ToUniqueIdentifier,
+        iv_counter_nonce: Option<Vec<u8>>,
+        authentication_encryption_tag: Option<Vec<u8>>,
+    ) -> Self {
+        Self::new(move || {
+            Ok(Operation::Decrypt(build_decryption_request(
+                &key_identifier.0,
+                iv_counter_nonce.clone(),
+                encrypted_data.clone(),
+                authentication_encryption_tag.clone(),
+                None,
+                None,
+            )))
+        })
+    }
+
+    /// Revoke a key.
+    ///
+    /// Args:
+    ///     - `revocation_reason` (str): explanation of the revocation
+    ///     - `key_identifier` (Union[str, List[str]]): key unique id or associated tags
+    #[staticmethod]
+    pub fn revoke(revocation_reason: String, key_identifier: ToUniqueIdentifier) -> Self {
+        Self::new(move || {
+            build_revoke_key_request(
+                &key_identifier.0,
+                RevocationReason::TextString(revocation_reason.clone()),
+            )
+            .map(Operation::Revoke)
+            .map_err(|e| PyException::new_err(e.to_string()))
+        })
+    }
+
+    /// Destroy a key.
+    ///
+    /// Args:
+    ///     - `key_identifier` (Union[str, List[str]]): key unique id or associated tags
+    #[staticmethod]
+    pub fn destroy(key_identifier: ToUniqueIdentifier) -> Self {
+        Self::new(move || {
+            build_destroy_key_request(&key_identifier.0)
+                .map(Operation::Destroy)
+                .map_err(|e| PyException::new_err(e.to_string()))
+        })
+    }
+
+    /// Gets the KMIP operation of the request, e.g. `Get`.
+    ///
+    /// Returns:
+    ///     str
+    pub fn operation(&self) -> PyResult<String> {
+        Ok((self.0)()?.operation_enum().to_string())
+    }
+}
+
+/// The result of a KMIP operation sent in a batch with `KmsClient.message`
+#[pyclass]
+pub struct KmsResponse(pub MessageResponseBatchItem);
+
+#[pymethods]
+impl KmsResponse {
+    /// Gets the KMIP operation of the response, e.g. `Get`.
+    ///
+    /// Returns:
+    ///     Optional[str]
+    pub fn operation(&self) -> Option<String> {
+        self.0.operation.map(|operation| operation.to_string())
+    }
+
+    /// Whether the operation succeeded
+    ///
+    /// Returns:
+    ///     bool
+    pub fn is_success(&self) -> bool {
+        self.0.result_status == ResultStatusEnumeration::Success
+    }
+
+    /// Gets the status of the operation, e.g. `OperationFailed`.
+    ///
+    /// Returns:
+    ///     str
+    pub fn result_status(&self) -> String {
+        self.0.result_status.to_string()
+    }
+
+    /// Gets the reason of the failure of the operation
+    ///
+    /// Returns:
+    ///     Optional[str]
+    pub fn result_reason(&self) -> Option<String> {
+        self.0.result_reason.map(|reason| reason.to_string())
+    }
+
+    /// Gets the message explaining the failure of the operation
+    ///
+    /// Returns:
+    ///     Optional[str]
+    pub fn result_message(&self) -> Option<String> {
+        self.0.result_message.clone()
+    }
+
+    /// Gets the unique identifier of the object the operation applied to,
+    /// or created by the operation.
+    ///
+    /// Returns:
+    ///     Optional[str]
+    pub fn unique_identifier(&self) -> Option<String> {
+        match self.0.response_payload.as_ref()? {
+            Operation::CreateResponse(response) => Some(response.unique_identifier.to_string()),
+            Operation::ImportResponse(response) => Some(response.unique_identifier.to_string()),
+            Operation::CertifyResponse(response) => Some(response.unique_identifier.to_string()),
+            Operation::GetResponse(response) => Some(response.unique_identifier.to_string()),
+            Operation::EncryptResponse(response) => Some(response.unique_identifier.to_string()),
+            Operation::DecryptResponse(response) => Some(response.unique_identifier.to_string()),
+            Operation::RevokeResponse(response) => Some(response.unique_identifier.to_string()),
+            Operation::DestroyResponse(response) => Some(response.unique_identifier.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Gets the unique identifiers of a created key pair.
+    ///
+    /// Returns:
+    ///     Optional[Tuple[str, str]]: (Public key UID, Private key UID)
+    pub fn key_pair_unique_identifiers(&self) -> Option<(String, String)> {
+        match self.0.response_payload.as_ref()? {
+            Operation::CreateKeyPairResponse(response) => Some((
+                response.public_key_unique_identifier.to_string(),
+                response.private_key_unique_identifier.to_string(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Gets the unique identifiers of the located objects.
+    ///
+    /// Returns:
+    ///     Optional[List[str]]
+    pub fn located_unique_identifiers(&self) -> Option<Vec<String>> {
+        match self.0.response_payload.as_ref()? {
+            Operation::LocateResponse(response) => Some(
+                response
+                    .unique_identifiers
+                    .iter()
+                    .flatten()
+                    .map(ToString::to_string)
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Gets the object of a Get operation.
+    ///
+    /// Returns:
+    ///     Optional[KmsObject]
+    pub fn object(&self) -> Option<KmsObject> {
+        match self.0.response_payload.as_ref()? {
+            Operation::GetResponse(response) => Some(KmsObject(GetResponse {
+                object_type: response.object_type,
+                unique_identifier: response.unique_identifier.clone(),
+                object: response.object.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Gets the result of an Encrypt operation.
+    ///
+    /// Returns:
+    ///     Optional[KmsEncryptResponse]
+    pub fn encrypt_response(&self) -> Option<KmsEncryptResponse> {
+        match self.0.response_payload.as_ref()? {
+            Operation::EncryptResponse(response) => Some(KmsEncryptResponse(EncryptResponse {
+                unique_identifier: response.unique_identifier.clone(),
+                data: response.data.clone(),
+                iv_counter_nonce: response.iv_counter_nonce.clone(),
+                correlation_value: response.correlation_value.clone(),
+                authenticated_encryption_tag: response.authenticated_encryption_tag.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Gets the plaintext of a Decrypt operation.
+    ///
+    /// Returns:
+    ///     Optional[bytes]
+    pub fn decrypted_data(&self, py: Python) -> Option<Py<PyBytes>> {
+        match self.0.response_payload.as_ref()? {
+            Operation::DecryptResponse(response) => response
+                .data
+                .as_ref()
+                .map(|data| PyBytes::new(py, data).into()),
+            _ => None,
+        }
+    }
+}
@@ -1,4 +1,7 @@
 use cosmian_kmip::kmip::kmip_operations::{EncryptResponse, GetResponse};
+use cosmian_kms_client::access::{
+    AccessRightsObtainedResponse, ObjectOwnedResponse, UserAccessResponse,
+};
 use pyo3::{exceptions::PyException, prelude::*, types::PyBytes};
 
 #[pyclass]
@@ -14,6 +17,14 @@ impl KmsObject {
         self.0.object_type.to_string()
     }
 
+    /// Gets the unique identifier of the object.
+    ///
+    /// Returns:
+    ///     str
+    pub fn unique_identifier(&self) -> String {
+        self.0.unique_identifier.to_string()
+    }
+
     /// Retrieves key bytes
     ///
     /// Returns:
@@ -92,3 +103,110 @@ impl KmsEncryptResponse {
         Ok(PyBytes::new(py, &bytes).into())
     }
 }
+
+#[pyclass]
+pub struct KmsUserAccess(pub UserAccessResponse);
+
+#[pymethods]
+impl KmsUserAccess {
+    /// Retrieves the user granted the access
+    ///
+    /// Returns:
+    ///     str
+    pub fn user_id(&self) -> String {
+        self.0.user_id.clone()
+    }
+
+    /// Retrieves the operations the user is allowed to perform
+    ///
+    /// Returns:
+    ///     List[str]
+    pub fn operations(&self) -> Vec<String> {
+        self.0.operations.iter().map(ToString::to_string).collect()
+    }
+}
+
+#[pyclass]
+pub struct KmsOwnedObject(pub ObjectOwnedResponse);
+
+#[pymethods]
+impl KmsOwnedObject {
+    /// Retrieves the unique identifier of the object
+    ///
+    /// Returns:
+    ///     str
+    pub fn unique_identifier(&self) -> String {
+        self.0.object_id.to_string()
+    }
+
+    /// Retrieves the state of the object, e.g. `Active`
+    ///
+    /// Returns:
+    ///     str
+    pub fn state(&self) -> String {
+        self.0.state.to_string()
+    }
+
+    /// Retrieves the tags of the object
+    ///
+    /// Returns:
+    ///     List[str]
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags = self.0.attributes.get_tags().into_iter().collect::<Vec<_>>();
+        tags.sort();
+        tags
+    }
+
+    /// Whether the object is wrapped
+    ///
+    /// Returns:
+    ///     bool
+    pub fn is_wrapped(&self) -> bool {
+        self.0.is_wrapped
+    }
+}
+
+#[pyclass]
+pub struct KmsAccessRightsObtained(pub AccessRightsObtainedResponse);
+
+#[pymethods]
+impl KmsAccessRightsObtained {
+    /// Retrieves the unique identifier of the object
+    ///
+    /// Returns:
+    ///     str
+    pub fn unique_identifier(&self) -> String {
+        self.0.object_id.to_string()
+    }
+
+    /// Retrieves the owner of the object
+    ///
+    /// Returns:
+    ///     str
+    pub fn owner_id(&self) -> String {
+        self.0.owner_id.clone()
+    }
+
+    /// Retrieves the state of the object, e.g. `Active`
+    ///
+    /// Returns:
+    ///     str
+    pub fn state(&self) -> String {
+        self.0.state.to_string()
+    }
+
+    /// Retrieves the operations the user is allowed to perform
+    ///
+    /// Returns:
+    ///     List[str]
+    pub fn operations(&self) -> Vec<String> {
+        let mut operations = self
+            .0
+            .operations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        operations.sort();
+        operations
+    }
+}
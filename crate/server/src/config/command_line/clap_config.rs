@@ -16,8 +16,15 @@ impl Default for ClapConfig {
             workspace: WorkspaceConfig::default(),
             default_username: DEFAULT_USERNAME.to_owned(),
             force_default_username: false,
+            access_admins: None,
             google_cse_kacls_url: None,
+            google_cse_privileged_users: None,
+            google_cse_perimeters: None,
             ms_dke_service_url: None,
+            crl_distribution_url: None,
+            ocsp_responder_url: None,
+            acme_directory_url: None,
+            acme_issuer_certificate_id: None,
         }
     }
 }
@@ -47,6 +54,12 @@ pub struct ClapConfig {
     #[clap(long, env = "KMS_FORCE_DEFAULT_USERNAME")]
     pub force_default_username: bool,
 
+    /// The users allowed to manage the roles and the groups of the access rights
+    ///
+    /// When not set, only the default username is allowed
+    #[clap(long, env = "KMS_ACCESS_ADMINS", num_args = 1..)]
+    pub access_admins: Option<Vec<String>>,
+
     /// This setting enables the Google Workspace Client Side Encryption feature of this KMS server.
     ///
     /// It should contain the external URL of this server as configured in Google Workspace client side encryption settings
@@ -55,6 +68,19 @@ pub struct ClapConfig {
     #[clap(long, env = "KMS_GOOGLE_CSE_KACLS_URL")]
     pub google_cse_kacls_url: Option<String>,
 
+    /// The users allowed to call the privileged Google CSE endpoints:
+    /// `privilegedunwrap`, `privilegedwrap` and `privilegedprivatekeydecrypt`
+    ///
+    /// When not set, the privileged endpoints are refused to all users
+    #[clap(long, env = "KMS_GOOGLE_CSE_PRIVILEGED_USERS", num_args = 1..)]
+    pub google_cse_privileged_users: Option<Vec<String>>,
+
+    /// The Google CSE perimeters served by this KMS server
+    ///
+    /// When not set, only the resources outside of any perimeter are served
+    #[clap(long, env = "KMS_GOOGLE_CSE_PERIMETERS", num_args = 1..)]
+    pub google_cse_perimeters: Option<Vec<String>>,
+
     /// This setting enables the Microsoft Double Key Encryption service feature of this server.
     ///
     /// It should contain the external URL of this server as configured in Azure App Registrations
@@ -63,6 +89,44 @@ pub struct ClapConfig {
     /// The URL should be something like <https://cse.my_domain.com/ms_dke>
     #[clap(verbatim_doc_comment, long, env = "KMS_MS_DKE_SERVICE_URL")]
     pub ms_dke_service_url: Option<String>,
+
+    /// This setting enables the CRL distribution point of this server.
+    ///
+    /// It should contain the external URL of the `/crl` endpoint of this server.
+    /// The CRL of an issuer certificate held in the KMS is then served at `<url>/<issuer certificate id>`
+    /// and this distribution point is added to the certificates issued by `Certify`.
+    ///
+    /// The URL should be something like <https://kms.my_domain.com/crl>
+    #[clap(verbatim_doc_comment, long, env = "KMS_CRL_DISTRIBUTION_URL")]
+    pub crl_distribution_url: Option<String>,
+
+    /// This setting enables the OCSP responder of this server.
+    ///
+    /// It should contain the external URL of the `/ocsp` endpoint of this server.
+    /// The status of the certificates issued by an issuer certificate held in the KMS
+    /// is then served at `<url>/<issuer certificate id>`
+    /// and this responder is added to the certificates issued by `Certify`.
+    ///
+    /// The URL should be something like <https://kms.my_domain.com/ocsp>
+    #[clap(verbatim_doc_comment, long, env = "KMS_OCSP_RESPONDER_URL")]
+    pub ocsp_responder_url: Option<String>,
+
+    /// This setting enables the ACME (RFC 8555) server of this server.
+    ///
+    /// It should contain the external URL of the `/acme` endpoint of this server.
+    /// The ACME directory is then served at `<url>/directory`
+    /// and ACME clients such as certbot or cert-manager can obtain certificates
+    /// signed by the issuer certificate set with `acme_issuer_certificate_id`.
+    ///
+    /// The URL should be something like <https://kms.my_domain.com/acme>
+    #[clap(verbatim_doc_comment, long, env = "KMS_ACME_DIRECTORY_URL")]
+    pub acme_directory_url: Option<String>,
+
+    /// The unique identifier of the issuer certificate held in the KMS
+    /// which signs the certificates ordered through the ACME server.
+    /// The issuer private key must also be held in the KMS.
+    #[clap(verbatim_doc_comment, long, env = "KMS_ACME_ISSUER_CERTIFICATE_ID")]
+    pub acme_issuer_certificate_id: Option<String>,
 }
 
 impl fmt::Debug for ClapConfig {
@@ -78,14 +142,30 @@ impl fmt::Debug for ClapConfig {
         let x = x.field("workspace", &self.workspace);
         let x = x.field("default username", &self.default_username);
         let x = x.field("force default username", &self.force_default_username);
+        let x = x.field("access rights admins", &self.access_admins);
         let x = x.field(
             "Google Workspace CSE, KACLS Url",
             &self.google_cse_kacls_url,
         );
+        let x = x.field(
+            "Google Workspace CSE, privileged users",
+            &self.google_cse_privileged_users,
+        );
+        let x = x.field(
+            "Google Workspace CSE, perimeters",
+            &self.google_cse_perimeters,
+        );
         let x = x.field(
             "Microsoft Double Key Encryption URL",
             &self.ms_dke_service_url,
         );
+        let x = x.field("CRL distribution URL", &self.crl_distribution_url);
+        let x = x.field("OCSP responder URL", &self.ocsp_responder_url);
+        let x = x.field("ACME directory URL", &self.acme_directory_url);
+        let x = x.field(
+            "ACME issuer certificate id",
+            &self.acme_issuer_certificate_id,
+        );
         x.finish()
     }
 }
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 const DEFAULT_PORT: u16 = 9998;
 const DEFAULT_HOSTNAME: &str = "0.0.0.0";
+const DEFAULT_SOCKET_SERVER_PORT: u16 = 5696;
 
 #[derive(Args, Clone, Deserialize, Serialize)]
 #[serde(default)]
@@ -30,6 +31,16 @@ pub struct HttpConfig {
     /// The server must run in TLS mode for this to be used.
     #[clap(long, env = "KMS_AUTHORITY_CERT_FILE")]
     pub authority_cert_file: Option<PathBuf>,
+
+    /// Start the KMIP socket server (binary TTLV over TLS) alongside the HTTP server.
+    /// The server must run in TLS mode and an authority certificate file must be provided
+    /// since socket clients are authenticated using their TLS client certificate.
+    #[clap(long, env = "KMS_SOCKET_SERVER_START")]
+    pub socket_server_start: bool,
+
+    /// The KMIP socket server port
+    #[clap(long, env = "KMS_SOCKET_SERVER_PORT", default_value_t = DEFAULT_SOCKET_SERVER_PORT)]
+    pub socket_server_port: u16,
 }
 
 impl Display for HttpConfig {
@@ -44,7 +55,11 @@ impl Display for HttpConfig {
                 f,
                 "authority cert file: {:?}",
                 self.authority_cert_file.as_ref()
-            )
+            )?;
+            if self.socket_server_start {
+                write!(f, ", KMIP socket server port: {}", self.socket_server_port)?;
+            }
+            Ok(())
         } else {
             write!(f, "http://{}:{}", self.hostname, self.port)
         }
@@ -65,6 +80,8 @@ impl Default for HttpConfig {
             https_p12_file: None,
             https_p12_password: None,
             authority_cert_file: None,
+            socket_server_start: false,
+            socket_server_port: DEFAULT_SOCKET_SERVER_PORT,
         }
     }
 }
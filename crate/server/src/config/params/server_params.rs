@@ -23,6 +23,9 @@ pub struct ServerParams {
     /// but always use the default username instead of the one provided by the authentication method
     pub force_default_username: bool,
 
+    /// The users allowed to manage the roles and the groups of the access rights
+    pub access_admins: Vec<String>,
+
     /// The DB parameters may be supplied on the command line
     pub db_params: Option<DbParams>,
 
@@ -35,6 +38,9 @@ pub struct ServerParams {
 
     pub http_params: HttpParams,
 
+    /// The port of the KMIP socket server (binary TTLV over TLS), if started
+    pub socket_server_port: Option<u16>,
+
     /// The certificate used to verify the client TLS certificates
     /// used for authentication
     pub client_cert: Option<X509>,
@@ -46,6 +52,13 @@ pub struct ServerParams {
     /// the URL should be something like <https://cse.my_domain.com/google_cse>
     pub google_cse_kacls_url: Option<String>,
 
+    /// The users allowed to call the privileged Google CSE endpoints
+    pub google_cse_privileged_users: Vec<String>,
+
+    /// The Google CSE perimeters served by this KMS server;
+    /// the resources outside of any perimeter are always served
+    pub google_cse_perimeters: Vec<String>,
+
     /// This setting enables the Microsoft Double Key Encryption service feature of this server.
     ///
     /// It should contain the external URL of this server as configured in
@@ -54,6 +67,29 @@ pub struct ServerParams {
     ///
     /// The URL should be something like <https://cse.my_domain.com/ms_dke>
     pub ms_dke_service_url: Option<String>,
+
+    /// This setting enables the CRL distribution point of this server.
+    ///
+    /// The CRL of an issuer certificate held in the KMS is served at `<url>/<issuer certificate id>`.
+    /// The URL should be something like <https://kms.my_domain.com/crl>
+    pub crl_distribution_url: Option<String>,
+
+    /// This setting enables the OCSP responder of this server.
+    ///
+    /// The status of the certificates issued by an issuer certificate held in the KMS
+    /// is served at `<url>/<issuer certificate id>`.
+    /// The URL should be something like <https://kms.my_domain.com/ocsp>
+    pub ocsp_responder_url: Option<String>,
+
+    /// This setting enables the ACME (RFC 8555) server of this server.
+    ///
+    /// The ACME directory is served at `<url>/directory`.
+    /// The URL should be something like <https://kms.my_domain.com/acme>
+    pub acme_directory_url: Option<String>,
+
+    /// The unique identifier of the issuer certificate which signs
+    /// the certificates ordered through the ACME server
+    pub acme_issuer_certificate_id: Option<String>,
 }
 
 impl ServerParams {
@@ -76,6 +112,27 @@ impl ServerParams {
             })
             .transpose()?;
 
+        // The socket server authenticates clients using their TLS certificate
+        let socket_server_port = if conf.http.socket_server_start {
+            if verify_cert.is_none() {
+                kms_bail!(
+                    "The KMIP socket server requires the server to run in HTTPS mode with an \
+                     authority certificate file to authenticate the clients"
+                )
+            }
+            Some(conf.http.socket_server_port)
+        } else {
+            None
+        };
+
+        // The ACME server issues its certificates from a single issuer
+        if conf.acme_directory_url.is_some() && conf.acme_issuer_certificate_id.is_none() {
+            kms_bail!(
+                "The ACME server requires the unique identifier of the issuer certificate held in \
+                 the KMS: set `acme_issuer_certificate_id`"
+            )
+        }
+
         Ok(Self {
             identity_provider_configurations: conf.auth.extract_idp_configs()?,
             db_params: conf.db.init(&conf.workspace.init()?)?,
@@ -83,11 +140,21 @@ impl ServerParams {
             hostname: conf.http.hostname,
             port: conf.http.port,
             http_params,
+            socket_server_port,
+            access_admins: conf
+                .access_admins
+                .unwrap_or_else(|| vec![conf.default_username.clone()]),
             default_username: conf.default_username,
             force_default_username: conf.force_default_username,
             client_cert: verify_cert,
             google_cse_kacls_url: conf.google_cse_kacls_url,
+            google_cse_privileged_users: conf.google_cse_privileged_users.unwrap_or_default(),
+            google_cse_perimeters: conf.google_cse_perimeters.unwrap_or_default(),
             ms_dke_service_url: conf.ms_dke_service_url,
+            crl_distribution_url: conf.crl_distribution_url,
+            ocsp_responder_url: conf.ocsp_responder_url,
+            acme_directory_url: conf.acme_directory_url,
+            acme_issuer_certificate_id: conf.acme_issuer_certificate_id,
         })
     }
 
@@ -139,14 +206,33 @@ impl fmt::Debug for ServerParams {
         };
         let x = x
             .field("default_username", &self.default_username)
-            .field("force_default_username", &self.force_default_username);
+            .field("force_default_username", &self.force_default_username)
+            .field("access_admins", &self.access_admins);
         let x = x.field("http_params", &self.http_params);
+        let x = if let Some(socket_server_port) = &self.socket_server_port {
+            x.field("socket_server_port", &socket_server_port)
+        } else {
+            x
+        };
         let x = if let Some(google_cse_kacls_url) = &self.google_cse_kacls_url {
             x.field("google_cse_kacls_url", &google_cse_kacls_url)
         } else {
             x
         };
+        let x = x
+            .field(
+                "google_cse_privileged_users",
+                &self.google_cse_privileged_users,
+            )
+            .field("google_cse_perimeters", &self.google_cse_perimeters);
         let x = x.field("ms_dke_service_url", &self.ms_dke_service_url);
+        let x = x.field("crl_distribution_url", &self.crl_distribution_url);
+        let x = x.field("ocsp_responder_url", &self.ocsp_responder_url);
+        let x = x.field("acme_directory_url", &self.acme_directory_url);
+        let x = x.field(
+            "acme_issuer_certificate_id",
+            &self.acme_issuer_certificate_id,
+        );
         x.finish()
     }
 }
@@ -160,14 +246,22 @@ impl Clone for ServerParams {
             identity_provider_configurations: self.identity_provider_configurations.clone(),
             default_username: self.default_username.clone(),
             force_default_username: self.force_default_username,
+            access_admins: self.access_admins.clone(),
             db_params: None,
             clear_db_on_start: self.clear_db_on_start,
             hostname: self.hostname.clone(),
             port: self.port,
             http_params: HttpParams::Http,
+            socket_server_port: self.socket_server_port,
             client_cert: self.client_cert.clone(),
             google_cse_kacls_url: self.google_cse_kacls_url.clone(),
+            google_cse_privileged_users: self.google_cse_privileged_users.clone(),
+            google_cse_perimeters: self.google_cse_perimeters.clone(),
             ms_dke_service_url: self.ms_dke_service_url.clone(),
+            crl_distribution_url: self.crl_distribution_url.clone(),
+            ocsp_responder_url: self.ocsp_responder_url.clone(),
+            acme_directory_url: self.acme_directory_url.clone(),
+            acme_issuer_certificate_id: self.acme_issuer_certificate_id.clone(),
         }
     }
 }
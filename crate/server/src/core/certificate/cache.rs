@@ -0,0 +1,81 @@
+//! Caches of the signed revocation data of the issuers, which are served until their next update.
+
+use std::{collections::HashMap, hash::Hash, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use openssl::x509::X509;
+
+use crate::{error::KmsError, result::KResult};
+
+/// Maximum number of entries kept by a cache
+const MAX_CACHED_ENTRIES: usize = 1024;
+
+/// Values kept until their expiry date
+///
+/// The lock is never held across an await point.
+pub(crate) struct ExpiringCache<K, V>(Mutex<HashMap<K, (DateTime<Utc>, V)>>);
+
+impl<K, V> Default for ExpiringCache<K, V> {
+    fn default() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> ExpiringCache<K, V> {
+    fn lock(&self) -> KResult<std::sync::MutexGuard<'_, HashMap<K, (DateTime<Utc>, V)>>> {
+        self.0
+            .lock()
+            .map_err(|e| KmsError::ServerError(format!("revocation cache lock poisoned: {e}")))
+    }
+
+    /// The cached value, unless it has expired
+    pub(crate) fn get(&self, key: &K) -> KResult<Option<V>> {
+        let now = Utc::now();
+        Ok(self
+            .lock()?
+            .get(key)
+            .filter(|(expiry, _)| now < *expiry)
+            .map(|(_, value)| value.clone()))
+    }
+
+    /// Cache the value until the given expiry date
+    pub(crate) fn insert(&self, key: K, expiry: DateTime<Utc>, value: V) -> KResult<()> {
+        let now = Utc::now();
+        let mut entries = self.lock()?;
+        entries.retain(|_, (expiry, _)| now < *expiry);
+        if entries.len() >= MAX_CACHED_ENTRIES && !entries.contains_key(&key) {
+            // the cache is full of live entries: start afresh rather than grow
+            entries.clear();
+        }
+        entries.insert(key, (expiry, value));
+        Ok(())
+    }
+
+    /// Discard all the cached values
+    pub(crate) fn clear(&self) -> KResult<()> {
+        self.lock()?.clear();
+        Ok(())
+    }
+}
+
+/// The revocation data of the issuers, indexed by database group and issuer certificate
+#[derive(Default)]
+pub(crate) struct RevocationCache {
+    /// The signed CRLs
+    pub(crate) crls: ExpiringCache<(Option<u128>, String), Vec<u8>>,
+    /// The signed OCSP responses without nonce, also indexed by the requested `CertID`s
+    pub(crate) ocsp_responses: ExpiringCache<(Option<u128>, String, Vec<u8>), Vec<u8>>,
+    /// The delegated OCSP responder certificates, if any
+    pub(crate) ocsp_responders: ExpiringCache<(Option<u128>, String), Option<(String, X509)>>,
+}
+
+impl RevocationCache {
+    /// Discard the cached revocation data, once a certificate or an issuer has changed:
+    /// revoked, destroyed, archived, recovered, activated, replaced,
+    /// restored by an undone batch or with edited attributes
+    pub(crate) fn clear(&self) -> KResult<()> {
+        self.crls.clear()?;
+        self.ocsp_responses.clear()?;
+        self.ocsp_responders.clear()
+    }
+}
@@ -0,0 +1,318 @@
+//! Certificate Revocation Lists (RFC 5280) of the issuers held in the KMS.
+//!
+//! The revoked certificates of an issuer are those linked to its certificate
+//! by the `CertificateLink` set by `Certify`, which are no longer active.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use cosmian_kmip::{
+    kmip::kmip_types::{
+        Attributes, RevocationReason, RevocationReasonEnumeration, StateEnumeration,
+    },
+    openssl::{kmip_certificate_to_openssl, kmip_private_key_to_openssl},
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use openssl::{
+    pkey::{PKey, Private},
+    x509::X509,
+};
+
+use super::{der, find_issued_certificates, retrieve_matching_private_key_and_certificate};
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    error::KmsError,
+    result::KResult,
+};
+
+const OID_CRL_NUMBER: &str = "2.5.29.20";
+const OID_REASON_CODE: &str = "2.5.29.21";
+const OID_INVALIDITY_DATE: &str = "2.5.29.24";
+const OID_AUTHORITY_KEY_IDENTIFIER: &str = "2.5.29.35";
+
+/// Number of days before the next CRL is issued
+const CRL_VALIDITY_DAYS: i64 = 1;
+
+/// A revoked certificate, as listed in the CRL of its issuer
+pub(crate) struct RevokedCertificate {
+    /// The unsigned big endian serial number
+    pub serial_number: Vec<u8>,
+    pub revocation_date: DateTime<Utc>,
+    pub reason: Option<RevocationReasonEnumeration>,
+    pub invalidity_date: Option<DateTime<Utc>>,
+}
+
+impl RevokedCertificate {
+    pub(crate) fn new(
+        serial_number: Vec<u8>,
+        state: StateEnumeration,
+        attributes: &Attributes,
+    ) -> Self {
+        let millis_to_date = |millis: u64| Utc.timestamp_millis_opt(millis as i64).single();
+        let reason = match &attributes.revocation_reason {
+            Some(RevocationReason::Enumeration(reason)) => Some(*reason),
+            Some(RevocationReason::TextString(_)) => None,
+            // revoked before revocation reasons were kept
+            None => match state {
+                StateEnumeration::Compromised | StateEnumeration::Destroyed_Compromised => {
+                    Some(RevocationReasonEnumeration::KeyCompromise)
+                }
+                _ => None,
+            },
+        };
+        Self {
+            serial_number,
+            revocation_date: attributes
+                .deactivation_date
+                .and_then(millis_to_date)
+                .unwrap_or_else(Utc::now),
+            reason,
+            invalidity_date: attributes
+                .compromise_occurrence_date
+                .and_then(millis_to_date),
+        }
+    }
+}
+
+/// Build the DER encoded CRL of the certificates issued by the given issuer certificate.
+///
+/// The CRL is signed by the private key of the issuer, which must be held in the KMS.
+/// The user must be granted `Certify` on the issuer; the issued certificates are then
+/// resolved with the permissions of the issuer owner.
+/// A signed CRL is served from the cache until its next update.
+pub(crate) async fn build_crl(
+    issuer_certificate_id: &str,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Vec<u8>> {
+    let (issuer_private_key, issuer_certificate) = retrieve_matching_private_key_and_certificate(
+        None,
+        Some(issuer_certificate_id.to_owned()),
+        kms,
+        user,
+        params,
+    )
+    .await?;
+
+    let cache_key = (params.map(|p| p.group_id), issuer_certificate.id.clone());
+    if let Some(crl) = kms.revocation_cache.crls.get(&cache_key)? {
+        return Ok(crl)
+    }
+
+    let issuer_pkey = kmip_private_key_to_openssl(&issuer_private_key.object)?;
+    let issuer_x509 = kmip_certificate_to_openssl(&issuer_certificate.object)?;
+    let revoked = revoked_certificates(
+        &issuer_certificate.id,
+        kms,
+        &issuer_certificate.owner,
+        params,
+    )
+    .await?;
+    let this_update = Utc::now();
+    let crl = encode_crl(&issuer_x509, &issuer_pkey, &revoked, this_update)?;
+    kms.revocation_cache.crls.insert(
+        cache_key,
+        this_update + Duration::days(CRL_VALIDITY_DAYS),
+        crl.clone(),
+    )?;
+    Ok(crl)
+}
+
+/// List the certificates issued by the given issuer certificate which are no longer active,
+/// as seen by the given user
+pub(crate) async fn revoked_certificates(
+    issuer_certificate_id: &str,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Vec<RevokedCertificate>> {
+    let mut revoked = Vec::new();
+    for (uid, state, attributes) in
+        find_issued_certificates(issuer_certificate_id, None, kms, user, params).await?
+    {
+        if state == StateEnumeration::Active || state == StateEnumeration::PreActive {
+            continue
+        }
+        let owm = kms
+            .db
+            .retrieve(&uid, user, ObjectOperationType::Get, params)
+            .await?
+            .remove(&uid)
+            .ok_or_else(|| KmsError::ItemNotFound(uid.clone()))?;
+        let x509 = kmip_certificate_to_openssl(&owm.object)?;
+        revoked.push(RevokedCertificate::new(
+            x509.serial_number().to_bn()?.to_vec(),
+            state,
+            &attributes,
+        ));
+    }
+    Ok(revoked)
+}
+
+/// Encode and sign a version 2 CRL
+pub(crate) fn encode_crl(
+    issuer_x509: &X509,
+    issuer_pkey: &PKey<Private>,
+    revoked: &[RevokedCertificate],
+    this_update: DateTime<Utc>,
+) -> KResult<Vec<u8>> {
+    let signature_algorithm = der::signature_algorithm(issuer_pkey)?;
+
+    let mut tbs_items = vec![
+        // v2
+        der::integer(1),
+        signature_algorithm.clone(),
+        issuer_x509.subject_name().to_der()?,
+        der::time(&this_update),
+        der::time(&(this_update + Duration::days(CRL_VALIDITY_DAYS))),
+    ];
+
+    if !revoked.is_empty() {
+        let entries = revoked
+            .iter()
+            .map(encode_revoked_certificate)
+            .collect::<KResult<Vec<_>>>()?;
+        tbs_items.push(der::sequence(&entries));
+    }
+
+    // CRL extensions: the CRL number must increase with each issued CRL
+    let mut crl_extensions = vec![der::extension(
+        OID_CRL_NUMBER,
+        false,
+        &der::integer(this_update.timestamp() as u64),
+    )?];
+    if let Some(ski) = issuer_x509.subject_key_id() {
+        // keyIdentifier [0] IMPLICIT
+        crl_extensions.push(der::extension(
+            OID_AUTHORITY_KEY_IDENTIFIER,
+            false,
+            &der::sequence(&[der::tlv(0x80, ski.as_slice())]),
+        )?);
+    }
+    tbs_items.push(der::explicit(0, &der::sequence(&crl_extensions)));
+
+    let tbs_cert_list = der::sequence(&tbs_items);
+    let signature = der::sign(issuer_pkey, &tbs_cert_list)?;
+    Ok(der::sequence(&[
+        tbs_cert_list,
+        signature_algorithm,
+        der::bit_string(&signature),
+    ]))
+}
+
+fn encode_revoked_certificate(revoked: &RevokedCertificate) -> KResult<Vec<u8>> {
+    let mut items = vec![
+        der::unsigned_integer(&revoked.serial_number),
+        der::time(&revoked.revocation_date),
+    ];
+    let mut extensions = vec![];
+    if let Some(reason_code) = revoked.reason.and_then(crl_reason_code) {
+        extensions.push(der::extension(
+            OID_REASON_CODE,
+            false,
+            &der::enumerated(reason_code),
+        )?);
+    }
+    if let Some(invalidity_date) = &revoked.invalidity_date {
+        extensions.push(der::extension(
+            OID_INVALIDITY_DATE,
+            false,
+            &der::generalized_time(invalidity_date),
+        )?);
+    }
+    if !extensions.is_empty() {
+        items.push(der::sequence(&extensions));
+    }
+    Ok(der::sequence(&items))
+}
+
+/// The RFC 5280 `CRLReason` of a KMIP revocation reason.
+/// `unspecified` should not be used: the reason code is then omitted.
+pub(crate) fn crl_reason_code(reason: RevocationReasonEnumeration) -> Option<u8> {
+    match reason {
+        RevocationReasonEnumeration::Unspecified => None,
+        RevocationReasonEnumeration::KeyCompromise => Some(1),
+        RevocationReasonEnumeration::CACompromise => Some(2),
+        RevocationReasonEnumeration::AffiliationChanged => Some(3),
+        RevocationReasonEnumeration::Superseded => Some(4),
+        RevocationReasonEnumeration::CessationOfOperation => Some(5),
+        RevocationReasonEnumeration::PrivilegeWithdrawn => Some(9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use cosmian_kmip::kmip::kmip_types::RevocationReasonEnumeration;
+    use openssl::{
+        asn1::{Asn1Integer, Asn1Time},
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        x509::{X509Crl, X509NameBuilder, X509},
+    };
+
+    use super::{encode_crl, RevokedCertificate};
+    use crate::result::KResult;
+
+    fn self_signed(pkey: &PKey<Private>) -> KResult<X509> {
+        let mut name = X509NameBuilder::new()?;
+        name.append_entry_by_nid(Nid::COMMONNAME, "Test CA")?;
+        let name = name.build();
+        let mut builder = X509::builder()?;
+        builder.set_version(2)?;
+        builder.set_serial_number(Asn1Integer::from_bn(&BigNum::from_u32(1)?)?.as_ref())?;
+        builder.set_subject_name(&name)?;
+        builder.set_issuer_name(&name)?;
+        builder.set_pubkey(pkey)?;
+        builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+        builder.set_not_after(Asn1Time::days_from_now(365)?.as_ref())?;
+        builder.sign(pkey, MessageDigest::sha256())?;
+        Ok(builder.build())
+    }
+
+    #[test]
+    fn test_crl() -> KResult<()> {
+        let pkey = PKey::from_ec_key(EcKey::generate(
+            EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?.as_ref(),
+        )?)?;
+        let issuer = self_signed(&pkey)?;
+
+        let revoked = vec![
+            RevokedCertificate {
+                serial_number: vec![0x01, 0x02, 0x03],
+                revocation_date: Utc::now(),
+                reason: Some(RevocationReasonEnumeration::KeyCompromise),
+                invalidity_date: Some(Utc::now()),
+            },
+            RevokedCertificate {
+                serial_number: vec![0xFF, 0x00],
+                revocation_date: Utc::now(),
+                reason: None,
+                invalidity_date: None,
+            },
+        ];
+        let der = encode_crl(&issuer, &pkey, &revoked, Utc::now())?;
+
+        let crl = X509Crl::from_der(&der)?;
+        assert!(crl.verify(&pkey)?);
+        assert_eq!(crl.issuer_name().to_der()?, issuer.subject_name().to_der()?);
+        let entries = crl.get_revoked().expect("revoked certificates");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries.get(0).unwrap().serial_number().to_bn()?.to_vec(),
+            vec![0x01, 0x02, 0x03]
+        );
+        assert_eq!(
+            entries.get(1).unwrap().serial_number().to_bn()?.to_vec(),
+            vec![0xFF, 0x00]
+        );
+
+        // an empty CRL
+        let crl = X509Crl::from_der(&encode_crl(&issuer, &pkey, &[], Utc::now())?)?;
+        assert!(crl.verify(&pkey)?);
+        assert!(crl.get_revoked().is_none());
+        Ok(())
+    }
+}
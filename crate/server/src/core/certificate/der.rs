@@ -0,0 +1,252 @@
+//! Minimal DER encoding and decoding of the ASN.1 structures that openssl cannot
+//! build or parse, such as Certificate Revocation Lists and OCSP messages.
+
+use chrono::{DateTime, Datelike, Utc};
+use openssl::{
+    hash::MessageDigest,
+    pkey::{Id, PKey, Private},
+    sign::Signer,
+};
+
+use crate::{error::KmsError, kms_bail, result::KResult};
+
+pub(crate) const BOOLEAN: u8 = 0x01;
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const NULL: u8 = 0x05;
+pub(crate) const OBJECT_IDENTIFIER: u8 = 0x06;
+pub(crate) const ENUMERATED: u8 = 0x0A;
+pub(crate) const UTC_TIME: u8 = 0x17;
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const SEQUENCE: u8 = 0x30;
+
+/// A decoded Tag-Length-Value
+pub(crate) struct Tlv<'a> {
+    pub tag: u8,
+    pub content: &'a [u8],
+    /// The whole encoding: tag, length and content
+    pub encoded: &'a [u8],
+}
+
+/// Decode the first Tag-Length-Value of the input and return it with the remaining bytes.
+/// Only single byte tags are supported.
+pub(crate) fn parse(input: &[u8]) -> KResult<(Tlv<'_>, &[u8])> {
+    let malformed = || KmsError::InvalidRequest("malformed DER encoding".to_owned());
+    let (&tag, rest) = input.split_first().ok_or_else(malformed)?;
+    let (&first_length_byte, rest) = rest.split_first().ok_or_else(malformed)?;
+    let (length, rest) = if first_length_byte & 0x80 == 0 {
+        (first_length_byte as usize, rest)
+    } else {
+        let length_size = (first_length_byte & 0x7F) as usize;
+        if length_size == 0
+            || length_size > std::mem::size_of::<usize>()
+            || rest.len() < length_size
+        {
+            return Err(malformed())
+        }
+        let (length_bytes, rest) = rest.split_at(length_size);
+        (
+            length_bytes
+                .iter()
+                .fold(0_usize, |length, b| (length << 8) | *b as usize),
+            rest,
+        )
+    };
+    if rest.len() < length {
+        return Err(malformed())
+    }
+    let (content, remaining) = rest.split_at(length);
+    let header_length = input.len() - rest.len();
+    Ok((
+        Tlv {
+            tag,
+            content,
+            encoded: &input[..header_length + length],
+        },
+        remaining,
+    ))
+}
+
+/// Decode all the Tag-Length-Values of the input, e.g. the content of a SEQUENCE
+pub(crate) fn parse_all(mut input: &[u8]) -> KResult<Vec<Tlv<'_>>> {
+    let mut items = Vec::new();
+    while !input.is_empty() {
+        let (item, rest) = parse(input)?;
+        items.push(item);
+        input = rest;
+    }
+    Ok(items)
+}
+
+/// Decode a single Tag-Length-Value, checking its tag
+pub(crate) fn parse_expected(input: &[u8], tag: u8) -> KResult<Tlv<'_>> {
+    let (item, _) = parse(input)?;
+    if item.tag != tag {
+        kms_bail!(KmsError::InvalidRequest(format!(
+            "unexpected DER tag: {:#04x}, expected: {tag:#04x}",
+            item.tag
+        )))
+    }
+    Ok(item)
+}
+
+/// Encode a Tag-Length-Value
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut der = vec![tag];
+    let length = content.len();
+    if length < 0x80 {
+        der.push(length as u8);
+    } else {
+        let length_bytes = length.to_be_bytes();
+        let first = length_bytes
+            .iter()
+            .position(|b| *b != 0)
+            .unwrap_or(length_bytes.len() - 1);
+        der.push(0x80 | (length_bytes.len() - first) as u8);
+        der.extend_from_slice(&length_bytes[first..]);
+    }
+    der.extend_from_slice(content);
+    der
+}
+
+/// Encode a SEQUENCE of already encoded items
+pub(crate) fn sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    tlv(SEQUENCE, &items.concat())
+}
+
+/// Encode an explicitly tagged, context specific, constructed value
+pub(crate) fn explicit(tag_number: u8, content: &[u8]) -> Vec<u8> {
+    tlv(0xA0 | tag_number, content)
+}
+
+/// Encode a positive INTEGER from its unsigned big endian representation
+pub(crate) fn unsigned_integer(big_endian: &[u8]) -> Vec<u8> {
+    let first = big_endian
+        .iter()
+        .position(|b| *b != 0)
+        .unwrap_or(big_endian.len());
+    let mut content = big_endian[first..].to_vec();
+    if content.first().map_or(true, |b| *b & 0x80 != 0) {
+        content.insert(0, 0);
+    }
+    tlv(INTEGER, &content)
+}
+
+/// The unsigned big endian representation of a positive INTEGER content,
+/// without leading zeros
+pub(crate) fn unsigned_integer_value(content: &[u8]) -> &[u8] {
+    let first = content
+        .iter()
+        .position(|b| *b != 0)
+        .unwrap_or(content.len());
+    &content[first..]
+}
+
+pub(crate) fn integer(value: u64) -> Vec<u8> {
+    unsigned_integer(&value.to_be_bytes())
+}
+
+pub(crate) fn enumerated(value: u8) -> Vec<u8> {
+    let content = if value & 0x80 == 0 {
+        vec![value]
+    } else {
+        vec![0, value]
+    };
+    tlv(ENUMERATED, &content)
+}
+
+pub(crate) fn boolean(value: bool) -> Vec<u8> {
+    tlv(BOOLEAN, &[if value { 0xFF } else { 0x00 }])
+}
+
+pub(crate) fn null() -> Vec<u8> {
+    tlv(NULL, &[])
+}
+
+pub(crate) fn octet_string(content: &[u8]) -> Vec<u8> {
+    tlv(OCTET_STRING, content)
+}
+
+/// Encode a BIT STRING with no unused bits
+pub(crate) fn bit_string(content: &[u8]) -> Vec<u8> {
+    tlv(BIT_STRING, &[&[0_u8], content].concat())
+}
+
+/// Encode an OBJECT IDENTIFIER from its dotted representation e.g. `2.5.29.20`
+pub(crate) fn oid(dotted: &str) -> KResult<Vec<u8>> {
+    let arcs = dotted
+        .split('.')
+        .map(str::parse::<u64>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| KmsError::ConversionError(format!("invalid OID {dotted}: {e}")))?;
+    if arcs.len() < 2 {
+        kms_bail!(KmsError::ConversionError(format!("invalid OID {dotted}")))
+    }
+    let mut content = vec![];
+    for arc in std::iter::once(arcs[0] * 40 + arcs[1]).chain(arcs[2..].iter().copied()) {
+        let mut base128 = vec![(arc & 0x7F) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            base128.push(0x80 | (rest & 0x7F) as u8);
+            rest >>= 7;
+        }
+        base128.reverse();
+        content.extend(base128);
+    }
+    Ok(tlv(OBJECT_IDENTIFIER, &content))
+}
+
+/// Encode a `Time` as mandated by RFC 5280:
+/// UTCTime until 2049 and GeneralizedTime from 2050
+pub(crate) fn time(date: &DateTime<Utc>) -> Vec<u8> {
+    if date.year() < 2050 {
+        tlv(
+            UTC_TIME,
+            date.format("%y%m%d%H%M%SZ").to_string().as_bytes(),
+        )
+    } else {
+        generalized_time(date)
+    }
+}
+
+pub(crate) fn generalized_time(date: &DateTime<Utc>) -> Vec<u8> {
+    tlv(
+        GENERALIZED_TIME,
+        date.format("%Y%m%d%H%M%SZ").to_string().as_bytes(),
+    )
+}
+
+/// Encode an `Extension`: the value is wrapped in an OCTET STRING
+pub(crate) fn extension(extension_oid: &str, critical: bool, value: &[u8]) -> KResult<Vec<u8>> {
+    let mut items = vec![oid(extension_oid)?];
+    if critical {
+        items.push(boolean(true));
+    }
+    items.push(octet_string(value));
+    Ok(sequence(&items))
+}
+
+/// The `AlgorithmIdentifier` of the signature made by `sign` with this key
+pub(crate) fn signature_algorithm(pkey: &PKey<Private>) -> KResult<Vec<u8>> {
+    Ok(match pkey.id() {
+        // sha256WithRSAEncryption
+        Id::RSA => sequence(&[oid("1.2.840.113549.1.1.11")?, null()]),
+        // ecdsa-with-SHA256
+        Id::EC => sequence(&[oid("1.2.840.10045.4.3.2")?]),
+        Id::ED25519 => sequence(&[oid("1.3.101.112")?]),
+        Id::ED448 => sequence(&[oid("1.3.101.113")?]),
+        id => kms_bail!(KmsError::NotSupported(format!(
+            "signing with keys of type {id:?} is not supported"
+        ))),
+    })
+}
+
+/// Sign the DER encoded data with the key, using the algorithm of `signature_algorithm`
+pub(crate) fn sign(pkey: &PKey<Private>, data: &[u8]) -> KResult<Vec<u8>> {
+    let mut signer = match pkey.id() {
+        Id::ED25519 | Id::ED448 => Signer::new_without_digest(pkey)?,
+        _ => Signer::new(MessageDigest::sha256(), pkey)?,
+    };
+    Ok(signer.sign_oneshot_to_vec(data)?)
+}
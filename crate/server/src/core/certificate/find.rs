@@ -1,5 +1,11 @@
-use cosmian_kmip::kmip::{kmip_objects::Object, kmip_types::LinkType};
+use cosmian_kmip::kmip::{
+    kmip_objects::{Object, ObjectType},
+    kmip_types::{
+        Attributes, CertificateAttributes, LinkType, LinkedObjectIdentifier, StateEnumeration,
+    },
+};
 use cosmian_kms_client::access::ObjectOperationType;
+use openssl::bn::BigNum;
 
 use crate::{
     core::{extra_database_params::ExtraDatabaseParams, KMS},
@@ -159,10 +165,8 @@ pub(crate) async fn retrieve_private_key_for_certificate(
     )
     .await?;
 
-    let private_key_id = owm
-        .attributes
-        .get_link(LinkType::PKCS12CertificateLink)
-        .or_else(|| owm.attributes.get_link(LinkType::CertificateLink));
+    // the certificate link of a certificate points to its issuer, not to its private key
+    let private_key_id = owm.attributes.get_link(LinkType::PrivateKeyLink);
 
     let private_key_id = if let Some(private_key_id) = private_key_id {
         private_key_id
@@ -213,3 +217,41 @@ async fn find_link_in_public_key(
         KmsError::InvalidRequest(format!("No {link_type:?} found in the public key"))
     })
 }
+
+/// Find the certificates issued by the given issuer certificate,
+/// i.e. the certificates whose `CertificateLink` points to it, whatever their state.
+/// The search is restricted to the given unsigned big endian serial number, if any.
+pub(crate) async fn find_issued_certificates(
+    issuer_certificate_id: &str,
+    serial_number: Option<&[u8]>,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Vec<(String, StateEnumeration, Attributes)>> {
+    let mut researched_attributes = Attributes {
+        object_type: Some(ObjectType::Certificate),
+        ..Attributes::default()
+    };
+    researched_attributes.add_link(
+        LinkType::CertificateLink,
+        LinkedObjectIdentifier::TextString(issuer_certificate_id.to_owned()),
+    );
+    if let Some(serial_number) = serial_number {
+        // the serial number is kept in the same form in the certificate attributes
+        researched_attributes.certificate_attributes = Some(Box::new(CertificateAttributes {
+            certificate_subject_serial_number: BigNum::from_slice(serial_number)?
+                .to_hex_str()?
+                .to_string(),
+            ..CertificateAttributes::default()
+        }));
+    }
+    Ok(kms
+        .db
+        .find(Some(&researched_attributes), None, user, false, params)
+        .await?
+        .into_iter()
+        // a self-signed certificate may be linked to itself
+        .filter(|(uid, ..)| uid != issuer_certificate_id)
+        .map(|(uid, state, attributes, _)| (uid, state, attributes))
+        .collect())
+}
@@ -1,5 +1,13 @@
+mod cache;
+mod crl;
+mod der;
 mod find;
+mod ocsp;
 
+pub(crate) use cache::RevocationCache;
+pub(crate) use crl::build_crl;
 pub(crate) use find::{
-    retrieve_certificate_for_private_key, retrieve_matching_private_key_and_certificate,
+    find_issued_certificates, retrieve_certificate_for_private_key,
+    retrieve_matching_private_key_and_certificate, retrieve_private_key_for_certificate,
 };
+pub(crate) use ocsp::ocsp_response;
@@ -0,0 +1,584 @@
+//! OCSP responder (RFC 6960) for the certificates issued by the KMS.
+//!
+//! The status of a certificate is derived from the state of the certificate in the database:
+//! active certificates are `good`, the others `revoked`, and certificates unknown to the
+//! issuer `unknown`. Responses are signed by a delegated responder, i.e. an active certificate
+//! issued by the issuer with the `OCSPSigning` extended key usage and whose private key is
+//! held in the KMS; failing that, by the issuer itself.
+
+use chrono::{DateTime, Duration, Utc};
+use cosmian_kmip::{
+    kmip::{kmip_operations::ErrorReason, kmip_types::StateEnumeration},
+    openssl::{kmip_certificate_to_openssl, kmip_private_key_to_openssl},
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use openssl::{
+    hash::{hash, MessageDigest},
+    pkey::{PKey, Private},
+    x509::X509,
+};
+use tracing::{debug, warn};
+
+use super::{
+    crl::{crl_reason_code, RevokedCertificate},
+    der, find_issued_certificates, retrieve_matching_private_key_and_certificate,
+    retrieve_private_key_for_certificate,
+};
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    database::object_with_metadata::ObjectWithMetadata,
+    error::KmsError,
+    result::KResult,
+};
+
+const OID_OCSP_BASIC: &str = "1.3.6.1.5.5.7.48.1.1";
+const OID_OCSP_NONCE: &str = "1.3.6.1.5.5.7.48.1.2";
+const OID_SHA1: &str = "1.3.14.3.2.26";
+const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+const OID_SHA384: &str = "2.16.840.1.101.3.4.2.2";
+const OID_SHA512: &str = "2.16.840.1.101.3.4.2.3";
+
+/// Number of hours before a client should fetch a newer response
+const RESPONSE_VALIDITY_HOURS: i64 = 1;
+
+/// The `OCSPResponseStatus` values
+#[derive(Clone, Copy, Debug)]
+enum ResponseStatus {
+    Successful = 0,
+    MalformedRequest = 1,
+    InternalError = 2,
+    Unauthorized = 6,
+}
+
+/// The identifier of a certificate in a request
+struct CertId<'a> {
+    /// The DER encoding, echoed in the response
+    encoded: &'a [u8],
+    digest: MessageDigest,
+    issuer_name_hash: &'a [u8],
+    issuer_key_hash: &'a [u8],
+    /// The unsigned big endian serial number
+    serial_number: &'a [u8],
+}
+
+struct OcspRequest<'a> {
+    cert_ids: Vec<CertId<'a>>,
+    /// The DER encoding of the nonce extension, echoed in the response
+    nonce: Option<&'a [u8]>,
+}
+
+enum CertStatus {
+    Good,
+    Revoked(RevokedCertificate),
+    Unknown,
+}
+
+/// Answer the DER encoded OCSP request about certificates issued by the given issuer certificate.
+///
+/// An OCSP response is always returned: failures are reported in its response status.
+pub(crate) async fn ocsp_response(
+    issuer_certificate_id: &str,
+    request: &[u8],
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> Vec<u8> {
+    match respond(issuer_certificate_id, request, kms, user, params).await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("OCSP request for issuer {issuer_certificate_id} failed: {e}");
+            let status = match e {
+                KmsError::InvalidRequest(_) | KmsError::ConversionError(_) => {
+                    ResponseStatus::MalformedRequest
+                }
+                KmsError::ItemNotFound(_)
+                | KmsError::Unauthorized(_)
+                | KmsError::KmipError(ErrorReason::Item_Not_Found, _) => {
+                    ResponseStatus::Unauthorized
+                }
+                _ => ResponseStatus::InternalError,
+            };
+            error_response(status)
+        }
+    }
+}
+
+async fn respond(
+    issuer_certificate_id: &str,
+    request: &[u8],
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Vec<u8>> {
+    let request = parse_request(request)?;
+
+    // the user must be granted `Certify` on the issuer: the issued certificates
+    // and the delegated responder are then resolved as the issuer owner
+    let (issuer_private_key, issuer_certificate) = retrieve_matching_private_key_and_certificate(
+        None,
+        Some(issuer_certificate_id.to_owned()),
+        kms,
+        user,
+        params,
+    )
+    .await?;
+    let issuer_x509 = kmip_certificate_to_openssl(&issuer_certificate.object)?;
+
+    // a response without nonce is served from the cache until its next update,
+    // or until a certificate or an issuer changes (see `RevocationCache::clear`)
+    let cache_key = request.nonce.is_none().then(|| {
+        (
+            params.map(|p| p.group_id),
+            issuer_certificate.id.clone(),
+            request
+                .cert_ids
+                .iter()
+                .flat_map(|cert_id| cert_id.encoded.iter().copied())
+                .collect::<Vec<_>>(),
+        )
+    });
+    if let Some(cache_key) = &cache_key {
+        if let Some(response) = kms.revocation_cache.ocsp_responses.get(cache_key)? {
+            return Ok(response)
+        }
+    }
+
+    let mut statuses = Vec::with_capacity(request.cert_ids.len());
+    for cert_id in &request.cert_ids {
+        statuses.push(
+            certificate_status(cert_id, &issuer_x509, &issuer_certificate, kms, params).await?,
+        );
+    }
+
+    // Sign with the delegated responder if any, with the issuer otherwise
+    let (signer_pkey, signer_x509, delegated) =
+        match delegated_responder(&issuer_certificate, kms, params).await? {
+            Some((responder_certificate_id, responder_x509)) => {
+                let responder_private_key = retrieve_private_key_for_certificate(
+                    &responder_certificate_id,
+                    ObjectOperationType::Certify,
+                    kms,
+                    &issuer_certificate.owner,
+                    params,
+                )
+                .await?;
+                debug!(
+                    "OCSP response signed by the delegated responder {responder_certificate_id}"
+                );
+                (
+                    kmip_private_key_to_openssl(&responder_private_key.object)?,
+                    responder_x509,
+                    true,
+                )
+            }
+            None => (
+                kmip_private_key_to_openssl(&issuer_private_key.object)?,
+                issuer_x509,
+                false,
+            ),
+        };
+
+    let produced_at = Utc::now();
+    let response = encode_response(
+        &request,
+        &statuses,
+        &signer_pkey,
+        &signer_x509,
+        delegated,
+        produced_at,
+    )?;
+    if let Some(cache_key) = cache_key {
+        kms.revocation_cache.ocsp_responses.insert(
+            cache_key,
+            produced_at + Duration::hours(RESPONSE_VALIDITY_HOURS),
+            response.clone(),
+        )?;
+    }
+    Ok(response)
+}
+
+/// The status of a requested certificate, looked up by serial number among
+/// the certificates issued by the issuer, with the permissions of the issuer owner
+async fn certificate_status(
+    cert_id: &CertId<'_>,
+    issuer_x509: &X509,
+    issuer_certificate: &ObjectWithMetadata,
+    kms: &KMS,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<CertStatus> {
+    if !issued_by(cert_id, issuer_x509)? {
+        return Ok(CertStatus::Unknown)
+    }
+    let issued = find_issued_certificates(
+        &issuer_certificate.id,
+        Some(cert_id.serial_number),
+        kms,
+        &issuer_certificate.owner,
+        params,
+    )
+    .await?;
+    Ok(match issued.into_iter().next() {
+        None => CertStatus::Unknown,
+        Some((_, StateEnumeration::Active | StateEnumeration::PreActive, _)) => CertStatus::Good,
+        Some((_, state, attributes)) => CertStatus::Revoked(RevokedCertificate::new(
+            cert_id.serial_number.to_vec(),
+            state,
+            &attributes,
+        )),
+    })
+}
+
+/// The delegated responder of the issuer, if any: an active certificate issued by
+/// the issuer with the `OCSPSigning` extended key usage.
+/// It is looked up as the issuer owner and cached until the next update of the responses.
+async fn delegated_responder(
+    issuer_certificate: &ObjectWithMetadata,
+    kms: &KMS,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Option<(String, X509)>> {
+    let cache_key = (params.map(|p| p.group_id), issuer_certificate.id.clone());
+    if let Some(responder) = kms.revocation_cache.ocsp_responders.get(&cache_key)? {
+        return Ok(responder)
+    }
+
+    let mut responder = None;
+    for (uid, state, _) in find_issued_certificates(
+        &issuer_certificate.id,
+        None,
+        kms,
+        &issuer_certificate.owner,
+        params,
+    )
+    .await?
+    {
+        if state != StateEnumeration::Active {
+            continue
+        }
+        let owm = kms
+            .db
+            .retrieve(
+                &uid,
+                &issuer_certificate.owner,
+                ObjectOperationType::Get,
+                params,
+            )
+            .await?
+            .remove(&uid)
+            .ok_or_else(|| KmsError::ItemNotFound(uid.clone()))?;
+        let x509 = kmip_certificate_to_openssl(&owm.object)?;
+        if is_ocsp_signer(&x509)? {
+            responder = Some((uid, x509));
+            break
+        }
+    }
+    kms.revocation_cache.ocsp_responders.insert(
+        cache_key,
+        Utc::now() + Duration::hours(RESPONSE_VALIDITY_HOURS),
+        responder.clone(),
+    )?;
+    Ok(responder)
+}
+
+fn parse_request(request: &[u8]) -> KResult<OcspRequest<'_>> {
+    // OCSPRequest ::= SEQUENCE { tbsRequest, optionalSignature [0] EXPLICIT OPTIONAL }
+    let ocsp_request = der::parse_expected(request, der::SEQUENCE)?;
+    let tbs_request = der::parse_expected(ocsp_request.content, der::SEQUENCE)?;
+    // TBSRequest ::= SEQUENCE { version [0], requestorName [1], requestList, requestExtensions [2] }
+    let mut cert_ids = Vec::new();
+    let mut nonce = None;
+    for item in der::parse_all(tbs_request.content)? {
+        match item.tag {
+            der::SEQUENCE => {
+                for single_request in der::parse_all(item.content)? {
+                    // Request ::= SEQUENCE { reqCert CertID, singleRequestExtensions [0] OPTIONAL }
+                    let cert_id = der::parse_expected(single_request.content, der::SEQUENCE)?;
+                    cert_ids.push(parse_cert_id(cert_id.encoded, cert_id.content)?);
+                }
+            }
+            // requestExtensions [2] EXPLICIT Extensions
+            0xA2 => {
+                let extensions = der::parse_expected(item.content, der::SEQUENCE)?;
+                for extension in der::parse_all(extensions.content)? {
+                    let extension_oid =
+                        der::parse_expected(extension.content, der::OBJECT_IDENTIFIER)?;
+                    if extension_oid.encoded == der::oid(OID_OCSP_NONCE)?.as_slice() {
+                        nonce = Some(extension.encoded);
+                    }
+                }
+            }
+            // version and requestor name are ignored
+            _ => {}
+        }
+    }
+    if cert_ids.is_empty() {
+        return Err(KmsError::InvalidRequest(
+            "the OCSP request does not contain any certificate".to_owned(),
+        ))
+    }
+    Ok(OcspRequest { cert_ids, nonce })
+}
+
+fn parse_cert_id<'a>(encoded: &'a [u8], content: &'a [u8]) -> KResult<CertId<'a>> {
+    // CertID ::= SEQUENCE { hashAlgorithm, issuerNameHash, issuerKeyHash, serialNumber }
+    let items = der::parse_all(content)?;
+    let [
+        hash_algorithm,
+        issuer_name_hash,
+        issuer_key_hash,
+        serial_number,
+    ] = items.as_slice()
+    else {
+        return Err(KmsError::InvalidRequest("invalid OCSP CertID".to_owned()))
+    };
+    let hash_oid = der::parse_expected(hash_algorithm.content, der::OBJECT_IDENTIFIER)?;
+    let digest = [
+        (OID_SHA1, MessageDigest::sha1()),
+        (OID_SHA256, MessageDigest::sha256()),
+        (OID_SHA384, MessageDigest::sha384()),
+        (OID_SHA512, MessageDigest::sha512()),
+    ]
+    .into_iter()
+    .find_map(|(oid, digest)| match der::oid(oid) {
+        Ok(encoded_oid) if encoded_oid.as_slice() == hash_oid.encoded => Some(Ok(digest)),
+        Ok(_) => None,
+        Err(e) => Some(Err(e)),
+    })
+    .transpose()?
+    .ok_or_else(|| KmsError::InvalidRequest("unsupported OCSP CertID hash algorithm".to_owned()))?;
+    if issuer_name_hash.tag != der::OCTET_STRING
+        || issuer_key_hash.tag != der::OCTET_STRING
+        || serial_number.tag != der::INTEGER
+    {
+        return Err(KmsError::InvalidRequest("invalid OCSP CertID".to_owned()))
+    }
+    Ok(CertId {
+        encoded,
+        digest,
+        issuer_name_hash: issuer_name_hash.content,
+        issuer_key_hash: issuer_key_hash.content,
+        serial_number: der::unsigned_integer_value(serial_number.content),
+    })
+}
+
+/// Whether the hashes of the CertID are those of the issuer name and public key
+fn issued_by(cert_id: &CertId, issuer_x509: &X509) -> KResult<bool> {
+    let name_hash = hash(cert_id.digest, &issuer_x509.subject_name().to_der()?)?;
+    let key_hash = hash(cert_id.digest, &subject_public_key(issuer_x509)?)?;
+    Ok(&*name_hash == cert_id.issuer_name_hash && &*key_hash == cert_id.issuer_key_hash)
+}
+
+/// The value of the `subjectPublicKey` BIT STRING of the certificate, without the unused bits
+fn subject_public_key(x509: &X509) -> KResult<Vec<u8>> {
+    let spki = x509.public_key()?.public_key_to_der()?;
+    let spki = der::parse_expected(&spki, der::SEQUENCE)?;
+    let items = der::parse_all(spki.content)?;
+    let public_key = items
+        .get(1)
+        .filter(|item| item.tag == der::BIT_STRING && !item.content.is_empty())
+        .ok_or_else(|| KmsError::Certificate("invalid subject public key info".to_owned()))?;
+    Ok(public_key.content[1..].to_vec())
+}
+
+/// Whether the certificate has the `OCSPSigning` extended key usage
+fn is_ocsp_signer(x509: &X509) -> KResult<bool> {
+    let der = x509.to_der()?;
+    let (_, certificate) = x509_parser::parse_x509_certificate(&der)?;
+    Ok(certificate
+        .extended_key_usage()?
+        .map_or(false, |eku| eku.value.ocsp_signing))
+}
+
+fn error_response(status: ResponseStatus) -> Vec<u8> {
+    der::sequence(&[der::enumerated(status as u8)])
+}
+
+fn encode_response(
+    request: &OcspRequest,
+    statuses: &[CertStatus],
+    signer_pkey: &PKey<Private>,
+    signer_x509: &X509,
+    delegated: bool,
+    produced_at: DateTime<Utc>,
+) -> KResult<Vec<u8>> {
+    let next_update = produced_at + Duration::hours(RESPONSE_VALIDITY_HOURS);
+    let responses = request
+        .cert_ids
+        .iter()
+        .zip(statuses)
+        .map(|(cert_id, status)| {
+            // SingleResponse ::= SEQUENCE { certID, certStatus, thisUpdate, nextUpdate [0] }
+            let cert_status = match status {
+                CertStatus::Good => der::tlv(0x80, &[]),
+                CertStatus::Revoked(revoked) => {
+                    let mut revoked_info = der::generalized_time(&revoked.revocation_date);
+                    if let Some(reason_code) = revoked.reason.and_then(crl_reason_code) {
+                        revoked_info.extend(der::explicit(0, &der::enumerated(reason_code)));
+                    }
+                    der::tlv(0xA1, &revoked_info)
+                }
+                CertStatus::Unknown => der::tlv(0x82, &[]),
+            };
+            der::sequence(&[
+                cert_id.encoded.to_vec(),
+                cert_status,
+                der::generalized_time(&produced_at),
+                der::explicit(0, &der::generalized_time(&next_update)),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    // ResponseData ::= SEQUENCE { responderID byName [1], producedAt, responses, responseExtensions [1] }
+    let mut response_data = vec![
+        der::explicit(1, &signer_x509.subject_name().to_der()?),
+        der::generalized_time(&produced_at),
+        der::sequence(&responses),
+    ];
+    if let Some(nonce) = request.nonce {
+        response_data.push(der::explicit(1, &der::sequence(&[nonce.to_vec()])));
+    }
+    let tbs_response_data = der::sequence(&response_data);
+
+    // BasicOCSPResponse ::= SEQUENCE { tbsResponseData, signatureAlgorithm, signature, certs [0] }
+    let signature = der::sign(signer_pkey, &tbs_response_data)?;
+    let mut basic_response = vec![
+        tbs_response_data,
+        der::signature_algorithm(signer_pkey)?,
+        der::bit_string(&signature),
+    ];
+    if delegated {
+        // the delegated responder certificate lets clients verify the signature
+        basic_response.push(der::explicit(0, &der::sequence(&[signer_x509.to_der()?])));
+    }
+
+    // OCSPResponse ::= SEQUENCE { responseStatus, responseBytes [0] EXPLICIT ResponseBytes }
+    Ok(der::sequence(&[
+        der::enumerated(ResponseStatus::Successful as u8),
+        der::explicit(
+            0,
+            &der::sequence(&[
+                der::oid(OID_OCSP_BASIC)?,
+                der::octet_string(&der::sequence(&basic_response)),
+            ]),
+        ),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use cosmian_kmip::kmip::kmip_types::RevocationReasonEnumeration;
+    use openssl::{
+        asn1::{Asn1Integer, Asn1Time},
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        ocsp::{OcspCertId, OcspCertStatus, OcspRequest, OcspResponse, OcspResponseStatus},
+        pkey::{PKey, Private},
+        stack::Stack,
+        x509::{store::X509StoreBuilder, X509NameBuilder, X509},
+    };
+
+    use super::{encode_response, issued_by, parse_request, CertStatus};
+    use crate::{core::certificate::crl::RevokedCertificate, result::KResult};
+
+    fn certificate(
+        cn: &str,
+        serial: u32,
+        pkey: &PKey<Private>,
+        issuer: Option<(&X509, &PKey<Private>)>,
+    ) -> KResult<X509> {
+        let mut name = X509NameBuilder::new()?;
+        name.append_entry_by_nid(Nid::COMMONNAME, cn)?;
+        let name = name.build();
+        let mut builder = X509::builder()?;
+        builder.set_version(2)?;
+        builder.set_serial_number(Asn1Integer::from_bn(&BigNum::from_u32(serial)?)?.as_ref())?;
+        builder.set_subject_name(&name)?;
+        builder.set_pubkey(pkey)?;
+        builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+        builder.set_not_after(Asn1Time::days_from_now(365)?.as_ref())?;
+        match issuer {
+            Some((issuer_x509, issuer_pkey)) => {
+                builder.set_issuer_name(issuer_x509.subject_name())?;
+                builder.sign(issuer_pkey, MessageDigest::sha256())?;
+            }
+            None => {
+                builder.set_issuer_name(&name)?;
+                builder.sign(pkey, MessageDigest::sha256())?;
+            }
+        }
+        Ok(builder.build())
+    }
+
+    fn ec_key() -> KResult<PKey<Private>> {
+        Ok(PKey::from_ec_key(EcKey::generate(
+            EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?.as_ref(),
+        )?)?)
+    }
+
+    #[test]
+    fn test_ocsp() -> KResult<()> {
+        let issuer_pkey = ec_key()?;
+        let issuer = certificate("Test CA", 1, &issuer_pkey, None)?;
+        let good = certificate("good", 2, &ec_key()?, Some((&issuer, &issuer_pkey)))?;
+        let revoked = certificate("revoked", 3, &ec_key()?, Some((&issuer, &issuer_pkey)))?;
+
+        let good_id = OcspCertId::from_cert(MessageDigest::sha1(), &good, &issuer)?;
+        let revoked_id = OcspCertId::from_cert(MessageDigest::sha1(), &revoked, &issuer)?;
+        let mut request = OcspRequest::new()?;
+        request.add_id(OcspCertId::from_cert(
+            MessageDigest::sha1(),
+            &good,
+            &issuer,
+        )?)?;
+        request.add_id(OcspCertId::from_cert(
+            MessageDigest::sha1(),
+            &revoked,
+            &issuer,
+        )?)?;
+        let request_der = request.to_der()?;
+
+        let request = parse_request(&request_der)?;
+        assert_eq!(request.cert_ids.len(), 2);
+        assert!(issued_by(&request.cert_ids[0], &issuer)?);
+        assert_eq!(request.cert_ids[1].serial_number, [3]);
+
+        let statuses = [
+            CertStatus::Good,
+            CertStatus::Revoked(RevokedCertificate {
+                serial_number: vec![3],
+                revocation_date: Utc::now(),
+                reason: Some(RevocationReasonEnumeration::KeyCompromise),
+                invalidity_date: None,
+            }),
+        ];
+        let response_der = encode_response(
+            &request,
+            &statuses,
+            &issuer_pkey,
+            &issuer,
+            false,
+            Utc::now(),
+        )?;
+
+        let response = OcspResponse::from_der(&response_der)?;
+        assert_eq!(response.status(), OcspResponseStatus::SUCCESSFUL);
+        let basic = response.basic()?;
+        let mut store = X509StoreBuilder::new()?;
+        store.add_cert(issuer.clone())?;
+        let store = store.build();
+        let mut certs = Stack::new()?;
+        certs.push(issuer.clone())?;
+        basic.verify(&certs, &store, openssl::ocsp::OcspFlag::empty())?;
+        assert_eq!(
+            basic.find_status(&good_id).unwrap().status,
+            OcspCertStatus::GOOD
+        );
+        assert_eq!(
+            basic.find_status(&revoked_id).unwrap().status,
+            OcspCertStatus::REVOKED
+        );
+        Ok(())
+    }
+}
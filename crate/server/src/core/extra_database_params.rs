@@ -2,6 +2,7 @@ use cosmian_kmip::crypto::{secret::Secret, symmetric::AES_256_GCM_KEY_LENGTH};
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroizing;
 
+#[derive(Clone)]
 pub struct ExtraDatabaseParams {
     pub group_id: u128,
     pub key: Secret<AES_256_GCM_KEY_LENGTH>,
@@ -29,7 +29,11 @@ use tracing::warn;
 use zeroize::Zeroizing;
 
 use super::{
-    cover_crypt::create_user_decryption_key, extra_database_params::ExtraDatabaseParams, KMS,
+    certificate::RevocationCache,
+    cover_crypt::create_user_decryption_key,
+    extra_database_params::ExtraDatabaseParams,
+    operations::{MacStreams, RunningJobs},
+    KMS,
 };
 use crate::{
     config::{DbParams, ServerParams},
@@ -90,6 +94,10 @@ impl KMS {
         Ok(Self {
             params: shared_config,
             db,
+            mac_streams: MacStreams::default(),
+            jobs: RunningJobs::default(),
+            revocation_cache: RevocationCache::default(),
+            usage_limits_lock: tokio::sync::Mutex::default(),
         })
     }
 
@@ -125,7 +133,16 @@ impl KMS {
             | CryptographicAlgorithm::SHA3384
             | CryptographicAlgorithm::SHA3512
             | CryptographicAlgorithm::SHAKE128
-            | CryptographicAlgorithm::SHAKE256 => match attributes.key_format_type {
+            | CryptographicAlgorithm::SHAKE256
+            | CryptographicAlgorithm::HMACSHA1
+            | CryptographicAlgorithm::HMACSHA224
+            | CryptographicAlgorithm::HMACSHA256
+            | CryptographicAlgorithm::HMACSHA384
+            | CryptographicAlgorithm::HMACSHA512
+            | CryptographicAlgorithm::HMACSHA3224
+            | CryptographicAlgorithm::HMACSHA3256
+            | CryptographicAlgorithm::HMACSHA3384
+            | CryptographicAlgorithm::HMACSHA3512 => match attributes.key_format_type {
                 None => Err(KmsError::InvalidRequest(
                     "Unable to create a symmetric key, the format type is not specified"
                         .to_string(),
@@ -137,8 +154,17 @@ impl KMS {
                         .map_or(AES_256_GCM_KEY_LENGTH, |v| v as usize / 8);
                     let mut symmetric_key = Zeroizing::from(vec![0; key_len]);
                     rand_bytes(&mut symmetric_key)?;
-                    let object =
+                    let mut object =
                         create_symmetric_key_kmip_object(&symmetric_key, *cryptographic_algorithm);
+                    // honor the usage mask of the request, if any
+                    if let Some(usage_mask) = attributes.cryptographic_usage_mask {
+                        object.attributes_mut()?.cryptographic_usage_mask = Some(usage_mask);
+                    }
+                    // the usage limits can only be set at creation
+                    object
+                        .attributes_mut()?
+                        .usage_limits
+                        .clone_from(&attributes.usage_limits);
 
                     //return the object and the tags
                     Ok((object, tags))
@@ -1,6 +1,7 @@
 use std::{
     collections::{BTreeSet, HashSet},
     fs,
+    sync::Arc,
 };
 
 use actix_web::{HttpMessage, HttpRequest};
@@ -11,26 +12,39 @@ use base64::{
 use cosmian_kmip::{
     crypto::secret::Secret,
     kmip::{
-        kmip_messages::{Message, MessageResponse},
+        kmip_messages::{Message, MessageResponse, MessageResponseBatchItem},
         kmip_operations::{
-            Certify, CertifyResponse, Create, CreateKeyPair, CreateKeyPairResponse, CreateResponse,
-            Decrypt, DecryptResponse, Destroy, DestroyResponse, Encrypt, EncryptResponse, Export,
-            ExportResponse, Get, GetAttributes, GetAttributesResponse, GetResponse, Import,
-            ImportResponse, Locate, LocateResponse, ReKeyKeyPair, ReKeyKeyPairResponse, Revoke,
-            RevokeResponse,
+            Activate, ActivateResponse, AddAttribute, AddAttributeResponse, Archive,
+            ArchiveResponse, Cancel, CancelResponse, Certify, CertifyResponse, Create,
+            CreateKeyPair, CreateKeyPairResponse, CreateResponse, Decrypt, DecryptResponse,
+            DeleteAttribute, DeleteAttributeResponse, DeriveKey, DeriveKeyResponse, Destroy,
+            DestroyResponse, DiscoverVersions, DiscoverVersionsResponse, Encrypt, EncryptResponse,
+            Export, ExportResponse, Get, GetAttributes, GetAttributesResponse, GetResponse, Import,
+            ImportResponse, Locate, LocateResponse, MACResponse, MACVerify, MACVerifyResponse,
+            ModifyAttribute, ModifyAttributeResponse, Poll, Query, QueryResponse, RNGRetrieve,
+            RNGRetrieveResponse, RNGSeed, RNGSeedResponse, ReKey, ReKeyKeyPair,
+            ReKeyKeyPairResponse, ReKeyResponse, Recover, RecoverResponse, Revoke, RevokeResponse,
+            SetAttribute, SetAttributeResponse, Sign, SignResponse, SignatureVerify,
+            SignatureVerifyResponse, Validate, ValidateResponse, MAC,
         },
         kmip_types::{StateEnumeration, UniqueIdentifier},
+        ttlv::TTLV,
     },
 };
 use cosmian_kms_client::access::{
-    Access, AccessRightsObtainedResponse, ObjectOwnedResponse, UserAccessResponse,
+    Access, AccessRightsObtainedResponse, GroupMember, GroupResponse, ObjectOperationType,
+    ObjectOwnedResponse, Role, RoleResponse, UserAccessResponse, GROUP_PREFIX,
 };
 use tracing::debug;
 use uuid::Uuid;
 
 use crate::{
     config::{DbParams, ServerParams},
-    core::{extra_database_params::ExtraDatabaseParams, operations},
+    core::{
+        certificate::{self, RevocationCache},
+        extra_database_params::ExtraDatabaseParams,
+        operations::{self, MacStreams, RunningJobs},
+    },
     database::Database,
     error::KmsError,
     kms_bail, kms_error,
@@ -43,6 +57,14 @@ use crate::{
 pub struct KMS {
     pub(crate) params: ServerParams,
     pub(crate) db: Box<dyn Database + Sync + Send>,
+    /// The multi-part MAC operations in progress
+    pub(crate) mac_streams: MacStreams,
+    /// The operations processed asynchronously in the background
+    pub(crate) jobs: RunningJobs,
+    /// The signed revocation data served until their next update
+    pub(crate) revocation_cache: RevocationCache,
+    /// Serializes the consumption of the Usage Limits of the objects
+    pub(crate) usage_limits_lock: tokio::sync::Mutex<()>,
 }
 
 /// Implement the KMIP Server operations and dispatches the actual actions
@@ -242,6 +264,27 @@ impl KMS {
         operations::decrypt(self, request, user, params).await
     }
 
+    /// This request is used to derive a Symmetric Key or Secret Data object
+    /// from keys or Secret Data objects that are already known to the key
+    /// management system. The request SHALL only apply to Managed Objects
+    /// that have the Derive Key bit set in the Cryptographic Usage Mask
+    /// attribute of the specified Managed Object (i.e., are able to be used
+    /// for key derivation). The server SHALL create a Link attribute of Link
+    /// Type Derivation Base Object in the derived object pointing to the
+    /// base object, and a Link attribute of Link Type Derived Key in the base
+    /// object pointing to the derived object.
+    ///
+    /// The supported derivation methods are PBKDF2, HKDF and NIST SP 800-108
+    /// in counter mode.
+    pub async fn derive_key(
+        &self,
+        request: DeriveKey,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<DeriveKeyResponse> {
+        operations::derive_key(self, request, user, params).await
+    }
+
     /// This operation is used to indicate to the server that the key material
     /// for the specified Managed Object SHALL be destroyed or rendered
     /// inaccessible. The meta-data for the key material SHALL be retained by
@@ -256,6 +299,32 @@ impl KMS {
         operations::destroy_operation(self, request, user, params).await
     }
 
+    /// This operation is used to specify that a Managed Object MAY be archived.
+    /// The actual time when the object is archived, the location of the
+    /// archive, or level of protection of the archive, are server dependent.
+    /// Objects that have been archived are not available for any operation,
+    /// other than Recover, until they are recovered.
+    pub async fn archive(
+        &self,
+        request: Archive,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<ArchiveResponse> {
+        operations::archive(self, request, user, params).await
+    }
+
+    /// This operation is used to obtain access to a Managed Object that has
+    /// been archived. Once the response is received, the object is now
+    /// on-line, and MAY be obtained (e.g., via a Get operation).
+    pub async fn recover(
+        &self,
+        request: Recover,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<RecoverResponse> {
+        operations::recover(self, request, user, params).await
+    }
+
     /// This operation requests the server to perform an encryption operation on
     /// the provided data using a Managed Cryptographic Object as the key
     /// for the encryption operation.
@@ -359,6 +428,64 @@ impl KMS {
         operations::get_attributes(self, request, user, params).await
     }
 
+    /// This operation requests the server to either add or modify an attribute.
+    /// The request contains the Unique Identifier of the Managed Object to which
+    /// the attribute pertains, along with the attribute and value. If the object
+    /// did not have any instances of the attribute, one is created. If the
+    /// object had exactly one instance, then it is modified. If it has more than
+    /// one instance an error is raised. Read-Only attributes SHALL NOT be added
+    /// or modified using this operation.
+    pub async fn set_attribute(
+        &self,
+        request: SetAttribute,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<SetAttributeResponse> {
+        operations::set_attribute(self, request, user, params).await
+    }
+
+    /// This operation requests the server to add a new attribute instance to be
+    /// associated with a Managed Object and set its value. For single-instance
+    /// attributes, this creates the attribute value. For multi-instance
+    /// attributes, this is how the first and subsequent values are created.
+    /// Existing attribute values SHALL NOT be changed by this operation.
+    pub async fn add_attribute(
+        &self,
+        request: AddAttribute,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<AddAttributeResponse> {
+        operations::add_attribute(self, request, user, params).await
+    }
+
+    /// This operation requests the server to modify the value of an existing
+    /// attribute instance associated with a Managed Object. If the Current
+    /// Attribute is omitted from the request, then the attribute SHALL be
+    /// single-instance. The attribute instance SHALL exist, otherwise an error
+    /// is returned.
+    pub async fn modify_attribute(
+        &self,
+        request: ModifyAttribute,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<ModifyAttributeResponse> {
+        operations::modify_attribute(self, request, user, params).await
+    }
+
+    /// This operation requests the server to delete an attribute associated
+    /// with a Managed Object. The request contains either the attribute
+    /// instance to delete, or references to the attributes whose instances
+    /// are all deleted. Attributes that are always required to have a value
+    /// SHALL never be deleted by this operation.
+    pub async fn delete_attribute(
+        &self,
+        request: DeleteAttribute,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<DeleteAttributeResponse> {
+        operations::delete_attribute(self, request, user, params).await
+    }
+
     /// This operation requests that the server search for one or more Managed
     /// Objects, depending on the attributes specified in the request. All
     /// attributes are allowed to be used. The request MAY contain a Maximum
@@ -458,6 +585,74 @@ impl KMS {
         operations::locate(self, request, Some(StateEnumeration::Active), user, params).await
     }
 
+    /// This operation is used by the client to interrogate the server to
+    /// determine its capabilities and/or protocol mechanisms.
+    ///
+    /// The server reports the operations and object types it supports,
+    /// its server information, including whether it was built in FIPS mode
+    /// and which database backend it uses, and its capabilities.
+    pub async fn query(&self, request: Query) -> KResult<QueryResponse> {
+        operations::query(self, request).await
+    }
+
+    /// This operation is used by the client to determine a list of protocol
+    /// versions that is supported by the server. If the client provides the
+    /// server with a list of supported protocol versions in the request
+    /// payload, the server SHALL return only the protocol versions that are
+    /// supported by both the client and server.
+    pub async fn discover_versions(
+        &self,
+        request: DiscoverVersions,
+    ) -> KResult<DiscoverVersionsResponse> {
+        operations::discover_versions(request).await
+    }
+
+    /// This operation requests the server to return output from a Random
+    /// Number Generator (RNG).
+    ///
+    /// The random bytes are drawn from the OpenSSL DRBG of the server, the one
+    /// of the FIPS provider when the server is built in FIPS mode. A single
+    /// request cannot retrieve more than 1 MiB. The caller and the number of
+    /// bytes are logged for audit.
+    pub async fn rng_retrieve(
+        &self,
+        request: RNGRetrieve,
+        user: &str,
+    ) -> KResult<RNGRetrieveResponse> {
+        operations::rng_retrieve(request, user).await
+    }
+
+    /// This operation requests the server to seed a Random Number Generator.
+    ///
+    /// The server DRBG is seeded from the entropy source of the host: the
+    /// seed data are validated but not used, and the response reports that
+    /// no seed data were accepted.
+    pub async fn rng_seed(&self, request: RNGSeed, user: &str) -> KResult<RNGSeedResponse> {
+        operations::rng_seed(request, user).await
+    }
+
+    /// This request is used to generate a replacement key for an existing
+    /// symmetric key. It is analogous to the Create operation, except that
+    /// attributes of the replacement key are copied from the existing key.
+    ///
+    /// For the existing key, the server SHALL create a Link attribute of Link
+    /// Type Replacement Object pointing to the replacement key. For the
+    /// replacement key, the server SHALL create a Link attribute of Link Type
+    /// Replaced Object pointing to the existing key. The existing key is
+    /// deactivated.
+    ///
+    /// An Offset MAY be used to indicate the difference between the Initial
+    /// Date and the Activation Date of the replacement key. If no Offset is
+    /// specified, the Activation Date is copied from the existing key.
+    pub async fn rekey(
+        &self,
+        request: ReKey,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<ReKeyResponse> {
+        operations::rekey(self, request, user, params).await
+    }
+
     // This request is used to generate a replacement key pair for an existing
     // public/private key pair.  It is analogous to the Create Key Pair operation,
     // except that attributes of the replacement key pair are copied from the
@@ -492,6 +687,22 @@ impl KMS {
         operations::rekey_keypair(self, request, user, params).await
     }
 
+    /// This operation requests the server to activate a Managed Cryptographic
+    /// Object. The operation SHALL only be performed on an object in the
+    /// Pre-Active state and has the effect of changing its state to Active,
+    /// and setting its Activation Date to the current date and time.
+    ///
+    /// Pre-active objects are also activated automatically once their
+    /// Activation Date is reached.
+    pub async fn activate(
+        &self,
+        request: Activate,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<ActivateResponse> {
+        operations::activate(self, request, user, params).await
+    }
+
     /// This operation requests the server to revoke a Managed Cryptographic
     /// Object or an Opaque Object. The request contains a reason for the
     /// revocation (e.g., "key compromise", "cessation of operation", etc.). The
@@ -513,6 +724,122 @@ impl KMS {
         operations::revoke_operation(self, request, user, params).await
     }
 
+    /// Build the Certificate Revocation List of the certificates issued by
+    /// the given issuer certificate, signed by the issuer private key held in the KMS.
+    /// The CRL is returned DER encoded.
+    pub async fn crl(
+        &self,
+        issuer_certificate_id: &str,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<u8>> {
+        certificate::build_crl(issuer_certificate_id, self, user, params).await
+    }
+
+    /// Answer a DER encoded OCSP request about the certificates issued by
+    /// the given issuer certificate. The DER encoded OCSP response is signed by
+    /// a delegated responder certificate held in the KMS, or by the issuer.
+    pub async fn ocsp(
+        &self,
+        issuer_certificate_id: &str,
+        request: &[u8],
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Vec<u8> {
+        certificate::ocsp_response(issuer_certificate_id, request, self, user, params).await
+    }
+
+    /// This operation requests the server to perform a signature operation on
+    /// the provided data using a Managed Cryptographic Object as the key for
+    /// the signature operation. The request contains information about the
+    /// cryptographic parameters (digital signature algorithm or cryptographic
+    /// algorithm and hash algorithm) and the data to be signed. The
+    /// cryptographic parameters MAY be omitted from the request as they can be
+    /// specified as associated attributes of the Managed Cryptographic Object.
+    /// The response contains the Unique Identifier of the Managed Cryptographic
+    /// Object used as the key and the result of the signature operation.
+    pub async fn sign(
+        &self,
+        request: Sign,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<SignResponse> {
+        operations::sign(self, request, user, params).await
+    }
+
+    /// This operation requests the server to perform a signature verify
+    /// operation on the provided data using a Managed Cryptographic Object as
+    /// the key for the signature verification operation. The request contains
+    /// information about the cryptographic parameters (digital signature
+    /// algorithm or cryptographic algorithm and hash algorithm) and the
+    /// signature to be verified and MAY contain the data that was passed to
+    /// the signing operation (for those algorithms which need the original
+    /// data to verify a signature). The response contains the Unique
+    /// Identifier of the Managed Cryptographic Object used as the key and a
+    /// Validity Indicator stating whether the signature is valid.
+    pub async fn signature_verify(
+        &self,
+        request: SignatureVerify,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<SignatureVerifyResponse> {
+        operations::signature_verify(self, request, user, params).await
+    }
+
+    /// This operation requests the server to validate a certificate chain and
+    /// return information on its validity. The chain starts with the leaf
+    /// certificate and is completed with the certificates held by the server,
+    /// up to a self-signed root. The response contains a Validity Indicator
+    /// and, when the chain is not valid, the first reason found.
+    pub async fn validate(
+        &self,
+        request: Validate,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<ValidateResponse> {
+        operations::validate(self, request, user, params).await
+    }
+
+    /// This operation requests the server to perform message authentication
+    /// code (MAC) operation on the provided data using a Managed Cryptographic
+    /// Object as the key for the MAC operation. The request contains
+    /// information about the cryptographic parameters (cryptographic
+    /// algorithm) and the data to be MACed. The cryptographic parameters MAY
+    /// be omitted from the request as they can be specified as associated
+    /// attributes of the Managed Cryptographic Object. The response contains
+    /// the Unique Identifier of the Managed Cryptographic Object used as the
+    /// key and the result of the MAC operation.
+    ///
+    /// Large data can be MACed by parts: the first request sets the Init
+    /// Indicator and the server returns a Correlation Value which must be
+    /// provided in the subsequent requests; the last request sets the Final
+    /// Indicator.
+    pub async fn mac(
+        &self,
+        request: MAC,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<MACResponse> {
+        operations::mac(self, request, user, params).await
+    }
+
+    /// This operation requests the server to perform message authentication
+    /// code (MAC) verify operation on the provided data using a Managed
+    /// Cryptographic Object as the key for the MAC verify operation. The
+    /// request contains information about the cryptographic parameters
+    /// (cryptographic algorithm) and the data to be MAC verified. The response
+    /// contains the Unique Identifier of the Managed Cryptographic Object that
+    /// was used as the key and the result of the MAC verify operation. The
+    /// validity of the MAC is indicated by the Validity Indicator field.
+    pub async fn mac_verify(
+        &self,
+        request: MACVerify,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<MACVerifyResponse> {
+        operations::mac_verify(self, request, user, params).await
+    }
+
     /// Grant an access to a user (identified by `access.userid`)
     /// to an object (identified by `access.unique_identifier`)
     /// which is owned by `owner` (identified by `access.owner`)
@@ -536,22 +863,28 @@ impl KMS {
             )))
         }
 
+        check_user_identity(owner)?;
+        let user_id = access_target(access)?;
         // check if owner is trying to grant them self
-        if owner == access.user_id {
+        if owner == user_id {
             kms_bail!(KmsError::Unauthorized(
                 "You can't grant yourself, you have already all rights on your own objects"
                     .to_string()
             ))
         }
 
-        self.db
-            .grant_access(
-                uid,
-                &access.user_id,
-                HashSet::from_iter(access.operation_types.clone()),
-                params,
-            )
-            .await?;
+        let operation_types = access_operation_types(access)?;
+        if let Some(role) = &access.role {
+            if !self.db.list_roles(params).await?.contains_key(role) {
+                kms_bail!(KmsError::ItemNotFound(format!("role {role}")))
+            }
+            self.db.grant_role(uid, user_id, role, params).await?;
+        }
+        if !operation_types.is_empty() {
+            self.db
+                .grant_access(uid, user_id, operation_types, params)
+                .await?;
+        }
         Ok(())
     }
 
@@ -578,23 +911,134 @@ impl KMS {
             )))
         }
 
+        check_user_identity(owner)?;
+        let user_id = access_target(access)?;
         // check if owner is trying to revoke itself
-        if owner == access.user_id {
+        if owner == user_id {
             kms_bail!(KmsError::Unauthorized(
                 "You can't revoke yourself, you should keep all rights on your own objects"
                     .to_string()
             ))
         }
 
+        let operation_types = access_operation_types(access)?;
+        if let Some(role) = &access.role {
+            self.db.remove_role(uid, user_id, role, params).await?;
+        }
+        if !operation_types.is_empty() {
+            self.db
+                .remove_access(uid, user_id, operation_types, params)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Check that `user` is allowed to manage the roles and the groups
+    fn check_access_admin(&self, user: &str) -> KResult<()> {
+        if !self.params.access_admins.iter().any(|admin| admin == user) {
+            kms_bail!(KmsError::Unauthorized(format!(
+                "User `{user}` is not allowed to manage the roles and the groups"
+            )))
+        }
+        Ok(())
+    }
+
+    /// Create or replace a role: a named bundle of operation types
+    pub async fn set_role(
+        &self,
+        role: &Role,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        self.check_access_admin(user)?;
+        if role.name.is_empty() {
+            kms_bail!(KmsError::InvalidRequest(
+                "the name of a role cannot be empty".to_owned()
+            ))
+        }
         self.db
-            .remove_access(
-                uid,
-                &access.user_id,
-                HashSet::from_iter(access.operation_types.clone()),
+            .set_role(
+                &role.name,
+                &HashSet::from_iter(role.operation_types.clone()),
                 params,
             )
-            .await?;
-        Ok(())
+            .await
+    }
+
+    /// Delete a role and revoke the access rights granted with it
+    pub async fn delete_role(
+        &self,
+        name: &str,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        self.check_access_admin(user)?;
+        self.db.delete_role(name, params).await
+    }
+
+    /// List the roles, which any user can grant on the objects it owns
+    pub async fn list_roles(
+        &self,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<RoleResponse>> {
+        let roles = self.db.list_roles(params).await?;
+        Ok(roles
+            .into_iter()
+            .map(|(name, operations)| RoleResponse {
+                name,
+                operations: operations.into_iter().collect::<BTreeSet<_>>(),
+            })
+            .collect())
+    }
+
+    /// Add a user to a local group
+    pub async fn add_group_member(
+        &self,
+        member: &GroupMember,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        self.check_access_admin(user)?;
+        let group = group_name(&member.group)?;
+        if member.user_id.starts_with(GROUP_PREFIX) {
+            kms_bail!(KmsError::InvalidRequest(
+                "a group cannot be a member of another group".to_owned()
+            ))
+        }
+        self.db
+            .add_group_member(group, &member.user_id, params)
+            .await
+    }
+
+    /// Remove a user from a local group
+    pub async fn remove_group_member(
+        &self,
+        member: &GroupMember,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        self.check_access_admin(user)?;
+        let group = group_name(&member.group)?;
+        self.db
+            .remove_group_member(group, &member.user_id, params)
+            .await
+    }
+
+    /// List the local groups and their members
+    pub async fn list_groups(
+        &self,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<GroupResponse>> {
+        self.check_access_admin(user)?;
+        let groups = self.db.list_groups(params).await?;
+        Ok(groups
+            .into_iter()
+            .map(|(group, members)| GroupResponse {
+                group,
+                members: members.into_iter().collect::<BTreeSet<_>>(),
+            })
+            .collect())
     }
 
     /// Get all the access granted for a given object
@@ -685,6 +1129,7 @@ impl KMS {
                 }
             }
         };
+        check_user_identity(&user)?;
         debug!("Authenticated user: {}", user);
         Ok(user)
     }
@@ -730,11 +1175,107 @@ impl KMS {
     }
 
     pub async fn message(
-        &self,
+        self: &Arc<Self>,
         request: Message,
         user: &str,
         params: Option<&ExtraDatabaseParams>,
     ) -> KResult<MessageResponse> {
         operations::message(self, request, user, params).await
     }
+
+    /// Process a KMIP Message in its `TTLV` form, whatever its KMIP protocol version
+    pub async fn message_ttlv(
+        self: &Arc<Self>,
+        request: &TTLV,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<TTLV> {
+        operations::message_ttlv(self, request, user, params).await
+    }
+
+    /// Same as `message_ttlv` for a message decoded from the binary TTLV encoding
+    /// used by the KMIP socket server
+    pub async fn binary_message_ttlv(
+        self: &Arc<Self>,
+        request: &TTLV,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<TTLV> {
+        operations::binary_message_ttlv(self, request, user, params).await
+    }
+
+    /// This operation is used to poll the server in order to obtain the status
+    /// of an outstanding asynchronous operation. The correlation value of the
+    /// original operation SHALL be specified in the request. The response to
+    /// this operation SHALL NOT be asynchronous.
+    ///
+    /// The response batch item of the original operation is returned once it
+    /// completed or failed.
+    pub async fn poll(
+        self: &Arc<Self>,
+        request: Poll,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<MessageResponseBatchItem> {
+        operations::poll(self, request, user, params).await
+    }
+
+    /// Resume the asynchronous operations interrupted by a stop of the server
+    pub(crate) async fn resume_interrupted_jobs(self: &Arc<Self>) -> KResult<()> {
+        operations::resume_interrupted_jobs(self).await
+    }
+
+    /// This operation requests the server to cancel an outstanding asynchronous
+    /// operation. The correlation value of the original operation SHALL be
+    /// specified in the request. The server SHALL respond with a Cancellation
+    /// Result that contains the status of the cancellation.
+    pub async fn cancel(
+        &self,
+        request: Cancel,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<CancelResponse> {
+        operations::cancel(self, request, user, params).await
+    }
+}
+
+/// The operation types of an access, which must name at least one of them or a role
+fn access_operation_types(access: &Access) -> KResult<HashSet<ObjectOperationType>> {
+    if access.operation_types.is_empty() && access.role.is_none() {
+        kms_bail!(KmsError::InvalidRequest(
+            "no operation type or role specified".to_owned()
+        ))
+    }
+    Ok(HashSet::from_iter(access.operation_types.clone()))
+}
+
+/// Reject an authenticated identity which would be taken for a group:
+/// the `group:<name>` principals only designate the groups
+pub(crate) fn check_user_identity(user: &str) -> KResult<()> {
+    if user.starts_with(GROUP_PREFIX) {
+        kms_bail!(KmsError::Unauthorized(format!(
+            "the identity `{user}` is reserved for the groups"
+        )))
+    }
+    Ok(())
+}
+
+/// The user or group an access is granted to or revoked from:
+/// a `group:<name>` target must name a group
+fn access_target(access: &Access) -> KResult<&str> {
+    if access.user_id.starts_with(GROUP_PREFIX) {
+        group_name(&access.user_id)?;
+    }
+    Ok(&access.user_id)
+}
+
+/// The name of a group given with or without its `group:` prefix
+fn group_name(group: &str) -> KResult<&str> {
+    let name = group.strip_prefix(GROUP_PREFIX).unwrap_or(group);
+    if name.is_empty() {
+        kms_bail!(KmsError::InvalidRequest(
+            "the name of a group cannot be empty".to_owned()
+        ))
+    }
+    Ok(name)
 }
@@ -0,0 +1,76 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{Activate, ActivateResponse, ErrorReason},
+    kmip_types::{StateEnumeration, UniqueIdentifier},
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::{debug, trace};
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::{now_millis, object_attributes, retrieve_single_object},
+        KMS,
+    },
+    database::AtomicOperation,
+    error::KmsError,
+    kms_bail,
+    result::KResult,
+};
+
+pub async fn activate(
+    kms: &KMS,
+    request: Activate,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ActivateResponse> {
+    trace!("Activate: {}", serde_json::to_string(&request)?);
+
+    let mut owm = retrieve_single_object(
+        request.unique_identifier.as_ref(),
+        ObjectOperationType::Activate,
+        "Activate",
+        kms,
+        user,
+        params,
+    )
+    .await?;
+
+    // only pre-active objects can be activated
+    if owm.state != StateEnumeration::PreActive {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Wrong_Key_Lifecycle_State,
+            format!(
+                "Activate: the object {} is not pre-active: its state is {}",
+                owm.id, owm.state
+            )
+        ))
+    }
+
+    // the activation date is set to the current date and time
+    let now = now_millis();
+    let mut attributes = object_attributes(&owm);
+    attributes.activation_date = Some(now);
+    attributes.last_change_date = Some(now);
+    attributes.state = Some(StateEnumeration::Active);
+    if let Ok(key_block_attributes) = owm.object.attributes_mut() {
+        key_block_attributes.clone_from(&attributes);
+    }
+
+    kms.db
+        .atomic(
+            &owm.owner,
+            &[
+                AtomicOperation::UpdateObject((owm.id.clone(), owm.object, attributes, None)),
+                AtomicOperation::UpdateState((owm.id.clone(), StateEnumeration::Active)),
+            ],
+            params,
+        )
+        .await?;
+    // an activated certificate may become the OCSP responder of its issuer
+    kms.revocation_cache.clear()?;
+    debug!("Activated object {}", owm.id);
+
+    Ok(ActivateResponse {
+        unique_identifier: UniqueIdentifier::TextString(owm.id),
+    })
+}
@@ -0,0 +1,83 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{AddAttribute, AddAttributeResponse, ErrorReason},
+    kmip_types::{Attribute, Attributes},
+};
+use tracing::{debug, trace};
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::{
+            object_attributes, retrieve_object_for_update, retrieve_split_tags,
+            set_single_instance_value, single_instance_value, update_object_attributes,
+        },
+        KMS,
+    },
+    error::KmsError,
+    kms_bail,
+    result::KResult,
+};
+
+pub async fn add_attribute(
+    kms: &KMS,
+    request: AddAttribute,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<AddAttributeResponse> {
+    trace!("Add attribute: {}", serde_json::to_string(&request)?);
+
+    let owm = retrieve_object_for_update(
+        request.unique_identifier.as_ref(),
+        "Add Attribute",
+        kms,
+        user,
+        params,
+    )
+    .await?;
+    let mut attributes = object_attributes(&owm);
+
+    let mut tags = None;
+    if let Some(user_tags) = request.new_attribute.get_tags()? {
+        // the tags are added to the existing ones
+        Attributes::check_user_tags(&user_tags)?;
+        let (system_tags, existing_user_tags) = retrieve_split_tags(kms, &owm.id, params).await?;
+        tags = Some(
+            system_tags
+                .into_iter()
+                .chain(existing_user_tags)
+                .chain(user_tags)
+                .collect(),
+        );
+    } else if let Attribute::Link(link) = request.new_attribute {
+        // links are multi-instance: a new instance is added unless it already exists
+        if !attributes
+            .link
+            .as_ref()
+            .is_some_and(|links| links.contains(&link))
+        {
+            attributes.add_link(link.link_type, link.linked_object_identifier);
+        }
+    } else if let Attribute::Name(name) = request.new_attribute {
+        // names are multi-instance: a new instance is added unless it already exists
+        let names = attributes.name.get_or_insert_with(Vec::new);
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    } else {
+        // existing single-instance attributes cannot be changed by this operation
+        if single_instance_value(&attributes, &request.new_attribute)?.is_some() {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Attribute_Single_Valued,
+                "the attribute already exists: use Set Attribute or Modify Attribute to change \
+                 its value"
+                    .to_owned()
+            ))
+        }
+        set_single_instance_value(&mut attributes, request.new_attribute)?;
+    }
+
+    let unique_identifier = update_object_attributes(kms, owm, attributes, tags, params).await?;
+    kms.revocation_cache.clear()?;
+    debug!("Added an attribute to the object {unique_identifier}");
+    Ok(AddAttributeResponse { unique_identifier })
+}
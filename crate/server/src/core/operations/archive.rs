@@ -0,0 +1,42 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{Archive, ArchiveResponse},
+    kmip_types::UniqueIdentifier,
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::{debug, trace};
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::retrieve_single_object, KMS,
+    },
+    result::KResult,
+};
+
+pub async fn archive(
+    kms: &KMS,
+    request: Archive,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ArchiveResponse> {
+    trace!("Archive: {}", serde_json::to_string(&request)?);
+
+    let owm = retrieve_single_object(
+        request.unique_identifier.as_ref(),
+        ObjectOperationType::Archive,
+        "Archive",
+        kms,
+        user,
+        params,
+    )
+    .await?;
+
+    // the object is moved to the archive, with its state, tags and access rights
+    kms.db.archive(&owm.id, params).await?;
+    kms.revocation_cache.clear()?;
+    debug!("Archived object {}", owm.id);
+
+    Ok(ArchiveResponse {
+        unique_identifier: UniqueIdentifier::TextString(owm.id),
+    })
+}
@@ -0,0 +1,379 @@
+use std::collections::HashSet;
+
+use cosmian_kmip::kmip::{
+    kmip_objects::Object,
+    kmip_operations::ErrorReason,
+    kmip_types::{
+        Attribute, AttributeReference, Attributes, StateEnumeration, Tag, UniqueIdentifier,
+        UsageLimitsUnit,
+    },
+};
+use cosmian_kms_client::access::ObjectOperationType;
+
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    database::{initial_state, is_object_archived, object_with_metadata::ObjectWithMetadata},
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+/// Retrieve the single object whose attributes are updated by `user`
+pub(crate) async fn retrieve_object_for_update(
+    unique_identifier: Option<&UniqueIdentifier>,
+    operation: &str,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ObjectWithMetadata> {
+    retrieve_single_object(
+        unique_identifier,
+        ObjectOperationType::SetAttributes,
+        operation,
+        kms,
+        user,
+        params,
+    )
+    .await
+}
+
+/// Retrieve the single, non destroyed, object on which `user`
+/// performs an operation requiring the `operation_type` access right
+pub(crate) async fn retrieve_single_object(
+    unique_identifier: Option<&UniqueIdentifier>,
+    operation_type: ObjectOperationType,
+    operation: &str,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ObjectWithMetadata> {
+    // there must be an identifier
+    let uid_or_tags = unique_identifier
+        .ok_or(KmsError::UnsupportedPlaceholder)?
+        .as_str()
+        .with_context(|| format!("{operation}: the unique identifier must be a string"))?;
+
+    // destroyed objects are ignored
+    let mut owm_s = kms
+        .db
+        .retrieve(uid_or_tags, user, operation_type, params)
+        .await?
+        .into_values()
+        .filter(|owm| {
+            owm.state != StateEnumeration::Destroyed
+                && owm.state != StateEnumeration::Destroyed_Compromised
+        })
+        .collect::<Vec<ObjectWithMetadata>>();
+
+    // there can only be one object
+    let Some(owm) = owm_s.pop() else {
+        return Err(object_not_found(uid_or_tags, kms, params).await?)
+    };
+    if !owm_s.is_empty() {
+        return Err(KmsError::InvalidRequest(format!(
+            "{operation}: too many objects for {uid_or_tags}",
+        )))
+    }
+    Ok(owm)
+}
+
+/// The error returned when no object is found for `uid_or_tags`:
+/// `Object_Archived` if it has been archived, `Item_Not_Found` otherwise
+pub(crate) async fn object_not_found(
+    uid_or_tags: &str,
+    kms: &KMS,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<KmsError> {
+    let reason = if is_object_archived(uid_or_tags, kms, params).await? {
+        ErrorReason::Object_Archived
+    } else {
+        ErrorReason::Item_Not_Found
+    };
+    Ok(KmsError::KmipError(reason, uid_or_tags.to_owned()))
+}
+
+/// The attributes of the object: those of the key block for keys,
+/// those stored alongside the object otherwise (e.g. for certificates)
+pub(crate) fn object_attributes(owm: &ObjectWithMetadata) -> Attributes {
+    owm.object
+        .attributes()
+        .cloned()
+        .unwrap_or_else(|_| owm.attributes.clone())
+}
+
+/// The current date and time, in milliseconds since the epoch
+pub(crate) fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Set the attributes maintained by the server on an object being created or registered:
+/// its Initial, Original Creation and Last Change Dates, its Fresh flag,
+/// and its extractability, which defaults to an extractable and non sensitive object.
+/// The key block attributes of a key are set alike.
+pub(crate) fn set_initial_attributes(object: &mut Object, attributes: &mut Attributes) {
+    let now = now_millis();
+    let initialize = |attributes: &mut Attributes| {
+        attributes.initial_date = Some(now);
+        attributes.original_creation_date.get_or_insert(now);
+        attributes.last_change_date = Some(now);
+        attributes.fresh = Some(true);
+        let extractable = *attributes.extractable.get_or_insert(true);
+        attributes.never_extractable = Some(!extractable);
+        attributes.sensitive.get_or_insert(false);
+        attributes.state = Some(initial_state(attributes));
+    };
+    initialize(attributes);
+    if let Ok(key_block_attributes) = object.attributes_mut() {
+        initialize(key_block_attributes);
+    }
+}
+
+/// Persist the updated attributes of the object and, if any, its new tags.
+/// The Last Change Date of the object is updated.
+pub(crate) async fn update_object_attributes(
+    kms: &KMS,
+    mut owm: ObjectWithMetadata,
+    mut attributes: Attributes,
+    tags: Option<HashSet<String>>,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<UniqueIdentifier> {
+    attributes.last_change_date = Some(now_millis());
+    if let Ok(key_block_attributes) = owm.object.attributes_mut() {
+        key_block_attributes.clone_from(&attributes);
+    }
+    kms.db
+        .update_object(&owm.id, &owm.object, &attributes, tags.as_ref(), params)
+        .await?;
+    Ok(UniqueIdentifier::TextString(owm.id))
+}
+
+/// Consume the Usage Limits of the object, if any, for an operation on `data_length` bytes:
+/// the remaining count is decremented by the number of bytes or by one object,
+/// and the operation fails with `Usage_Limit_Exceeded` once the count is exhausted.
+///
+/// The count is read from the database and decremented under a lock,
+/// so that concurrent operations cannot exceed the limits.
+pub(crate) async fn consume_usage_limits(
+    kms: &KMS,
+    owm: &ObjectWithMetadata,
+    data_length: usize,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<()> {
+    if object_attributes(owm).usage_limits.is_none() {
+        return Ok(())
+    }
+    let _guard = kms.usage_limits_lock.lock().await;
+    // the object in hand may be stale, and its key unwrapped
+    let stored = kms
+        .db
+        .retrieve(&owm.id, &owm.owner, ObjectOperationType::Get, params)
+        .await?
+        .remove(&owm.id)
+        .ok_or_else(|| KmsError::ItemNotFound(owm.id.clone()))?;
+    let mut attributes = object_attributes(&stored);
+    let Some(usage_limits) = attributes.usage_limits.as_mut() else {
+        return Ok(())
+    };
+    let units = match usage_limits.usage_limits_unit {
+        UsageLimitsUnit::Byte => i64::try_from(data_length).unwrap_or(i64::MAX),
+        UsageLimitsUnit::Object => 1,
+    };
+    let remaining = usage_limits
+        .usage_limits_count
+        .unwrap_or(usage_limits.usage_limits_total);
+    if remaining < units {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Usage_Limit_Exceeded,
+            format!("the usage limits of the object {} are exhausted", owm.id)
+        ))
+    }
+    usage_limits.usage_limits_count = Some(remaining - units);
+    update_object_attributes(kms, stored, attributes, None, params).await?;
+    Ok(())
+}
+
+/// Retrieve the tags of the object, split into its system tags (starting with `_`),
+/// which are always preserved, and its user tags
+pub(crate) async fn retrieve_split_tags(
+    kms: &KMS,
+    uid: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<(HashSet<String>, HashSet<String>)> {
+    Ok(kms
+        .db
+        .retrieve_tags(uid, params)
+        .await?
+        .into_iter()
+        .partition(|tag| tag.starts_with('_')))
+}
+
+/// The value of the single-instance attribute of the same kind as `attribute`, if any
+pub(crate) fn single_instance_value(
+    attributes: &Attributes,
+    attribute: &Attribute,
+) -> KResult<Option<Attribute>> {
+    Ok(match attribute {
+        Attribute::ActivationDate(_) => attributes.activation_date.map(Attribute::ActivationDate),
+        Attribute::Comment(_) => attributes.comment.clone().map(Attribute::Comment),
+        Attribute::ContactInformation(_) => attributes
+            .contact_information
+            .clone()
+            .map(Attribute::ContactInformation),
+        Attribute::CryptographicParameters(_) => attributes
+            .cryptographic_parameters
+            .as_deref()
+            .cloned()
+            .map(Attribute::CryptographicParameters),
+        Attribute::CryptographicUsageMask(_) => attributes
+            .cryptographic_usage_mask
+            .map(Attribute::CryptographicUsageMask),
+        Attribute::Description(_) => attributes.description.clone().map(Attribute::Description),
+        Attribute::Extractable(_) => attributes.extractable.map(Attribute::Extractable),
+        Attribute::ObjectGroup(_) => attributes.object_group.clone().map(Attribute::ObjectGroup),
+        Attribute::ProcessStartDate(_) => attributes
+            .process_start_date
+            .map(Attribute::ProcessStartDate),
+        Attribute::ProtectStopDate(_) => {
+            attributes.protect_stop_date.map(Attribute::ProtectStopDate)
+        }
+        Attribute::Sensitive(_) => attributes.sensitive.map(Attribute::Sensitive),
+        Attribute::UsageLimits(_) => attributes.usage_limits.clone().map(Attribute::UsageLimits),
+        Attribute::VendorAttribute(va) => attributes
+            .vendor_attributes
+            .as_ref()
+            .and_then(|vas| {
+                vas.iter().find(|v| {
+                    v.vendor_identification == va.vendor_identification
+                        && v.attribute_name == va.attribute_name
+                })
+            })
+            .cloned()
+            .map(Attribute::VendorAttribute),
+        Attribute::Link(_) => kms_bail!(KmsError::KmipError(
+            ErrorReason::Multi_Valued_Attribute,
+            "links are multi-instance attributes".to_owned()
+        )),
+        Attribute::Name(_) => kms_bail!(KmsError::KmipError(
+            ErrorReason::Multi_Valued_Attribute,
+            "names are multi-instance attributes".to_owned()
+        )),
+    })
+}
+
+/// Set the value of a single-instance attribute, replacing the existing one if any
+pub(crate) fn set_single_instance_value(
+    attributes: &mut Attributes,
+    attribute: Attribute,
+) -> KResult<()> {
+    match attribute {
+        Attribute::ActivationDate(activation_date) => {
+            attributes.activation_date = Some(activation_date);
+        }
+        Attribute::Comment(comment) => attributes.comment = Some(comment),
+        Attribute::ContactInformation(contact_information) => {
+            attributes.contact_information = Some(contact_information);
+        }
+        Attribute::CryptographicParameters(cryptographic_parameters) => {
+            attributes.cryptographic_parameters = Some(Box::new(cryptographic_parameters));
+        }
+        Attribute::CryptographicUsageMask(cryptographic_usage_mask) => {
+            attributes.cryptographic_usage_mask = Some(cryptographic_usage_mask);
+        }
+        Attribute::Description(description) => attributes.description = Some(description),
+        Attribute::Extractable(extractable) => {
+            // once not extractable, an object remains so
+            if extractable && attributes.extractable == Some(false) {
+                kms_bail!(KmsError::KmipError(
+                    ErrorReason::Attribute_Read_Only,
+                    "a non extractable object cannot be made extractable".to_owned()
+                ))
+            }
+            attributes.extractable = Some(extractable);
+            if extractable {
+                attributes.never_extractable = Some(false);
+            }
+        }
+        Attribute::ObjectGroup(object_group) => attributes.object_group = Some(object_group),
+        Attribute::ProcessStartDate(process_start_date) => {
+            attributes.process_start_date = Some(process_start_date);
+        }
+        Attribute::ProtectStopDate(protect_stop_date) => {
+            attributes.protect_stop_date = Some(protect_stop_date);
+        }
+        Attribute::Sensitive(sensitive) => {
+            // once sensitive, an object remains so
+            if !sensitive && attributes.sensitive == Some(true) {
+                kms_bail!(KmsError::KmipError(
+                    ErrorReason::Attribute_Read_Only,
+                    "a sensitive object cannot be made non sensitive".to_owned()
+                ))
+            }
+            attributes.sensitive = Some(sensitive);
+        }
+        // the usage limits are set at creation, then only consumed by the server
+        Attribute::UsageLimits(_) => kms_bail!(KmsError::KmipError(
+            ErrorReason::Attribute_Read_Only,
+            "the usage limits can only be set at creation".to_owned()
+        )),
+        Attribute::VendorAttribute(va) => {
+            attributes.set_vendor_attribute(
+                &va.vendor_identification,
+                &va.attribute_name,
+                va.attribute_value,
+            );
+        }
+        Attribute::Link(_) => kms_bail!(KmsError::KmipError(
+            ErrorReason::Multi_Valued_Attribute,
+            "links are multi-instance attributes".to_owned()
+        )),
+        Attribute::Name(_) => kms_bail!(KmsError::KmipError(
+            ErrorReason::Multi_Valued_Attribute,
+            "names are multi-instance attributes".to_owned()
+        )),
+    }
+    Ok(())
+}
+
+/// Delete all the instances of the attribute designated by `reference`.
+/// Return `false` if the object has no such attribute.
+pub(crate) fn delete_referenced_attribute(
+    attributes: &mut Attributes,
+    reference: &AttributeReference,
+) -> KResult<bool> {
+    Ok(match reference {
+        AttributeReference::Standard(Tag::ActivationDate) => {
+            attributes.activation_date.take().is_some()
+        }
+        AttributeReference::Standard(Tag::CryptographicParameters) => {
+            attributes.cryptographic_parameters.take().is_some()
+        }
+        AttributeReference::Standard(Tag::Comment) => attributes.comment.take().is_some(),
+        AttributeReference::Standard(Tag::ContactInformation) => {
+            attributes.contact_information.take().is_some()
+        }
+        AttributeReference::Standard(Tag::Description) => attributes.description.take().is_some(),
+        AttributeReference::Standard(Tag::Link) => attributes.link.take().is_some(),
+        AttributeReference::Standard(Tag::Name) => attributes.name.take().is_some(),
+        AttributeReference::Standard(Tag::ObjectGroup) => attributes.object_group.take().is_some(),
+        AttributeReference::Standard(Tag::ProcessStartDate) => {
+            attributes.process_start_date.take().is_some()
+        }
+        AttributeReference::Standard(Tag::ProtectStopDate) => {
+            attributes.protect_stop_date.take().is_some()
+        }
+        AttributeReference::Vendor(va) => {
+            let exists = attributes
+                .get_vendor_attribute_value(&va.vendor_identification, &va.attribute_name)
+                .is_some();
+            attributes.remove_vendor_attribute(&va.vendor_identification, &va.attribute_name);
+            exists
+        }
+        // the `CryptographicUsageMask` is not deletable by the client (KMIP 2.1)
+        // and neither are the `UsageLimits`:
+        // deleting them would lift the usage restrictions of the key
+        AttributeReference::Standard(tag) => kms_bail!(KmsError::KmipError(
+            ErrorReason::Attribute_Read_Only,
+            format!("the attribute {tag} cannot be deleted")
+        )),
+    })
+}
@@ -0,0 +1,51 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{Cancel, CancelResponse},
+    kmip_types::CancellationResult,
+};
+use tracing::trace;
+
+use super::jobs::retrieve_job;
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    database::job::JobState,
+    result::KResult,
+};
+
+/// Cancel an operation processed asynchronously
+///
+/// Only a pending operation can be canceled: the result of a completed
+/// or failed operation is kept and the cancellation result reports it.
+pub(crate) async fn cancel(
+    kms: &KMS,
+    request: Cancel,
+    owner: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<CancelResponse> {
+    trace!("Cancel: {}", serde_json::to_string(&request)?);
+
+    let mut job = retrieve_job(kms, &request.asynchronous_correlation_value, owner, params).await?;
+    let mut state = job.state;
+    if state == JobState::Pending {
+        job.state = JobState::Canceled;
+        if kms.db.update_pending_job(&job, params).await? {
+            kms.jobs.abort(&job.id)?;
+            state = JobState::Canceled;
+        } else {
+            // the job completed in the meantime
+            state = retrieve_job(kms, &request.asynchronous_correlation_value, owner, params)
+                .await?
+                .state;
+        }
+    }
+
+    let cancellation_result = match state {
+        JobState::Canceled => CancellationResult::Canceled,
+        JobState::Completed => CancellationResult::Completed,
+        JobState::Failed => CancellationResult::Failed,
+        JobState::Pending => CancellationResult::UnableToCancel,
+    };
+    Ok(CancelResponse {
+        asynchronous_correlation_value: request.asynchronous_correlation_value,
+        cancellation_result,
+    })
+}
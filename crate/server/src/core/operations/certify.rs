@@ -17,17 +17,21 @@ use cosmian_kmip::{
 };
 use cosmian_kms_client::access::ObjectOperationType;
 use openssl::{
-    asn1::Asn1Time,
+    asn1::{Asn1Integer, Asn1Time},
+    bn::{BigNum, MsbOption},
     hash::MessageDigest,
+    nid::Nid,
     pkey::{PKey, Private, Public},
-    x509::{X509Name, X509Req, X509},
+    x509::{X509Extension, X509Name, X509Req, X509},
 };
 use tracing::trace;
 
 use crate::{
+    config::ServerParams,
     core::{
         certificate::retrieve_matching_private_key_and_certificate,
-        extra_database_params::ExtraDatabaseParams, KMS,
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::set_initial_attributes, KMS,
     },
     database::{retrieve_object_for_operation, AtomicOperation},
     error::KmsError,
@@ -36,6 +40,8 @@ use crate::{
 };
 
 const X509_VERSION3: i32 = 2;
+/// RFC 5280: serial numbers are positive and at most 20 bytes long
+const SERIAL_NUMBER_BITS: i32 = 159;
 
 pub async fn certify(
     kms: &KMS,
@@ -104,6 +110,7 @@ pub async fn certify(
             number_of_days,
             certificate_subject_name,
             certificate_public_key,
+            &kms.params,
         )?;
         (
             issued_certificate_id.clone(),
@@ -130,6 +137,28 @@ pub async fn certify(
             params,
         )
         .await?;
+        // A private key (typically the ID Placeholder set by a Create Key Pair
+        // in the same batch) stands for the public key it is linked to
+        if let Object::PrivateKey { .. } = public_key_owm.object {
+            let linked_public_key_id = public_key_owm
+                .object
+                .attributes()?
+                .get_link(LinkType::PublicKeyLink)
+                .ok_or_else(|| {
+                    KmsError::InvalidRequest(
+                        "Certify: no public key link found for the private key".to_string(),
+                    )
+                })?;
+            public_key_owm = retrieve_object_for_operation(
+                &linked_public_key_id,
+                ObjectOperationType::Certify,
+                kms,
+                user,
+                params,
+            )
+            .await?;
+        }
+        let public_key_id = public_key_owm.id.clone();
         let certificate_public_key = kmip_public_key_to_openssl(&public_key_owm.object)?;
         let certificate_subject_name = attributes
             .certificate_attributes
@@ -143,7 +172,7 @@ pub async fn certify(
         // Add link to the public key in certificate
         attributes.add_link(
             LinkType::PublicKeyLink,
-            LinkedObjectIdentifier::TextString(public_key_id.to_string()),
+            LinkedObjectIdentifier::TextString(public_key_id),
         );
         let (issued_certificate_id, issued_certificate) = build_certificate(
             &mut tags,
@@ -155,6 +184,7 @@ pub async fn certify(
             number_of_days,
             certificate_subject_name,
             certificate_public_key,
+            &kms.params,
         )?;
         // Add link to certificate in public key
         public_key_owm.object.attributes_mut()?.add_link(
@@ -191,6 +221,7 @@ pub async fn certify(
 
     // perform DB operations
     kms.db.atomic(user, &operations, params).await?;
+    kms.revocation_cache.clear()?;
 
     Ok(CertifyResponse {
         unique_identifier: UniqueIdentifier::TextString(certificate_id),
@@ -208,27 +239,32 @@ fn build_certificate(
     number_of_days: usize,
     subject_name: X509Name,
     certificate_public_key: PKey<Public>,
+    server_params: &ServerParams,
 ) -> Result<(String, Object), KmsError> {
     // Create an X509 struct with the desired certificate information.
     let mut x509_builder = X509::builder().unwrap();
     x509_builder.set_version(X509_VERSION3)?;
+    // A random serial number identifies the certificate in the CRL of its issuer
+    let mut serial_number = BigNum::new()?;
+    serial_number.rand(SERIAL_NUMBER_BITS, MsbOption::MAYBE_ZERO, false)?;
+    x509_builder.set_serial_number(Asn1Integer::from_bn(&serial_number)?.as_ref())?;
     x509_builder.set_subject_name(subject_name.as_ref())?;
     x509_builder.set_pubkey(certificate_public_key.as_ref())?;
     x509_builder.set_not_before(now.as_ref())?;
-    // Sign the X509 struct with the PKey struct.
     x509_builder.set_not_after(
         Asn1Time::days_from_now(number_of_days as u32)
             .context("could not get a date in ASN.1")?
             .as_ref(),
     )?;
     x509_builder.set_issuer_name(issuer_x509.subject_name())?;
-    x509_builder.sign(issuer_pkey, MessageDigest::sha256())?;
 
     // Extensions
+    let mut requested_extensions = String::new();
     if let Some(extensions) =
         attributes.get_vendor_attribute_value(VENDOR_ID_COSMIAN, VENDOR_ATTR_X509_EXTENSION)
     {
         let extensions_as_str = String::from_utf8(extensions.to_vec())?;
+        requested_extensions.clone_from(&extensions_as_str);
 
         let context = x509_builder.x509v3_context(Some(issuer_x509), None);
 
@@ -236,7 +272,43 @@ fn build_certificate(
             .into_iter()
             .try_for_each(|extension| x509_builder.append_extension(extension))?;
     }
+    // Point to the CRL and OCSP responder served by this KMS,
+    // unless the requester provided its own
+    let served_extensions = [
+        (
+            server_params.crl_distribution_url.as_deref(),
+            "crlDistributionPoints",
+            Nid::CRL_DISTRIBUTION_POINTS,
+            "URI:",
+        ),
+        (
+            server_params.ocsp_responder_url.as_deref(),
+            "authorityInfoAccess",
+            Nid::INFO_ACCESS,
+            "OCSP;URI:",
+        ),
+    ];
+    for (url, name, nid, prefix) in served_extensions {
+        let Some(url) = url else { continue };
+        if requested_extensions.contains(name) {
+            continue
+        }
+        let context = x509_builder.x509v3_context(Some(issuer_x509), None);
+        #[allow(deprecated)]
+        let extension = X509Extension::new_nid(
+            None,
+            Some(&context),
+            nid,
+            &format!(
+                "{prefix}{}/{issuer_certificate_id}",
+                url.trim_end_matches('/')
+            ),
+        )?;
+        x509_builder.append_extension(extension)?;
+    }
 
+    // Sign the X509 struct with the PKey struct, once all the extensions are set
+    x509_builder.sign(issuer_pkey, MessageDigest::sha256())?;
     let x509 = x509_builder.build();
 
     // link the certificate to the issuer certificate
@@ -251,7 +323,8 @@ fn build_certificate(
     let certificate_attributes = CertificateAttributes::from(&x509);
     attributes.certificate_attributes = Some(Box::new(certificate_attributes));
 
-    let (issued_certificate_id, issued_certificate) = openssl_certificate_to_kmip(&x509)?;
+    let (issued_certificate_id, mut issued_certificate) = openssl_certificate_to_kmip(&x509)?;
+    set_initial_attributes(&mut issued_certificate, attributes);
     Ok((issued_certificate_id, issued_certificate))
 }
 
@@ -6,7 +6,10 @@ use cosmian_kmip::kmip::{
 use tracing::{debug, trace};
 
 use crate::{
-    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::set_initial_attributes, KMS,
+    },
     error::KmsError,
     kms_bail,
     result::KResult,
@@ -23,7 +26,7 @@ pub async fn create(
         kms_bail!(KmsError::UnsupportedPlaceholder)
     }
 
-    let (object, tags) = match &request.object_type {
+    let (mut object, tags) = match &request.object_type {
         ObjectType::SymmetricKey => kms.create_symmetric_key_and_tags(&request)?,
         ObjectType::PrivateKey => {
             kms.create_private_key_and_tags(&request, owner, params)
@@ -36,9 +39,11 @@ pub async fn create(
             )))
         }
     };
+    let mut attributes = object.attributes()?.clone();
+    set_initial_attributes(&mut object, &mut attributes);
     let uid = kms
         .db
-        .create(None, owner, &object, object.attributes()?, &tags, params)
+        .create(None, owner, &object, &attributes, &tags, params)
         .await?;
     debug!(
         "Created KMS Object of type {:?} with id {uid}",
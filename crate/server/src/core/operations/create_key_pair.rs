@@ -6,7 +6,10 @@ use tracing::{debug, trace};
 use uuid::Uuid;
 
 use crate::{
-    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::set_initial_attributes, KMS,
+    },
     database::AtomicOperation,
     error::KmsError,
     kms_bail,
@@ -27,15 +30,26 @@ pub async fn create_key_pair(
         kms_bail!(KmsError::UnsupportedPlaceholder)
     }
 
+    // the usage limits, which can only be set at creation, apply to the private key
+    let usage_limits = request
+        .private_key_attributes
+        .as_ref()
+        .or(request.common_attributes.as_ref())
+        .and_then(|attributes| attributes.usage_limits.clone());
+
     // generate uids and create the key pair and tags
     let sk_uid = Uuid::new_v4().to_string();
     let pk_uid = Uuid::new_v4().to_string();
-    let (key_pair, sk_tags, pk_tags) = kms.create_key_pair_and_tags(request, &sk_uid, &pk_uid)?;
+    let (mut key_pair, sk_tags, pk_tags) =
+        kms.create_key_pair_and_tags(request, &sk_uid, &pk_uid)?;
 
     trace!("create_key_pair: sk_uid: {sk_uid}, pk_uid: {pk_uid}");
+    key_pair.private_key_mut().attributes_mut()?.usage_limits = usage_limits;
 
-    let private_key_attributes = key_pair.private_key().attributes()?.clone();
-    let public_key_attributes = key_pair.public_key().attributes()?.clone();
+    let mut private_key_attributes = key_pair.private_key().attributes()?.clone();
+    set_initial_attributes(key_pair.private_key_mut(), &mut private_key_attributes);
+    let mut public_key_attributes = key_pair.public_key().attributes()?.clone();
+    set_initial_attributes(key_pair.public_key_mut(), &mut public_key_attributes);
 
     let operations = vec![
         AtomicOperation::Create((
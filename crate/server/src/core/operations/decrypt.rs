@@ -10,15 +10,20 @@ use cosmian_kmip::{
             ckm_rsa_aes_key_wrap::ckm_rsa_aes_key_unwrap,
             ckm_rsa_pkcs_oaep::ckm_rsa_pkcs_oaep_key_decrypt, default_cryptographic_parameters,
         },
-        symmetric::aead::{aead_decrypt, AeadCipher},
+        symmetric::{
+            aead::{aead_decrypt, AeadCipher},
+            aes_cbc::aes_cbc_pad_decrypt,
+            rfc5649::rfc5649_unwrap,
+        },
         DecryptionSystem,
     },
     kmip::{
         kmip_objects::{Object, ObjectType},
         kmip_operations::{Decrypt, DecryptResponse, ErrorReason},
         kmip_types::{
-            CryptographicAlgorithm, CryptographicParameters, CryptographicUsageMask, KeyFormatType,
-            PaddingMethod, StateEnumeration, UniqueIdentifier,
+            BlockCipherMode, CryptographicAlgorithm, CryptographicParameters,
+            CryptographicUsageMask, KeyFormatType, PaddingMethod, StateEnumeration,
+            UniqueIdentifier,
         },
     },
     openssl::kmip_private_key_to_openssl,
@@ -29,7 +34,13 @@ use tracing::trace;
 use zeroize::Zeroizing;
 
 use crate::{
-    core::{extra_database_params::ExtraDatabaseParams, operations::unwrap_key, KMS},
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::{
+            attributes_utils::consume_usage_limits, encrypt::check_cbc_padding, unwrap_key,
+        },
+        KMS,
+    },
     database::object_with_metadata::ObjectWithMetadata,
     error::KmsError,
     kms_bail,
@@ -65,14 +76,16 @@ pub async fn decrypt(
         owm.object
     );
 
-    match &owm.object {
-        Object::SymmetricKey { .. } => decrypt_with_aead(&request, &owm),
+    let response = match &owm.object {
+        Object::SymmetricKey { .. } => decrypt_with_symmetric_key(&request, &owm),
         Object::PrivateKey { .. } => decrypt_with_private_key(&request, &owm),
         other => kms_bail!(KmsError::NotSupported(format!(
             "decrypt: decryption with keys of type: {} is not supported",
             other.object_type()
         ))),
-    }
+    }?;
+    consume_usage_limits(kms, &owm, request.data.as_ref().map_or(0, Vec::len), params).await?;
+    Ok(response)
 }
 
 async fn get_key(
@@ -138,7 +151,10 @@ async fn get_key(
     Ok(owm)
 }
 
-fn decrypt_with_aead(request: &Decrypt, owm: &ObjectWithMetadata) -> KResult<DecryptResponse> {
+fn decrypt_with_symmetric_key(
+    request: &Decrypt,
+    owm: &ObjectWithMetadata,
+) -> KResult<DecryptResponse> {
     let ciphertext = request.data.as_ref().ok_or_else(|| {
         KmsError::InvalidRequest("Decrypt: data to decrypt must be provided".to_owned())
     })?;
@@ -161,6 +177,26 @@ fn decrypt_with_aead(request: &Decrypt, owm: &ObjectWithMetadata) -> KResult<Dec
                 .as_ref()
                 .and_then(|cp| cp.block_cipher_mode);
             let key_bytes = key_block.key_bytes()?;
+            let plaintext = match block_cipher_mode {
+                Some(BlockCipherMode::CBC) => {
+                    check_cbc_padding(request.cryptographic_parameters.as_ref())?;
+                    let iv = request.iv_counter_nonce.as_ref().ok_or_else(|| {
+                        KmsError::InvalidRequest("Decrypt: the IV must be provided".to_owned())
+                    })?;
+                    Some(aes_cbc_pad_decrypt(&key_bytes, iv, ciphertext)?)
+                }
+                Some(BlockCipherMode::AESKeyWrapPadding) => {
+                    Some(rfc5649_unwrap(ciphertext, &key_bytes)?)
+                }
+                _ => None,
+            };
+            if let Some(plaintext) = plaintext {
+                return Ok(DecryptResponse {
+                    unique_identifier: UniqueIdentifier::TextString(owm.id.to_string()),
+                    data: Some(plaintext),
+                    correlation_value: request.correlation_value.clone(),
+                })
+            }
             let aead = AeadCipher::from_algorithm_and_key_size(
                 cryptographic_algorithm,
                 block_cipher_mode,
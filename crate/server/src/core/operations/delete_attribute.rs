@@ -0,0 +1,109 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{DeleteAttribute, DeleteAttributeResponse, ErrorReason},
+    kmip_types::{Attribute, AttributeReference, Attributes},
+};
+use tracing::{debug, trace};
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::{
+            delete_referenced_attribute, object_attributes, retrieve_object_for_update,
+            retrieve_split_tags, single_instance_value, update_object_attributes,
+        },
+        KMS,
+    },
+    error::KmsError,
+    kms_bail,
+    result::KResult,
+};
+
+pub async fn delete_attribute(
+    kms: &KMS,
+    request: DeleteAttribute,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<DeleteAttributeResponse> {
+    trace!("Delete attribute: {}", serde_json::to_string(&request)?);
+
+    let owm = retrieve_object_for_update(
+        request.unique_identifier.as_ref(),
+        "Delete Attribute",
+        kms,
+        user,
+        params,
+    )
+    .await?;
+    let mut attributes = object_attributes(&owm);
+    let (system_tags, mut user_tags) = retrieve_split_tags(kms, &owm.id, params).await?;
+    let mut tags_updated = false;
+
+    // delete the current attribute instance
+    if let Some(current_attribute) = &request.current_attribute {
+        if let Some(current_tags) = current_attribute.get_tags()? {
+            // only user tags can be deleted, the system tags are preserved
+            Attributes::check_user_tags(&current_tags)?;
+            if !current_tags.is_subset(&user_tags) {
+                kms_bail!(KmsError::KmipError(
+                    ErrorReason::Attribute_Instance_Not_Found,
+                    "the tags to delete are not all tags of the object".to_owned()
+                ))
+            }
+            user_tags.retain(|tag| !current_tags.contains(tag));
+            tags_updated = true;
+        } else if let Attribute::Link(link) = current_attribute {
+            let links = attributes.link.get_or_insert_with(Vec::new);
+            let Some(position) = links.iter().position(|l| l == link) else {
+                kms_bail!(KmsError::KmipError(
+                    ErrorReason::Attribute_Instance_Not_Found,
+                    format!("no such link of type {}", link.link_type)
+                ))
+            };
+            links.remove(position);
+            if links.is_empty() {
+                attributes.link = None;
+            }
+        } else if let Attribute::Name(name) = current_attribute {
+            let names = attributes.name.get_or_insert_with(Vec::new);
+            let Some(position) = names.iter().position(|n| n == name) else {
+                kms_bail!(KmsError::KmipError(
+                    ErrorReason::Attribute_Instance_Not_Found,
+                    format!("no such name: {}", name.name_value)
+                ))
+            };
+            names.remove(position);
+            if names.is_empty() {
+                attributes.name = None;
+            }
+        } else {
+            if single_instance_value(&attributes, current_attribute)?.as_ref()
+                != Some(current_attribute)
+            {
+                kms_bail!(KmsError::KmipError(
+                    ErrorReason::Attribute_Instance_Not_Found,
+                    "the current attribute does not match the value of the object".to_owned()
+                ))
+            }
+            delete_referenced_attribute(&mut attributes, &current_attribute.reference())?;
+        }
+    }
+
+    // delete all the instances of the referenced attributes
+    for reference in request.attribute_references.iter().flatten() {
+        if reference == &AttributeReference::tags_reference() {
+            user_tags.clear();
+            tags_updated = true;
+        } else if !delete_referenced_attribute(&mut attributes, reference)? {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Attribute_Not_Found,
+                format!("the object has no attribute {reference:?}")
+            ))
+        }
+    }
+
+    let tags = tags_updated.then(|| system_tags.into_iter().chain(user_tags).collect());
+    let unique_identifier = update_object_attributes(kms, owm, attributes, tags, params).await?;
+    kms.revocation_cache.clear()?;
+    debug!("Deleted attributes of the object {unique_identifier}");
+    Ok(DeleteAttributeResponse { unique_identifier })
+}
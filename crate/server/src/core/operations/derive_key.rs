@@ -0,0 +1,193 @@
+use cosmian_kmip::{
+    crypto::{key_derivation::derive_key_material, symmetric::create_symmetric_key_kmip_object},
+    kmip::{
+        kmip_data_structures::{KeyBlock, KeyMaterial, KeyValue},
+        kmip_objects::{Object, ObjectType},
+        kmip_operations::{DeriveKey, DeriveKeyResponse, ErrorReason},
+        kmip_types::{
+            Attributes, CryptographicUsageMask, KeyFormatType, LinkType, LinkedObjectIdentifier,
+            SecretDataType, StateEnumeration, UniqueIdentifier,
+        },
+    },
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::{debug, trace};
+use uuid::Uuid;
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::{attributes_utils::set_initial_attributes, unwrap_key},
+        KMS,
+    },
+    database::{object_with_metadata::ObjectWithMetadata, AtomicOperation},
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+pub async fn derive_key(
+    kms: &KMS,
+    request: DeriveKey,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<DeriveKeyResponse> {
+    trace!("DeriveKey: {}", serde_json::to_string(&request)?);
+
+    let attributes = &request.attributes;
+
+    // the length of the derived key, in bits
+    let cryptographic_length = attributes.cryptographic_length.ok_or_else(|| {
+        KmsError::InvalidRequest(
+            "DeriveKey: the cryptographic length of the derived key must be specified".to_owned(),
+        )
+    })?;
+    if cryptographic_length <= 0 || cryptographic_length % 8 != 0 {
+        kms_bail!(KmsError::InvalidRequest(format!(
+            "DeriveKey: invalid cryptographic length: {cryptographic_length}. It must be a \
+             positive multiple of 8 bits"
+        )))
+    }
+
+    // recover tags
+    let mut tags = attributes.get_tags();
+    Attributes::check_user_tags(&tags)?;
+
+    // retrieve the base key
+    let mut base_owm = get_base_key(kms, &request.object_unique_identifier, user, params).await?;
+    if !base_owm
+        .object
+        .attributes()?
+        .is_usage_authorized_for(CryptographicUsageMask::DeriveKey)?
+    {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Incompatible_Cryptographic_Usage_Mask,
+            "CryptographicUsageMask not authorized for DeriveKey".to_owned(),
+        ))
+    }
+
+    // derive the key material using an unwrapped copy of the base key
+    let derived_key = {
+        let mut base_object = base_owm.object.clone();
+        if base_object.key_wrapping_data().is_some() {
+            unwrap_key(base_object.key_block_mut()?, kms, &base_owm.owner, params).await?;
+        }
+        let base_key = base_object.key_block()?.key_bytes()?;
+        derive_key_material(
+            request.derivation_method,
+            &base_key,
+            &request.derivation_parameters,
+            cryptographic_length as usize / 8,
+        )?
+    };
+
+    // build the derived object
+    let mut object = match request.object_type {
+        ObjectType::SymmetricKey => {
+            let cryptographic_algorithm = attributes.cryptographic_algorithm.ok_or_else(|| {
+                KmsError::InvalidRequest(
+                    "DeriveKey: the cryptographic algorithm must be specified for a symmetric key"
+                        .to_owned(),
+                )
+            })?;
+            tags.insert("_kk".to_owned());
+            create_symmetric_key_kmip_object(&derived_key, cryptographic_algorithm)
+        }
+        ObjectType::SecretData => Object::SecretData {
+            secret_data_type: SecretDataType::Seed,
+            key_block: KeyBlock {
+                key_format_type: KeyFormatType::Raw,
+                key_compression_type: None,
+                key_value: KeyValue {
+                    key_material: KeyMaterial::ByteString(derived_key),
+                    attributes: Some(Box::new(Attributes {
+                        object_type: Some(ObjectType::SecretData),
+                        cryptographic_length: Some(cryptographic_length),
+                        cryptographic_usage_mask: Some(CryptographicUsageMask::DeriveKey),
+                        key_format_type: Some(KeyFormatType::Raw),
+                        ..Attributes::default()
+                    })),
+                },
+                cryptographic_algorithm: None,
+                cryptographic_length: None,
+                key_wrapping_data: None,
+            },
+        },
+        other => kms_bail!(KmsError::InvalidRequest(format!(
+            "DeriveKey: the derivation of a {other:?} is not supported"
+        ))),
+    };
+    // honor the usage mask of the request, if any
+    if let Some(usage_mask) = attributes.cryptographic_usage_mask {
+        object.attributes_mut()?.cryptographic_usage_mask = Some(usage_mask);
+    }
+
+    // link the derived object and its base object
+    let uid = Uuid::new_v4().to_string();
+    object.attributes_mut()?.add_link(
+        LinkType::DerivationBaseObjectLink,
+        LinkedObjectIdentifier::TextString(base_owm.id.clone()),
+    );
+    base_owm.object.attributes_mut()?.add_link(
+        LinkType::DerivedKeyLink,
+        LinkedObjectIdentifier::TextString(uid.clone()),
+    );
+    let mut object_attributes = object.attributes()?.clone();
+    set_initial_attributes(&mut object, &mut object_attributes);
+    let base_attributes = base_owm.object.attributes_mut()?;
+    base_attributes.last_change_date = object_attributes.initial_date;
+    let base_attributes = base_attributes.clone();
+
+    let operations = [
+        AtomicOperation::Create((uid.clone(), object, object_attributes, tags)),
+        AtomicOperation::UpdateObject((base_owm.id, base_owm.object, base_attributes, None)),
+    ];
+    kms.db.atomic(user, &operations, params).await?;
+
+    debug!("Derived key: {uid}");
+    Ok(DeriveKeyResponse {
+        unique_identifier: UniqueIdentifier::TextString(uid),
+    })
+}
+
+/// Retrieve the base key of the derivation: an active symmetric key or secret data.
+///
+/// The key is returned as stored, i.e. possibly wrapped.
+async fn get_base_key(
+    kms: &KMS,
+    unique_identifier: &UniqueIdentifier,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ObjectWithMetadata> {
+    let uid_or_tags = unique_identifier
+        .as_str()
+        .context("DeriveKey: object_unique_identifier must be a string")?;
+    trace!("derive_key: uid_or_tags: {uid_or_tags}");
+
+    // retrieve from tags or use passed identifier
+    let mut owm_s = kms
+        .db
+        .retrieve(uid_or_tags, user, ObjectOperationType::Derive, params)
+        .await?
+        .into_values()
+        .filter(|owm| {
+            owm.state == StateEnumeration::Active
+                && matches!(
+                    owm.object.object_type(),
+                    ObjectType::SymmetricKey | ObjectType::SecretData
+                )
+        })
+        .collect::<Vec<ObjectWithMetadata>>();
+
+    // there can only be one key
+    let owm = owm_s
+        .pop()
+        .ok_or_else(|| KmsError::KmipError(ErrorReason::Item_Not_Found, uid_or_tags.to_string()))?;
+
+    if !owm_s.is_empty() {
+        return Err(KmsError::InvalidRequest(format!(
+            "derive_key: too many objects for key {uid_or_tags}",
+        )))
+    }
+    Ok(owm)
+}
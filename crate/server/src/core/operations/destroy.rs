@@ -16,7 +16,10 @@ use zeroize::Zeroizing;
 
 use crate::{
     core::{
-        cover_crypt::destroy_user_decryption_keys, extra_database_params::ExtraDatabaseParams, KMS,
+        cover_crypt::destroy_user_decryption_keys,
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::{now_millis, object_attributes},
+        KMS,
     },
     database::object_with_metadata::ObjectWithMetadata,
     error::KmsError,
@@ -47,6 +50,8 @@ pub async fn destroy_operation(
         HashSet::new(),
     )
     .await?;
+    // the destroyed certificates must show in the next CRL and OCSP responses
+    kms.revocation_cache.clear()?;
     Ok(DestroyResponse {
         unique_identifier: uid_or_tags.clone(),
     })
@@ -92,7 +97,7 @@ pub(crate) async fn recursively_destroy_key<'a: 'async_recursion>(
         match object_type {
             SymmetricKey | ObjectType::Certificate => {
                 // destroy the key
-                destroy_key_core(&owm.id, &mut owm.object, owm.state, kms, params).await?;
+                destroy_key_core(&mut owm, kms, params).await?;
             }
             PrivateKey => {
                 //add this key to the ids to skip
@@ -119,7 +124,7 @@ pub(crate) async fn recursively_destroy_key<'a: 'async_recursion>(
                 }
 
                 // destroy the private key
-                destroy_key_core(&owm.id, &mut owm.object, owm.state, kms, params).await?;
+                destroy_key_core(&mut owm, kms, params).await?;
             }
             PublicKey => {
                 //add this key to the ids to skip
@@ -141,7 +146,7 @@ pub(crate) async fn recursively_destroy_key<'a: 'async_recursion>(
                 }
 
                 // destroy the public key
-                destroy_key_core(&owm.id, &mut owm.object, owm.state, kms, params).await?;
+                destroy_key_core(&mut owm, kms, params).await?;
             }
             x => kms_bail!(KmsError::NotSupported(format!(
                 "destroy operation is not supported for object type {x:?}"
@@ -154,14 +159,13 @@ pub(crate) async fn recursively_destroy_key<'a: 'async_recursion>(
 
 /// Destroy a key, knowing the object and state
 async fn destroy_key_core(
-    unique_identifier: &str,
-    object: &mut Object,
-    state: StateEnumeration,
+    owm: &mut ObjectWithMetadata,
     kms: &KMS,
     params: Option<&ExtraDatabaseParams>,
 ) -> KResult<()> {
+    let unique_identifier = owm.id.as_str();
     // map the state to the new state
-    let new_state = match state {
+    let new_state = match owm.state {
         StateEnumeration::Active => {
             return Err(KmsError::InvalidRequest(format!(
                 "Object with unique identifier: {unique_identifier} is active. It must be revoked \
@@ -174,25 +178,26 @@ async fn destroy_key_core(
         StateEnumeration::Destroyed | StateEnumeration::Destroyed_Compromised => return Ok(()),
     };
 
+    // the server records the destruction date
+    let mut attributes = object_attributes(owm);
+    let now = now_millis();
+    attributes.destroy_date = Some(now);
+    attributes.last_change_date = Some(now);
+    attributes.state = Some(new_state);
+
     // the KMIP specs mandates that e KeyMaterial be destroyed
-    if let Object::Certificate { .. } = object {
+    if let Object::Certificate { .. } = owm.object {
         trace!("Certificate destroying");
     } else {
-        let key_block = object.key_block_mut()?;
+        let key_block = owm.object.key_block_mut()?;
         key_block.key_value = KeyValue {
             key_material: KeyMaterial::ByteString(Zeroizing::from(vec![])),
-            attributes: key_block.key_value.attributes.clone(),
+            attributes: Some(Box::new(attributes.clone())),
         };
     }
 
     kms.db
-        .update_object(
-            unique_identifier,
-            object,
-            object.attributes()?,
-            None,
-            params,
-        )
+        .update_object(unique_identifier, &owm.object, &attributes, None, params)
         .await?;
 
     kms.db
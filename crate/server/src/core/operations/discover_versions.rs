@@ -0,0 +1,40 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{DiscoverVersions, DiscoverVersionsResponse},
+    kmip_types::ProtocolVersion,
+};
+use tracing::trace;
+
+use crate::result::KResult;
+
+/// The protocol versions supported by the server,
+/// in decreasing order of preference
+///
+/// KMIP 1.x messages are converted from and to KMIP 2.1 by the message layer
+pub(super) fn supported_protocol_versions() -> Vec<ProtocolVersion> {
+    let kmip_1_x = (0..=4).rev().map(|protocol_version_minor| ProtocolVersion {
+        protocol_version_major: 1,
+        protocol_version_minor,
+    });
+    std::iter::once(ProtocolVersion::default())
+        .chain(kmip_1_x)
+        .collect()
+}
+
+pub(crate) async fn discover_versions(
+    request: DiscoverVersions,
+) -> KResult<DiscoverVersionsResponse> {
+    trace!("DiscoverVersions: {}", serde_json::to_string(&request)?);
+
+    let supported = supported_protocol_versions();
+    let protocol_version = match request.protocol_version {
+        // only the versions supported by both the client and the server
+        Some(client_versions) => supported
+            .into_iter()
+            .filter(|version| client_versions.contains(version))
+            .collect(),
+        None => supported,
+    };
+    Ok(DiscoverVersionsResponse {
+        protocol_version: Some(protocol_version),
+    })
+}
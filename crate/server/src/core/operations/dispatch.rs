@@ -1,7 +1,9 @@
 use cosmian_kmip::kmip::{
     kmip_operations::{
-        Certify, Create, CreateKeyPair, Decrypt, Destroy, Encrypt, Export, Get, GetAttributes,
-        Import, Locate, Operation, ReKeyKeyPair, Revoke,
+        Activate, AddAttribute, Archive, Cancel, Certify, Create, CreateKeyPair, Decrypt,
+        DeleteAttribute, DeriveKey, Destroy, DiscoverVersions, Encrypt, Export, Get, GetAttributes,
+        Import, Locate, MACVerify, ModifyAttribute, Operation, Query, RNGRetrieve, RNGSeed, ReKey,
+        ReKeyKeyPair, Recover, Revoke, SetAttribute, Sign, SignatureVerify, Validate, MAC,
     },
     ttlv::{deserializer::from_ttlv, TTLV},
 };
@@ -21,6 +23,26 @@ pub async fn dispatch(
     database_params: Option<&ExtraDatabaseParams>,
 ) -> KResult<Operation> {
     Ok(match ttlv.tag.as_str() {
+        "Activate" => {
+            let req = from_ttlv::<Activate>(ttlv)?;
+            let resp = kms.activate(req, user, database_params).await?;
+            Operation::ActivateResponse(resp)
+        }
+        "AddAttribute" => {
+            let req = from_ttlv::<AddAttribute>(ttlv)?;
+            let resp = kms.add_attribute(req, user, database_params).await?;
+            Operation::AddAttributeResponse(resp)
+        }
+        "Archive" => {
+            let req = from_ttlv::<Archive>(ttlv)?;
+            let resp = kms.archive(req, user, database_params).await?;
+            Operation::ArchiveResponse(resp)
+        }
+        "Cancel" => {
+            let req = from_ttlv::<Cancel>(ttlv)?;
+            let resp = kms.cancel(req, user, database_params).await?;
+            Operation::CancelResponse(resp)
+        }
         "Certify" => {
             let req = from_ttlv::<Certify>(ttlv)?;
             let resp = kms.certify(req, user, database_params).await?;
@@ -41,11 +63,26 @@ pub async fn dispatch(
             let resp = kms.decrypt(req, user, database_params).await?;
             Operation::DecryptResponse(resp)
         }
+        "DeleteAttribute" => {
+            let req = from_ttlv::<DeleteAttribute>(ttlv)?;
+            let resp = kms.delete_attribute(req, user, database_params).await?;
+            Operation::DeleteAttributeResponse(resp)
+        }
+        "DeriveKey" => {
+            let req = from_ttlv::<DeriveKey>(ttlv)?;
+            let resp = kms.derive_key(req, user, database_params).await?;
+            Operation::DeriveKeyResponse(resp)
+        }
         "Destroy" => {
             let req = from_ttlv::<Destroy>(ttlv)?;
             let resp = kms.destroy(req, user, database_params).await?;
             Operation::DestroyResponse(resp)
         }
+        "DiscoverVersions" => {
+            let req = from_ttlv::<DiscoverVersions>(ttlv)?;
+            let resp = kms.discover_versions(req).await?;
+            Operation::DiscoverVersionsResponse(resp)
+        }
         "Encrypt" => {
             let req = from_ttlv::<Encrypt>(ttlv)?;
             let resp = kms.encrypt(req, user, database_params).await?;
@@ -76,16 +113,76 @@ pub async fn dispatch(
             let resp = kms.locate(req, user, database_params).await?;
             Operation::LocateResponse(resp)
         }
+        "ModifyAttribute" => {
+            let req = from_ttlv::<ModifyAttribute>(ttlv)?;
+            let resp = kms.modify_attribute(req, user, database_params).await?;
+            Operation::ModifyAttributeResponse(resp)
+        }
+        "Query" => {
+            let req = from_ttlv::<Query>(ttlv)?;
+            let resp = kms.query(req).await?;
+            Operation::QueryResponse(resp)
+        }
+        "RNGRetrieve" => {
+            let req = from_ttlv::<RNGRetrieve>(ttlv)?;
+            let resp = kms.rng_retrieve(req, user).await?;
+            Operation::RNGRetrieveResponse(resp)
+        }
+        "RNGSeed" => {
+            let req = from_ttlv::<RNGSeed>(ttlv)?;
+            let resp = kms.rng_seed(req, user).await?;
+            Operation::RNGSeedResponse(resp)
+        }
+        "ReKey" => {
+            let req = from_ttlv::<ReKey>(ttlv)?;
+            let resp = kms.rekey(req, user, database_params).await?;
+            Operation::ReKeyResponse(resp)
+        }
         "ReKeyKeyPair" => {
             let req = from_ttlv::<ReKeyKeyPair>(ttlv)?;
             let resp = kms.rekey_keypair(req, user, database_params).await?;
             Operation::ReKeyKeyPairResponse(resp)
         }
+        "Recover" => {
+            let req = from_ttlv::<Recover>(ttlv)?;
+            let resp = kms.recover(req, user, database_params).await?;
+            Operation::RecoverResponse(resp)
+        }
         "Revoke" => {
             let req = from_ttlv::<Revoke>(ttlv)?;
             let resp = kms.revoke(req, user, database_params).await?;
             Operation::RevokeResponse(resp)
         }
+        "SetAttribute" => {
+            let req = from_ttlv::<SetAttribute>(ttlv)?;
+            let resp = kms.set_attribute(req, user, database_params).await?;
+            Operation::SetAttributeResponse(resp)
+        }
+        "Sign" => {
+            let req = from_ttlv::<Sign>(ttlv)?;
+            let resp = kms.sign(req, user, database_params).await?;
+            Operation::SignResponse(resp)
+        }
+        "SignatureVerify" => {
+            let req = from_ttlv::<SignatureVerify>(ttlv)?;
+            let resp = kms.signature_verify(req, user, database_params).await?;
+            Operation::SignatureVerifyResponse(resp)
+        }
+        "Validate" => {
+            let req = from_ttlv::<Validate>(ttlv)?;
+            let resp = kms.validate(req, user, database_params).await?;
+            Operation::ValidateResponse(resp)
+        }
+        "MAC" => {
+            let req = from_ttlv::<MAC>(ttlv)?;
+            let resp = kms.mac(req, user, database_params).await?;
+            Operation::MACResponse(resp)
+        }
+        "MACVerify" => {
+            let req = from_ttlv::<MACVerify>(ttlv)?;
+            let resp = kms.mac_verify(req, user, database_params).await?;
+            Operation::MACVerifyResponse(resp)
+        }
         x => kms_bail!(KmsError::RouteNotFound(format!("Operation: {x}"))),
     })
 }
@@ -10,15 +10,20 @@ use cosmian_kmip::{
             ckm_rsa_aes_key_wrap::ckm_rsa_aes_key_wrap,
             ckm_rsa_pkcs_oaep::ckm_rsa_pkcs_oaep_encrypt, default_cryptographic_parameters,
         },
-        symmetric::aead::{aead_encrypt, random_nonce, AeadCipher},
+        symmetric::{
+            aead::{aead_encrypt, random_nonce, AeadCipher},
+            aes_cbc::{aes_cbc_pad_encrypt, random_iv},
+            rfc5649::rfc5649_wrap,
+        },
         EncryptionSystem,
     },
     kmip::{
         kmip_objects::{Object, ObjectType},
         kmip_operations::{Encrypt, EncryptResponse, ErrorReason},
         kmip_types::{
-            CryptographicAlgorithm, CryptographicParameters, CryptographicUsageMask, KeyFormatType,
-            PaddingMethod, StateEnumeration, UniqueIdentifier,
+            BlockCipherMode, CryptographicAlgorithm, CryptographicParameters,
+            CryptographicUsageMask, KeyFormatType, PaddingMethod, StateEnumeration,
+            UniqueIdentifier,
         },
     },
     openssl::kmip_public_key_to_openssl,
@@ -32,7 +37,11 @@ use openssl::{
 use tracing::trace;
 
 use crate::{
-    core::{extra_database_params::ExtraDatabaseParams, operations::unwrap_key, KMS},
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::{attributes_utils::consume_usage_limits, unwrap_key},
+        KMS,
+    },
     database::object_with_metadata::ObjectWithMetadata,
     error::KmsError,
     kms_bail,
@@ -52,8 +61,8 @@ pub async fn encrypt(
     let owm = get_key(kms, &request, user, params).await?;
     trace!("get_encryption_system: unwrap done (if required)");
 
-    match &owm.object {
-        Object::SymmetricKey { .. } => encrypt_with_aead(&request, &owm),
+    let response = match &owm.object {
+        Object::SymmetricKey { .. } => encrypt_with_symmetric_key(&request, &owm),
         Object::PublicKey { .. } => encrypt_with_public_key(&request, &owm),
         Object::Certificate {
             certificate_value, ..
@@ -62,7 +71,10 @@ pub async fn encrypt(
             "encrypt: encryption with keys of type: {} is not supported",
             other.object_type()
         ))),
-    }
+    }?;
+    let data_length = request.data.as_ref().map_or(0, |data| data.len());
+    consume_usage_limits(kms, &owm, data_length, params).await?;
+    Ok(response)
 }
 
 async fn get_key(
@@ -128,7 +140,10 @@ async fn get_key(
     Ok(owm)
 }
 
-fn encrypt_with_aead(request: &Encrypt, owm: &ObjectWithMetadata) -> KResult<EncryptResponse> {
+fn encrypt_with_symmetric_key(
+    request: &Encrypt,
+    owm: &ObjectWithMetadata,
+) -> KResult<EncryptResponse> {
     // Make sure that the key used to encrypt can be used to encrypt.
     if !owm
         .object
@@ -163,6 +178,33 @@ fn encrypt_with_aead(request: &Encrypt, owm: &ObjectWithMetadata) -> KResult<Enc
                 .as_ref()
                 .and_then(|cp| cp.block_cipher_mode);
             let key_bytes = key_block.key_bytes()?;
+            match block_cipher_mode {
+                Some(BlockCipherMode::CBC) => {
+                    check_cbc_padding(request.cryptographic_parameters.as_ref())?;
+                    let iv = match &request.iv_counter_nonce {
+                        Some(iv) => iv.clone(),
+                        None => random_iv()?,
+                    };
+                    let ciphertext = aes_cbc_pad_encrypt(&key_bytes, &iv, plaintext)?;
+                    return Ok(EncryptResponse {
+                        unique_identifier: UniqueIdentifier::TextString(owm.id.to_string()),
+                        data: Some(ciphertext),
+                        iv_counter_nonce: Some(iv),
+                        correlation_value: request.correlation_value.clone(),
+                        authenticated_encryption_tag: None,
+                    })
+                }
+                Some(BlockCipherMode::AESKeyWrapPadding) => {
+                    return Ok(EncryptResponse {
+                        unique_identifier: UniqueIdentifier::TextString(owm.id.to_string()),
+                        data: Some(rfc5649_wrap(plaintext, &key_bytes)?),
+                        iv_counter_nonce: None,
+                        correlation_value: request.correlation_value.clone(),
+                        authenticated_encryption_tag: None,
+                    })
+                }
+                _ => {}
+            }
             let aead = AeadCipher::from_algorithm_and_key_size(
                 cryptographic_algorithm,
                 block_cipher_mode,
@@ -191,6 +233,18 @@ fn encrypt_with_aead(request: &Encrypt, owm: &ObjectWithMetadata) -> KResult<Enc
     }
 }
 
+/// AES CBC is only supported with PKCS#5 padding (`CKM_AES_CBC_PAD`)
+pub(crate) fn check_cbc_padding(
+    cryptographic_parameters: Option<&CryptographicParameters>,
+) -> KResult<()> {
+    match cryptographic_parameters.and_then(|cp| cp.padding_method) {
+        None | Some(PaddingMethod::PKCS5) => Ok(()),
+        Some(other) => Err(KmsError::NotSupported(format!(
+            "AES CBC is only supported with PKCS5 padding, not: {other:?}"
+        ))),
+    }
+}
+
 fn encrypt_with_public_key(
     request: &Encrypt,
     owm: &ObjectWithMetadata,
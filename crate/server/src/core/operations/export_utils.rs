@@ -2,7 +2,7 @@ use cosmian_kmip::{
     kmip::{
         kmip_data_structures::{KeyBlock, KeyMaterial, KeyValue, KeyWrappingSpecification},
         kmip_objects::{Object, ObjectType},
-        kmip_operations::{Export, ExportResponse},
+        kmip_operations::{ErrorReason, Export, ExportResponse},
         kmip_types::{
             CryptographicAlgorithm, CryptographicUsageMask, KeyFormatType, KeyWrapType, LinkType,
             StateEnumeration, UniqueIdentifier,
@@ -26,7 +26,10 @@ use crate::{
     core::{
         certificate::retrieve_certificate_for_private_key,
         extra_database_params::ExtraDatabaseParams,
-        operations::{import::add_imported_links_to_attributes, unwrap_key, wrapping::wrap_key},
+        operations::{
+            attributes_utils::object_attributes, import::add_imported_links_to_attributes,
+            unwrap_key, wrapping::wrap_key,
+        },
         KMS,
     },
     database::{object_with_metadata::ObjectWithMetadata, retrieve_object_for_operation},
@@ -59,6 +62,21 @@ pub async fn export_get(
     let object_type = owm.object.object_type();
     let export = operation_type == ObjectOperationType::Export;
 
+    // the key material of the private and symmetric keys is subject to their extractability
+    let destroyed = owm.state == StateEnumeration::Destroyed
+        || owm.state == StateEnumeration::Destroyed_Compromised;
+    let exported_key = matches!(
+        object_type,
+        ObjectType::PrivateKey | ObjectType::SymmetricKey
+    ) && !destroyed;
+    let fresh_key = if exported_key {
+        check_extractable(&owm, &request)?;
+        let attributes = object_attributes(&owm);
+        (attributes.fresh != Some(false)).then(|| (owm.object.clone(), attributes))
+    } else {
+        None
+    };
+
     // export based on the Object type
     match object_type {
         ObjectType::PrivateKey => {
@@ -174,6 +192,17 @@ pub async fn export_get(
         }
     };
 
+    // a key is no longer fresh once its key material has been exported
+    if let Some((mut object, mut attributes)) = fresh_key {
+        attributes.fresh = Some(false);
+        if let Ok(key_block_attributes) = object.attributes_mut() {
+            key_block_attributes.fresh = Some(false);
+        }
+        kms.db
+            .update_object(&owm.id, &object, &attributes, None, params)
+            .await?;
+    }
+
     Ok(ExportResponse {
         object_type: owm.object.object_type(),
         unique_identifier: UniqueIdentifier::TextString(owm.id),
@@ -182,6 +211,30 @@ pub async fn export_get(
     })
 }
 
+/// Check that the key material may be exported as requested:
+/// never for a non extractable key, and only wrapped for a sensitive key
+fn check_extractable(owm: &ObjectWithMetadata, request: &Export) -> KResult<()> {
+    let attributes = object_attributes(owm);
+    if attributes.extractable == Some(false) {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Not_Extractable,
+            format!("the key {} is not extractable", owm.id)
+        ))
+    }
+    if attributes.sensitive == Some(true) {
+        let is_wrapped = owm.object.key_block()?.key_wrapping_data.is_some();
+        let exported_in_clear = request.key_wrapping_specification.is_none()
+            && (!is_wrapped || request.key_wrap_type == Some(KeyWrapType::NotWrapped));
+        if exported_in_clear {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Sensitive,
+                format!("the sensitive key {} can only be exported wrapped", owm.id)
+            ))
+        }
+    }
+    Ok(())
+}
+
 async fn process_private_key(
     object_with_metadata: &mut ObjectWithMetadata,
     key_format_type: &Option<KeyFormatType>,
@@ -27,19 +27,53 @@ use crate::{
 };
 
 /// All the tags that can be retrieved
-const ALL_TAGS: [Tag; 10] = [
+const ALL_TAGS: [Tag; 31] = [
     Tag::ActivationDate,
+    Tag::Comment,
+    Tag::CompromiseDate,
+    Tag::CompromiseOccurrenceDate,
+    Tag::ContactInformation,
     Tag::CryptographicAlgorithm,
     Tag::CryptographicLength,
     Tag::CryptographicParameters,
     Tag::CryptographicDomainParameters,
     Tag::CryptographicUsageMask,
+    Tag::DeactivationDate,
+    Tag::Description,
+    Tag::DestroyDate,
+    Tag::Extractable,
+    Tag::Fresh,
+    Tag::InitialDate,
     Tag::KeyFormatType,
+    Tag::LastChangeDate,
+    Tag::Name,
+    Tag::NeverExtractable,
+    Tag::ObjectGroup,
+    Tag::OriginalCreationDate,
+    Tag::ProcessStartDate,
+    Tag::ProtectStopDate,
+    Tag::RevocationReason,
+    Tag::Sensitive,
+    Tag::State,
+    Tag::UsageLimits,
     Tag::Certificate,
     Tag::PrivateKey,
     Tag::PublicKey,
 ];
 
+/// The attributes of a key, whose cryptographic attributes are those of its default format
+fn with_default_format_attributes(attributes: Attributes, default_kmip: &Object) -> Attributes {
+    let default_attributes = default_kmip.attributes().cloned().unwrap_or_default();
+    Attributes {
+        cryptographic_algorithm: default_attributes.cryptographic_algorithm,
+        cryptographic_domain_parameters: default_attributes.cryptographic_domain_parameters,
+        cryptographic_length: default_attributes.cryptographic_length,
+        cryptographic_usage_mask: default_attributes.cryptographic_usage_mask,
+        key_format_type: default_attributes.key_format_type,
+        ..attributes
+    }
+}
+
 pub async fn get_attributes(
     kms: &KMS,
     request: GetAttributes,
@@ -93,15 +127,7 @@ pub async fn get_attributes(
                     &pkey,
                     attributes.cryptographic_usage_mask,
                 )?;
-                let mut default_attributes = default_kmip.attributes().cloned().unwrap_or_default();
-                default_attributes.object_type = Some(object_type);
-                //re-add the vendor attributes
-                default_attributes
-                    .vendor_attributes
-                    .clone_from(&attributes.vendor_attributes);
-                // re-add the links
-                default_attributes.link.clone_from(&attributes.link);
-                default_attributes
+                with_default_format_attributes(*attributes, &default_kmip)
             }
         }
         Object::PublicKey { key_block } => {
@@ -117,15 +143,7 @@ pub async fn get_attributes(
                     &pkey,
                     attributes.cryptographic_usage_mask,
                 )?;
-                let mut default_attributes = default_kmip.attributes().cloned().unwrap_or_default();
-                default_attributes.object_type = Some(object_type);
-                //re-add the vendor attributes
-                default_attributes
-                    .vendor_attributes
-                    .clone_from(&attributes.vendor_attributes);
-                // re-add the links
-                default_attributes.link.clone_from(&attributes.link);
-                default_attributes
+                with_default_format_attributes(*attributes, &default_kmip)
             }
         }
         Object::SymmetricKey { key_block } => {
@@ -180,6 +198,19 @@ pub async fn get_attributes(
                 Tag::ActivationDate => {
                     res.activation_date = attributes.activation_date;
                 }
+                Tag::Comment => {
+                    res.comment.clone_from(&attributes.comment);
+                }
+                Tag::CompromiseDate => {
+                    res.compromise_date = attributes.compromise_date;
+                }
+                Tag::CompromiseOccurrenceDate => {
+                    res.compromise_occurrence_date = attributes.compromise_occurrence_date;
+                }
+                Tag::ContactInformation => {
+                    res.contact_information
+                        .clone_from(&attributes.contact_information);
+                }
                 Tag::CryptographicAlgorithm => {
                     res.cryptographic_algorithm = attributes.cryptographic_algorithm;
                 }
@@ -197,9 +228,62 @@ pub async fn get_attributes(
                 Tag::CryptographicUsageMask => {
                     res.cryptographic_usage_mask = attributes.cryptographic_usage_mask;
                 }
+                Tag::DeactivationDate => {
+                    res.deactivation_date = attributes.deactivation_date;
+                }
+                Tag::Description => {
+                    res.description.clone_from(&attributes.description);
+                }
+                Tag::DestroyDate => {
+                    res.destroy_date = attributes.destroy_date;
+                }
+                Tag::Extractable => {
+                    res.extractable = attributes.extractable;
+                }
+                Tag::Fresh => {
+                    res.fresh = attributes.fresh;
+                }
+                Tag::InitialDate => {
+                    res.initial_date = attributes.initial_date;
+                }
                 Tag::KeyFormatType => {
                     res.key_format_type = attributes.key_format_type;
                 }
+                Tag::LastChangeDate => {
+                    res.last_change_date = attributes.last_change_date;
+                }
+                Tag::Name => {
+                    res.name.clone_from(&attributes.name);
+                }
+                Tag::NeverExtractable => {
+                    res.never_extractable = attributes.never_extractable;
+                }
+                Tag::ObjectGroup => {
+                    res.object_group.clone_from(&attributes.object_group);
+                }
+                Tag::OriginalCreationDate => {
+                    res.original_creation_date = attributes.original_creation_date;
+                }
+                Tag::ProcessStartDate => {
+                    res.process_start_date = attributes.process_start_date;
+                }
+                Tag::ProtectStopDate => {
+                    res.protect_stop_date = attributes.protect_stop_date;
+                }
+                Tag::RevocationReason => {
+                    res.revocation_reason
+                        .clone_from(&attributes.revocation_reason);
+                }
+                Tag::Sensitive => {
+                    res.sensitive = attributes.sensitive;
+                }
+                // the state is held by the server alongside the object
+                Tag::State => {
+                    res.state = Some(owm.state);
+                }
+                Tag::UsageLimits => {
+                    res.usage_limits.clone_from(&attributes.usage_limits);
+                }
                 Tag::PrivateKey => {
                     if let Some(link) = attributes.get_link(LinkType::PrivateKeyLink) {
                         res.add_link(
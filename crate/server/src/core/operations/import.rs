@@ -29,7 +29,10 @@ use uuid::Uuid;
 
 use super::wrapping::unwrap_key;
 use crate::{
-    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::set_initial_attributes, KMS,
+    },
     database::AtomicOperation,
     error::KmsError,
     kms_bail,
@@ -56,6 +59,7 @@ pub async fn import(
     {
         kms_bail!("Importing objects with unique identifiers starting with `[` is not supported");
     }
+    let request_replaces = request.replace_existing.unwrap_or(false);
     // process the request based on the object type
     let (uid, operations) = match request.object.object_type() {
         ObjectType::SymmetricKey => process_symmetric_key(kms, request, owner, params).await?,
@@ -70,6 +74,10 @@ pub async fn import(
     };
     // execute the operations
     kms.db.atomic(owner, &operations, params).await?;
+    // a replaced certificate may have changed state or issuer
+    if request_replaces {
+        kms.revocation_cache.clear()?;
+    }
     // return the uid
     debug!("Imported object with uid: {}", uid);
     Ok(ImportResponse {
@@ -409,10 +417,11 @@ fn private_key_from_openssl(
 fn single_operation(
     tags: Option<HashSet<String>>,
     replace_existing: bool,
-    object: Object,
-    attributes: Attributes,
+    mut object: Object,
+    mut attributes: Attributes,
     uid: String,
 ) -> AtomicOperation {
+    set_initial_attributes(&mut object, &mut attributes);
     if replace_existing {
         AtomicOperation::Upsert((
             uid,
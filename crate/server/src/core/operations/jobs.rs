@@ -0,0 +1,262 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use cosmian_kmip::kmip::{
+    kmip_messages::MessageResponseBatchItem,
+    kmip_operations::{ErrorReason, Operation},
+    kmip_types::{AsynchronousIndicator, ResultStatusEnumeration},
+    ttlv::serializer::to_ttlv,
+};
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, operations::dispatch, KMS},
+    database::job::{Job, JobState, JOB_RETENTION},
+    error::KmsError,
+    middlewares::ClaimedGroups,
+    result::KResult,
+};
+
+/// The lease a server holds on the pending jobs it processes: it is renewed
+/// every third of its duration, while the job is being processed
+const JOB_LEASE: Duration = Duration::from_secs(60);
+
+/// The operations processed asynchronously in the background, indexed by job identifier
+///
+/// A pending job whose task is not registered here and whose lease expired
+/// (e.g. after a restart of the server) was interrupted: it is resumed
+/// when the server restarts or when it is polled.
+/// The lock is never held across an await point.
+#[derive(Default)]
+pub(crate) struct RunningJobs(Mutex<HashMap<String, JoinHandle<()>>>);
+
+impl RunningJobs {
+    fn insert(&self, id: String, handle: JoinHandle<()>) -> KResult<()> {
+        self.0
+            .lock()
+            .map_err(|e| KmsError::ServerError(format!("running jobs lock poisoned: {e}")))?
+            .insert(id, handle);
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> KResult<Option<JoinHandle<()>>> {
+        Ok(self
+            .0
+            .lock()
+            .map_err(|e| KmsError::ServerError(format!("running jobs lock poisoned: {e}")))?
+            .remove(id))
+    }
+
+    /// Whether the task of the job is running on this server
+    pub(crate) fn is_running(&self, id: &str) -> KResult<bool> {
+        Ok(self
+            .0
+            .lock()
+            .map_err(|e| KmsError::ServerError(format!("running jobs lock poisoned: {e}")))?
+            .contains_key(id))
+    }
+
+    /// Abort the task of the job, if it is running
+    pub(crate) fn abort(&self, id: &str) -> KResult<()> {
+        if let Some(handle) = self.remove(id)? {
+            handle.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Whether the operation of a batch item is processed asynchronously
+///
+/// When the client makes asynchronous processing mandatory, every operation
+/// is, but `Poll` and `Cancel`. When it is optional, only the key pair generations,
+/// which may take long for large RSA or Covercrypt keys, are.
+pub(crate) fn is_asynchronous(
+    indicator: Option<&AsynchronousIndicator>,
+    operation: &Operation,
+) -> bool {
+    match indicator {
+        Some(AsynchronousIndicator::Mandatory) => {
+            !matches!(operation, Operation::Poll(_) | Operation::Cancel(_))
+        }
+        Some(AsynchronousIndicator::Optional) => matches!(
+            operation,
+            Operation::CreateKeyPair(_) | Operation::ReKeyKeyPair(_)
+        ),
+        _ => false,
+    }
+}
+
+/// Persist a pending job for the operation, start processing it in the
+/// background and return its asynchronous correlation value
+pub(crate) async fn submit_job(
+    kms: &Arc<KMS>,
+    operation: &Operation,
+    owner: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Vec<u8>> {
+    let now = chrono::Utc::now().timestamp();
+    kms.db
+        .purge_jobs(now - JOB_RETENTION.as_secs() as i64, params)
+        .await?;
+    let job = Job {
+        id: Uuid::new_v4().to_string(),
+        owner: owner.to_owned(),
+        operation: operation.operation_enum(),
+        state: JobState::Pending,
+        request: to_ttlv(operation)?,
+        response: None,
+        lease_expiry: now + JOB_LEASE.as_secs() as i64,
+        updated_at: now,
+    };
+    kms.db.create_job(&job, params).await?;
+    let correlation_value = job.id.as_bytes().to_vec();
+    spawn_job(kms, job, params.cloned())?;
+    Ok(correlation_value)
+}
+
+/// Process a pending job in the background
+fn spawn_job(kms: &Arc<KMS>, job: Job, params: Option<ExtraDatabaseParams>) -> KResult<()> {
+    debug!("Starting the asynchronous {} job {}", job.operation, job.id);
+    let id = job.id.clone();
+    let task_kms = kms.clone();
+    // the job is processed with the groups claimed by the request submitting it
+    let claimed_groups = ClaimedGroups::current();
+    // the task does not run before it is registered since it is spawned on the current thread
+    let handle = actix_web::rt::spawn(async move {
+        let id = job.id.clone();
+        let processing = run_job(&task_kms, job, params.as_ref());
+        let result = match claimed_groups {
+            Some(claimed_groups) => claimed_groups.scope(processing).await,
+            None => processing.await,
+        };
+        if let Err(e) = result {
+            error!("Failed saving the result of the asynchronous job {id}: {e}");
+        }
+        if let Err(e) = task_kms.jobs.remove(&id) {
+            error!("{e}");
+        }
+    });
+    kms.jobs.insert(id, handle)
+}
+
+/// Process the operation of the job, renewing the lease on the job meanwhile,
+/// and save its response batch item, unless the job was canceled in the meantime
+async fn run_job(kms: &KMS, mut job: Job, params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+    // the processing future borrows the job: it is dropped before the job is updated
+    let result = {
+        let processing = dispatch(kms, &job.request, &job.owner, params);
+        tokio::pin!(processing);
+        let mut renewal = tokio::time::interval(JOB_LEASE / 3);
+        // the first tick completes immediately
+        renewal.tick().await;
+        loop {
+            tokio::select! {
+                result = &mut processing => break result,
+                _ = renewal.tick() => {
+                    let now = chrono::Utc::now().timestamp();
+                    job.lease_expiry = now + JOB_LEASE.as_secs() as i64;
+                    job.updated_at = now;
+                    if !kms.db.update_pending_job(&job, params).await? {
+                        debug!("The asynchronous job {} is no longer pending", job.id);
+                        return Ok(())
+                    }
+                }
+            }
+        }
+    };
+
+    let response_item = match result {
+        Ok(response) => {
+            job.state = JobState::Completed;
+            MessageResponseBatchItem::new_with_response(ResultStatusEnumeration::Success, response)
+        }
+        Err(err) => {
+            job.state = JobState::Failed;
+            MessageResponseBatchItem {
+                operation: Some(job.operation),
+                ..failed_item(err)
+            }
+        }
+    };
+    job.response = Some(to_ttlv(&response_item)?);
+    job.updated_at = chrono::Utc::now().timestamp();
+    if !kms.db.update_pending_job(&job, params).await? {
+        debug!("The asynchronous job {} was canceled", job.id);
+    }
+    Ok(())
+}
+
+/// Resume the pending job if it was interrupted: its task is not running on this server
+/// and the lease on it expired. The server takes over the lease before processing the job
+/// again, so that a job is resumed once. The job is returned in its current state.
+pub(crate) async fn resume_interrupted_job(
+    kms: &Arc<KMS>,
+    mut job: Job,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Job> {
+    let now = chrono::Utc::now().timestamp();
+    if job.state != JobState::Pending || job.lease_expiry >= now || kms.jobs.is_running(&job.id)? {
+        return Ok(job)
+    }
+    job.lease_expiry = now + JOB_LEASE.as_secs() as i64;
+    job.updated_at = now;
+    if kms.db.lease_interrupted_job(&job, now, params).await? {
+        debug!("Resuming the interrupted asynchronous job {}", job.id);
+        spawn_job(kms, job.clone(), params.cloned())?;
+        return Ok(job)
+    }
+    // the job was resumed, completed or canceled in the meantime
+    kms.db
+        .retrieve_job(&job.id, &job.owner, params)
+        .await?
+        .ok_or_else(|| KmsError::ServerError(format!("the job {} vanished", job.id)))
+}
+
+/// Resume the jobs interrupted by a stop of the server, once it restarted.
+/// The jobs kept in the databases of the groups of users are resumed when polled.
+pub(crate) async fn resume_interrupted_jobs(kms: &Arc<KMS>) -> KResult<()> {
+    let now = chrono::Utc::now().timestamp();
+    for job in kms.db.list_interrupted_jobs(now, None).await? {
+        resume_interrupted_job(kms, job, None).await?;
+    }
+    Ok(())
+}
+
+/// The response batch item of a failed operation
+pub(crate) fn failed_item(err: KmsError) -> MessageResponseBatchItem {
+    let (result_reason, result_message) = match err {
+        KmsError::KmipError(reason, error_message) => (reason, error_message),
+        err => (ErrorReason::Operation_Not_Supported, err.to_string()),
+    };
+    MessageResponseBatchItem {
+        result_reason: Some(result_reason),
+        result_message: Some(result_message),
+        ..MessageResponseBatchItem::new(ResultStatusEnumeration::OperationFailed)
+    }
+}
+
+/// Retrieve the job of `owner` identified by an asynchronous correlation value
+pub(crate) async fn retrieve_job(
+    kms: &KMS,
+    asynchronous_correlation_value: &[u8],
+    owner: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Job> {
+    let invalid_correlation_value = || {
+        KmsError::KmipError(
+            ErrorReason::Invalid_Asynchronous_Correlation_Value,
+            "no asynchronous operation matches the correlation value".to_owned(),
+        )
+    };
+    let id = std::str::from_utf8(asynchronous_correlation_value)
+        .map_err(|_| invalid_correlation_value())?;
+    kms.db
+        .retrieve_job(id, owner, params)
+        .await?
+        .ok_or_else(invalid_correlation_value)
+}
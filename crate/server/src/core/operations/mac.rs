@@ -0,0 +1,428 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use cosmian_kmip::{
+    crypto::mac::{MacAlgorithm, MacContext},
+    kmip::{
+        kmip_objects::ObjectType,
+        kmip_operations::{ErrorReason, MACResponse, MAC},
+        kmip_types::{
+            CryptographicParameters, CryptographicUsageMask, StateEnumeration, UniqueIdentifier,
+        },
+    },
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use openssl::rand::rand_bytes;
+use tracing::trace;
+
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, operations::unwrap_key, KMS},
+    database::object_with_metadata::ObjectWithMetadata,
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+/// The maximum number of multi-part MAC operations in progress on the server
+const MAX_MAC_STREAMS: usize = 1024;
+
+/// The maximum number of multi-part MAC operations in progress for a single user
+const MAX_MAC_STREAMS_PER_USER: usize = 16;
+
+/// A multi-part MAC operation which is not continued within this delay is discarded
+const MAC_STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The length in bytes of the correlation values identifying multi-part MAC operations
+const CORRELATION_VALUE_LENGTH: usize = 16;
+
+/// A multi-part MAC operation in progress
+pub(crate) struct MacStream {
+    /// The unique identifier of the key used for the MAC operation
+    pub(crate) key_uid: String,
+    /// The user who initiated the operation
+    user: String,
+    /// `MACGenerate` for a MAC operation, `MACVerify` for a MAC verify operation
+    usage: CryptographicUsageMask,
+    /// The MAC computation context
+    pub(crate) context: MacContext,
+    /// When the operation was last continued
+    last_used: Instant,
+}
+
+/// The multi-part MAC operations in progress, indexed by correlation value
+///
+/// The lock is never held across an await point.
+#[derive(Default)]
+pub(crate) struct MacStreams(Mutex<HashMap<Vec<u8>, MacStream>>);
+
+/// Discard the multi-part MAC operations which have been idle for too long
+fn evict_idle(streams: &mut HashMap<Vec<u8>, MacStream>) {
+    streams.retain(|_, stream| stream.last_used.elapsed() < MAC_STREAM_IDLE_TIMEOUT);
+}
+
+impl MacStreams {
+    /// Register a new multi-part MAC operation and return its correlation value
+    pub(crate) fn open(
+        &self,
+        key_uid: String,
+        user: &str,
+        usage: CryptographicUsageMask,
+        context: MacContext,
+    ) -> KResult<Vec<u8>> {
+        let mut correlation_value = vec![0_u8; CORRELATION_VALUE_LENGTH];
+        rand_bytes(&mut correlation_value)?;
+        let mut streams = self
+            .0
+            .lock()
+            .map_err(|e| KmsError::ServerError(format!("MAC streams lock poisoned: {e}")))?;
+        evict_idle(&mut streams);
+        if streams.len() >= MAX_MAC_STREAMS {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Operation_Not_Supported,
+                "too many multi-part MAC operations in progress".to_owned()
+            ))
+        }
+        if streams
+            .values()
+            .filter(|stream| stream.user == user)
+            .count()
+            >= MAX_MAC_STREAMS_PER_USER
+        {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Operation_Not_Supported,
+                format!("too many multi-part MAC operations in progress for {user}")
+            ))
+        }
+        streams.insert(
+            correlation_value.clone(),
+            MacStream {
+                key_uid,
+                user: user.to_owned(),
+                usage,
+                context,
+                last_used: Instant::now(),
+            },
+        );
+        Ok(correlation_value)
+    }
+
+    /// Remove the multi-part MAC operation identified by `correlation_value`
+    /// and return it. Only the user who initiated the operation can continue it,
+    /// using the same kind of operation, before it expires.
+    pub(crate) fn take(
+        &self,
+        correlation_value: &[u8],
+        user: &str,
+        usage: CryptographicUsageMask,
+    ) -> KResult<MacStream> {
+        let mut streams = self
+            .0
+            .lock()
+            .map_err(|e| KmsError::ServerError(format!("MAC streams lock poisoned: {e}")))?;
+        evict_idle(&mut streams);
+        match streams.get(correlation_value) {
+            Some(stream) if stream.user == user && stream.usage == usage => {}
+            _ => kms_bail!(KmsError::KmipError(
+                ErrorReason::Item_Not_Found,
+                "no multi-part MAC operation found for this correlation value".to_owned()
+            )),
+        }
+        streams.remove(correlation_value).ok_or_else(|| {
+            KmsError::ServerError("the multi-part MAC operation vanished".to_owned())
+        })
+    }
+
+    /// Put back a multi-part MAC operation that is not complete
+    pub(crate) fn put_back(
+        &self,
+        correlation_value: Vec<u8>,
+        mut stream: MacStream,
+    ) -> KResult<()> {
+        stream.last_used = Instant::now();
+        self.0
+            .lock()
+            .map_err(|e| KmsError::ServerError(format!("MAC streams lock poisoned: {e}")))?
+            .insert(correlation_value, stream);
+        Ok(())
+    }
+}
+
+pub async fn mac(
+    kms: &KMS,
+    request: MAC,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<MACResponse> {
+    trace!("MAC: {:?}", &request.unique_identifier);
+
+    let data = request.data.as_deref().unwrap_or_default();
+    let is_final = request.final_indicator == Some(true);
+
+    // continue an existing multi-part operation
+    if let Some(correlation_value) = request.correlation_value {
+        let mut stream = kms.mac_streams.take(
+            &correlation_value,
+            user,
+            CryptographicUsageMask::MACGenerate,
+        )?;
+        check_stream_key(kms, &stream, user, params).await?;
+        stream.context.update(data)?;
+        let unique_identifier = UniqueIdentifier::TextString(stream.key_uid.clone());
+        if is_final {
+            return Ok(MACResponse {
+                unique_identifier,
+                mac_data: Some(stream.context.finalize()?),
+                correlation_value: None,
+            })
+        }
+        kms.mac_streams
+            .put_back(correlation_value.clone(), stream)?;
+        return Ok(MACResponse {
+            unique_identifier,
+            mac_data: None,
+            correlation_value: Some(correlation_value),
+        })
+    }
+
+    let (key_uid, mut context) = mac_context(
+        kms,
+        request.unique_identifier.as_ref(),
+        request.cryptographic_parameters.as_ref(),
+        CryptographicUsageMask::MACGenerate,
+        user,
+        params,
+    )
+    .await?;
+    context.update(data)?;
+
+    // start a multi-part operation
+    if request.init_indicator == Some(true) && !is_final {
+        let correlation_value = kms.mac_streams.open(
+            key_uid.clone(),
+            user,
+            CryptographicUsageMask::MACGenerate,
+            context,
+        )?;
+        return Ok(MACResponse {
+            unique_identifier: UniqueIdentifier::TextString(key_uid),
+            mac_data: None,
+            correlation_value: Some(correlation_value),
+        })
+    }
+
+    Ok(MACResponse {
+        unique_identifier: UniqueIdentifier::TextString(key_uid),
+        mac_data: Some(context.finalize()?),
+        correlation_value: None,
+    })
+}
+
+/// Retrieve the key identified by `unique_identifier`, check that it can be used
+/// for `usage` and initialize a MAC computation with it.
+///
+/// Returns the unique identifier of the key and the MAC context.
+pub(crate) async fn mac_context(
+    kms: &KMS,
+    unique_identifier: Option<&UniqueIdentifier>,
+    cryptographic_parameters: Option<&CryptographicParameters>,
+    usage: CryptographicUsageMask,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<(String, MacContext)> {
+    let owm = get_key(kms, unique_identifier, user, params).await?;
+    check_usage(&owm, usage)?;
+
+    let key_block = owm.object.key_block()?;
+    let algorithm =
+        MacAlgorithm::resolve(key_block.cryptographic_algorithm, cryptographic_parameters)?;
+    let key_bytes = key_block.key_bytes()?;
+    let context = MacContext::new(algorithm, &key_bytes)?;
+    Ok((owm.id, context))
+}
+
+/// Check, before each part of a multi-part operation, that the key is still active,
+/// usable for the operation and accessible to the user.
+/// The operation is discarded otherwise.
+pub(crate) async fn check_stream_key(
+    kms: &KMS,
+    stream: &MacStream,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<()> {
+    let owm = retrieve_key(kms, &stream.key_uid, user, params).await?;
+    check_usage(&owm, stream.usage)
+}
+
+/// Make sure that the key can be used for the MAC operation.
+fn check_usage(owm: &ObjectWithMetadata, usage: CryptographicUsageMask) -> KResult<()> {
+    if !owm.object.attributes()?.is_usage_authorized_for(usage)? {
+        return Err(KmsError::KmipError(
+            ErrorReason::Incompatible_Cryptographic_Usage_Mask,
+            format!(
+                "CryptographicUsageMask not authorized for {}",
+                if usage == CryptographicUsageMask::MACVerify {
+                    "MACVerify"
+                } else {
+                    "MACGenerate"
+                }
+            ),
+        ))
+    }
+    Ok(())
+}
+
+async fn get_key(
+    kms: &KMS,
+    unique_identifier: Option<&UniqueIdentifier>,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ObjectWithMetadata> {
+    // there must be an identifier
+    let uid_or_tags = unique_identifier
+        .ok_or(KmsError::UnsupportedPlaceholder)?
+        .as_str()
+        .context("MAC: unique_identifier must be a string")?;
+    let mut owm = retrieve_key(kms, uid_or_tags, user, params).await?;
+
+    // unwrap if wrapped
+    if owm.object.key_wrapping_data().is_some() {
+        let key_block = owm.object.key_block_mut()?;
+        unwrap_key(key_block, kms, &owm.owner, params).await?;
+    }
+    Ok(owm)
+}
+
+/// Retrieve the active symmetric key the user can MAC with, without unwrapping it
+async fn retrieve_key(
+    kms: &KMS,
+    uid_or_tags: &str,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ObjectWithMetadata> {
+    trace!("mac: uid_or_tags: {uid_or_tags}");
+
+    // retrieve from tags or use passed identifier
+    let mut owm_s = kms
+        .db
+        .retrieve(uid_or_tags, user, ObjectOperationType::Mac, params)
+        .await?
+        .into_values()
+        .filter(|owm| {
+            owm.state == StateEnumeration::Active
+                && owm.object.object_type() == ObjectType::SymmetricKey
+        })
+        .collect::<Vec<ObjectWithMetadata>>();
+    trace!("mac: owm_s: {:?}", owm_s);
+
+    // there can only be one key
+    let owm = owm_s
+        .pop()
+        .ok_or_else(|| KmsError::KmipError(ErrorReason::Item_Not_Found, uid_or_tags.to_string()))?;
+
+    if !owm_s.is_empty() {
+        return Err(KmsError::InvalidRequest(format!(
+            "mac: too many objects for key {uid_or_tags}",
+        )))
+    }
+    Ok(owm)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmian_kmip::{
+        crypto::mac::{MacAlgorithm, MacContext},
+        kmip::kmip_types::{CryptographicAlgorithm, CryptographicUsageMask},
+    };
+
+    use super::{MacStreams, MAC_STREAM_IDLE_TIMEOUT, MAX_MAC_STREAMS_PER_USER};
+
+    fn context() -> MacContext {
+        MacContext::new(
+            MacAlgorithm::Hmac(CryptographicAlgorithm::HMACSHA256),
+            &[0_u8; 32],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mac_streams_per_user_cap() {
+        let streams = MacStreams::default();
+        for _ in 0..MAX_MAC_STREAMS_PER_USER {
+            streams
+                .open(
+                    "key".to_owned(),
+                    "alice",
+                    CryptographicUsageMask::MACGenerate,
+                    context(),
+                )
+                .unwrap();
+        }
+        assert!(
+            streams
+                .open(
+                    "key".to_owned(),
+                    "alice",
+                    CryptographicUsageMask::MACGenerate,
+                    context(),
+                )
+                .is_err()
+        );
+        // the other users are not affected
+        streams
+            .open(
+                "key".to_owned(),
+                "bob",
+                CryptographicUsageMask::MACGenerate,
+                context(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_mac_streams_idle_eviction() {
+        let streams = MacStreams::default();
+        let correlation_value = streams
+            .open(
+                "key".to_owned(),
+                "alice",
+                CryptographicUsageMask::MACGenerate,
+                context(),
+            )
+            .unwrap();
+        // the stream is still usable
+        let mut stream = streams
+            .take(
+                &correlation_value,
+                "alice",
+                CryptographicUsageMask::MACGenerate,
+            )
+            .unwrap();
+        streams.put_back(correlation_value.clone(), stream).unwrap();
+        // age the stream past the idle timeout
+        stream = streams
+            .take(
+                &correlation_value,
+                "alice",
+                CryptographicUsageMask::MACGenerate,
+            )
+            .unwrap();
+        stream.last_used -= MAC_STREAM_IDLE_TIMEOUT;
+        streams
+            .0
+            .lock()
+            .unwrap()
+            .insert(correlation_value.clone(), stream);
+        assert!(
+            streams
+                .take(
+                    &correlation_value,
+                    "alice",
+                    CryptographicUsageMask::MACGenerate
+                )
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{MACVerify, MACVerifyResponse},
+    kmip_types::{CryptographicUsageMask, UniqueIdentifier, ValidityIndicator},
+};
+use tracing::trace;
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::mac::{check_stream_key, mac_context},
+        KMS,
+    },
+    error::KmsError,
+    result::KResult,
+};
+
+pub async fn mac_verify(
+    kms: &KMS,
+    request: MACVerify,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<MACVerifyResponse> {
+    trace!("MACVerify: {:?}", &request.unique_identifier);
+
+    let data = request.data.as_deref().unwrap_or_default();
+    let is_final = request.final_indicator == Some(true);
+
+    // continue an existing multi-part operation
+    if let Some(correlation_value) = request.correlation_value {
+        let mut stream =
+            kms.mac_streams
+                .take(&correlation_value, user, CryptographicUsageMask::MACVerify)?;
+        check_stream_key(kms, &stream, user, params).await?;
+        stream.context.update(data)?;
+        let unique_identifier = UniqueIdentifier::TextString(stream.key_uid.clone());
+        if is_final {
+            let mac_data = request.mac_data.as_ref().ok_or_else(|| {
+                KmsError::InvalidRequest(
+                    "MACVerify: the MAC data must be provided with the final part".to_owned(),
+                )
+            })?;
+            return Ok(MACVerifyResponse {
+                unique_identifier,
+                validity_indicator: Some(validity_indicator(stream.context.verify(mac_data)?)),
+                correlation_value: None,
+            })
+        }
+        kms.mac_streams
+            .put_back(correlation_value.clone(), stream)?;
+        return Ok(MACVerifyResponse {
+            unique_identifier,
+            validity_indicator: None,
+            correlation_value: Some(correlation_value),
+        })
+    }
+
+    let (key_uid, mut context) = mac_context(
+        kms,
+        request.unique_identifier.as_ref(),
+        request.cryptographic_parameters.as_ref(),
+        CryptographicUsageMask::MACVerify,
+        user,
+        params,
+    )
+    .await?;
+    context.update(data)?;
+
+    // start a multi-part operation
+    if request.init_indicator == Some(true) && !is_final {
+        let correlation_value = kms.mac_streams.open(
+            key_uid.clone(),
+            user,
+            CryptographicUsageMask::MACVerify,
+            context,
+        )?;
+        return Ok(MACVerifyResponse {
+            unique_identifier: UniqueIdentifier::TextString(key_uid),
+            validity_indicator: None,
+            correlation_value: Some(correlation_value),
+        })
+    }
+
+    let mac_data = request.mac_data.as_ref().ok_or_else(|| {
+        KmsError::InvalidRequest("MACVerify: the MAC data must be provided".to_owned())
+    })?;
+    Ok(MACVerifyResponse {
+        unique_identifier: UniqueIdentifier::TextString(key_uid),
+        validity_indicator: Some(validity_indicator(context.verify(mac_data)?)),
+        correlation_value: None,
+    })
+}
+
+const fn validity_indicator(valid: bool) -> ValidityIndicator {
+    if valid {
+        ValidityIndicator::Valid
+    } else {
+        ValidityIndicator::Invalid
+    }
+}
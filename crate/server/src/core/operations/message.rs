@@ -1,70 +1,233 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
 use cosmian_kmip::kmip::{
     kmip_messages::{Message, MessageResponse, MessageResponseBatchItem, MessageResponseHeader},
-    kmip_operations::ErrorReason,
-    kmip_types::ResultStatusEnumeration,
-    ttlv::serializer::to_ttlv,
+    kmip_operations::{ErrorReason, Operation},
+    kmip_types::{
+        BatchErrorContinuationOption, LinkType, ProtocolVersion, ResultStatusEnumeration,
+        UniqueIdentifier,
+    },
+    ttlv::{
+        deserializer::{from_binary_ttlv, from_ttlv},
+        kmip_1_x::{to_kmip_1_x, to_kmip_2_1},
+        serializer::to_ttlv,
+        TTLValue, TTLV,
+    },
 };
-use tracing::trace;
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::{debug, trace};
 
+use super::{
+    attributes_utils::object_attributes,
+    discover_versions::supported_protocol_versions,
+    jobs::{failed_item, is_asynchronous, submit_job},
+};
 use crate::{
     core::{extra_database_params::ExtraDatabaseParams, operations::dispatch, KMS},
+    database::AtomicOperation,
     error::KmsError,
+    kms_bail,
     result::KResult,
 };
 
+/// Processing of a KMIP Message in its `TTLV` form
+///
+/// The protocol version of the request header selects the representation
+/// of the message: KMIP 1.x requests are upgraded to KMIP 2.1 before being
+/// processed and the response is downgraded to the version of the request.
+pub async fn message_ttlv(
+    kms: &Arc<KMS>,
+    ttlv: &TTLV,
+    owner: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<TTLV> {
+    process_message_ttlv(kms, ttlv, false, owner, params).await
+}
+
+/// Processing of a KMIP Message decoded from the binary TTLV encoding
+/// of the KMIP socket server, see `message_ttlv`
+pub async fn binary_message_ttlv(
+    kms: &Arc<KMS>,
+    ttlv: &TTLV,
+    owner: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<TTLV> {
+    process_message_ttlv(kms, ttlv, true, owner, params).await
+}
+
+async fn process_message_ttlv(
+    kms: &Arc<KMS>,
+    ttlv: &TTLV,
+    binary: bool,
+    owner: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<TTLV> {
+    let protocol_version = request_protocol_version(ttlv)?;
+    if !supported_protocol_versions().contains(&protocol_version) {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Invalid_Message,
+            format!("unsupported KMIP protocol version: {protocol_version}")
+        ))
+    }
+    let is_kmip_1_x = protocol_version.protocol_version_major == 1;
+
+    let upgraded_ttlv;
+    let ttlv = if is_kmip_1_x {
+        upgraded_ttlv = to_kmip_2_1(ttlv)?;
+        &upgraded_ttlv
+    } else {
+        ttlv
+    };
+    let request = if binary {
+        from_binary_ttlv::<Message>(ttlv)?
+    } else {
+        from_ttlv::<Message>(ttlv)?
+    };
+    let response = to_ttlv(&message(kms, request, owner, params).await?)?;
+    if is_kmip_1_x {
+        return Ok(to_kmip_1_x(&response)?)
+    }
+    Ok(response)
+}
+
+/// The protocol version found in the header of a request message
+fn request_protocol_version(ttlv: &TTLV) -> KResult<ProtocolVersion> {
+    fn child<'a>(ttlv: &'a TTLV, tag: &str) -> Option<&'a TTLV> {
+        match &ttlv.value {
+            TTLValue::Structure(children) => children.iter().find(|c| c.tag == tag),
+            _ => None,
+        }
+    }
+
+    let protocol_version = child(ttlv, "Header")
+        .and_then(|header| child(header, "ProtocolVersion"))
+        .ok_or_else(|| {
+            KmsError::KmipError(
+                ErrorReason::Invalid_Message,
+                "the request message has no protocol version".to_owned(),
+            )
+        })?;
+    Ok(from_ttlv(protocol_version)?)
+}
+
 /// Processing of an input KMIP Message
 ///
 /// Process every item from the message request.
 /// Each batch item contains an operation to process.
 ///
-/// The items are processed sequentially.
-/// Each item may fail but a response is still sent back.
+/// The items are processed sequentially, in order.
+/// When an item fails, the Batch Error Continuation Option of the header
+/// decides what happens next:
+///  - `Continue`: the following items are processed
+///  - `Stop` (the default): the following items are not processed
+///  - `Undo`: the following items are not processed and the changes made
+///    by the previous items are rolled back
+///
+/// The ID Placeholder is the unique identifier returned by the last item
+/// which returned one; it is used by any later item omitting its unique identifier.
+///
+/// When the client sets the Asynchronous Indicator, the operations may be
+/// processed in the background: the item is then pending and its Asynchronous
+/// Correlation Value is used to `Poll` or `Cancel` the operation.
+/// The items of a batch processed with the `Undo` option are always processed synchronously.
 pub async fn message(
-    kms: &KMS,
+    kms: &Arc<KMS>,
     request: Message,
     owner: &str,
     params: Option<&ExtraDatabaseParams>,
 ) -> KResult<MessageResponse> {
     trace!("Entering message KMIP operation: {request:#?}");
 
+    let continuation_option = request
+        .header
+        .batch_error_continuation_option
+        .unwrap_or(BatchErrorContinuationOption::Stop);
+    let mut undo_journal = UndoJournal::default();
+    let mut id_placeholder: Option<UniqueIdentifier> = None;
+
     let mut response_items = Vec::new();
     for item_request in request.items {
-        let operation = item_request.request_payload;
-        // conversion for `dispatch` call convenience
-        let ttlv = to_ttlv(&operation)?;
-
-        let (result_status, result_reason, result_message, response_payload) =
-            match dispatch(kms, &ttlv, owner, params).await {
-                Ok(operation) => (
-                    ResultStatusEnumeration::Success,
-                    None,
-                    None,
-                    Some(operation),
-                ),
-                Err(KmsError::KmipError(reason, error_message)) => (
-                    ResultStatusEnumeration::OperationFailed,
-                    Some(reason),
-                    Some(error_message),
-                    None,
-                ),
-                Err(err) => (
-                    ResultStatusEnumeration::OperationFailed,
-                    Some(ErrorReason::Operation_Not_Supported),
-                    Some(err.to_string()),
-                    None,
-                ),
-            };
+        let mut operation = item_request.request_payload;
+        if let Some(id_placeholder) = &id_placeholder {
+            set_id_placeholder(&mut operation, id_placeholder);
+        }
+        // an item whose changes could not be journaled fails without being processed
+        let snapshot = if continuation_option == BatchErrorContinuationOption::Undo {
+            undo_journal.snapshot(kms, &operation, owner, params).await
+        } else {
+            Ok(())
+        };
+        let asynchronous = continuation_option != BatchErrorContinuationOption::Undo
+            && is_asynchronous(request.header.asynchronous_indicator.as_ref(), &operation);
+
+        let mut response_item = if let Err(err) = snapshot {
+            failed_item(err)
+        } else {
+            match operation {
+                Operation::Poll(poll_request) => kms
+                    .poll(poll_request, owner, params)
+                    .await
+                    .unwrap_or_else(failed_item),
+                operation if asynchronous => match submit_job(kms, &operation, owner, params).await
+                {
+                    Ok(asynchronous_correlation_value) => MessageResponseBatchItem {
+                        asynchronous_correlation_value: Some(asynchronous_correlation_value),
+                        ..MessageResponseBatchItem::new(ResultStatusEnumeration::OperationPending)
+                    },
+                    Err(err) => failed_item(err),
+                },
+                operation => {
+                    // conversion for `dispatch` call convenience
+                    let dispatched = match to_ttlv(&operation) {
+                        Ok(ttlv) => dispatch(kms, &ttlv, owner, params).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    match dispatched {
+                        Ok(operation) => {
+                            undo_journal.record(&operation, owner);
+                            if let Some(unique_identifier) = returned_unique_identifier(&operation)
+                            {
+                                id_placeholder = Some(unique_identifier);
+                            }
+                            MessageResponseBatchItem::new_with_response(
+                                ResultStatusEnumeration::Success,
+                                operation,
+                            )
+                        }
+                        Err(err) => failed_item(err),
+                    }
+                }
+            }
+        };
+        // the response of a completed polled operation names the polled operation
+        if response_item.operation.is_none() {
+            response_item.operation = Some(item_request.operation);
+        }
+        response_item.unique_batch_item_id = item_request.unique_batch_item_id;
+        let failed = response_item.result_status == ResultStatusEnumeration::OperationFailed;
+        response_items.push(response_item);
 
-        response_items.push(MessageResponseBatchItem {
-            operation: Some(item_request.operation),
-            unique_batch_item_id: item_request.unique_batch_item_id,
-            result_status,
-            result_reason,
-            result_message,
-            asynchronous_correlation_value: None,
-            response_payload,
-            message_extension: None,
-        });
+        if failed {
+            match continuation_option {
+                BatchErrorContinuationOption::Continue => continue,
+                BatchErrorContinuationOption::Stop => break,
+                BatchErrorContinuationOption::Undo => {
+                    undo_journal.rollback(kms, params).await?;
+                    for item in &mut response_items {
+                        if item.result_status == ResultStatusEnumeration::Success {
+                            item.result_status = ResultStatusEnumeration::OperationUndone;
+                            item.result_message =
+                                Some("undone after the failure of a later batch item".to_owned());
+                            item.response_payload = None;
+                        }
+                    }
+                    break
+                }
+            }
+        }
     }
 
     let response_message = MessageResponse {
@@ -83,3 +246,356 @@ pub async fn message(
 
     Ok(response_message)
 }
+
+/// Use the ID Placeholder as the unique identifier of a request
+/// which does not specify one
+fn set_id_placeholder(operation: &mut Operation, id_placeholder: &UniqueIdentifier) {
+    let unique_identifier = match operation {
+        Operation::Certify(request) => &mut request.unique_identifier,
+        Operation::Export(request) => &mut request.unique_identifier,
+        Operation::Get(request) => &mut request.unique_identifier,
+        Operation::GetAttributes(request) => &mut request.unique_identifier,
+        Operation::SetAttribute(request) => &mut request.unique_identifier,
+        Operation::AddAttribute(request) => &mut request.unique_identifier,
+        Operation::ModifyAttribute(request) => &mut request.unique_identifier,
+        Operation::DeleteAttribute(request) => &mut request.unique_identifier,
+        Operation::Encrypt(request) => &mut request.unique_identifier,
+        Operation::Decrypt(request) => &mut request.unique_identifier,
+        Operation::Sign(request) => &mut request.unique_identifier,
+        Operation::SignatureVerify(request) => &mut request.unique_identifier,
+        Operation::MAC(request) => &mut request.unique_identifier,
+        Operation::MACVerify(request) => &mut request.unique_identifier,
+        Operation::Activate(request) => &mut request.unique_identifier,
+        Operation::Revoke(request) => &mut request.unique_identifier,
+        Operation::ReKey(request) => &mut request.unique_identifier,
+        Operation::ReKeyKeyPair(request) => &mut request.private_key_unique_identifier,
+        Operation::Destroy(request) => &mut request.unique_identifier,
+        Operation::Archive(request) => &mut request.unique_identifier,
+        Operation::Recover(request) => &mut request.unique_identifier,
+        Operation::Validate(request) => {
+            request
+                .unique_identifiers
+                .get_or_insert_with(|| vec![id_placeholder.clone()]);
+            return
+        }
+        _ => return,
+    };
+    if unique_identifier.is_none() {
+        *unique_identifier = Some(id_placeholder.clone());
+    }
+}
+
+/// The unique identifier a successful operation sets the ID Placeholder to:
+/// that of the private key for key pairs and that of the object found by a `Locate`,
+/// if it found a single one
+fn returned_unique_identifier(operation: &Operation) -> Option<UniqueIdentifier> {
+    let unique_identifier = match operation {
+        Operation::ImportResponse(response) => &response.unique_identifier,
+        Operation::CertifyResponse(response) => &response.unique_identifier,
+        Operation::CreateResponse(response) => &response.unique_identifier,
+        Operation::CreateKeyPairResponse(response) => &response.private_key_unique_identifier,
+        Operation::ExportResponse(response) => &response.unique_identifier,
+        Operation::GetResponse(response) => &response.unique_identifier,
+        Operation::GetAttributesResponse(response) => &response.unique_identifier,
+        Operation::SetAttributeResponse(response) => &response.unique_identifier,
+        Operation::AddAttributeResponse(response) => &response.unique_identifier,
+        Operation::ModifyAttributeResponse(response) => &response.unique_identifier,
+        Operation::DeleteAttributeResponse(response) => &response.unique_identifier,
+        Operation::EncryptResponse(response) => &response.unique_identifier,
+        Operation::DecryptResponse(response) => &response.unique_identifier,
+        Operation::SignResponse(response) => &response.unique_identifier,
+        Operation::SignatureVerifyResponse(response) => &response.unique_identifier,
+        Operation::MACResponse(response) => &response.unique_identifier,
+        Operation::MACVerifyResponse(response) => &response.unique_identifier,
+        Operation::DeriveKeyResponse(response) => &response.unique_identifier,
+        Operation::ActivateResponse(response) => &response.unique_identifier,
+        Operation::RevokeResponse(response) => &response.unique_identifier,
+        Operation::ReKeyResponse(response) => &response.unique_identifier,
+        Operation::ReKeyKeyPairResponse(response) => &response.private_key_unique_identifier,
+        Operation::DestroyResponse(response) => &response.unique_identifier,
+        Operation::ArchiveResponse(response) => &response.unique_identifier,
+        Operation::RecoverResponse(response) => &response.unique_identifier,
+        Operation::LocateResponse(response) => match response.unique_identifiers.as_deref() {
+            Some([unique_identifier]) => unique_identifier,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(unique_identifier.clone())
+}
+
+/// The changes made by the items of a batch processed with the `Undo` option
+///
+/// The objects an item may modify are saved before the item is processed
+/// and the objects it creates are recorded after it succeeded,
+/// so that a failure of a later item can roll the batch back.
+///
+/// The saved objects cover the changes of their content, attributes, state
+/// and tags, including the usage limits consumed by `Encrypt`, `Decrypt` and `Sign`
+/// and the link `DeriveKey` adds to the base key.
+/// The KMIP operations do not change the access rights,
+/// which are only granted and revoked through the access endpoints.
+#[derive(Default)]
+struct UndoJournal {
+    /// The objects saved before being modified, with their owner and tags
+    saved: Vec<(String, AtomicOperation)>,
+    /// The identifiers of the saved objects
+    saved_uids: HashSet<String>,
+    /// The identifiers of the objects created by the batch, with their owner
+    created_uids: Vec<(String, String)>,
+    /// The identifiers of the objects archived or recovered by the batch
+    /// with `true` when archived
+    moved_uids: Vec<(String, bool)>,
+    /// The owner of the object targeted by the last item
+    target_owner: Option<String>,
+}
+
+/// The objects saved before an item is processed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SnapshotScope {
+    /// The targeted objects and the objects they are linked to
+    Linked,
+    /// The targeted objects only
+    Target,
+    /// The targeted objects only, when they have usage limits
+    UsageLimits,
+}
+
+impl UndoJournal {
+    /// Save the objects that the operation may modify: the object targeted
+    /// by the request, which `user` has the right to operate on,
+    /// and, depending on the operation, the objects it is linked to, directly or not
+    async fn snapshot(
+        &mut self,
+        kms: &KMS,
+        operation: &Operation,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        self.target_owner = None;
+        let (unique_identifier, operation_type, scope) = match operation {
+            Operation::Import(request) => (
+                Some(&request.unique_identifier),
+                ObjectOperationType::Import,
+                SnapshotScope::Linked,
+            ),
+            Operation::Certify(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Certify,
+                SnapshotScope::Linked,
+            ),
+            Operation::SetAttribute(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::SetAttributes,
+                SnapshotScope::Linked,
+            ),
+            Operation::AddAttribute(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::SetAttributes,
+                SnapshotScope::Linked,
+            ),
+            Operation::ModifyAttribute(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::SetAttributes,
+                SnapshotScope::Linked,
+            ),
+            Operation::DeleteAttribute(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::SetAttributes,
+                SnapshotScope::Linked,
+            ),
+            Operation::Activate(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Activate,
+                SnapshotScope::Linked,
+            ),
+            Operation::Revoke(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Revoke,
+                SnapshotScope::Linked,
+            ),
+            Operation::ReKey(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Rekey,
+                SnapshotScope::Linked,
+            ),
+            Operation::ReKeyKeyPair(request) => (
+                request.private_key_unique_identifier.as_ref(),
+                ObjectOperationType::Rekey,
+                SnapshotScope::Linked,
+            ),
+            Operation::Destroy(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Destroy,
+                SnapshotScope::Linked,
+            ),
+            Operation::DeriveKey(request) => (
+                Some(&request.object_unique_identifier),
+                ObjectOperationType::Derive,
+                SnapshotScope::Target,
+            ),
+            Operation::Encrypt(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Encrypt,
+                SnapshotScope::UsageLimits,
+            ),
+            Operation::Decrypt(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Decrypt,
+                SnapshotScope::UsageLimits,
+            ),
+            Operation::Sign(request) => (
+                request.unique_identifier.as_ref(),
+                ObjectOperationType::Sign,
+                SnapshotScope::UsageLimits,
+            ),
+            _ => return Ok(()),
+        };
+        let Some(uid_or_tags) = unique_identifier.and_then(UniqueIdentifier::as_str) else {
+            return Ok(())
+        };
+
+        // the linked objects are updated by the operation on behalf of the owner
+        // of the object linking them: they are retrieved as that owner
+        let mut pending = vec![(uid_or_tags.to_owned(), user.to_owned(), operation_type)];
+        while let Some((uid_or_tags, user, operation_type)) = pending.pop() {
+            let objects = kms
+                .db
+                .retrieve(&uid_or_tags, &user, operation_type, params)
+                .await?;
+            for (uid, owm) in objects {
+                if self.target_owner.is_none() {
+                    self.target_owner = Some(owm.owner.clone());
+                }
+                if self.saved_uids.contains(&uid)
+                    || self.created_uids.iter().any(|(created, _)| created == &uid)
+                    || (scope == SnapshotScope::UsageLimits
+                        && object_attributes(&owm).usage_limits.is_none())
+                {
+                    continue
+                }
+                if scope == SnapshotScope::Linked {
+                    pending.extend(
+                        [
+                            LinkType::PublicKeyLink,
+                            LinkType::PrivateKeyLink,
+                            LinkType::CertificateLink,
+                        ]
+                        .into_iter()
+                        .filter_map(|link_type| owm.attributes.get_link(link_type))
+                        .map(|link| (link, owm.owner.clone(), ObjectOperationType::Get)),
+                    );
+                }
+                let tags = kms.db.retrieve_tags(&uid, params).await?;
+                self.saved_uids.insert(uid.clone());
+                self.saved.push((
+                    owm.owner,
+                    AtomicOperation::Upsert((
+                        uid,
+                        owm.object,
+                        owm.attributes,
+                        Some(tags),
+                        owm.state,
+                    )),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Record the objects created, archived or recovered by a successful operation
+    /// requested by `user`
+    fn record(&mut self, operation: &Operation, user: &str) {
+        // a re-keyed object is replaced under the owner of the existing object
+        let rekey_owner = self.target_owner.as_deref().unwrap_or(user);
+        let (created, creator) = match operation {
+            Operation::ImportResponse(response) => (vec![&response.unique_identifier], user),
+            Operation::CertifyResponse(response) => (vec![&response.unique_identifier], user),
+            Operation::CreateResponse(response) => (vec![&response.unique_identifier], user),
+            Operation::DeriveKeyResponse(response) => (vec![&response.unique_identifier], user),
+            Operation::ReKeyResponse(response) => (vec![&response.unique_identifier], rekey_owner),
+            Operation::CreateKeyPairResponse(response) => (
+                vec![
+                    &response.private_key_unique_identifier,
+                    &response.public_key_unique_identifier,
+                ],
+                user,
+            ),
+            Operation::ReKeyKeyPairResponse(response) => (
+                vec![
+                    &response.private_key_unique_identifier,
+                    &response.public_key_unique_identifier,
+                ],
+                rekey_owner,
+            ),
+            Operation::ArchiveResponse(response) => {
+                if let Some(uid) = response.unique_identifier.as_str() {
+                    self.moved_uids.push((uid.to_owned(), true));
+                }
+                return
+            }
+            Operation::RecoverResponse(response) => {
+                if let Some(uid) = response.unique_identifier.as_str() {
+                    self.moved_uids.push((uid.to_owned(), false));
+                }
+                return
+            }
+            _ => return,
+        };
+        for uid in created.into_iter().filter_map(UniqueIdentifier::as_str) {
+            // an object replaced by an import was saved and is restored instead
+            if !self.saved_uids.contains(uid) {
+                self.created_uids.push((uid.to_owned(), creator.to_owned()));
+            }
+        }
+    }
+
+    /// Roll back the changes recorded in the journal:
+    /// archived and recovered objects are moved back, then the created objects
+    /// are deleted and the saved objects restored in a transaction per owner
+    async fn rollback(self, kms: &KMS, params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        debug!(
+            "Undoing the batch: deleting {} objects, restoring {} objects",
+            self.created_uids.len(),
+            self.saved.len()
+        );
+        let undo_failed = |e: KmsError| {
+            KmsError::KmipError(
+                ErrorReason::General_Failure,
+                format!("failed undoing the batch: {e}"),
+            )
+        };
+
+        for (uid, archived) in self.moved_uids.iter().rev() {
+            let moved_back = if *archived {
+                kms.db.recover(uid, params).await
+            } else {
+                kms.db.archive(uid, params).await
+            };
+            moved_back.map_err(undo_failed)?;
+        }
+
+        // objects are deleted and restored with the identity of their owner
+        let mut operations_per_owner: BTreeMap<String, Vec<AtomicOperation>> = BTreeMap::new();
+        for (uid, owner) in self.created_uids {
+            operations_per_owner
+                .entry(owner)
+                .or_default()
+                .push(AtomicOperation::Delete(uid));
+        }
+        for (owner, operation) in self.saved {
+            operations_per_owner
+                .entry(owner)
+                .or_default()
+                .push(operation);
+        }
+        for (owner, operations) in &operations_per_owner {
+            kms.db
+                .atomic(owner, operations, params)
+                .await
+                .map_err(undo_failed)?;
+        }
+        // the restored certificates may have changed state
+        kms.revocation_cache.clear()
+    }
+}
@@ -1,8 +1,16 @@
+mod activate;
+mod add_attribute;
+mod archive;
+mod attributes_utils;
+mod cancel;
 mod certify;
 mod create;
 mod create_key_pair;
 mod decrypt;
+mod delete_attribute;
+mod derive_key;
 mod destroy;
+mod discover_versions;
 mod dispatch;
 mod encrypt;
 mod export;
@@ -10,17 +18,37 @@ mod export_utils;
 mod get;
 mod get_attributes;
 mod import;
+mod jobs;
 mod locate;
+mod mac;
+mod mac_verify;
 mod message;
+mod modify_attribute;
+mod poll;
+mod query;
+mod recover;
+mod rekey;
 mod rekey_keypair;
 mod revoke;
+mod rng;
+mod set_attribute;
+mod sign;
+mod signature_verify;
+mod validate;
 mod wrapping;
 
+pub(crate) use activate::activate;
+pub(crate) use add_attribute::add_attribute;
+pub(crate) use archive::archive;
+pub(crate) use cancel::cancel;
 pub(crate) use certify::certify;
 pub(crate) use create::create;
 pub(crate) use create_key_pair::create_key_pair;
 pub(crate) use decrypt::decrypt;
+pub(crate) use delete_attribute::delete_attribute;
+pub(crate) use derive_key::derive_key;
 pub(crate) use destroy::{destroy_operation, recursively_destroy_key};
+pub(crate) use discover_versions::discover_versions;
 pub(crate) use dispatch::dispatch;
 pub(crate) use encrypt::encrypt;
 pub(crate) use export::export;
@@ -28,8 +56,21 @@ pub(crate) use export_utils::export_get;
 pub(crate) use get::get;
 pub(crate) use get_attributes::get_attributes;
 pub(crate) use import::import;
+pub(crate) use jobs::{resume_interrupted_jobs, RunningJobs};
 pub(crate) use locate::locate;
-pub(crate) use message::message;
+pub(crate) use mac::{mac, MacStreams};
+pub(crate) use mac_verify::mac_verify;
+pub(crate) use message::{binary_message_ttlv, message, message_ttlv};
+pub(crate) use modify_attribute::modify_attribute;
+pub(crate) use poll::poll;
+pub(crate) use query::query;
+pub(crate) use recover::recover;
+pub(crate) use rekey::rekey;
 pub(crate) use rekey_keypair::rekey_keypair;
 pub(crate) use revoke::{recursively_revoke_key, revoke_operation};
+pub(crate) use rng::{rng_retrieve, rng_seed};
+pub(crate) use set_attribute::set_attribute;
+pub(crate) use sign::sign;
+pub(crate) use signature_verify::signature_verify;
+pub(crate) use validate::validate;
 pub(crate) use wrapping::{unwrap_key, wrap_key};
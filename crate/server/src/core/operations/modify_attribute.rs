@@ -0,0 +1,157 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{ErrorReason, ModifyAttribute, ModifyAttributeResponse},
+    kmip_types::{Attribute, Attributes},
+};
+use tracing::{debug, trace};
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::{
+            object_attributes, retrieve_object_for_update, retrieve_split_tags,
+            set_single_instance_value, single_instance_value, update_object_attributes,
+        },
+        KMS,
+    },
+    error::KmsError,
+    kms_bail,
+    result::KResult,
+};
+
+pub async fn modify_attribute(
+    kms: &KMS,
+    request: ModifyAttribute,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ModifyAttributeResponse> {
+    trace!("Modify attribute: {}", serde_json::to_string(&request)?);
+
+    let owm = retrieve_object_for_update(
+        request.unique_identifier.as_ref(),
+        "Modify Attribute",
+        kms,
+        user,
+        params,
+    )
+    .await?;
+    let mut attributes = object_attributes(&owm);
+
+    let mut tags = None;
+    if let Some(user_tags) = request.new_attribute.get_tags()? {
+        // the current user tags, or all of them if not specified, are replaced
+        Attributes::check_user_tags(&user_tags)?;
+        let (system_tags, mut existing_user_tags) =
+            retrieve_split_tags(kms, &owm.id, params).await?;
+        match request
+            .current_attribute
+            .as_ref()
+            .map(Attribute::get_tags)
+            .transpose()?
+            .flatten()
+        {
+            Some(current_tags) => {
+                if !current_tags.is_subset(&existing_user_tags) {
+                    kms_bail!(KmsError::KmipError(
+                        ErrorReason::Attribute_Instance_Not_Found,
+                        "the current tags are not all tags of the object".to_owned()
+                    ))
+                }
+                existing_user_tags.retain(|tag| !current_tags.contains(tag));
+            }
+            None => {
+                if existing_user_tags.is_empty() {
+                    kms_bail!(KmsError::KmipError(
+                        ErrorReason::Attribute_Not_Found,
+                        "the object has no user tags".to_owned()
+                    ))
+                }
+                existing_user_tags.clear();
+            }
+        }
+        tags = Some(
+            system_tags
+                .into_iter()
+                .chain(existing_user_tags)
+                .chain(user_tags)
+                .collect(),
+        );
+    } else if let Attribute::Link(link) = request.new_attribute {
+        // the current link, or the single link of the same type, is replaced
+        let links = attributes.link.get_or_insert_with(Vec::new);
+        let positions = match &request.current_attribute {
+            Some(Attribute::Link(current_link)) => links
+                .iter()
+                .position(|l| l == current_link)
+                .into_iter()
+                .collect::<Vec<usize>>(),
+            Some(_) => kms_bail!(KmsError::InvalidRequest(
+                "Modify Attribute: the current and new attributes must be of the same kind"
+                    .to_owned()
+            )),
+            None => links
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.link_type == link.link_type)
+                .map(|(position, _)| position)
+                .collect(),
+        };
+        match positions.as_slice() {
+            [position] => links[*position] = link,
+            [] => kms_bail!(KmsError::KmipError(
+                ErrorReason::Attribute_Instance_Not_Found,
+                format!("no link of type {} to modify", link.link_type)
+            )),
+            _ => kms_bail!(KmsError::KmipError(
+                ErrorReason::Multi_Valued_Attribute,
+                format!(
+                    "the object has multiple links of type {}: the current link must be specified",
+                    link.link_type
+                )
+            )),
+        }
+    } else if let Attribute::Name(name) = request.new_attribute {
+        // the current name, or the single name, is replaced
+        let names = attributes.name.get_or_insert_with(Vec::new);
+        let position = match &request.current_attribute {
+            Some(Attribute::Name(current_name)) => names.iter().position(|n| n == current_name),
+            Some(_) => kms_bail!(KmsError::InvalidRequest(
+                "Modify Attribute: the current and new attributes must be of the same kind"
+                    .to_owned()
+            )),
+            None if names.len() > 1 => kms_bail!(KmsError::KmipError(
+                ErrorReason::Multi_Valued_Attribute,
+                "the object has multiple names: the current name must be specified".to_owned()
+            )),
+            None => names.first().map(|_| 0),
+        };
+        let Some(position) = position else {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Attribute_Instance_Not_Found,
+                "no name to modify".to_owned()
+            ))
+        };
+        names[position] = name;
+    } else {
+        // the single-instance attribute must exist and match the current value, if specified
+        let Some(value) = single_instance_value(&attributes, &request.new_attribute)? else {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Attribute_Not_Found,
+                "the attribute to modify does not exist".to_owned()
+            ))
+        };
+        if let Some(current_attribute) = &request.current_attribute {
+            if current_attribute != &value {
+                kms_bail!(KmsError::KmipError(
+                    ErrorReason::Attribute_Instance_Not_Found,
+                    "the current attribute does not match the value of the object".to_owned()
+                ))
+            }
+        }
+        set_single_instance_value(&mut attributes, request.new_attribute)?;
+    }
+
+    let unique_identifier = update_object_attributes(kms, owm, attributes, tags, params).await?;
+    kms.revocation_cache.clear()?;
+    debug!("Modified an attribute of the object {unique_identifier}");
+    Ok(ModifyAttributeResponse { unique_identifier })
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use cosmian_kmip::kmip::{
+    kmip_messages::MessageResponseBatchItem,
+    kmip_operations::{ErrorReason, Poll},
+    kmip_types::ResultStatusEnumeration,
+    ttlv::deserializer::from_ttlv,
+};
+use tracing::trace;
+
+use super::jobs::{resume_interrupted_job, retrieve_job};
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    database::job::JobState,
+    error::KmsError,
+    kms_bail,
+    result::KResult,
+};
+
+/// Poll an operation processed asynchronously
+///
+/// The response batch item of the operation is returned once it completed or failed;
+/// a still pending operation is reported as such, and resumed if it was interrupted.
+pub(crate) async fn poll(
+    kms: &Arc<KMS>,
+    request: Poll,
+    owner: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<MessageResponseBatchItem> {
+    trace!("Poll: {}", serde_json::to_string(&request)?);
+
+    let job = retrieve_job(kms, &request.asynchronous_correlation_value, owner, params).await?;
+    let job = resume_interrupted_job(kms, job, params).await?;
+    match job.state {
+        JobState::Pending => Ok(MessageResponseBatchItem {
+            asynchronous_correlation_value: Some(request.asynchronous_correlation_value),
+            ..MessageResponseBatchItem::new(ResultStatusEnumeration::OperationPending)
+        }),
+        JobState::Completed | JobState::Failed => {
+            let response = job.response.ok_or_else(|| {
+                KmsError::ServerError(format!("the job {} has no response", job.id))
+            })?;
+            Ok(from_ttlv(&response)?)
+        }
+        JobState::Canceled => kms_bail!(KmsError::KmipError(
+            ErrorReason::Operation_Canceled_By_Requester,
+            "the operation was canceled".to_owned()
+        )),
+    }
+}
@@ -0,0 +1,112 @@
+use clap::crate_version;
+use cosmian_kmip::kmip::{
+    kmip_objects::ObjectType,
+    kmip_operations::{Query, QueryResponse},
+    kmip_types::{CapabilityInformation, OperationEnumeration, QueryFunction, ServerInformation},
+};
+use tracing::trace;
+
+use crate::{core::KMS, result::KResult};
+
+/// The KMIP operations supported by the server
+const SUPPORTED_OPERATIONS: [OperationEnumeration; 33] = [
+    OperationEnumeration::Create,
+    OperationEnumeration::CreateKeyPair,
+    OperationEnumeration::Rekey,
+    OperationEnumeration::DeriveKey,
+    OperationEnumeration::Certify,
+    OperationEnumeration::Locate,
+    OperationEnumeration::Get,
+    OperationEnumeration::GetAttributes,
+    OperationEnumeration::AddAttribute,
+    OperationEnumeration::ModifyAttribute,
+    OperationEnumeration::DeleteAttribute,
+    OperationEnumeration::Activate,
+    OperationEnumeration::Revoke,
+    OperationEnumeration::Destroy,
+    OperationEnumeration::Archive,
+    OperationEnumeration::Recover,
+    OperationEnumeration::Query,
+    OperationEnumeration::Cancel,
+    OperationEnumeration::Poll,
+    OperationEnumeration::RekeyKeyPair,
+    OperationEnumeration::DiscoverVersions,
+    OperationEnumeration::Encrypt,
+    OperationEnumeration::Decrypt,
+    OperationEnumeration::Sign,
+    OperationEnumeration::SignatureVerify,
+    OperationEnumeration::Validate,
+    OperationEnumeration::MAC,
+    OperationEnumeration::MACVerify,
+    OperationEnumeration::RNGRetrieve,
+    OperationEnumeration::RNGSeed,
+    OperationEnumeration::Import,
+    OperationEnumeration::Export,
+    OperationEnumeration::SetAttribute,
+];
+
+/// The KMIP object types which can be managed by the server
+const SUPPORTED_OBJECT_TYPES: [ObjectType; 5] = [
+    ObjectType::Certificate,
+    ObjectType::SymmetricKey,
+    ObjectType::PublicKey,
+    ObjectType::PrivateKey,
+    ObjectType::SecretData,
+];
+
+pub(crate) async fn query(kms: &KMS, request: Query) -> KResult<QueryResponse> {
+    trace!("Query: {}", serde_json::to_string(&request)?);
+
+    let mut response = QueryResponse::default();
+    for query_function in request.query_function.unwrap_or_default() {
+        match query_function {
+            QueryFunction::QueryOperations => {
+                response.operation = Some(SUPPORTED_OPERATIONS.to_vec());
+            }
+            QueryFunction::QueryObjects => {
+                response.object_type = Some(SUPPORTED_OBJECT_TYPES.to_vec());
+            }
+            QueryFunction::QueryServerInformation => {
+                response.vendor_identification = Some("Cosmian".to_owned());
+                response.server_information = Some(server_information(kms));
+            }
+            QueryFunction::QueryCapabilities => {
+                response.capability_information = Some(vec![CapabilityInformation {
+                    // multi-part MAC operations
+                    streaming_capability: Some(true),
+                    asynchronous_capability: Some(true),
+                    attestation_capability: Some(false),
+                    // the rollback of a batch is a best effort, not a transaction
+                    batch_undo_capability: Some(false),
+                    // the Batch Error Continuation Option of messages is honored
+                    batch_continue_capability: Some(true),
+                    quantum_safe_capability: None,
+                }]);
+            }
+            // nothing to report for the other query functions
+            _ => {}
+        }
+    }
+    Ok(response)
+}
+
+fn server_information(kms: &KMS) -> ServerInformation {
+    ServerInformation {
+        server_name: Some("Cosmian KMS".to_owned()),
+        server_version: Some(crate_version!().to_owned()),
+        product_name: Some("cosmian_kms_server".to_owned()),
+        build_level: Some(
+            if cfg!(feature = "fips") {
+                "FIPS"
+            } else {
+                "non-FIPS"
+            }
+            .to_owned(),
+        ),
+        cluster_info: kms
+            .params
+            .db_params
+            .as_ref()
+            .map(|db_params| db_params.db_name().to_owned()),
+    }
+}
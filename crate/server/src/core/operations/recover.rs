@@ -0,0 +1,62 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{ErrorReason, Recover, RecoverResponse},
+    kmip_types::UniqueIdentifier,
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::{debug, trace};
+
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+pub async fn recover(
+    kms: &KMS,
+    request: Recover,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<RecoverResponse> {
+    trace!("Recover: {}", serde_json::to_string(&request)?);
+
+    // archived objects are not indexed by tags: there must be an identifier
+    let uid = request
+        .unique_identifier
+        .as_ref()
+        .ok_or(KmsError::UnsupportedPlaceholder)?
+        .as_str()
+        .context("Recover: the unique identifier must be a string")?;
+    if uid.starts_with('[') {
+        kms_bail!(KmsError::InvalidRequest(
+            "Recover: archived objects must be recovered using their unique identifier".to_owned()
+        ))
+    }
+
+    // the user must own the object or have been granted the archive access right
+    let owner = kms
+        .db
+        .retrieve_archived_owner(uid, params)
+        .await?
+        .ok_or_else(|| KmsError::KmipError(ErrorReason::Item_Not_Found, uid.to_owned()))?;
+    if owner != user
+        && !kms
+            .db
+            .list_user_access_rights_on_object(uid, user, false, params)
+            .await?
+            .contains(&ObjectOperationType::Archive)
+    {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Item_Not_Found,
+            uid.to_owned()
+        ))
+    }
+
+    kms.db.recover(uid, params).await?;
+    kms.revocation_cache.clear()?;
+    debug!("Recovered object {uid}");
+
+    Ok(RecoverResponse {
+        unique_identifier: UniqueIdentifier::TextString(uid.to_owned()),
+    })
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+
+use cosmian_kmip::kmip::{
+    kmip_data_structures::KeyWrappingSpecification,
+    kmip_objects::ObjectType,
+    kmip_operations::{Create, ErrorReason, ReKey, ReKeyResponse},
+    kmip_types::{
+        Attributes, KeyFormatType, LinkType, LinkedObjectIdentifier, StateEnumeration,
+        UniqueIdentifier,
+    },
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::{debug, trace};
+use uuid::Uuid;
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::{attributes_utils::set_initial_attributes, wrap_key},
+        KMS,
+    },
+    database::{object_with_metadata::ObjectWithMetadata, AtomicOperation},
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+pub async fn rekey(
+    kms: &KMS,
+    request: ReKey,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ReKeyResponse> {
+    trace!("ReKey: {}", serde_json::to_string(&request)?);
+    if request.protection_storage_masks.is_some() {
+        kms_bail!(KmsError::UnsupportedPlaceholder)
+    }
+
+    // there must be an identifier
+    let uid_or_tags = request
+        .unique_identifier
+        .as_ref()
+        .ok_or(KmsError::UnsupportedPlaceholder)?
+        .as_str()
+        .context("ReKey: the unique identifier must be a string")?;
+
+    // retrieve from tags or use passed identifier
+    let mut owm_s = kms
+        .db
+        .retrieve(uid_or_tags, user, ObjectOperationType::Rekey, params)
+        .await?
+        .into_values()
+        .filter(|owm| {
+            owm.state == StateEnumeration::Active
+                && owm.object.object_type() == ObjectType::SymmetricKey
+        })
+        .collect::<Vec<ObjectWithMetadata>>();
+
+    // there can only be one key
+    let mut owm = owm_s
+        .pop()
+        .ok_or_else(|| KmsError::KmipError(ErrorReason::Item_Not_Found, uid_or_tags.to_string()))?;
+
+    if !owm_s.is_empty() {
+        return Err(KmsError::InvalidRequest(format!(
+            "rekey: too many objects for key {uid_or_tags}",
+        )))
+    }
+
+    // the replacement key has the algorithm, length and usage of the existing key,
+    // possibly overridden by the attributes of the request
+    let existing_attributes = owm.object.attributes()?;
+    let mut attributes = Attributes {
+        object_type: Some(ObjectType::SymmetricKey),
+        cryptographic_algorithm: existing_attributes.cryptographic_algorithm,
+        cryptographic_length: existing_attributes.cryptographic_length,
+        cryptographic_usage_mask: existing_attributes.cryptographic_usage_mask,
+        key_format_type: Some(KeyFormatType::TransparentSymmetricKey),
+        ..Attributes::default()
+    };
+    if let Some(request_attributes) = &request.attributes {
+        if request_attributes.cryptographic_length.is_some() {
+            attributes.cryptographic_length = request_attributes.cryptographic_length;
+        }
+        if request_attributes.cryptographic_usage_mask.is_some() {
+            attributes.cryptographic_usage_mask = request_attributes.cryptographic_usage_mask;
+        }
+    }
+
+    // the replacement key takes over the user tags of the existing key
+    let mut user_tags = kms
+        .db
+        .retrieve_tags(&owm.id, params)
+        .await?
+        .into_iter()
+        .filter(|tag| !tag.starts_with('_'))
+        .collect::<HashSet<String>>();
+    if let Some(request_attributes) = &request.attributes {
+        user_tags.extend(request_attributes.get_tags());
+    }
+    attributes.set_tags(&user_tags)?;
+
+    // create the replacement key
+    let (mut object, tags) = kms.create_symmetric_key_and_tags(&Create {
+        object_type: ObjectType::SymmetricKey,
+        attributes,
+        protection_storage_masks: None,
+    })?;
+
+    // If no offset is specified, the activation date is copied from the existing key,
+    // otherwise the replacement key is activated `offset` seconds from now
+    let activation_date = match request.offset {
+        None => existing_attributes.activation_date,
+        Some(offset) if offset >= 0 => {
+            Some(chrono::Utc::now().timestamp_millis() as u64 + offset as u64 * 1000)
+        }
+        Some(_) => kms_bail!(KmsError::InvalidRequest(
+            "ReKey: the offset must be positive".to_owned()
+        )),
+    };
+    let cryptographic_parameters = existing_attributes.cryptographic_parameters.clone();
+    let replacement_attributes = object.attributes_mut()?;
+    replacement_attributes.activation_date = activation_date;
+    replacement_attributes.cryptographic_parameters = cryptographic_parameters;
+
+    // the replacement key is wrapped like the existing key
+    if let Some(key_wrapping_data) = owm.object.key_wrapping_data() {
+        let key_wrapping_specification = KeyWrappingSpecification {
+            wrapping_method: key_wrapping_data.wrapping_method,
+            encryption_key_information: key_wrapping_data.encryption_key_information.clone(),
+            mac_or_signature_key_information: key_wrapping_data
+                .mac_or_signature_key_information
+                .clone(),
+            attribute_name: None,
+            encoding_option: key_wrapping_data.encoding_option,
+        };
+        wrap_key(
+            object.key_block_mut()?,
+            &key_wrapping_specification,
+            kms,
+            &owm.owner,
+            params,
+        )
+        .await?;
+    }
+
+    // link the existing and the replacement keys
+    let uid = Uuid::new_v4().to_string();
+    object.attributes_mut()?.add_link(
+        LinkType::ReplacedObjectLink,
+        LinkedObjectIdentifier::TextString(owm.id.clone()),
+    );
+    owm.object.attributes_mut()?.add_link(
+        LinkType::ReplacementObjectLink,
+        LinkedObjectIdentifier::TextString(uid.clone()),
+    );
+    let mut object_attributes = object.attributes()?.clone();
+    set_initial_attributes(&mut object, &mut object_attributes);
+    let existing_attributes = owm.object.attributes_mut()?;
+    existing_attributes.last_change_date = object_attributes.initial_date;
+    existing_attributes.state = Some(StateEnumeration::Deactivated);
+    let existing_attributes = existing_attributes.clone();
+
+    let operations = [
+        AtomicOperation::Create((uid.clone(), object, object_attributes, tags)),
+        AtomicOperation::UpdateObject((owm.id.clone(), owm.object, existing_attributes, None)),
+        AtomicOperation::UpdateState((owm.id.clone(), StateEnumeration::Deactivated)),
+    ];
+    // the replacement key belongs to the owner of the existing key,
+    // not to a user who was granted the right to re-key it
+    kms.db.atomic(&owm.owner, &operations, params).await?;
+
+    debug!("Re-keyed symmetric key {} into {uid}", owm.id);
+    Ok(ReKeyResponse {
+        unique_identifier: UniqueIdentifier::TextString(uid),
+    })
+}
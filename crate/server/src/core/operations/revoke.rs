@@ -14,9 +14,12 @@ use tracing::debug;
 
 use crate::{
     core::{
-        cover_crypt::revoke_user_decryption_keys, extra_database_params::ExtraDatabaseParams, KMS,
+        cover_crypt::revoke_user_decryption_keys,
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::{now_millis, object_attributes},
+        KMS,
     },
-    database::object_with_metadata::ObjectWithMetadata,
+    database::{object_with_metadata::ObjectWithMetadata, AtomicOperation},
     error::KmsError,
     kms_bail,
     result::{KResult, KResultHelper},
@@ -28,8 +31,6 @@ pub(crate) async fn revoke_operation(
     user: &str,
     params: Option<&ExtraDatabaseParams>,
 ) -> KResult<RevokeResponse> {
-    //TODO   Reasons should be kept
-
     let revocation_reason = request.revocation_reason.clone();
     let compromise_occurrence_date = request.compromise_occurrence_date;
 
@@ -51,6 +52,8 @@ pub(crate) async fn revoke_operation(
         HashSet::new(),
     )
     .await?;
+    // the revoked certificates must show in the next CRL and OCSP responses
+    kms.revocation_cache.clear()?;
 
     Ok(RevokeResponse {
         unique_identifier: UniqueIdentifier::TextString(uid_or_tags.to_string()),
@@ -100,7 +103,7 @@ pub(crate) async fn recursively_revoke_key<'a: 'async_recursion>(
             SymmetricKey | ObjectType::Certificate => {
                 // revoke the key
                 revoke_key_core(
-                    &owm.id,
+                    owm,
                     revocation_reason.clone(),
                     compromise_occurrence_date,
                     kms,
@@ -143,7 +146,7 @@ pub(crate) async fn recursively_revoke_key<'a: 'async_recursion>(
                 }
                 // now revoke the private key
                 revoke_key_core(
-                    &owm.id,
+                    owm,
                     revocation_reason.clone(),
                     compromise_occurrence_date,
                     kms,
@@ -173,7 +176,7 @@ pub(crate) async fn recursively_revoke_key<'a: 'async_recursion>(
                 }
                 // revoke the public key
                 revoke_key_core(
-                    &owm.id,
+                    owm,
                     revocation_reason.clone(),
                     compromise_occurrence_date,
                     kms,
@@ -190,16 +193,16 @@ pub(crate) async fn recursively_revoke_key<'a: 'async_recursion>(
     Ok(())
 }
 
-/// Revoke a key, knowing the object and state
-#[allow(clippy::too_many_arguments)]
+/// Revoke a key, knowing the object and state.
+/// The revocation reason and dates are kept in the attributes of the object.
 async fn revoke_key_core(
-    unique_identifier: &str,
+    mut owm: ObjectWithMetadata,
     revocation_reason: RevocationReason,
     compromise_occurrence_date: Option<u64>,
     kms: &KMS,
     params: Option<&ExtraDatabaseParams>,
 ) -> KResult<()> {
-    let state = match revocation_reason {
+    let state = match &revocation_reason {
         RevocationReason::Enumeration(e) => match e {
             RevocationReasonEnumeration::Unspecified
             | RevocationReasonEnumeration::AffiliationChanged
@@ -219,14 +222,33 @@ async fn revoke_key_core(
         },
         RevocationReason::TextString(_) => StateEnumeration::Deactivated,
     };
+
+    let now = now_millis();
+    let mut attributes = object_attributes(&owm);
+    attributes.revocation_reason = Some(revocation_reason);
+    attributes.deactivation_date = Some(now);
+    attributes.last_change_date = Some(now);
+    attributes.state = Some(state);
+    attributes.compromise_occurrence_date = compromise_occurrence_date;
+    if state == StateEnumeration::Compromised {
+        attributes.compromise_date = Some(now);
+    }
+    if let Ok(key_block_attributes) = owm.object.attributes_mut() {
+        key_block_attributes.clone_from(&attributes);
+    }
+
     kms.db
-        .update_state(unique_identifier, state, params)
+        .atomic(
+            &owm.owner,
+            &[
+                AtomicOperation::UpdateObject((owm.id.clone(), owm.object, attributes, None)),
+                AtomicOperation::UpdateState((owm.id.clone(), state)),
+            ],
+            params,
+        )
         .await?;
 
-    debug!(
-        "Object with unique identifier: {} revoked",
-        unique_identifier
-    );
+    debug!("Object with unique identifier: {} revoked", owm.id);
 
     Ok(())
 }
@@ -0,0 +1,75 @@
+use cosmian_kmip::kmip::kmip_operations::{
+    ErrorReason, RNGRetrieve, RNGRetrieveResponse, RNGSeed, RNGSeedResponse,
+};
+use openssl::rand::rand_bytes;
+use tracing::{info, trace};
+
+use crate::{error::KmsError, kms_bail, result::KResult};
+
+/// The maximum amount of data, in bytes, exchanged by a single RNG request
+const MAX_RNG_DATA_LENGTH: usize = 1024 * 1024;
+
+/// Return random bytes drawn from the OpenSSL DRBG of the server
+/// (the DRBG of the FIPS provider when the server is built in FIPS mode)
+///
+/// Each retrieval is logged, with its caller, for audit.
+pub(crate) async fn rng_retrieve(request: RNGRetrieve, user: &str) -> KResult<RNGRetrieveResponse> {
+    trace!("RNGRetrieve: {} bytes", request.data_length);
+
+    let data_length = usize::try_from(request.data_length).map_err(|_e| {
+        KmsError::KmipError(
+            ErrorReason::Invalid_Field,
+            format!("invalid random data length: {}", request.data_length),
+        )
+    })?;
+    if data_length == 0 {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Invalid_Field,
+            "the random data length must be positive".to_owned()
+        ))
+    }
+    if data_length > MAX_RNG_DATA_LENGTH {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Response_Too_Large,
+            format!(
+                "at most {MAX_RNG_DATA_LENGTH} random bytes can be retrieved by a single request, \
+                 {data_length} were requested"
+            )
+        ))
+    }
+
+    let mut data = vec![0_u8; data_length];
+    rand_bytes(&mut data)?;
+    info!("RNGRetrieve: {data_length} random bytes retrieved by {user}");
+    Ok(RNGRetrieveResponse { data })
+}
+
+/// Seed data are not used by the server: the OpenSSL DRBG is seeded and
+/// reseeded from the entropy source of the host, and a FIPS-approved DRBG
+/// must not be seeded by external input.
+///
+/// The request is validated and the response reports that no seed data
+/// were accepted, as allowed by the KMIP specification.
+/// Each request is logged, with its caller, for audit.
+pub(crate) async fn rng_seed(request: RNGSeed, user: &str) -> KResult<RNGSeedResponse> {
+    trace!("RNGSeed: {} bytes", request.data.len());
+
+    if request.data.is_empty() {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Invalid_Field,
+            "the seed data must not be empty".to_owned()
+        ))
+    }
+    if request.data.len() > MAX_RNG_DATA_LENGTH {
+        kms_bail!(KmsError::KmipError(
+            ErrorReason::Invalid_Field,
+            format!("at most {MAX_RNG_DATA_LENGTH} bytes of seed data can be provided")
+        ))
+    }
+
+    info!(
+        "RNGSeed: {} bytes of seed data provided by {user}, none accepted",
+        request.data.len()
+    );
+    Ok(RNGSeedResponse { data_length: 0 })
+}
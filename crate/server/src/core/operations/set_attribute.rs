@@ -0,0 +1,82 @@
+use cosmian_kmip::kmip::{
+    kmip_operations::{ErrorReason, SetAttribute, SetAttributeResponse},
+    kmip_types::{Attribute, Attributes},
+};
+use tracing::{debug, trace};
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::attributes_utils::{
+            object_attributes, retrieve_object_for_update, retrieve_split_tags,
+            set_single_instance_value, update_object_attributes,
+        },
+        KMS,
+    },
+    error::KmsError,
+    kms_bail,
+    result::KResult,
+};
+
+pub async fn set_attribute(
+    kms: &KMS,
+    request: SetAttribute,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<SetAttributeResponse> {
+    trace!("Set attribute: {}", serde_json::to_string(&request)?);
+
+    let owm = retrieve_object_for_update(
+        request.unique_identifier.as_ref(),
+        "Set Attribute",
+        kms,
+        user,
+        params,
+    )
+    .await?;
+    let mut attributes = object_attributes(&owm);
+
+    let mut tags = None;
+    if let Some(user_tags) = request.new_attribute.get_tags()? {
+        // the user tags are replaced, the system tags are preserved
+        Attributes::check_user_tags(&user_tags)?;
+        let (system_tags, _) = retrieve_split_tags(kms, &owm.id, params).await?;
+        tags = Some(system_tags.into_iter().chain(user_tags).collect());
+    } else if let Attribute::Link(link) = request.new_attribute {
+        // the link of the same type, if any, is replaced
+        let same_type_links = attributes.link.as_ref().map_or(0, |links| {
+            links
+                .iter()
+                .filter(|l| l.link_type == link.link_type)
+                .count()
+        });
+        if same_type_links > 1 {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Multi_Valued_Attribute,
+                format!("the object has multiple links of type {}", link.link_type)
+            ))
+        }
+        attributes.remove_link(link.link_type);
+        attributes.add_link(link.link_type, link.linked_object_identifier);
+    } else if let Attribute::Name(name) = request.new_attribute {
+        // the single name, if any, is replaced
+        if attributes
+            .name
+            .as_ref()
+            .is_some_and(|names| names.len() > 1)
+        {
+            kms_bail!(KmsError::KmipError(
+                ErrorReason::Multi_Valued_Attribute,
+                "the object has multiple names".to_owned()
+            ))
+        }
+        attributes.name = Some(vec![name]);
+    } else {
+        set_single_instance_value(&mut attributes, request.new_attribute)?;
+    }
+
+    let unique_identifier = update_object_attributes(kms, owm, attributes, tags, params).await?;
+    kms.revocation_cache.clear()?;
+    debug!("Set an attribute of the object {unique_identifier}");
+    Ok(SetAttributeResponse { unique_identifier })
+}
@@ -0,0 +1,135 @@
+use cosmian_kmip::{
+    crypto::signature::sign as sign_with_pkey,
+    kmip::{
+        kmip_objects::ObjectType,
+        kmip_operations::{ErrorReason, Sign, SignResponse},
+        kmip_types::{CryptographicUsageMask, KeyFormatType, StateEnumeration, UniqueIdentifier},
+    },
+    openssl::kmip_private_key_to_openssl,
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::trace;
+
+use crate::{
+    core::{
+        extra_database_params::ExtraDatabaseParams,
+        operations::{attributes_utils::consume_usage_limits, unwrap_key},
+        KMS,
+    },
+    database::object_with_metadata::ObjectWithMetadata,
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+pub async fn sign(
+    kms: &KMS,
+    request: Sign,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<SignResponse> {
+    trace!("Sign: {:?}", &request.unique_identifier);
+
+    // streaming signatures are not supported
+    if request.correlation_value.is_some()
+        || request.init_indicator == Some(true)
+        || request.final_indicator == Some(false)
+    {
+        kms_bail!(KmsError::NotSupported(
+            "Sign: multi-part signatures are not supported".to_owned()
+        ))
+    }
+
+    let owm = get_key(kms, &request, user, params).await?;
+
+    // Make sure that the key used to sign can be used to sign.
+    if !owm
+        .object
+        .attributes()?
+        .is_usage_authorized_for(CryptographicUsageMask::Sign)?
+    {
+        return Err(KmsError::KmipError(
+            ErrorReason::Incompatible_Cryptographic_Usage_Mask,
+            "CryptographicUsageMask not authorized for Sign".to_owned(),
+        ))
+    }
+
+    let key_block = owm.object.key_block()?;
+    match &key_block.key_format_type {
+        KeyFormatType::TransparentECPrivateKey
+        | KeyFormatType::TransparentRSAPrivateKey
+        | KeyFormatType::PKCS1
+        | KeyFormatType::PKCS8 => {
+            let private_key = kmip_private_key_to_openssl(&owm.object)?;
+            trace!("sign: OpenSSL Private Key instantiated before signature");
+            let signature = sign_with_pkey(
+                &private_key,
+                request.cryptographic_parameters.as_ref(),
+                request.data.as_deref().map(Vec::as_slice),
+                request.digested_data.as_deref(),
+            )?;
+            let data_length = request
+                .data
+                .as_ref()
+                .map(|data| data.len())
+                .or_else(|| request.digested_data.as_ref().map(Vec::len))
+                .unwrap_or(0);
+            consume_usage_limits(kms, &owm, data_length, params).await?;
+            Ok(SignResponse {
+                unique_identifier: UniqueIdentifier::TextString(owm.id),
+                signature_data: Some(signature),
+                correlation_value: None,
+            })
+        }
+        other => Err(KmsError::NotSupported(format!(
+            "signature with private keys of format: {other}"
+        ))),
+    }
+}
+
+async fn get_key(
+    kms: &KMS,
+    request: &Sign,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ObjectWithMetadata> {
+    // there must be an identifier
+    let uid_or_tags = request
+        .unique_identifier
+        .as_ref()
+        .ok_or(KmsError::UnsupportedPlaceholder)?
+        .as_str()
+        .context("Sign: unique_identifier must be a string")?;
+    trace!("sign: uid_or_tags: {uid_or_tags}");
+
+    // retrieve from tags or use passed identifier
+    let mut owm_s = kms
+        .db
+        .retrieve(uid_or_tags, user, ObjectOperationType::Sign, params)
+        .await?
+        .into_values()
+        .filter(|owm| {
+            owm.state == StateEnumeration::Active
+                && owm.object.object_type() == ObjectType::PrivateKey
+        })
+        .collect::<Vec<ObjectWithMetadata>>();
+    trace!("sign: owm_s: {:?}", owm_s);
+
+    // there can only be one key
+    let mut owm = owm_s
+        .pop()
+        .ok_or_else(|| KmsError::KmipError(ErrorReason::Item_Not_Found, uid_or_tags.to_string()))?;
+
+    if !owm_s.is_empty() {
+        return Err(KmsError::InvalidRequest(format!(
+            "sign: too many objects for key {uid_or_tags}",
+        )))
+    }
+
+    // unwrap if wrapped
+    if owm.object.key_wrapping_data().is_some() {
+        let key_block = owm.object.key_block_mut()?;
+        unwrap_key(key_block, kms, &owm.owner, params).await?;
+    }
+    Ok(owm)
+}
@@ -0,0 +1,165 @@
+use cosmian_kmip::{
+    crypto::signature::verify,
+    kmip::{
+        kmip_objects::{Object, ObjectType},
+        kmip_operations::{ErrorReason, SignatureVerify, SignatureVerifyResponse},
+        kmip_types::{
+            CryptographicUsageMask, KeyFormatType, StateEnumeration, UniqueIdentifier,
+            ValidityIndicator,
+        },
+    },
+    openssl::kmip_public_key_to_openssl,
+    KmipError,
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use openssl::{
+    pkey::{PKey, Public},
+    x509::X509,
+};
+use tracing::trace;
+
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, operations::unwrap_key, KMS},
+    database::object_with_metadata::ObjectWithMetadata,
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+pub async fn signature_verify(
+    kms: &KMS,
+    request: SignatureVerify,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<SignatureVerifyResponse> {
+    trace!("SignatureVerify: {:?}", &request.unique_identifier);
+
+    // streaming verifications are not supported
+    if request.correlation_value.is_some()
+        || request.init_indicator == Some(true)
+        || request.final_indicator == Some(false)
+    {
+        kms_bail!(KmsError::NotSupported(
+            "SignatureVerify: multi-part signature verifications are not supported".to_owned()
+        ))
+    }
+
+    let signature = request.signature_data.as_ref().ok_or_else(|| {
+        KmsError::InvalidRequest("SignatureVerify: the signature must be provided".to_owned())
+    })?;
+
+    let owm = get_key(kms, &request, user, params).await?;
+
+    let public_key = match &owm.object {
+        Object::PublicKey { .. } => {
+            // Make sure that the key used to verify can be used to verify.
+            if !owm
+                .object
+                .attributes()?
+                .is_usage_authorized_for(CryptographicUsageMask::Verify)?
+            {
+                return Err(KmsError::KmipError(
+                    ErrorReason::Incompatible_Cryptographic_Usage_Mask,
+                    "CryptographicUsageMask not authorized for Verify".to_owned(),
+                ))
+            }
+            public_key_from_key_block(&owm)?
+        }
+        Object::Certificate {
+            certificate_value, ..
+        } => {
+            let cert = X509::from_der(certificate_value)
+                .map_err(|e| KmipError::ConversionError(format!("invalid X509 DER: {e:?}")))?;
+            cert.public_key().map_err(|e| {
+                KmipError::ConversionError(format!("invalid certificate public key: error: {e:?}"))
+            })?
+        }
+        other => kms_bail!(KmsError::NotSupported(format!(
+            "signature verification with objects of type: {} is not supported",
+            other.object_type()
+        ))),
+    };
+
+    let valid = verify(
+        &public_key,
+        request.cryptographic_parameters.as_ref(),
+        request.data.as_deref(),
+        request.digested_data.as_deref(),
+        signature,
+    )?;
+
+    Ok(SignatureVerifyResponse {
+        unique_identifier: UniqueIdentifier::TextString(owm.id),
+        validity_indicator: if valid {
+            ValidityIndicator::Valid
+        } else {
+            ValidityIndicator::Invalid
+        },
+        data: None,
+        correlation_value: None,
+    })
+}
+
+fn public_key_from_key_block(owm: &ObjectWithMetadata) -> KResult<PKey<Public>> {
+    let key_block = owm.object.key_block()?;
+    match &key_block.key_format_type {
+        KeyFormatType::TransparentECPublicKey
+        | KeyFormatType::TransparentRSAPublicKey
+        | KeyFormatType::PKCS1
+        | KeyFormatType::PKCS8 => Ok(kmip_public_key_to_openssl(&owm.object)?),
+        other => Err(KmsError::NotSupported(format!(
+            "signature verification with public keys of format: {other}"
+        ))),
+    }
+}
+
+async fn get_key(
+    kms: &KMS,
+    request: &SignatureVerify,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ObjectWithMetadata> {
+    // there must be an identifier
+    let uid_or_tags = request
+        .unique_identifier
+        .as_ref()
+        .ok_or(KmsError::UnsupportedPlaceholder)?
+        .as_str()
+        .context("SignatureVerify: unique_identifier must be a string")?;
+    trace!("signature_verify: uid_or_tags: {uid_or_tags}");
+
+    // retrieve from tags or use passed identifier
+    // the public material is enough to verify: the `Get` right is required, not `Sign`
+    let mut owm_s = kms
+        .db
+        .retrieve(uid_or_tags, user, ObjectOperationType::Get, params)
+        .await?
+        .into_values()
+        .filter(|owm| {
+            let object_type = owm.object.object_type();
+            owm.state == StateEnumeration::Active
+                && (object_type == ObjectType::PublicKey || object_type == ObjectType::Certificate)
+        })
+        .collect::<Vec<ObjectWithMetadata>>();
+    trace!("signature_verify: owm_s: {:?}", owm_s);
+
+    // there can only be one key
+    let mut owm = owm_s
+        .pop()
+        .ok_or_else(|| KmsError::KmipError(ErrorReason::Item_Not_Found, uid_or_tags.to_string()))?;
+
+    if !owm_s.is_empty() {
+        return Err(KmsError::InvalidRequest(format!(
+            "signature_verify: too many objects for key {uid_or_tags}",
+        )))
+    }
+
+    // unwrap if wrapped
+    if let Object::PublicKey { .. } = &owm.object {
+        if owm.object.key_wrapping_data().is_some() {
+            let key_block = owm.object.key_block_mut()?;
+            unwrap_key(key_block, kms, &owm.owner, params).await?;
+        }
+    }
+    Ok(owm)
+}
@@ -0,0 +1,324 @@
+use cosmian_kmip::{
+    kmip::{
+        kmip_objects::ObjectType,
+        kmip_operations::{Validate, ValidateResponse},
+        kmip_types::{
+            Attributes, LinkType, RevocationReason, StateEnumeration, UniqueIdentifier,
+            ValidityIndicator,
+        },
+    },
+    openssl::kmip_certificate_to_openssl,
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use openssl::{
+    nid::Nid,
+    x509::{X509NameRef, X509},
+};
+use tracing::trace;
+use x509_parser::{
+    prelude::{FromDer, X509Certificate},
+    time::ASN1Time,
+    x509::X509Version,
+};
+
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    error::KmsError,
+    kms_bail,
+    result::{KResult, KResultHelper},
+};
+
+/// The maximum number of certificates in a chain, root included
+const MAX_CHAIN_LENGTH: usize = 10;
+
+/// A certificate of the chain, as held by the server
+struct ChainCertificate {
+    uid: String,
+    state: StateEnumeration,
+    attributes: Attributes,
+    x509: X509,
+}
+
+/// Validate a certificate chain.
+///
+/// The chain starts with the leaf certificate, the first of the provided unique identifiers.
+/// The issuer of each certificate is looked for among the other provided certificates,
+/// then through its `CertificateLink` and finally among all the certificates
+/// the user has access to, such as the imported CCADB roots.
+/// The chain is trusted when it ends with a self-signed certificate held by the server.
+///
+/// Every certificate of the chain must be valid at the requested date and not revoked;
+/// every issuer must be a CA allowed to sign certificates.
+/// The first failure found, from the leaf to the root, is returned in the response.
+pub async fn validate(
+    kms: &KMS,
+    request: Validate,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ValidateResponse> {
+    trace!("Validate: {:?}", request.unique_identifiers);
+    let unique_identifiers = request.unique_identifiers.unwrap_or_default();
+    if unique_identifiers.is_empty() {
+        kms_bail!(KmsError::InvalidRequest(
+            "Validate: the unique identifier of the leaf certificate must be provided".to_owned()
+        ))
+    }
+    let validity_date = match request.validity_date {
+        Some(millis) => ASN1Time::from_timestamp((millis / 1000) as i64)?,
+        None => ASN1Time::now(),
+    };
+
+    let mut provided = Vec::with_capacity(unique_identifiers.len());
+    for unique_identifier in &unique_identifiers {
+        provided.push(retrieve_certificate(unique_identifier, kms, user, params).await?);
+    }
+    let leaf = provided.remove(0);
+
+    let response = match validate_chain(leaf, provided, validity_date, kms, user, params).await? {
+        None => ValidateResponse {
+            validity_indicator: ValidityIndicator::Valid,
+            result_message: None,
+        },
+        Some(reason) => {
+            trace!("Validate: invalid chain: {reason}");
+            ValidateResponse {
+                validity_indicator: ValidityIndicator::Invalid,
+                result_message: Some(reason),
+            }
+        }
+    };
+    Ok(response)
+}
+
+/// Build the chain from the leaf to a self-signed root, checking the certificates on the way.
+/// Returns the reason of the first failure, if any.
+async fn validate_chain(
+    leaf: ChainCertificate,
+    mut intermediates: Vec<ChainCertificate>,
+    validity_date: ASN1Time,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Option<String>> {
+    let mut current = leaf;
+    let mut chain_length = 1;
+    loop {
+        if let Some(reason) = check_certificate(&current, validity_date)? {
+            return Ok(Some(reason))
+        }
+        if is_self_signed(&current.x509)? {
+            return Ok(None)
+        }
+        if chain_length == MAX_CHAIN_LENGTH {
+            return Ok(Some(format!(
+                "no self-signed root found within {MAX_CHAIN_LENGTH} certificates"
+            )))
+        }
+        let issuer = match find_issuer(&current, &mut intermediates, kms, user, params).await? {
+            Some(issuer) => issuer,
+            None => {
+                return Ok(Some(format!(
+                    "the issuer of the certificate {} ({}) is not held by the server",
+                    current.uid,
+                    name_to_string(current.x509.issuer_name())
+                )))
+            }
+        };
+        if let Some(reason) = check_issuer(&issuer)? {
+            return Ok(Some(reason))
+        }
+        current = issuer;
+        chain_length += 1;
+    }
+}
+
+/// Check the validity period and the revocation status of a certificate
+fn check_certificate(
+    certificate: &ChainCertificate,
+    validity_date: ASN1Time,
+) -> KResult<Option<String>> {
+    let der = certificate.x509.to_der()?;
+    let (_, x509) = X509Certificate::from_der(&der)?;
+    let validity = x509.validity();
+    if !validity.is_valid_at(validity_date) {
+        return Ok(Some(format!(
+            "the certificate {} is not valid on {validity_date}: it is valid from {} to {}",
+            certificate.uid, validity.not_before, validity.not_after
+        )))
+    }
+    if !matches!(
+        certificate.state,
+        StateEnumeration::Active | StateEnumeration::PreActive
+    ) {
+        let reason = match &certificate.attributes.revocation_reason {
+            Some(RevocationReason::Enumeration(reason)) => format!(" ({reason})"),
+            Some(RevocationReason::TextString(reason)) => format!(" ({reason})"),
+            None => String::new(),
+        };
+        return Ok(Some(format!(
+            "the certificate {} is revoked{reason}",
+            certificate.uid
+        )))
+    }
+    Ok(None)
+}
+
+/// Check that an issuer is a CA allowed to sign certificates
+fn check_issuer(issuer: &ChainCertificate) -> KResult<Option<String>> {
+    let der = issuer.x509.to_der()?;
+    let (_, x509) = X509Certificate::from_der(&der)?;
+    let is_ca = match x509.basic_constraints()? {
+        Some(basic_constraints) => basic_constraints.value.ca,
+        // version 1 certificates have no extensions
+        None => x509.version() != X509Version::V3,
+    };
+    if !is_ca {
+        return Ok(Some(format!(
+            "the issuer certificate {} is not a CA certificate",
+            issuer.uid
+        )))
+    }
+    if let Some(key_usage) = x509.key_usage()? {
+        if !key_usage.value.key_cert_sign() {
+            return Ok(Some(format!(
+                "the key usage of the issuer certificate {} does not allow signing certificates",
+                issuer.uid
+            )))
+        }
+    }
+    Ok(None)
+}
+
+/// Find the issuer of a certificate: among the provided certificates first,
+/// then through its `CertificateLink`, and finally among all the certificates
+/// the user has access to
+async fn find_issuer(
+    certificate: &ChainCertificate,
+    intermediates: &mut Vec<ChainCertificate>,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Option<ChainCertificate>> {
+    for (i, candidate) in intermediates.iter().enumerate() {
+        if is_issuer(&candidate.x509, &certificate.x509)? {
+            return Ok(Some(intermediates.remove(i)))
+        }
+    }
+
+    let linked_uid = certificate.attributes.get_link(LinkType::CertificateLink);
+    if let Some(linked_uid) = &linked_uid {
+        // a dangling link is not an error: the issuer may be found otherwise
+        if let Ok(candidate) = retrieve_certificate(
+            &UniqueIdentifier::TextString(linked_uid.clone()),
+            kms,
+            user,
+            params,
+        )
+        .await
+        {
+            if is_issuer(&candidate.x509, &certificate.x509)? {
+                return Ok(Some(candidate))
+            }
+        }
+    }
+
+    // look for the issuer among the stored certificates with the expected common name,
+    // preferring the active ones
+    let issuer_cn = common_name(certificate.x509.issuer_name());
+    let researched_attributes = Attributes {
+        object_type: Some(ObjectType::Certificate),
+        ..Attributes::default()
+    };
+    let mut candidates = kms
+        .db
+        .find(Some(&researched_attributes), None, user, false, params)
+        .await?
+        .into_iter()
+        .filter(|(uid, _, attributes, _)| {
+            uid != &certificate.uid
+                && Some(uid) != linked_uid.as_ref()
+                && attributes
+                    .certificate_attributes
+                    .as_ref()
+                    .map_or(true, |attributes| {
+                        attributes.certificate_subject_cn == issuer_cn
+                    })
+        })
+        .map(|(uid, state, ..)| (uid, state))
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(_, state)| *state != StateEnumeration::Active);
+    for (uid, _) in candidates {
+        let candidate =
+            retrieve_certificate(&UniqueIdentifier::TextString(uid), kms, user, params).await?;
+        if is_issuer(&candidate.x509, &certificate.x509)? {
+            return Ok(Some(candidate))
+        }
+    }
+    Ok(None)
+}
+
+/// Retrieve a certificate, whatever its state
+async fn retrieve_certificate(
+    unique_identifier: &UniqueIdentifier,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<ChainCertificate> {
+    let uid_or_tags = unique_identifier
+        .as_str()
+        .context("Validate: the unique identifiers must be strings")?;
+    let mut owm_s = kms
+        .db
+        .retrieve(uid_or_tags, user, ObjectOperationType::Get, params)
+        .await?
+        .into_values()
+        .filter(|owm| owm.object.object_type() == ObjectType::Certificate)
+        .collect::<Vec<_>>();
+    let owm = owm_s
+        .pop()
+        .ok_or_else(|| KmsError::ItemNotFound(format!("certificate {uid_or_tags}")))?;
+    if !owm_s.is_empty() {
+        kms_bail!(KmsError::InvalidRequest(format!(
+            "Validate: too many certificates for {uid_or_tags}",
+        )))
+    }
+    Ok(ChainCertificate {
+        x509: kmip_certificate_to_openssl(&owm.object)?,
+        uid: owm.id,
+        state: owm.state,
+        attributes: owm.attributes,
+    })
+}
+
+/// Whether the `issuer` certificate signed the `certificate`
+fn is_issuer(issuer: &X509, certificate: &X509) -> KResult<bool> {
+    if issuer.subject_name().to_der()? != certificate.issuer_name().to_der()? {
+        return Ok(false)
+    }
+    Ok(certificate.verify(&issuer.public_key()?).unwrap_or(false))
+}
+
+fn is_self_signed(x509: &X509) -> KResult<bool> {
+    is_issuer(x509, x509)
+}
+
+fn common_name(name: &X509NameRef) -> String {
+    name.entries_by_nid(Nid::COMMONNAME)
+        .next()
+        .and_then(|entry| entry.data().as_utf8().ok())
+        .map(|cn| cn.to_string())
+        .unwrap_or_default()
+}
+
+fn name_to_string(name: &X509NameRef) -> String {
+    name.entries()
+        .filter_map(|entry| {
+            let value = entry.data().as_utf8().ok()?;
+            Some(format!(
+                "{}={value}",
+                entry.object().nid().short_name().ok()?
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
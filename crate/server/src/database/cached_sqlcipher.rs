@@ -22,11 +22,15 @@ use tracing::trace;
 
 use super::{
     cached_sqlite_struct::KMSSqliteCache,
+    job::Job,
     object_with_metadata::ObjectWithMetadata,
     sqlite::{
-        create_, delete_, find_, insert_access_, is_object_owned_by_, list_accesses_,
-        list_user_granted_access_rights_, remove_access_, retrieve_, update_object_, update_state_,
-        upsert_,
+        add_group_member_, archive_, create_, create_job_, delete_, delete_role_, find_,
+        grant_role_, insert_access_, is_object_owned_by_, lease_interrupted_job_, list_accesses_,
+        list_groups_, list_interrupted_jobs_, list_roles_, list_user_granted_access_rights_,
+        list_user_groups_, purge_jobs_, recover_, remove_access_, remove_group_member_,
+        remove_role_, retrieve_, retrieve_archived_owner_, retrieve_job_, set_role_,
+        update_object_, update_pending_job_, update_state_, upsert_,
     },
 };
 use crate::{
@@ -112,6 +116,46 @@ impl CachedSqlCipher {
         .execute(pool)
         .await?;
 
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-archived_objects")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-jobs")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-roles")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-group_members")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-role_access")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(pool)
+        .await?;
+
         Ok(())
     }
 
@@ -172,16 +216,17 @@ impl Database for CachedSqlCipher {
         kms_bail!("Missing group_id/key for opening SQLCipher")
     }
 
-    async fn retrieve(
+    async fn retrieve_for_principals(
         &self,
         uid: &str,
         user: &str,
+        principals: &[String],
         operation_type: ObjectOperationType,
         params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, ObjectWithMetadata>> {
         if let Some(params) = params {
             let pool = self.pre_query(params.group_id, &params.key).await?;
-            let ret = retrieve_(uid, user, operation_type, &*pool).await;
+            let ret = retrieve_(uid, user, principals, operation_type, &*pool).await;
             self.post_query(params.group_id)?;
             return ret
         }
@@ -314,14 +359,157 @@ impl Database for CachedSqlCipher {
         kms_bail!("Missing group_id/key for opening SQLCipher")
     }
 
-    async fn list_user_granted_access_rights(
+    async fn archive(&self, uid: &str, params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let mut tx = pool.begin().await?;
+            match archive_(uid, &mut tx).await {
+                Ok(()) => {
+                    tx.commit().await?;
+                    self.post_query(params.group_id)?;
+                    return Ok(())
+                }
+                Err(e) => {
+                    tx.rollback().await.context("transaction failed")?;
+                    self.post_query(params.group_id)?;
+                    kms_bail!("archive of object {uid} failed: {e}")
+                }
+            }
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn recover(&self, uid: &str, params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let mut tx = pool.begin().await?;
+            match recover_(uid, &mut tx).await {
+                Ok(()) => {
+                    tx.commit().await?;
+                    self.post_query(params.group_id)?;
+                    return Ok(())
+                }
+                Err(e) => {
+                    tx.rollback().await.context("transaction failed")?;
+                    self.post_query(params.group_id)?;
+                    kms_bail!("recovery of object {uid} failed: {e}")
+                }
+            }
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn retrieve_archived_owner(
+        &self,
+        uid: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<String>> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = retrieve_archived_owner_(uid, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn create_job(&self, job: &Job, params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = create_job_(job, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn retrieve_job(
         &self,
+        id: &str,
         owner: &str,
         params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<Job>> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = retrieve_job_(id, owner, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn update_pending_job(
+        &self,
+        job: &Job,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = update_pending_job_(job, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn list_interrupted_jobs(
+        &self,
+        now: i64,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<Job>> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = list_interrupted_jobs_(now, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        // the databases of the groups cannot be opened without the key of their users:
+        // their interrupted jobs are resumed when polled
+        Ok(vec![])
+    }
+
+    async fn lease_interrupted_job(
+        &self,
+        job: &Job,
+        now: i64,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = lease_interrupted_job_(job, now, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn purge_jobs(&self, before: i64, params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = purge_jobs_(before, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn list_principals_granted_access_rights(
+        &self,
+        principals: &[String],
+        params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, (String, StateEnumeration, HashSet<ObjectOperationType>)>> {
         if let Some(params) = params {
             let pool = self.pre_query(params.group_id, &params.key).await?;
-            let ret = list_user_granted_access_rights_(owner, &*pool).await;
+            let ret = list_user_granted_access_rights_(principals, &*pool).await;
             self.post_query(params.group_id)?;
             return ret
         }
@@ -378,6 +566,40 @@ impl Database for CachedSqlCipher {
         kms_bail!("Missing group_id/key for opening SQLCipher")
     }
 
+    async fn grant_role(
+        &self,
+        uid: &str,
+        userid: &str,
+        role: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = grant_role_(uid, userid, role, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn remove_role(
+        &self,
+        uid: &str,
+        userid: &str,
+        role: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = remove_role_(uid, userid, role, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
     async fn is_object_owned_by(
         &self,
         uid: &str,
@@ -394,11 +616,12 @@ impl Database for CachedSqlCipher {
         kms_bail!("Missing group_id/key for opening SQLCipher")
     }
 
-    async fn find(
+    async fn find_for_principals(
         &self,
         researched_attributes: Option<&Attributes>,
         state: Option<StateEnumeration>,
         user: &str,
+        principals: &[String],
         user_must_be_owner: bool,
         params: Option<&ExtraDatabaseParams>,
     ) -> KResult<Vec<(String, StateEnumeration, Attributes, IsWrapped)>> {
@@ -409,6 +632,7 @@ impl Database for CachedSqlCipher {
                 researched_attributes,
                 state,
                 user,
+                principals,
                 user_must_be_owner,
                 &*pool,
             )
@@ -421,10 +645,10 @@ impl Database for CachedSqlCipher {
         kms_bail!("Missing group_id/key for opening SQLCipher")
     }
 
-    async fn list_user_access_rights_on_object(
+    async fn list_principals_access_rights_on_object(
         &self,
         uid: &str,
-        userid: &str,
+        principals: &[String],
         no_inherited_access: bool,
         params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashSet<ObjectOperationType>> {
@@ -433,7 +657,121 @@ impl Database for CachedSqlCipher {
         if let Some(params) = params {
             let pool = self.pre_query(params.group_id, &params.key).await?;
             let ret =
-                list_user_access_rights_on_object_(uid, userid, no_inherited_access, &*pool).await;
+                list_user_access_rights_on_object_(uid, principals, no_inherited_access, &*pool)
+                    .await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn set_role(
+        &self,
+        name: &str,
+        operation_types: &HashSet<ObjectOperationType>,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = set_role_(name, operation_types, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn delete_role(&self, name: &str, params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            // the grants of the role and the role are deleted together
+            let mut tx = pool.begin().await?;
+            return match delete_role_(name, &mut tx).await {
+                Ok(()) => {
+                    tx.commit().await?;
+                    self.post_query(params.group_id)?;
+                    Ok(())
+                }
+                Err(e) => {
+                    tx.rollback().await.context("transaction failed")?;
+                    self.post_query(params.group_id)?;
+                    Err(e)
+                }
+            }
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn list_roles(
+        &self,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = list_roles_(&*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn add_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = add_group_member_(group, user, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn remove_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = remove_group_member_(group, user, &*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn list_groups(
+        &self,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<String>>> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = list_groups_(&*pool).await;
+            self.post_query(params.group_id)?;
+            return ret
+        }
+
+        kms_bail!("Missing group_id/key for opening SQLCipher")
+    }
+
+    async fn list_user_groups(
+        &self,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashSet<String>> {
+        if let Some(params) = params {
+            let pool = self.pre_query(params.group_id, &params.key).await?;
+            let ret = list_user_groups_(user, &*pool).await;
             self.post_query(params.group_id)?;
             return ret
         }
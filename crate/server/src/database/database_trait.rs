@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    iter,
     path::PathBuf,
 };
 
@@ -8,10 +9,12 @@ use cosmian_kmip::kmip::{
     kmip_objects::Object,
     kmip_types::{Attributes, StateEnumeration},
 };
-use cosmian_kms_client::access::{IsWrapped, ObjectOperationType};
+use cosmian_kms_client::access::{IsWrapped, ObjectOperationType, GROUP_PREFIX};
 
-use super::object_with_metadata::ObjectWithMetadata;
-use crate::{core::extra_database_params::ExtraDatabaseParams, result::KResult};
+use super::{job::Job, object_with_metadata::ObjectWithMetadata};
+use crate::{
+    core::extra_database_params::ExtraDatabaseParams, middlewares::ClaimedGroups, result::KResult,
+};
 
 #[async_trait(?Send)]
 pub trait Database {
@@ -40,12 +43,30 @@ pub trait Database {
     ///
     /// The `query_access_grant` allows additional filtering in the `access` table to see
     /// if a `user`, that is not a owner, has the corresponding access granted
+    /// either directly, to one of the groups the user belongs to or through a role
     async fn retrieve(
         &self,
         uid_or_tags: &str,
         user: &str,
         query_access_grant: ObjectOperationType,
         params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, ObjectWithMetadata>> {
+        let principals = self.user_principals(user, params).await?;
+        self.retrieve_for_principals(uid_or_tags, user, &principals, query_access_grant, params)
+            .await
+    }
+
+    /// Retrieve objects from the database for `user`, in a single query
+    /// for all its `principals` and the wildcard user.
+    ///
+    /// See [`Database::retrieve`] and [`Database::user_principals`]
+    async fn retrieve_for_principals(
+        &self,
+        uid_or_tags: &str,
+        user: &str,
+        principals: &[String],
+        query_access_grant: ObjectOperationType,
+        params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, ObjectWithMetadata>>;
 
     /// Retrieve the tags of the object with the given `uid`
@@ -98,8 +119,69 @@ pub trait Database {
         params: Option<&ExtraDatabaseParams>,
     ) -> KResult<()>;
 
-    /// List all the access rights granted to the `user`
-    /// on all the objects in the database
+    /// Move the object with the given `uid` to the archive (cold storage).
+    ///
+    /// Archived objects are neither retrieved nor found until they are recovered;
+    /// their tags and access rights are preserved.
+    async fn archive(&self, uid: &str, params: Option<&ExtraDatabaseParams>) -> KResult<()>;
+
+    /// Move the archived object with the given `uid` back from the archive
+    async fn recover(&self, uid: &str, params: Option<&ExtraDatabaseParams>) -> KResult<()>;
+
+    /// Return the owner of the archived object with the given `uid`
+    /// or `None` if there is no such archived object
+    async fn retrieve_archived_owner(
+        &self,
+        uid: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<String>>;
+
+    /// Insert a new asynchronous job
+    async fn create_job(&self, job: &Job, params: Option<&ExtraDatabaseParams>) -> KResult<()>;
+
+    /// Retrieve the asynchronous job with the given `id` requested by `owner`
+    async fn retrieve_job(
+        &self,
+        id: &str,
+        owner: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<Job>>;
+
+    /// Replace a pending asynchronous job with its new version.
+    ///
+    /// Return `false`, leaving the job untouched, if it is no longer pending
+    /// (typically because it was canceled)
+    async fn update_pending_job(
+        &self,
+        job: &Job,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool>;
+
+    /// List the pending asynchronous jobs whose lease expired before the `now` UNIX timestamp
+    async fn list_interrupted_jobs(
+        &self,
+        now: i64,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<Job>>;
+
+    /// Replace a pending asynchronous job whose lease expired before the `now` UNIX timestamp
+    /// with its new version, leased by the server which resumes it.
+    ///
+    /// Return `false`, leaving the job untouched, if it is no longer pending
+    /// or was leased again in the meantime
+    async fn lease_interrupted_job(
+        &self,
+        job: &Job,
+        now: i64,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool>;
+
+    /// Delete the jobs last updated before the `before` UNIX timestamp
+    /// which are no longer pending or whose lease expired by then
+    async fn purge_jobs(&self, before: i64, params: Option<&ExtraDatabaseParams>) -> KResult<()>;
+
+    /// List all the access rights granted to the `user`, directly, through its groups
+    /// or through a role, on all the objects in the database
     /// (i.e. the objects for which `user` is not the owner)
     /// The result is a list of tuples (uid, owner, state, operations, is_wrapped)
     /// where `operations` is a list of operations that `user` can perform on the object
@@ -107,9 +189,23 @@ pub trait Database {
         &self,
         user: &str,
         params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, (String, StateEnumeration, HashSet<ObjectOperationType>)>> {
+        let principals = self.user_principals(user, params).await?;
+        self.list_principals_granted_access_rights(&principals, params)
+            .await
+    }
+
+    /// List all the access rights granted to any of the `principals`
+    /// in a single query.
+    ///
+    /// See [`Database::list_user_granted_access_rights`]
+    async fn list_principals_granted_access_rights(
+        &self,
+        principals: &[String],
+        params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, (String, StateEnumeration, HashSet<ObjectOperationType>)>>;
 
-    /// List all the accessed granted per `user`
+    /// List all the accessed granted per `user`, including those of the roles granted to it
     /// This is called by the owner only
     async fn list_object_accesses_granted(
         &self,
@@ -137,6 +233,27 @@ pub trait Database {
         params: Option<&ExtraDatabaseParams>,
     ) -> KResult<()>;
 
+    /// Grant the `role` to `user` on the object identified by its `uid`
+    ///
+    /// The role is kept by reference: its operation types are resolved
+    /// when the access rights are checked, so that its later changes apply
+    async fn grant_role(
+        &self,
+        uid: &str,
+        user: &str,
+        role: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()>;
+
+    /// Revoke the `role` granted to `user` on the object identified by its `uid`
+    async fn remove_role(
+        &self,
+        uid: &str,
+        user: &str,
+        role: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()>;
+
     /// Test if an object identified by its `uid` is currently owned by `owner`
     async fn is_object_owned_by(
         &self,
@@ -147,6 +264,9 @@ pub trait Database {
 
     /// Return uid, state and attributes of the object identified by its owner,
     /// and possibly by its attributes and/or its `state`
+    ///
+    /// Unless `user_must_be_owner` is set, the objects shared with
+    /// the `user` or its groups are also returned
     async fn find(
         &self,
         researched_attributes: Option<&Attributes>,
@@ -154,20 +274,129 @@ pub trait Database {
         user: &str,
         user_must_be_owner: bool,
         params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<(String, StateEnumeration, Attributes, IsWrapped)>> {
+        let principals = if user_must_be_owner {
+            vec![]
+        } else {
+            self.user_principals(user, params).await?
+        };
+        self.find_for_principals(
+            researched_attributes,
+            state,
+            user,
+            &principals,
+            user_must_be_owner,
+            params,
+        )
+        .await
+    }
+
+    /// Same as [`Database::find`] in a single query
+    /// for the objects shared with any of the `principals`
+    async fn find_for_principals(
+        &self,
+        researched_attributes: Option<&Attributes>,
+        state: Option<StateEnumeration>,
+        user: &str,
+        principals: &[String],
+        user_must_be_owner: bool,
+        params: Option<&ExtraDatabaseParams>,
     ) -> KResult<Vec<(String, StateEnumeration, Attributes, IsWrapped)>>;
 
     /// List all the access rights that have been granted to a user on an object
     ///
-    /// These access rights may have been directly granted or via the wildcard user
-    /// unless `no_inherited_access` is set to `true`
+    /// These access rights may have been directly granted, through a role or via the
+    /// wildcard user or the groups of the user, unless `no_inherited_access` is set to `true`
     async fn list_user_access_rights_on_object(
         &self,
         uid: &str,
         user: &str,
         no_inherited_access: bool,
         params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashSet<ObjectOperationType>> {
+        let principals = if no_inherited_access {
+            vec![user.to_owned()]
+        } else {
+            self.user_principals(user, params).await?
+        };
+        self.list_principals_access_rights_on_object(uid, &principals, no_inherited_access, params)
+            .await
+    }
+
+    /// Same as [`Database::list_user_access_rights_on_object`]
+    /// in a single query for all the `principals`
+    async fn list_principals_access_rights_on_object(
+        &self,
+        uid: &str,
+        principals: &[String],
+        no_inherited_access: bool,
+        params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashSet<ObjectOperationType>>;
 
+    /// Create or replace the role `name`: a named bundle of operation types
+    async fn set_role(
+        &self,
+        name: &str,
+        operation_types: &HashSet<ObjectOperationType>,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()>;
+
+    /// Delete the role `name` and the grants of this role
+    async fn delete_role(&self, name: &str, params: Option<&ExtraDatabaseParams>) -> KResult<()>;
+
+    /// List the roles and their operation types
+    async fn list_roles(
+        &self,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>>;
+
+    /// Add `user` to the local `group`
+    async fn add_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()>;
+
+    /// Remove `user` from the local `group`
+    async fn remove_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()>;
+
+    /// List the local groups and their members
+    async fn list_groups(
+        &self,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<String>>>;
+
+    /// List the local groups `user` belongs to
+    async fn list_user_groups(
+        &self,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashSet<String>>;
+
+    /// List the principals of `user`: the user itself and the groups (`group:<name>`)
+    /// it belongs to, either locally or as claimed in the JWT of the current request
+    async fn user_principals(
+        &self,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<String>> {
+        let mut groups = self.list_user_groups(user, params).await?;
+        groups.extend(ClaimedGroups::of(user));
+        Ok(iter::once(user.to_owned())
+            .chain(
+                groups
+                    .into_iter()
+                    .map(|group| format!("{GROUP_PREFIX}{group}")),
+            )
+            .collect())
+    }
+
     /// Perform an atomic set of operation on the database
     /// (typically in a transaction)
     async fn atomic(
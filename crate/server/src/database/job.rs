@@ -0,0 +1,54 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use cosmian_kmip::kmip::{kmip_types::OperationEnumeration, ttlv::TTLV};
+use serde::{Deserialize, Serialize};
+
+/// How long the completed, failed and canceled jobs are kept in the database
+pub(crate) const JOB_RETENTION: Duration = Duration::from_secs(24 * 3600);
+
+/// The state of an operation processed asynchronously
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum JobState {
+    Pending,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+impl Display for JobState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            Self::Pending => "Pending",
+            Self::Completed => "Completed",
+            Self::Failed => "Failed",
+            Self::Canceled => "Canceled",
+        };
+        write!(f, "{state}")
+    }
+}
+
+/// An operation processed asynchronously
+///
+/// The job is identified by the asynchronous correlation value
+/// returned to the client, which polls or cancels it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Job {
+    /// The identifier of the job, whose bytes are the asynchronous correlation value
+    pub(crate) id: String,
+    /// The user who requested the operation
+    pub(crate) owner: String,
+    pub(crate) operation: OperationEnumeration,
+    pub(crate) state: JobState,
+    /// The request payload of the operation
+    pub(crate) request: TTLV,
+    /// The response batch item of the operation, once completed or failed
+    pub(crate) response: Option<TTLV>,
+    /// The end, as a UNIX timestamp, of the lease of the server processing the pending job:
+    /// the job was interrupted if the lease is not renewed by then
+    pub(crate) lease_expiry: i64,
+    /// The UNIX timestamp of the last update of the job
+    pub(crate) updated_at: i64,
+}
@@ -42,15 +42,22 @@ pub trait PlaceholderTrait {
     /// Build the query part that evaluates link, depending on the SQL engine.
     /// Searching and evaluating nodes can't be unified between MySQL/MariaDB and others
     #[must_use]
-    fn link_evaluation(node_name: &str, node_value: &str) -> String {
+    fn link_evaluation(node_name: &str, placeholder: &str) -> String {
         format!(
-            "{}(value, {}) = '{}'",
+            "{}(value, {}) = {}",
             Self::JSON_FN_EXTRACT_TEXT,
-            node_name,  // `P::JSON_TEXT_LINK_TYPE` or `P::JSON_TEXT_LINK_OBJ_ID`
-            node_value  // `link.link_type` or `uid`
+            node_name,   // `P::JSON_TEXT_LINK_TYPE` or `P::JSON_TEXT_LINK_OBJ_ID`
+            placeholder  // bound to `link.link_type` or `uid`
         )
     }
 
+    /// The value bound to a placeholder searched with `json_search`
+    /// (ie: a name or a link): only `MySQL` needs escaping its wildcards
+    #[must_use]
+    fn json_search_value(value: &str) -> String {
+        value.to_owned()
+    }
+
     /// Get node specifier depending on `key_name` (ie: `CryptographicAlgorithm`)
     #[must_use]
     fn extract_text_from_key_block_path(key_name: &str) -> String {
@@ -62,6 +69,40 @@ pub trait PlaceholderTrait {
     fn extract_text_from_object_type_path() -> String {
         "object ->> 'object_type'".to_string()
     }
+
+    /// Build the query part that evaluates a text attribute (ie: `ObjectGroup`)
+    /// of the `attributes` column against the value bound to the placeholder
+    #[must_use]
+    fn text_attribute_evaluation(attribute_name: &str, placeholder: &str) -> String {
+        format!("objects.attributes ->> '{attribute_name}' = {placeholder}")
+    }
+
+    /// Build the query part that evaluates a text attribute of the certificate
+    /// (ie: `CertificateSubjectSerialNumber`) of the `attributes` column
+    /// against the value bound to the placeholder
+    #[must_use]
+    fn certificate_attribute_evaluation(attribute_name: &str, placeholder: &str) -> String {
+        format!(
+            "objects.attributes -> 'CertificateAttributes' ->> '{attribute_name}' = {placeholder}"
+        )
+    }
+
+    /// Build the query part that evaluates a boolean attribute (ie: `Sensitive`)
+    /// of the `attributes` column
+    #[must_use]
+    fn boolean_attribute_evaluation(attribute_name: &str, value: bool) -> String {
+        format!("json_type(objects.attributes, '$.{attribute_name}') = '{value}'")
+    }
+
+    /// Build the query part that evaluates the value of one of the names
+    /// of the `attributes` column against the value bound to the placeholder
+    #[must_use]
+    fn name_evaluation(placeholder: &str) -> String {
+        format!(
+            "EXISTS (SELECT 1 FROM json_each(objects.attributes, '$.Name') AS name WHERE \
+             name.value ->> 'NameValue' = {placeholder})"
+        )
+    }
 }
 
 pub enum MySqlPlaceholder {}
@@ -80,25 +121,33 @@ impl PlaceholderTrait for MySqlPlaceholder {
         None
     }
 
-    fn link_evaluation(node_name: &str, node_value: &str) -> String {
+    fn link_evaluation(node_name: &str, placeholder: &str) -> String {
         // built evaluation is going to be like:
         // json_search(
         //      json_extract(objects.attributes, '$.Link'),
         //      'one',          -> need at most 1 match
-        //      'ParentLink',   -> `node_value` (from either `link.link_type` or `uid`)
+        //      ?,              -> `placeholder` (bound to either `link.link_type` or `uid`)
         //      NULL,
         //      '$[*].LinkType' -> `node_name` (from either `P::JSON_TEXT_LINK_TYPE` or `P::JSON_TEXT_LINK_OBJ_ID`)
         // )
         format!(
-            "{}({}(objects.attributes, {}), 'one', '{}', NULL, {}) IS NOT NULL",
+            "{}({}(objects.attributes, {}), 'one', {}, NULL, {}) IS NOT NULL",
             Self::JSON_FN_EACH_ELEMENT,
             Self::JSON_FN_EXTRACT_PATH,
             Self::JSON_NODE_LINK,
-            node_value,
+            placeholder,
             node_name,
         )
     }
 
+    fn json_search_value(value: &str) -> String {
+        // `json_search` matches `%` and `_` as wildcards, escaped with `\`
+        value
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    }
+
     fn extract_text_from_key_block_path(key_name: &str) -> String {
         format!(
             "{}(object, '$.object.KeyBlock.{key_name}')",
@@ -109,6 +158,32 @@ impl PlaceholderTrait for MySqlPlaceholder {
     fn extract_text_from_object_type_path() -> String {
         format!("{}(object, '$.object_type')", Self::JSON_FN_EXTRACT_TEXT)
     }
+
+    fn text_attribute_evaluation(attribute_name: &str, placeholder: &str) -> String {
+        format!(
+            "json_unquote(json_extract(objects.attributes, '$.{attribute_name}')) = {placeholder}"
+        )
+    }
+
+    fn certificate_attribute_evaluation(attribute_name: &str, placeholder: &str) -> String {
+        format!(
+            "json_unquote(json_extract(objects.attributes, \
+             '$.CertificateAttributes.{attribute_name}')) = {placeholder}"
+        )
+    }
+
+    fn boolean_attribute_evaluation(attribute_name: &str, value: bool) -> String {
+        format!("json_unquote(json_extract(objects.attributes, '$.{attribute_name}')) = '{value}'")
+    }
+
+    fn name_evaluation(placeholder: &str) -> String {
+        format!(
+            "{}({}(objects.attributes, '$.Name'), 'one', {placeholder}, NULL, '$[*].NameValue') \
+             IS NOT NULL",
+            Self::JSON_FN_EACH_ELEMENT,
+            Self::JSON_FN_EXTRACT_PATH,
+        )
+    }
 }
 pub enum PgSqlPlaceholder {}
 impl PlaceholderTrait for PgSqlPlaceholder {
@@ -120,22 +195,61 @@ impl PlaceholderTrait for PgSqlPlaceholder {
     const JSON_NODE_WRAPPING: &'static str = "'object', 'KeyBlock', 'KeyWrappingData'";
     const JSON_TEXT_LINK_OBJ_ID: &'static str = "'LinkedObjectIdentifier'";
     const JSON_TEXT_LINK_TYPE: &'static str = "'LinkType'";
+
+    fn boolean_attribute_evaluation(attribute_name: &str, value: bool) -> String {
+        format!("objects.attributes ->> '{attribute_name}' = '{value}'")
+    }
+
+    fn name_evaluation(placeholder: &str) -> String {
+        format!(
+            "EXISTS (SELECT 1 FROM {}(objects.attributes -> 'Name') AS name WHERE name ->> \
+             'NameValue' = {placeholder})",
+            Self::JSON_FN_EACH_ELEMENT,
+        )
+    }
 }
 pub enum SqlitePlaceholder {}
 impl PlaceholderTrait for SqlitePlaceholder {}
 
+/// Add a value to bind with the query and return its placeholder
+fn bind<P: PlaceholderTrait>(values: &mut Vec<String>, value: String) -> String {
+    values.push(value);
+    P::binder(values.len())
+}
+
+/// The comma-separated placeholders of `count` values bound from position `first`,
+/// e.g. the principals of a user looked up with `IN (...)`
+pub fn placeholders<P: PlaceholderTrait>(first: usize, count: usize) -> String {
+    (first..first + count)
+        .map(P::binder)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Add the values to bind with the query and return their comma-separated placeholders
+fn bind_all<P: PlaceholderTrait>(values: &mut Vec<String>, all: &[String]) -> String {
+    all.iter()
+        .map(|value| bind::<P>(values, value.clone()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Builds a SQL query depending on `attributes` and `state` constraints,
 /// to search for items in database.
 /// Returns a tuple containing the stringified query and the values to bind with.
 /// The different placeholder for variable binding is handled by trait specification.
-// TODO  although this is a select query, it is complex and the occurrence is unlikely,
-// TODO  protection against SQL Injection is not covered here
+/// The values supplied by the client are always bound, never formatted in the query.
+///
+/// Unless `user_must_be_owner` is set, the objects shared with any of the `principals`
+/// of the `user`, directly or through a role, are also selected.
 pub fn query_from_attributes<P: PlaceholderTrait>(
     attributes: Option<&Attributes>,
     state: Option<StateEnumeration>,
     user: &str,
+    principals: &[String],
     user_must_be_owner: bool,
-) -> KResult<String> {
+) -> KResult<(String, Vec<String>)> {
+    let mut values = Vec::new();
     let mut query = format!(
         "SELECT objects.id as id, objects.state as state, objects.attributes as attrs, \
          {}(objects.object, {}) IS NOT NULL AS is_wrapped FROM objects",
@@ -159,7 +273,7 @@ pub fn query_from_attributes<P: PlaceholderTrait>(
         if tags_len > 0 {
             let tags_string = tags
                 .iter()
-                .map(|t| format!("'{t}'"))
+                .map(|t| bind::<P>(&mut values, t.clone()))
                 .collect::<Vec<String>>()
                 .join(", ");
             query = format!(
@@ -177,15 +291,18 @@ ON objects.id = matched_tags.id"
 
     if user_must_be_owner {
         // only select objects for which the user is the owner
-        query = format!("{query} WHERE objects.owner = '{user}'",);
+        let user = bind::<P>(&mut values, user.to_owned());
+        query = format!("{query} WHERE objects.owner = {user}",);
     } else {
         // select objects for which the user is the owner or has been granted an access right
+        let owner = bind::<P>(&mut values, user.to_owned());
+        let read_access_principals = bind_all::<P>(&mut values, principals);
+        let role_access_principals = bind_all::<P>(&mut values, principals);
         query = format!(
-            "{query}\n LEFT JOIN read_access ON objects.id = read_access.id AND \
-             read_access.userid = '{user}'"
+            "{query} WHERE (objects.owner = {owner} OR objects.id IN (SELECT id FROM read_access \
+             WHERE userid IN ({read_access_principals}) UNION SELECT id FROM role_access WHERE \
+             userid IN ({role_access_principals})))"
         );
-        query =
-            format!("{query} WHERE (objects.owner = '{user}' OR read_access.userid = '{user}')");
     }
 
     if let Some(state) = state {
@@ -226,24 +343,93 @@ ON objects.id = matched_tags.id"
             );
         };
 
+        // State
+        if let Some(state) = attributes.state {
+            query = format!("{query} AND state = '{state}'");
+        }
+
+        // Name
+        for name in attributes.name.iter().flatten() {
+            let placeholder = bind::<P>(&mut values, P::json_search_value(&name.name_value));
+            query = format!("{query} AND {}", P::name_evaluation(&placeholder));
+        }
+
+        // descriptive attributes
+        for (attribute_name, value) in [
+            ("ObjectGroup", &attributes.object_group),
+            ("Description", &attributes.description),
+            ("Comment", &attributes.comment),
+            ("ContactInformation", &attributes.contact_information),
+        ] {
+            if let Some(value) = value {
+                let placeholder = bind::<P>(&mut values, value.clone());
+                query = format!(
+                    "{query} AND {}",
+                    P::text_attribute_evaluation(attribute_name, &placeholder)
+                );
+            }
+        }
+
+        // the serial number of a certificate
+        if let Some(certificate_attributes) = &attributes.certificate_attributes {
+            if !certificate_attributes
+                .certificate_subject_serial_number
+                .is_empty()
+            {
+                let placeholder = bind::<P>(
+                    &mut values,
+                    certificate_attributes
+                        .certificate_subject_serial_number
+                        .clone(),
+                );
+                query = format!(
+                    "{query} AND {}",
+                    P::certificate_attribute_evaluation(
+                        "CertificateSubjectSerialNumber",
+                        &placeholder
+                    )
+                );
+            }
+        }
+
+        // extractability attributes
+        for (attribute_name, value) in [
+            ("Sensitive", attributes.sensitive),
+            ("Extractable", attributes.extractable),
+            ("NeverExtractable", attributes.never_extractable),
+            ("Fresh", attributes.fresh),
+        ] {
+            if let Some(value) = value {
+                query = format!(
+                    "{query} AND {}",
+                    P::boolean_attribute_evaluation(attribute_name, value)
+                );
+            }
+        }
+
         // Link
         if let Some(links) = &attributes.link {
             for link in links {
                 // LinkType
+                let placeholder = bind::<P>(
+                    &mut values,
+                    P::json_search_value(&link.link_type.to_string()),
+                );
                 query = format!(
                     "{query} AND {}",
-                    P::link_evaluation(P::JSON_TEXT_LINK_TYPE, &link.link_type.to_string())
+                    P::link_evaluation(P::JSON_TEXT_LINK_TYPE, &placeholder)
                 );
 
                 // LinkedObjectIdentifier
                 if let TextString(uid) = &link.linked_object_identifier {
+                    let placeholder = bind::<P>(&mut values, P::json_search_value(uid));
                     query = format!(
                         "{query} AND {}",
-                        P::link_evaluation(P::JSON_TEXT_LINK_OBJ_ID, uid)
+                        P::link_evaluation(P::JSON_TEXT_LINK_OBJ_ID, &placeholder)
                     );
                 }
             }
         }
     }
-    Ok(query)
+    Ok((query, values))
 }
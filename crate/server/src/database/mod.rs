@@ -1,6 +1,6 @@
 use cosmian_kmip::kmip::{
     kmip_objects::{Object, ObjectType},
-    kmip_types::StateEnumeration,
+    kmip_types::{Attributes, StateEnumeration},
 };
 use lazy_static::lazy_static;
 use rawsql::Loader;
@@ -13,6 +13,7 @@ pub type KMSServer = crate::core::KMS;
 pub(crate) mod cached_sqlcipher;
 pub(crate) mod cached_sqlite_struct;
 mod database_trait;
+pub(crate) mod job;
 pub(crate) mod mysql;
 pub(crate) mod object_with_metadata;
 pub(crate) mod pgsql;
@@ -22,8 +23,9 @@ pub(crate) use database_trait::{AtomicOperation, Database};
 mod locate_query;
 mod retrieve_object_utils;
 pub(crate) use locate_query::{
-    query_from_attributes, MySqlPlaceholder, PgSqlPlaceholder, SqlitePlaceholder,
+    placeholders, query_from_attributes, MySqlPlaceholder, PgSqlPlaceholder, SqlitePlaceholder,
 };
+pub(crate) use retrieve_object_utils::is_object_archived;
 pub use retrieve_object_utils::retrieve_object_for_operation; //, retrieve_object_with_metadata};
 
 #[cfg(test)]
@@ -66,3 +68,14 @@ pub fn state_from_string(s: &str) -> KResult<StateEnumeration> {
         x => kms_bail!("invalid state in db: {}", x),
     }
 }
+
+/// The state of a newly created object:
+/// `PreActive` if its activation date is in the future, `Active` otherwise
+pub(crate) fn initial_state(attributes: &Attributes) -> StateEnumeration {
+    match attributes.activation_date {
+        Some(activation_date) if activation_date > chrono::Utc::now().timestamp_millis() as u64 => {
+            StateEnumeration::PreActive
+        }
+        _ => StateEnumeration::Active,
+    }
+}
@@ -21,6 +21,25 @@ pub struct ObjectWithMetadata {
     pub(crate) attributes: Attributes,
 }
 
+impl ObjectWithMetadata {
+    /// Whether the object is `PreActive` and its activation date has been reached,
+    /// in which case it must transition to the `Active` state
+    pub(crate) fn is_activation_due(&self) -> bool {
+        if self.state != StateEnumeration::PreActive {
+            return false
+        }
+        let activation_date = self
+            .object
+            .attributes()
+            .map_or(self.attributes.activation_date, |attributes| {
+                attributes.activation_date
+            });
+        activation_date.is_some_and(|activation_date| {
+            activation_date <= chrono::Utc::now().timestamp_millis() as u64
+        })
+    }
+}
+
 impl TryFrom<&PgRow> for ObjectWithMetadata {
     type Error = KmsError;
 
@@ -22,23 +22,87 @@ CREATE TABLE IF NOT EXISTS tags (
         UNIQUE (id, tag)
 );
 
+-- name: create-table-archived_objects
+CREATE TABLE IF NOT EXISTS archived_objects (
+        id VARCHAR(40) PRIMARY KEY,
+        object json NOT NULL,
+        attributes json NOT NULL,
+        state VARCHAR(32),
+        owner VARCHAR(255)
+);
+
+-- name: create-table-jobs
+CREATE TABLE IF NOT EXISTS jobs (
+        id VARCHAR(40) PRIMARY KEY,
+        owner VARCHAR(255),
+        state VARCHAR(32),
+        lease_expiry BIGINT,
+        updated_at BIGINT,
+        job json NOT NULL
+);
+
+-- name: create-table-roles
+CREATE TABLE IF NOT EXISTS roles (
+        name VARCHAR(255) PRIMARY KEY,
+        permissions json NOT NULL
+);
+
+-- name: create-table-group_members
+CREATE TABLE IF NOT EXISTS group_members (
+        groupname VARCHAR(255),
+        userid VARCHAR(255),
+        UNIQUE (groupname, userid)
+);
+
+-- name: create-table-role_access
+CREATE TABLE IF NOT EXISTS role_access (
+        id VARCHAR(40),
+        userid VARCHAR(255),
+        rolename VARCHAR(255),
+        UNIQUE (id, userid, rolename)
+);
+
 -- name: clean-table-objects
 DELETE FROM objects;
 
 -- name: clean-table-read_access
 DELETE FROM read_access;
 
+-- name: clean-table-role_access
+DELETE FROM role_access;
+
 -- name: clean-table-tags
 DELETE FROM tags;
 
+-- name: clean-table-archived_objects
+DELETE FROM archived_objects;
+
+-- name: clean-table-jobs
+DELETE FROM jobs;
+
+-- name: clean-table-roles
+DELETE FROM roles;
+
+-- name: clean-table-group_members
+DELETE FROM group_members;
+
 -- name: insert-objects
 INSERT INTO objects (id, object, attributes, state, owner) VALUES ($1, $2, $3, $4, $5);
 
 -- name: select-object
-SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, read_access.permissions
+SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, granted.permissions
         FROM objects
-        LEFT JOIN read_access
-        ON objects.id = read_access.id AND ( read_access.userid=$2 OR read_access.userid='*' )
+        LEFT JOIN (
+                SELECT id, permissions
+                        FROM read_access
+                        WHERE id=$1 AND userid IN (@PRINCIPALS)
+                UNION ALL
+                SELECT role_access.id, roles.permissions
+                        FROM role_access
+                        INNER JOIN roles ON role_access.rolename = roles.name
+                        WHERE role_access.id=$1 AND role_access.userid IN (@PRINCIPALS)
+        ) AS granted
+        ON objects.id = granted.id
         WHERE objects.id=$1;
 
 -- name: update-object-with-object
@@ -53,10 +117,20 @@ DELETE FROM objects WHERE id=$1 AND owner=$2;
 -- name: upsert-object
 INSERT INTO objects (id, object, attributes, state, owner) VALUES ($1, $2, $3, $4, $5)
         ON CONFLICT(id)
-        DO UPDATE SET object=$2, state=$4
+        DO UPDATE SET object=$2, attributes=$3, state=$4
         WHERE objects.owner=$5;
 
 -- name: select-user-accesses-for-object
+SELECT permissions
+        FROM read_access
+        WHERE id=$1 AND userid IN (@PRINCIPALS)
+UNION ALL
+SELECT roles.permissions
+        FROM role_access
+        INNER JOIN roles ON role_access.rolename = roles.name
+        WHERE role_access.id=$1 AND role_access.userid IN (@PRINCIPALS);
+
+-- name: select-row-read_access
 SELECT permissions
         FROM read_access
         WHERE id=$1 AND userid=$2;
@@ -80,14 +154,38 @@ UPDATE read_access SET permissions=$3
 -- name: select-rows-read_access-with-object-id
 SELECT userid, permissions
         FROM read_access
-        WHERE id=$1;
+        WHERE id=$1
+UNION ALL
+SELECT role_access.userid, roles.permissions
+        FROM role_access
+        INNER JOIN roles ON role_access.rolename = roles.name
+        WHERE role_access.id=$1;
 
 -- name: select-objects-access-obtained
-SELECT objects.id, objects.owner, objects.state, read_access.permissions
+SELECT objects.id, objects.owner, objects.state, granted.permissions
         FROM objects
-        INNER JOIN read_access
-        ON objects.id = read_access.id
-        WHERE read_access.userid=$1;
+        INNER JOIN (
+                SELECT id, permissions
+                        FROM read_access
+                        WHERE userid IN (@PRINCIPALS)
+                UNION ALL
+                SELECT role_access.id, roles.permissions
+                        FROM role_access
+                        INNER JOIN roles ON role_access.rolename = roles.name
+                        WHERE role_access.userid IN (@PRINCIPALS)
+        ) AS granted
+        ON objects.id = granted.id;
+
+-- name: insert-role_access
+INSERT INTO role_access (id, userid, rolename) VALUES ($1, $2, $3)
+        ON CONFLICT(id, userid, rolename)
+        DO NOTHING;
+
+-- name: delete-role_access
+DELETE FROM role_access WHERE id=$1 AND userid=$2 AND rolename=$3;
+
+-- name: delete-role_access-of-role
+DELETE FROM role_access WHERE rolename=$1;
 
 -- name: insert-tags
 INSERT INTO tags (id, tag) VALUES ($1, $2);
@@ -100,7 +198,7 @@ DELETE FROM tags WHERE id=$1;
 
 
 -- name: select-from-tags
-SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, read_access.permissions
+SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, granted.permissions
 FROM objects
 INNER JOIN (
     SELECT id
@@ -110,5 +208,74 @@ INNER JOIN (
     HAVING COUNT(DISTINCT tag) = @LEN
 ) AS matched_tags
 ON objects.id = matched_tags.id
-LEFT JOIN read_access
-ON objects.id = read_access.id AND (read_access.userid=@USER OR read_access.userid='*' );
+LEFT JOIN (
+        SELECT id, permissions
+                FROM read_access
+                WHERE userid IN (@PRINCIPALS)
+        UNION ALL
+        SELECT role_access.id, roles.permissions
+                FROM role_access
+                INNER JOIN roles ON role_access.rolename = roles.name
+                WHERE role_access.userid IN (@PRINCIPALS)
+) AS granted
+ON objects.id = granted.id;
+
+-- name: archive-object
+INSERT INTO archived_objects (id, object, attributes, state, owner)
+        SELECT id, object, attributes, state, owner FROM objects WHERE id=$1;
+
+-- name: delete-archived-object-from-objects
+DELETE FROM objects WHERE id=$1;
+
+-- name: recover-object
+INSERT INTO objects (id, object, attributes, state, owner)
+        SELECT id, object, attributes, state, owner FROM archived_objects WHERE id=$1;
+
+-- name: delete-recovered-object-from-archive
+DELETE FROM archived_objects WHERE id=$1;
+
+-- name: select-archived-object-owner
+SELECT owner FROM archived_objects WHERE id=$1;
+
+-- name: insert-job
+INSERT INTO jobs (id, owner, state, lease_expiry, updated_at, job) VALUES ($1, $2, $3, $4, $5, $6);
+
+-- name: select-job
+SELECT job FROM jobs WHERE id=$1 AND owner=$2;
+
+-- name: update-pending-job
+UPDATE jobs SET state=$1, lease_expiry=$2, updated_at=$3, job=$4 WHERE id=$5 AND state='Pending';
+
+-- name: select-interrupted-jobs
+SELECT job FROM jobs WHERE state='Pending' AND lease_expiry<$1;
+
+-- name: lease-interrupted-job
+UPDATE jobs SET lease_expiry=$1, updated_at=$2, job=$3 WHERE id=$4 AND state='Pending' AND lease_expiry<$5;
+
+-- name: delete-expired-jobs
+DELETE FROM jobs WHERE updated_at<$1 AND (state<>'Pending' OR lease_expiry<$1);
+
+-- name: upsert-role
+INSERT INTO roles (name, permissions) VALUES ($1, $2)
+        ON CONFLICT(name)
+        DO UPDATE SET permissions=$2;
+
+-- name: delete-role
+DELETE FROM roles WHERE name=$1;
+
+-- name: select-roles
+SELECT name, permissions FROM roles;
+
+-- name: insert-group_member
+INSERT INTO group_members (groupname, userid) VALUES ($1, $2)
+        ON CONFLICT(groupname, userid)
+        DO NOTHING;
+
+-- name: delete-group_member
+DELETE FROM group_members WHERE groupname=$1 AND userid=$2;
+
+-- name: select-group_members
+SELECT groupname, userid FROM group_members;
+
+-- name: select-user-groups
+SELECT groupname FROM group_members WHERE userid=$1;
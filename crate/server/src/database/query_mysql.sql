@@ -22,23 +22,87 @@ CREATE TABLE IF NOT EXISTS tags (
         UNIQUE (id, tag)
 );
 
+-- name: create-table-archived_objects
+CREATE TABLE IF NOT EXISTS archived_objects (
+        id VARCHAR(40) PRIMARY KEY,
+        object json NOT NULL,
+        attributes json NOT NULL,
+        state VARCHAR(32),
+        owner VARCHAR(255)
+);
+
+-- name: create-table-jobs
+CREATE TABLE IF NOT EXISTS jobs (
+        id VARCHAR(40) PRIMARY KEY,
+        owner VARCHAR(255),
+        state VARCHAR(32),
+        lease_expiry BIGINT,
+        updated_at BIGINT,
+        job json NOT NULL
+);
+
+-- name: create-table-roles
+CREATE TABLE IF NOT EXISTS roles (
+        name VARCHAR(255) PRIMARY KEY,
+        permissions json NOT NULL
+);
+
+-- name: create-table-group_members
+CREATE TABLE IF NOT EXISTS group_members (
+        groupname VARCHAR(255),
+        userid VARCHAR(255),
+        UNIQUE (groupname, userid)
+);
+
+-- name: create-table-role_access
+CREATE TABLE IF NOT EXISTS role_access (
+        id VARCHAR(40),
+        userid VARCHAR(255),
+        rolename VARCHAR(255),
+        UNIQUE (id, userid, rolename)
+);
+
 -- name: clean-table-objects
 DELETE FROM objects;
 
 -- name: clean-table-read_access
 DELETE FROM read_access;
 
+-- name: clean-table-role_access
+DELETE FROM role_access;
+
 -- name: clean-table-tags
 DELETE FROM tags;
 
+-- name: clean-table-archived_objects
+DELETE FROM archived_objects;
+
+-- name: clean-table-jobs
+DELETE FROM jobs;
+
+-- name: clean-table-roles
+DELETE FROM roles;
+
+-- name: clean-table-group_members
+DELETE FROM group_members;
+
 -- name: insert-objects
 INSERT INTO objects (id, object, attributes, state, owner) VALUES (?, ?, ?, ?, ?);
 
 -- name: select-object
-SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, read_access.permissions
+SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, granted.permissions
         FROM objects
-        LEFT JOIN read_access
-        ON objects.id = read_access.id AND ( read_access.userid=? OR read_access.userid='*' )
+        LEFT JOIN (
+                SELECT id, permissions
+                        FROM read_access
+                        WHERE id=? AND userid IN (@PRINCIPALS)
+                UNION ALL
+                SELECT role_access.id, roles.permissions
+                        FROM role_access
+                        INNER JOIN roles ON role_access.rolename = roles.name
+                        WHERE role_access.id=? AND role_access.userid IN (@PRINCIPALS)
+        ) AS granted
+        ON objects.id = granted.id
         WHERE objects.id=?;
 
 -- name: update-object-with-object
@@ -54,9 +118,20 @@ DELETE FROM objects WHERE id=? AND owner=?;
 INSERT INTO objects (id, object, attributes, state, owner) VALUES (?, ?, ?, ?, ?)
         ON DUPLICATE KEY UPDATE
                 object = IF(objects.owner=?, VALUES(object), object),
+                attributes = IF(objects.owner=?, VALUES(attributes), attributes),
                 state = IF(objects.owner=?, VALUES(state), state);
 
 -- name: select-user-accesses-for-object
+SELECT permissions
+        FROM read_access
+        WHERE id=? AND userid IN (@PRINCIPALS)
+UNION ALL
+SELECT roles.permissions
+        FROM role_access
+        INNER JOIN roles ON role_access.rolename = roles.name
+        WHERE role_access.id=? AND role_access.userid IN (@PRINCIPALS);
+
+-- name: select-row-read_access
 SELECT permissions
         FROM read_access
         WHERE id=? AND userid=?;
@@ -79,14 +154,36 @@ UPDATE read_access SET permissions=?
 -- name: select-rows-read_access-with-object-id
 SELECT userid, permissions
         FROM read_access
-        WHERE id=?;
+        WHERE id=?
+UNION ALL
+SELECT role_access.userid, roles.permissions
+        FROM role_access
+        INNER JOIN roles ON role_access.rolename = roles.name
+        WHERE role_access.id=?;
 
 -- name: select-objects-access-obtained
-SELECT objects.id, owner, state, permissions
+SELECT objects.id, objects.owner, objects.state, granted.permissions
         FROM objects
-        INNER JOIN read_access
-        ON objects.id = read_access.id
-        WHERE read_access.userid=?;
+        INNER JOIN (
+                SELECT id, permissions
+                        FROM read_access
+                        WHERE userid IN (@PRINCIPALS)
+                UNION ALL
+                SELECT role_access.id, roles.permissions
+                        FROM role_access
+                        INNER JOIN roles ON role_access.rolename = roles.name
+                        WHERE role_access.userid IN (@PRINCIPALS)
+        ) AS granted
+        ON objects.id = granted.id;
+
+-- name: insert-role_access
+INSERT IGNORE INTO role_access (id, userid, rolename) VALUES (?, ?, ?);
+
+-- name: delete-role_access
+DELETE FROM role_access WHERE id=? AND userid=? AND rolename=?;
+
+-- name: delete-role_access-of-role
+DELETE FROM role_access WHERE rolename=?;
 
 -- name: insert-tags
 INSERT INTO tags (id, tag) VALUES (?, ?);
@@ -99,7 +196,7 @@ DELETE FROM tags WHERE id=?;
 
 
 -- name: select-from-tags
-SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, read_access.permissions
+SELECT objects.id, objects.object, objects.attributes, objects.owner, objects.state, granted.permissions
 FROM objects
 INNER JOIN (
     SELECT id
@@ -109,5 +206,72 @@ INNER JOIN (
     HAVING COUNT(DISTINCT tag) = ?
 ) AS matched_tags
 ON objects.id = matched_tags.id
-LEFT JOIN read_access
-ON objects.id = read_access.id AND ( read_access.userid=? OR read_access.userid='*' );
+LEFT JOIN (
+        SELECT id, permissions
+                FROM read_access
+                WHERE userid IN (@PRINCIPALS)
+        UNION ALL
+        SELECT role_access.id, roles.permissions
+                FROM role_access
+                INNER JOIN roles ON role_access.rolename = roles.name
+                WHERE role_access.userid IN (@PRINCIPALS)
+) AS granted
+ON objects.id = granted.id;
+
+-- name: archive-object
+INSERT INTO archived_objects (id, object, attributes, state, owner)
+        SELECT id, object, attributes, state, owner FROM objects WHERE id=?;
+
+-- name: delete-archived-object-from-objects
+DELETE FROM objects WHERE id=?;
+
+-- name: recover-object
+INSERT INTO objects (id, object, attributes, state, owner)
+        SELECT id, object, attributes, state, owner FROM archived_objects WHERE id=?;
+
+-- name: delete-recovered-object-from-archive
+DELETE FROM archived_objects WHERE id=?;
+
+-- name: select-archived-object-owner
+SELECT owner FROM archived_objects WHERE id=?;
+
+-- name: insert-job
+INSERT INTO jobs (id, owner, state, lease_expiry, updated_at, job) VALUES (?, ?, ?, ?, ?, ?);
+
+-- name: select-job
+SELECT job FROM jobs WHERE id=? AND owner=?;
+
+-- name: update-pending-job
+UPDATE jobs SET state=?, lease_expiry=?, updated_at=?, job=? WHERE id=? AND state='Pending';
+
+-- name: select-interrupted-jobs
+SELECT job FROM jobs WHERE state='Pending' AND lease_expiry<?;
+
+-- name: lease-interrupted-job
+UPDATE jobs SET lease_expiry=?, updated_at=?, job=? WHERE id=? AND state='Pending' AND lease_expiry<?;
+
+-- name: delete-expired-jobs
+DELETE FROM jobs WHERE updated_at<? AND (state<>'Pending' OR lease_expiry<?);
+
+-- name: upsert-role
+INSERT INTO roles (name, permissions) VALUES (?, ?)
+        ON DUPLICATE KEY
+        UPDATE permissions = VALUES(permissions);
+
+-- name: delete-role
+DELETE FROM roles WHERE name=?;
+
+-- name: select-roles
+SELECT name, permissions FROM roles;
+
+-- name: insert-group_member
+INSERT IGNORE INTO group_members (groupname, userid) VALUES (?, ?);
+
+-- name: delete-group_member
+DELETE FROM group_members WHERE groupname=? AND userid=?;
+
+-- name: select-group_members
+SELECT groupname, userid FROM group_members;
+
+-- name: select-user-groups
+SELECT groupname FROM group_members WHERE userid=?;
@@ -16,10 +16,16 @@ use cosmian_kmip::kmip::{
     kmip_objects::{Object, ObjectType},
     kmip_types::{Attributes, StateEnumeration},
 };
+use cosmian_kms_client::access::ObjectOperationType;
 use redis::{aio::ConnectionManager, pipe, AsyncCommands};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::{error::KmsError, kms_bail, result::KResult};
+use crate::{
+    database::job::{Job, JOB_RETENTION},
+    error::KmsError,
+    kms_bail,
+    result::KResult,
+};
 
 /// Extract the keywords from the attributes
 pub(crate) fn keywords_from_attributes(attributes: &Attributes) -> HashSet<Keyword> {
@@ -42,6 +48,51 @@ pub(crate) fn keywords_from_attributes(attributes: &Attributes) -> HashSet<Keywo
             };
         }
     }
+    // the descriptive and extractability attributes are prefixed by their name
+    // so that they cannot match a tag or another attribute
+    for name in attributes.name.iter().flatten() {
+        keywords.insert(Keyword::from(
+            format!("Name={}", name.name_value).as_bytes(),
+        ));
+    }
+    for (attribute_name, value) in [
+        ("ObjectGroup", &attributes.object_group),
+        ("Description", &attributes.description),
+        ("Comment", &attributes.comment),
+        ("ContactInformation", &attributes.contact_information),
+    ] {
+        if let Some(value) = value {
+            keywords.insert(Keyword::from(
+                format!("{attribute_name}={value}").as_bytes(),
+            ));
+        }
+    }
+    if let Some(certificate_attributes) = &attributes.certificate_attributes {
+        if !certificate_attributes
+            .certificate_subject_serial_number
+            .is_empty()
+        {
+            keywords.insert(Keyword::from(
+                format!(
+                    "CertificateSubjectSerialNumber={}",
+                    certificate_attributes.certificate_subject_serial_number
+                )
+                .as_bytes(),
+            ));
+        }
+    }
+    for (attribute_name, value) in [
+        ("Sensitive", attributes.sensitive),
+        ("Extractable", attributes.extractable),
+        ("NeverExtractable", attributes.never_extractable),
+        ("Fresh", attributes.fresh),
+    ] {
+        if let Some(value) = value {
+            keywords.insert(Keyword::from(
+                format!("{attribute_name}={value}").as_bytes(),
+            ));
+        }
+    }
     keywords
 }
 
@@ -98,6 +149,9 @@ impl RedisDbObject {
 
 pub const DB_KEY_LENGTH: usize = 32;
 
+/// The names of the roles granted per object uid, then per user
+pub(crate) type RoleGrants = HashMap<String, HashMap<String, HashSet<String>>>;
+
 pub(crate) struct ObjectsDB {
     mgr: ConnectionManager,
     dem: Aes256Gcm,
@@ -117,23 +171,50 @@ impl ObjectsDB {
         format!("do::{uid}")
     }
 
+    fn archived_object_key(uid: &str) -> String {
+        format!("ao::{uid}")
+    }
+
+    fn job_key(id: &str) -> String {
+        format!("jo::{id}")
+    }
+
+    fn roles_key() -> String {
+        "ro::roles".to_owned()
+    }
+
+    fn local_groups_key() -> String {
+        "gl::groups".to_owned()
+    }
+
+    fn role_grants_key() -> String {
+        "rg::grants".to_owned()
+    }
+
     fn encrypt_object(&self, uid: &str, redis_db_object: &RedisDbObject) -> KResult<Vec<u8>> {
+        self.encrypt_value(uid, redis_db_object)
+    }
+
+    fn decrypt_object(&self, uid: &str, ciphertext: &[u8]) -> KResult<RedisDbObject> {
+        self.decrypt_value(uid, ciphertext)
+    }
+
+    /// Encrypt the JSON serialization of `value`, bound to the identifier `uid`
+    fn encrypt_value<T: Serialize>(&self, uid: &str, value: &T) -> KResult<Vec<u8>> {
         let nonce = {
             let mut rng = self.rng.lock().expect("failed acquiring a lock on the RNG");
             Nonce::new(&mut *rng)
         };
-        let ct = self.dem.encrypt(
-            &nonce,
-            &serde_json::to_vec(redis_db_object)?,
-            Some(uid.as_bytes()),
-        )?;
+        let ct = self
+            .dem
+            .encrypt(&nonce, &serde_json::to_vec(value)?, Some(uid.as_bytes()))?;
         let mut ciphertext = Vec::with_capacity(Aes256Gcm::NONCE_LENGTH + ct.len());
         ciphertext.extend_from_slice(nonce.as_bytes());
         ciphertext.extend(ct);
         Ok(ciphertext)
     }
 
-    fn decrypt_object(&self, uid: &str, ciphertext: &[u8]) -> KResult<RedisDbObject> {
+    fn decrypt_value<T: DeserializeOwned>(&self, uid: &str, ciphertext: &[u8]) -> KResult<T> {
         if ciphertext.len() <= Aes256Gcm::NONCE_LENGTH {
             return Err(KmsError::CryptographicError(
                 "invalid ciphertext".to_string(),
@@ -145,8 +226,7 @@ impl ObjectsDB {
             &ciphertext[Aes256Gcm::NONCE_LENGTH..],
             Some(uid.as_bytes()),
         )?;
-        let redis_db_object: RedisDbObject = serde_json::from_slice(&plaintext)?;
-        Ok(redis_db_object)
+        Ok(serde_json::from_slice(&plaintext)?)
     }
 
     pub async fn object_create(&self, uid: &str, redis_db_object: &RedisDbObject) -> KResult<()> {
@@ -191,6 +271,163 @@ impl ObjectsDB {
         Ok(())
     }
 
+    /// Move the object to the archive: the encrypted object is kept as is
+    pub async fn object_archive(&self, uid: &str) -> KResult<()> {
+        let res: usize = self
+            .mgr
+            .clone()
+            .rename_nx(
+                ObjectsDB::object_key(uid),
+                ObjectsDB::archived_object_key(uid),
+            )
+            .await?;
+        if res == 1 {
+            Ok(())
+        } else {
+            kms_bail!("object {uid} is already archived")
+        }
+    }
+
+    /// Move the object back from the archive
+    pub async fn object_recover(&self, uid: &str) -> KResult<()> {
+        let res: usize = self
+            .mgr
+            .clone()
+            .rename_nx(
+                ObjectsDB::archived_object_key(uid),
+                ObjectsDB::object_key(uid),
+            )
+            .await?;
+        if res == 1 {
+            Ok(())
+        } else {
+            kms_bail!("object {uid} already exists")
+        }
+    }
+
+    pub async fn archived_object_get(&self, uid: &str) -> KResult<Option<RedisDbObject>> {
+        let ciphertext: Vec<u8> = self
+            .mgr
+            .clone()
+            .get(ObjectsDB::archived_object_key(uid))
+            .await?;
+        if ciphertext.is_empty() {
+            return Ok(None)
+        }
+        let mut dbo: RedisDbObject = self.decrypt_object(uid, &ciphertext)?;
+        dbo.object = Object::post_fix(dbo.object_type, dbo.object);
+        Ok(Some(dbo))
+    }
+
+    /// Upsert the job, which expires once its retention period is over
+    pub async fn job_upsert(&self, job: &Job) -> KResult<()> {
+        let key = ObjectsDB::job_key(&job.id);
+        let expiry = job.lease_expiry.max(job.updated_at) + JOB_RETENTION.as_secs() as i64;
+        let ttl = (expiry - chrono::Utc::now().timestamp()).max(1) as usize;
+        self.mgr
+            .clone()
+            .set_ex(&key, self.encrypt_value(&key, job)?, ttl)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn job_get(&self, id: &str) -> KResult<Option<Job>> {
+        let key = ObjectsDB::job_key(id);
+        let ciphertext: Vec<u8> = self.mgr.clone().get(&key).await?;
+        if ciphertext.is_empty() {
+            return Ok(None)
+        }
+        Ok(Some(self.decrypt_value(&key, &ciphertext)?))
+    }
+
+    /// All the jobs, whatever their owner
+    pub async fn jobs_get_all(&self) -> KResult<Vec<Job>> {
+        let mut mgr = self.mgr.clone();
+        let mut keys: Vec<String> = vec![];
+        let mut iter = mgr.scan_match::<_, String>(ObjectsDB::job_key("*")).await?;
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        let mut jobs = Vec::with_capacity(keys.len());
+        for key in keys {
+            // the job may have expired since it was listed
+            let ciphertext: Vec<u8> = self.mgr.clone().get(&key).await?;
+            if !ciphertext.is_empty() {
+                jobs.push(self.decrypt_value(&key, &ciphertext)?);
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// Set the encrypted `value` at `key`
+    async fn value_set<T: Serialize>(&self, key: &str, value: &T) -> KResult<()> {
+        self.mgr
+            .clone()
+            .set(key, self.encrypt_value(key, value)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Get the value at `key` or its default if there is none
+    async fn value_get_or_default<T: DeserializeOwned + Default>(&self, key: &str) -> KResult<T> {
+        let ciphertext: Vec<u8> = self.mgr.clone().get(key).await?;
+        if ciphertext.is_empty() {
+            return Ok(T::default())
+        }
+        self.decrypt_value(key, &ciphertext)
+    }
+
+    /// The roles and their operation types
+    pub async fn roles_get(&self) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
+        self.value_get_or_default(&ObjectsDB::roles_key()).await
+    }
+
+    pub async fn roles_set(
+        &self,
+        roles: &HashMap<String, HashSet<ObjectOperationType>>,
+    ) -> KResult<()> {
+        self.value_set(&ObjectsDB::roles_key(), roles).await
+    }
+
+    /// The local groups and their members
+    pub async fn local_groups_get(&self) -> KResult<HashMap<String, HashSet<String>>> {
+        self.value_get_or_default(&ObjectsDB::local_groups_key())
+            .await
+    }
+
+    pub async fn local_groups_set(&self, groups: &HashMap<String, HashSet<String>>) -> KResult<()> {
+        self.value_set(&ObjectsDB::local_groups_key(), groups).await
+    }
+
+    /// The roles granted on the objects
+    pub async fn role_grants_get(&self) -> KResult<RoleGrants> {
+        self.value_get_or_default(&ObjectsDB::role_grants_key())
+            .await
+    }
+
+    pub async fn role_grants_set(&self, grants: &RoleGrants) -> KResult<()> {
+        self.value_set(&ObjectsDB::role_grants_key(), grants).await
+    }
+
+    /// Set the roles and the role grants together, in a single transaction
+    pub async fn roles_and_grants_set(
+        &self,
+        roles: &HashMap<String, HashSet<ObjectOperationType>>,
+        grants: &RoleGrants,
+    ) -> KResult<()> {
+        let roles_key = ObjectsDB::roles_key();
+        let grants_key = ObjectsDB::role_grants_key();
+        pipe()
+            .atomic()
+            .set(&roles_key, self.encrypt_value(&roles_key, roles)?)
+            .ignore()
+            .set(&grants_key, self.encrypt_value(&grants_key, grants)?)
+            .ignore()
+            .query_async(&mut self.mgr.clone())
+            .await?;
+        Ok(())
+    }
+
     pub async fn objects_get(
         &self,
         uids: &HashSet<String>,
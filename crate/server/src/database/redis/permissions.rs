@@ -133,19 +133,25 @@ impl PermissionsDB {
         findex_key: &SymmetricKey<MASTER_KEY_LENGTH>,
         keyword: &str,
     ) -> KResult<HashSet<Triple>> {
-        let keyword = Keyword::from(format!("p::{keyword}").as_bytes());
+        self.search_keywords(findex_key, &[keyword.to_owned()])
+            .await
+    }
+
+    /// Search for several keywords at once and merge the results
+    async fn search_keywords(
+        &self,
+        findex_key: &SymmetricKey<MASTER_KEY_LENGTH>,
+        keywords: &[String],
+    ) -> KResult<HashSet<Triple>> {
+        let keywords = keywords
+            .iter()
+            .map(|keyword| Keyword::from(format!("p::{keyword}").as_bytes()))
+            .collect::<HashSet<Keyword>>();
         self.findex
-            .search(
-                &findex_key.to_bytes(),
-                &self.label,
-                HashSet::from([keyword.clone()]),
-            )
+            .search(&findex_key.to_bytes(), &self.label, keywords)
             .await?
-            .into_iter()
-            .next()
-            .unwrap_or((keyword, HashSet::new()))
-            .1
-            .iter()
+            .values()
+            .flatten()
             .map(Triple::try_from)
             .collect::<KResult<HashSet<Triple>>>()
     }
@@ -156,9 +162,20 @@ impl PermissionsDB {
         &self,
         findex_key: &SymmetricKey<MASTER_KEY_LENGTH>,
         user_id: &str,
+    ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
+        self.list_principals_permissions(findex_key, &[user_id.to_owned()])
+            .await
+    }
+
+    /// List all the permissions granted to any of the principals
+    /// per object uid
+    pub async fn list_principals_permissions(
+        &self,
+        findex_key: &SymmetricKey<MASTER_KEY_LENGTH>,
+        principals: &[String],
     ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
         Ok(Triple::permissions_per_object(
-            self.search_one_keyword(findex_key, user_id).await?,
+            self.search_keywords(findex_key, principals).await?,
         ))
     }
 
@@ -182,23 +199,31 @@ impl PermissionsDB {
         user_id: &str,
         no_inherited_access: bool,
     ) -> KResult<HashSet<ObjectOperationType>> {
-        let mut user_perms = self
-            .search_one_keyword(findex_key, &Triple::build_key(obj_uid, user_id))
-            .await?
-            .into_iter()
-            .map(|triple| triple.permission)
-            .collect::<HashSet<ObjectOperationType>>();
-        if no_inherited_access {
-            return Ok(user_perms)
+        let mut principals = vec![user_id.to_owned()];
+        if !no_inherited_access {
+            principals.push("*".to_owned());
         }
-        let wildcard_user_perms = self
-            .search_one_keyword(findex_key, &Triple::build_key(obj_uid, "*"))
+        self.get_for_principals(findex_key, obj_uid, &principals)
+            .await
+    }
+
+    /// List all the permissions granted to any of the principals on an object
+    pub async fn get_for_principals(
+        &self,
+        findex_key: &SymmetricKey<MASTER_KEY_LENGTH>,
+        obj_uid: &str,
+        principals: &[String],
+    ) -> KResult<HashSet<ObjectOperationType>> {
+        let keywords = principals
+            .iter()
+            .map(|principal| Triple::build_key(obj_uid, principal))
+            .collect::<Vec<String>>();
+        Ok(self
+            .search_keywords(findex_key, &keywords)
             .await?
             .into_iter()
             .map(|triple| triple.permission)
-            .collect::<HashSet<ObjectOperationType>>();
-        user_perms.extend(wildcard_user_perms);
-        Ok(user_perms)
+            .collect())
     }
 
     /// Add a permission to the user on an object
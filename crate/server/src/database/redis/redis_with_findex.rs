@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    iter,
     path::PathBuf,
     sync::Arc,
 };
@@ -29,8 +30,12 @@ use super::{
 use crate::{
     core::extra_database_params::ExtraDatabaseParams,
     database::{
-        database_trait::AtomicOperation, object_with_metadata::ObjectWithMetadata,
-        redis::objects_db::RedisOperation, Database,
+        database_trait::AtomicOperation,
+        initial_state,
+        job::{Job, JobState},
+        object_with_metadata::ObjectWithMetadata,
+        redis::objects_db::RedisOperation,
+        Database,
     },
     error::KmsError,
     kms_bail, kms_error,
@@ -106,6 +111,40 @@ impl RedisWithFindex {
         Ok(master_secret_key)
     }
 
+    /// The operation types granted to any of the `principals` through their roles,
+    /// per object uid, restricted to the object `uid` if any
+    ///
+    /// The roles are resolved now, so that their later changes apply
+    async fn role_permissions(
+        &self,
+        uid: Option<&str>,
+        principals: &[String],
+    ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
+        let grants = self.objects_db.role_grants_get().await?;
+        if grants.is_empty() {
+            return Ok(HashMap::new())
+        }
+        let roles = self.objects_db.roles_get().await?;
+        let mut permissions: HashMap<String, HashSet<ObjectOperationType>> = HashMap::new();
+        for (obj_uid, users) in grants {
+            if uid.is_some_and(|uid| uid != obj_uid) {
+                continue
+            }
+            for (user, granted_roles) in users {
+                if !principals.contains(&user) {
+                    continue
+                }
+                permissions.entry(obj_uid.clone()).or_default().extend(
+                    granted_roles
+                        .iter()
+                        .filter_map(|role| roles.get(role))
+                        .flatten(),
+                );
+            }
+        }
+        Ok(permissions)
+    }
+
     /// Prepare an object for upsert
     /// Note: Findex indexes are upserted even if the object is not upserted later on
     async fn prepare_object_for_upsert(
@@ -156,15 +195,11 @@ impl RedisWithFindex {
     ) -> Result<(String, RedisDbObject), KmsError> {
         // If the uid is not provided, generate a new one
         let uid = uid.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let state = object
+            .attributes()
+            .map_or(StateEnumeration::Active, initial_state);
         let db_object = self
-            .prepare_object_for_upsert(
-                &uid,
-                owner,
-                object,
-                Some(tags),
-                StateEnumeration::Active,
-                None,
-            )
+            .prepare_object_for_upsert(&uid, owner, object, Some(tags), state, None)
             .await?;
         Ok((uid, db_object))
     }
@@ -259,10 +294,11 @@ impl Database for RedisWithFindex {
     ///
     /// The `query_access_grant` allows additional filtering in the `access` table to see
     /// if a `user`, that is not a owner, has the corresponding access granted
-    async fn retrieve(
+    async fn retrieve_for_principals(
         &self,
         uid_or_tags: &str,
         user: &str,
+        principals: &[String],
         query_access_grant: ObjectOperationType,
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, ObjectWithMetadata>> {
@@ -293,6 +329,13 @@ impl Database for RedisWithFindex {
 
         // now retrieve the object
         let results = self.objects_db.objects_get(&uids).await?;
+        // the access rights are inherited from the groups of the user and the wildcard user
+        let principals = principals
+            .iter()
+            .cloned()
+            .chain(iter::once("*".to_owned()))
+            .collect::<Vec<String>>();
+        let role_permissions = self.role_permissions(None, &principals).await?;
         let mut objects: HashMap<String, ObjectWithMetadata> = HashMap::new();
         for (uid, redis_db_object) in results {
             // if the user is the owner, return it
@@ -311,12 +354,15 @@ impl Database for RedisWithFindex {
                 continue
             }
 
-            // fetch the permissions for the user and the wildcard user
-            let permissions: HashSet<ObjectOperationType> = self
+            // fetch the permissions for all the principals, including those of their roles
+            let mut permissions: HashSet<ObjectOperationType> = self
                 .permissions_db
-                .get(&self.findex_key, &uid, user, false)
+                .get_for_principals(&self.findex_key, &uid, &principals)
                 .await
                 .unwrap_or_default();
+            if let Some(role_permissions) = role_permissions.get(&uid) {
+                permissions.extend(role_permissions);
+            }
             if permissions.contains(&query_access_grant) {
                 objects.insert(
                     uid.clone(),
@@ -331,6 +377,17 @@ impl Database for RedisWithFindex {
                 );
             }
         }
+
+        // activate the pre-active objects whose activation date has been reached
+        for owm in objects.values_mut() {
+            if owm.is_activation_due() {
+                let db_object = self
+                    .prepare_object_for_state_update(&owm.id, StateEnumeration::Active)
+                    .await?;
+                self.objects_db.object_upsert(&owm.id, &db_object).await?;
+                owm.state = StateEnumeration::Active;
+            }
+        }
         Ok(objects)
     }
 
@@ -413,33 +470,128 @@ impl Database for RedisWithFindex {
         Ok(())
     }
 
-    async fn list_user_granted_access_rights(
+    async fn archive(&self, uid: &str, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if self.objects_db.object_get(uid).await?.is_none() {
+            kms_bail!(KmsError::ItemNotFound(uid.to_owned()))
+        }
+        // the index entries are kept: archived objects are simply skipped on retrieval
+        self.objects_db.object_archive(uid).await
+    }
+
+    async fn recover(&self, uid: &str, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if self.objects_db.archived_object_get(uid).await?.is_none() {
+            kms_bail!(KmsError::ItemNotFound(uid.to_owned()))
+        }
+        self.objects_db.object_recover(uid).await
+    }
+
+    async fn retrieve_archived_owner(
         &self,
-        user: &str,
+        uid: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<String>> {
+        Ok(self
+            .objects_db
+            .archived_object_get(uid)
+            .await?
+            .map(|db_object| db_object.owner))
+    }
+
+    async fn create_job(&self, job: &Job, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        if self.objects_db.job_get(&job.id).await?.is_some() {
+            kms_bail!("job {} already exists", job.id)
+        }
+        self.objects_db.job_upsert(job).await
+    }
+
+    async fn retrieve_job(
+        &self,
+        id: &str,
+        owner: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<Job>> {
+        Ok(self
+            .objects_db
+            .job_get(id)
+            .await?
+            .filter(|job| job.owner == owner))
+    }
+
+    async fn update_pending_job(
+        &self,
+        job: &Job,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool> {
+        // this check and the update are not atomic: a job canceled in between is overwritten
+        match self.objects_db.job_get(&job.id).await? {
+            Some(stored) if stored.state == JobState::Pending => {
+                self.objects_db.job_upsert(job).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn list_interrupted_jobs(
+        &self,
+        now: i64,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<Job>> {
+        Ok(self
+            .objects_db
+            .jobs_get_all()
+            .await?
+            .into_iter()
+            .filter(|job| job.state == JobState::Pending && job.lease_expiry < now)
+            .collect())
+    }
+
+    async fn lease_interrupted_job(
+        &self,
+        job: &Job,
+        now: i64,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool> {
+        // this check and the update are not atomic: two servers may resume the same job
+        match self.objects_db.job_get(&job.id).await? {
+            Some(stored) if stored.state == JobState::Pending && stored.lease_expiry < now => {
+                self.objects_db.job_upsert(job).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn purge_jobs(&self, _before: i64, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        // the jobs expire in Redis once their retention period is over
+        Ok(())
+    }
+
+    async fn list_principals_granted_access_rights(
+        &self,
+        principals: &[String],
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, (String, StateEnumeration, HashSet<ObjectOperationType>)>> {
-        let permissions = self
+        let mut permissions = self
             .permissions_db
-            .list_user_permissions(&self.findex_key, user)
+            .list_principals_permissions(&self.findex_key, principals)
             .await?;
+        for (uid, role_permissions) in self.role_permissions(None, principals).await? {
+            permissions.entry(uid).or_default().extend(role_permissions);
+        }
         let redis_db_objects = self
             .objects_db
             .objects_get(&permissions.keys().cloned().collect::<HashSet<String>>())
             .await?;
-        Ok(permissions
+        // the objects are matched by uid: the two maps are not iterated in the same order
+        Ok(redis_db_objects
             .into_iter()
-            .zip(redis_db_objects)
-            .map(|((uid, permissions), (_, redis_db_object))| {
-                (
+            .filter_map(|(uid, redis_db_object)| {
+                let permissions = permissions.remove(&uid)?;
+                Some((
                     uid,
-                    (
-                        redis_db_object.owner,
-                        redis_db_object.state,
-                        permissions
-                            .into_iter()
-                            .collect::<HashSet<ObjectOperationType>>(),
-                    ),
-                )
+                    (redis_db_object.owner, redis_db_object.state, permissions),
+                ))
             })
             .collect())
     }
@@ -451,9 +603,23 @@ impl Database for RedisWithFindex {
         uid: &str,
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
-        self.permissions_db
+        let mut permissions = self
+            .permissions_db
             .list_object_permissions(&self.findex_key, uid)
-            .await
+            .await?;
+        let grants = self.objects_db.role_grants_get().await?;
+        if let Some(users) = grants.get(uid) {
+            let roles = self.objects_db.roles_get().await?;
+            for (user, granted_roles) in users {
+                permissions.entry(user.clone()).or_default().extend(
+                    granted_roles
+                        .iter()
+                        .filter_map(|role| roles.get(role))
+                        .flatten(),
+                );
+            }
+        }
+        Ok(permissions)
     }
 
     /// Grant the access right to `user` to perform the `operation_type`
@@ -490,6 +656,55 @@ impl Database for RedisWithFindex {
         Ok(())
     }
 
+    // the role grants are stored as a single value:
+    // their read-modify-write updates are not atomic
+
+    async fn grant_role(
+        &self,
+        uid: &str,
+        user: &str,
+        role: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        let mut grants = self.objects_db.role_grants_get().await?;
+        if grants
+            .entry(uid.to_owned())
+            .or_default()
+            .entry(user.to_owned())
+            .or_default()
+            .insert(role.to_owned())
+        {
+            self.objects_db.role_grants_set(&grants).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_role(
+        &self,
+        uid: &str,
+        user: &str,
+        role: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        let mut grants = self.objects_db.role_grants_get().await?;
+        let Some(users) = grants.get_mut(uid) else {
+            return Ok(())
+        };
+        let Some(roles) = users.get_mut(user) else {
+            return Ok(())
+        };
+        if roles.remove(role) {
+            if roles.is_empty() {
+                users.remove(user);
+            }
+            if users.is_empty() {
+                grants.remove(uid);
+            }
+            self.objects_db.role_grants_set(&grants).await?;
+        }
+        Ok(())
+    }
+
     /// Test if an object identified by its `uid` is currently owned by `owner`
     async fn is_object_owned_by(
         &self,
@@ -507,11 +722,12 @@ impl Database for RedisWithFindex {
 
     /// Return uid, state and attributes of the object identified by its owner,
     /// and possibly by its attributes and/or its `state`
-    async fn find(
+    async fn find_for_principals(
         &self,
         researched_attributes: Option<&Attributes>,
         state: Option<StateEnumeration>,
         user: &str,
+        principals: &[String],
         user_must_be_owner: bool,
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<Vec<(String, StateEnumeration, Attributes, IsWrapped)>> {
@@ -538,6 +754,9 @@ impl Database for RedisWithFindex {
         if keywords.is_empty() {
             return Ok(vec![])
         }
+        // the index is only appended to: the keywords of the objects are checked again
+        // once fetched, since some of them may have been modified
+        let researched_keywords = keywords.clone();
         // search the keywords in the index
         let res = self
             .findex
@@ -555,28 +774,36 @@ impl Database for RedisWithFindex {
         trace!("find: uids before permissions: {:?}", uids);
         // if the user is not the owner, we need to check the permissions
         let permissions = if !user_must_be_owner {
-            self.permissions_db
-                .list_user_permissions(&self.findex_key, user)
-                .await?
+            let mut permissions = self
+                .permissions_db
+                .list_principals_permissions(&self.findex_key, principals)
+                .await?;
+            permissions.extend(self.role_permissions(None, principals).await?);
+            permissions
         } else {
             HashMap::new()
         };
 
+        // the state may also be researched as an attribute
+        let state = state.or_else(|| researched_attributes.and_then(|attributes| attributes.state));
+
         // fetch the corresponding objects
         let redis_db_objects = self.objects_db.objects_get(&uids).await?;
         trace!("find: redis_db_objects: {:?}", redis_db_objects);
         Ok(redis_db_objects
             .into_iter()
             .filter(|(uid, redis_db_object)| {
-                (if let Some(state) = state {
-                    redis_db_object.state == state
-                } else {
-                    true
-                }) && (if user != redis_db_object.owner {
-                    permissions.contains_key(uid)
-                } else {
-                    true
-                })
+                researched_keywords.is_subset(&redis_db_object.keywords())
+                    && (if let Some(state) = state {
+                        redis_db_object.state == state
+                    } else {
+                        true
+                    })
+                    && (if user != redis_db_object.owner {
+                        permissions.contains_key(uid)
+                    } else {
+                        true
+                    })
             })
             .map(|(uid, redis_db_object)| {
                 (
@@ -596,19 +823,129 @@ impl Database for RedisWithFindex {
             .collect())
     }
 
-    async fn list_user_access_rights_on_object(
+    async fn list_principals_access_rights_on_object(
         &self,
         uid: &str,
-        user: &str,
+        principals: &[String],
         no_inherited_access: bool,
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashSet<ObjectOperationType>> {
-        Ok(self
+        let mut principals = principals.to_vec();
+        if !no_inherited_access {
+            principals.push("*".to_owned());
+        }
+        let mut permissions = self
             .permissions_db
-            .get(&self.findex_key, uid, user, no_inherited_access)
+            .get_for_principals(&self.findex_key, uid, &principals)
             .await
-            .unwrap_or_default()
+            .unwrap_or_default();
+        if let Some(role_permissions) = self
+            .role_permissions(Some(uid), &principals)
+            .await?
+            .remove(uid)
+        {
+            permissions.extend(role_permissions);
+        }
+        Ok(permissions)
+    }
+
+    // the roles and the groups are each stored as a single value:
+    // their read-modify-write updates are not atomic
+
+    async fn set_role(
+        &self,
+        name: &str,
+        operation_types: &HashSet<ObjectOperationType>,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        let mut roles = self.objects_db.roles_get().await?;
+        roles.insert(name.to_owned(), operation_types.clone());
+        self.objects_db.roles_set(&roles).await
+    }
+
+    async fn delete_role(&self, name: &str, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        let mut roles = self.objects_db.roles_get().await?;
+        if roles.remove(name).is_none() {
+            kms_bail!(KmsError::ItemNotFound(format!("role {name}")))
+        }
+        // the access rights granted with the role are revoked with it
+        let mut grants = self.objects_db.role_grants_get().await?;
+        let mut revoked = false;
+        for users in grants.values_mut() {
+            for roles in users.values_mut() {
+                revoked |= roles.remove(name);
+            }
+            users.retain(|_, roles| !roles.is_empty());
+        }
+        if !revoked {
+            return self.objects_db.roles_set(&roles).await
+        }
+        grants.retain(|_, users| !users.is_empty());
+        self.objects_db.roles_and_grants_set(&roles, &grants).await
+    }
+
+    async fn list_roles(
+        &self,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
+        self.objects_db.roles_get().await
+    }
+
+    async fn add_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        let mut groups = self.objects_db.local_groups_get().await?;
+        if groups
+            .entry(group.to_owned())
+            .or_default()
+            .insert(user.to_owned())
+        {
+            self.objects_db.local_groups_set(&groups).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        let mut groups = self.objects_db.local_groups_get().await?;
+        let Some(members) = groups.get_mut(group) else {
+            return Ok(())
+        };
+        if members.remove(user) {
+            if members.is_empty() {
+                groups.remove(group);
+            }
+            self.objects_db.local_groups_set(&groups).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_groups(
+        &self,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<String>>> {
+        self.objects_db.local_groups_get().await
+    }
+
+    async fn list_user_groups(
+        &self,
+        user: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashSet<String>> {
+        Ok(self
+            .objects_db
+            .local_groups_get()
+            .await?
             .into_iter()
+            .filter(|(_, members)| members.contains(user))
+            .map(|(group, _)| group)
             .collect())
     }
 
@@ -1,4 +1,4 @@
-use cosmian_kmip::kmip::kmip_types::StateEnumeration;
+use cosmian_kmip::kmip::{kmip_operations::ErrorReason, kmip_types::StateEnumeration};
 use cosmian_kms_client::access::ObjectOperationType;
 use tracing::trace;
 
@@ -28,7 +28,18 @@ pub async fn retrieve_object_for_operation(
             Ok(key) => key,
             Err(_) => {
                 // see if we can Get: in that case the user can always re-import the object and own it
-                _retrieve_object(uid_or_tags, ObjectOperationType::Get, kms, user, params).await?
+                match _retrieve_object(uid_or_tags, ObjectOperationType::Get, kms, user, params)
+                    .await
+                {
+                    Ok(key) => key,
+                    Err(_) if is_object_archived(uid_or_tags, kms, params).await? => {
+                        return Err(KmsError::KmipError(
+                            ErrorReason::Object_Archived,
+                            uid_or_tags.to_owned(),
+                        ))
+                    }
+                    Err(e) => return Err(e),
+                }
             }
         },
     )
@@ -66,3 +77,22 @@ async fn _retrieve_object(
     }
     Ok(owm)
 }
+
+/// Whether `uid_or_tags` is the unique identifier of an archived object.
+///
+/// Archived objects are not retrieved: this is used to return
+/// an `Object_Archived` error rather than an `Item_Not_Found` one.
+pub(crate) async fn is_object_archived(
+    uid_or_tags: &str,
+    kms: &KMS,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<bool> {
+    if uid_or_tags.starts_with('[') {
+        return Ok(false)
+    }
+    Ok(kms
+        .db
+        .retrieve_archived_owner(uid_or_tags, params)
+        .await?
+        .is_some())
+}
@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    iter,
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -19,13 +20,14 @@ use sqlx::{
 use tracing::{debug, trace};
 use uuid::Uuid;
 
-use super::object_with_metadata::ObjectWithMetadata;
+use super::{job::Job, object_with_metadata::ObjectWithMetadata};
 use crate::{
     core::extra_database_params::ExtraDatabaseParams,
     database::{
-        database_trait::AtomicOperation, query_from_attributes, state_from_string, DBObject,
-        Database, SqlitePlaceholder, SQLITE_QUERIES,
+        database_trait::AtomicOperation, initial_state, placeholders, query_from_attributes,
+        state_from_string, DBObject, Database, SqlitePlaceholder, SQLITE_QUERIES,
     },
+    error::KmsError,
     kms_bail, kms_error,
     result::{KResult, KResultHelper},
 };
@@ -75,6 +77,46 @@ impl SqlitePool {
         .execute(&pool)
         .await?;
 
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-archived_objects")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-jobs")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-roles")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-group_members")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("create-table-role_access")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .execute(&pool)
+        .await?;
+
         if clear_database {
             clear_database_(&pool).await?;
         }
@@ -110,14 +152,15 @@ impl Database for SqlitePool {
         Ok(uid)
     }
 
-    async fn retrieve(
+    async fn retrieve_for_principals(
         &self,
         uid_or_tags: &str,
         user: &str,
+        principals: &[String],
         operation_type: ObjectOperationType,
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, ObjectWithMetadata>> {
-        retrieve_(uid_or_tags, user, operation_type, &self.pool).await
+        retrieve_(uid_or_tags, user, principals, operation_type, &self.pool).await
     }
 
     async fn retrieve_tags(
@@ -210,12 +253,90 @@ impl Database for SqlitePool {
         }
     }
 
-    async fn list_user_granted_access_rights(
+    async fn archive(&self, uid: &str, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        let mut tx = self.pool.begin().await?;
+        match archive_(uid, &mut tx).await {
+            Ok(()) => {
+                tx.commit().await?;
+                Ok(())
+            }
+            Err(e) => {
+                tx.rollback().await.context("transaction failed")?;
+                kms_bail!("archive of object {uid} failed: {e}");
+            }
+        }
+    }
+
+    async fn recover(&self, uid: &str, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        let mut tx = self.pool.begin().await?;
+        match recover_(uid, &mut tx).await {
+            Ok(()) => {
+                tx.commit().await?;
+                Ok(())
+            }
+            Err(e) => {
+                tx.rollback().await.context("transaction failed")?;
+                kms_bail!("recovery of object {uid} failed: {e}");
+            }
+        }
+    }
+
+    async fn retrieve_archived_owner(
         &self,
-        user: &str,
+        uid: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<String>> {
+        retrieve_archived_owner_(uid, &self.pool).await
+    }
+
+    async fn create_job(&self, job: &Job, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        create_job_(job, &self.pool).await
+    }
+
+    async fn retrieve_job(
+        &self,
+        id: &str,
+        owner: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Option<Job>> {
+        retrieve_job_(id, owner, &self.pool).await
+    }
+
+    async fn update_pending_job(
+        &self,
+        job: &Job,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool> {
+        update_pending_job_(job, &self.pool).await
+    }
+
+    async fn list_interrupted_jobs(
+        &self,
+        now: i64,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<Vec<Job>> {
+        list_interrupted_jobs_(now, &self.pool).await
+    }
+
+    async fn lease_interrupted_job(
+        &self,
+        job: &Job,
+        now: i64,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<bool> {
+        lease_interrupted_job_(job, now, &self.pool).await
+    }
+
+    async fn purge_jobs(&self, before: i64, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        purge_jobs_(before, &self.pool).await
+    }
+
+    async fn list_principals_granted_access_rights(
+        &self,
+        principals: &[String],
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashMap<String, (String, StateEnumeration, HashSet<ObjectOperationType>)>> {
-        list_user_granted_access_rights_(user, &self.pool).await
+        list_user_granted_access_rights_(principals, &self.pool).await
     }
 
     async fn list_object_accesses_granted(
@@ -246,6 +367,26 @@ impl Database for SqlitePool {
         remove_access_(uid, userid, operation_types, &self.pool).await
     }
 
+    async fn grant_role(
+        &self,
+        uid: &str,
+        userid: &str,
+        role: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        grant_role_(uid, userid, role, &self.pool).await
+    }
+
+    async fn remove_role(
+        &self,
+        uid: &str,
+        userid: &str,
+        role: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        remove_role_(uid, userid, role, &self.pool).await
+    }
+
     async fn is_object_owned_by(
         &self,
         uid: &str,
@@ -255,11 +396,12 @@ impl Database for SqlitePool {
         is_object_owned_by_(uid, userid, &self.pool).await
     }
 
-    async fn find(
+    async fn find_for_principals(
         &self,
         researched_attributes: Option<&Attributes>,
         state: Option<StateEnumeration>,
         user: &str,
+        principals: &[String],
         user_must_be_owner: bool,
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<Vec<(String, StateEnumeration, Attributes, IsWrapped)>> {
@@ -267,20 +409,85 @@ impl Database for SqlitePool {
             researched_attributes,
             state,
             user,
+            principals,
             user_must_be_owner,
             &self.pool,
         )
         .await
     }
 
-    async fn list_user_access_rights_on_object(
+    async fn list_principals_access_rights_on_object(
         &self,
         uid: &str,
-        userid: &str,
+        principals: &[String],
         no_inherited_access: bool,
         _params: Option<&ExtraDatabaseParams>,
     ) -> KResult<HashSet<ObjectOperationType>> {
-        list_user_access_rights_on_object_(uid, userid, no_inherited_access, &self.pool).await
+        list_user_access_rights_on_object_(uid, principals, no_inherited_access, &self.pool).await
+    }
+
+    async fn set_role(
+        &self,
+        name: &str,
+        operation_types: &HashSet<ObjectOperationType>,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        set_role_(name, operation_types, &self.pool).await
+    }
+
+    async fn delete_role(&self, name: &str, _params: Option<&ExtraDatabaseParams>) -> KResult<()> {
+        // the grants of the role and the role are deleted together
+        let mut tx = self.pool.begin().await?;
+        match delete_role_(name, &mut tx).await {
+            Ok(()) => {
+                tx.commit().await?;
+                Ok(())
+            }
+            Err(e) => {
+                tx.rollback().await.context("transaction failed")?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn list_roles(
+        &self,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<ObjectOperationType>>> {
+        list_roles_(&self.pool).await
+    }
+
+    async fn add_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        add_group_member_(group, user, &self.pool).await
+    }
+
+    async fn remove_group_member(
+        &self,
+        group: &str,
+        user: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<()> {
+        remove_group_member_(group, user, &self.pool).await
+    }
+
+    async fn list_groups(
+        &self,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashMap<String, HashSet<String>>> {
+        list_groups_(&self.pool).await
+    }
+
+    async fn list_user_groups(
+        &self,
+        user: &str,
+        _params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<HashSet<String>> {
+        list_user_groups_(user, &self.pool).await
     }
 
     async fn atomic(
@@ -333,7 +540,7 @@ pub(crate) async fn create_(
     .bind(uid.clone())
     .bind(object_json)
     .bind(attributes_json)
-    .bind(StateEnumeration::Active.to_string())
+    .bind(initial_state(attributes).to_string())
     .bind(owner)
     .execute(&mut **executor)
     .await?;
@@ -358,23 +565,33 @@ pub(crate) async fn create_(
 pub(crate) async fn retrieve_<'e, E>(
     uid_or_tags: &str,
     user: &str,
+    principals: &[String],
     operation_type: ObjectOperationType,
     executor: E,
 ) -> KResult<HashMap<String, ObjectWithMetadata>>
 where
     E: Executor<'e, Database = Sqlite> + Copy,
 {
+    // the access rights granted to any principal of the user and to the wildcard user,
+    // directly or through a role, are all fetched by the same query
+    let principals = principals
+        .iter()
+        .map(String::as_str)
+        .chain(iter::once("*"))
+        .collect::<Vec<_>>();
     let rows: Vec<SqliteRow> = if !uid_or_tags.starts_with('[') {
-        sqlx::query(
-            SQLITE_QUERIES
-                .get("select-object")
-                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
-        )
-        .bind(uid_or_tags)
-        .bind(user)
-        .fetch_optional(executor)
-        .await?
-        .map_or(vec![], |row| vec![row])
+        let raw_sql = SQLITE_QUERIES
+            .get("select-object")
+            .context("SQL query can't be found")?
+            .replace(
+                "@PRINCIPALS",
+                &placeholders::<SqlitePlaceholder>(2, principals.len()),
+            );
+        let mut query = sqlx::query::<Sqlite>(&raw_sql).bind(uid_or_tags);
+        for principal in &principals {
+            query = query.bind(*principal);
+        }
+        query.fetch_all(executor).await?
     } else {
         // deserialize the array to an HashSet
         let tags: HashSet<String> = serde_json::from_str(uid_or_tags)
@@ -396,7 +613,10 @@ where
             .context("SQL query can't be found")?
             .replace("@TAGS", &tags_params)
             .replace("@LEN", &format!("${}", tags.len() + 1))
-            .replace("@USER", &format!("${}", tags.len() + 2));
+            .replace(
+                "@PRINCIPALS",
+                &placeholders::<SqlitePlaceholder>(tags.len() + 2, principals.len()),
+            );
 
         trace!("retrieve: tags: {tags:?}, user: {user}, raw_sql = {raw_sql},");
 
@@ -405,8 +625,11 @@ where
         for tag in &tags {
             query = query.bind(tag);
         }
-        // Bind the tags len and the user
-        query = query.bind(tags.len() as i16).bind(user);
+        // Bind the tags len and the principals
+        query = query.bind(tags.len() as i16);
+        for principal in &principals {
+            query = query.bind(*principal);
+        }
 
         // Execute the query
         query.fetch_all(executor).await?
@@ -427,7 +650,7 @@ where
 
         // check if the object is already in the result
         // this can happen as permissions may have been granted
-        // to both this user and the wildcard user
+        // to several principals of this user, the wildcard user or through roles
         match res.get_mut(&object_with_metadata.id) {
             Some(existing_object) => {
                 // update the permissions
@@ -441,6 +664,23 @@ where
             }
         };
     }
+
+    // activate the pre-active objects whose activation date has been reached
+    for object_with_metadata in res.values_mut() {
+        if object_with_metadata.is_activation_due() {
+            sqlx::query(
+                SQLITE_QUERIES
+                    .get("update-object-with-state")
+                    .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+            )
+            .bind(StateEnumeration::Active.to_string())
+            .bind(&object_with_metadata.id)
+            .execute(executor)
+            .await?;
+            object_with_metadata.state = StateEnumeration::Active;
+            trace!("Activated in DB: {}", object_with_metadata.id);
+        }
+    }
     Ok(res)
 }
 
@@ -491,18 +731,18 @@ pub(crate) async fn update_object_(
     .execute(&mut **executor)
     .await?;
 
-    // delete the existing tags
-    sqlx::query(
-        SQLITE_QUERIES
-            .get("delete-tags")
-            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
-    )
-    .bind(uid)
-    .execute(&mut **executor)
-    .await?;
-
-    // Insert the new tags if any
+    // Insert the new tags if present
     if let Some(tags) = tags {
+        // delete the existing tags
+        sqlx::query(
+            SQLITE_QUERIES
+                .get("delete-tags")
+                .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+        )
+        .bind(uid)
+        .execute(&mut **executor)
+        .await?;
+        // insert the new ones
         for tag in tags {
             sqlx::query(
                 SQLITE_QUERIES
@@ -568,6 +808,213 @@ pub(crate) async fn delete_(
     Ok(())
 }
 
+pub(crate) async fn archive_(uid: &str, executor: &mut Transaction<'_, Sqlite>) -> KResult<()> {
+    // copy the object to the archive
+    let archived = sqlx::query(
+        SQLITE_QUERIES
+            .get("archive-object")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(uid)
+    .execute(&mut **executor)
+    .await?
+    .rows_affected();
+    if archived == 0 {
+        kms_bail!(KmsError::ItemNotFound(uid.to_owned()))
+    }
+
+    // remove it from the objects; tags and access rights are kept
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("delete-archived-object-from-objects")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(uid)
+    .execute(&mut **executor)
+    .await?;
+
+    trace!("Archived in DB: {uid}");
+    Ok(())
+}
+
+pub(crate) async fn recover_(uid: &str, executor: &mut Transaction<'_, Sqlite>) -> KResult<()> {
+    // copy the object back from the archive
+    let recovered = sqlx::query(
+        SQLITE_QUERIES
+            .get("recover-object")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(uid)
+    .execute(&mut **executor)
+    .await?
+    .rows_affected();
+    if recovered == 0 {
+        kms_bail!(KmsError::ItemNotFound(uid.to_owned()))
+    }
+
+    // remove it from the archive
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("delete-recovered-object-from-archive")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(uid)
+    .execute(&mut **executor)
+    .await?;
+
+    trace!("Recovered in DB: {uid}");
+    Ok(())
+}
+
+pub(crate) async fn retrieve_archived_owner_<'e, E>(
+    uid: &str,
+    executor: E,
+) -> KResult<Option<String>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let row: Option<SqliteRow> = sqlx::query(
+        SQLITE_QUERIES
+            .get("select-archived-object-owner")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(uid)
+    .fetch_optional(executor)
+    .await?;
+    Ok(row.map(|row| row.get::<String, _>(0)))
+}
+
+pub(crate) async fn create_job_<'e, E>(job: &Job, executor: E) -> KResult<()>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let job_json = serde_json::to_value(job)
+        .context("failed serializing the job to JSON")
+        .reason(ErrorReason::Internal_Server_Error)?;
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("insert-job")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(&job.id)
+    .bind(&job.owner)
+    .bind(job.state.to_string())
+    .bind(job.lease_expiry)
+    .bind(job.updated_at)
+    .bind(job_json)
+    .execute(executor)
+    .await?;
+    trace!("Created job in DB: {}", job.id);
+    Ok(())
+}
+
+pub(crate) async fn retrieve_job_<'e, E>(id: &str, owner: &str, executor: E) -> KResult<Option<Job>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let row: Option<SqliteRow> = sqlx::query(
+        SQLITE_QUERIES
+            .get("select-job")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(id)
+    .bind(owner)
+    .fetch_optional(executor)
+    .await?;
+    row.map(|row| {
+        serde_json::from_value(row.get::<Value, _>(0))
+            .context("failed deserializing the job")
+            .reason(ErrorReason::Internal_Server_Error)
+    })
+    .transpose()
+}
+
+pub(crate) async fn update_pending_job_<'e, E>(job: &Job, executor: E) -> KResult<bool>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let job_json = serde_json::to_value(job)
+        .context("failed serializing the job to JSON")
+        .reason(ErrorReason::Internal_Server_Error)?;
+    let updated = sqlx::query(
+        SQLITE_QUERIES
+            .get("update-pending-job")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(job.state.to_string())
+    .bind(job.lease_expiry)
+    .bind(job.updated_at)
+    .bind(job_json)
+    .bind(&job.id)
+    .execute(executor)
+    .await?
+    .rows_affected();
+    trace!("Updated job in DB: {} ({})", job.id, job.state);
+    Ok(updated == 1)
+}
+
+pub(crate) async fn list_interrupted_jobs_<'e, E>(now: i64, executor: E) -> KResult<Vec<Job>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let rows: Vec<SqliteRow> = sqlx::query(
+        SQLITE_QUERIES
+            .get("select-interrupted-jobs")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(now)
+    .fetch_all(executor)
+    .await?;
+    rows.into_iter()
+        .map(|row| {
+            serde_json::from_value::<Job>(row.get::<Value, _>(0))
+                .context("failed deserializing the job")
+                .reason(ErrorReason::Internal_Server_Error)
+        })
+        .collect()
+}
+
+pub(crate) async fn lease_interrupted_job_<'e, E>(job: &Job, now: i64, executor: E) -> KResult<bool>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let job_json = serde_json::to_value(job)
+        .context("failed serializing the job to JSON")
+        .reason(ErrorReason::Internal_Server_Error)?;
+    let leased = sqlx::query(
+        SQLITE_QUERIES
+            .get("lease-interrupted-job")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(job.lease_expiry)
+    .bind(job.updated_at)
+    .bind(job_json)
+    .bind(&job.id)
+    .bind(now)
+    .execute(executor)
+    .await?
+    .rows_affected();
+    trace!("Leased interrupted job in DB: {}", job.id);
+    Ok(leased == 1)
+}
+
+pub(crate) async fn purge_jobs_<'e, E>(before: i64, executor: E) -> KResult<()>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let purged = sqlx::query(
+        SQLITE_QUERIES
+            .get("delete-expired-jobs")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(before)
+    .execute(executor)
+    .await?
+    .rows_affected();
+    trace!("Purged {purged} jobs from DB");
+    Ok(())
+}
+
 pub(crate) async fn upsert_(
     uid: &str,
     owner: &str,
@@ -648,11 +1095,15 @@ where
     .await?;
     let mut ids: HashMap<String, HashSet<ObjectOperationType>> = HashMap::with_capacity(list.len());
     for row in list {
-        ids.insert(
+        // a user may have been granted operation types and roles
+        ids.entry(
             // userid
             row.get::<String, _>(0),
+        )
+        .or_default()
+        .extend(
             // permissions
-            serde_json::from_value(row.get::<Value, _>(1))?,
+            serde_json::from_value::<HashSet<ObjectOperationType>>(row.get::<Value, _>(1))?,
         );
     }
     debug!("Listed {} rows", ids.len());
@@ -660,32 +1111,39 @@ where
 }
 
 pub(crate) async fn list_user_granted_access_rights_<'e, E>(
-    user: &str,
+    principals: &[String],
     executor: E,
 ) -> KResult<HashMap<String, (String, StateEnumeration, HashSet<ObjectOperationType>)>>
 where
     E: Executor<'e, Database = Sqlite> + Copy,
 {
-    debug!("Owner = {}", user);
-    let list = sqlx::query(
-        SQLITE_QUERIES
-            .get("select-objects-access-obtained")
-            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
-    )
-    .bind(user)
-    .fetch_all(executor)
-    .await?;
+    debug!("Principals = {principals:?}");
+    let raw_sql = SQLITE_QUERIES
+        .get("select-objects-access-obtained")
+        .context("SQL query can't be found")?
+        .replace(
+            "@PRINCIPALS",
+            &placeholders::<SqlitePlaceholder>(1, principals.len()),
+        );
+    let mut query = sqlx::query::<Sqlite>(&raw_sql);
+    for principal in principals {
+        query = query.bind(principal);
+    }
+    let list = query.fetch_all(executor).await?;
     let mut ids: HashMap<String, (String, StateEnumeration, HashSet<ObjectOperationType>)> =
         HashMap::with_capacity(list.len());
     for row in list {
-        ids.insert(
-            row.get::<String, _>(0),
-            (
+        // the access rights granted to several principals or through roles are merged
+        ids.entry(row.get::<String, _>(0))
+            .or_insert((
                 row.get::<String, _>(1),
                 state_from_string(&row.get::<String, _>(2))?,
-                serde_json::from_slice(&row.get::<Vec<u8>, _>(3))?,
-            ),
-        );
+                HashSet::new(),
+            ))
+            .2
+            .extend(serde_json::from_slice::<HashSet<ObjectOperationType>>(
+                &row.get::<Vec<u8>, _>(3),
+            )?);
     }
     debug!("Listed {} rows", ids.len());
     Ok(ids)
@@ -693,28 +1151,49 @@ where
 
 pub(crate) async fn list_user_access_rights_on_object_<'e, E>(
     uid: &str,
-    userid: &str,
+    principals: &[String],
     no_inherited_access: bool,
     executor: E,
 ) -> KResult<HashSet<ObjectOperationType>>
 where
     E: Executor<'e, Database = Sqlite> + Copy,
 {
-    let mut user_perms = perms(uid, userid, executor).await?;
-    if no_inherited_access || userid == "*" {
-        return Ok(user_perms)
+    let mut principals = principals.iter().map(String::as_str).collect::<Vec<_>>();
+    if !no_inherited_access {
+        principals.push("*");
+    }
+    let raw_sql = SQLITE_QUERIES
+        .get("select-user-accesses-for-object")
+        .context("SQL query can't be found")?
+        .replace(
+            "@PRINCIPALS",
+            &placeholders::<SqlitePlaceholder>(2, principals.len()),
+        );
+    let mut query = sqlx::query::<Sqlite>(&raw_sql).bind(uid);
+    for principal in &principals {
+        query = query.bind(*principal);
+    }
+    let mut user_perms = HashSet::new();
+    for row in query.fetch_all(executor).await? {
+        let perms_raw = row.get::<Vec<u8>, _>(0);
+        user_perms.extend(
+            serde_json::from_slice::<HashSet<ObjectOperationType>>(&perms_raw)
+                .context("failed deserializing the permissions")
+                .reason(ErrorReason::Internal_Server_Error)?,
+        );
     }
-    user_perms.extend(perms(uid, "*", executor).await?);
     Ok(user_perms)
 }
 
+/// The operation types granted to `userid` on the object itself,
+/// neither inherited nor through a role
 async fn perms<'e, E>(uid: &str, userid: &str, executor: E) -> KResult<HashSet<ObjectOperationType>>
 where
     E: Executor<'e, Database = Sqlite> + Copy,
 {
     let row: Option<SqliteRow> = sqlx::query(
         SQLITE_QUERIES
-            .get("select-user-accesses-for-object")
+            .get("select-row-read_access")
             .ok_or_else(|| kms_error!("SQL query can't be found"))?,
     )
     .bind(uid)
@@ -741,7 +1220,7 @@ where
 {
     debug!("insert_access_ {:?}", operation_types);
     // Retrieve existing permissions if any
-    let mut perms = list_user_access_rights_on_object_(uid, userid, false, executor).await?;
+    let mut perms = perms(uid, userid, executor).await?;
     if operation_types.is_subset(&perms) {
         // permissions are already setup
         return Ok(())
@@ -778,7 +1257,7 @@ where
     E: Executor<'e, Database = Sqlite> + Copy,
 {
     // Retrieve existing permissions if any
-    let perms = list_user_access_rights_on_object_(uid, userid, true, executor)
+    let perms = perms(uid, userid, executor)
         .await?
         .difference(&operation_types)
         .copied()
@@ -818,6 +1297,52 @@ where
     Ok(())
 }
 
+pub(crate) async fn grant_role_<'e, E>(
+    uid: &str,
+    userid: &str,
+    role: &str,
+    executor: E,
+) -> KResult<()>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("insert-role_access")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(uid)
+    .bind(userid)
+    .bind(role)
+    .execute(executor)
+    .await?;
+    trace!("Insert role access right in DB: {uid} / {userid} / {role}");
+    Ok(())
+}
+
+pub(crate) async fn remove_role_<'e, E>(
+    uid: &str,
+    userid: &str,
+    role: &str,
+    executor: E,
+) -> KResult<()>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("delete-role_access")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(uid)
+    .bind(userid)
+    .bind(role)
+    .execute(executor)
+    .await?;
+    trace!("Deleted role access right in DB: {uid} / {userid} / {role}");
+    Ok(())
+}
+
 pub(crate) async fn is_object_owned_by_<'e, E>(uid: &str, owner: &str, executor: E) -> KResult<bool>
 where
     E: Executor<'e, Database = Sqlite> + Copy,
@@ -838,20 +1363,25 @@ pub(crate) async fn find_<'e, E>(
     researched_attributes: Option<&Attributes>,
     state: Option<StateEnumeration>,
     user: &str,
+    principals: &[String],
     user_must_be_owner: bool,
     executor: E,
 ) -> KResult<Vec<(String, StateEnumeration, Attributes, IsWrapped)>>
 where
     E: Executor<'e, Database = Sqlite> + Copy,
 {
-    let query = query_from_attributes::<SqlitePlaceholder>(
+    let (query, values) = query_from_attributes::<SqlitePlaceholder>(
         researched_attributes,
         state,
         user,
+        principals,
         user_must_be_owner,
     )?;
-    trace!("find_: {query:?}");
-    let query = sqlx::query(&query);
+    trace!("find_: {query:?}, values: {values:?}");
+    let mut query = sqlx::query(&query);
+    for value in values {
+        query = query.bind(value);
+    }
     let rows = query.fetch_all(executor).await?;
 
     to_qualified_uids(&rows)
@@ -882,6 +1412,161 @@ fn to_qualified_uids(
     Ok(uids)
 }
 
+pub(crate) async fn set_role_<'e, E>(
+    name: &str,
+    operation_types: &HashSet<ObjectOperationType>,
+    executor: E,
+) -> KResult<()>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let json = serde_json::to_value(operation_types)
+        .context("failed serializing the permissions to JSON")
+        .reason(ErrorReason::Internal_Server_Error)?;
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("upsert-role")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(name)
+    .bind(json)
+    .execute(executor)
+    .await?;
+    trace!("Set role in DB: {name}");
+    Ok(())
+}
+
+pub(crate) async fn delete_role_(
+    name: &str,
+    executor: &mut Transaction<'_, Sqlite>,
+) -> KResult<()> {
+    // the grants of the role are revoked with it
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("delete-role_access-of-role")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(name)
+    .execute(&mut **executor)
+    .await?;
+    let deleted = sqlx::query(
+        SQLITE_QUERIES
+            .get("delete-role")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(name)
+    .execute(&mut **executor)
+    .await?
+    .rows_affected();
+    if deleted == 0 {
+        kms_bail!(KmsError::ItemNotFound(format!("role {name}")))
+    }
+    trace!("Deleted role in DB: {name}");
+    Ok(())
+}
+
+pub(crate) async fn list_roles_<'e, E>(
+    executor: E,
+) -> KResult<HashMap<String, HashSet<ObjectOperationType>>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let list = sqlx::query(
+        SQLITE_QUERIES
+            .get("select-roles")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .fetch_all(executor)
+    .await?;
+    let mut roles = HashMap::with_capacity(list.len());
+    for row in list {
+        roles.insert(
+            // name
+            row.get::<String, _>(0),
+            // permissions
+            serde_json::from_value(row.get::<Value, _>(1))?,
+        );
+    }
+    Ok(roles)
+}
+
+pub(crate) async fn add_group_member_<'e, E>(group: &str, userid: &str, executor: E) -> KResult<()>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("insert-group_member")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(group)
+    .bind(userid)
+    .execute(executor)
+    .await?;
+    trace!("Added {userid} to group {group} in DB");
+    Ok(())
+}
+
+pub(crate) async fn remove_group_member_<'e, E>(
+    group: &str,
+    userid: &str,
+    executor: E,
+) -> KResult<()>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("delete-group_member")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(group)
+    .bind(userid)
+    .execute(executor)
+    .await?;
+    trace!("Removed {userid} from group {group} in DB");
+    Ok(())
+}
+
+pub(crate) async fn list_groups_<'e, E>(executor: E) -> KResult<HashMap<String, HashSet<String>>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let list = sqlx::query(
+        SQLITE_QUERIES
+            .get("select-group_members")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .fetch_all(executor)
+    .await?;
+    let mut groups: HashMap<String, HashSet<String>> = HashMap::new();
+    for row in list {
+        groups
+            .entry(row.get::<String, _>(0))
+            .or_default()
+            .insert(row.get::<String, _>(1));
+    }
+    Ok(groups)
+}
+
+pub(crate) async fn list_user_groups_<'e, E>(userid: &str, executor: E) -> KResult<HashSet<String>>
+where
+    E: Executor<'e, Database = Sqlite> + Copy,
+{
+    let list = sqlx::query(
+        SQLITE_QUERIES
+            .get("select-user-groups")
+            .ok_or_else(|| kms_error!("SQL query can't be found"))?,
+    )
+    .bind(userid)
+    .fetch_all(executor)
+    .await?;
+    Ok(list
+        .into_iter()
+        .map(|row| row.get::<String, _>(0))
+        .collect())
+}
+
 pub(crate) async fn clear_database_<'e, E>(executor: E) -> KResult<()>
 where
     E: Executor<'e, Database = Sqlite> + Copy,
@@ -902,6 +1587,14 @@ where
     )
     .execute(executor)
     .await?;
+    // Erase `role_access` table
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("clean-table-role_access")
+            .expect("SQL query can't be found"),
+    )
+    .execute(executor)
+    .await?;
     // Erase `tags` table
     sqlx::query(
         SQLITE_QUERIES
@@ -910,6 +1603,38 @@ where
     )
     .execute(executor)
     .await?;
+    // Erase `archived_objects` table
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("clean-table-archived_objects")
+            .expect("SQL query can't be found"),
+    )
+    .execute(executor)
+    .await?;
+    // Erase `jobs` table
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("clean-table-jobs")
+            .expect("SQL query can't be found"),
+    )
+    .execute(executor)
+    .await?;
+    // Erase `roles` table
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("clean-table-roles")
+            .expect("SQL query can't be found"),
+    )
+    .execute(executor)
+    .await?;
+    // Erase `group_members` table
+    sqlx::query(
+        SQLITE_QUERIES
+            .get("clean-table-group_members")
+            .expect("SQL query can't be found"),
+    )
+    .execute(executor)
+    .await?;
     Ok(())
 }
 
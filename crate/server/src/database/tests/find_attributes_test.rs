@@ -9,8 +9,8 @@ use cosmian_kmip::{
     kmip::{
         kmip_objects::ObjectType,
         kmip_types::{
-            Attributes, CryptographicAlgorithm, Link, LinkType, LinkedObjectIdentifier,
-            StateEnumeration,
+            Attributes, CertificateAttributes, CryptographicAlgorithm, Link, LinkType,
+            LinkedObjectIdentifier, Name, NameType, StateEnumeration,
         },
     },
 };
@@ -51,6 +51,17 @@ pub async fn find_attributes<DB: Database>(
 
     let attributes = symmetric_key.attributes_mut()?;
     attributes.link = Some(link.clone());
+    attributes.name = Some(vec![Name {
+        name_value: "database key".to_string(),
+        name_type: NameType::UninterpretedTextString,
+    }]);
+    attributes.object_group = Some("databases".to_string());
+    attributes.sensitive = Some(true);
+    attributes.description = Some(r"the O'Reilly \' key".to_string());
+    attributes.certificate_attributes = Some(Box::new(CertificateAttributes {
+        certificate_subject_serial_number: "0A1B2C".to_string(),
+        ..CertificateAttributes::default()
+    }));
 
     let uid_ = db
         .create(
@@ -122,5 +133,90 @@ pub async fn find_attributes<DB: Database>(
         .await?;
     assert_eq!(found.len(), 0);
 
+    // Search by name, object group and sensitivity
+    let researched_attributes = Some(Attributes {
+        object_type: Some(ObjectType::SymmetricKey),
+        name: Some(vec![Name {
+            name_value: "database key".to_string(),
+            name_type: NameType::UninterpretedTextString,
+        }]),
+        object_group: Some("databases".to_string()),
+        sensitive: Some(true),
+        ..Attributes::default()
+    });
+    let found = db
+        .find(
+            researched_attributes.as_ref(),
+            Some(StateEnumeration::Active),
+            owner,
+            true,
+            db_params,
+        )
+        .await?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].0, uid);
+
+    // A different object group does not match
+    let researched_attributes = Some(Attributes {
+        object_type: Some(ObjectType::SymmetricKey),
+        object_group: Some("web servers".to_string()),
+        ..Attributes::default()
+    });
+    let found = db
+        .find(
+            researched_attributes.as_ref(),
+            Some(StateEnumeration::Active),
+            owner,
+            true,
+            db_params,
+        )
+        .await?;
+    assert_eq!(found.len(), 0);
+
+    // The values are bound, quotes and backslashes included
+    for (description, expected) in [
+        (r"the O'Reilly \' key", 1),
+        (r"x' OR 'a'='a", 0),
+        (r"x\' OR 'a'='a", 0),
+    ] {
+        let researched_attributes = Some(Attributes {
+            object_type: Some(ObjectType::SymmetricKey),
+            description: Some(description.to_string()),
+            ..Attributes::default()
+        });
+        let found = db
+            .find(
+                researched_attributes.as_ref(),
+                Some(StateEnumeration::Active),
+                owner,
+                true,
+                db_params,
+            )
+            .await?;
+        assert_eq!(found.len(), expected);
+    }
+
+    // Search by serial number
+    for (serial_number, expected) in [("0A1B2C", 1), ("0A1B2D", 0)] {
+        let researched_attributes = Some(Attributes {
+            object_type: Some(ObjectType::SymmetricKey),
+            certificate_attributes: Some(Box::new(CertificateAttributes {
+                certificate_subject_serial_number: serial_number.to_string(),
+                ..CertificateAttributes::default()
+            })),
+            ..Attributes::default()
+        });
+        let found = db
+            .find(
+                researched_attributes.as_ref(),
+                Some(StateEnumeration::Active),
+                owner,
+                true,
+                db_params,
+            )
+            .await?;
+        assert_eq!(found.len(), expected);
+    }
+
     Ok(())
 }
@@ -0,0 +1,289 @@
+use std::collections::HashSet;
+
+use cloudproof::reexport::crypto_core::{
+    reexport::rand_core::{RngCore, SeedableRng},
+    CsRng,
+};
+use cosmian_kmip::{
+    crypto::symmetric::create_symmetric_key_kmip_object,
+    kmip::kmip_types::{CryptographicAlgorithm, StateEnumeration},
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use uuid::Uuid;
+
+use crate::{
+    core::extra_database_params::ExtraDatabaseParams, database::Database, error::KmsError,
+    middlewares::ClaimedGroups, result::KResult,
+};
+
+/// Create a symmetric key owned by `owner` and return its uid
+async fn create_key<DB: Database>(
+    db: &DB,
+    owner: &str,
+    db_params: Option<&ExtraDatabaseParams>,
+) -> KResult<String> {
+    let mut rng = CsRng::from_entropy();
+    let mut symmetric_key_bytes = vec![0; 32];
+    rng.fill_bytes(&mut symmetric_key_bytes);
+    let symmetric_key =
+        create_symmetric_key_kmip_object(&symmetric_key_bytes, CryptographicAlgorithm::AES);
+    let uid = Uuid::new_v4().to_string();
+    db.upsert(
+        &uid,
+        owner,
+        &symmetric_key,
+        symmetric_key.attributes()?,
+        Some(&HashSet::new()),
+        StateEnumeration::Active,
+        db_params,
+    )
+    .await?;
+    Ok(uid)
+}
+
+pub async fn groups_and_roles<DB: Database>(
+    db_and_params: &(DB, Option<ExtraDatabaseParams>),
+) -> KResult<()> {
+    roles(db_and_params).await?;
+    groups(db_and_params).await?;
+    Ok(())
+}
+
+async fn roles<DB: Database>(db_and_params: &(DB, Option<ExtraDatabaseParams>)) -> KResult<()> {
+    let db = &db_and_params.0;
+    let db_params = db_and_params.1.as_ref();
+
+    let role = Uuid::new_v4().to_string();
+    db.set_role(
+        &role,
+        &HashSet::from([ObjectOperationType::Encrypt, ObjectOperationType::Decrypt]),
+        db_params,
+    )
+    .await?;
+    assert_eq!(
+        db.list_roles(db_params).await?.get(&role),
+        Some(&HashSet::from([
+            ObjectOperationType::Encrypt,
+            ObjectOperationType::Decrypt
+        ]))
+    );
+
+    // setting the role again replaces its operations
+    db.set_role(&role, &HashSet::from([ObjectOperationType::Get]), db_params)
+        .await?;
+    assert_eq!(
+        db.list_roles(db_params).await?.get(&role),
+        Some(&HashSet::from([ObjectOperationType::Get]))
+    );
+
+    // a granted role is resolved when the access rights are checked
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let user = format!("{}@example.org", Uuid::new_v4());
+    let uid = create_key(db, owner, db_params).await?;
+    db.grant_role(&uid, &user, &role, db_params).await?;
+    // granting twice is a no-op
+    db.grant_role(&uid, &user, &role, db_params).await?;
+    assert_eq!(
+        db.list_user_access_rights_on_object(&uid, &user, false, db_params)
+            .await?,
+        HashSet::from([ObjectOperationType::Get])
+    );
+    assert_eq!(
+        db.list_object_accesses_granted(&uid, db_params)
+            .await?
+            .get(&user),
+        Some(&HashSet::from([ObjectOperationType::Get]))
+    );
+
+    // editing the role changes the access rights of its grantees
+    db.set_role(
+        &role,
+        &HashSet::from([ObjectOperationType::Encrypt]),
+        db_params,
+    )
+    .await?;
+    assert!(
+        db.retrieve(&uid, &user, ObjectOperationType::Get, db_params)
+            .await?
+            .is_empty()
+    );
+    let objects = db
+        .retrieve(&uid, &user, ObjectOperationType::Encrypt, db_params)
+        .await?;
+    assert_eq!(
+        objects.get(&uid).map(|owm| owm.permissions.clone()),
+        Some(vec![ObjectOperationType::Encrypt])
+    );
+    assert_eq!(
+        db.list_user_granted_access_rights(&user, db_params)
+            .await?
+            .get(&uid)
+            .map(|(_, _, operations)| operations.clone()),
+        Some(HashSet::from([ObjectOperationType::Encrypt]))
+    );
+
+    // revoking the role leaves the operations granted directly
+    db.grant_access(
+        &uid,
+        &user,
+        HashSet::from([ObjectOperationType::Encrypt]),
+        db_params,
+    )
+    .await?;
+    db.remove_role(&uid, &user, &role, db_params).await?;
+    assert_eq!(
+        db.list_user_access_rights_on_object(&uid, &user, false, db_params)
+            .await?,
+        HashSet::from([ObjectOperationType::Encrypt])
+    );
+    db.remove_access(
+        &uid,
+        &user,
+        HashSet::from([ObjectOperationType::Encrypt]),
+        db_params,
+    )
+    .await?;
+
+    // deleting the role revokes its grants
+    db.grant_role(&uid, &user, &role, db_params).await?;
+    db.delete_role(&role, db_params).await?;
+    assert!(!db.list_roles(db_params).await?.contains_key(&role));
+    db.set_role(
+        &role,
+        &HashSet::from([ObjectOperationType::Encrypt]),
+        db_params,
+    )
+    .await?;
+    assert!(
+        db.list_user_access_rights_on_object(&uid, &user, false, db_params)
+            .await?
+            .is_empty()
+    );
+    db.delete_role(&role, db_params).await?;
+    // an unknown role cannot be deleted
+    assert!(db.delete_role(&role, db_params).await.is_err());
+
+    Ok(())
+}
+
+async fn groups<DB: Database>(db_and_params: &(DB, Option<ExtraDatabaseParams>)) -> KResult<()> {
+    let db = &db_and_params.0;
+    let db_params = db_and_params.1.as_ref();
+
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let user = format!("{}@example.org", Uuid::new_v4());
+    let local_group = Uuid::new_v4().to_string();
+    let claimed_group = Uuid::new_v4().to_string();
+    let uid = create_key(db, owner, db_params).await?;
+
+    // grant the groups, not the user
+    db.grant_access(
+        &uid,
+        &format!("group:{local_group}"),
+        HashSet::from([ObjectOperationType::Get]),
+        db_params,
+    )
+    .await?;
+    db.grant_access(
+        &uid,
+        &format!("group:{claimed_group}"),
+        HashSet::from([ObjectOperationType::Encrypt]),
+        db_params,
+    )
+    .await?;
+    assert!(
+        db.retrieve(&uid, &user, ObjectOperationType::Get, db_params)
+            .await?
+            .is_empty()
+    );
+
+    // the local members of a group obtain its access rights
+    db.add_group_member(&local_group, &user, db_params).await?;
+    // adding twice is a no-op
+    db.add_group_member(&local_group, &user, db_params).await?;
+    assert_eq!(
+        db.list_groups(db_params).await?.get(&local_group),
+        Some(&HashSet::from([user.clone()]))
+    );
+    let objects = db
+        .retrieve(&uid, &user, ObjectOperationType::Get, db_params)
+        .await?;
+    assert_eq!(
+        objects.get(&uid).map(|owm| owm.permissions.clone()),
+        Some(vec![ObjectOperationType::Get])
+    );
+    assert_eq!(
+        db.list_user_access_rights_on_object(&uid, &user, false, db_params)
+            .await?,
+        HashSet::from([ObjectOperationType::Get])
+    );
+    // but not through `no_inherited_access`
+    assert!(
+        db.list_user_access_rights_on_object(&uid, &user, true, db_params)
+            .await?
+            .is_empty()
+    );
+
+    // and so do the members claimed by the identity provider, for the request only
+    let claimed_groups = ClaimedGroups::new(user.clone(), vec![claimed_group.clone()]);
+    claimed_groups
+        .scope(async {
+            let objects = db
+                .retrieve(&uid, &user, ObjectOperationType::Encrypt, db_params)
+                .await?;
+            assert_eq!(
+                objects.get(&uid).map(|owm| owm.permissions.clone()),
+                Some(vec![ObjectOperationType::Encrypt])
+            );
+            let obtained = db.list_user_granted_access_rights(&user, db_params).await?;
+            assert_eq!(
+                obtained
+                    .get(&uid)
+                    .map(|(_, _, operations)| operations.clone()),
+                Some(HashSet::from([
+                    ObjectOperationType::Get,
+                    ObjectOperationType::Encrypt
+                ]))
+            );
+            // the groups claimed for another user do not apply
+            assert!(
+                db.retrieve(
+                    &uid,
+                    "someone.else@example.org",
+                    ObjectOperationType::Encrypt,
+                    db_params
+                )
+                .await?
+                .is_empty()
+            );
+            Ok::<_, KmsError>(())
+        })
+        .await?;
+    // the claimed groups are never stored
+    assert_eq!(
+        db.list_user_groups(&user, db_params).await?,
+        HashSet::from([local_group.clone()])
+    );
+    assert!(
+        !db.list_groups(db_params)
+            .await?
+            .contains_key(&claimed_group)
+    );
+    assert!(
+        db.retrieve(&uid, &user, ObjectOperationType::Encrypt, db_params)
+            .await?
+            .is_empty()
+    );
+
+    // a removed member loses the access rights of the group
+    db.remove_group_member(&local_group, &user, db_params)
+        .await?;
+    assert!(db.list_user_groups(&user, db_params).await?.is_empty());
+    assert!(
+        db.retrieve(&uid, &user, ObjectOperationType::Get, db_params)
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
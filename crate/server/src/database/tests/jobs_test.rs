@@ -0,0 +1,81 @@
+use cosmian_kmip::kmip::{
+    kmip_types::OperationEnumeration,
+    ttlv::{TTLValue, TTLV},
+};
+use uuid::Uuid;
+
+use crate::{
+    core::extra_database_params::ExtraDatabaseParams,
+    database::{
+        job::{Job, JobState},
+        Database,
+    },
+    result::KResult,
+};
+
+pub async fn jobs<DB: Database>(db_and_params: &(DB, Option<ExtraDatabaseParams>)) -> KResult<()> {
+    let db = &db_and_params.0;
+    let db_params = db_and_params.1.as_ref();
+
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let now = chrono::Utc::now().timestamp();
+    let mut job = Job {
+        id: Uuid::new_v4().to_string(),
+        owner: owner.to_owned(),
+        operation: OperationEnumeration::Query,
+        state: JobState::Pending,
+        request: TTLV {
+            tag: "Query".to_owned(),
+            value: TTLValue::Structure(vec![]),
+        },
+        response: None,
+        lease_expiry: now + 60,
+        updated_at: now,
+    };
+    db.create_job(&job, db_params).await?;
+
+    // only the owner can retrieve the job
+    let retrieved = db
+        .retrieve_job(&job.id, owner, db_params)
+        .await?
+        .expect("the job should exist");
+    assert_eq!(retrieved.state, JobState::Pending);
+    assert_eq!(retrieved.operation, OperationEnumeration::Query);
+    assert_eq!(retrieved.request, job.request);
+    assert!(
+        db.retrieve_job(&job.id, "another user", db_params)
+            .await?
+            .is_none()
+    );
+
+    // a pending job can be updated once
+    job.state = JobState::Canceled;
+    assert!(db.update_pending_job(&job, db_params).await?);
+    job.state = JobState::Completed;
+    assert!(!db.update_pending_job(&job, db_params).await?);
+    let retrieved = db
+        .retrieve_job(&job.id, owner, db_params)
+        .await?
+        .expect("the job should exist");
+    assert_eq!(retrieved.state, JobState::Canceled);
+
+    // the jobs are kept during their retention period
+    db.purge_jobs(now, db_params).await?;
+    assert!(db.retrieve_job(&job.id, owner, db_params).await?.is_some());
+
+    // a pending job is kept until its lease expires
+    let pending = Job {
+        id: Uuid::new_v4().to_string(),
+        state: JobState::Pending,
+        ..job.clone()
+    };
+    db.create_job(&pending, db_params).await?;
+    db.purge_jobs(now + 1, db_params).await?;
+    assert!(
+        db.retrieve_job(&pending.id, owner, db_params)
+            .await?
+            .is_some()
+    );
+
+    Ok(())
+}
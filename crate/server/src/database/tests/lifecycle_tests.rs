@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use cloudproof::reexport::crypto_core::{
+    reexport::rand_core::{RngCore, SeedableRng},
+    CsRng,
+};
+use cosmian_kmip::{
+    crypto::symmetric::create_symmetric_key_kmip_object,
+    kmip::kmip_types::{CryptographicAlgorithm, StateEnumeration},
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use uuid::Uuid;
+
+use crate::{
+    core::extra_database_params::ExtraDatabaseParams, database::Database, result::KResult,
+};
+
+pub async fn archive_and_recover<DB: Database>(
+    db_and_params: &(DB, Option<ExtraDatabaseParams>),
+) -> KResult<()> {
+    let db = &db_and_params.0;
+    let db_params = db_and_params.1.as_ref();
+
+    let mut rng = CsRng::from_entropy();
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    let mut symmetric_key = vec![0; 32];
+    rng.fill_bytes(&mut symmetric_key);
+    let symmetric_key =
+        create_symmetric_key_kmip_object(symmetric_key.as_slice(), CryptographicAlgorithm::AES);
+    let uid = Uuid::new_v4().to_string();
+    let tag = Uuid::new_v4().to_string();
+    db.create(
+        Some(uid.clone()),
+        owner,
+        &symmetric_key,
+        symmetric_key.attributes()?,
+        &HashSet::from([tag.clone()]),
+        db_params,
+    )
+    .await?;
+
+    // archived objects are neither retrieved by id nor by tags
+    db.archive(&uid, db_params).await?;
+    assert!(
+        db.retrieve(&uid, owner, ObjectOperationType::Get, db_params)
+            .await?
+            .is_empty()
+    );
+    assert!(
+        db.retrieve(
+            &serde_json::to_string(&[&tag])?,
+            owner,
+            ObjectOperationType::Get,
+            db_params
+        )
+        .await?
+        .is_empty()
+    );
+    assert_eq!(
+        db.retrieve_archived_owner(&uid, db_params).await?,
+        Some(owner.to_owned())
+    );
+    assert!(db.archive(&uid, db_params).await.is_err());
+
+    // recovered objects are back, with their state and tags
+    db.recover(&uid, db_params).await?;
+    let objs = db
+        .retrieve(
+            &serde_json::to_string(&[&tag])?,
+            owner,
+            ObjectOperationType::Get,
+            db_params,
+        )
+        .await?;
+    assert_eq!(objs.len(), 1);
+    assert_eq!(objs[&uid].state, StateEnumeration::Active);
+    assert!(db.retrieve_archived_owner(&uid, db_params).await?.is_none());
+    assert!(db.recover(&uid, db_params).await.is_err());
+
+    Ok(())
+}
+
+pub async fn pre_active<DB: Database>(
+    db_and_params: &(DB, Option<ExtraDatabaseParams>),
+) -> KResult<()> {
+    let db = &db_and_params.0;
+    let db_params = db_and_params.1.as_ref();
+
+    let mut rng = CsRng::from_entropy();
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+
+    // an object with a future activation date is created pre-active
+    let mut symmetric_key = vec![0; 32];
+    rng.fill_bytes(&mut symmetric_key);
+    let mut symmetric_key =
+        create_symmetric_key_kmip_object(symmetric_key.as_slice(), CryptographicAlgorithm::AES);
+    symmetric_key.attributes_mut()?.activation_date = Some(now + 3_600_000);
+    let uid = Uuid::new_v4().to_string();
+    db.create(
+        Some(uid.clone()),
+        owner,
+        &symmetric_key,
+        symmetric_key.attributes()?,
+        &HashSet::new(),
+        db_params,
+    )
+    .await?;
+    let objs = db
+        .retrieve(&uid, owner, ObjectOperationType::Get, db_params)
+        .await?;
+    assert_eq!(objs[&uid].state, StateEnumeration::PreActive);
+
+    // once its activation date is reached, it is activated when retrieved
+    symmetric_key.attributes_mut()?.activation_date = Some(now - 1_000);
+    db.update_object(
+        &uid,
+        &symmetric_key,
+        symmetric_key.attributes()?,
+        None,
+        db_params,
+    )
+    .await?;
+    let objs = db
+        .retrieve(&uid, owner, ObjectOperationType::Get, db_params)
+        .await?;
+    assert_eq!(objs[&uid].state, StateEnumeration::Active);
+    let found = db
+        .find(None, Some(StateEnumeration::Active), owner, true, db_params)
+        .await?;
+    assert!(found.iter().any(|(id, ..)| id == &uid));
+
+    Ok(())
+}
@@ -6,7 +6,10 @@ use self::{
     additional_redis_findex_tests::{test_corner_case, test_objects_db, test_permissions_db},
     database_tests::{crud, tx_and_list, upsert},
     find_attributes_test::find_attributes,
+    groups_test::groups_and_roles,
+    jobs_test::jobs,
     json_access_test::json_access,
+    lifecycle_tests::{archive_and_recover, pre_active},
     owner_test::owner,
     permissions_test::permissions,
     tagging_tests::tags,
@@ -26,7 +29,10 @@ use crate::{
 mod additional_redis_findex_tests;
 mod database_tests;
 mod find_attributes_test;
+mod groups_test;
+mod jobs_test;
 mod json_access_test;
+mod lifecycle_tests;
 mod owner_test;
 mod permissions_test;
 mod tagging_tests;
@@ -112,6 +118,10 @@ pub async fn test_redis_with_findex() -> KResult<()> {
     atomic(&get_redis_with_findex().await?).await?;
     upsert(&get_redis_with_findex().await?).await?;
     crud(&get_redis_with_findex().await?).await?;
+    archive_and_recover(&get_redis_with_findex().await?).await?;
+    pre_active(&get_redis_with_findex().await?).await?;
+    jobs(&get_redis_with_findex().await?).await?;
+    groups_and_roles(&get_redis_with_findex().await?).await?;
     Ok(())
 }
 
@@ -126,6 +136,10 @@ pub async fn test_sql_cipher() -> KResult<()> {
     atomic(&get_sql_cipher().await?).await?;
     upsert(&get_sql_cipher().await?).await?;
     crud(&get_sql_cipher().await?).await?;
+    archive_and_recover(&get_sql_cipher().await?).await?;
+    pre_active(&get_sql_cipher().await?).await?;
+    jobs(&get_sql_cipher().await?).await?;
+    groups_and_roles(&get_sql_cipher().await?).await?;
     Ok(())
 }
 
@@ -140,6 +154,10 @@ pub async fn test_sqlite() -> KResult<()> {
     atomic(&get_sqlite().await?).await?;
     upsert(&get_sqlite().await?).await?;
     crud(&get_sqlite().await?).await?;
+    archive_and_recover(&get_sqlite().await?).await?;
+    pre_active(&get_sqlite().await?).await?;
+    jobs(&get_sqlite().await?).await?;
+    groups_and_roles(&get_sqlite().await?).await?;
     Ok(())
 }
 
@@ -154,6 +172,10 @@ pub async fn test_pgsql() -> KResult<()> {
     atomic(&get_pgsql().await?).await?;
     upsert(&get_pgsql().await?).await?;
     crud(&get_pgsql().await?).await?;
+    archive_and_recover(&get_pgsql().await?).await?;
+    pre_active(&get_pgsql().await?).await?;
+    jobs(&get_pgsql().await?).await?;
+    groups_and_roles(&get_pgsql().await?).await?;
     Ok(())
 }
 
@@ -168,5 +190,9 @@ pub async fn test_mysql() -> KResult<()> {
     owner(&get_mysql().await?).await?;
     permissions(&get_mysql().await?).await?;
     tags(&get_mysql().await?, true).await?;
+    archive_and_recover(&get_mysql().await?).await?;
+    pre_active(&get_mysql().await?).await?;
+    jobs(&get_mysql().await?).await?;
+    groups_and_roles(&get_mysql().await?).await?;
     Ok(())
 }
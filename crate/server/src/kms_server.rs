@@ -1,4 +1,8 @@
-use std::sync::{mpsc, Arc};
+use std::{
+    pin::Pin,
+    sync::{mpsc, Arc},
+    time::Duration,
+};
 
 use actix_cors::Cors;
 use actix_identity::IdentityMiddleware;
@@ -8,30 +12,66 @@ use actix_web::{
     web::{self, Data, JsonConfig, PayloadConfig},
     App, HttpServer,
 };
+use cosmian_kmip::kmip::{
+    kmip_messages::{MessageResponse, MessageResponseBatchItem, MessageResponseHeader},
+    kmip_operations::ErrorReason,
+    kmip_types::ResultStatusEnumeration,
+    ttlv::{
+        binary::{from_bytes, item_length, to_bytes, TTLV_HEADER_LENGTH},
+        serializer::to_ttlv,
+    },
+};
 use openssl::{
-    ssl::{SslAcceptor, SslAcceptorBuilder, SslMethod, SslVerifyMode},
-    x509::store::X509StoreBuilder,
+    pkcs12::ParsedPkcs12_2,
+    ssl::{Ssl, SslAcceptor, SslAcceptorBuilder, SslMethod, SslVerifyMode},
+    x509::{store::X509StoreBuilder, X509},
 };
-use tracing::info;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+    time::timeout,
+};
+use tokio_openssl::SslStream;
+use tracing::{debug, error, info, warn};
 
 use crate::{
     config::{self, JwtAuthConfig, ServerParams},
-    core::KMS,
+    core::{kms::check_user_identity, KMS},
     error::KmsError,
     kms_bail,
     middlewares::{
-        ssl_auth::{extract_peer_certificate, SslAuth},
+        ssl_auth::{extract_common_name, extract_peer_certificate, SslAuth},
         JwksManager, JwtAuth, JwtConfig,
     },
     result::{KResult, KResultHelper},
     routes::{
-        access, add_new_database, get_version,
+        access,
+        acme::{self, AcmeServer, NetworkValidator},
+        add_new_database, crl, get_version,
         google_cse::{self, GoogleCseConfig},
-        kmip, ms_dke,
+        kmip, ms_dke, ocsp,
     },
     KMSServer,
 };
 
+/// The maximum size of a binary TTLV message received by the KMIP socket server
+const MAX_SOCKET_MESSAGE_LENGTH: usize = 64 * 1024 * 1024;
+
+/// The maximum number of connections the KMIP socket server serves at once;
+/// the connections above this number are closed immediately
+const MAX_SOCKET_CONNECTIONS: usize = 1024;
+
+/// The delay for a KMIP socket client to complete the TLS handshake
+const SOCKET_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The delay after which a KMIP socket connection which does not send a new request is closed
+const SOCKET_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The delay for a KMIP socket client to send a whole request once it started it,
+/// and to read the whole response
+const SOCKET_READ_WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Starts the Key Management System (KMS) server based on the provided configuration.
 ///
 /// The server is started using one of three methods:
@@ -123,7 +163,48 @@ async fn start_https_kms_server(
         _ => kms_bail!("http/s: a PKCS#12 file must be provided"),
     };
 
-    // Create and configure an SSL acceptor with the certificate and key
+    let builder = create_openssl_acceptor(&server_params, p12)?;
+
+    // The KMIP socket server uses its own acceptor and listener
+    let socket_server = match server_params.socket_server_port {
+        Some(port) => {
+            let acceptor = create_openssl_acceptor(&server_params, p12)?.build();
+            let address = format!("{}:{}", server_params.hostname, port);
+            let listener = TcpListener::bind(&address).await?;
+            Some((listener, acceptor))
+        }
+        None => None,
+    };
+
+    // Instantiate and prepare the KMS server
+    let kms_server = Arc::new(KMSServer::instantiate(server_params).await?);
+    let server = prepare_kms_server(kms_server.clone(), Some(builder)).await?;
+
+    // send the server handle to the caller
+    if let Some(tx) = &server_handle_transmitter {
+        tx.send(server.handle())?;
+    }
+
+    if let Some((listener, acceptor)) = socket_server {
+        info!(
+            "Starting the KMIP socket server on {}...",
+            listener.local_addr()?
+        );
+        actix_web::rt::spawn(run_kmip_socket_server(kms_server, listener, acceptor));
+    }
+
+    info!("Starting the HTTPS KMS server...");
+
+    // Run the server and return the result
+    server.await.map_err(Into::into)
+}
+
+/// Create an SSL acceptor builder configured with the server certificate and key
+/// and, when client certificate authentication is enabled, the authority certificate
+fn create_openssl_acceptor(
+    server_params: &ServerParams,
+    p12: &ParsedPkcs12_2,
+) -> KResult<SslAcceptorBuilder> {
     let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls())?;
     if let Some(pkey) = &p12.pkey {
         builder.set_private_key(pkey)?;
@@ -144,20 +225,181 @@ async fn start_https_kms_server(
         store_builder.add_cert(verify_cert.clone())?;
         builder.set_verify_cert_store(store_builder.build())?;
     }
+    Ok(builder)
+}
 
-    // Instantiate and prepare the KMS server
-    let kms_server = Arc::new(KMSServer::instantiate(server_params).await?);
-    let server = prepare_kms_server(kms_server, Some(builder)).await?;
+/// Run the KMIP socket server: accept the TLS connections of KMIP clients
+/// on the listener and serve their binary TTLV requests
+///
+/// Each connection is handled in its own task; errors are logged
+/// and close the faulty connection only.
+/// At most `MAX_SOCKET_CONNECTIONS` connections are served at once,
+/// and the idle or slow connections are closed.
+async fn run_kmip_socket_server(kms: Arc<KMS>, listener: TcpListener, acceptor: SslAcceptor) {
+    let connections = Arc::new(Semaphore::new(MAX_SOCKET_CONNECTIONS));
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                error!("KMIP socket server: failed accepting a connection: {e}");
+                continue
+            }
+        };
+        let Ok(permit) = connections.clone().try_acquire_owned() else {
+            warn!("KMIP socket server: too many connections, closing the connection from {peer}");
+            continue
+        };
+        let kms = kms.clone();
+        let ssl = match Ssl::new(acceptor.context()) {
+            Ok(ssl) => ssl,
+            Err(e) => {
+                error!("KMIP socket server: failed creating a TLS session: {e}");
+                continue
+            }
+        };
+        actix_web::rt::spawn(async move {
+            if let Err(e) = handle_kmip_socket_connection(&kms, ssl, stream).await {
+                warn!("KMIP socket server: connection from {peer} closed: {e}");
+            }
+            drop(permit);
+        });
+    }
+}
 
-    // send the server handle to the caller
-    if let Some(tx) = &server_handle_transmitter {
-        tx.send(server.handle())?;
+/// Serve the binary TTLV requests of a KMIP client until it closes the connection
+///
+/// The client is authenticated using the common name of its TLS certificate,
+/// in the same way as HTTPS clients are
+async fn handle_kmip_socket_connection(kms: &Arc<KMS>, ssl: Ssl, stream: TcpStream) -> KResult<()> {
+    let mut stream = SslStream::new(ssl, stream)?;
+    timeout(SOCKET_HANDSHAKE_TIMEOUT, Pin::new(&mut stream).accept())
+        .await
+        .map_err(|_| KmsError::Unauthorized("TLS handshake timed out".to_owned()))?
+        .map_err(|e| KmsError::Unauthorized(format!("TLS handshake failed: {e}")))?;
+
+    let user = socket_user(kms, stream.ssl().peer_certificate().as_ref())?;
+    debug!("KMIP socket server: authenticated user: {user}");
+
+    while let Some(request) =
+        read_socket_request(&mut stream, SOCKET_IDLE_TIMEOUT, SOCKET_READ_WRITE_TIMEOUT).await?
+    {
+        let response = match handle_binary_ttlv(kms, &request, &user).await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("KMIP socket server: request failed: {e}");
+                binary_error_response(&e)?
+            }
+        };
+        timeout(SOCKET_READ_WRITE_TIMEOUT, async {
+            stream.write_all(&response).await?;
+            stream.flush().await
+        })
+        .await
+        .map_err(|_| {
+            KmsError::InvalidRequest(
+                "KMIP socket server: writing the response timed out".to_owned(),
+            )
+        })??;
     }
+    Ok(())
+}
 
-    info!("Starting the HTTPS KMS server...");
+/// The user authenticated by the client certificate of a KMIP socket connection
+pub(crate) fn socket_user(kms: &KMS, certificate: Option<&X509>) -> KResult<String> {
+    if kms.params.force_default_username {
+        return Ok(kms.params.default_username.clone())
+    }
+    let certificate =
+        certificate.ok_or_else(|| KmsError::Unauthorized("no client certificate".to_owned()))?;
+    let user = extract_common_name(certificate)?;
+    check_user_identity(&user)?;
+    Ok(user)
+}
 
-    // Run the server and return the result
-    server.await.map_err(Into::into)
+/// Read the next binary TTLV request of a KMIP socket client.
+/// Return `None` when the client closed the connection.
+///
+/// The request must start within `idle_timeout`
+/// and be received entirely within `read_timeout` once started.
+pub(crate) async fn read_socket_request<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    idle_timeout: Duration,
+    read_timeout: Duration,
+) -> KResult<Option<Vec<u8>>> {
+    // wait for the first byte of the request
+    let mut header = [0_u8; TTLV_HEADER_LENGTH];
+    match timeout(idle_timeout, stream.read(&mut header[..1])).await {
+        Err(_) => kms_bail!(KmsError::InvalidRequest(
+            "KMIP socket server: idle connection timed out".to_owned()
+        )),
+        // the client closed the connection
+        Ok(Ok(0)) => return Ok(None),
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => return Err(e.into()),
+    }
+    let request = timeout(read_timeout, async {
+        stream.read_exact(&mut header[1..]).await?;
+        let length = item_length(&header)?;
+        if length > MAX_SOCKET_MESSAGE_LENGTH {
+            kms_bail!(KmsError::InvalidRequest(format!(
+                "KMIP socket server: message of {length} bytes exceeds the maximum size of \
+                 {MAX_SOCKET_MESSAGE_LENGTH} bytes"
+            )))
+        }
+        let mut request = header.to_vec();
+        request.resize(length, 0);
+        stream
+            .read_exact(&mut request[TTLV_HEADER_LENGTH..])
+            .await?;
+        Ok::<_, KmsError>(request)
+    })
+    .await
+    .map_err(|_| {
+        KmsError::InvalidRequest("KMIP socket server: reading the request timed out".to_owned())
+    })??;
+    Ok(Some(request))
+}
+
+/// Process a binary TTLV request and return the binary TTLV response
+pub(crate) async fn handle_binary_ttlv(
+    kms: &Arc<KMS>,
+    request: &[u8],
+    user: &str,
+) -> KResult<Vec<u8>> {
+    let ttlv = from_bytes(request)?;
+    info!("KMIP socket. Request: {:?} {}", ttlv.tag.as_str(), user);
+    // the socket server does not support the encrypted SQLite database
+    // which requires the database secrets sent in HTTP headers
+    let ttlv = if ttlv.tag == "Message" {
+        kms.binary_message_ttlv(&ttlv, user, None).await?
+    } else {
+        kmip::handle_ttlv(kms, &ttlv, user, None).await?
+    };
+    Ok(to_bytes(&ttlv)?)
+}
+
+/// Build the binary TTLV of a failed `MessageResponse` for a request which could not be processed
+pub(crate) fn binary_error_response(error: &KmsError) -> KResult<Vec<u8>> {
+    let result_reason = match error {
+        KmsError::KmipError(reason, _) => *reason,
+        KmsError::ItemNotFound(_) => ErrorReason::Item_Not_Found,
+        KmsError::Unauthorized(_) => ErrorReason::Permission_Denied,
+        KmsError::InvalidRequest(_) => ErrorReason::Invalid_Message,
+        KmsError::NotSupported(_) => ErrorReason::Operation_Not_Supported,
+        _ => ErrorReason::General_Failure,
+    };
+    let response = MessageResponse {
+        header: MessageResponseHeader {
+            batch_count: 1,
+            ..Default::default()
+        },
+        items: vec![MessageResponseBatchItem {
+            result_reason: Some(result_reason),
+            result_message: Some(error.to_string()),
+            ..MessageResponseBatchItem::new(ResultStatusEnumeration::OperationFailed)
+        }],
+    };
+    Ok(to_bytes(&to_ttlv(&response)?)?)
 }
 
 /**
@@ -180,6 +422,9 @@ pub async fn prepare_kms_server(
     kms_server: Arc<KMS>,
     builder: Option<SslAcceptorBuilder>,
 ) -> KResult<actix_web::dev::Server> {
+    // resume the asynchronous operations interrupted by a previous stop of the server
+    kms_server.resume_interrupted_jobs().await?;
+
     // Check if this auth server is enabled for Google Client-Side Encryption
     let enable_google_cse = kms_server.params.google_cse_kacls_url.is_some();
 
@@ -252,6 +497,28 @@ pub async fn prepare_kms_server(
     // Should we enable the MS DKE Service ?
     let enable_ms_dke = kms_server.params.ms_dke_service_url.is_some();
 
+    // Should we serve the CRLs of the issuers held in the KMS ?
+    let enable_crl = kms_server.params.crl_distribution_url.is_some();
+
+    // Should we serve the OCSP responder ?
+    let enable_ocsp = kms_server.params.ocsp_responder_url.is_some();
+
+    // Should we serve the ACME server ?
+    // Its orders are kept in memory and shared by all the workers.
+    let acme_server = match (
+        &kms_server.params.acme_directory_url,
+        &kms_server.params.acme_issuer_certificate_id,
+    ) {
+        (Some(acme_directory_url), Some(acme_issuer_certificate_id)) => {
+            Some(Arc::new(AcmeServer::new(
+                acme_directory_url,
+                acme_issuer_certificate_id,
+                Arc::new(NetworkValidator::instantiate()?),
+            )))
+        }
+        _ => None,
+    };
+
     // Create the `HttpServer` instance.
     let server = HttpServer::new(move || {
         // Create an `App` instance and configure the passed data and the various scopes
@@ -286,10 +553,55 @@ pub async fn prepare_kms_server(
                 .wrap(Cors::permissive())
                 .service(ms_dke::version)
                 .service(ms_dke::get_key)
-                .service(ms_dke::decrypt);
+                .service(
+                    // Office clients authenticate to decrypt, not to get the public keys
+                    web::scope("")
+                        .wrap(Condition::new(
+                            use_jwt_auth,
+                            JwtAuth::new(jwt_configurations.clone()),
+                        ))
+                        .service(ms_dke::decrypt),
+                );
             app = app.service(ms_dke_scope);
         }
 
+        if enable_crl {
+            // The scope for the CRL distribution point served from /crl
+            let crl_scope = web::scope("/crl")
+                .wrap(Cors::permissive())
+                .service(crl::get_crl);
+            app = app.service(crl_scope);
+        }
+
+        if enable_ocsp {
+            // The scope for the OCSP responder served from /ocsp
+            let ocsp_scope = web::scope("/ocsp")
+                .wrap(Cors::permissive())
+                .service(ocsp::post_ocsp)
+                .service(ocsp::get_ocsp);
+            app = app.service(ocsp_scope);
+        }
+
+        if let Some(acme_server) = &acme_server {
+            // The scope for the ACME server served from /acme
+            let acme_scope = web::scope("/acme")
+                .app_data(Data::new(acme_server.clone()))
+                .wrap(Cors::permissive())
+                .service(acme::directory)
+                .service(acme::head_new_nonce)
+                .service(acme::get_new_nonce)
+                .service(acme::new_account)
+                .service(acme::account)
+                .service(acme::account_orders)
+                .service(acme::new_order)
+                .service(acme::order)
+                .service(acme::finalize)
+                .service(acme::authorization)
+                .service(acme::challenge)
+                .service(acme::certificate);
+            app = app.service(acme_scope);
+        }
+
         // The default scope serves from the root / the KMIP, permissions and tee endpoints
         let default_scope = web::scope("")
             .wrap(Condition::new(
@@ -308,6 +620,12 @@ pub async fn prepare_kms_server(
             .service(access::list_accesses)
             .service(access::grant_access)
             .service(access::revoke_access)
+            .service(access::list_roles)
+            .service(access::set_role)
+            .service(access::delete_role)
+            .service(access::list_groups)
+            .service(access::add_group_member)
+            .service(access::remove_group_member)
             .service(get_version);
 
         // The default scope is extended with the /new_database endpoint if the application is using an encrypted SQLite database.
@@ -130,6 +130,8 @@ mod tests {
                 https_p12_file: Some(PathBuf::from("[https p12 file]")),
                 https_p12_password: Some("[https p12 password]".to_string()),
                 authority_cert_file: Some(PathBuf::from("[authority cert file]")),
+                socket_server_start: false,
+                socket_server_port: 5696,
             },
             auth: JwtAuthConfig {
                 jwt_issuer_uri: Some(vec![
@@ -148,15 +150,38 @@ mod tests {
             },
             default_username: "[default username]".to_string(),
             force_default_username: false,
+            access_admins: Some(vec![
+                "[access admin 1]".to_string(),
+                "[access admin 2]".to_string(),
+            ]),
             google_cse_kacls_url: Some("[google cse kacls url]".to_string()),
+            google_cse_privileged_users: Some(vec![
+                "[google cse privileged user 1]".to_string(),
+                "[google cse privileged user 2]".to_string(),
+            ]),
+            google_cse_perimeters: Some(vec![
+                "[google cse perimeter 1]".to_string(),
+                "[google cse perimeter 2]".to_string(),
+            ]),
             ms_dke_service_url: Some("[ms dke service url]".to_string()),
+            crl_distribution_url: Some("[crl distribution url]".to_string()),
+            ocsp_responder_url: Some("[ocsp responder url]".to_string()),
+            acme_directory_url: Some("[acme directory url]".to_string()),
+            acme_issuer_certificate_id: Some("[acme issuer certificate id]".to_string()),
         };
 
         let toml_string = r#"
 default_username = "[default username]"
 force_default_username = false
+access_admins = ["[access admin 1]", "[access admin 2]"]
 google_cse_kacls_url = "[google cse kacls url]"
+google_cse_privileged_users = ["[google cse privileged user 1]", "[google cse privileged user 2]"]
+google_cse_perimeters = ["[google cse perimeter 1]", "[google cse perimeter 2]"]
 ms_dke_service_url = "[ms dke service url]"
+crl_distribution_url = "[crl distribution url]"
+ocsp_responder_url = "[ocsp responder url]"
+acme_directory_url = "[acme directory url]"
+acme_issuer_certificate_id = "[acme issuer certificate id]"
 
 [db]
 database_type = "[redis-findex, postgresql,...]"
@@ -172,6 +197,8 @@ hostname = "[hostname]"
 https_p12_file = "[https p12 file]"
 https_p12_password = "[https p12 password]"
 authority_cert_file = "[authority cert file]"
+socket_server_start = false
+socket_server_port = 5696
 
 [auth]
 jwt_issuer_uri = ["[jwt issuer uri 1]", "[jwt issuer uri 2]"]
@@ -25,6 +25,10 @@ pub struct UserClaim {
     pub perimeter_id: Option<String>,
     // Google CSE
     pub kacls_url: Option<String>,
+    // Azure AD: the user principal name, used when there is no email
+    pub upn: Option<String>,
+    // Azure AD: the object ids of the groups of the user
+    pub groups: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -134,14 +134,24 @@ where
         private_claim = extract_user_claim();
     }
 
-    match private_claim.map(|user_claim| user_claim.email) {
-        Ok(Some(email)) => {
+    match private_claim.map(|user_claim| {
+        (
+            user_claim.email.or(user_claim.upn),
+            user_claim.groups.unwrap_or_default(),
+        )
+    }) {
+        Ok((Some(email), groups)) => {
             debug!("JWT Access granted to {email} !");
-            request.extensions_mut().insert(JwtAuthClaim::new(email));
-            let res = service.call(request).await?;
+            request
+                .extensions_mut()
+                .insert(JwtAuthClaim::new(email.clone(), groups.clone()));
+            // the claimed groups only apply to this request: they are never persisted
+            let res = ClaimedGroups::new(email, groups)
+                .scope(service.call(request))
+                .await?;
             Ok(res.map_into_left_body())
         }
-        Ok(None) => {
+        Ok((None, _)) => {
             error!(
                 "{:?} {} 401 unauthorized, no email in JWT",
                 request.method(),
@@ -170,11 +180,58 @@ where
 #[derive(Debug)]
 pub struct JwtAuthClaim {
     pub email: String,
+    /// The groups the user belongs to, when the identity provider lists them
+    pub groups: Vec<String>,
 }
 
 impl JwtAuthClaim {
     #[must_use]
-    pub fn new(email: String) -> Self {
-        Self { email }
+    pub fn new(email: String, groups: Vec<String>) -> Self {
+        Self { email, groups }
+    }
+}
+
+tokio::task_local! {
+    static CLAIMED_GROUPS: ClaimedGroups;
+}
+
+/// The groups claimed in the JWT of the request being processed
+/// for the user it authenticates
+#[derive(Clone, Debug)]
+pub struct ClaimedGroups {
+    user: String,
+    groups: Vec<String>,
+}
+
+impl ClaimedGroups {
+    #[must_use]
+    pub fn new(user: String, groups: Vec<String>) -> Self {
+        Self { user, groups }
+    }
+
+    /// The claimed groups of the request being processed, if any
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CLAIMED_GROUPS.try_with(Clone::clone).ok()
+    }
+
+    /// The groups claimed for `user` by the request being processed:
+    /// none if the request authenticates another user
+    #[must_use]
+    pub fn of(user: &str) -> Vec<String> {
+        CLAIMED_GROUPS
+            .try_with(|claimed| {
+                if claimed.user == user {
+                    claimed.groups.clone()
+                } else {
+                    vec![]
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    /// Process `future` with these claimed groups
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CLAIMED_GROUPS.scope(self, future).await
     }
 }
@@ -1,5 +1,5 @@
 mod jwt_auth;
-pub use jwt_auth::{JwtAuth, JwtAuthClaim};
+pub use jwt_auth::{ClaimedGroups, JwtAuth, JwtAuthClaim};
 
 pub mod ssl_auth;
 
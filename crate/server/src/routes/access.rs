@@ -7,7 +7,8 @@ use actix_web::{
 };
 use cosmian_kmip::kmip::kmip_types::UniqueIdentifier;
 use cosmian_kms_client::access::{
-    Access, AccessRightsObtainedResponse, ObjectOwnedResponse, SuccessResponse, UserAccessResponse,
+    Access, AccessRightsObtainedResponse, GroupMember, GroupResponse, ObjectOwnedResponse, Role,
+    RoleResponse, SuccessResponse, UserAccessResponse,
 };
 use tracing::{debug, info};
 
@@ -115,3 +116,118 @@ pub async fn revoke_access(
         success: format!("Access for {} successfully deleted", access.user_id),
     }))
 }
+
+/// List the roles: the named bundles of operation types which can be granted
+#[get("/access/roles")]
+pub async fn list_roles(
+    req: HttpRequest,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<Json<Vec<RoleResponse>>> {
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("GET /access/roles {user}");
+
+    let list = kms.list_roles(database_params.as_ref()).await?;
+
+    Ok(Json(list))
+}
+
+/// Create or replace a role
+#[post("/access/roles/set")]
+pub async fn set_role(
+    req: HttpRequest,
+    role: Json<Role>,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<Json<SuccessResponse>> {
+    let role = role.into_inner();
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("POST /access/roles/set {role:?} {user}");
+
+    kms.set_role(&role, &user, database_params.as_ref()).await?;
+
+    Ok(Json(SuccessResponse {
+        success: format!("Role {} successfully set", role.name),
+    }))
+}
+
+/// Delete a role
+#[post("/access/roles/delete")]
+pub async fn delete_role(
+    req: HttpRequest,
+    role: Json<Role>,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<Json<SuccessResponse>> {
+    let role = role.into_inner();
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("POST /access/roles/delete {} {user}", role.name);
+
+    kms.delete_role(&role.name, &user, database_params.as_ref())
+        .await?;
+
+    Ok(Json(SuccessResponse {
+        success: format!("Role {} successfully deleted", role.name),
+    }))
+}
+
+/// List the local groups and their members
+#[get("/access/groups")]
+pub async fn list_groups(
+    req: HttpRequest,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<Json<Vec<GroupResponse>>> {
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("GET /access/groups {user}");
+
+    let list = kms.list_groups(&user, database_params.as_ref()).await?;
+
+    Ok(Json(list))
+}
+
+/// Add a user to a local group
+#[post("/access/groups/add")]
+pub async fn add_group_member(
+    req: HttpRequest,
+    member: Json<GroupMember>,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<Json<SuccessResponse>> {
+    let member = member.into_inner();
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("POST /access/groups/add {member:?} {user}");
+
+    kms.add_group_member(&member, &user, database_params.as_ref())
+        .await?;
+
+    Ok(Json(SuccessResponse {
+        success: format!(
+            "User {} successfully added to group {}",
+            member.user_id, member.group
+        ),
+    }))
+}
+
+/// Remove a user from a local group
+#[post("/access/groups/remove")]
+pub async fn remove_group_member(
+    req: HttpRequest,
+    member: Json<GroupMember>,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<Json<SuccessResponse>> {
+    let member = member.into_inner();
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("POST /access/groups/remove {member:?} {user}");
+
+    kms.remove_group_member(&member, &user, database_params.as_ref())
+        .await?;
+
+    Ok(Json(SuccessResponse {
+        success: format!(
+            "User {} successfully removed from group {}",
+            member.user_id, member.group
+        ),
+    }))
+}
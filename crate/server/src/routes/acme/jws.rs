@@ -0,0 +1,305 @@
+//! The JSON Web Signatures (RFC 7515) authenticating the ACME requests.
+//!
+//! ACME requests are flattened JSON serializations signed either with the
+//! account key itself (`jwk` header, when creating an account) or with a
+//! reference to the account (`kid` header).
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use openssl::{
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    ecdsa::EcdsaSig,
+    hash::{hash, MessageDigest},
+    nid::Nid,
+    pkey::{Id, PKey, Public},
+    rsa::Rsa,
+    sign::Verifier,
+};
+use serde::Deserialize;
+
+use super::problem::Problem;
+
+/// A flattened JWS, as posted by ACME clients
+#[derive(Deserialize)]
+struct FlattenedJws {
+    protected: String,
+    payload: String,
+    signature: String,
+}
+
+/// The JWS protected header of an ACME request
+#[derive(Deserialize, Debug)]
+pub struct ProtectedHeader {
+    pub alg: String,
+    pub nonce: Option<String>,
+    pub url: String,
+    pub jwk: Option<Jwk>,
+    pub kid: Option<String>,
+}
+
+/// A JSON Web Key (RFC 7517) holding an RSA or EC public key
+#[derive(Deserialize, Debug, Clone)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: Option<String>,
+    pub x: Option<String>,
+    pub y: Option<String>,
+    pub n: Option<String>,
+    pub e: Option<String>,
+}
+
+impl Jwk {
+    fn member<'a>(value: &'a Option<String>, name: &str) -> Result<&'a str, Problem> {
+        value
+            .as_deref()
+            .ok_or_else(|| Problem::malformed(format!("the JWK has no `{name}` member")))
+    }
+
+    fn decoded_member(value: &Option<String>, name: &str) -> Result<BigNum, Problem> {
+        let bytes = decode(Self::member(value, name)?)?;
+        BigNum::from_slice(&bytes).map_err(|e| Problem::malformed(format!("invalid JWK: {e}")))
+    }
+
+    /// The openssl public key
+    pub fn public_key(&self) -> Result<PKey<Public>, Problem> {
+        let invalid =
+            |e: openssl::error::ErrorStack| Problem::malformed(format!("invalid JWK: {e}"));
+        match self.kty.as_str() {
+            "RSA" => {
+                let rsa = Rsa::from_public_components(
+                    Self::decoded_member(&self.n, "n")?,
+                    Self::decoded_member(&self.e, "e")?,
+                )
+                .map_err(invalid)?;
+                PKey::from_rsa(rsa).map_err(invalid)
+            }
+            "EC" => {
+                let group = EcGroup::from_curve_name(curve_nid(Self::member(&self.crv, "crv")?)?)
+                    .map_err(invalid)?;
+                let ec_key = EcKey::from_public_key_affine_coordinates(
+                    &group,
+                    &Self::decoded_member(&self.x, "x")?,
+                    &Self::decoded_member(&self.y, "y")?,
+                )
+                .map_err(invalid)?;
+                ec_key.check_key().map_err(invalid)?;
+                PKey::from_ec_key(ec_key).map_err(invalid)
+            }
+            kty => Err(Problem::bad_signature_algorithm(format!(
+                "unsupported JWK key type: {kty}"
+            ))),
+        }
+    }
+
+    /// The RFC 7638 thumbprint, base64url encoded:
+    /// the SHA-256 digest of the required members, in lexicographic order
+    pub fn thumbprint(&self) -> Result<String, Problem> {
+        let canonical = match self.kty.as_str() {
+            "RSA" => format!(
+                r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+                Self::member(&self.e, "e")?,
+                Self::member(&self.n, "n")?
+            ),
+            "EC" => format!(
+                r#"{{"crv":"{}","kty":"EC","x":"{}","y":"{}"}}"#,
+                Self::member(&self.crv, "crv")?,
+                Self::member(&self.x, "x")?,
+                Self::member(&self.y, "y")?
+            ),
+            kty => {
+                return Err(Problem::bad_signature_algorithm(format!(
+                    "unsupported JWK key type: {kty}"
+                )))
+            }
+        };
+        let digest = hash(MessageDigest::sha256(), canonical.as_bytes())
+            .map_err(|e| Problem::server_internal(e.to_string()))?;
+        Ok(URL_SAFE_NO_PAD.encode(digest))
+    }
+}
+
+/// A parsed JWS whose signature remains to be verified
+pub struct Jws {
+    pub header: ProtectedHeader,
+    /// The decoded payload: empty for POST-as-GET requests
+    pub payload: Vec<u8>,
+    signing_input: String,
+    signature: Vec<u8>,
+}
+
+impl Jws {
+    pub fn parse(body: &[u8]) -> Result<Self, Problem> {
+        let jws: FlattenedJws = serde_json::from_slice(body)
+            .map_err(|e| Problem::malformed(format!("the request is not a flattened JWS: {e}")))?;
+        let header: ProtectedHeader = serde_json::from_slice(&decode(&jws.protected)?)
+            .map_err(|e| Problem::malformed(format!("invalid JWS protected header: {e}")))?;
+        Ok(Self {
+            header,
+            payload: decode(&jws.payload)?,
+            signing_input: format!("{}.{}", jws.protected, jws.payload),
+            signature: decode(&jws.signature)?,
+        })
+    }
+
+    /// Verify the signature with the account public key
+    pub fn verify(&self, public_key: &PKey<Public>) -> Result<(), Problem> {
+        let (digest, key_id, coordinate_size) = match self.header.alg.as_str() {
+            "RS256" => (MessageDigest::sha256(), Id::RSA, 0),
+            "ES256" => (MessageDigest::sha256(), Id::EC, 32),
+            "ES384" => (MessageDigest::sha384(), Id::EC, 48),
+            "ES512" => (MessageDigest::sha512(), Id::EC, 66),
+            alg => {
+                return Err(Problem::bad_signature_algorithm(format!(
+                    "unsupported JWS algorithm: {alg}"
+                )))
+            }
+        };
+        if public_key.id() != key_id {
+            return Err(Problem::bad_signature_algorithm(format!(
+                "the {} algorithm does not match the account key",
+                self.header.alg
+            )))
+        }
+        let internal = |e: openssl::error::ErrorStack| Problem::server_internal(e.to_string());
+        // JWS ECDSA signatures are the concatenation of r and s
+        let signature = if key_id == Id::EC {
+            if self.signature.len() != 2 * coordinate_size {
+                return Err(Problem::malformed("invalid ECDSA signature length"))
+            }
+            let (r, s) = self.signature.split_at(coordinate_size);
+            EcdsaSig::from_private_components(
+                BigNum::from_slice(r).map_err(internal)?,
+                BigNum::from_slice(s).map_err(internal)?,
+            )
+            .and_then(|signature| signature.to_der())
+            .map_err(internal)?
+        } else {
+            self.signature.clone()
+        };
+        let mut verifier = Verifier::new(digest, public_key).map_err(internal)?;
+        let valid = verifier
+            .verify_oneshot(&signature, self.signing_input.as_bytes())
+            .unwrap_or(false);
+        if !valid {
+            return Err(Problem::malformed("invalid JWS signature"))
+        }
+        Ok(())
+    }
+}
+
+fn curve_nid(crv: &str) -> Result<Nid, Problem> {
+    match crv {
+        "P-256" => Ok(Nid::X9_62_PRIME256V1),
+        "P-384" => Ok(Nid::SECP384R1),
+        "P-521" => Ok(Nid::SECP521R1),
+        crv => Err(Problem::bad_signature_algorithm(format!(
+            "unsupported JWK curve: {crv}"
+        ))),
+    }
+}
+
+/// Decode a base64url value, without padding
+pub fn decode(value: &str) -> Result<Vec<u8>, Problem> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|e| Problem::malformed(format!("invalid base64url encoding: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    use openssl::{
+        ec::{EcGroup, EcKey},
+        ecdsa::EcdsaSig,
+        hash::{hash, MessageDigest},
+        nid::Nid,
+        pkey::PKey,
+        rsa::Rsa,
+        sign::Signer,
+    };
+    use serde_json::json;
+
+    use super::{Jwk, Jws};
+
+    #[test]
+    fn test_rfc7638_thumbprint() {
+        // the example of RFC 7638 section 3.1
+        let jwk: Jwk = serde_json::from_value(json!({
+            "kty": "RSA",
+            "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+            "e": "AQAB",
+            "alg": "RS256",
+            "kid": "2011-04-29"
+        }))
+        .unwrap();
+        assert_eq!(
+            jwk.thumbprint().unwrap(),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+        // the JWK is a valid RSA public key
+        assert_eq!(jwk.public_key().unwrap().bits(), 2048);
+    }
+
+    fn flattened(protected: &serde_json::Value, sign: impl Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+        let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+        let payload = URL_SAFE_NO_PAD.encode(b"{}");
+        let signature = sign(format!("{protected}.{payload}").as_bytes());
+        json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": URL_SAFE_NO_PAD.encode(signature),
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_verify() {
+        // ES256
+        let ec_key = EcKey::generate(
+            EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)
+                .unwrap()
+                .as_ref(),
+        )
+        .unwrap();
+        let ec_public_key = PKey::public_key_from_der(
+            &PKey::from_ec_key(ec_key.clone())
+                .unwrap()
+                .public_key_to_der()
+                .unwrap(),
+        )
+        .unwrap();
+        let body = flattened(&json!({"alg": "ES256", "url": "u", "nonce": "n"}), |data| {
+            let digest = hash(MessageDigest::sha256(), data).unwrap();
+            let signature = EcdsaSig::sign(&digest, &ec_key).unwrap();
+            [
+                signature.r().to_vec_padded(32).unwrap(),
+                signature.s().to_vec_padded(32).unwrap(),
+            ]
+            .concat()
+        });
+        let jws = Jws::parse(&body).unwrap();
+        assert_eq!(jws.payload, b"{}");
+        jws.verify(&ec_public_key).unwrap();
+
+        // RS256
+        let rsa_key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let rsa_public_key =
+            PKey::public_key_from_der(&rsa_key.public_key_to_der().unwrap()).unwrap();
+        let body = flattened(&json!({"alg": "RS256", "url": "u", "nonce": "n"}), |data| {
+            Signer::new(MessageDigest::sha256(), &rsa_key)
+                .unwrap()
+                .sign_oneshot_to_vec(data)
+                .unwrap()
+        });
+        let jws = Jws::parse(&body).unwrap();
+        jws.verify(&rsa_public_key).unwrap();
+
+        // wrong key or algorithm
+        assert!(jws.verify(&ec_public_key).is_err());
+        let other_rsa_key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let other_rsa_public_key =
+            PKey::public_key_from_der(&other_rsa_key.public_key_to_der().unwrap()).unwrap();
+        assert!(jws.verify(&other_rsa_public_key).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! An ACME (RFC 8555) server, so that ACME clients such as certbot or cert-manager
+//! can obtain and renew certificates signed by an issuer held in the KMS.
+//!
+//! The accounts keys are stored in the KMS; the orders, authorizations and challenges
+//! are kept in memory by the `AcmeServer`.
+
+use std::sync::Arc;
+
+use actix_web::{
+    get, head, post,
+    web::{Bytes, Data, Path},
+    HttpRequest, HttpResponse, HttpResponseBuilder,
+};
+use http::header;
+use tracing::{info, warn};
+
+use crate::{
+    core::extra_database_params::ExtraDatabaseParams, database::KMSServer, result::KResult,
+};
+
+mod jws;
+mod problem;
+mod server;
+mod validator;
+
+use problem::Problem;
+pub use server::AcmeServer;
+use server::{AcmeResponse, Body};
+pub use validator::{ChallengeValidator, NetworkValidator};
+
+const REPLAY_NONCE: &str = "Replay-Nonce";
+const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+const PEM_CERTIFICATE_CHAIN_CONTENT_TYPE: &str = "application/pem-certificate-chain";
+
+/// Build the HTTP response, adding a fresh nonce and the link to the directory
+async fn respond(acme: &AcmeServer, result: Result<AcmeResponse, Problem>) -> HttpResponse {
+    let nonce = acme.new_nonce().await;
+    let index = format!("<{}>;rel=\"index\"", acme.url("directory"));
+    match result {
+        Ok(response) => {
+            let mut builder = HttpResponseBuilder::new(response.status);
+            builder
+                .insert_header((REPLAY_NONCE, nonce))
+                .append_header((header::LINK, index));
+            if let Some(location) = response.location {
+                builder.insert_header((header::LOCATION, location));
+            }
+            if let Some(up) = response.up {
+                builder.append_header((header::LINK, format!("<{up}>;rel=\"up\"")));
+            }
+            match response.body {
+                Body::Json(json) => builder.json(json),
+                Body::PemCertificateChain(chain) => builder
+                    .insert_header((header::CONTENT_TYPE, PEM_CERTIFICATE_CHAIN_CONTENT_TYPE))
+                    .body(chain),
+            }
+        }
+        Err(problem) => {
+            warn!("ACME: {} - {}", problem.status, problem.detail);
+            HttpResponseBuilder::new(problem.status)
+                .insert_header((REPLAY_NONCE, nonce))
+                .append_header((header::LINK, index))
+                .insert_header((header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE))
+                .body(problem.to_json().to_string())
+        }
+    }
+}
+
+/// The database parameters and the user of the request
+fn identify(req: HttpRequest, kms: &KMSServer) -> KResult<(Option<ExtraDatabaseParams>, String)> {
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    Ok((database_params, user))
+}
+
+#[get("/directory")]
+pub async fn directory(acme: Data<Arc<AcmeServer>>) -> HttpResponse {
+    info!("GET /acme/directory");
+    HttpResponse::Ok().json(acme.directory())
+}
+
+#[head("/new-nonce")]
+pub async fn head_new_nonce(acme: Data<Arc<AcmeServer>>) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((REPLAY_NONCE, acme.new_nonce().await))
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .finish()
+}
+
+#[get("/new-nonce")]
+pub async fn get_new_nonce(acme: Data<Arc<AcmeServer>>) -> HttpResponse {
+    HttpResponse::NoContent()
+        .insert_header((REPLAY_NONCE, acme.new_nonce().await))
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .finish()
+}
+
+#[post("/new-account")]
+pub async fn new_account(
+    req: HttpRequest,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/new-account");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.new_account(&body, &kms, &user, database_params.as_ref())
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/account/{account_id}")]
+pub async fn account(
+    req: HttpRequest,
+    account_id: Path<String>,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/account/{account_id}");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.update_account(&account_id, &body, &kms, &user, database_params.as_ref())
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/account/{account_id}/orders")]
+pub async fn account_orders(
+    req: HttpRequest,
+    account_id: Path<String>,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/account/{account_id}/orders");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.account_orders(&account_id, &body, &kms, &user, database_params.as_ref())
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/new-order")]
+pub async fn new_order(
+    req: HttpRequest,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/new-order");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.new_order(&body, &kms, &user, database_params.as_ref())
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/order/{order_id}")]
+pub async fn order(
+    req: HttpRequest,
+    order_id: Path<String>,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/order/{order_id}");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.order(&order_id, &body, &kms, &user, database_params.as_ref())
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/order/{order_id}/finalize")]
+pub async fn finalize(
+    req: HttpRequest,
+    order_id: Path<String>,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/order/{order_id}/finalize");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.finalize(&order_id, &body, &kms, &user, database_params.as_ref())
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/authz/{authorization_id}")]
+pub async fn authorization(
+    req: HttpRequest,
+    authorization_id: Path<String>,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/authz/{authorization_id}");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.authorization(
+                &authorization_id,
+                &body,
+                &kms,
+                &user,
+                database_params.as_ref(),
+            )
+            .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/challenge/{authorization_id}/{challenge_type}")]
+pub async fn challenge(
+    req: HttpRequest,
+    path: Path<(String, String)>,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    let (authorization_id, challenge_type) = path.into_inner();
+    info!("POST /acme/challenge/{authorization_id}/{challenge_type}");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.challenge(
+                &authorization_id,
+                &challenge_type,
+                &body,
+                &kms,
+                &user,
+                database_params.as_ref(),
+            )
+            .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
+
+#[post("/certificate/{certificate_id}")]
+pub async fn certificate(
+    req: HttpRequest,
+    certificate_id: Path<String>,
+    body: Bytes,
+    acme: Data<Arc<AcmeServer>>,
+    kms: Data<Arc<KMSServer>>,
+) -> HttpResponse {
+    info!("POST /acme/certificate/{certificate_id}");
+    let result = match identify(req, &kms) {
+        Ok((database_params, user)) => {
+            acme.certificate(
+                &certificate_id,
+                &body,
+                &kms,
+                &user,
+                database_params.as_ref(),
+            )
+            .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    respond(&acme, result).await
+}
@@ -0,0 +1,101 @@
+use http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::error::KmsError;
+
+/// An ACME error, returned to the client as a problem document (RFC 7807)
+#[derive(Debug, Clone)]
+pub struct Problem {
+    /// The ACME error type, without the `urn:ietf:params:acme:error:` prefix
+    pub kind: &'static str,
+    pub detail: String,
+    pub status: StatusCode,
+}
+
+impl Problem {
+    fn new(kind: &'static str, detail: impl Into<String>, status: StatusCode) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+            status,
+        }
+    }
+
+    pub fn malformed(detail: impl Into<String>) -> Self {
+        Self::new("malformed", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn bad_nonce(detail: impl Into<String>) -> Self {
+        Self::new("badNonce", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn bad_signature_algorithm(detail: impl Into<String>) -> Self {
+        Self::new("badSignatureAlgorithm", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn bad_csr(detail: impl Into<String>) -> Self {
+        Self::new("badCSR", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn unauthorized(detail: impl Into<String>) -> Self {
+        Self::new("unauthorized", detail, StatusCode::FORBIDDEN)
+    }
+
+    pub fn account_does_not_exist(detail: impl Into<String>) -> Self {
+        Self::new("accountDoesNotExist", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new("malformed", detail, StatusCode::NOT_FOUND)
+    }
+
+    pub fn order_not_ready(detail: impl Into<String>) -> Self {
+        Self::new("orderNotReady", detail, StatusCode::FORBIDDEN)
+    }
+
+    pub fn rejected_identifier(detail: impl Into<String>) -> Self {
+        Self::new("rejectedIdentifier", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn unsupported_identifier(detail: impl Into<String>) -> Self {
+        Self::new("unsupportedIdentifier", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn incorrect_response(detail: impl Into<String>) -> Self {
+        Self::new("incorrectResponse", detail, StatusCode::FORBIDDEN)
+    }
+
+    pub fn connection(detail: impl Into<String>) -> Self {
+        Self::new("connection", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn dns(detail: impl Into<String>) -> Self {
+        Self::new("dns", detail, StatusCode::BAD_REQUEST)
+    }
+
+    pub fn server_internal(detail: impl Into<String>) -> Self {
+        Self::new("serverInternal", detail, StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// The JSON problem document
+    pub fn to_json(&self) -> Value {
+        json!({
+            "type": format!("urn:ietf:params:acme:error:{}", self.kind),
+            "detail": self.detail,
+            "status": self.status.as_u16(),
+        })
+    }
+}
+
+impl From<KmsError> for Problem {
+    fn from(e: KmsError) -> Self {
+        match e {
+            KmsError::InvalidRequest(detail) | KmsError::NotSupported(detail) => {
+                Self::malformed(detail)
+            }
+            KmsError::ItemNotFound(detail) => Self::not_found(detail),
+            KmsError::Unauthorized(detail) => Self::unauthorized(detail),
+            e => Self::server_internal(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,1129 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use cosmian_kmip::{
+    kmip::{
+        kmip_objects::ObjectType,
+        kmip_operations::Certify,
+        kmip_types::{
+            Attributes, CertificateRequestType, KeyFormatType, LinkType, LinkedObjectIdentifier,
+            StateEnumeration,
+        },
+    },
+    openssl::{
+        kmip_certificate_to_openssl, kmip_public_key_to_openssl, openssl_public_key_to_kmip,
+    },
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use http::StatusCode;
+use openssl::{
+    hash::{hash, MessageDigest},
+    pkey::{PKey, Public},
+    rand::rand_bytes,
+    x509::{X509Req, X509VerifyResult},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+use x509_parser::{
+    certification_request::X509CertificationRequest,
+    extensions::{GeneralName, ParsedExtension},
+    prelude::FromDer,
+};
+
+use super::{
+    jws::{self, Jws},
+    problem::Problem,
+    validator::ChallengeValidator,
+};
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, KMS},
+    database::AtomicOperation,
+    error::KmsError,
+    kms_error,
+    result::KResult,
+};
+
+/// The system tag of the ACME account keys stored in the KMS
+const ACCOUNT_TAG: &str = "_acme_account";
+/// The number of unused nonces kept before the oldest are discarded
+const NONCES_CAPACITY: usize = 10_000;
+/// The number of days an order and its authorizations can be completed
+const ORDER_VALIDITY_DAYS: i64 = 7;
+/// The number of days a certificate issued through ACME is valid,
+/// capped by the validity of the issuer certificate
+const CERTIFICATE_VALIDITY_DAYS: usize = 90;
+/// The maximum number of certificates in a downloaded chain
+const MAX_CHAIN_LENGTH: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pending,
+    Ready,
+    Processing,
+    Valid,
+    Invalid,
+    Deactivated,
+    Expired,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChallengeType {
+    Http01,
+    Dns01,
+}
+
+impl ChallengeType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Http01 => "http-01",
+            Self::Dns01 => "dns-01",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Identifier {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: String,
+}
+
+struct Challenge {
+    kind: ChallengeType,
+    token: String,
+    status: Status,
+    validated: Option<DateTime<Utc>>,
+    error: Option<Problem>,
+}
+
+struct Authorization {
+    account_id: String,
+    /// The domain to validate, without the wildcard prefix
+    domain: String,
+    wildcard: bool,
+    status: Status,
+    expires: DateTime<Utc>,
+    challenges: Vec<Challenge>,
+}
+
+struct Order {
+    account_id: String,
+    status: Status,
+    expires: DateTime<Utc>,
+    identifiers: Vec<Identifier>,
+    authorization_ids: Vec<String>,
+    certificate_id: Option<String>,
+    error: Option<Problem>,
+}
+
+/// The orders, authorizations and unused nonces, which are kept in memory.
+/// The accounts are stored in the KMS.
+#[derive(Default)]
+struct State {
+    nonces: HashSet<String>,
+    nonces_by_age: VecDeque<String>,
+    orders: HashMap<String, Order>,
+    authorizations: HashMap<String, Authorization>,
+}
+
+pub enum Body {
+    Json(Value),
+    PemCertificateChain(String),
+}
+
+/// A successful ACME response
+pub struct AcmeResponse {
+    pub status: StatusCode,
+    pub location: Option<String>,
+    /// The parent resource, e.g. the authorization of a challenge
+    pub up: Option<String>,
+    pub body: Body,
+}
+
+impl AcmeResponse {
+    fn json(status: StatusCode, body: Value) -> Self {
+        Self {
+            status,
+            location: None,
+            up: None,
+            body: Body::Json(body),
+        }
+    }
+
+    fn with_location(mut self, location: String) -> Self {
+        self.location = Some(location);
+        self
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct NewAccountRequest {
+    only_return_existing: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+struct StatusUpdateRequest {
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct NewOrderRequest {
+    identifiers: Vec<Identifier>,
+}
+
+#[derive(Deserialize)]
+struct FinalizeRequest {
+    csr: String,
+}
+
+/// An ACME (RFC 8555) server issuing certificates signed by an issuer held in the KMS.
+///
+/// The identifiers of the orders are validated with HTTP-01 or DNS-01 challenges,
+/// using the configured validator, then the certificate signing requests
+/// are certified by the KMS as the user of the ACME requests.
+pub struct AcmeServer {
+    /// The external URL of the `/acme` endpoint, without trailing slash
+    url: String,
+    issuer_certificate_id: String,
+    validator: Arc<dyn ChallengeValidator>,
+    state: Mutex<State>,
+}
+
+impl AcmeServer {
+    #[must_use]
+    pub fn new(
+        url: &str,
+        issuer_certificate_id: &str,
+        validator: Arc<dyn ChallengeValidator>,
+    ) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_owned(),
+            issuer_certificate_id: issuer_certificate_id.to_owned(),
+            validator,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// The external URL of an ACME resource
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{path}", self.url)
+    }
+
+    pub fn directory(&self) -> Value {
+        json!({
+            "newNonce": self.url("new-nonce"),
+            "newAccount": self.url("new-account"),
+            "newOrder": self.url("new-order"),
+            "meta": {
+                "externalAccountRequired": false,
+            },
+        })
+    }
+
+    /// Issue a nonce, which can be used once to sign a request
+    pub async fn new_nonce(&self) -> String {
+        let nonce = Uuid::new_v4().simple().to_string();
+        let mut state = self.state.lock().await;
+        state.nonces.insert(nonce.clone());
+        state.nonces_by_age.push_back(nonce.clone());
+        while state.nonces_by_age.len() > NONCES_CAPACITY {
+            if let Some(oldest) = state.nonces_by_age.pop_front() {
+                state.nonces.remove(&oldest);
+            }
+        }
+        nonce
+    }
+
+    /// Check that the JWS was sent to the URL it was signed for, and consume its nonce
+    async fn check_url_and_nonce(&self, jws: &Jws, url: &str) -> Result<(), Problem> {
+        if jws.header.url != url {
+            return Err(Problem::unauthorized(format!(
+                "the JWS was signed for {} but sent to {url}",
+                jws.header.url
+            )))
+        }
+        let nonce = jws
+            .header
+            .nonce
+            .as_deref()
+            .ok_or_else(|| Problem::bad_nonce("the JWS has no nonce"))?;
+        if !self.state.lock().await.nonces.remove(nonce) {
+            return Err(Problem::bad_nonce(format!(
+                "unknown or already used nonce: {nonce}"
+            )))
+        }
+        Ok(())
+    }
+
+    /// Authenticate a request signed with the key of an existing account.
+    /// Return the account id and the request payload.
+    async fn authenticate(
+        &self,
+        body: &[u8],
+        url: &str,
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<(String, Vec<u8>), Problem> {
+        let jws = Jws::parse(body)?;
+        self.check_url_and_nonce(&jws, url).await?;
+        if jws.header.jwk.is_some() {
+            return Err(Problem::malformed(
+                "the request must be signed with the account key id (kid), not a JWK",
+            ))
+        }
+        let kid = jws
+            .header
+            .kid
+            .as_deref()
+            .ok_or_else(|| Problem::malformed("the JWS has no account key id (kid)"))?;
+        let account_id = kid
+            .strip_prefix(&self.url("account/"))
+            .ok_or_else(|| Problem::account_does_not_exist(format!("unknown account: {kid}")))?;
+        let (public_key, state) = retrieve_account(account_id, kms, user, params)
+            .await?
+            .ok_or_else(|| Problem::account_does_not_exist(format!("unknown account: {kid}")))?;
+        jws.verify(&public_key)?;
+        if state != StateEnumeration::Active {
+            return Err(Problem::unauthorized("the account is deactivated"))
+        }
+        Ok((account_id.to_owned(), jws.payload))
+    }
+
+    fn account_json(&self, account_id: &str, state: StateEnumeration) -> Value {
+        let status = if state == StateEnumeration::Active {
+            Status::Valid
+        } else {
+            Status::Deactivated
+        };
+        json!({
+            "status": status,
+            "orders": self.url(&format!("account/{account_id}/orders")),
+        })
+    }
+
+    /// Create an account, or find the account of the JWK signing the request
+    pub async fn new_account(
+        &self,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let jws = Jws::parse(body)?;
+        self.check_url_and_nonce(&jws, &self.url("new-account"))
+            .await?;
+        let jwk = match (&jws.header.jwk, &jws.header.kid) {
+            (Some(jwk), None) => jwk,
+            _ => {
+                return Err(Problem::malformed(
+                    "a new account request must be signed with a JWK",
+                ))
+            }
+        };
+        let public_key = jwk.public_key()?;
+        jws.verify(&public_key)?;
+        let request: NewAccountRequest = parse_payload(&jws.payload)?;
+
+        let account_id = jwk.thumbprint()?;
+        let location = self.url(&format!("account/{account_id}"));
+        if let Some((_, state)) = retrieve_account(&account_id, kms, user, params).await? {
+            return Ok(
+                AcmeResponse::json(StatusCode::OK, self.account_json(&account_id, state))
+                    .with_location(location),
+            )
+        }
+        if request.only_return_existing == Some(true) {
+            return Err(Problem::account_does_not_exist(
+                "no account exists for this key",
+            ))
+        }
+        create_account(&account_id, &public_key, kms, user, params).await?;
+        Ok(AcmeResponse::json(
+            StatusCode::CREATED,
+            self.account_json(&account_id, StateEnumeration::Active),
+        )
+        .with_location(location))
+    }
+
+    /// Fetch or deactivate an account
+    pub async fn update_account(
+        &self,
+        account_id: &str,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let url = self.url(&format!("account/{account_id}"));
+        let (signer_id, payload) = self.authenticate(body, &url, kms, user, params).await?;
+        if signer_id != account_id {
+            return Err(Problem::unauthorized(
+                "the request is not signed by this account",
+            ))
+        }
+        let request: StatusUpdateRequest = parse_payload(&payload)?;
+        let state = match request.status.as_deref() {
+            None => StateEnumeration::Active,
+            Some("deactivated") => {
+                kms.db
+                    .atomic(
+                        user,
+                        &[AtomicOperation::UpdateState((
+                            account_uid(account_id),
+                            StateEnumeration::Deactivated,
+                        ))],
+                        params,
+                    )
+                    .await?;
+                StateEnumeration::Deactivated
+            }
+            Some(status) => {
+                return Err(Problem::malformed(format!(
+                    "an account status cannot be set to {status}"
+                )))
+            }
+        };
+        Ok(AcmeResponse::json(
+            StatusCode::OK,
+            self.account_json(account_id, state),
+        ))
+    }
+
+    /// List the orders of an account
+    pub async fn account_orders(
+        &self,
+        account_id: &str,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let url = self.url(&format!("account/{account_id}/orders"));
+        let (signer_id, _) = self.authenticate(body, &url, kms, user, params).await?;
+        if signer_id != account_id {
+            return Err(Problem::unauthorized(
+                "the request is not signed by this account",
+            ))
+        }
+        let state = self.state.lock().await;
+        let orders: Vec<String> = state
+            .orders
+            .iter()
+            .filter(|(_, order)| order.account_id == account_id)
+            .map(|(order_id, _)| self.url(&format!("order/{order_id}")))
+            .collect();
+        Ok(AcmeResponse::json(
+            StatusCode::OK,
+            json!({ "orders": orders }),
+        ))
+    }
+
+    /// Create an order and an authorization for each of its identifiers
+    pub async fn new_order(
+        &self,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let (account_id, payload) = self
+            .authenticate(body, &self.url("new-order"), kms, user, params)
+            .await?;
+        let request: NewOrderRequest = serde_json::from_slice(&payload)
+            .map_err(|e| Problem::malformed(format!("invalid new order request: {e}")))?;
+        if request.identifiers.is_empty() {
+            return Err(Problem::malformed("the order has no identifiers"))
+        }
+
+        let now = Utc::now();
+        let expires = now + Duration::days(ORDER_VALIDITY_DAYS);
+        let mut identifiers: Vec<Identifier> = Vec::new();
+        let mut authorizations = Vec::new();
+        for identifier in &request.identifiers {
+            let (domain, wildcard) = parse_identifier(identifier)?;
+            let value = if wildcard {
+                format!("*.{domain}")
+            } else {
+                domain.clone()
+            };
+            if identifiers.iter().any(|i| i.value == value) {
+                continue
+            }
+            identifiers.push(Identifier {
+                kind: "dns".to_owned(),
+                value,
+            });
+            // wildcard domains can only be validated with DNS-01
+            let challenge_types: &[ChallengeType] = if wildcard {
+                &[ChallengeType::Dns01]
+            } else {
+                &[ChallengeType::Http01, ChallengeType::Dns01]
+            };
+            let challenges = challenge_types
+                .iter()
+                .map(|kind| {
+                    Ok(Challenge {
+                        kind: *kind,
+                        token: random_token()?,
+                        status: Status::Pending,
+                        validated: None,
+                        error: None,
+                    })
+                })
+                .collect::<Result<Vec<_>, Problem>>()?;
+            authorizations.push((
+                Uuid::new_v4().to_string(),
+                Authorization {
+                    account_id: account_id.clone(),
+                    domain,
+                    wildcard,
+                    status: Status::Pending,
+                    expires,
+                    challenges,
+                },
+            ));
+        }
+        let order_id = Uuid::new_v4().to_string();
+        let order = Order {
+            account_id,
+            status: Status::Pending,
+            expires,
+            identifiers,
+            authorization_ids: authorizations.iter().map(|(id, _)| id.clone()).collect(),
+            certificate_id: None,
+            error: None,
+        };
+
+        let mut state = self.state.lock().await;
+        // forget the orders and authorizations which expired a while ago
+        let horizon = now - Duration::days(ORDER_VALIDITY_DAYS);
+        state.orders.retain(|_, order| order.expires > horizon);
+        state
+            .authorizations
+            .retain(|_, authorization| authorization.expires > horizon);
+        let json = self.order_json(&order_id, &order);
+        state.authorizations.extend(authorizations);
+        state.orders.insert(order_id.clone(), order);
+        Ok(AcmeResponse::json(StatusCode::CREATED, json)
+            .with_location(self.url(&format!("order/{order_id}"))))
+    }
+
+    fn order_json(&self, order_id: &str, order: &Order) -> Value {
+        let mut json = json!({
+            "status": order.status,
+            "expires": format_date(&order.expires),
+            "identifiers": order.identifiers,
+            "authorizations": order
+                .authorization_ids
+                .iter()
+                .map(|id| self.url(&format!("authz/{id}")))
+                .collect::<Vec<_>>(),
+            "finalize": self.url(&format!("order/{order_id}/finalize")),
+        });
+        if let Some(certificate_id) = &order.certificate_id {
+            json["certificate"] = json!(self.url(&format!("certificate/{certificate_id}")));
+        }
+        if let Some(error) = &order.error {
+            json["error"] = error.to_json();
+        }
+        json
+    }
+
+    /// Fetch an order
+    pub async fn order(
+        &self,
+        order_id: &str,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let url = self.url(&format!("order/{order_id}"));
+        let (account_id, _) = self.authenticate(body, &url, kms, user, params).await?;
+        let mut state = self.state.lock().await;
+        let State {
+            orders,
+            authorizations,
+            ..
+        } = &mut *state;
+        let order = owned_order(orders, order_id, &account_id)?;
+        refresh_order(order, authorizations);
+        Ok(AcmeResponse::json(
+            StatusCode::OK,
+            self.order_json(order_id, order),
+        ))
+    }
+
+    fn authorization_json(&self, authorization_id: &str, authorization: &Authorization) -> Value {
+        let mut json = json!({
+            "identifier": {
+                "type": "dns",
+                "value": authorization.domain,
+            },
+            "status": authorization.status,
+            "expires": format_date(&authorization.expires),
+            "challenges": authorization
+                .challenges
+                .iter()
+                .map(|challenge| self.challenge_json(authorization_id, challenge))
+                .collect::<Vec<_>>(),
+        });
+        if authorization.wildcard {
+            json["wildcard"] = json!(true);
+        }
+        json
+    }
+
+    fn challenge_json(&self, authorization_id: &str, challenge: &Challenge) -> Value {
+        let mut json = json!({
+            "type": challenge.kind.as_str(),
+            "url": self.url(&format!(
+                "challenge/{authorization_id}/{}",
+                challenge.kind.as_str()
+            )),
+            "status": challenge.status,
+            "token": challenge.token,
+        });
+        if let Some(validated) = &challenge.validated {
+            json["validated"] = json!(format_date(validated));
+        }
+        if let Some(error) = &challenge.error {
+            json["error"] = error.to_json();
+        }
+        json
+    }
+
+    /// Fetch or deactivate an authorization
+    pub async fn authorization(
+        &self,
+        authorization_id: &str,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let url = self.url(&format!("authz/{authorization_id}"));
+        let (account_id, payload) = self.authenticate(body, &url, kms, user, params).await?;
+        let request: StatusUpdateRequest = parse_payload(&payload)?;
+        let mut state = self.state.lock().await;
+        let authorization = owned_authorization(&mut state, authorization_id, &account_id)?;
+        match request.status.as_deref() {
+            None => {}
+            Some("deactivated") => authorization.status = Status::Deactivated,
+            Some(status) => {
+                return Err(Problem::malformed(format!(
+                    "an authorization status cannot be set to {status}"
+                )))
+            }
+        }
+        if authorization.status == Status::Pending && authorization.expires < Utc::now() {
+            authorization.status = Status::Expired;
+        }
+        Ok(AcmeResponse::json(
+            StatusCode::OK,
+            self.authorization_json(authorization_id, authorization),
+        ))
+    }
+
+    /// Respond to a challenge: the server validates it with the validator
+    pub async fn challenge(
+        &self,
+        authorization_id: &str,
+        challenge_type: &str,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let url = self.url(&format!("challenge/{authorization_id}/{challenge_type}"));
+        let (account_id, _) = self.authenticate(body, &url, kms, user, params).await?;
+
+        // Find the challenge to validate, then release the lock during the validation
+        let (kind, domain, token, to_validate) = {
+            let mut state = self.state.lock().await;
+            let authorization = owned_authorization(&mut state, authorization_id, &account_id)?;
+            if authorization.status == Status::Pending && authorization.expires < Utc::now() {
+                authorization.status = Status::Expired;
+            }
+            let authorization_status = authorization.status;
+            let domain = authorization.domain.clone();
+            let challenge = find_challenge(authorization, challenge_type)?;
+            let to_validate =
+                authorization_status == Status::Pending && challenge.status == Status::Pending;
+            if to_validate {
+                challenge.status = Status::Processing;
+            }
+            (challenge.kind, domain, challenge.token.clone(), to_validate)
+        };
+
+        if to_validate {
+            // The key authorization binds the token to the account key thumbprint
+            let key_authorization = format!("{token}.{account_id}");
+            let result = self
+                .validate(kind, &domain, &token, &key_authorization)
+                .await;
+            let mut state = self.state.lock().await;
+            let authorization = owned_authorization(&mut state, authorization_id, &account_id)?;
+            let (challenge_status, authorization_status, error) = match result {
+                Ok(()) => (Status::Valid, Status::Valid, None),
+                Err(problem) => (Status::Invalid, Status::Invalid, Some(problem)),
+            };
+            authorization.status = authorization_status;
+            let challenge = find_challenge(authorization, challenge_type)?;
+            challenge.status = challenge_status;
+            challenge.error = error;
+            if challenge_status == Status::Valid {
+                challenge.validated = Some(Utc::now());
+            }
+        }
+
+        let mut state = self.state.lock().await;
+        let authorization = owned_authorization(&mut state, authorization_id, &account_id)?;
+        let challenge = find_challenge(authorization, challenge_type)?;
+        let mut response = AcmeResponse::json(
+            StatusCode::OK,
+            self.challenge_json(authorization_id, challenge),
+        );
+        response.up = Some(self.url(&format!("authz/{authorization_id}")));
+        Ok(response)
+    }
+
+    /// Check that the proof published for the domain matches the key authorization
+    async fn validate(
+        &self,
+        kind: ChallengeType,
+        domain: &str,
+        token: &str,
+        key_authorization: &str,
+    ) -> Result<(), Problem> {
+        match kind {
+            ChallengeType::Http01 => {
+                let content = self
+                    .validator
+                    .http_01(domain, token)
+                    .await
+                    .map_err(|e| Problem::connection(e.to_string()))?;
+                if content.trim_end() != key_authorization {
+                    return Err(Problem::incorrect_response(format!(
+                        "the key authorization served for {domain} is incorrect"
+                    )))
+                }
+            }
+            ChallengeType::Dns01 => {
+                let digest = hash(MessageDigest::sha256(), key_authorization.as_bytes())
+                    .map_err(|e| Problem::server_internal(e.to_string()))?;
+                let expected = URL_SAFE_NO_PAD.encode(digest);
+                let records = self
+                    .validator
+                    .dns_01(domain)
+                    .await
+                    .map_err(|e| Problem::dns(e.to_string()))?;
+                if !records.iter().any(|record| record.trim() == expected) {
+                    return Err(Problem::incorrect_response(format!(
+                        "no TXT record of _acme-challenge.{domain} matches the key authorization"
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Certify the CSR of a ready order with the issuer of the ACME server
+    pub async fn finalize(
+        &self,
+        order_id: &str,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let url = self.url(&format!("order/{order_id}/finalize"));
+        let (account_id, payload) = self.authenticate(body, &url, kms, user, params).await?;
+        let request: FinalizeRequest = serde_json::from_slice(&payload)
+            .map_err(|e| Problem::malformed(format!("invalid finalize request: {e}")))?;
+        let csr = jws::decode(&request.csr)?;
+
+        let identifiers = {
+            let mut state = self.state.lock().await;
+            let State {
+                orders,
+                authorizations,
+                ..
+            } = &mut *state;
+            let order = owned_order(orders, order_id, &account_id)?;
+            refresh_order(order, authorizations);
+            if order.status != Status::Ready {
+                return Err(Problem::order_not_ready(format!(
+                    "the order is {:?}, not ready",
+                    order.status
+                )))
+            }
+            let requested = order
+                .identifiers
+                .iter()
+                .map(|identifier| identifier.value.clone())
+                .collect::<BTreeSet<_>>();
+            let names = csr_names(&csr)?;
+            if names != requested {
+                return Err(Problem::bad_csr(format!(
+                    "the CSR names {names:?} do not match the order identifiers {requested:?}"
+                )))
+            }
+            order.status = Status::Processing;
+            requested
+        };
+
+        let result = self.certify(csr, &identifiers, kms, user, params).await;
+
+        let mut state = self.state.lock().await;
+        let order = owned_order(&mut state.orders, order_id, &account_id)?;
+        match result {
+            Ok(certificate_id) => {
+                order.status = Status::Valid;
+                order.certificate_id = Some(certificate_id);
+            }
+            Err(e) => {
+                let problem = Problem::from(e);
+                order.status = Status::Invalid;
+                order.error = Some(problem.clone());
+                return Err(problem)
+            }
+        }
+        Ok(
+            AcmeResponse::json(StatusCode::OK, self.order_json(order_id, order))
+                .with_location(self.url(&format!("order/{order_id}"))),
+        )
+    }
+
+    /// Issue a TLS server and client certificate for the validated domains
+    async fn certify(
+        &self,
+        csr: Vec<u8>,
+        domains: &BTreeSet<String>,
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> KResult<String> {
+        let subject_alt_name = domains
+            .iter()
+            .map(|domain| format!("DNS:{domain}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let extensions = [
+            "[v3_ca]".to_owned(),
+            "basicConstraints=critical".to_owned(),
+            "keyUsage=critical,digitalSignature,keyEncipherment".to_owned(),
+            "extendedKeyUsage=serverAuth,clientAuth".to_owned(),
+            "subjectKeyIdentifier=hash".to_owned(),
+            "authorityKeyIdentifier=keyid".to_owned(),
+            format!("subjectAltName={subject_alt_name}"),
+        ]
+        .join("\n");
+
+        let mut attributes = Attributes {
+            object_type: Some(ObjectType::Certificate),
+            ..Attributes::default()
+        };
+        attributes.add_link(
+            LinkType::CertificateLink,
+            LinkedObjectIdentifier::TextString(self.issuer_certificate_id.clone()),
+        );
+        attributes.set_requested_validity_days(CERTIFICATE_VALIDITY_DAYS);
+        attributes.set_x509_extension_file(extensions.into_bytes());
+
+        let response = kms
+            .certify(
+                Certify {
+                    certificate_request_type: Some(CertificateRequestType::PKCS10),
+                    certificate_request_value: Some(csr),
+                    attributes: Some(attributes),
+                    ..Certify::default()
+                },
+                user,
+                params,
+            )
+            .await?;
+        response
+            .unique_identifier
+            .as_str()
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| kms_error!("ACME: the certificate has no text unique identifier"))
+    }
+
+    /// Download the PEM chain of a certificate issued for the account
+    pub async fn certificate(
+        &self,
+        certificate_id: &str,
+        body: &[u8],
+        kms: &KMS,
+        user: &str,
+        params: Option<&ExtraDatabaseParams>,
+    ) -> Result<AcmeResponse, Problem> {
+        let url = self.url(&format!("certificate/{certificate_id}"));
+        let (account_id, _) = self.authenticate(body, &url, kms, user, params).await?;
+        let issued_to_account = self.state.lock().await.orders.values().any(|order| {
+            order.account_id == account_id
+                && order.certificate_id.as_deref() == Some(certificate_id)
+        });
+        if !issued_to_account {
+            return Err(Problem::unauthorized(
+                "the certificate was not issued to this account",
+            ))
+        }
+        let chain = certificate_chain(certificate_id, kms, user, params).await?;
+        Ok(AcmeResponse {
+            status: StatusCode::OK,
+            location: None,
+            up: None,
+            body: Body::PemCertificateChain(chain),
+        })
+    }
+}
+
+/// The unique identifier of the account key stored in the KMS
+fn account_uid(account_id: &str) -> String {
+    format!("acme_account_{account_id}")
+}
+
+/// Retrieve the public key and state of an account, if it exists
+async fn retrieve_account(
+    account_id: &str,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<Option<(PKey<Public>, StateEnumeration)>> {
+    let uid = account_uid(account_id);
+    let Some(owm) = kms
+        .db
+        .retrieve(&uid, user, ObjectOperationType::Get, params)
+        .await?
+        .remove(&uid)
+    else {
+        return Ok(None)
+    };
+    Ok(Some((kmip_public_key_to_openssl(&owm.object)?, owm.state)))
+}
+
+/// Store the public key of a new account in the KMS
+async fn create_account(
+    account_id: &str,
+    public_key: &PKey<Public>,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<()> {
+    let object = openssl_public_key_to_kmip(public_key, KeyFormatType::PKCS8, None)?;
+    let attributes = object.attributes()?.clone();
+    kms.db
+        .atomic(
+            user,
+            &[AtomicOperation::Create((
+                account_uid(account_id),
+                object,
+                attributes,
+                HashSet::from([ACCOUNT_TAG.to_owned()]),
+            ))],
+            params,
+        )
+        .await
+}
+
+/// The PEM chain of a certificate, up to but excluding the self-signed root
+async fn certificate_chain(
+    certificate_id: &str,
+    kms: &KMS,
+    user: &str,
+    params: Option<&ExtraDatabaseParams>,
+) -> KResult<String> {
+    let mut chain = String::new();
+    let mut next = Some(certificate_id.to_owned());
+    let mut length = 0;
+    while let Some(uid) = next.take() {
+        let owm = kms
+            .db
+            .retrieve(&uid, user, ObjectOperationType::Get, params)
+            .await?
+            .remove(&uid)
+            .ok_or_else(|| KmsError::ItemNotFound(uid.clone()))?;
+        let x509 = kmip_certificate_to_openssl(&owm.object)?;
+        if length > 0 && x509.issued(&x509) == X509VerifyResult::OK {
+            break
+        }
+        chain.push_str(&String::from_utf8(x509.to_pem()?)?);
+        length += 1;
+        next = owm
+            .attributes
+            .get_link(LinkType::CertificateLink)
+            .filter(|issuer_uid| *issuer_uid != uid && length < MAX_CHAIN_LENGTH);
+    }
+    Ok(chain)
+}
+
+/// Parse an empty (POST-as-GET) or JSON payload
+fn parse_payload<T: DeserializeOwned + Default>(payload: &[u8]) -> Result<T, Problem> {
+    if payload.is_empty() {
+        return Ok(T::default())
+    }
+    serde_json::from_slice(payload)
+        .map_err(|e| Problem::malformed(format!("invalid request payload: {e}")))
+}
+
+/// Validate a DNS identifier and return its lowercase domain
+/// and whether it is a wildcard
+fn parse_identifier(identifier: &Identifier) -> Result<(String, bool), Problem> {
+    if identifier.kind != "dns" {
+        return Err(Problem::unsupported_identifier(format!(
+            "unsupported identifier type: {}",
+            identifier.kind
+        )))
+    }
+    let value = identifier.value.trim_end_matches('.').to_lowercase();
+    let (domain, wildcard) = match value.strip_prefix("*.") {
+        Some(domain) => (domain.to_owned(), true),
+        None => (value.clone(), false),
+    };
+    let valid = !domain.is_empty()
+        && domain.len() <= 253
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid {
+        return Err(Problem::rejected_identifier(format!(
+            "invalid domain name: {}",
+            identifier.value
+        )))
+    }
+    Ok((domain, wildcard))
+}
+
+/// The lowercase DNS names of a DER encoded CSR: its common names and DNS subject alternative names
+fn csr_names(csr: &[u8]) -> Result<BTreeSet<String>, Problem> {
+    let invalid = |e: &dyn std::fmt::Display| Problem::bad_csr(format!("invalid CSR: {e}"));
+    let request = X509Req::from_der(csr).map_err(|e| invalid(&e))?;
+    let public_key = request.public_key().map_err(|e| invalid(&e))?;
+    if !request.verify(&public_key).map_err(|e| invalid(&e))? {
+        return Err(Problem::bad_csr("invalid CSR signature"))
+    }
+
+    let (_, request) = X509CertificationRequest::from_der(csr).map_err(|e| invalid(&e))?;
+    let mut names = BTreeSet::new();
+    for common_name in request
+        .certification_request_info
+        .subject
+        .iter_common_name()
+    {
+        names.insert(
+            common_name
+                .as_str()
+                .map_err(|e| invalid(&e))?
+                .to_lowercase(),
+        );
+    }
+    for extension in request.requested_extensions().into_iter().flatten() {
+        if let ParsedExtension::SubjectAlternativeName(san) = extension {
+            for name in &san.general_names {
+                match name {
+                    GeneralName::DNSName(dns_name) => {
+                        names.insert(dns_name.to_lowercase());
+                    }
+                    name => {
+                        return Err(Problem::bad_csr(format!(
+                            "unsupported subject alternative name: {name:?}"
+                        )))
+                    }
+                }
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// A random token of 256 bits, base64url encoded
+fn random_token() -> Result<String, Problem> {
+    let mut token = [0_u8; 32];
+    rand_bytes(&mut token).map_err(|e| Problem::server_internal(e.to_string()))?;
+    Ok(URL_SAFE_NO_PAD.encode(token))
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn owned_order<'a>(
+    orders: &'a mut HashMap<String, Order>,
+    order_id: &str,
+    account_id: &str,
+) -> Result<&'a mut Order, Problem> {
+    let order = orders
+        .get_mut(order_id)
+        .ok_or_else(|| Problem::not_found(format!("unknown order: {order_id}")))?;
+    if order.account_id != account_id {
+        return Err(Problem::unauthorized(
+            "the order belongs to another account",
+        ))
+    }
+    Ok(order)
+}
+
+fn owned_authorization<'a>(
+    state: &'a mut State,
+    authorization_id: &str,
+    account_id: &str,
+) -> Result<&'a mut Authorization, Problem> {
+    let authorization = state
+        .authorizations
+        .get_mut(authorization_id)
+        .ok_or_else(|| Problem::not_found(format!("unknown authorization: {authorization_id}")))?;
+    if authorization.account_id != account_id {
+        return Err(Problem::unauthorized(
+            "the authorization belongs to another account",
+        ))
+    }
+    Ok(authorization)
+}
+
+fn find_challenge<'a>(
+    authorization: &'a mut Authorization,
+    challenge_type: &str,
+) -> Result<&'a mut Challenge, Problem> {
+    authorization
+        .challenges
+        .iter_mut()
+        .find(|challenge| challenge.kind.as_str() == challenge_type)
+        .ok_or_else(|| Problem::not_found(format!("unknown challenge: {challenge_type}")))
+}
+
+/// Update the status of a pending or ready order from that of its authorizations
+fn refresh_order(order: &mut Order, authorizations: &HashMap<String, Authorization>) {
+    if order.status == Status::Pending {
+        let statuses = order
+            .authorization_ids
+            .iter()
+            .map(|id| authorizations.get(id).map_or(Status::Invalid, |a| a.status))
+            .collect::<Vec<_>>();
+        if statuses
+            .iter()
+            .any(|status| *status != Status::Pending && *status != Status::Valid)
+        {
+            order.status = Status::Invalid;
+        } else if statuses.iter().all(|status| *status == Status::Valid) {
+            order.status = Status::Ready;
+        }
+    }
+    if matches!(order.status, Status::Pending | Status::Ready) && order.expires < Utc::now() {
+        order.status = Status::Invalid;
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hickory_resolver::TokioAsyncResolver;
+
+use crate::{kms_error, result::KResult};
+
+/// Fetch the proofs published by the ACME clients to answer the challenges.
+///
+/// The validator only retrieves what is published for a domain:
+/// the ACME server compares it to the expected key authorization.
+#[async_trait]
+pub trait ChallengeValidator: Send + Sync {
+    /// The content served at `http://<domain>/.well-known/acme-challenge/<token>` (HTTP-01)
+    async fn http_01(&self, domain: &str, token: &str) -> KResult<String>;
+
+    /// The TXT records of `_acme-challenge.<domain>` (DNS-01)
+    async fn dns_01(&self, domain: &str) -> KResult<Vec<String>>;
+}
+
+/// The validator querying the domains over the network,
+/// using the DNS resolvers configured on the system
+pub struct NetworkValidator {
+    http_client: reqwest::Client,
+    resolver: TokioAsyncResolver,
+}
+
+impl NetworkValidator {
+    pub fn instantiate() -> KResult<Self> {
+        Ok(Self {
+            http_client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .map_err(|e| kms_error!("ACME: cannot build the HTTP-01 client: {e}"))?,
+            resolver: TokioAsyncResolver::tokio_from_system_conf()
+                .map_err(|e| kms_error!("ACME: cannot build the DNS-01 resolver: {e}"))?,
+        })
+    }
+}
+
+#[async_trait]
+impl ChallengeValidator for NetworkValidator {
+    async fn http_01(&self, domain: &str, token: &str) -> KResult<String> {
+        let url = format!("http://{domain}/.well-known/acme-challenge/{token}");
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| kms_error!("cannot fetch {url}: {e}"))?;
+        if !response.status().is_success() {
+            return Err(kms_error!(
+                "fetching {url} returned the status {}",
+                response.status()
+            ))
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| kms_error!("cannot read {url}: {e}"))
+    }
+
+    async fn dns_01(&self, domain: &str) -> KResult<Vec<String>> {
+        let name = format!("_acme-challenge.{domain}.");
+        let lookup = self
+            .resolver
+            .txt_lookup(name.as_str())
+            .await
+            .map_err(|e| kms_error!("cannot resolve the TXT records of {name}: {e}"))?;
+        Ok(lookup
+            .iter()
+            .map(|txt| {
+                txt.txt_data()
+                    .iter()
+                    .map(|data| String::from_utf8_lossy(data))
+                    .collect::<String>()
+            })
+            .collect())
+    }
+}
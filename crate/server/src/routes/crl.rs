@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use actix_web::{
+    get,
+    web::{Data, Path},
+    HttpRequest, HttpResponse,
+};
+use http::header;
+use tracing::info;
+
+use crate::{database::KMSServer, result::KResult};
+
+/// Serve the DER encoded CRL of the certificates issued by the given issuer certificate
+#[get("/{issuer_certificate_id}")]
+pub async fn get_crl(
+    req: HttpRequest,
+    issuer_certificate_id: Path<String>,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<HttpResponse> {
+    let issuer_certificate_id = issuer_certificate_id.into_inner();
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("GET /crl/{issuer_certificate_id} {user}");
+
+    let crl = kms
+        .crl(&issuer_certificate_id, &user, database_params.as_ref())
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "application/pkix-crl"))
+        .body(crl))
+}
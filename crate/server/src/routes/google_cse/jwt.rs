@@ -140,23 +140,22 @@ pub struct GoogleCseConfig {
     pub kacls_url: String,
 }
 
-/// Validate the authentication and the authorization tokens and return the calling user
-/// See [doc](https://developers.google.com/workspace/cse/guides/encrypt-and-decrypt-data?hl=en)
-pub async fn validate_tokens(
-    authentication_token: &str,
-    authorization_token: &str,
-    cse_config: &Option<GoogleCseConfig>,
-    application: &str,
-    roles: Option<&[&str]>,
-) -> KResult<String> {
-    let cse_config = cse_config.as_ref().ok_or_else(|| {
+/// The Google CSE configuration, which must be set to serve the CSE endpoints
+fn cse_config(cse_config: &Option<GoogleCseConfig>) -> KResult<&GoogleCseConfig> {
+    cse_config.as_ref().ok_or_else(|| {
         KmsError::ServerError(
             "JWT authentication and authorization configurations for Google CSE are not set"
                 .to_string(),
         )
-    })?;
+    })
+}
 
-    // validate authentication token
+/// Decode the authentication token with the first identity provider that accepts it
+/// and return the authenticated email
+fn decode_authentication_token(
+    authentication_token: &str,
+    cse_config: &GoogleCseConfig,
+) -> KResult<String> {
     let mut decoded_token = None;
     for idp_config in cse_config.authentication.iter() {
         if let Ok(token) = idp_config.decode_authentication_token(authentication_token) {
@@ -173,6 +172,20 @@ pub async fn validate_tokens(
 
     tracing::trace!("authentication token: {authentication_token:?}");
 
+    authentication_token.email.ok_or_else(|| {
+        KmsError::Unauthorized("Authentication token should contain an email".to_string())
+    })
+}
+
+/// Decode the authorization token issued by Google for the `application`
+/// and check its role and perimeter
+async fn decode_authorization_token(
+    authorization_token: &str,
+    cse_config: &GoogleCseConfig,
+    application: &str,
+    roles: Option<&[&str]>,
+    perimeters: &[String],
+) -> KResult<UserClaim> {
     let jwt_config = cse_config.authorization.get(application).ok_or_else(|| {
         KmsError::NotSupported(format!(
             "no JWT config available for application: {application} "
@@ -183,35 +196,83 @@ pub async fn validate_tokens(
     tracing::trace!("authorization token: {authorization_token:?}");
     tracing::trace!("authorization token headers: {jwt_headers:?}");
 
+    if let Some(roles) = roles {
+        let role = authorization_token.role.as_ref().ok_or_else(|| {
+            KmsError::Unauthorized("Authorization token should contain a role".to_string())
+        })?;
+        kms_ensure!(
+            roles.contains(&role.as_str()),
+            KmsError::Unauthorized(format!(
+                "Authorization token should contain one of the roles {roles:?}, got: {role}"
+            ))
+        );
+    }
+
+    check_perimeter(
+        authorization_token
+            .perimeter_id
+            .as_deref()
+            .unwrap_or_default(),
+        perimeters,
+    )?;
+
+    Ok(authorization_token)
+}
+
+/// Check that the perimeter of a resource is one of the `perimeters` served by this KACLS,
+/// configured with `google_cse_perimeters`.
+///
+/// An empty perimeter id means no perimeter: such resources are always served.
+/// See [doc](https://developers.google.com/workspace/cse/reference/authorization-tokens)
+pub fn check_perimeter(perimeter_id: &str, perimeters: &[String]) -> KResult<()> {
+    kms_ensure!(
+        perimeter_id.is_empty() || perimeters.iter().any(|p| p == perimeter_id),
+        KmsError::Unauthorized(format!(
+            "the perimeter {perimeter_id} is not served by this KACLS"
+        ))
+    );
+    Ok(())
+}
+
+/// Validate the authentication and the authorization tokens
+/// and return the calling user and the claims of the authorization token
+/// See [doc](https://developers.google.com/workspace/cse/guides/encrypt-and-decrypt-data?hl=en)
+pub async fn validate_tokens(
+    authentication_token: &str,
+    authorization_token: &str,
+    cse_config: &Option<GoogleCseConfig>,
+    application: &str,
+    roles: Option<&[&str]>,
+    perimeters: &[String],
+) -> KResult<(String, UserClaim)> {
+    let cse_config = cse_config(cse_config)?;
+
+    // validate authentication token
+    let authentication_email = decode_authentication_token(authentication_token, cse_config)?;
+
+    let authorization_token = decode_authorization_token(
+        authorization_token,
+        cse_config,
+        application,
+        roles,
+        perimeters,
+    )
+    .await?;
+
     // The emails should match (case insensitive)
-    let authentication_email = authentication_token.email.ok_or_else(|| {
-        KmsError::Unauthorized("Authentication token should contain an email".to_string())
-    })?;
-    let authorization_email = authorization_token.email.ok_or_else(|| {
+    let authorization_email = authorization_token.email.as_ref().ok_or_else(|| {
         KmsError::Unauthorized("Authorization token should contain an email".to_string())
     })?;
     kms_ensure!(
-        authorization_email == authentication_email,
+        *authorization_email == authentication_email,
         KmsError::Unauthorized(
             "Authentication and authorization emails in tokens do not match".to_string()
         )
     );
 
-    if let Some(roles) = roles {
-        let role = authorization_token.role.ok_or_else(|| {
-            KmsError::Unauthorized("Authorization token should contain a role".to_string())
-        })?;
-        kms_ensure!(
-            roles.contains(&role.as_str()),
-            KmsError::Unauthorized(
-                "Authorization token should contain a role of writer or owner".to_string()
-            )
-        );
-    }
-
-    if let Some(kacls_url) = authorization_token.kacls_url {
+    if let Some(kacls_url) = &authorization_token.kacls_url {
         kms_ensure!(
-            kacls_url == cse_config.kacls_url,
+            *kacls_url == cse_config.kacls_url,
             KmsError::Unauthorized(format!(
                 "KACLS Urls should match: expected: {}, got: {} ",
                 cse_config.kacls_url, kacls_url
@@ -221,9 +282,72 @@ pub async fn validate_tokens(
 
     tracing::debug!("Google CSE request authorized for user {authentication_email}");
 
+    Ok((authentication_email, authorization_token))
+}
+
+/// Validate the authentication token of a privileged request and return the calling user.
+///
+/// Privileged requests are not issued by Google but by an administrator
+/// of the domain, authenticated by one of the configured identity providers,
+/// hence they carry no authorization token.
+/// See [doc](https://developers.google.com/workspace/cse/reference/privileged-unwrap)
+pub fn validate_authentication_token(
+    authentication_token: &str,
+    cse_config: &Option<GoogleCseConfig>,
+) -> KResult<String> {
+    let cse_config = cse_config(cse_config)?;
+    let authentication_email = decode_authentication_token(authentication_token, cse_config)?;
+
+    tracing::debug!("Google CSE privileged request authenticated for user {authentication_email}");
+
     Ok(authentication_email)
 }
 
+/// Validate an authorization token sent without an authentication token
+/// (e.g. for `rewrap` or `digest`) and return its claims.
+///
+/// The KACLS URL of the token must be `kacls_url`, or the URL of this KACLS
+/// when `kacls_url` is `None`.
+pub async fn validate_authorization_token(
+    authorization_token: &str,
+    cse_config: &Option<GoogleCseConfig>,
+    application: &str,
+    roles: Option<&[&str]>,
+    kacls_url: Option<&str>,
+    perimeters: &[String],
+) -> KResult<UserClaim> {
+    let cse_config = cse_config(cse_config)?;
+    let authorization_token = decode_authorization_token(
+        authorization_token,
+        cse_config,
+        application,
+        roles,
+        perimeters,
+    )
+    .await?;
+
+    kms_ensure!(
+        authorization_token.email.is_some(),
+        KmsError::Unauthorized("Authorization token should contain an email".to_string())
+    );
+    let expected_kacls_url = kacls_url.unwrap_or(&cse_config.kacls_url);
+    if let Some(kacls_url) = &authorization_token.kacls_url {
+        kms_ensure!(
+            kacls_url == expected_kacls_url,
+            KmsError::Unauthorized(format!(
+                "KACLS Urls should match: expected: {expected_kacls_url}, got: {kacls_url} "
+            ))
+        );
+    }
+
+    tracing::debug!(
+        "Google CSE request authorized for user {:?}",
+        authorization_token.email
+    );
+
+    Ok(authorization_token)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -236,7 +360,8 @@ mod tests {
         routes::google_cse::{
             self,
             jwt::{
-                decode_jwt_authorization_token, jwt_authorization_config, JWKS_URI, JWT_ISSUER_URI,
+                check_perimeter, decode_jwt_authorization_token, jwt_authorization_config,
+                JWKS_URI, JWT_ISSUER_URI,
             },
             operations::WrapRequest,
         },
@@ -333,4 +458,16 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_check_perimeter() {
+        // without configured perimeters, only the resources outside of any perimeter are served
+        assert!(check_perimeter("", &[]).is_ok());
+        assert!(check_perimeter("eu", &[]).is_err());
+
+        let perimeters = vec!["eu".to_owned()];
+        assert!(check_perimeter("", &perimeters).is_ok());
+        assert!(check_perimeter("eu", &perimeters).is_ok());
+        assert!(check_perimeter("us", &perimeters).is_err());
+    }
 }
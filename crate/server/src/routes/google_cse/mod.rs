@@ -5,13 +5,14 @@ use actix_web::{
     web::{Data, Json},
     HttpRequest, HttpResponse, ResponseError,
 };
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tracing::{debug, info, trace};
 
 use crate::{error::KmsError, result::KResult, KMSServer};
 
 mod jwt;
 pub mod operations;
+mod wrapping_keys;
 
 pub use jwt::{jwt_authorization_config, list_jwks_uri, GoogleCseConfig};
 
@@ -60,115 +61,167 @@ pub async fn get_status(
     Ok(Json(operations::get_status()))
 }
 
-#[derive(Deserialize, Debug)]
-pub struct DigestRequest {
-    pub authorization: String,
-    pub reason: String,
-    pub wrapped_key: String,
-}
+/// Unwraps a Data Encryption Key (DEK) and returns its resource key hash.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/digest)
 #[post("/digest")]
 pub async fn digest(
-    _req_http: HttpRequest,
-    _request: Json<DigestRequest>,
-    _cse_config: Data<Option<GoogleCseConfig>>,
-    _kms: Data<Arc<KMSServer>>,
+    req_http: HttpRequest,
+    request: Json<operations::DigestRequest>,
+    cse_config: Data<Option<GoogleCseConfig>>,
+    kms: Data<Arc<KMSServer>>,
 ) -> HttpResponse {
-    info!("POST /google_cse/digest: not implemented yet");
-    HttpResponse::Ok().finish()
-}
+    info!("POST /google_cse/digest");
+
+    // unwrap all calls parameters
+    let request = request.into_inner();
+    trace!("request: {request:?}");
+    let kms = kms.into_inner();
+    let cse_config = cse_config.into_inner();
 
-#[derive(Deserialize, Debug)]
-pub struct PrivilegedPrivateKeyDecryptRequest {
-    pub authentication: String,
-    pub algorithm: String,
-    pub encrypted_data_encryption_key: String,
-    pub rsa_oaep_label: String,
-    pub reason: String,
-    pub spki_hash: String,
-    pub spki_hash_algorithm: String,
-    pub wrapped_private_key: String,
+    match operations::digest(req_http, request, &cse_config, &kms)
+        .await
+        .map(Json)
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => CseErrorReply::from(e).into(),
+    }
 }
+
+/// Unwraps a wrapped private key on behalf of an administrator and then decrypts
+/// the content encryption key that is encrypted to the public key.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/privileged-private-key-decrypt)
 #[post("/privilegedprivatekeydecrypt")]
 pub async fn privilegedprivatekeydecrypt(
-    _req_http: HttpRequest,
-    _request: Json<PrivilegedPrivateKeyDecryptRequest>,
-    _cse_config: Data<Option<GoogleCseConfig>>,
-    _kms: Data<Arc<KMSServer>>,
+    req_http: HttpRequest,
+    request: Json<operations::PrivilegedPrivateKeyDecryptRequest>,
+    cse_config: Data<Option<GoogleCseConfig>>,
+    kms: Data<Arc<KMSServer>>,
 ) -> HttpResponse {
-    info!("POST /google_cse/privilegedprivatekeydecrypt: not implemented yet");
-    HttpResponse::Ok().finish()
-}
+    info!("POST /google_cse/privilegedprivatekeydecrypt");
+
+    // unwrap all calls parameters
+    let request = request.into_inner();
+    trace!("request: {request:?}");
+    let kms = kms.into_inner();
+    let cse_config = cse_config.into_inner();
 
-#[derive(Deserialize, Debug)]
-pub struct PrivilegedUnwrapRequest {
-    pub authentication: String,
-    pub reason: String,
-    pub resource_name: String,
-    pub wrapped_key: String,
+    match operations::privileged_private_key_decrypt(req_http, request, &cse_config, &kms)
+        .await
+        .map(Json)
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => CseErrorReply::from(e).into(),
+    }
 }
+
+/// Unwraps a Data Encryption Key (DEK) on behalf of an administrator.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/privileged-unwrap)
 #[post("/privilegedunwrap")]
 pub async fn privilegedunwrap(
-    _req_http: HttpRequest,
-    _request: Json<PrivilegedUnwrapRequest>,
-    _cse_config: Data<Option<GoogleCseConfig>>,
-    _kms: Data<Arc<KMSServer>>,
+    req_http: HttpRequest,
+    request: Json<operations::PrivilegedUnwrapRequest>,
+    cse_config: Data<Option<GoogleCseConfig>>,
+    kms: Data<Arc<KMSServer>>,
 ) -> HttpResponse {
-    info!("POST /google_cse/privilegedunwrap: not implemented yet");
-    HttpResponse::Ok().finish()
-}
+    info!("POST /google_cse/privilegedunwrap");
+
+    // unwrap all calls parameters
+    let request = request.into_inner();
+    trace!("request: {request:?}");
+    let kms = kms.into_inner();
+    let cse_config = cse_config.into_inner();
 
-#[derive(Deserialize, Debug)]
-pub struct PrivilegedWrapRequest {
-    pub authentication: String,
-    pub key: String,
-    pub perimeter_id: String,
-    pub reason: String,
-    pub resource_name: String,
+    match operations::privileged_unwrap(req_http, request, &cse_config, &kms)
+        .await
+        .map(Json)
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => CseErrorReply::from(e).into(),
+    }
 }
+
+/// Wraps a Data Encryption Key (DEK) on behalf of an administrator.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/privileged-wrap)
 #[post("/privilegedwrap")]
 pub async fn privilegedwrap(
-    _req_http: HttpRequest,
-    _request: Json<PrivilegedWrapRequest>,
-    _cse_config: Data<Option<GoogleCseConfig>>,
-    _kms: Data<Arc<KMSServer>>,
+    req_http: HttpRequest,
+    request: Json<operations::PrivilegedWrapRequest>,
+    cse_config: Data<Option<GoogleCseConfig>>,
+    kms: Data<Arc<KMSServer>>,
 ) -> HttpResponse {
-    info!("POST /google_cse/privilegedwrap: not implemented yet");
-    HttpResponse::Ok().finish()
-}
+    info!("POST /google_cse/privilegedwrap");
+
+    // unwrap all calls parameters
+    let request = request.into_inner();
+    trace!("request: {request:?}");
+    let kms = kms.into_inner();
+    let cse_config = cse_config.into_inner();
 
-#[derive(Deserialize, Debug)]
-pub struct RewrapRequest {
-    pub authorization: String,
-    pub original_kacls_url: String,
-    pub reason: String,
-    pub wrapped_key: String,
+    match operations::privileged_wrap(req_http, request, &cse_config, &kms)
+        .await
+        .map(Json)
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => CseErrorReply::from(e).into(),
+    }
 }
+
+/// Re-wraps with this KACLS a Data Encryption Key (DEK) wrapped by another KACLS.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/rewrap)
 #[post("/rewrap")]
 pub async fn rewrap(
-    _req_http: HttpRequest,
-    _request: Json<RewrapRequest>,
-    _cse_config: Data<Option<GoogleCseConfig>>,
-    _kms: Data<Arc<KMSServer>>,
+    req_http: HttpRequest,
+    request: Json<operations::RewrapRequest>,
+    cse_config: Data<Option<GoogleCseConfig>>,
+    kms: Data<Arc<KMSServer>>,
 ) -> HttpResponse {
-    info!("POST /google_cse/rewrap: not implemented yet");
-    HttpResponse::Ok().finish()
-}
+    info!("POST /google_cse/rewrap");
+
+    // unwrap all calls parameters
+    let request = request.into_inner();
+    trace!("request: {request:?}");
+    let kms = kms.into_inner();
+    let cse_config = cse_config.into_inner();
 
-#[derive(Deserialize, Debug)]
-pub struct WrapPrivateKeyRequest {
-    pub authentication: String,
-    pub perimeter_id: String,
-    pub private_key: String,
+    match operations::rewrap(req_http, request, &cse_config, &kms)
+        .await
+        .map(Json)
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => CseErrorReply::from(e).into(),
+    }
 }
+
+/// Wraps an S/MIME private key for Gmail.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/wrap-private-key)
 #[post("/wrapprivatekey")]
 pub async fn wrapprivatekey(
-    _req_http: HttpRequest,
-    _request: Json<WrapPrivateKeyRequest>,
-    _cse_config: Data<Option<GoogleCseConfig>>,
-    _kms: Data<Arc<KMSServer>>,
+    req_http: HttpRequest,
+    request: Json<operations::WrapPrivateKeyRequest>,
+    cse_config: Data<Option<GoogleCseConfig>>,
+    kms: Data<Arc<KMSServer>>,
 ) -> HttpResponse {
-    info!("POST /google_cse/wrapprivatekey: not implemented yet");
-    HttpResponse::Ok().finish()
+    info!("POST /google_cse/wrapprivatekey");
+
+    // unwrap all calls parameters
+    let request = request.into_inner();
+    trace!("request: {request:?}");
+    let kms = kms.into_inner();
+    let cse_config = cse_config.into_inner();
+
+    match operations::wrap_private_key(req_http, request, &cse_config, &kms)
+        .await
+        .map(Json)
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => CseErrorReply::from(e).into(),
+    }
 }
 
 /// Returns encrypted Data Encryption Key (DEK) and associated data.
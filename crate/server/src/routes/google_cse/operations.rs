@@ -3,18 +3,16 @@ use std::sync::Arc;
 use actix_web::HttpRequest;
 use base64::{engine::general_purpose, Engine};
 use clap::crate_version;
-use cosmian_kmip::{
-    crypto::symmetric::create_symmetric_key_kmip_object,
-    kmip::{
-        kmip_data_structures::{KeyWrappingData, KeyWrappingSpecification},
-        kmip_types::{self, CryptographicAlgorithm, EncodingOption, UniqueIdentifier},
-    },
-};
+use cosmian_kmip::kmip::kmip_types::EncodingOption;
 use openssl::{
+    hash::MessageDigest,
     md::Md,
+    memcmp,
     pkey::{PKey, Private},
     pkey_ctx::PkeyCtx,
     rsa::{Padding, Rsa},
+    sha::sha256,
+    sign::Signer,
 };
 use serde::{Deserialize, Serialize};
 use tracing::debug;
@@ -22,13 +20,18 @@ use zeroize::Zeroizing;
 
 use super::GoogleCseConfig;
 use crate::{
-    core::{
-        extra_database_params::ExtraDatabaseParams,
-        operations::{unwrap_key, wrap_key},
-    },
-    kms_ensure,
+    core::extra_database_params::ExtraDatabaseParams,
+    error::KmsError,
+    kms_bail, kms_ensure,
+    middlewares::UserClaim,
     result::KResult,
-    routes::google_cse::jwt::validate_tokens,
+    routes::google_cse::{
+        jwt::{
+            check_perimeter, validate_authentication_token, validate_authorization_token,
+            validate_tokens,
+        },
+        wrapping_keys::{cse_unwrap, cse_wrap},
+    },
     KMSServer,
 };
 
@@ -102,47 +105,31 @@ pub async fn wrap(
     let roles = &["writer", "upgrader"];
 
     debug!("wrap: validate_tokens");
-    let user = validate_tokens(
+    let (_, authorization_token) = validate_tokens(
         &wrap_request.authentication,
         &wrap_request.authorization,
         cse_config,
         application,
         Some(roles),
+        &kms.params.google_cse_perimeters,
     )
     .await?;
-
-    // decode the DEK and create a KMIP object from the key bytes
-    debug!("wrap: create KMIP dek object");
-    let mut dek = create_symmetric_key_kmip_object(
-        &general_purpose::STANDARD.decode(&wrap_request.key)?,
-        CryptographicAlgorithm::AES,
-    );
-
-    debug!("wrap: wrap dek");
-    wrap_key(
-        dek.key_block_mut()?,
-        &KeyWrappingSpecification {
-            wrapping_method: kmip_types::WrappingMethod::Encrypt,
-            encoding_option: Some(EncodingOption::NoEncoding),
-            encryption_key_information: Some(kmip_types::EncryptionKeyInformation {
-                unique_identifier: UniqueIdentifier::TextString("[\"google_cse\"]".to_string()),
-                cryptographic_parameters: Some(Box::default()),
-            }),
-            ..Default::default()
-        },
-        kms,
+    let (user, resource_name, perimeter_id) = authorization_claims(authorization_token)?;
+
+    // decode the DEK and wrap it with the Google CSE key, bound to the resource
+    let dek = Zeroizing::new(general_purpose::STANDARD.decode(&wrap_request.key)?);
+    let (wrapped_key, _) = cse_dek_wrap(
+        &dek,
+        &resource_name,
+        &perimeter_id,
         &user,
+        kms,
         database_params.as_ref(),
     )
     .await?;
 
-    // re-extract the bytes from the key
-    let wrapped_dek = dek.key_block()?.key_bytes()?;
-
     debug!("wrap: exiting with success");
-    Ok(WrapResponse {
-        wrapped_key: general_purpose::STANDARD.encode(wrapped_dek),
-    })
+    Ok(WrapResponse { wrapped_key })
 }
 
 #[derive(Deserialize, Debug)]
@@ -181,45 +168,29 @@ pub async fn unwrap(
     // the possible roles to unwrap a key
     let roles = &["writer", "reader"];
 
-    let user = validate_tokens(
+    let (_, authorization_token) = validate_tokens(
         &unwrap_request.authentication,
         &unwrap_request.authorization,
         cse_config,
         application,
         Some(roles),
+        &kms.params.google_cse_perimeters,
     )
     .await?;
     debug!("unwrap: validate_tokens");
+    let (user, resource_name, perimeter_id) = authorization_claims(authorization_token)?;
 
-    // Base 64 decode the encrypted DEK and create a wrapped KMIP object from the key bytes
-    debug!("unwrap: create wrapped_dek KMIP object");
-    let mut wrapped_dek = create_symmetric_key_kmip_object(
-        &general_purpose::STANDARD.decode(&unwrap_request.wrapped_key)?,
-        CryptographicAlgorithm::AES,
-    );
-    // add key wrapping parameters to the wrapped key
-    wrapped_dek.key_block_mut()?.key_wrapping_data = Some(Box::new(KeyWrappingData {
-        wrapping_method: kmip_types::WrappingMethod::Encrypt,
-        encryption_key_information: Some(kmip_types::EncryptionKeyInformation {
-            unique_identifier: UniqueIdentifier::TextString("[\"google_cse\"]".to_string()),
-            cryptographic_parameters: None,
-        }),
-        encoding_option: Some(EncodingOption::NoEncoding),
-        ..Default::default()
-    }));
-
-    debug!("unwrap: unwrap key");
-    unwrap_key(
-        wrapped_dek.key_block_mut()?,
-        kms,
+    // unwrap the DEK with the Google CSE key, for the resource it was wrapped for
+    let (dek, _) = cse_dek_unwrap(
+        &unwrap_request.wrapped_key,
+        &resource_name,
+        Some(&perimeter_id),
         &user,
+        kms,
         database_params.as_ref(),
     )
     .await?;
 
-    // re-extract the bytes from the key
-    let dek = wrapped_dek.key_block()?.key_bytes()?;
-
     debug!("unwrap: exiting with success");
     Ok(UnwrapResponse {
         key: general_purpose::STANDARD.encode(dek),
@@ -273,12 +244,13 @@ pub async fn private_key_sign(
     let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
 
     debug!("private_key_sign: validate_tokens");
-    let user = validate_tokens(
+    let (user, _) = validate_tokens(
         &request.authentication,
         &request.authorization,
         cse_config,
         "gmail",
         None,
+        &kms.params.google_cse_perimeters,
     )
     .await?;
 
@@ -361,12 +333,13 @@ pub async fn private_key_decrypt(
     let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
 
     debug!("private_key_decrypt: validate_tokens");
-    let user = validate_tokens(
+    let (user, _) = validate_tokens(
         &request.authentication,
         &request.authorization,
         cse_config,
         "gmail",
         None,
+        &kms.params.google_cse_perimeters,
     )
     .await?;
 
@@ -386,25 +359,472 @@ pub async fn private_key_decrypt(
     // Decrypt with the unwrapped RSA private key
     debug!("private_key_decrypt: from_rsa");
     let private_key = PKey::from_rsa(Rsa::<Private>::private_key_from_der(&dek)?)?;
+    let data_encryption_key = rsa_decrypt_dek(
+        &private_key,
+        &encrypted_dek,
+        request.rsa_oaep_label.as_deref(),
+    )?;
+
+    debug!("private_key_decrypt: exiting with success");
+    Ok(PrivateKeyDecryptResponse {
+        data_encryption_key: general_purpose::STANDARD.encode(data_encryption_key),
+    })
+}
 
-    // Perform RSA PKCS1 decryption.
-    let mut ctx = PkeyCtx::new(&private_key)?;
+/// Decrypt a Data Encryption Key (DEK) encrypted with the RSA public key
+/// matching `private_key`, using PKCS#1 v1.5 or, when a label is provided, OAEP
+fn rsa_decrypt_dek(
+    private_key: &PKey<Private>,
+    encrypted_dek: &[u8],
+    rsa_oaep_label: Option<&str>,
+) -> KResult<Zeroizing<Vec<u8>>> {
+    let mut ctx = PkeyCtx::new(private_key)?;
     ctx.decrypt_init()?;
     ctx.set_rsa_padding(Padding::PKCS1)?;
-    if let Some(label) = request.rsa_oaep_label {
+    if let Some(label) = rsa_oaep_label {
         ctx.set_rsa_oaep_label(label.as_bytes())?;
         ctx.set_rsa_padding(Padding::PKCS1_OAEP)?;
     }
-    let allocation_size = ctx.decrypt(&encrypted_dek, None)?;
-    debug!("privatekeydecrypt: allocation_size: {allocation_size}");
-    let mut plaintext = vec![0_u8; allocation_size];
-    let decrypt_size = ctx.decrypt(&encrypted_dek, Some(&mut *plaintext))?;
-
-    debug!("private_key_decrypt: exiting with success: decrypt_size: {decrypt_size}");
-    let response = PrivateKeyDecryptResponse {
-        data_encryption_key: general_purpose::STANDARD.encode(&plaintext[0..decrypt_size]),
+    let allocation_size = ctx.decrypt(encrypted_dek, None)?;
+    debug!("rsa_decrypt_dek: allocation_size: {allocation_size}");
+    let mut plaintext = Zeroizing::new(vec![0_u8; allocation_size]);
+    let decrypt_size = ctx.decrypt(encrypted_dek, Some(&mut *plaintext))?;
+    plaintext.truncate(decrypt_size);
+    Ok(plaintext)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DigestRequest {
+    pub authorization: String,
+    pub reason: String,
+    pub wrapped_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DigestResponse {
+    /// The base64-encoded resource key hash of the unwrapped DEK
+    pub resource_key_hash: String,
+}
+
+/// Unwraps a Data Encryption Key (DEK) and returns its resource key hash,
+/// so that Google can check that the DEK is bound to the resource and perimeter
+/// of the authorization token.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/digest)
+pub async fn digest(
+    req_http: HttpRequest,
+    request: DigestRequest,
+    cse_config: &Arc<Option<GoogleCseConfig>>,
+    kms: &Arc<KMSServer>,
+) -> KResult<DigestResponse> {
+    debug!("digest: entering");
+    let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
+
+    let application = if request.reason.contains("Meet") {
+        "meet"
+    } else {
+        "drive"
     };
-    Ok(response)
+
+    debug!("digest: validate_authorization_token");
+    let authorization_token = validate_authorization_token(
+        &request.authorization,
+        cse_config,
+        application,
+        Some(&["verifier"]),
+        None,
+        &kms.params.google_cse_perimeters,
+    )
+    .await?;
+    let (user, resource_name, perimeter_id) = authorization_claims(authorization_token)?;
+
+    let (_, resource_key_hash) = cse_dek_unwrap(
+        &request.wrapped_key,
+        &resource_name,
+        Some(&perimeter_id),
+        &user,
+        kms,
+        database_params.as_ref(),
+    )
+    .await?;
+
+    debug!("digest: exiting with success");
+    Ok(DigestResponse { resource_key_hash })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrivilegedUnwrapRequest {
+    pub authentication: String,
+    pub reason: String,
+    /// The identifier of the object encrypted by the DEK. Max size: 128 bytes.
+    pub resource_name: String,
+    pub wrapped_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrivilegedUnwrapResponse {
+    pub key: String,
+}
+
+/// Unwraps a Data Encryption Key (DEK) on behalf of an administrator
+/// (e.g. to decrypt a Google Takeout export or to migrate keys),
+/// without any authorization token from Google.
+///
+/// The administrator must be one of the configured `google_cse_privileged_users`
+/// and the DEK must have been wrapped for the resource `resource_name`.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/privileged-unwrap)
+pub async fn privileged_unwrap(
+    req_http: HttpRequest,
+    request: PrivilegedUnwrapRequest,
+    cse_config: &Arc<Option<GoogleCseConfig>>,
+    kms: &Arc<KMSServer>,
+) -> KResult<PrivilegedUnwrapResponse> {
+    debug!("privileged_unwrap: entering");
+    let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
+
+    debug!("privileged_unwrap: validate_authentication_token");
+    let user = validate_authentication_token(&request.authentication, cse_config)?;
+    check_privileged_user(&user, kms)?;
+    check_resource_name(&request.resource_name)?;
+    tracing::info!(
+        "privileged unwrap of resource {} by {user}, reason: {}",
+        request.resource_name,
+        request.reason
+    );
+
+    let (dek, _) = cse_dek_unwrap(
+        &request.wrapped_key,
+        &request.resource_name,
+        None,
+        &user,
+        kms,
+        database_params.as_ref(),
+    )
+    .await?;
+
+    debug!("privileged_unwrap: exiting with success");
+    Ok(PrivilegedUnwrapResponse {
+        key: general_purpose::STANDARD.encode(dek),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrivilegedWrapRequest {
+    pub authentication: String,
+    pub key: String,
+    pub perimeter_id: String,
+    pub reason: String,
+    /// The identifier of the object encrypted by the DEK. Max size: 128 bytes.
+    pub resource_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrivilegedWrapResponse {
+    pub wrapped_key: String,
+}
+
+/// Wraps a Data Encryption Key (DEK) on behalf of an administrator
+/// (e.g. to import encrypted data into Google Drive),
+/// without any authorization token from Google.
+///
+/// The administrator must be one of the configured `google_cse_privileged_users`.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/privileged-wrap)
+pub async fn privileged_wrap(
+    req_http: HttpRequest,
+    request: PrivilegedWrapRequest,
+    cse_config: &Arc<Option<GoogleCseConfig>>,
+    kms: &Arc<KMSServer>,
+) -> KResult<PrivilegedWrapResponse> {
+    debug!("privileged_wrap: entering");
+    let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
+
+    debug!("privileged_wrap: validate_authentication_token");
+    let user = validate_authentication_token(&request.authentication, cse_config)?;
+    check_privileged_user(&user, kms)?;
+    check_resource_name(&request.resource_name)?;
+    check_perimeter(&request.perimeter_id, &kms.params.google_cse_perimeters)?;
+    tracing::info!(
+        "privileged wrap of resource {} by {user}, reason: {}",
+        request.resource_name,
+        request.reason
+    );
+
+    let dek = Zeroizing::new(general_purpose::STANDARD.decode(&request.key)?);
+    let (wrapped_key, _) = cse_dek_wrap(
+        &dek,
+        &request.resource_name,
+        &request.perimeter_id,
+        &user,
+        kms,
+        database_params.as_ref(),
+    )
+    .await?;
+
+    debug!("privileged_wrap: exiting with success");
+    Ok(PrivilegedWrapResponse { wrapped_key })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RewrapRequest {
+    pub authorization: String,
+    /// The URL of the KACLS which originally wrapped the key
+    pub original_kacls_url: String,
+    pub reason: String,
+    pub wrapped_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RewrapResponse {
+    /// The base64-encoded resource key hash of the DEK
+    pub resource_key_hash: String,
+    /// The DEK wrapped by this KACLS
+    pub wrapped_key: String,
+}
+
+/// Unwraps a Data Encryption Key (DEK) wrapped by the KACLS at `original_kacls_url`
+/// and wraps it again with the current Google CSE key of this KACLS,
+/// e.g. when moving Drive files between KACLS instances.
+///
+/// The keys of the original KACLS must be held by this KMS.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/rewrap)
+pub async fn rewrap(
+    req_http: HttpRequest,
+    request: RewrapRequest,
+    cse_config: &Arc<Option<GoogleCseConfig>>,
+    kms: &Arc<KMSServer>,
+) -> KResult<RewrapResponse> {
+    debug!("rewrap: entering");
+    let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
+
+    // the authorization token is issued for the original KACLS
+    debug!("rewrap: validate_authorization_token");
+    let authorization_token = validate_authorization_token(
+        &request.authorization,
+        cse_config,
+        "drive",
+        Some(&["migrator"]),
+        Some(&request.original_kacls_url),
+        &kms.params.google_cse_perimeters,
+    )
+    .await?;
+    let (user, resource_name, perimeter_id) = authorization_claims(authorization_token)?;
+
+    let (dek, _) = cse_dek_unwrap(
+        &request.wrapped_key,
+        &resource_name,
+        Some(&perimeter_id),
+        &user,
+        kms,
+        database_params.as_ref(),
+    )
+    .await?;
+    let (wrapped_key, resource_key_hash) = cse_dek_wrap(
+        &dek,
+        &resource_name,
+        &perimeter_id,
+        &user,
+        kms,
+        database_params.as_ref(),
+    )
+    .await?;
+
+    debug!("rewrap: exiting with success");
+    Ok(RewrapResponse {
+        resource_key_hash,
+        wrapped_key,
+    })
+}
+
+/// Request to perform a privileged `encryption key` decryption
+///
+/// The `encrypted_data_encryption_key` will be decrypted with the
+/// `wrapped_private_key` (once decrypted), after checking that the
+/// private key matches the public key which hash is `spki_hash`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrivilegedPrivateKeyDecryptRequest {
+    pub authentication: String,
+    /// The algorithm that was used to encrypt the Data Encryption Key (DEK) in envelope encryption.
+    pub algorithm: String,
+    /// Base64-encoded encrypted content encryption key, which is encrypted with the public key associated with the private key. Max size: 1 KB.
+    pub encrypted_data_encryption_key: String,
+    /// Base64-encoded label L, if the algorithm is RSAES-OAEP. If the algorithm is not RSAES-OAEP, this field is ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsa_oaep_label: Option<String>,
+    /// A passthrough JSON string providing additional context about the operation. The JSON provided should be sanitized before being displayed. Max size: 1 KB.
+    pub reason: String,
+    /// Base64-encoded hash of the DER-encoded `SubjectPublicKeyInfo` of the public key.
+    pub spki_hash: String,
+    /// The algorithm used to compute `spki_hash`; only `SHA-256` is supported.
+    pub spki_hash_algorithm: String,
+    /// The base64-encoded wrapped private key. Max size: 8 KB.
+    pub wrapped_private_key: String,
+}
+
+/// Unwraps a wrapped private key on behalf of an administrator and then decrypts
+/// the content encryption key that is encrypted to the public key.
+///
+/// The administrator must be one of the configured `google_cse_privileged_users`.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/privileged-private-key-decrypt)
+pub async fn privileged_private_key_decrypt(
+    req_http: HttpRequest,
+    request: PrivilegedPrivateKeyDecryptRequest,
+    cse_config: &Arc<Option<GoogleCseConfig>>,
+    kms: &Arc<KMSServer>,
+) -> KResult<PrivateKeyDecryptResponse> {
+    debug!("privileged_private_key_decrypt: entering");
+    let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
+
+    debug!("privileged_private_key_decrypt: validate_authentication_token");
+    let user = validate_authentication_token(&request.authentication, cse_config)?;
+    check_privileged_user(&user, kms)?;
+    tracing::info!(
+        "privileged private key decrypt by {user}, reason: {}",
+        request.reason
+    );
+
+    debug!("privileged_private_key_decrypt: check algorithms");
+    kms_ensure!(
+        request.algorithm == "RSA/ECB/PKCS1Padding",
+        "Only RSA/ECB/PKCS1Padding is supported"
+    );
+    kms_ensure!(
+        request.spki_hash_algorithm == "SHA-256",
+        "Only SHA-256 is supported to hash the SPKI"
+    );
+
+    let encrypted_dek = general_purpose::STANDARD.decode(&request.encrypted_data_encryption_key)?;
+
+    // Unwrap private key which has been previously wrapped using AES
+    let dek = cse_symmetric_unwrap(request.wrapped_private_key, user, kms, database_params).await?;
+    let private_key = PKey::from_rsa(Rsa::<Private>::private_key_from_der(&dek)?)?;
+
+    // the private key must match the public key the DEK was encrypted with
+    debug!("privileged_private_key_decrypt: check spki hash");
+    let spki_hash = general_purpose::STANDARD.encode(sha256(&private_key.public_key_to_der()?));
+    kms_ensure!(
+        spki_hash == request.spki_hash,
+        KmsError::InvalidRequest(
+            "the SPKI hash does not match the public key of the wrapped private key".to_string()
+        )
+    );
+
+    let data_encryption_key = rsa_decrypt_dek(
+        &private_key,
+        &encrypted_dek,
+        request.rsa_oaep_label.as_deref(),
+    )?;
+
+    debug!("privileged_private_key_decrypt: exiting with success");
+    Ok(PrivateKeyDecryptResponse {
+        data_encryption_key: general_purpose::STANDARD.encode(data_encryption_key),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WrapPrivateKeyRequest {
+    pub authentication: String,
+    pub perimeter_id: String,
+    /// The base64-encoded DER private key, in PKCS#8 or PKCS#1 format.
+    pub private_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WrapPrivateKeyResponse {
+    /// The base64-encoded wrapped private key, to upload as S/MIME key pair metadata
+    pub wrapped_private_key: String,
+}
+
+/// Wraps an S/MIME RSA private key with the Google CSE key, so that it can be
+/// uploaded to Gmail and used with `privatekeysign` and `privatekeydecrypt`.
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/wrap-private-key)
+pub async fn wrap_private_key(
+    req_http: HttpRequest,
+    request: WrapPrivateKeyRequest,
+    cse_config: &Arc<Option<GoogleCseConfig>>,
+    kms: &Arc<KMSServer>,
+) -> KResult<WrapPrivateKeyResponse> {
+    debug!("wrap_private_key: entering");
+    let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
+
+    debug!("wrap_private_key: validate_authentication_token");
+    let user = validate_authentication_token(&request.authentication, cse_config)?;
+    check_perimeter(&request.perimeter_id, &kms.params.google_cse_perimeters)?;
+
+    // the private key is stored as PKCS#1, as expected by the signature and decryption endpoints
+    let private_key = PKey::private_key_from_der(&Zeroizing::new(
+        general_purpose::STANDARD.decode(&request.private_key)?,
+    ))?;
+    let rsa_private_key = Zeroizing::new(private_key.rsa()?.private_key_to_der()?);
+
+    let wrapped_private_key =
+        cse_symmetric_wrap(&rsa_private_key, &user, kms, database_params).await?;
+
+    debug!("wrap_private_key: exiting with success");
+    Ok(WrapPrivateKeyResponse {
+        wrapped_private_key: general_purpose::STANDARD.encode(wrapped_private_key),
+    })
+}
+
+/// The user, resource name and perimeter id of a validated authorization token
+fn authorization_claims(authorization_token: UserClaim) -> KResult<(String, String, String)> {
+    let user = authorization_token.email.ok_or_else(|| {
+        KmsError::Unauthorized("Authorization token should contain an email".to_string())
+    })?;
+    let resource_name = authorization_token.resource_name.ok_or_else(|| {
+        KmsError::Unauthorized("Authorization token should contain a resource name".to_string())
+    })?;
+    Ok((
+        user,
+        resource_name,
+        authorization_token.perimeter_id.unwrap_or_default(),
+    ))
+}
+
+/// Check the resource name of a privileged request
+fn check_resource_name(resource_name: &str) -> KResult<()> {
+    kms_ensure!(
+        !resource_name.is_empty() && resource_name.len() <= 128,
+        KmsError::InvalidRequest(
+            "the resource name must be between 1 and 128 bytes long".to_string()
+        )
+    );
+    Ok(())
+}
+
+/// Check that the user of a privileged request is one of the configured
+/// `google_cse_privileged_users`: the other users may only use the Google CSE key
+/// with the authorization Google issues for each resource
+fn check_privileged_user(user: &str, kms: &KMSServer) -> KResult<()> {
+    kms_ensure!(
+        kms.params
+            .google_cse_privileged_users
+            .iter()
+            .any(|privileged_user| privileged_user == user),
+        KmsError::Unauthorized(format!(
+            "{user} is not allowed to perform privileged Google CSE operations"
+        ))
+    );
+    Ok(())
+}
+
+/// Compute the resource key hash binding a DEK to a resource and a perimeter:
+/// `base64(HMAC_SHA256(DEK, "ResourceKeyDigest:" + resource_name + ":" + perimeter_id))`
+///
+/// See [doc](https://developers.google.com/workspace/cse/reference/digest)
+pub fn compute_resource_key_hash(
+    resource_name: &str,
+    perimeter_id: &str,
+    dek: &[u8],
+) -> KResult<String> {
+    let key = PKey::hmac(dek)?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(format!("ResourceKeyDigest:{resource_name}:{perimeter_id}").as_bytes())?;
+    Ok(general_purpose::STANDARD.encode(signer.sign_to_vec()?))
 }
 
 /// Unwraps a private key
@@ -430,40 +850,164 @@ async fn cse_symmetric_unwrap(
     kms: &Arc<KMSServer>,
     database_params: Option<ExtraDatabaseParams>,
 ) -> KResult<Zeroizing<Vec<u8>>> {
-    debug!("cse_unwrap: decode base64 wrapped_dek");
-    // Base 64 decode the encrypted DEK and create a wrapped KMIP object from the key bytes
-    let mut wrapped_dek = create_symmetric_key_kmip_object(
-        &general_purpose::STANDARD.decode(&wrapped_private_key)?,
-        CryptographicAlgorithm::AES,
-    );
-
-    debug!("cse_unwrap: add key wrapping data substruct");
-    // add key wrapping parameters to the wrapped key
-    wrapped_dek.key_block_mut()?.key_wrapping_data = Some(
-        KeyWrappingData {
-            wrapping_method: kmip_types::WrappingMethod::Encrypt,
-            encryption_key_information: Some(kmip_types::EncryptionKeyInformation {
-                unique_identifier: UniqueIdentifier::TextString("google_cse".to_string()),
-                cryptographic_parameters: None,
-            }),
-            encoding_option: Some(EncodingOption::TTLVEncoding),
-            ..Default::default()
-        }
-        .into(),
-    );
+    debug!("cse_unwrap: decode base64 wrapped private key");
+    let wrapped_private_key = general_purpose::STANDARD.decode(&wrapped_private_key)?;
 
     debug!("cse_unwrap: unwrap private key");
-    unwrap_key(
-        wrapped_dek.key_block_mut()?,
-        kms,
+    cse_unwrap(
+        &wrapped_private_key,
+        EncodingOption::TTLVEncoding,
         &user,
+        kms,
         database_params.as_ref(),
     )
+    .await
+}
+
+/// Wraps a private key with the Google CSE key, the reverse of `cse_symmetric_unwrap`
+async fn cse_symmetric_wrap(
+    private_key: &[u8],
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<ExtraDatabaseParams>,
+) -> KResult<Vec<u8>> {
+    debug!("cse_wrap: wrap private key");
+    cse_wrap(
+        private_key,
+        EncodingOption::TTLVEncoding,
+        user,
+        kms,
+        database_params.as_ref(),
+    )
+    .await
+}
+
+/// The header of a DEK bound to the resource and the perimeter it is wrapped for:
+/// these magic bytes are followed by the length of the perimeter id (one byte),
+/// the perimeter id, the length of the resource key hash (one byte), the hash, then the DEK.
+///
+/// DEKs wrapped before they were bound have no header.
+const BOUND_DEK_MAGIC: &[u8; 4] = b"\0CKB";
+
+/// Bind a DEK to its perimeter and resource key hash, before it is wrapped
+fn bind_dek(
+    dek: &[u8],
+    perimeter_id: &str,
+    resource_key_hash: &str,
+) -> KResult<Zeroizing<Vec<u8>>> {
+    let mut bound_dek = Zeroizing::new(Vec::with_capacity(
+        BOUND_DEK_MAGIC.len() + 2 + perimeter_id.len() + resource_key_hash.len() + dek.len(),
+    ));
+    bound_dek.extend_from_slice(BOUND_DEK_MAGIC);
+    for (name, field) in [
+        ("perimeter id", perimeter_id),
+        ("resource key hash", resource_key_hash),
+    ] {
+        let Ok(length) = u8::try_from(field.len()) else {
+            kms_bail!(KmsError::InvalidRequest(format!(
+                "the {name} is too long: {field}"
+            )))
+        };
+        bound_dek.push(length);
+        bound_dek.extend_from_slice(field.as_bytes());
+    }
+    bound_dek.extend_from_slice(dek);
+    Ok(bound_dek)
+}
+
+/// Split a bound DEK into its perimeter id, its resource key hash and the DEK
+fn split_bound_dek(bound_dek: &[u8]) -> Option<(String, String, Zeroizing<Vec<u8>>)> {
+    let rest = bound_dek.strip_prefix(BOUND_DEK_MAGIC.as_slice())?;
+    let (perimeter_id, rest) = split_length_prefixed(rest)?;
+    let (resource_key_hash, dek) = split_length_prefixed(rest)?;
+    Some((
+        String::from_utf8(perimeter_id.to_vec()).ok()?,
+        String::from_utf8(resource_key_hash.to_vec()).ok()?,
+        Zeroizing::new(dek.to_vec()),
+    ))
+}
+
+/// Split the field prefixed by its length (one byte) from the rest of the bytes
+fn split_length_prefixed(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (length, rest) = bytes.split_first()?;
+    (rest.len() >= usize::from(*length)).then(|| rest.split_at(usize::from(*length)))
+}
+
+/// Wraps a Data Encryption Key (DEK) with the Google CSE key, bound to the resource
+/// and the perimeter it encrypts, and returns it base64 encoded with its resource key hash
+async fn cse_dek_wrap(
+    dek: &[u8],
+    resource_name: &str,
+    perimeter_id: &str,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<(String, String)> {
+    debug!("cse_dek_wrap: wrap dek");
+    let resource_key_hash = compute_resource_key_hash(resource_name, perimeter_id, dek)?;
+    let bound_dek = bind_dek(dek, perimeter_id, &resource_key_hash)?;
+    let wrapped_dek = cse_wrap(
+        &bound_dek,
+        EncodingOption::NoEncoding,
+        user,
+        kms,
+        database_params,
+    )
     .await?;
+    Ok((
+        general_purpose::STANDARD.encode(wrapped_dek),
+        resource_key_hash,
+    ))
+}
 
-    debug!("cse_unwrap: unwrapped private key");
+/// Unwraps a base64 encoded Data Encryption Key (DEK) with the Google CSE key
+/// and returns it with its resource key hash.
+///
+/// The DEK must have been wrapped for the resource `resource_name`
+/// and for the perimeter `perimeter_id`, or for any perimeter when it is `None`;
+/// that perimeter must be served by this KACLS.
+/// DEKs wrapped before they were bound to their resource are not checked.
+async fn cse_dek_unwrap(
+    wrapped_key: &str,
+    resource_name: &str,
+    perimeter_id: Option<&str>,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<(Zeroizing<Vec<u8>>, String)> {
+    debug!("cse_dek_unwrap: unwrap dek");
+    let unwrapped = cse_unwrap(
+        &general_purpose::STANDARD.decode(wrapped_key)?,
+        EncodingOption::NoEncoding,
+        user,
+        kms,
+        database_params,
+    )
+    .await?;
+    let perimeters = &kms.params.google_cse_perimeters;
+
+    let Some((bound_perimeter_id, bound_resource_key_hash, dek)) = split_bound_dek(&unwrapped)
+    else {
+        debug!("cse_dek_unwrap: the dek is not bound to a resource");
+        let perimeter_id = perimeter_id.unwrap_or_default();
+        check_perimeter(perimeter_id, perimeters)?;
+        let resource_key_hash = compute_resource_key_hash(resource_name, perimeter_id, &unwrapped)?;
+        return Ok((unwrapped, resource_key_hash))
+    };
 
-    // re-extract the bytes from the key
-    let dek = wrapped_dek.key_block()?.key_bytes()?;
-    Ok(dek)
+    let perimeter_id = perimeter_id.unwrap_or(&bound_perimeter_id);
+    check_perimeter(perimeter_id, perimeters)?;
+    let resource_key_hash = compute_resource_key_hash(resource_name, perimeter_id, &dek)?;
+    kms_ensure!(
+        resource_key_hash.len() == bound_resource_key_hash.len()
+            && memcmp::eq(
+                resource_key_hash.as_bytes(),
+                bound_resource_key_hash.as_bytes()
+            ),
+        KmsError::Unauthorized(format!(
+            "the key was not wrapped for the resource {resource_name} in the perimeter \
+             {perimeter_id:?}"
+        ))
+    );
+    Ok((dek, resource_key_hash))
 }
@@ -0,0 +1,233 @@
+use std::sync::Arc;
+
+use cosmian_kmip::{
+    crypto::{
+        symmetric::create_symmetric_key_kmip_object,
+        wrap::{unwrap_key_block, wrap_key_block},
+    },
+    kmip::{
+        kmip_data_structures::{KeyWrappingData, KeyWrappingSpecification},
+        kmip_objects::ObjectType,
+        kmip_types::{
+            self, CryptographicAlgorithm, EncodingOption, StateEnumeration, UniqueIdentifier,
+        },
+    },
+};
+use cosmian_kms_client::access::ObjectOperationType;
+use tracing::debug;
+use zeroize::Zeroizing;
+
+use crate::{
+    core::extra_database_params::ExtraDatabaseParams,
+    database::{object_with_metadata::ObjectWithMetadata, retrieve_object_for_operation},
+    error::KmsError,
+    kms_ensure,
+    result::KResult,
+    KMSServer,
+};
+
+/// The tag of the Google CSE wrapping keys.
+///
+/// Only one key carrying this tag is active at a time and wraps the keys:
+/// rotating it with a `ReKey` deactivates the previous version,
+/// which is then only used to unwrap the keys it wrapped.
+pub const GOOGLE_CSE_TAG: &str = "google_cse";
+
+/// The header of a key wrapped by a version of the Google CSE key:
+/// these magic bytes are followed by the length of the unique identifier
+/// of the key version (one byte), the identifier, then the wrapped key.
+///
+/// Keys wrapped before the keys were versioned have no header.
+const KEY_VERSION_MAGIC: &[u8; 4] = b"\0CKV";
+
+/// Wrap `key` with the active version of the Google CSE key
+/// and prefix the wrapped key with the identifier of that version
+pub(crate) async fn cse_wrap(
+    key: &[u8],
+    encoding_option: EncodingOption,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<Vec<u8>> {
+    let wrapping_key = retrieve_object_for_operation(
+        &format!("[\"{GOOGLE_CSE_TAG}\"]"),
+        ObjectOperationType::Encrypt,
+        kms,
+        user,
+        database_params,
+    )
+    .await?;
+    let key_version = wrapping_key.id.as_bytes();
+    let key_version_length = u8::try_from(key_version.len()).map_err(|_| {
+        KmsError::ServerError(format!(
+            "the unique identifier of the Google CSE key is too long: {}",
+            wrapping_key.id
+        ))
+    })?;
+    debug!(
+        "cse_wrap: wrapping with the Google CSE key {}",
+        wrapping_key.id
+    );
+
+    let mut object = create_symmetric_key_kmip_object(key, CryptographicAlgorithm::AES);
+    wrap_key_block(
+        object.key_block_mut()?,
+        &wrapping_key.object,
+        &KeyWrappingSpecification {
+            wrapping_method: kmip_types::WrappingMethod::Encrypt,
+            encoding_option: Some(encoding_option),
+            encryption_key_information: Some(kmip_types::EncryptionKeyInformation {
+                unique_identifier: UniqueIdentifier::TextString(wrapping_key.id.clone()),
+                cryptographic_parameters: None,
+            }),
+            ..Default::default()
+        },
+    )?;
+
+    let ciphertext = object.key_block()?.key_bytes()?;
+    let mut wrapped_key =
+        Vec::with_capacity(KEY_VERSION_MAGIC.len() + 1 + key_version.len() + ciphertext.len());
+    wrapped_key.extend_from_slice(KEY_VERSION_MAGIC);
+    wrapped_key.push(key_version_length);
+    wrapped_key.extend_from_slice(key_version);
+    wrapped_key.extend_from_slice(&ciphertext);
+    Ok(wrapped_key)
+}
+
+/// Unwrap a key wrapped by `cse_wrap` with the version of the Google CSE key
+/// identified in its header, whether it is still active or has been rotated.
+///
+/// Keys wrapped before the keys were versioned are unwrapped
+/// by trying every version of the Google CSE key.
+pub(crate) async fn cse_unwrap(
+    wrapped_key: &[u8],
+    encoding_option: EncodingOption,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<Zeroizing<Vec<u8>>> {
+    if let Some((key_version, ciphertext)) = split_key_version(wrapped_key) {
+        debug!("cse_unwrap: unwrapping with the Google CSE key {key_version}");
+        if let Some(unwrapping_key) =
+            cse_key_version(&key_version, user, kms, database_params).await?
+        {
+            if let Ok(key) = unwrap(ciphertext, &unwrapping_key, encoding_option) {
+                return Ok(key)
+            }
+        }
+    }
+
+    // a key wrapped before versioning: try all the versions, most recent first
+    debug!("cse_unwrap: no key version found, trying all the Google CSE keys");
+    let mut versions = cse_key_versions(
+        &format!("[\"{GOOGLE_CSE_TAG}\"]"),
+        user,
+        kms,
+        database_params,
+    )
+    .await?;
+    versions.sort_by_key(|owm| owm.state != StateEnumeration::Active);
+    for unwrapping_key in &versions {
+        if let Ok(key) = unwrap(wrapped_key, unwrapping_key, encoding_option) {
+            return Ok(key)
+        }
+    }
+    Err(KmsError::InvalidRequest(
+        "the key cannot be unwrapped by any version of the Google CSE key".to_owned(),
+    ))
+}
+
+/// Split a versioned wrapped key into the key version and the ciphertext
+fn split_key_version(wrapped_key: &[u8]) -> Option<(String, &[u8])> {
+    let rest = wrapped_key.strip_prefix(KEY_VERSION_MAGIC.as_slice())?;
+    let (length, rest) = rest.split_first()?;
+    if rest.len() <= usize::from(*length) {
+        return None
+    }
+    let (key_version, ciphertext) = rest.split_at(usize::from(*length));
+    Some((String::from_utf8(key_version.to_vec()).ok()?, ciphertext))
+}
+
+/// The version of the Google CSE key named in the header of a wrapped key, if it still exists.
+///
+/// The header is supplied by the client: it must be the unique identifier,
+/// not a tag expression, of a key carrying the Google CSE tag,
+/// so that no other key the user may decrypt with can be selected.
+async fn cse_key_version(
+    key_version: &str,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<Option<ObjectWithMetadata>> {
+    kms_ensure!(
+        !key_version.starts_with('['),
+        KmsError::InvalidRequest(format!(
+            "the key version of a wrapped key must be a unique identifier: {key_version}"
+        ))
+    );
+    let Some(unwrapping_key) = cse_key_versions(key_version, user, kms, database_params)
+        .await?
+        .into_iter()
+        .find(|owm| owm.id == key_version)
+    else {
+        return Ok(None)
+    };
+    let tags = kms
+        .db
+        .retrieve_tags(&unwrapping_key.id, database_params)
+        .await?;
+    kms_ensure!(
+        tags.contains(GOOGLE_CSE_TAG),
+        KmsError::InvalidRequest(format!(
+            "the key {key_version} is not a version of the Google CSE key"
+        ))
+    );
+    Ok(Some(unwrapping_key))
+}
+
+/// The versions of the Google CSE key matching `uid_or_tags`
+/// which can still be used to unwrap: the active and the deactivated ones
+async fn cse_key_versions(
+    uid_or_tags: &str,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<Vec<ObjectWithMetadata>> {
+    Ok(kms
+        .db
+        .retrieve(
+            uid_or_tags,
+            user,
+            ObjectOperationType::Decrypt,
+            database_params,
+        )
+        .await?
+        .into_values()
+        .filter(|owm| {
+            matches!(
+                owm.state,
+                StateEnumeration::Active | StateEnumeration::Deactivated
+            ) && owm.object.object_type() == ObjectType::SymmetricKey
+        })
+        .collect())
+}
+
+/// Unwrap `ciphertext` with a version of the Google CSE key
+fn unwrap(
+    ciphertext: &[u8],
+    unwrapping_key: &ObjectWithMetadata,
+    encoding_option: EncodingOption,
+) -> KResult<Zeroizing<Vec<u8>>> {
+    let mut object = create_symmetric_key_kmip_object(ciphertext, CryptographicAlgorithm::AES);
+    object.key_block_mut()?.key_wrapping_data = Some(Box::new(KeyWrappingData {
+        wrapping_method: kmip_types::WrappingMethod::Encrypt,
+        encryption_key_information: Some(kmip_types::EncryptionKeyInformation {
+            unique_identifier: UniqueIdentifier::TextString(unwrapping_key.id.clone()),
+            cryptographic_parameters: None,
+        }),
+        encoding_option: Some(encoding_option),
+        ..Default::default()
+    }));
+    unwrap_key_block(object.key_block_mut()?, &unwrapping_key.object)?;
+    Ok(object.key_block()?.key_bytes()?)
+}
@@ -5,10 +5,7 @@ use actix_web::{
     web::{Data, Json},
     HttpRequest,
 };
-use cosmian_kmip::kmip::{
-    kmip_messages::Message,
-    ttlv::{deserializer::from_ttlv, serializer::to_ttlv, TTLV},
-};
+use cosmian_kmip::kmip::ttlv::{serializer::to_ttlv, TTLV};
 use tracing::info;
 
 use crate::{
@@ -42,17 +39,13 @@ pub async fn kmip(
 /// The input request could be either a single KMIP `Operation` or
 /// multiple KMIP `Operation`s serialized in a single KMIP `Message`
 pub async fn handle_ttlv(
-    kms: &KMS,
+    kms: &Arc<KMS>,
     ttlv: &TTLV,
     user: &str,
     database_params: Option<&ExtraDatabaseParams>,
 ) -> KResult<TTLV> {
     match ttlv.tag.as_str() {
-        "Message" => {
-            let req = from_ttlv::<Message>(ttlv)?;
-            let resp = kms.message(req, user, database_params).await?;
-            Ok(to_ttlv(&resp)?)
-        }
+        "Message" => kms.message_ttlv(ttlv, user, database_params).await,
         _ => {
             let operation = dispatch(kms, ttlv, user, database_params).await?;
             Ok(to_ttlv(&operation)?)
@@ -12,9 +12,12 @@ use tracing::{error, info, warn};
 use crate::{database::KMSServer, error::KmsError, result::KResult};
 
 pub mod access;
+pub mod acme;
+pub mod crl;
 pub mod google_cse;
 pub mod kmip;
 pub mod ms_dke;
+pub mod ocsp;
 
 impl actix_web::error::ResponseError for KmsError {
     fn status_code(&self) -> StatusCode {
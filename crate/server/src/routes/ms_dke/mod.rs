@@ -8,21 +8,33 @@ use actix_web::{
 use base64::{engine::general_purpose::STANDARD, Engine};
 use chrono::{Duration, Utc};
 use clap::crate_version;
-use cosmian_kmip::kmip::{
-    kmip_data_structures::KeyMaterial,
-    kmip_objects::Object,
-    kmip_operations::{Decrypt, Get},
-    kmip_types::{
-        CryptographicAlgorithm, CryptographicParameters, HashingAlgorithm, KeyFormatType,
-        KeyWrapType, PaddingMethod, UniqueIdentifier,
+use cosmian_kmip::{
+    crypto::rsa::ckm_rsa_pkcs_oaep::ckm_rsa_pkcs_oaep_key_decrypt,
+    kmip::{
+        kmip_data_structures::KeyMaterial,
+        kmip_objects::{Object, ObjectType},
+        kmip_operations::{ErrorReason, Get},
+        kmip_types::{
+            CryptographicUsageMask, HashingAlgorithm, KeyFormatType, KeyWrapType, LinkType,
+            StateEnumeration, UniqueIdentifier,
+        },
     },
+    openssl::kmip_private_key_to_openssl,
 };
+use cosmian_kms_client::access::ObjectOperationType;
 use num_bigint_dig::BigUint;
 use serde::{Deserialize, Serialize};
-use tracing::{info, log::trace};
+use tracing::{debug, info, log::trace};
 use url::Url;
 
-use crate::{kms_bail, kms_error, result::KResult, KMSServer};
+use crate::{
+    core::{extra_database_params::ExtraDatabaseParams, operations::unwrap_key},
+    database::object_with_metadata::ObjectWithMetadata,
+    error::KmsError,
+    kms_bail, kms_error,
+    result::KResult,
+    KMSServer,
+};
 
 #[derive(Serialize, Debug)]
 pub enum KeyType {
@@ -65,7 +77,7 @@ pub struct DkePublicKey {
     /// This URI will be called by the client to decrypt the data by appending /decrypt to the end.
     /// Ex. https://hostname/KeyName/2BE4E378-1317-4D64-AC44-D75f638F7B29
     #[serde(rename = "kid")]
-    key_id: String,
+    pub key_id: String,
 }
 
 #[derive(Serialize, Debug)]
@@ -80,8 +92,8 @@ pub struct DkePublicKeyCache {
 
 #[derive(Serialize, Debug)]
 pub struct KeyData {
-    key: DkePublicKey,
-    cache: DkePublicKeyCache,
+    pub key: DkePublicKey,
+    pub cache: DkePublicKeyCache,
 }
 
 #[get("/version")]
@@ -116,6 +128,20 @@ pub async fn get_key(
 async fn _get_key(key_tag: &str, req_http: HttpRequest, kms: &Arc<KMSServer>) -> KResult<KeyData> {
     let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
     let user = kms.get_user(req_http)?;
+    dke_public_key(key_tag, &user, kms, database_params.as_ref()).await
+}
+
+/// Get the public key of the current version of the DKE key `key_tag`.
+///
+/// The versions of a DKE key are the RSA key pairs carrying the `key_tag` tag:
+/// the current version is the only active one, and its version GUID,
+/// at the end of the key id, is the unique identifier of its public key.
+pub(crate) async fn dke_public_key(
+    key_tag: &str,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<KeyData> {
     let dke_service_url = kms
         .params
         .ms_dke_service_url
@@ -132,14 +158,14 @@ async fn _get_key(key_tag: &str, req_http: HttpRequest, kms: &Arc<KMSServer>) ->
         key_compression_type: None,
         key_wrapping_specification: None,
     };
-    let resp = kms.get(op, &user, database_params.as_ref()).await?;
+    let resp = kms.get(op, user, database_params).await?;
     match resp.object {
         Object::PublicKey { key_block, .. } => match key_block.key_value.key_material {
             KeyMaterial::TransparentRSAPublicKey {
                 modulus,
                 public_exponent,
             } => {
-                let key_id = resp.unique_identifier.as_str().ok_or_else(|| {
+                let key_version = resp.unique_identifier.as_str().ok_or_else(|| {
                     kms_error!(
                         "MS DKE: The RSA public key does not have a text unique identifier. This \
                          is not supported"
@@ -150,7 +176,7 @@ async fn _get_key(key_tag: &str, req_http: HttpRequest, kms: &Arc<KMSServer>) ->
                 if existing_path.ends_with('/') {
                     existing_path.pop();
                 }
-                dke_service_url.set_path(&format!("{existing_path}/{key_tag}/{key_id}"));
+                dke_service_url.set_path(&format!("{existing_path}/{key_tag}/{key_version}"));
                 Ok(KeyData {
                     key: DkePublicKey {
                         key_type: KeyType::RSA,
@@ -181,7 +207,7 @@ async fn _get_key(key_tag: &str, req_http: HttpRequest, kms: &Arc<KMSServer>) ->
 #[derive(Serialize, Debug)]
 pub struct DecryptedData {
     /// The base 64 value of the decrypted bytes
-    value: String,
+    pub value: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -207,9 +233,8 @@ pub async fn decrypt(
         serde_json::to_string(&encrypted_data).unwrap()
     );
     let (key_name, key_id) = path.into_inner();
-    // let _key_id = key_id.into_inner();
     trace!("POST /{}/{}/Decrypt {:?}", key_name, key_id, encrypted_data);
-    match _decrypt(&key_name, encrypted_data, req_http, &kms).await {
+    match _decrypt(&key_name, &key_id, encrypted_data, req_http, &kms).await {
         Ok(decrypted_data) => HttpResponse::Ok().json(decrypted_data),
         Err(e) => HttpResponse::from_error(e),
     }
@@ -217,37 +242,106 @@ pub async fn decrypt(
 
 async fn _decrypt(
     key_tag: &str,
+    key_version: &str,
     encrypted_data: EncryptedData,
     req_http: HttpRequest,
     kms: &Arc<KMSServer>,
 ) -> KResult<DecryptedData> {
     let database_params = kms.get_sqlite_enc_secrets(&req_http)?;
     let user = kms.get_user(req_http)?;
-    let decrypt_request = Decrypt {
-        unique_identifier: Some(UniqueIdentifier::TextString(
-            serde_json::to_string(&vec![key_tag, "_sk"]).map_err(|e| kms_error!(e))?,
-        )),
-        data: Some(STANDARD.decode(encrypted_data.value.as_bytes())?),
-        cryptographic_parameters: Some(CryptographicParameters {
-            cryptographic_algorithm: Some(CryptographicAlgorithm::RSA),
-            padding_method: Some(PaddingMethod::OAEP),
-            hashing_algorithm: Some(HashingAlgorithm::SHA256),
-            ..CryptographicParameters::default()
-        }),
-        ..Decrypt::default()
+    dke_decrypt(
+        key_tag,
+        key_version,
+        &encrypted_data,
+        &user,
+        kms,
+        database_params.as_ref(),
+    )
+    .await
+}
+
+/// Decrypt `encrypted_data` with the version `key_version` of the DKE key `key_tag`.
+///
+/// Rotated versions are deactivated but still decrypt the content they protected.
+/// The `user` must be granted the right to decrypt with the version,
+/// either directly or through one of its groups.
+pub(crate) async fn dke_decrypt(
+    key_tag: &str,
+    key_version: &str,
+    encrypted_data: &EncryptedData,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<DecryptedData> {
+    let Some(mut owm) = dke_private_key(key_tag, key_version, user, kms, database_params).await?
+    else {
+        return Err(KmsError::Unauthorized(format!(
+            "MS DKE: version {key_version} of the key {key_tag} does not exist or cannot be used \
+             by {user}"
+        )))
     };
-    let response = kms
-        .decrypt(decrypt_request, &user, database_params.as_ref())
-        .await?;
+    debug!("MS DKE: decrypting with version {key_version} of {key_tag} as {user}");
+
+    // unwrap if wrapped
+    if owm.object.key_wrapping_data().is_some() {
+        let key_block = owm.object.key_block_mut()?;
+        unwrap_key(key_block, kms, &owm.owner, database_params).await?;
+    }
+    if !owm
+        .object
+        .attributes()?
+        .is_usage_authorized_for(CryptographicUsageMask::Decrypt)?
+    {
+        return Err(KmsError::KmipError(
+            ErrorReason::Incompatible_Cryptographic_Usage_Mask,
+            "CryptographicUsageMask not authorized for Decrypt".to_owned(),
+        ))
+    }
+
+    let plaintext = ckm_rsa_pkcs_oaep_key_decrypt(
+        &kmip_private_key_to_openssl(&owm.object)?,
+        HashingAlgorithm::SHA256,
+        &STANDARD.decode(encrypted_data.value.as_bytes())?,
+    )?;
     Ok(DecryptedData {
-        value: STANDARD.encode(
-            response
-                .data
-                .ok_or_else(|| kms_error!("The response does not contain the decrypted data"))?,
-        ),
+        value: STANDARD.encode(plaintext),
     })
 }
 
+/// The private key of the version `key_version` of the DKE key `key_tag`,
+/// if `user` is granted the right to decrypt with it
+async fn dke_private_key(
+    key_tag: &str,
+    key_version: &str,
+    user: &str,
+    kms: &Arc<KMSServer>,
+    database_params: Option<&ExtraDatabaseParams>,
+) -> KResult<Option<ObjectWithMetadata>> {
+    let uid_or_tags = serde_json::to_string(&vec![key_tag, "_sk"]).map_err(|e| kms_error!(e))?;
+    Ok(kms
+        .db
+        .retrieve(
+            &uid_or_tags,
+            user,
+            ObjectOperationType::Decrypt,
+            database_params,
+        )
+        .await?
+        .into_values()
+        .find(|owm| {
+            matches!(
+                owm.state,
+                StateEnumeration::Active | StateEnumeration::Deactivated
+            ) && owm.object.object_type() == ObjectType::PrivateKey
+                && owm
+                    .object
+                    .attributes()
+                    .ok()
+                    .and_then(|attributes| attributes.get_link(LinkType::PublicKeyLink))
+                    .is_some_and(|public_key_id| public_key_id == key_version)
+        }))
+}
+
 fn big_uint_to_u32(bu: &BigUint) -> u32 {
     let bytes = bu.to_bytes_be();
     let len = bytes.len();
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use actix_web::{
+    get, post,
+    web::{Bytes, Data, Path},
+    HttpRequest, HttpResponse,
+};
+use base64::{engine::general_purpose, Engine as _};
+use http::header;
+use tracing::info;
+
+use crate::{database::KMSServer, error::KmsError, result::KResult};
+
+const OCSP_RESPONSE_CONTENT_TYPE: &str = "application/ocsp-response";
+
+/// Answer a DER encoded OCSP request sent in the body of the POST request
+#[post("/{issuer_certificate_id}")]
+pub async fn post_ocsp(
+    req: HttpRequest,
+    issuer_certificate_id: Path<String>,
+    body: Bytes,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<HttpResponse> {
+    let issuer_certificate_id = issuer_certificate_id.into_inner();
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("POST /ocsp/{issuer_certificate_id} {user}");
+
+    let response = kms
+        .ocsp(
+            &issuer_certificate_id,
+            &body,
+            &user,
+            database_params.as_ref(),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, OCSP_RESPONSE_CONTENT_TYPE))
+        .body(response))
+}
+
+/// Answer a base64 and URL encoded OCSP request sent in the path of the GET request
+#[get("/{issuer_certificate_id}/{request:.*}")]
+pub async fn get_ocsp(
+    req: HttpRequest,
+    path: Path<(String, String)>,
+    kms: Data<Arc<KMSServer>>,
+) -> KResult<HttpResponse> {
+    let (issuer_certificate_id, request) = path.into_inner();
+    let database_params = kms.get_sqlite_enc_secrets(&req)?;
+    let user = kms.get_user(req)?;
+    info!("GET /ocsp/{issuer_certificate_id} {user}");
+
+    let request = general_purpose::STANDARD
+        .decode(percent_decode(&request)?)
+        .map_err(|e| KmsError::InvalidRequest(format!("invalid base64 OCSP request: {e}")))?;
+    let response = kms
+        .ocsp(
+            &issuer_certificate_id,
+            &request,
+            &user,
+            database_params.as_ref(),
+        )
+        .await;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, OCSP_RESPONSE_CONTENT_TYPE))
+        .body(response))
+}
+
+/// Decode the `%XX` escapes left in the path, such as `%2F` for `/`
+fn percent_decode(input: &str) -> KResult<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(input.len());
+    let mut bytes = input.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [
+                bytes.next().unwrap_or_default(),
+                bytes.next().unwrap_or_default(),
+            ];
+            let byte = std::str::from_utf8(&hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| KmsError::InvalidRequest("invalid URL encoding".to_owned()))?;
+            decoded.push(byte);
+        } else {
+            decoded.push(b);
+        }
+    }
+    Ok(decoded)
+}
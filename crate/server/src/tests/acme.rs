@@ -0,0 +1,465 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    test::{self, call_service, read_body},
+    web::{self, Data},
+    App,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use cosmian_kmip::{
+    kmip::{
+        kmip_data_structures::{KeyBlock, KeyMaterial, KeyValue},
+        kmip_objects::{Object, ObjectType},
+        kmip_operations::Import,
+        kmip_types::{
+            Attributes, KeyFormatType, LinkType, LinkedObjectIdentifier, UniqueIdentifier,
+        },
+    },
+    openssl::openssl_certificate_to_kmip,
+};
+use http::StatusCode;
+use openssl::{
+    asn1::{Asn1Integer, Asn1Time},
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    ecdsa::EcdsaSig,
+    hash::{hash, MessageDigest},
+    nid::Nid,
+    pkcs12::Pkcs12,
+    pkey::{PKey, Private},
+    stack::Stack,
+    x509::{
+        extension::{BasicConstraints, KeyUsage, SubjectAlternativeName},
+        X509Name, X509NameBuilder, X509Req, X509,
+    },
+};
+use serde_json::{json, Value};
+use zeroize::Zeroizing;
+
+use crate::{
+    config::ServerParams,
+    result::KResult,
+    routes::acme::{self, AcmeServer, ChallengeValidator},
+    tests::test_utils::https_clap_config,
+    KMSServer,
+};
+
+const ACME_URL: &str = "https://kms.acme.test/acme";
+const DOMAIN: &str = "service.acme.test";
+
+/// A validator serving the key authorizations and TXT records published by the test
+#[derive(Default)]
+struct StubValidator {
+    http_01: Mutex<HashMap<String, String>>,
+    dns_01: Mutex<HashMap<String, Vec<String>>>,
+}
+
+#[async_trait]
+impl ChallengeValidator for StubValidator {
+    async fn http_01(&self, domain: &str, token: &str) -> KResult<String> {
+        self.http_01
+            .lock()
+            .unwrap()
+            .get(&format!("{domain}/{token}"))
+            .cloned()
+            .ok_or_else(|| crate::kms_error!("404 Not Found"))
+    }
+
+    async fn dns_01(&self, domain: &str) -> KResult<Vec<String>> {
+        Ok(self
+            .dns_01
+            .lock()
+            .unwrap()
+            .get(domain)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+pub(super) fn name(common_name: &str) -> KResult<X509Name> {
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_nid(Nid::COMMONNAME, common_name)?;
+    Ok(name.build())
+}
+
+pub(super) fn p256_key() -> KResult<PKey<Private>> {
+    Ok(PKey::from_ec_key(EcKey::generate(
+        EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?.as_ref(),
+    )?)?)
+}
+
+/// Import a self-signed CA and its private key as a PKCS#12 and return the CA certificate id
+pub(super) async fn import_ca(kms: &KMSServer, owner: &str) -> KResult<(String, X509)> {
+    let pkey = p256_key()?;
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(Asn1Integer::from_bn(&BigNum::from_u32(1)?)?.as_ref())?;
+    builder.set_subject_name(&name("ACME Test CA")?)?;
+    builder.set_issuer_name(&name("ACME Test CA")?)?;
+    builder.set_pubkey(&pkey)?;
+    builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+    builder.set_not_after(Asn1Time::days_from_now(365)?.as_ref())?;
+    builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+    builder.append_extension(KeyUsage::new().key_cert_sign().crl_sign().build()?)?;
+    builder.sign(&pkey, MessageDigest::sha256())?;
+    let ca = builder.build();
+
+    let pkcs12 = Pkcs12::builder()
+        .name("acme_ca")
+        .pkey(&pkey)
+        .cert(&ca)
+        .build2("secret")?;
+    let mut attributes = Attributes::default();
+    attributes.add_link(
+        LinkType::PKCS12PasswordLink,
+        LinkedObjectIdentifier::TextString("secret".to_owned()),
+    );
+    kms.import(
+        Import {
+            unique_identifier: UniqueIdentifier::TextString("acme_ca_key".to_owned()),
+            object_type: ObjectType::PrivateKey,
+            replace_existing: Some(true),
+            key_wrap_type: None,
+            attributes,
+            object: Object::PrivateKey {
+                key_block: KeyBlock {
+                    key_format_type: KeyFormatType::PKCS12,
+                    key_compression_type: None,
+                    key_value: KeyValue {
+                        key_material: KeyMaterial::ByteString(Zeroizing::from(pkcs12.to_der()?)),
+                        attributes: Some(Box::default()),
+                    },
+                    cryptographic_algorithm: None,
+                    cryptographic_length: None,
+                    key_wrapping_data: None,
+                },
+            },
+        },
+        owner,
+        None,
+    )
+    .await?;
+    let (ca_id, _) = openssl_certificate_to_kmip(&ca)?;
+    Ok((ca_id, ca))
+}
+
+/// An ACME client signing its requests with an EC P-256 account key (ES256)
+struct Client {
+    key: PKey<Private>,
+    jwk: Value,
+    kid: Option<String>,
+    nonce: String,
+}
+
+impl Client {
+    fn new() -> KResult<Self> {
+        let key = p256_key()?;
+        let ec_key = key.ec_key()?;
+        let mut x = BigNum::new()?;
+        let mut y = BigNum::new()?;
+        ec_key.public_key().affine_coordinates(
+            ec_key.group(),
+            &mut x,
+            &mut y,
+            &mut openssl::bn::BigNumContext::new()?,
+        )?;
+        let jwk = json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": URL_SAFE_NO_PAD.encode(x.to_vec_padded(32)?),
+            "y": URL_SAFE_NO_PAD.encode(y.to_vec_padded(32)?),
+        });
+        Ok(Self {
+            key,
+            jwk,
+            kid: None,
+            nonce: String::new(),
+        })
+    }
+
+    fn thumbprint(&self) -> KResult<String> {
+        let canonical = format!(
+            r#"{{"crv":"P-256","kty":"EC","x":{},"y":{}}}"#,
+            self.jwk["x"], self.jwk["y"]
+        );
+        Ok(URL_SAFE_NO_PAD.encode(hash(MessageDigest::sha256(), canonical.as_bytes())?))
+    }
+
+    fn sign(&self, url: &str, payload: Option<&Value>) -> KResult<Value> {
+        let mut protected = json!({ "alg": "ES256", "nonce": self.nonce, "url": url });
+        match &self.kid {
+            Some(kid) => protected["kid"] = json!(kid),
+            None => protected["jwk"] = self.jwk.clone(),
+        }
+        let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+        let payload = payload.map_or_else(String::new, |payload| {
+            URL_SAFE_NO_PAD.encode(payload.to_string())
+        });
+        let digest = hash(
+            MessageDigest::sha256(),
+            format!("{protected}.{payload}").as_bytes(),
+        )?;
+        let signature = EcdsaSig::sign(&digest, self.key.ec_key()?.as_ref())?;
+        let signature = [
+            signature.r().to_vec_padded(32)?,
+            signature.s().to_vec_padded(32)?,
+        ]
+        .concat();
+        Ok(json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": URL_SAFE_NO_PAD.encode(signature),
+        }))
+    }
+
+    /// Post a signed request; return the status, the location and the body
+    async fn post<S, B>(
+        &mut self,
+        app: &S,
+        url: &str,
+        payload: Option<&Value>,
+    ) -> KResult<(StatusCode, Option<String>, Vec<u8>)>
+    where
+        S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+        B: MessageBody,
+    {
+        let body = self.sign(url, payload)?;
+        let req = test::TestRequest::post()
+            .uri(url.strip_prefix("https://kms.acme.test").unwrap())
+            .insert_header(("Content-Type", "application/jose+json"))
+            .set_payload(body.to_string())
+            .to_request();
+        let res = call_service(app, req).await;
+        let status = res.status();
+        let header = |name: &str| {
+            res.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+        self.nonce = header("Replay-Nonce").expect("a Replay-Nonce header");
+        let location = header("Location");
+        Ok((status, location, read_body(res).await.to_vec()))
+    }
+
+    async fn post_json<S, B>(&mut self, app: &S, url: &str, payload: Option<&Value>) -> Value
+    where
+        S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+        B: MessageBody,
+    {
+        let (_, _, body) = self.post(app, url, payload).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+}
+
+fn csr(domains: &[&str]) -> KResult<String> {
+    let pkey = p256_key()?;
+    let mut builder = X509Req::builder()?;
+    builder.set_subject_name(&name(domains[0])?)?;
+    builder.set_pubkey(&pkey)?;
+    let mut san = SubjectAlternativeName::new();
+    for domain in domains {
+        san.dns(domain);
+    }
+    let mut extensions = Stack::new()?;
+    extensions.push(san.build(&builder.x509v3_context(None))?)?;
+    builder.add_extensions(&extensions)?;
+    builder.sign(&pkey, MessageDigest::sha256())?;
+    Ok(URL_SAFE_NO_PAD.encode(builder.build().to_der()?))
+}
+
+#[tokio::test]
+async fn test_acme() -> KResult<()> {
+    let kms =
+        Arc::new(KMSServer::instantiate(ServerParams::try_from(https_clap_config()).await?).await?);
+    let (ca_id, ca) = import_ca(&kms, &kms.params.default_username).await?;
+
+    let validator = Arc::new(StubValidator::default());
+    let acme_server = Arc::new(AcmeServer::new(ACME_URL, &ca_id, validator.clone()));
+    let app = test::init_service(
+        App::new().app_data(Data::new(kms.clone())).service(
+            web::scope("/acme")
+                .app_data(Data::new(acme_server.clone()))
+                .service(acme::directory)
+                .service(acme::head_new_nonce)
+                .service(acme::get_new_nonce)
+                .service(acme::new_account)
+                .service(acme::account)
+                .service(acme::account_orders)
+                .service(acme::new_order)
+                .service(acme::order)
+                .service(acme::finalize)
+                .service(acme::authorization)
+                .service(acme::challenge)
+                .service(acme::certificate),
+        ),
+    )
+    .await;
+
+    // directory and first nonce
+    let directory: Value = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get().uri("/acme/directory").to_request(),
+    )
+    .await;
+    assert_eq!(directory["newOrder"], format!("{ACME_URL}/new-order"));
+    let res = call_service(
+        &app,
+        test::TestRequest::default()
+            .method(http::Method::HEAD)
+            .uri("/acme/new-nonce")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let mut client = Client::new()?;
+    client.nonce = res.headers()["Replay-Nonce"].to_str().unwrap().to_owned();
+
+    // create the account
+    let used_nonce = client.nonce.clone();
+    let (status, location, _) = client
+        .post(
+            &app,
+            &format!("{ACME_URL}/new-account"),
+            Some(&json!({ "termsOfServiceAgreed": true })),
+        )
+        .await?;
+    assert_eq!(status, StatusCode::CREATED);
+    let account_url = location.expect("the account URL");
+    assert_eq!(
+        account_url,
+        format!("{ACME_URL}/account/{}", client.thumbprint()?)
+    );
+
+    // a nonce cannot be replayed
+    client.nonce = used_nonce;
+    let problem = client
+        .post_json(&app, &format!("{ACME_URL}/new-account"), Some(&json!({})))
+        .await;
+    assert_eq!(problem["type"], "urn:ietf:params:acme:error:badNonce");
+
+    // the same key finds the existing account
+    let (status, location, _) = client
+        .post(&app, &format!("{ACME_URL}/new-account"), Some(&json!({})))
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(location, Some(account_url.clone()));
+    client.kid = Some(account_url);
+
+    // order a certificate validated with HTTP-01
+    let (status, order_url, body) = client
+        .post(
+            &app,
+            &format!("{ACME_URL}/new-order"),
+            Some(&json!({ "identifiers": [{ "type": "dns", "value": DOMAIN }] })),
+        )
+        .await?;
+    assert_eq!(status, StatusCode::CREATED);
+    let order_url = order_url.expect("the order URL");
+    let order: Value = serde_json::from_slice(&body)?;
+    assert_eq!(order["status"], "pending");
+
+    let authorization_url = order["authorizations"][0].as_str().unwrap().to_owned();
+    let authorization = client.post_json(&app, &authorization_url, None).await;
+    assert_eq!(authorization["identifier"]["value"], DOMAIN);
+    let challenge = authorization["challenges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|challenge| challenge["type"] == "http-01")
+        .unwrap()
+        .clone();
+    let token = challenge["token"].as_str().unwrap();
+    validator.http_01.lock().unwrap().insert(
+        format!("{DOMAIN}/{token}"),
+        format!("{token}.{}", client.thumbprint()?),
+    );
+    let challenge = client
+        .post_json(&app, challenge["url"].as_str().unwrap(), Some(&json!({})))
+        .await;
+    assert_eq!(challenge["status"], "valid");
+
+    let order = client.post_json(&app, &order_url, None).await;
+    assert_eq!(order["status"], "ready");
+
+    // a CSR for other names is rejected
+    let problem = client
+        .post_json(
+            &app,
+            order["finalize"].as_str().unwrap(),
+            Some(&json!({ "csr": csr(&["other.acme.test"])? })),
+        )
+        .await;
+    assert_eq!(problem["type"], "urn:ietf:params:acme:error:badCSR");
+
+    let order = client
+        .post_json(
+            &app,
+            order["finalize"].as_str().unwrap(),
+            Some(&json!({ "csr": csr(&[DOMAIN])? })),
+        )
+        .await;
+    assert_eq!(order["status"], "valid", "{order}");
+
+    // download the certificate chain
+    let (status, _, chain) = client
+        .post(&app, order["certificate"].as_str().unwrap(), None)
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    let chain = X509::stack_from_pem(&chain)?;
+    assert_eq!(chain.len(), 1);
+    let certificate = &chain[0];
+    assert!(certificate.verify(ca.public_key()?.as_ref())?);
+    let names = certificate
+        .subject_alt_names()
+        .expect("subject alternative names");
+    assert_eq!(names.len(), 1);
+    assert_eq!(names.get(0).unwrap().dnsname(), Some(DOMAIN));
+
+    // a wildcard order is validated with DNS-01 only: a missing TXT record fails it
+    let order = client
+        .post_json(
+            &app,
+            &format!("{ACME_URL}/new-order"),
+            Some(&json!({ "identifiers": [{ "type": "dns", "value": "*.acme.test" }] })),
+        )
+        .await;
+    let authorization_url = order["authorizations"][0].as_str().unwrap().to_owned();
+    let authorization = client.post_json(&app, &authorization_url, None).await;
+    assert_eq!(authorization["wildcard"], true);
+    let challenges = authorization["challenges"].as_array().unwrap();
+    assert_eq!(challenges.len(), 1);
+    assert_eq!(challenges[0]["type"], "dns-01");
+    let challenge = client
+        .post_json(
+            &app,
+            challenges[0]["url"].as_str().unwrap(),
+            Some(&json!({})),
+        )
+        .await;
+    assert_eq!(challenge["status"], "invalid");
+    assert_eq!(
+        challenge["error"]["type"],
+        "urn:ietf:params:acme:error:incorrectResponse"
+    );
+
+    // another account cannot read the orders of this account
+    let mut other = Client::new()?;
+    other.nonce = client.nonce.clone();
+    let (status, location, _) = other
+        .post(&app, &format!("{ACME_URL}/new-account"), Some(&json!({})))
+        .await?;
+    assert_eq!(status, StatusCode::CREATED);
+    other.kid = location;
+    let (status, _, _) = other.post(&app, &authorization_url, None).await?;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    Ok(())
+}
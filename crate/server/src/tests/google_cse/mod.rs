@@ -5,11 +5,15 @@ use std::{
 };
 
 use base64::{engine::general_purpose, Engine};
-use cosmian_kmip::kmip::{
-    kmip_objects::Object,
-    kmip_operations::{Import, ImportResponse},
-    kmip_types::UniqueIdentifier,
-    ttlv::{deserializer::from_ttlv, TTLV},
+use cosmian_kmip::{
+    crypto::symmetric::symmetric_key_create_request,
+    kmip::{
+        extra::tagging::EMPTY_TAGS,
+        kmip_objects::Object,
+        kmip_operations::{CreateResponse, Import, ImportResponse, ReKey, ReKeyResponse},
+        kmip_types::{CryptographicAlgorithm, UniqueIdentifier},
+        ttlv::{deserializer::from_ttlv, TTLV},
+    },
 };
 use cosmian_kms_client::access::{Access, ObjectOperationType, SuccessResponse};
 use openssl::{
@@ -17,15 +21,20 @@ use openssl::{
     pkey::{PKey, Private},
     pkey_ctx::PkeyCtx,
     rsa::{Padding, Rsa},
+    sha::sha256,
     sign::{Signer, Verifier},
     x509::X509,
 };
 
 use crate::{
+    config::ClapConfig,
     result::{KResult, KResultHelper},
     routes::google_cse::operations::{
-        PrivateKeyDecryptRequest, PrivateKeyDecryptResponse, PrivateKeySignRequest,
-        PrivateKeySignResponse,
+        compute_resource_key_hash, DigestRequest, DigestResponse, PrivateKeyDecryptRequest,
+        PrivateKeyDecryptResponse, PrivateKeySignRequest, PrivateKeySignResponse,
+        PrivilegedPrivateKeyDecryptRequest, PrivilegedUnwrapRequest, PrivilegedUnwrapResponse,
+        PrivilegedWrapRequest, PrivilegedWrapResponse, RewrapRequest, RewrapResponse,
+        WrapPrivateKeyRequest, WrapPrivateKeyResponse,
     },
     tests::{google_cse::utils::generate_google_jwt, test_utils},
 };
@@ -61,6 +70,15 @@ pub fn read_object_from_json_ttlv_bytes(bytes: &[u8]) -> KResult<Object> {
     Ok(object)
 }
 
+/// The configuration of a KMS server allowing the user of the test tokens
+/// to call the privileged Google CSE endpoints
+fn privileged_clap_config() -> ClapConfig {
+    ClapConfig {
+        google_cse_privileged_users: Some(vec!["blue@cosmian.com".to_string()]),
+        ..test_utils::https_clap_config_opts(Some("http://127.0.0.1/".to_string()))
+    }
+}
+
 fn import_google_cse_symmetric_key() -> Import {
     let symmetric_key = read_bytes_from_file(&PathBuf::from(
         "../../documentation/docs/google_cse/17fd53a2-a753-4ec4-800b-ccc68bc70480.demo.key.json",
@@ -69,12 +87,16 @@ fn import_google_cse_symmetric_key() -> Import {
 
     let object = read_object_from_json_ttlv_bytes(&symmetric_key).unwrap();
 
+    // the Google CSE keys are looked up by their tag
+    let mut attributes = object.attributes().cloned().unwrap_or_default();
+    attributes.set_tags(["google_cse"]).unwrap();
+
     let request = Import {
         unique_identifier: UniqueIdentifier::TextString("google_cse".to_string()),
         object_type: object.object_type(),
         replace_existing: Some(false),
         key_wrap_type: None,
-        attributes: object.attributes().cloned().unwrap_or_default(),
+        attributes,
         object,
     };
 
@@ -157,6 +179,7 @@ async fn test_cse_private_key_sign() -> KResult<()> {
             ObjectOperationType::Encrypt,
             ObjectOperationType::Decrypt,
         ],
+        role: None,
     };
 
     let access_response: SuccessResponse =
@@ -256,6 +279,7 @@ async fn test_cse_private_key_decrypt() -> KResult<()> {
             ObjectOperationType::Encrypt,
             ObjectOperationType::Decrypt,
         ],
+        role: None,
     };
 
     let access_response: SuccessResponse =
@@ -289,3 +313,338 @@ async fn test_cse_private_key_decrypt() -> KResult<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_resource_key_hash() -> KResult<()> {
+    // HMAC-SHA256(DEK, "ResourceKeyDigest:" + resource_name + ":" + perimeter_id)
+    let resource_key_hash =
+        compute_resource_key_hash("//googleapis.com/drive/files/1A2B3C", "", &[1_u8; 32])?;
+    assert_eq!(
+        resource_key_hash,
+        "VcNsyFFpR3BiWaWgsOqKjoRfWanfrAwsWDusBHmQ5zc="
+    );
+    // the perimeter is bound to the hash
+    assert_ne!(
+        compute_resource_key_hash("//googleapis.com/drive/files/1A2B3C", "eu", &[1_u8; 32])?,
+        resource_key_hash
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cse_privileged_operations() -> KResult<()> {
+    cosmian_logger::log_utils::log_init("info,cosmian_kms_server=trace");
+
+    let jwt = generate_google_jwt().await;
+
+    let app = test_utils::test_app_with_clap_config(privileged_clap_config()).await;
+
+    let import_request = import_google_cse_symmetric_key();
+    let response: ImportResponse = test_utils::post(&app, import_request).await?;
+    tracing::debug!("import response: {response:?}");
+
+    let access = Access {
+        unique_identifier: Some(UniqueIdentifier::TextString("google_cse".to_string())),
+        user_id: "*".to_string(),
+        operation_types: vec![
+            ObjectOperationType::Get,
+            ObjectOperationType::Encrypt,
+            ObjectOperationType::Decrypt,
+        ],
+        role: None,
+    };
+    let access_response: SuccessResponse =
+        test_utils::post_with_uri(&app, access, "/access/grant").await?;
+    tracing::debug!("grant response post: {access_response:?}");
+
+    // privileged wrap then unwrap of a DEK
+    let dek = vec![2_u8; 32];
+    let request = PrivilegedWrapRequest {
+        authentication: jwt.clone(),
+        key: general_purpose::STANDARD.encode(&dek),
+        perimeter_id: String::new(),
+        reason: "import".to_string(),
+        resource_name: "//googleapis.com/drive/files/1A2B3C".to_string(),
+    };
+    let wrapped: PrivilegedWrapResponse =
+        test_utils::post_with_uri(&app, request, "/google_cse/privilegedwrap").await?;
+
+    let unwrap_request = |resource_name: &str| PrivilegedUnwrapRequest {
+        authentication: jwt.clone(),
+        reason: "e-discovery".to_string(),
+        resource_name: resource_name.to_string(),
+        wrapped_key: wrapped.wrapped_key.clone(),
+    };
+    let response: PrivilegedUnwrapResponse = test_utils::post_with_uri(
+        &app,
+        unwrap_request("//googleapis.com/drive/files/1A2B3C"),
+        "/google_cse/privilegedunwrap",
+    )
+    .await?;
+    assert_eq!(general_purpose::STANDARD.encode(&dek), response.key);
+
+    // the DEK is bound to the resource it was wrapped for
+    let response: KResult<PrivilegedUnwrapResponse> = test_utils::post_with_uri(
+        &app,
+        unwrap_request("//googleapis.com/drive/files/4D5E6F"),
+        "/google_cse/privilegedunwrap",
+    )
+    .await;
+    assert!(response.is_err());
+
+    // rewrap and digest require an authorization token issued by Google
+    // with the migrator or verifier role, which the test token is not
+    let response: KResult<RewrapResponse> = test_utils::post_with_uri(
+        &app,
+        RewrapRequest {
+            authorization: jwt.clone(),
+            original_kacls_url: "http://127.0.0.1/".to_string(),
+            reason: "migration".to_string(),
+            wrapped_key: wrapped.wrapped_key.clone(),
+        },
+        "/google_cse/rewrap",
+    )
+    .await;
+    assert!(response.is_err());
+    let response: KResult<DigestResponse> = test_utils::post_with_uri(
+        &app,
+        DigestRequest {
+            authorization: jwt.clone(),
+            reason: "verification".to_string(),
+            wrapped_key: wrapped.wrapped_key.clone(),
+        },
+        "/google_cse/digest",
+    )
+    .await;
+    assert!(response.is_err());
+
+    // wrap the blue S/MIME private key, then decrypt a DEK encrypted for it
+    let blue_private_key = PKey::from_rsa(Rsa::<Private>::private_key_from_pem(
+        &read_bytes_from_file(&PathBuf::from(
+            "src/routes/google_cse/python/openssl/blue.key",
+        ))?,
+    )?)?;
+    let request = WrapPrivateKeyRequest {
+        authentication: jwt.clone(),
+        perimeter_id: String::new(),
+        private_key: general_purpose::STANDARD.encode(blue_private_key.private_key_to_pkcs8()?),
+    };
+    let response: WrapPrivateKeyResponse =
+        test_utils::post_with_uri(&app, request, "/google_cse/wrapprivatekey").await?;
+
+    let public_key = X509::from_pem(&read_bytes_from_file(&PathBuf::from(
+        "src/routes/google_cse/python/openssl/blue.pem",
+    ))?)?
+    .public_key()?;
+    let mut ctx = PkeyCtx::new(&public_key)?;
+    ctx.encrypt_init()?;
+    ctx.set_rsa_padding(Padding::PKCS1)?;
+    let mut encrypted_dek = vec![];
+    ctx.encrypt_to_vec(&dek, &mut encrypted_dek)?;
+
+    let request = PrivilegedPrivateKeyDecryptRequest {
+        authentication: jwt,
+        algorithm: "RSA/ECB/PKCS1Padding".to_string(),
+        encrypted_data_encryption_key: general_purpose::STANDARD.encode(encrypted_dek),
+        rsa_oaep_label: None,
+        reason: "e-discovery".to_string(),
+        spki_hash: general_purpose::STANDARD.encode(sha256(&public_key.public_key_to_der()?)),
+        spki_hash_algorithm: "SHA-256".to_string(),
+        wrapped_private_key: response.wrapped_private_key,
+    };
+    let response: PrivateKeyDecryptResponse =
+        test_utils::post_with_uri(&app, request, "/google_cse/privilegedprivatekeydecrypt").await?;
+    assert_eq!(
+        general_purpose::STANDARD.encode(dek),
+        response.data_encryption_key
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cse_privileged_operations_refused() -> KResult<()> {
+    cosmian_logger::log_utils::log_init("info,cosmian_kms_server=trace");
+
+    let jwt = generate_google_jwt().await;
+
+    // the user may use the Google CSE key but is not one of the privileged users
+    let app = test_utils::test_app(Some("http://127.0.0.1/".to_string())).await;
+
+    let import_request = import_google_cse_symmetric_key();
+    let _: ImportResponse = test_utils::post(&app, import_request).await?;
+    let access = Access {
+        unique_identifier: Some(UniqueIdentifier::TextString("google_cse".to_string())),
+        user_id: "*".to_string(),
+        operation_types: vec![
+            ObjectOperationType::Get,
+            ObjectOperationType::Encrypt,
+            ObjectOperationType::Decrypt,
+        ],
+        role: None,
+    };
+    let _: SuccessResponse = test_utils::post_with_uri(&app, access, "/access/grant").await?;
+
+    let is_refused = |error: &str| error.contains("not allowed to perform privileged");
+    let request = PrivilegedWrapRequest {
+        authentication: jwt.clone(),
+        key: general_purpose::STANDARD.encode([2_u8; 32]),
+        perimeter_id: String::new(),
+        reason: "import".to_string(),
+        resource_name: "//googleapis.com/drive/files/1A2B3C".to_string(),
+    };
+    let response: KResult<PrivilegedWrapResponse> =
+        test_utils::post_with_uri(&app, request, "/google_cse/privilegedwrap").await;
+    assert!(response.is_err_and(|e| is_refused(&e.to_string())));
+
+    let request = PrivilegedUnwrapRequest {
+        authentication: jwt.clone(),
+        reason: "e-discovery".to_string(),
+        resource_name: "//googleapis.com/drive/files/1A2B3C".to_string(),
+        wrapped_key: general_purpose::STANDARD.encode([0_u8; 40]),
+    };
+    let response: KResult<PrivilegedUnwrapResponse> =
+        test_utils::post_with_uri(&app, request, "/google_cse/privilegedunwrap").await;
+    assert!(response.is_err_and(|e| is_refused(&e.to_string())));
+
+    let request = PrivilegedPrivateKeyDecryptRequest {
+        authentication: jwt,
+        algorithm: "RSA/ECB/PKCS1Padding".to_string(),
+        encrypted_data_encryption_key: general_purpose::STANDARD.encode([0_u8; 256]),
+        rsa_oaep_label: None,
+        reason: "e-discovery".to_string(),
+        spki_hash: general_purpose::STANDARD.encode([0_u8; 32]),
+        spki_hash_algorithm: "SHA-256".to_string(),
+        wrapped_private_key: general_purpose::STANDARD.encode([0_u8; 40]),
+    };
+    let response: KResult<PrivateKeyDecryptResponse> =
+        test_utils::post_with_uri(&app, request, "/google_cse/privilegedprivatekeydecrypt").await;
+    assert!(response.is_err_and(|e| is_refused(&e.to_string())));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cse_key_rotation() -> KResult<()> {
+    std::env::set_var("KMS_GOOGLE_CSE_GMAIL_JWKS_URI", JWKS_URI);
+    std::env::set_var("KMS_GOOGLE_CSE_GMAIL_JWT_ISSUER", JWT_ISSUER_URI);
+    cosmian_logger::log_utils::log_init("info,cosmian_kms_server=trace");
+
+    let jwt = generate_google_jwt().await;
+
+    let app = test_utils::test_app_with_clap_config(privileged_clap_config()).await;
+
+    let import_request = import_google_cse_symmetric_key();
+    let response: ImportResponse = test_utils::post(&app, import_request).await?;
+    tracing::debug!("import response: {response:?}");
+
+    let grant = |uid: String| Access {
+        unique_identifier: Some(UniqueIdentifier::TextString(uid)),
+        user_id: "*".to_string(),
+        operation_types: vec![
+            ObjectOperationType::Get,
+            ObjectOperationType::Encrypt,
+            ObjectOperationType::Decrypt,
+        ],
+        role: None,
+    };
+    let _: SuccessResponse =
+        test_utils::post_with_uri(&app, grant("google_cse".to_string()), "/access/grant").await?;
+
+    // wrap a DEK with the first version of the key
+    let dek = vec![3_u8; 32];
+    let wrap_request = |dek: &[u8]| PrivilegedWrapRequest {
+        authentication: jwt.clone(),
+        key: general_purpose::STANDARD.encode(dek),
+        perimeter_id: String::new(),
+        reason: "rotation".to_string(),
+        resource_name: "//googleapis.com/drive/files/1A2B3C".to_string(),
+    };
+    let wrapped_v1: PrivilegedWrapResponse =
+        test_utils::post_with_uri(&app, wrap_request(&dek), "/google_cse/privilegedwrap").await?;
+
+    // rotate the key
+    let response: ReKeyResponse = test_utils::post(
+        &app,
+        ReKey {
+            unique_identifier: Some(UniqueIdentifier::TextString("[\"google_cse\"]".to_string())),
+            ..ReKey::default()
+        },
+    )
+    .await?;
+    let new_key_id = response.unique_identifier.to_string();
+    assert_ne!(new_key_id, "google_cse");
+    let _: SuccessResponse =
+        test_utils::post_with_uri(&app, grant(new_key_id.clone()), "/access/grant").await?;
+
+    // new DEKs are wrapped with the new version, which identifier is embedded
+    let wrapped_v2: PrivilegedWrapResponse =
+        test_utils::post_with_uri(&app, wrap_request(&dek), "/google_cse/privilegedwrap").await?;
+    let wrapped_v2_bytes = general_purpose::STANDARD.decode(&wrapped_v2.wrapped_key)?;
+    assert!(
+        wrapped_v2_bytes
+            .windows(new_key_id.len())
+            .any(|w| w == new_key_id.as_bytes())
+    );
+
+    // both the DEK wrapped before and after the rotation can be unwrapped
+    for wrapped_key in [wrapped_v1.wrapped_key, wrapped_v2.wrapped_key] {
+        let request = PrivilegedUnwrapRequest {
+            authentication: jwt.clone(),
+            reason: "rotation".to_string(),
+            resource_name: "//googleapis.com/drive/files/1A2B3C".to_string(),
+            wrapped_key,
+        };
+        let response: PrivilegedUnwrapResponse =
+            test_utils::post_with_uri(&app, request, "/google_cse/privilegedunwrap").await?;
+        assert_eq!(general_purpose::STANDARD.encode(&dek), response.key);
+    }
+
+    // the key version in the header of a wrapped key can only name a Google CSE key
+    let other_key: CreateResponse = test_utils::post(
+        &app,
+        symmetric_key_create_request(256, CryptographicAlgorithm::AES, EMPTY_TAGS)?,
+    )
+    .await?;
+    let other_key_id = other_key.unique_identifier.to_string();
+    let _: SuccessResponse =
+        test_utils::post_with_uri(&app, grant(other_key_id.clone()), "/access/grant").await?;
+    for (key_version, error) in [
+        (
+            other_key_id.as_str(),
+            "is not a version of the Google CSE key",
+        ),
+        ("[\"google_cse\"]", "must be a unique identifier"),
+    ] {
+        let mut wrapped_key = b"\0CKV".to_vec();
+        wrapped_key.push(u8::try_from(key_version.len()).unwrap());
+        wrapped_key.extend_from_slice(key_version.as_bytes());
+        wrapped_key.extend_from_slice(&[0_u8; 40]);
+        let request = PrivilegedUnwrapRequest {
+            authentication: jwt.clone(),
+            reason: "rotation".to_string(),
+            resource_name: "//googleapis.com/drive/files/1A2B3C".to_string(),
+            wrapped_key: general_purpose::STANDARD.encode(wrapped_key),
+        };
+        let response: KResult<PrivilegedUnwrapResponse> =
+            test_utils::post_with_uri(&app, request, "/google_cse/privilegedunwrap").await;
+        assert!(response.is_err_and(|e| e.to_string().contains(error)));
+    }
+
+    // the legacy private key, wrapped before versioning, is still usable
+    let wrapped_private_key =
+        include_str!("../../../../../documentation/docs/google_cse/blue_wrapped_private_key");
+    let request = PrivateKeySignRequest {
+        authentication: jwt.clone(),
+        authorization: jwt,
+        algorithm: "SHA256withRSA".to_string(),
+        digest: "MSKaRPMiIFwZoWGYjA/MV8mLNNYGW3GpODrEjdbbQqE=".to_string(),
+        rsa_pss_salt_length: None,
+        reason: "Gmail".to_string(),
+        wrapped_private_key: wrapped_private_key.to_string(),
+    };
+    let _: PrivateKeySignResponse =
+        test_utils::post_with_uri(&app, request, "/google_cse/privatekeysign").await?;
+
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use std::{sync::Arc, time::Duration};
+
+use cosmian_kmip::{
+    crypto::symmetric::symmetric_key_create_request,
+    kmip::{
+        extra::tagging::EMPTY_TAGS,
+        kmip_messages::{Message, MessageBatchItem, MessageHeader, MessageResponseBatchItem},
+        kmip_operations::{Cancel, ErrorReason, Get, Operation, Poll},
+        kmip_types::{
+            AsynchronousIndicator, CancellationResult, CryptographicAlgorithm,
+            OperationEnumeration, ProtocolVersion, ResultStatusEnumeration,
+        },
+        ttlv::serializer::to_ttlv,
+    },
+};
+use uuid::Uuid;
+
+use crate::{
+    config::ServerParams,
+    core::KMS,
+    database::job::{Job, JobState},
+    result::KResult,
+    tests::test_utils::https_clap_config,
+    KMSServer,
+};
+
+fn asynchronous_message(operations: Vec<Operation>) -> Message {
+    Message {
+        header: MessageHeader {
+            protocol_version: ProtocolVersion::default(),
+            batch_count: operations.len() as u32,
+            asynchronous_indicator: Some(AsynchronousIndicator::Mandatory),
+            ..Default::default()
+        },
+        items: operations.into_iter().map(MessageBatchItem::new).collect(),
+    }
+}
+
+async fn poll(
+    kms: &Arc<KMS>,
+    asynchronous_correlation_value: &[u8],
+    owner: &str,
+) -> KResult<MessageResponseBatchItem> {
+    let request = asynchronous_message(vec![Operation::Poll(Poll {
+        asynchronous_correlation_value: asynchronous_correlation_value.to_vec(),
+    })]);
+    let mut response = kms.message(request, owner, None).await?;
+    assert_eq!(response.items.len(), 1);
+    Ok(response.items.remove(0))
+}
+
+#[actix_rt::test]
+async fn test_kmip_asynchronous_operations() -> KResult<()> {
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    // the creation is pending
+    let request = asynchronous_message(vec![Operation::Create(symmetric_key_create_request(
+        256,
+        CryptographicAlgorithm::AES,
+        EMPTY_TAGS,
+    )?)]);
+    let response = kms.message(request, owner, None).await?;
+    assert_eq!(response.items.len(), 1);
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::OperationPending
+    );
+    assert_eq!(
+        response.items[0].operation,
+        Some(OperationEnumeration::Create)
+    );
+    assert!(response.items[0].response_payload.is_none());
+    let correlation_value = response.items[0]
+        .asynchronous_correlation_value
+        .clone()
+        .expect("a pending item has a correlation value");
+
+    // poll until the creation completes
+    let mut item = poll(&kms, &correlation_value, owner).await?;
+    for _ in 0..100 {
+        if item.result_status != ResultStatusEnumeration::OperationPending {
+            break
+        }
+        assert_eq!(item.operation, Some(OperationEnumeration::Poll));
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        item = poll(&kms, &correlation_value, owner).await?;
+    }
+    assert_eq!(item.result_status, ResultStatusEnumeration::Success);
+    assert_eq!(item.operation, Some(OperationEnumeration::Create));
+    let Some(Operation::CreateResponse(create_response)) = item.response_payload else {
+        panic!("not a create response: {:?}", item.response_payload);
+    };
+    // the key exists
+    kms.get(Get::from(create_response.unique_identifier), owner, None)
+        .await?;
+
+    // a completed operation cannot be canceled
+    let cancel_response = kms
+        .cancel(
+            Cancel {
+                asynchronous_correlation_value: correlation_value.clone(),
+            },
+            owner,
+            None,
+        )
+        .await?;
+    assert_eq!(
+        cancel_response.cancellation_result,
+        CancellationResult::Completed
+    );
+
+    // the operation of a user cannot be polled by another one
+    let item = poll(&kms, &correlation_value, "another user").await?;
+    assert_eq!(item.result_status, ResultStatusEnumeration::OperationFailed);
+    assert_eq!(
+        item.result_reason,
+        Some(ErrorReason::Invalid_Asynchronous_Correlation_Value)
+    );
+
+    // unknown correlation value
+    let item = poll(&kms, b"unknown", owner).await?;
+    assert_eq!(item.result_status, ResultStatusEnumeration::OperationFailed);
+    assert_eq!(
+        item.result_reason,
+        Some(ErrorReason::Invalid_Asynchronous_Correlation_Value)
+    );
+
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_kmip_asynchronous_cancel() -> KResult<()> {
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    let request = asynchronous_message(vec![Operation::Create(symmetric_key_create_request(
+        256,
+        CryptographicAlgorithm::AES,
+        EMPTY_TAGS,
+    )?)]);
+    let response = kms.message(request, owner, None).await?;
+    let correlation_value = response.items[0]
+        .asynchronous_correlation_value
+        .clone()
+        .expect("a pending item has a correlation value");
+
+    // the operation may complete before it is canceled
+    let cancel_response = kms
+        .cancel(
+            Cancel {
+                asynchronous_correlation_value: correlation_value.clone(),
+            },
+            owner,
+            None,
+        )
+        .await?;
+    let item = poll(&kms, &correlation_value, owner).await?;
+    match cancel_response.cancellation_result {
+        CancellationResult::Canceled => {
+            assert_eq!(item.result_status, ResultStatusEnumeration::OperationFailed);
+            assert_eq!(
+                item.result_reason,
+                Some(ErrorReason::Operation_Canceled_By_Requester)
+            );
+        }
+        CancellationResult::Completed => {
+            assert_eq!(item.result_status, ResultStatusEnumeration::Success);
+        }
+        result => panic!("unexpected cancellation result: {result:?}"),
+    }
+
+    Ok(())
+}
+
+/// Poll the operation until it is no longer pending
+async fn poll_until_processed(
+    kms: &Arc<KMS>,
+    asynchronous_correlation_value: &[u8],
+    owner: &str,
+) -> KResult<MessageResponseBatchItem> {
+    let mut item = poll(kms, asynchronous_correlation_value, owner).await?;
+    for _ in 0..100 {
+        if item.result_status != ResultStatusEnumeration::OperationPending {
+            break
+        }
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        item = poll(kms, asynchronous_correlation_value, owner).await?;
+    }
+    Ok(item)
+}
+
+#[actix_rt::test]
+async fn test_kmip_asynchronous_interrupted() -> KResult<()> {
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    // a pending job whose lease expired, as left by a server which stopped
+    let now = chrono::Utc::now().timestamp();
+    let interrupted_job = || -> KResult<Job> {
+        Ok(Job {
+            id: Uuid::new_v4().to_string(),
+            owner: owner.to_owned(),
+            operation: OperationEnumeration::Create,
+            state: JobState::Pending,
+            request: to_ttlv(&symmetric_key_create_request(
+                256,
+                CryptographicAlgorithm::AES,
+                EMPTY_TAGS,
+            )?)?,
+            response: None,
+            lease_expiry: now - 1,
+            updated_at: now - 60,
+        })
+    };
+
+    // it is resumed when polled
+    let job = interrupted_job()?;
+    kms.db.create_job(&job, None).await?;
+    let item = poll_until_processed(&kms, job.id.as_bytes(), owner).await?;
+    assert_eq!(item.result_status, ResultStatusEnumeration::Success);
+    assert_eq!(item.operation, Some(OperationEnumeration::Create));
+
+    // and when the server restarts, under a new lease
+    let job = interrupted_job()?;
+    kms.db.create_job(&job, None).await?;
+    kms.resume_interrupted_jobs().await?;
+    let resumed = kms
+        .db
+        .retrieve_job(&job.id, owner, None)
+        .await?
+        .expect("the job should exist");
+    assert!(resumed.state != JobState::Pending || resumed.lease_expiry > now);
+    let item = poll_until_processed(&kms, job.id.as_bytes(), owner).await?;
+    assert_eq!(item.result_status, ResultStatusEnumeration::Success);
+
+    Ok(())
+}
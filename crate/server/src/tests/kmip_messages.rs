@@ -1,19 +1,29 @@
 use std::sync::Arc;
 
 use cosmian_kmip::{
-    crypto::elliptic_curves::kmip_requests::create_ec_key_pair_request,
+    crypto::{
+        elliptic_curves::kmip_requests::create_ec_key_pair_request,
+        symmetric::symmetric_key_create_request,
+    },
     kmip::{
-        kmip_messages::{Message, MessageBatchItem, MessageHeader},
-        kmip_operations::{Decrypt, ErrorReason, Locate, Operation},
+        extra::tagging::EMPTY_TAGS,
+        kmip_messages::{Message, MessageBatchItem, MessageHeader, MessageResponse},
+        kmip_operations::{
+            Decrypt, Encrypt, ErrorReason, Export, Get, Locate, Operation, Revoke, SetAttribute,
+        },
         kmip_types::{
-            OperationEnumeration, ProtocolVersion, RecommendedCurve, ResultStatusEnumeration,
-            UniqueIdentifier,
+            Attribute, BatchErrorContinuationOption, CryptographicAlgorithm, OperationEnumeration,
+            ProtocolVersion, RecommendedCurve, ResultStatusEnumeration, RevocationReason,
+            StateEnumeration, UniqueIdentifier,
         },
+        ttlv::{deserializer::from_ttlv, serializer::to_ttlv, TTLVEnumeration, TTLValue, TTLV},
     },
 };
+use cosmian_kms_client::access::{Access, ObjectOperationType};
 
 use crate::{
-    config::ServerParams, result::KResult, tests::test_utils::https_clap_config, KMSServer,
+    config::ServerParams, error::KmsError, result::KResult, tests::test_utils::https_clap_config,
+    KMSServer,
 };
 
 #[tokio::test]
@@ -114,3 +124,458 @@ async fn test_kmip_messages() -> KResult<()> {
     assert!(response.items[2].response_payload.is_none());
     Ok(())
 }
+
+fn ttlv(tag: &str, value: TTLValue) -> TTLV {
+    TTLV {
+        tag: tag.to_string(),
+        value,
+    }
+}
+
+/// A KMIP 1.x request message holding a single batch item
+fn kmip_1_x_request(protocol_version_minor: u32, operation: &str, payload: Vec<TTLV>) -> TTLV {
+    ttlv(
+        "Message",
+        TTLValue::Structure(vec![
+            ttlv(
+                "Header",
+                TTLValue::Structure(vec![
+                    to_ttlv(&ProtocolVersion {
+                        protocol_version_major: 1,
+                        protocol_version_minor,
+                    })
+                    .unwrap(),
+                    ttlv("BatchCount", TTLValue::Integer(1)),
+                ]),
+            ),
+            ttlv(
+                "Items",
+                TTLValue::Structure(vec![
+                    ttlv(
+                        "Operation",
+                        TTLValue::Enumeration(TTLVEnumeration::Name(operation.to_string())),
+                    ),
+                    ttlv("RequestPayload", TTLValue::Structure(payload)),
+                ]),
+            ),
+        ]),
+    )
+}
+
+/// A KMIP 1.x attribute
+fn kmip_1_x_attribute(name: &str, value: TTLValue) -> TTLV {
+    ttlv(
+        "Attribute",
+        TTLValue::Structure(vec![
+            ttlv("AttributeName", TTLValue::TextString(name.to_string())),
+            ttlv("AttributeValue", value),
+        ]),
+    )
+}
+
+#[tokio::test]
+async fn test_kmip_1_x_messages() -> KResult<()> {
+    // cosmian_logger::log_utils::log_init("info,hyper=info,reqwest=info");
+
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    // create a symmetric key using a KMIP 1.4 template attribute
+    let request = kmip_1_x_request(
+        4,
+        "Create",
+        vec![
+            ttlv(
+                "ObjectType",
+                TTLValue::Enumeration(TTLVEnumeration::Name("SymmetricKey".to_string())),
+            ),
+            ttlv(
+                "TemplateAttribute",
+                TTLValue::Structure(vec![
+                    // AES
+                    kmip_1_x_attribute(
+                        "Cryptographic Algorithm",
+                        TTLValue::Enumeration(TTLVEnumeration::Integer(3)),
+                    ),
+                    kmip_1_x_attribute("Cryptographic Length", TTLValue::Integer(256)),
+                    // Encrypt | Decrypt
+                    kmip_1_x_attribute("Cryptographic Usage Mask", TTLValue::Integer(12)),
+                ]),
+            ),
+        ],
+    );
+    let response: MessageResponse = from_ttlv(&kms.message_ttlv(&request, owner, None).await?)?;
+    // the response uses the protocol version of the request
+    assert_eq!(
+        response.header.protocol_version,
+        ProtocolVersion {
+            protocol_version_major: 1,
+            protocol_version_minor: 4,
+        }
+    );
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::Success
+    );
+    let Some(Operation::CreateResponse(create_response)) = &response.items[0].response_payload
+    else {
+        panic!("not a create response payload");
+    };
+    let uid = create_response
+        .unique_identifier
+        .as_str()
+        .expect("the unique identifier should be a string");
+
+    // the attributes are returned as KMIP 1.x attributes
+    let request = kmip_1_x_request(
+        2,
+        "GetAttributes",
+        vec![
+            ttlv("UniqueIdentifier", TTLValue::TextString(uid.to_string())),
+            ttlv(
+                "AttributeName",
+                TTLValue::TextString("Cryptographic Algorithm".to_string()),
+            ),
+        ],
+    );
+    let response = kms.message_ttlv(&request, owner, None).await?;
+    let response = serde_json::to_string(&response)?;
+    assert!(response.contains("\"AttributeName\""));
+    assert!(response.contains("\"Cryptographic Algorithm\""));
+    assert!(!response.contains("\"Attributes\""));
+
+    // unsupported protocol versions are rejected
+    let mut request = Message {
+        header: MessageHeader {
+            protocol_version: ProtocolVersion {
+                protocol_version_major: 3,
+                protocol_version_minor: 0,
+            },
+            batch_count: 1,
+            ..Default::default()
+        },
+        items: vec![MessageBatchItem::new(Operation::Locate(Locate::default()))],
+    };
+    let error = kms
+        .message_ttlv(&to_ttlv(&request)?, owner, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        KmsError::KmipError(ErrorReason::Invalid_Message, _)
+    ));
+
+    // while KMIP 2.1 messages are processed as is
+    request.header.protocol_version = ProtocolVersion::default();
+    let response: MessageResponse =
+        from_ttlv(&kms.message_ttlv(&to_ttlv(&request)?, owner, None).await?)?;
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::Success
+    );
+
+    Ok(())
+}
+
+/// A KMIP 2.1 request message with the given batch error continuation option
+fn batch(
+    batch_error_continuation_option: Option<BatchErrorContinuationOption>,
+    operations: Vec<Operation>,
+) -> Message {
+    Message {
+        header: MessageHeader {
+            protocol_version: ProtocolVersion::default(),
+            batch_count: operations.len() as u32,
+            batch_error_continuation_option,
+            ..Default::default()
+        },
+        items: operations.into_iter().map(MessageBatchItem::new).collect(),
+    }
+}
+
+#[tokio::test]
+async fn test_kmip_messages_id_placeholder() -> KResult<()> {
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    // create a key, then use and export it without knowing its unique identifier
+    let request = batch(
+        None,
+        vec![
+            Operation::Create(symmetric_key_create_request(
+                256,
+                CryptographicAlgorithm::AES,
+                EMPTY_TAGS,
+            )?),
+            Operation::Encrypt(Encrypt {
+                data: Some(b"plaintext".to_vec().into()),
+                ..Default::default()
+            }),
+            Operation::Export(Export::default()),
+        ],
+    );
+    let response = kms.message(request, owner, None).await?;
+    assert_eq!(response.items.len(), 3);
+    for item in &response.items {
+        assert_eq!(item.result_status, ResultStatusEnumeration::Success);
+    }
+    let Some(Operation::CreateResponse(create_response)) = &response.items[0].response_payload
+    else {
+        panic!("not a create response payload");
+    };
+    let Some(Operation::EncryptResponse(encrypt_response)) = &response.items[1].response_payload
+    else {
+        panic!("not an encrypt response payload");
+    };
+    assert_eq!(
+        encrypt_response.unique_identifier,
+        create_response.unique_identifier
+    );
+    let Some(Operation::ExportResponse(export_response)) = &response.items[2].response_payload
+    else {
+        panic!("not an export response payload");
+    };
+    assert_eq!(
+        export_response.unique_identifier,
+        create_response.unique_identifier
+    );
+
+    // there is no ID Placeholder outside of a batch
+    let response = kms
+        .message(
+            batch(None, vec![Operation::Get(Get::default())]),
+            owner,
+            None,
+        )
+        .await?;
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::OperationFailed
+    );
+
+    Ok(())
+}
+
+/// The state of the object with the given unique identifier
+async fn object_state(
+    kms: &KMSServer,
+    uid: &str,
+    owner: &str,
+) -> KResult<Option<StateEnumeration>> {
+    Ok(kms
+        .db
+        .retrieve(uid, owner, ObjectOperationType::Get, None)
+        .await?
+        .get(uid)
+        .map(|owm| owm.state))
+}
+
+#[tokio::test]
+async fn test_kmip_messages_batch_error_continuation() -> KResult<()> {
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    let uid = kms
+        .create(
+            symmetric_key_create_request(256, CryptographicAlgorithm::AES, EMPTY_TAGS)?,
+            owner,
+            None,
+        )
+        .await?
+        .unique_identifier;
+    let unknown_uid = UniqueIdentifier::TextString("unknown_uid".to_string());
+
+    // Stop is the default: the items following a failed one are not processed
+    let operations = || {
+        vec![
+            Operation::Get(Get::from(&unknown_uid)),
+            Operation::Get(Get::from(&uid)),
+        ]
+    };
+    let response = kms.message(batch(None, operations()), owner, None).await?;
+    assert_eq!(response.header.batch_count, 1);
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::OperationFailed
+    );
+
+    // Continue processes every item
+    let response = kms
+        .message(
+            batch(Some(BatchErrorContinuationOption::Continue), operations()),
+            owner,
+            None,
+        )
+        .await?;
+    assert_eq!(response.header.batch_count, 2);
+    assert_eq!(
+        response.items[1].result_status,
+        ResultStatusEnumeration::Success
+    );
+
+    // Undo rolls back the items processed before the failed one
+    let uid_str = uid
+        .as_str()
+        .expect("the unique identifier should be a string");
+    let initial_state = object_state(&kms, uid_str, owner).await?;
+    let request = batch(
+        Some(BatchErrorContinuationOption::Undo),
+        vec![
+            Operation::Create(symmetric_key_create_request(
+                256,
+                CryptographicAlgorithm::AES,
+                EMPTY_TAGS,
+            )?),
+            Operation::Revoke(Revoke {
+                unique_identifier: Some(uid.clone()),
+                revocation_reason: RevocationReason::TextString("undone".to_string()),
+                compromise_occurrence_date: None,
+            }),
+            Operation::Decrypt(Decrypt {
+                unique_identifier: Some(unknown_uid.clone()),
+                data: Some(b"ciphertext".to_vec()),
+                ..Default::default()
+            }),
+            Operation::Get(Get::from(&uid)),
+        ],
+    );
+    let response = kms.message(request, owner, None).await?;
+    assert_eq!(response.header.batch_count, 3);
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::OperationUndone
+    );
+    assert!(response.items[0].response_payload.is_none());
+    assert!(response.items[0].result_message.is_some());
+    assert_eq!(
+        response.items[1].result_status,
+        ResultStatusEnumeration::OperationUndone
+    );
+    assert_eq!(
+        response.items[2].result_status,
+        ResultStatusEnumeration::OperationFailed
+    );
+    // the revoked key is back to its initial state
+    assert_eq!(object_state(&kms, uid_str, owner).await?, initial_state);
+    // and the created key is gone: only the key created above remains
+    let locate_response = kms.locate(Locate::default(), owner, None).await?;
+    assert_eq!(locate_response.unique_identifiers, Some(vec![uid.clone()]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_kmip_messages_undo_shared_object() -> KResult<()> {
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let user = "user.undo@acme.com";
+
+    let uid = kms
+        .create(
+            symmetric_key_create_request(256, CryptographicAlgorithm::AES, EMPTY_TAGS)?,
+            owner,
+            None,
+        )
+        .await?
+        .unique_identifier;
+    kms.grant_access(
+        &Access {
+            unique_identifier: Some(uid.clone()),
+            user_id: user.to_owned(),
+            operation_types: vec![ObjectOperationType::Revoke],
+            role: None,
+        },
+        owner,
+        None,
+    )
+    .await?;
+
+    // the key revoked by the user it is shared with is restored under its owner
+    let uid_str = uid
+        .as_str()
+        .expect("the unique identifier should be a string");
+    let initial_state = object_state(&kms, uid_str, owner).await?;
+    let request = batch(
+        Some(BatchErrorContinuationOption::Undo),
+        vec![
+            Operation::Revoke(Revoke {
+                unique_identifier: Some(uid.clone()),
+                revocation_reason: RevocationReason::TextString("undone".to_string()),
+                compromise_occurrence_date: None,
+            }),
+            Operation::Get(Get::from("unknown_uid")),
+        ],
+    );
+    let response = kms.message(request, user, None).await?;
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::OperationUndone
+    );
+    assert_eq!(object_state(&kms, uid_str, owner).await?, initial_state);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_kmip_messages_undo_attribute_change() -> KResult<()> {
+    let clap_config = https_clap_config();
+
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    let uid = kms
+        .create(
+            symmetric_key_create_request(256, CryptographicAlgorithm::AES, EMPTY_TAGS)?,
+            owner,
+            None,
+        )
+        .await?
+        .unique_identifier;
+    let set_contact_information = |contact_information: &str| SetAttribute {
+        unique_identifier: Some(uid.clone()),
+        new_attribute: Attribute::ContactInformation(contact_information.to_owned()),
+    };
+    kms.set_attribute(set_contact_information("initial"), owner, None)
+        .await?;
+
+    // the attributes changed by the batch are restored
+    let request = batch(
+        Some(BatchErrorContinuationOption::Undo),
+        vec![
+            Operation::SetAttribute(set_contact_information("undone")),
+            Operation::Get(Get::from("unknown_uid")),
+        ],
+    );
+    let response = kms.message(request, owner, None).await?;
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::OperationUndone
+    );
+    let uid_str = uid
+        .as_str()
+        .expect("the unique identifier should be a string");
+    let owm = kms
+        .db
+        .retrieve(uid_str, owner, ObjectOperationType::Get, None)
+        .await?
+        .remove(uid_str)
+        .expect("the key should exist");
+    assert_eq!(
+        owm.attributes.contact_information.as_deref(),
+        Some("initial")
+    );
+    assert_eq!(
+        owm.object.attributes()?.contact_information.as_deref(),
+        Some("initial")
+    );
+
+    Ok(())
+}
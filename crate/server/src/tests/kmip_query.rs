@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use cosmian_kmip::kmip::{
+    kmip_objects::ObjectType,
+    kmip_operations::{DiscoverVersions, Query},
+    kmip_types::{OperationEnumeration, ProtocolVersion, QueryFunction},
+};
+
+use crate::{
+    config::ServerParams, result::KResult, tests::test_utils::https_clap_config, KMSServer,
+};
+
+#[tokio::test]
+async fn test_query() -> KResult<()> {
+    let clap_config = https_clap_config();
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+
+    // nothing requested, nothing returned
+    let response = kms.query(Query::default()).await?;
+    assert!(response.operation.is_none());
+    assert!(response.server_information.is_none());
+
+    let response = kms
+        .query(Query {
+            query_function: Some(vec![
+                QueryFunction::QueryOperations,
+                QueryFunction::QueryObjects,
+                QueryFunction::QueryServerInformation,
+                QueryFunction::QueryCapabilities,
+            ]),
+        })
+        .await?;
+    let operations = response.operation.unwrap();
+    assert!(operations.contains(&OperationEnumeration::Query));
+    assert!(operations.contains(&OperationEnumeration::DiscoverVersions));
+    assert!(operations.contains(&OperationEnumeration::Encrypt));
+    assert!(operations.contains(&OperationEnumeration::RNGRetrieve));
+    assert!(
+        response
+            .object_type
+            .unwrap()
+            .contains(&ObjectType::SymmetricKey)
+    );
+    let server_information = response.server_information.unwrap();
+    assert_eq!(
+        server_information.build_level,
+        Some(
+            if cfg!(feature = "fips") {
+                "FIPS"
+            } else {
+                "non-FIPS"
+            }
+            .to_owned()
+        )
+    );
+    assert_eq!(server_information.cluster_info, Some("Sqlite".to_owned()));
+    assert_eq!(
+        response.capability_information.unwrap()[0].batch_continue_capability,
+        Some(true)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_discover_versions() -> KResult<()> {
+    let clap_config = https_clap_config();
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+
+    // all the versions supported by the server
+    let response = kms.discover_versions(DiscoverVersions::default()).await?;
+    let mut expected = vec![ProtocolVersion::default()];
+    expected.extend((0..=4).rev().map(|protocol_version_minor| ProtocolVersion {
+        protocol_version_major: 1,
+        protocol_version_minor,
+    }));
+    assert_eq!(response.protocol_version, Some(expected));
+
+    // only the versions supported by both the client and the server
+    let response = kms
+        .discover_versions(DiscoverVersions {
+            protocol_version: Some(vec![
+                ProtocolVersion {
+                    protocol_version_major: 3,
+                    protocol_version_minor: 0,
+                },
+                ProtocolVersion::default(),
+            ]),
+        })
+        .await?;
+    assert_eq!(
+        response.protocol_version,
+        Some(vec![ProtocolVersion::default()])
+    );
+
+    let response = kms
+        .discover_versions(DiscoverVersions {
+            protocol_version: Some(vec![ProtocolVersion {
+                protocol_version_major: 3,
+                protocol_version_minor: 0,
+            }]),
+        })
+        .await?;
+    assert_eq!(response.protocol_version, Some(vec![]));
+
+    Ok(())
+}
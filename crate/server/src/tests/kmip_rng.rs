@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use cosmian_kmip::kmip::kmip_operations::{ErrorReason, RNGRetrieve, RNGSeed};
+
+use crate::{
+    config::ServerParams, error::KmsError, result::KResult, tests::test_utils::https_clap_config,
+    KMSServer,
+};
+
+#[tokio::test]
+async fn test_rng_retrieve() -> KResult<()> {
+    let clap_config = https_clap_config();
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    let first = kms
+        .rng_retrieve(RNGRetrieve { data_length: 32 }, owner)
+        .await?;
+    assert_eq!(first.data.len(), 32);
+    let second = kms
+        .rng_retrieve(RNGRetrieve { data_length: 32 }, owner)
+        .await?;
+    assert_ne!(first.data, second.data);
+
+    // the maximum length of a single request
+    let response = kms
+        .rng_retrieve(
+            RNGRetrieve {
+                data_length: 1024 * 1024,
+            },
+            owner,
+        )
+        .await?;
+    assert_eq!(response.data.len(), 1024 * 1024);
+
+    // invalid lengths are rejected
+    for data_length in [0, -1] {
+        let error = kms
+            .rng_retrieve(RNGRetrieve { data_length }, owner)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            KmsError::KmipError(ErrorReason::Invalid_Field, _)
+        ));
+    }
+    let error = kms
+        .rng_retrieve(
+            RNGRetrieve {
+                data_length: 1024 * 1024 + 1,
+            },
+            owner,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        KmsError::KmipError(ErrorReason::Response_Too_Large, _)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rng_seed() -> KResult<()> {
+    let clap_config = https_clap_config();
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    // the seed data are not used by the DRBG of the server
+    let response = kms
+        .rng_seed(
+            RNGSeed {
+                data: vec![42_u8; 64],
+            },
+            owner,
+        )
+        .await?;
+    assert_eq!(response.data_length, 0);
+
+    assert!(kms.rng_seed(RNGSeed { data: vec![] }, owner).await.is_err());
+    assert!(
+        kms.rng_seed(
+            RNGSeed {
+                data: vec![0_u8; 1024 * 1024 + 1],
+            },
+            owner
+        )
+        .await
+        .is_err()
+    );
+
+    Ok(())
+}
@@ -13,12 +13,17 @@ use cosmian_kmip::{
         symmetric::symmetric_key_create_request,
     },
     kmip::{
+        extra::tagging::EMPTY_TAGS,
         kmip_data_structures::{KeyBlock, KeyMaterial, KeyValue, KeyWrappingData},
         kmip_objects::{Object, ObjectType},
-        kmip_operations::{Get, Import},
+        kmip_operations::{
+            DeleteAttribute, Encrypt, ErrorReason, Get, Import, ModifyAttribute, SetAttribute,
+            SetAttributeResponse,
+        },
         kmip_types::{
-            Attributes, CryptographicAlgorithm, CryptographicUsageMask, KeyFormatType, KeyWrapType,
-            LinkType, LinkedObjectIdentifier, RecommendedCurve, UniqueIdentifier, WrappingMethod,
+            Attribute, Attributes, CryptographicAlgorithm, CryptographicUsageMask, KeyFormatType,
+            KeyWrapType, LinkType, LinkedObjectIdentifier, RecommendedCurve, UniqueIdentifier,
+            UsageLimits, UsageLimitsUnit, WrappingMethod,
         },
     },
 };
@@ -368,3 +373,105 @@ async fn test_database_user_tenant() -> KResult<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_attribute_restrictions() -> KResult<()> {
+    let kms =
+        Arc::new(KMSServer::instantiate(ServerParams::try_from(https_clap_config()).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    let request = symmetric_key_create_request(256, CryptographicAlgorithm::AES, EMPTY_TAGS)?;
+    let uid = kms.create(request, owner, None).await?.unique_identifier;
+    let set_attribute = |new_attribute| SetAttribute {
+        unique_identifier: Some(uid.clone()),
+        new_attribute,
+    };
+    let is_read_only = |result: KResult<SetAttributeResponse>| {
+        matches!(
+            result,
+            Err(KmsError::KmipError(ErrorReason::Attribute_Read_Only, _))
+        )
+    };
+
+    // a non extractable object cannot be made extractable
+    kms.set_attribute(set_attribute(Attribute::Extractable(false)), owner, None)
+        .await?;
+    assert!(is_read_only(
+        kms.set_attribute(set_attribute(Attribute::Extractable(true)), owner, None)
+            .await
+    ));
+
+    // a sensitive object cannot be made non sensitive
+    kms.set_attribute(set_attribute(Attribute::Sensitive(true)), owner, None)
+        .await?;
+    assert!(is_read_only(
+        kms.set_attribute(set_attribute(Attribute::Sensitive(false)), owner, None)
+            .await
+    ));
+
+    // the usage limits are set at creation, then only consumed by the server
+    let usage_limits = UsageLimits {
+        usage_limits_total: 2,
+        usage_limits_count: None,
+        usage_limits_unit: UsageLimitsUnit::Object,
+    };
+    assert!(is_read_only(
+        kms.set_attribute(
+            set_attribute(Attribute::UsageLimits(usage_limits.clone())),
+            owner,
+            None
+        )
+        .await
+    ));
+    let mut request = symmetric_key_create_request(256, CryptographicAlgorithm::AES, EMPTY_TAGS)?;
+    request.attributes.usage_limits = Some(usage_limits.clone());
+    let uid = kms.create(request, owner, None).await?.unique_identifier;
+    let is_read_only = |result: KResult<_>| {
+        matches!(
+            result,
+            Err(KmsError::KmipError(ErrorReason::Attribute_Read_Only, _))
+        )
+    };
+    let unlimited = UsageLimits {
+        usage_limits_total: i64::MAX,
+        ..usage_limits.clone()
+    };
+    assert!(is_read_only(
+        kms.modify_attribute(
+            ModifyAttribute {
+                unique_identifier: Some(uid.clone()),
+                current_attribute: None,
+                new_attribute: Attribute::UsageLimits(unlimited),
+            },
+            owner,
+            None
+        )
+        .await
+        .map(|_| ())
+    ));
+    assert!(is_read_only(
+        kms.delete_attribute(
+            DeleteAttribute {
+                unique_identifier: Some(uid.clone()),
+                current_attribute: Some(Attribute::UsageLimits(usage_limits)),
+                attribute_references: None,
+            },
+            owner,
+            None
+        )
+        .await
+        .map(|_| ())
+    ));
+    let encrypt = || Encrypt {
+        unique_identifier: Some(uid.clone()),
+        data: Some(b"plaintext".to_vec().into()),
+        ..Default::default()
+    };
+    kms.encrypt(encrypt(), owner, None).await?;
+    kms.encrypt(encrypt(), owner, None).await?;
+    assert!(matches!(
+        kms.encrypt(encrypt(), owner, None).await,
+        Err(KmsError::KmipError(ErrorReason::Usage_Limit_Exceeded, _))
+    ));
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use std::{sync::Arc, time::Duration};
+
+use actix_web::{test::TestRequest, HttpMessage};
+use cosmian_kmip::kmip::{
+    kmip_messages::{Message, MessageBatchItem, MessageHeader, MessageResponse},
+    kmip_operations::{ErrorReason, Operation, Query},
+    kmip_types::{OperationEnumeration, ProtocolVersion, QueryFunction, ResultStatusEnumeration},
+    ttlv::{
+        binary::{from_bytes, to_bytes},
+        deserializer::from_binary_ttlv,
+        serializer::to_ttlv,
+    },
+};
+use openssl::{
+    asn1::Asn1Time,
+    hash::MessageDigest,
+    x509::{X509Builder, X509},
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    config::ServerParams,
+    error::KmsError,
+    kms_server::{binary_error_response, handle_binary_ttlv, read_socket_request, socket_user},
+    middlewares::ssl_auth::PeerCommonName,
+    result::KResult,
+    tests::{
+        acme::{name, p256_key},
+        test_utils::https_clap_config,
+    },
+    KMSServer,
+};
+
+/// A self-signed client certificate with the given common name
+fn client_certificate(common_name: &str) -> KResult<X509> {
+    let pkey = p256_key()?;
+    let mut builder = X509Builder::new()?;
+    builder.set_version(2)?;
+    builder.set_subject_name(&name(common_name)?)?;
+    builder.set_issuer_name(&name(common_name)?)?;
+    builder.set_pubkey(&pkey)?;
+    builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+    builder.set_not_after(Asn1Time::days_from_now(1)?.as_ref())?;
+    builder.sign(&pkey, MessageDigest::sha256())?;
+    Ok(builder.build())
+}
+
+#[tokio::test]
+async fn test_socket_server_binary_ttlv() -> KResult<()> {
+    let clap_config = https_clap_config();
+    let kms = Arc::new(KMSServer::instantiate(ServerParams::try_from(clap_config).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+
+    let message = Message {
+        header: MessageHeader {
+            protocol_version: ProtocolVersion::default(),
+            batch_count: 1,
+            ..Default::default()
+        },
+        items: vec![MessageBatchItem::new(Operation::Query(Query {
+            query_function: Some(vec![QueryFunction::QueryOperations]),
+        }))],
+    };
+    let request = to_bytes(&to_ttlv(&message)?)?;
+    let response = handle_binary_ttlv(&kms, &request, owner).await?;
+    let response: MessageResponse = from_binary_ttlv(&from_bytes(&response)?)?;
+    assert_eq!(response.items.len(), 1);
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::Success
+    );
+    let Some(Operation::QueryResponse(query_response)) = &response.items[0].response_payload else {
+        panic!("not a query response: {:?}", response.items[0]);
+    };
+    assert!(
+        query_response
+            .operation
+            .as_ref()
+            .unwrap()
+            .contains(&OperationEnumeration::Query)
+    );
+
+    // an invalid request is answered with a failed message response
+    let error = handle_binary_ttlv(&kms, &request[..16], owner)
+        .await
+        .unwrap_err();
+    let response: MessageResponse =
+        from_binary_ttlv(&from_bytes(&binary_error_response(&error)?)?)?;
+    assert_eq!(
+        response.items[0].result_status,
+        ResultStatusEnumeration::OperationFailed
+    );
+    assert_eq!(
+        response.items[0].result_reason,
+        Some(ErrorReason::Codec_Error)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_socket_server_requires_client_certificates() {
+    let mut clap_config = https_clap_config();
+    clap_config.http.socket_server_start = true;
+    let error = ServerParams::try_from(clap_config).await.unwrap_err();
+    assert!(matches!(error, KmsError::ServerError(_)));
+}
+
+#[tokio::test]
+async fn test_socket_server_timeouts() -> KResult<()> {
+    let message = Message {
+        header: MessageHeader {
+            protocol_version: ProtocolVersion::default(),
+            batch_count: 1,
+            ..Default::default()
+        },
+        items: vec![MessageBatchItem::new(Operation::Query(Query {
+            query_function: Some(vec![QueryFunction::QueryOperations]),
+        }))],
+    };
+    let request = to_bytes(&to_ttlv(&message)?)?;
+    let timeout = Duration::from_millis(100);
+
+    // a complete request is read
+    let (mut client, mut server) = tokio::io::duplex(request.len());
+    client.write_all(&request).await?;
+    assert_eq!(
+        read_socket_request(&mut server, timeout, timeout).await?,
+        Some(request.clone())
+    );
+
+    // a closed connection has no more requests
+    drop(client);
+    assert_eq!(
+        read_socket_request(&mut server, timeout, timeout).await?,
+        None
+    );
+
+    // an idle connection times out
+    let (_client, mut server) = tokio::io::duplex(request.len());
+    assert!(
+        read_socket_request(&mut server, timeout, timeout)
+            .await
+            .is_err()
+    );
+
+    // a request which is never completed times out
+    let (mut client, mut server) = tokio::io::duplex(request.len());
+    client.write_all(&request[..request.len() / 2]).await?;
+    assert!(
+        read_socket_request(&mut server, timeout, timeout)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_group_identities_are_refused() -> KResult<()> {
+    let kms =
+        Arc::new(KMSServer::instantiate(ServerParams::try_from(https_clap_config()).await?).await?);
+
+    // a socket client authenticated as a user
+    let certificate = client_certificate("john.doe@acme.com")?;
+    assert_eq!(socket_user(&kms, Some(&certificate))?, "john.doe@acme.com");
+    // a socket client whose certificate names a group
+    let certificate = client_certificate("group:x")?;
+    let error = socket_user(&kms, Some(&certificate)).unwrap_err();
+    assert!(matches!(error, KmsError::Unauthorized(_)));
+
+    // an HTTPS client whose certificate names a group
+    let request = TestRequest::default().to_http_request();
+    request.extensions_mut().insert(PeerCommonName {
+        common_name: "group:x".to_owned(),
+    });
+    let error = kms.get_user(request).unwrap_err();
+    assert!(matches!(error, KmsError::Unauthorized(_)));
+
+    Ok(())
+}
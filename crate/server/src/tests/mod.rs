@@ -1,13 +1,19 @@
 #[cfg(not(feature = "fips"))]
 mod cover_crypt_tests;
 
+mod acme;
 pub mod google_cse;
 mod ms_dke;
 pub mod test_utils;
 
 #[cfg(not(feature = "fips"))]
 mod curve_25519_tests;
+mod kmip_async;
 #[cfg(not(feature = "fips"))]
 mod kmip_messages;
+mod kmip_query;
+mod kmip_rng;
 #[cfg(not(feature = "fips"))]
 mod kmip_server_tests;
+mod kmip_socket;
+mod revocation;
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use cosmian_kmip::{
+    kmip::{
+        kmip_objects::ObjectType,
+        kmip_operations::{Certify, Destroy, Get, Revoke},
+        kmip_types::{
+            Attributes, CertificateRequestType, LinkType, LinkedObjectIdentifier, RevocationReason,
+            RevocationReasonEnumeration, StateEnumeration, UniqueIdentifier,
+        },
+    },
+    openssl::kmip_certificate_to_openssl,
+};
+use cosmian_kms_client::access::{Access, ObjectOperationType};
+use openssl::{
+    hash::MessageDigest,
+    ocsp::{OcspCertId, OcspCertStatus, OcspRequest, OcspResponse, OcspResponseStatus},
+    x509::{X509Crl, X509Req, X509},
+};
+
+use crate::{
+    config::ServerParams,
+    result::KResult,
+    tests::{
+        acme::{import_ca, name, p256_key},
+        test_utils::https_clap_config,
+    },
+    KMSServer,
+};
+
+/// Issue a certificate signed by the given issuer and return its id
+async fn certify(kms: &KMSServer, issuer_certificate_id: &str, owner: &str) -> KResult<String> {
+    let pkey = p256_key()?;
+    let mut builder = X509Req::builder()?;
+    builder.set_subject_name(&name("leaf.revocation.test")?)?;
+    builder.set_pubkey(&pkey)?;
+    builder.sign(&pkey, MessageDigest::sha256())?;
+
+    let mut attributes = Attributes {
+        object_type: Some(ObjectType::Certificate),
+        ..Attributes::default()
+    };
+    attributes.add_link(
+        LinkType::CertificateLink,
+        LinkedObjectIdentifier::TextString(issuer_certificate_id.to_owned()),
+    );
+    let response = kms
+        .certify(
+            Certify {
+                certificate_request_type: Some(CertificateRequestType::PKCS10),
+                certificate_request_value: Some(builder.build().to_der()?),
+                attributes: Some(attributes),
+                ..Certify::default()
+            },
+            owner,
+            None,
+        )
+        .await?;
+    Ok(response.unique_identifier.to_string())
+}
+
+/// Import an issuer owned by `owner`, issue a certificate with it, and share
+/// only the issuer with `user`; return the ids and certificates of the issuer and of the issued certificate
+async fn shared_issuer(
+    kms: &KMSServer,
+    owner: &str,
+    user: &str,
+) -> KResult<(String, X509, String, X509)> {
+    let (ca_id, ca) = import_ca(kms, owner).await?;
+    let leaf_id = certify(kms, &ca_id, owner).await?;
+    for uid in [ca_id.as_str(), "acme_ca_key"] {
+        kms.grant_access(
+            &Access {
+                unique_identifier: Some(UniqueIdentifier::TextString(uid.to_owned())),
+                user_id: user.to_owned(),
+                operation_types: vec![
+                    ObjectOperationType::Certify,
+                    ObjectOperationType::GetAttributes,
+                ],
+                role: None,
+            },
+            owner,
+            None,
+        )
+        .await?;
+    }
+    let leaf = kms.get(Get::from(leaf_id.as_str()), owner, None).await?;
+    Ok((
+        ca_id,
+        ca,
+        leaf_id,
+        kmip_certificate_to_openssl(&leaf.object)?,
+    ))
+}
+
+async fn revoke(kms: &KMSServer, uid: &str, owner: &str) -> KResult<()> {
+    kms.revoke(
+        Revoke {
+            unique_identifier: Some(UniqueIdentifier::TextString(uid.to_owned())),
+            revocation_reason: RevocationReason::Enumeration(
+                RevocationReasonEnumeration::KeyCompromise,
+            ),
+            compromise_occurrence_date: None,
+        },
+        owner,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_crl_of_shared_issuer() -> KResult<()> {
+    let kms =
+        Arc::new(KMSServer::instantiate(ServerParams::try_from(https_clap_config()).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let user = "crl.requester@example.com";
+    let (ca_id, ca, leaf_id, _) = shared_issuer(&kms, owner, user).await?;
+
+    let crl = kms.crl(&ca_id, user, None).await?;
+    let parsed = X509Crl::from_der(&crl)?;
+    assert!(parsed.verify(ca.public_key()?.as_ref())?);
+    assert!(parsed.get_revoked().is_none());
+    // the signed CRL is served from the cache until its next update
+    assert_eq!(kms.crl(&ca_id, user, None).await?, crl);
+
+    // the issued certificates are resolved as the issuer owner: a revocation is listed at once
+    revoke(&kms, &leaf_id, owner).await?;
+    let parsed = X509Crl::from_der(&kms.crl(&ca_id, user, None).await?)?;
+    assert!(parsed.verify(ca.public_key()?.as_ref())?);
+    assert_eq!(parsed.get_revoked().map(|revoked| revoked.len()), Some(1));
+
+    // a user who may not certify with the issuer gets no CRL
+    assert!(kms.crl(&ca_id, "stranger@example.com", None).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_crl_after_destroy() -> KResult<()> {
+    let kms =
+        Arc::new(KMSServer::instantiate(ServerParams::try_from(https_clap_config()).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let user = "crl.destroy@example.com";
+    let (ca_id, ca, leaf_id, _) = shared_issuer(&kms, owner, user).await?;
+
+    // a pre-active certificate is not listed and may be destroyed without being revoked
+    kms.db
+        .update_state(&leaf_id, StateEnumeration::PreActive, None)
+        .await?;
+    let parsed = X509Crl::from_der(&kms.crl(&ca_id, user, None).await?)?;
+    assert!(parsed.get_revoked().is_none());
+
+    // the cached CRL is discarded: the destroyed certificate is listed at once
+    kms.destroy(
+        Destroy {
+            unique_identifier: Some(UniqueIdentifier::TextString(leaf_id.clone())),
+        },
+        owner,
+        None,
+    )
+    .await?;
+    let parsed = X509Crl::from_der(&kms.crl(&ca_id, user, None).await?)?;
+    assert!(parsed.verify(ca.public_key()?.as_ref())?);
+    assert_eq!(parsed.get_revoked().map(|revoked| revoked.len()), Some(1));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ocsp_of_shared_issuer() -> KResult<()> {
+    let kms =
+        Arc::new(KMSServer::instantiate(ServerParams::try_from(https_clap_config()).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let user = "ocsp.requester@example.com";
+    let (ca_id, ca, leaf_id, leaf) = shared_issuer(&kms, owner, user).await?;
+
+    let cert_id = || OcspCertId::from_cert(MessageDigest::sha1(), &leaf, &ca);
+    let mut request = OcspRequest::new()?;
+    request.add_id(cert_id()?)?;
+    let request = request.to_der()?;
+    let status = |response: &[u8]| -> KResult<OcspCertStatus> {
+        let response = OcspResponse::from_der(response)?;
+        assert_eq!(response.status(), OcspResponseStatus::SUCCESSFUL);
+        let basic = response.basic()?;
+        let cert_id = cert_id()?;
+        let status = basic
+            .find_status(&cert_id)
+            .expect("the status of the certificate")
+            .status;
+        Ok(status)
+    };
+
+    // the requested serial number is looked up as the issuer owner
+    let response = kms.ocsp(&ca_id, &request, user, None).await;
+    assert_eq!(status(&response)?, OcspCertStatus::GOOD);
+    // a response without nonce is served from the cache until its next update
+    assert_eq!(kms.ocsp(&ca_id, &request, user, None).await, response);
+
+    revoke(&kms, &leaf_id, owner).await?;
+    let response = kms.ocsp(&ca_id, &request, user, None).await;
+    assert_eq!(status(&response)?, OcspCertStatus::REVOKED);
+
+    // a user who may not certify with the issuer is unauthorized
+    let response = OcspResponse::from_der(
+        &kms.ocsp(&ca_id, &request, "stranger@example.com", None)
+            .await,
+    )?;
+    assert_eq!(response.status(), OcspResponseStatus::UNAUTHORIZED);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ocsp_after_destroy() -> KResult<()> {
+    let kms =
+        Arc::new(KMSServer::instantiate(ServerParams::try_from(https_clap_config()).await?).await?);
+    let owner = "eyJhbGciOiJSUzI1Ni";
+    let user = "ocsp.destroy@example.com";
+    let (ca_id, ca, leaf_id, leaf) = shared_issuer(&kms, owner, user).await?;
+
+    let mut request = OcspRequest::new()?;
+    request.add_id(OcspCertId::from_cert(MessageDigest::sha1(), &leaf, &ca)?)?;
+    let request = request.to_der()?;
+    let status = |response: &[u8]| -> KResult<OcspCertStatus> {
+        let basic = OcspResponse::from_der(response)?.basic()?;
+        let cert_id = OcspCertId::from_cert(MessageDigest::sha1(), &leaf, &ca)?;
+        Ok(basic
+            .find_status(&cert_id)
+            .expect("the status of the certificate")
+            .status)
+    };
+
+    // a pre-active certificate is good and may be destroyed without being revoked
+    kms.db
+        .update_state(&leaf_id, StateEnumeration::PreActive, None)
+        .await?;
+    let response = kms.ocsp(&ca_id, &request, user, None).await;
+    assert_eq!(status(&response)?, OcspCertStatus::GOOD);
+
+    // the cached response is discarded: the destroyed certificate is revoked at once
+    kms.destroy(
+        Destroy {
+            unique_identifier: Some(UniqueIdentifier::TextString(leaf_id.clone())),
+        },
+        owner,
+        None,
+    )
+    .await?;
+    let response = kms.ocsp(&ca_id, &request, user, None).await;
+    assert_eq!(status(&response)?, OcspCertStatus::REVOKED);
+    Ok(())
+}
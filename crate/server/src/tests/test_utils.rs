@@ -55,8 +55,12 @@ pub fn https_clap_config_opts(google_cse_kacls_url: Option<String>) -> ClapConfi
 pub async fn test_app(
     google_cse_kacls_url: Option<String>,
 ) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error> {
-    let clap_config = https_clap_config_opts(google_cse_kacls_url);
+    test_app_with_clap_config(https_clap_config_opts(google_cse_kacls_url)).await
+}
 
+pub async fn test_app_with_clap_config(
+    clap_config: ClapConfig,
+) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error> {
     let server_params = ServerParams::try_from(clap_config).await.unwrap();
 
     let kms_server = Arc::new(
@@ -82,7 +86,13 @@ pub async fn test_app(
         .service(routes::google_cse::wrap)
         .service(routes::google_cse::unwrap)
         .service(routes::google_cse::private_key_sign)
-        .service(routes::google_cse::private_key_decrypt);
+        .service(routes::google_cse::private_key_decrypt)
+        .service(routes::google_cse::privilegedprivatekeydecrypt)
+        .service(routes::google_cse::privilegedunwrap)
+        .service(routes::google_cse::privilegedwrap)
+        .service(routes::google_cse::rewrap)
+        .service(routes::google_cse::digest)
+        .service(routes::google_cse::wrapprivatekey);
     app = app.service(google_cse_scope);
 
     test::init_service(app).await
@@ -203,6 +203,8 @@ async fn generate_server_params(
                 ..Default::default()
             }
         },
+        // the owner manages the roles and the groups
+        access_admins: Some(vec!["owner.client@acme.com".to_string()]),
         ..Default::default()
     };
     ServerParams::try_from(clap_config)